@@ -0,0 +1,257 @@
+//! Two-subpass deferred rendering, without presentation.
+//!
+//! The first subpass renders a triangle into a G-buffer (albedo and normal),
+//! the second subpass reads it through input attachments to compute the lighting.
+//!
+//! The compiled SPIR-V shaders are shipped next to their GLSL sources,
+//! and can be rebuilt with:
+//! ```sh
+//! glslc examples/shaders/gbuffer.frag -o examples/shaders/gbuffer.frag.spv
+//! glslc examples/shaders/lighting.vert -o examples/shaders/lighting.vert.spv
+//! glslc examples/shaders/lighting.frag -o examples/shaders/lighting.frag.spv
+//! ```
+use std::{
+	sync::Arc,
+	rc::Rc,
+	path::Path
+};
+use magma::{
+	Entry,
+	Instance,
+	Device,
+	image,
+	Image,
+	pipeline::{
+		self,
+		shader,
+		layout,
+		descriptor
+	},
+	framebuffer::{
+		self,
+		render_pass::{
+			Attachment,
+			LoadOp,
+			StoreOp,
+			Subpass
+		},
+		SampleCount
+	},
+	Framebuffer,
+	format::ClearValue,
+	Format,
+	command::{
+		self,
+		Buffer as CommandBuffer
+	},
+	sync::{
+		Task,
+		fence,
+		future::SignalFence
+	},
+	mem
+};
+
+const EXTENT: (u32, u32) = (800, 600);
+
+/// Attachment indices.
+const ALBEDO: u32 = 0;
+const NORMAL: u32 = 1;
+const DEPTH: u32 = 2;
+const OUTPUT: u32 = 3;
+
+pub fn main() {
+	stderrlog::new().verbosity(3).init().unwrap();
+
+	let entry = Arc::new(Entry::new().expect("unable to load vulkan"));
+	let instance = Arc::new(Instance::new(entry, std::iter::empty::<&std::ffi::CStr>()).expect("unable to create instance"));
+	let physical_device = instance.physical_devices().next().expect("no physical device");
+	let queue_family = physical_device.queue_families().find(|q| q.supports_graphics()).expect("no graphics queue");
+	let (device, mut queues) = Device::new(
+		&physical_device,
+		physical_device.supported_features(),
+		std::iter::empty(),
+		[(queue_family, 1.0)].iter().cloned()
+	).expect("unable to create device");
+	let queue = queues.next().unwrap();
+
+	// G-buffer and output images.
+	let allocator = mem::Unbounded::new(&device);
+	let color_image = |format: Format, input: bool| {
+		let builder = image::Unbound::builder(format)
+			.extent_2d(EXTENT.0, EXTENT.1)
+			.usage(image::Usage {
+				color_attachment: true,
+				input_attachment: input,
+				transfer_source: !input,
+				..image::Usage::none()
+			});
+
+		let image = image::Bound::allocate(&allocator, &builder, std::iter::once(&queue)).expect("unable to allocate image");
+		Arc::new(image.create_default_view().expect("unable to create image view"))
+	};
+
+	let albedo = color_image(Format::R8G8B8A8Unorm, true);
+	let normal = color_image(Format::R16G16B16A16Sfloat, true);
+	let output = color_image(Format::R8G8B8A8Unorm, false);
+	let depth = Arc::new(image::Bound::depth_attachment(&allocator, EXTENT, SampleCount::S1, std::iter::once(&queue)).expect("unable to allocate depth image"));
+
+	let render_pass = create_render_pass(&device, depth.format());
+
+	let framebuffer = Arc::new(Framebuffer::new(
+		&device,
+		&render_pass,
+		vec![albedo.clone(), normal.clone(), depth.clone(), output.clone()],
+		EXTENT,
+		1
+	).expect("unable to create framebuffer"));
+
+	// G-buffer pipeline, for subpass 0.
+	let gbuffer_vertex_shader = unsafe { load_shader_module(&device, "examples/shaders/triangle.vert.spv") };
+	let gbuffer_fragment_shader = unsafe { load_shader_module(&device, "examples/shaders/gbuffer.frag.spv") };
+	let gbuffer_stages = unsafe {
+		pipeline::stage::Vertex::new(
+			gbuffer_vertex_shader.entry_point("main", shader::Stage::Vertex),
+			pipeline::stage::Fragment::new(
+				gbuffer_fragment_shader.entry_point("main", shader::Stage::Fragment)
+			)
+		)
+	};
+
+	let gbuffer_layout = layout::Empty::new(&device).expect("unable to create pipeline layout");
+	let gbuffer_pipeline: Arc<pipeline::Graphics<layout::Empty, (), ()>> = Arc::new(pipeline::Graphics::builder(&gbuffer_stages, gbuffer_layout, render_pass.subpass(0).unwrap())
+		.extent(EXTENT)
+		// The triangle of `triangle.vert` is wound clockwise.
+		.rasterization(pipeline::Rasterization::default()
+			.with_front_face(pipeline::rasterization::FrontFace::Clockwise)
+		)
+		.depth_stencil(pipeline::DepthStencil::simple_depth_test())
+		.build(&device)
+		.expect("unable to create G-buffer pipeline"));
+
+	// Lighting pipeline, for subpass 1, reading the G-buffer through input attachments.
+	let set_layout = descriptor::SetLayout::builder()
+		.binding(0, descriptor::Type::InputAttachment, shader::Stages { fragment: true, ..shader::Stages::none() })
+		.binding(1, descriptor::Type::InputAttachment, shader::Stages { fragment: true, ..shader::Stages::none() })
+		.build(&device)
+		.expect("unable to create descriptor set layout");
+
+	let lighting_vertex_shader = unsafe { load_shader_module(&device, "examples/shaders/lighting.vert.spv") };
+	let lighting_fragment_shader = unsafe { load_shader_module(&device, "examples/shaders/lighting.frag.spv") };
+	let lighting_stages = unsafe {
+		pipeline::stage::Vertex::new(
+			lighting_vertex_shader.entry_point("main", shader::Stage::Vertex),
+			pipeline::stage::Fragment::new(
+				lighting_fragment_shader.entry_point("main", shader::Stage::Fragment)
+			)
+		)
+	};
+
+	let lighting_layout = layout::Raw::new(&device, &[&set_layout], &[]).expect("unable to create pipeline layout");
+	let lighting_pipeline: Arc<pipeline::Graphics<layout::Raw, (), ()>> = Arc::new(pipeline::Graphics::builder(&lighting_stages, lighting_layout, render_pass.subpass(1).unwrap())
+		.extent(EXTENT)
+		// The full screen triangle of `lighting.vert` is wound clockwise.
+		.rasterization(pipeline::Rasterization::default()
+			.with_front_face(pipeline::rasterization::FrontFace::Clockwise)
+		)
+		.build(&device)
+		.expect("unable to create lighting pipeline"));
+
+	let pool = Arc::new(descriptor::Pool::for_layouts(&device, &[(&set_layout, 1)]).expect("unable to create descriptor pool"));
+	let set = Arc::new(pool.allocate(&set_layout).expect("unable to allocate descriptor set"));
+	unsafe {
+		set.update(&[
+			descriptor::Write::input_attachment(0, &albedo, image::Layout::ShaderReadOnlyOptimal),
+			descriptor::Write::input_attachment(1, &normal, image::Layout::ShaderReadOnlyOptimal)
+		]);
+	}
+
+	// Record and submit the render pass.
+	let command_pool = Rc::new(command::pool::Raw::new(&device, queue.family(), command::pool::Flags { reset_command_buffer: true, ..command::pool::Flags::none() }).expect("unable to create command pool"));
+	let command_buffer = command_pool.allocate_rc(1, command::pool::Level::Primary).expect("unable to allocate command buffer").pop().unwrap();
+	let mut recorder = command_buffer.begin_local(command::buffer::Usage::OneTimeSubmit).expect("unable to begin command buffer");
+	{
+		let mut render_pass = recorder.begin_render_pass(
+			&render_pass,
+			&framebuffer,
+			None,
+			&[
+				ClearValue::BLACK,
+				[0.0; 4].into(),
+				ClearValue::FAR,
+				ClearValue::BLACK
+			]
+		).expect("unable to begin render pass");
+
+		render_pass.bind_pipeline(&gbuffer_pipeline, ()).expect("unable to bind pipeline").draw((), (), 3, 1, 0, 0).expect("unable to record draw");
+
+		render_pass.next_subpass();
+		render_pass.bind_graphics_descriptor_sets(lighting_pipeline.layout(), 0, &[set.clone()], &[]).expect("unable to bind descriptor sets");
+		render_pass.bind_pipeline(&lighting_pipeline, ()).expect("unable to bind pipeline").draw((), (), 3, 1, 0, 0).expect("unable to record draw");
+	}
+	let executable = recorder.finish().expect("unable to record command buffer");
+
+	let render_finished_fence = fence::Raw::new(&device).expect("unable to create fence");
+	// One-time submit buffers are consumed by the submission.
+	let ((), rendered) = queue.submit(executable)
+		.then_signal_fence(&render_finished_fence)
+		.expect("unable to submit command buffer");
+
+	rendered.wait(None).expect("unable to wait for the render");
+	println!("rendered {}x{} image in {:?}", EXTENT.0, EXTENT.1, output.format());
+}
+
+fn create_render_pass(device: &Arc<Device>, depth_format: Format) -> Arc<framebuffer::RenderPass> {
+	let mut builder = framebuffer::RenderPass::builder();
+
+	let color = |format| Attachment {
+		format,
+		samples: SampleCount::S1,
+		load: LoadOp::Clear,
+		store: StoreOp::DontCare,
+		stencil_load: LoadOp::DontCare,
+		stencil_store: StoreOp::DontCare,
+		initial_layout: image::Layout::Undefined,
+		final_layout: image::Layout::ColorAttachmentOptimal
+	};
+
+	builder.add_attachment(color(Format::R8G8B8A8Unorm));
+	builder.add_attachment(color(Format::R16G16B16A16Sfloat));
+	builder.add_attachment(Attachment {
+		final_layout: image::Layout::DepthStencilAttachmentOptimal,
+		..color(depth_format)
+	});
+	builder.add_attachment(Attachment {
+		store: StoreOp::Store,
+		final_layout: image::Layout::TransferSrcOptimal,
+		..color(Format::R8G8B8A8Unorm)
+	});
+
+	// Subpass 0: fill the G-buffer.
+	let gbuffer = Subpass::new()
+		.color_attachment(ALBEDO, image::Layout::ColorAttachmentOptimal)
+		.color_attachment(NORMAL, image::Layout::ColorAttachmentOptimal)
+		.depth_stencil_attachment(DEPTH, image::Layout::DepthStencilAttachmentOptimal);
+
+	// Subpass 1: read the G-buffer, write the output.
+	// The by-region dependency from subpass 0 is added by the builder.
+	let lighting = Subpass::new()
+		.input_attachment(ALBEDO, image::Layout::ShaderReadOnlyOptimal)
+		.input_attachment(NORMAL, image::Layout::ShaderReadOnlyOptimal)
+		.color_attachment(OUTPUT, image::Layout::ColorAttachmentOptimal);
+
+	builder.add(&gbuffer);
+	builder.add(&lighting);
+
+	Arc::new(builder.build(device).expect("unable to build render pass"))
+}
+
+/// Load a shader module.
+///
+/// # Safety
+///
+/// The SPIR-V code is not validated or may require features that are not enabled.
+unsafe fn load_shader_module<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> Arc<shader::Module> {
+	let buffer = std::fs::read(path).expect("unable to read shader file");
+	Arc::new(shader::Module::from_bytes(device, &buffer).expect("unable to load shader module"))
+}
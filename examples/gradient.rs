@@ -0,0 +1,137 @@
+//! Compute shader writing into a storage image, without presentation.
+//!
+//! A gradient is written into the image in the `General` layout,
+//! then read back and checked.
+//!
+//! The compiled SPIR-V shader is shipped next to its GLSL source,
+//! and can be rebuilt with:
+//! ```sh
+//! glslc examples/shaders/gradient.comp -o examples/shaders/gradient.comp.spv
+//! ```
+use std::{
+	sync::Arc,
+	rc::Rc,
+	path::Path
+};
+use magma::{
+	Entry,
+	Instance,
+	Device,
+	image,
+	Image,
+	pipeline::{
+		self,
+		shader,
+		layout,
+		descriptor,
+		ComputePipeline
+	},
+	Format,
+	command::{
+		self,
+		Buffer as CommandBuffer
+	},
+	sync::{
+		Task,
+		fence,
+		future::SignalFence
+	},
+	ops,
+	mem
+};
+
+const EXTENT: (u32, u32) = (64, 64);
+
+/// Work group size of the shader.
+const LOCAL_SIZE: (u32, u32) = (8, 8);
+
+pub fn main() {
+	stderrlog::new().verbosity(3).init().unwrap();
+
+	let entry = Arc::new(Entry::new().expect("unable to load vulkan"));
+	let instance = Arc::new(Instance::new(entry, std::iter::empty::<&std::ffi::CStr>()).expect("unable to create instance"));
+	let physical_device = instance.physical_devices().next().expect("no physical device");
+	let queue_family = physical_device.queue_families().find(|q| q.supports_compute()).expect("no compute queue");
+	let (device, mut queues) = Device::new(
+		&physical_device,
+		physical_device.supported_features(),
+		std::iter::empty(),
+		[(queue_family, 1.0)].iter().cloned()
+	).expect("unable to create device");
+	let queue = queues.next().unwrap();
+
+	// The image builder checks that the format supports storage.
+	let allocator = mem::Unbounded::new(&device);
+	let builder = image::Unbound::builder(Format::R8G8B8A8Unorm)
+		.extent_2d(EXTENT.0, EXTENT.1)
+		.usage(image::Usage {
+			storage: true,
+			transfer_source: true,
+			..image::Usage::none()
+		});
+	let target = image::Bound::allocate(&allocator, &builder, std::iter::once(&queue)).expect("unable to allocate image");
+	let target = Arc::new(target.create_default_view().expect("unable to create image view"));
+
+	let shader = unsafe { load_shader_module(&device, "examples/shaders/gradient.comp.spv") };
+	let set_layout = descriptor::SetLayout::builder()
+		.binding(0, descriptor::Type::StorageImage, shader::Stages::compute())
+		.build(&device)
+		.expect("unable to create descriptor set layout");
+	let layout = layout::Raw::new(&device, &[&set_layout], &[]).expect("unable to create pipeline layout");
+	let entry_point = unsafe { shader.entry_point("main", shader::Stage::Compute) };
+	let pipeline = Arc::new(pipeline::Compute::new(&device, &entry_point, layout, None, None).expect("unable to create pipeline"));
+
+	// Storage images are accessed in the `General` layout.
+	let pool = Arc::new(descriptor::Pool::for_layouts(&device, &[(&set_layout, 1)]).expect("unable to create descriptor pool"));
+	let set = Arc::new(pool.allocate(&set_layout).expect("unable to allocate descriptor set"));
+	unsafe {
+		set.update(&[
+			descriptor::Write::storage_image(0, &target)
+		]);
+	}
+
+	let command_pool = Rc::new(command::pool::Raw::new(&device, queue.family(), command::pool::Flags::none()).expect("unable to create command pool"));
+	let command_buffer = command_pool.allocate_rc(1, command::pool::Level::Primary).expect("unable to allocate command buffer").pop().unwrap();
+	let mut recorder = command_buffer.begin_local(command::buffer::Usage::OneTimeSubmit).expect("unable to begin command buffer");
+	recorder.transition(target.image(), image::Layout::General).expect("unable to transition the image");
+	recorder.bind_compute_pipeline(&pipeline);
+	recorder.bind_compute_descriptor_sets(pipeline.layout(), 0, &[set.clone()], &[]).expect("unable to bind descriptor sets");
+	recorder.dispatch(EXTENT.0 / LOCAL_SIZE.0, EXTENT.1 / LOCAL_SIZE.1, 1);
+	recorder.transition(target.image(), image::Layout::TransferSrcOptimal).expect("unable to transition the image");
+	let executable = recorder.finish().expect("unable to record command buffer");
+
+	let fence = fence::Raw::new(&device).expect("unable to create fence");
+	let ((), done) = queue.submit(executable)
+		.then_signal_fence(&fence)
+		.expect("unable to submit command buffer");
+	done.wait(None).expect("unable to wait for the compute shader");
+
+	let output = ops::readback_image(&queue, target.image(), image::Layout::TransferSrcOptimal, (0, 0, 0), (EXTENT.0, EXTENT.1, 1))
+		.expect("unable to read back the image")
+		.wait_data(None)
+		.expect("unable to wait for the readback");
+
+	// Red grows from left to right, green from top to bottom.
+	let expected = |i: u32, size: u32| (i as f32 * 255.0 / (size - 1) as f32).round() as i32;
+	for y in 0..EXTENT.1 {
+		for x in 0..EXTENT.0 {
+			let offset = ((y * EXTENT.0 + x) * 4) as usize;
+			let texel = &output[offset..(offset + 4)];
+			assert!((texel[0] as i32 - expected(x, EXTENT.0)).abs() <= 1, "unexpected texel {:?} at ({}, {})", texel, x, y);
+			assert!((texel[1] as i32 - expected(y, EXTENT.1)).abs() <= 1, "unexpected texel {:?} at ({}, {})", texel, x, y);
+			assert_eq!(texel[2..], [0, 255]);
+		}
+	}
+
+	println!("computed a {}x{} gradient", EXTENT.0, EXTENT.1);
+}
+
+/// Load a shader module.
+///
+/// # Safety
+///
+/// The SPIR-V code is not validated or may require features that are not enabled.
+unsafe fn load_shader_module<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> Arc<shader::Module> {
+	let buffer = std::fs::read(path).expect("unable to read shader file");
+	Arc::new(shader::Module::from_bytes(device, &buffer).expect("unable to load shader module"))
+}
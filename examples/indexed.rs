@@ -0,0 +1,186 @@
+//! Indexed draw smoke test, without presentation.
+//!
+//! Renders the triangle of the `test` example with `draw_indexed`,
+//! reads the rendered image back and checks that the triangle was drawn.
+use std::{
+	sync::Arc,
+	rc::Rc,
+	path::Path
+};
+use magma::{
+	Entry,
+	Instance,
+	Device,
+	image,
+	Image,
+	pipeline::{
+		self,
+		shader,
+		layout
+	},
+	framebuffer::{
+		self,
+		render_pass::{
+			Attachment,
+			LoadOp,
+			StoreOp,
+			Subpass
+		},
+		SampleCount
+	},
+	Framebuffer,
+	format::ClearValue,
+	Format,
+	command::{
+		self,
+		Buffer as CommandBuffer
+	},
+	sync::{
+		Task,
+		fence,
+		future::SignalFence
+	},
+	ops,
+	mem
+};
+
+const EXTENT: (u32, u32) = (64, 64);
+const FORMAT: Format = Format::R8G8B8A8Unorm;
+
+pub fn main() {
+	stderrlog::new().verbosity(3).init().unwrap();
+
+	let entry = Arc::new(Entry::new().expect("unable to load vulkan"));
+	let instance = Arc::new(Instance::new(entry, std::iter::empty::<&std::ffi::CStr>()).expect("unable to create instance"));
+	let physical_device = instance.physical_devices().next().expect("no physical device");
+	let queue_family = physical_device.queue_families().find(|q| q.supports_graphics()).expect("no graphics queue");
+	let (device, mut queues) = Device::new(
+		&physical_device,
+		physical_device.supported_features(),
+		std::iter::empty(),
+		[(queue_family, 1.0)].iter().cloned()
+	).expect("unable to create device");
+	let queue = queues.next().unwrap();
+
+	let allocator = mem::Unbounded::new(&device);
+	let builder = image::Unbound::builder(FORMAT)
+		.extent_2d(EXTENT.0, EXTENT.1)
+		.usage(image::Usage {
+			color_attachment: true,
+			transfer_source: true,
+			..image::Usage::none()
+		});
+	let target = image::Bound::allocate(&allocator, &builder, std::iter::once(&queue)).expect("unable to allocate image");
+	let target = Arc::new(target.create_default_view().expect("unable to create image view"));
+
+	let render_pass = create_render_pass(&device);
+	let framebuffer = Arc::new(Framebuffer::new(
+		&device,
+		&render_pass,
+		vec![target.clone()],
+		EXTENT,
+		1
+	).expect("unable to create framebuffer"));
+
+	let vertex_shader = unsafe { load_shader_module(&device, "examples/shaders/triangle.vert.spv") };
+	let fragment_shader = unsafe { load_shader_module(&device, "examples/shaders/triangle.frag.spv") };
+	let stages = unsafe {
+		pipeline::stage::Vertex::new(
+			vertex_shader.entry_point("main", shader::Stage::Vertex),
+			pipeline::stage::Fragment::new(
+				fragment_shader.entry_point("main", shader::Stage::Fragment)
+			)
+		)
+	};
+
+	let layout = layout::Empty::new(&device).expect("unable to create pipeline layout");
+	let pipeline: Arc<pipeline::Graphics<layout::Empty, (), ()>> = Arc::new(pipeline::Graphics::builder(&stages, layout, render_pass.subpass(0).unwrap())
+		.extent(EXTENT)
+		// The triangle of `triangle.vert` is wound clockwise.
+		.rasterization(pipeline::Rasterization::default()
+			.with_front_face(pipeline::rasterization::FrontFace::Clockwise)
+		)
+		.build(&device)
+		.expect("unable to create pipeline"));
+
+	// The triangle vertices are indexed by the vertex shader from `gl_VertexIndex`.
+	let staging_allocator = mem::staging::Allocator::new(mem::Unbounded::new(&device));
+	let index_buffer: Arc<mem::buffer::Typed<[u16; 3]>> = Arc::new(mem::buffer::Typed::new(
+		&staging_allocator,
+		1,
+		mem::buffer::Usage::IndexBuffer,
+		std::iter::once(&queue)
+	).expect("unable to create index buffer"));
+
+	unsafe {
+		let indexes: [[u16; 3]; 1] = [[0, 1, 2]];
+		let ptr = index_buffer.memory_slot().ptr().expect("index buffer is not host visible");
+		std::ptr::copy_nonoverlapping(indexes.as_ptr(), ptr as *mut [u16; 3], 1);
+	}
+
+	let command_pool = Rc::new(command::pool::Raw::new(&device, queue.family(), command::pool::Flags::none()).expect("unable to create command pool"));
+	let command_buffer = command_pool.allocate_rc(1, command::pool::Level::Primary).expect("unable to allocate command buffer").pop().unwrap();
+	let mut recorder = command_buffer.begin_local(command::buffer::Usage::OneTimeSubmit).expect("unable to begin command buffer");
+	{
+		let mut render_pass = recorder.begin_render_pass(
+			&render_pass,
+			&framebuffer,
+			None,
+			&[ClearValue::BLACK]
+		).expect("unable to begin render pass");
+
+		render_pass.bind_pipeline(&pipeline, ()).expect("unable to bind pipeline").draw_indexed((), (), index_buffer.clone(), 0, 1, 1, 0, 0, 0).expect("unable to record draw");
+	}
+	let executable = recorder.finish().expect("unable to record command buffer");
+
+	let render_finished_fence = fence::Raw::new(&device).expect("unable to create fence");
+	let ((), rendered) = queue.submit(executable)
+		.then_signal_fence(&render_finished_fence)
+		.expect("unable to submit command buffer");
+	rendered.wait(None).expect("unable to wait for the render");
+
+	// The center of the image is covered by the triangle, the corners are not.
+	let texels = ops::readback_image(&queue, target.image(), image::Layout::TransferSrcOptimal, (0, 0, 0), (EXTENT.0, EXTENT.1, 1))
+		.expect("unable to read back the image")
+		.wait_data(None)
+		.expect("unable to wait for the readback");
+
+	let texel = |x: u32, y: u32| {
+		let offset = ((y * EXTENT.0 + x) * 4) as usize;
+		&texels[offset..(offset + 4)]
+	};
+
+	assert_ne!(texel(EXTENT.0 / 2, EXTENT.1 / 2), [0, 0, 0, 0]);
+	assert_eq!(texel(0, 0), [0, 0, 0, 0]);
+	println!("indexed triangle rendered");
+}
+
+fn create_render_pass(device: &Arc<Device>) -> Arc<framebuffer::RenderPass> {
+	let mut builder = framebuffer::RenderPass::builder();
+
+	builder.add_attachment(Attachment {
+		format: FORMAT,
+		samples: SampleCount::S1,
+		load: LoadOp::Clear,
+		store: StoreOp::Store,
+		stencil_load: LoadOp::DontCare,
+		stencil_store: StoreOp::DontCare,
+		initial_layout: image::Layout::Undefined,
+		final_layout: image::Layout::TransferSrcOptimal
+	});
+
+	let subpass = Subpass::new().color_attachment(0, image::Layout::ColorAttachmentOptimal);
+	builder.add(&subpass);
+
+	Arc::new(builder.build(device).expect("unable to build render pass"))
+}
+
+/// Load a shader module.
+///
+/// # Safety
+///
+/// The SPIR-V code is not validated or may require features that are not enabled.
+unsafe fn load_shader_module<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> Arc<shader::Module> {
+	let buffer = std::fs::read(path).expect("unable to read shader file");
+	Arc::new(shader::Module::from_bytes(device, &buffer).expect("unable to load shader module"))
+}
@@ -0,0 +1,250 @@
+//! PNG texture upload and mipmap generation, without presentation.
+//!
+//! The first level of the texture is uploaded from `examples/textures/checker.png`,
+//! the other levels are generated with `ops::generate_mipmaps`.
+//! The texture is then rendered at a quarter of its resolution,
+//! which samples its third level.
+//!
+//! The compiled SPIR-V shaders are shipped next to their GLSL sources,
+//! and can be rebuilt with:
+//! ```sh
+//! glslc examples/shaders/textured.vert -o examples/shaders/textured.vert.spv
+//! glslc examples/shaders/textured.frag -o examples/shaders/textured.frag.spv
+//! ```
+use std::{
+	sync::Arc,
+	path::Path
+};
+use magma::{
+	Entry,
+	Instance,
+	Device,
+	image::{
+		self,
+		ImageProperties
+	},
+	Image,
+	pipeline::{
+		self,
+		shader,
+		layout,
+		descriptor
+	},
+	framebuffer::{
+		self,
+		render_pass::{
+			Attachment,
+			LoadOp,
+			StoreOp,
+			Subpass
+		},
+		SampleCount
+	},
+	Framebuffer,
+	format::ClearValue,
+	Format,
+	command::{
+		self,
+		Pool as _,
+		Buffer as CommandBuffer
+	},
+	sync::{
+		Task,
+		fence,
+		future::SignalFence
+	},
+	ops,
+	mem
+};
+
+const FORMAT: Format = Format::R8G8B8A8Unorm;
+
+/// Decoded texture, with tightly packed RGBA texels.
+struct Texels {
+	width: u32,
+	height: u32,
+	rgba: Vec<u8>
+}
+
+/// Decode a 8-bit RGB or RGBA PNG image.
+fn decode_png<P: AsRef<Path>>(path: P) -> Texels {
+	let file = std::fs::File::open(path).expect("unable to open texture file");
+	let mut decoder = png::Decoder::new(file);
+	decoder.set_transformations(png::Transformations::EXPAND);
+	let (info, mut reader) = decoder.read_info().expect("unable to decode texture");
+	let mut data = vec![0; info.buffer_size()];
+	reader.next_frame(&mut data).expect("unable to decode texture");
+
+	let rgba = match info.color_type {
+		png::ColorType::RGBA => data,
+		png::ColorType::RGB => data.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 0xff]).collect(),
+		color_type => panic!("unsupported texture color type {:?}", color_type)
+	};
+
+	Texels {
+		width: info.width,
+		height: info.height,
+		rgba
+	}
+}
+
+pub fn main() {
+	stderrlog::new().verbosity(3).init().unwrap();
+
+	let entry = Arc::new(Entry::new().expect("unable to load vulkan"));
+	let instance = Arc::new(Instance::new(entry, std::iter::empty::<&std::ffi::CStr>()).expect("unable to create instance"));
+	let physical_device = instance.physical_devices().next().expect("no physical device");
+	let queue_family = physical_device.queue_families().find(|q| q.supports_graphics()).expect("no graphics queue");
+	let (device, mut queues) = Device::new(
+		&physical_device,
+		physical_device.supported_features(),
+		std::iter::empty(),
+		[(queue_family, 1.0)].iter().cloned()
+	).expect("unable to create device");
+	let queue = queues.next().unwrap();
+
+	let texels = decode_png("examples/textures/checker.png");
+
+	// Texture with a full mip chain.
+	let allocator = mem::Unbounded::new(&device);
+	let builder = image::Unbound::builder(FORMAT)
+		.extent_2d(texels.width, texels.height)
+		.mip_levels(image::Mips::Auto)
+		.usage(image::Usage {
+			sampled: true,
+			transfer_source: true,
+			transfer_destination: true,
+			..image::Usage::none()
+		});
+	let texture = image::Bound::allocate(&allocator, &builder, std::iter::once(&queue)).expect("unable to allocate texture");
+	let texture = Arc::new(texture.create_default_view().expect("unable to create texture view"));
+
+	// Every level is left in the `TransferDstOptimal` layout, as expected by `generate_mipmaps`.
+	let upload = ops::upload_image(&queue, &allocator, &texels.rgba, texture.image(), (texels.width, texels.height, 1), image::Layout::TransferDstOptimal).expect("unable to upload texture");
+	upload.wait_done(None).expect("unable to wait for the upload");
+
+	// Render target, a quarter of the texture resolution.
+	let extent = (texels.width / 4, texels.height / 4);
+	let builder = image::Unbound::builder(FORMAT)
+		.extent_2d(extent.0, extent.1)
+		.usage(image::Usage {
+			color_attachment: true,
+			transfer_source: true,
+			..image::Usage::none()
+		});
+	let target = image::Bound::allocate(&allocator, &builder, std::iter::once(&queue)).expect("unable to allocate image");
+	let target = Arc::new(target.create_default_view().expect("unable to create image view"));
+
+	let render_pass = create_render_pass(&device);
+	let framebuffer = Arc::new(Framebuffer::new(
+		&device,
+		&render_pass,
+		vec![target.clone()],
+		extent,
+		1
+	).expect("unable to create framebuffer"));
+
+	let vertex_shader = unsafe { load_shader_module(&device, "examples/shaders/textured.vert.spv") };
+	let fragment_shader = unsafe { load_shader_module(&device, "examples/shaders/textured.frag.spv") };
+	let stages = unsafe {
+		pipeline::stage::Vertex::new(
+			vertex_shader.entry_point("main", shader::Stage::Vertex),
+			pipeline::stage::Fragment::new(
+				fragment_shader.entry_point("main", shader::Stage::Fragment)
+			)
+		)
+	};
+
+	let set_layout = descriptor::SetLayout::builder()
+		.binding(0, descriptor::Type::CombinedImageSampler, shader::Stages { fragment: true, ..shader::Stages::none() })
+		.build(&device)
+		.expect("unable to create descriptor set layout");
+
+	let layout = layout::Raw::new(&device, &[&set_layout], &[]).expect("unable to create pipeline layout");
+	let pipeline: Arc<pipeline::Graphics<layout::Raw, (), ()>> = Arc::new(pipeline::Graphics::builder(&stages, layout, render_pass.subpass(0).unwrap())
+		.extent(extent)
+		// The full screen triangle of `textured.vert` is wound clockwise.
+		.rasterization(pipeline::Rasterization::default()
+			.with_front_face(pipeline::rasterization::FrontFace::Clockwise)
+		)
+		.build(&device)
+		.expect("unable to create pipeline"));
+
+	// Nearest filtering, so that the rendered texels come from a single level.
+	let sampler = Arc::new(image::Sampler::builder().build(&device).expect("unable to create sampler"));
+
+	let pool = Arc::new(descriptor::Pool::for_layouts(&device, &[(&set_layout, 1)]).expect("unable to create descriptor pool"));
+	let set = Arc::new(pool.allocate(&set_layout).expect("unable to allocate descriptor set"));
+	unsafe {
+		set.update(&[
+			descriptor::Write::combined_image_sampler(0, &texture, &sampler, image::Layout::ShaderReadOnlyOptimal)
+		]);
+	}
+
+	// `generate_mipmaps` records into `Send` command buffers.
+	let command_pool = command::pool::SyncPool::new(&device, queue.family(), command::pool::Flags::none()).expect("unable to create command pool");
+	let command_buffer = command_pool.allocate(1, command::pool::Level::Primary).expect("unable to allocate command buffer").pop().unwrap();
+	let mut recorder = command_buffer.begin(command::buffer::Usage::OneTimeSubmit).expect("unable to begin command buffer");
+	ops::generate_mipmaps(&mut recorder, texture.image()).expect("unable to generate mipmaps");
+	{
+		let mut render_pass = recorder.begin_render_pass::<_, ()>(
+			&render_pass,
+			&framebuffer,
+			None,
+			&[ClearValue::BLACK]
+		).expect("unable to begin render pass");
+
+		render_pass.bind_graphics_descriptor_sets(pipeline.layout(), 0, &[set.clone()], &[]).expect("unable to bind descriptor sets");
+		render_pass.draw(&pipeline, (), (), 3, 1, 0, 0).expect("unable to record draw");
+	}
+	let executable = recorder.finish().expect("unable to record command buffer");
+
+	let render_finished_fence = fence::Raw::new(&device).expect("unable to create fence");
+	let ((), rendered) = queue.submit(executable)
+		.then_signal_fence(&render_finished_fence)
+		.expect("unable to submit command buffer");
+	rendered.wait(None).expect("unable to wait for the render");
+
+	// The checkerboard cells are two texels wide:
+	// they alternate in the first level, and average to grey from the third level.
+	let output = ops::readback_image(&queue, target.image(), image::Layout::TransferSrcOptimal, (0, 0, 0), (extent.0, extent.1, 1))
+		.expect("unable to read back the image")
+		.wait_data(None)
+		.expect("unable to wait for the readback");
+
+	for texel in output.chunks_exact(4) {
+		assert!(texel[..3].iter().all(|c| (112..=144).contains(c)), "unexpected texel {:?}", texel);
+	}
+
+	println!("rendered the {}x{} texture with {} mip levels", texels.width, texels.height, texture.image().mip_levels());
+}
+
+fn create_render_pass(device: &Arc<Device>) -> Arc<framebuffer::RenderPass> {
+	let mut builder = framebuffer::RenderPass::builder();
+
+	builder.add_attachment(Attachment {
+		format: FORMAT,
+		samples: SampleCount::S1,
+		load: LoadOp::Clear,
+		store: StoreOp::Store,
+		stencil_load: LoadOp::DontCare,
+		stencil_store: StoreOp::DontCare,
+		initial_layout: image::Layout::Undefined,
+		final_layout: image::Layout::TransferSrcOptimal
+	});
+
+	let subpass = Subpass::new().color_attachment(0, image::Layout::ColorAttachmentOptimal);
+	builder.add(&subpass);
+
+	Arc::new(builder.build(device).expect("unable to build render pass"))
+}
+
+/// Load a shader module.
+///
+/// # Safety
+///
+/// The SPIR-V code is not validated or may require features that are not enabled.
+unsafe fn load_shader_module<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> Arc<shader::Module> {
+	let buffer = std::fs::read(path).expect("unable to read shader file");
+	Arc::new(shader::Module::from_bytes(device, &buffer).expect("unable to load shader module"))
+}
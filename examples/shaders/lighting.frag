@@ -0,0 +1,12 @@
+#version 450
+
+layout(input_attachment_index = 0, set = 0, binding = 0) uniform subpassInput albedo;
+layout(input_attachment_index = 1, set = 0, binding = 1) uniform subpassInput normal;
+
+layout(location = 0) out vec4 outColor;
+
+void main() {
+	vec3 light = normalize(vec3(0.5, 0.5, 1.0));
+	float diffuse = max(dot(subpassLoad(normal).xyz, light), 0.1);
+	outColor = vec4(subpassLoad(albedo).rgb * diffuse, 1.0);
+}
@@ -0,0 +1,17 @@
+#version 450
+
+layout(push_constant) uniform Camera {
+	// Maps the far plane to the view directions, in world space.
+	mat4 inverseViewProjection;
+};
+
+layout(set = 0, binding = 0) uniform samplerCube sky;
+
+layout(location = 0) in vec2 ndc;
+
+layout(location = 0) out vec4 outColor;
+
+void main() {
+	vec4 direction = inverseViewProjection * vec4(ndc, 1.0, 1.0);
+	outColor = texture(sky, direction.xyz);
+}
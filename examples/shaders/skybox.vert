@@ -0,0 +1,16 @@
+#version 450
+
+// Full screen triangle, forwarding the normalized device coordinates.
+vec2 positions[3] = vec2[](
+	vec2(-1.0, -1.0),
+	vec2(3.0, -1.0),
+	vec2(-1.0, 3.0)
+);
+
+layout(location = 0) out vec2 ndc;
+
+void main() {
+	vec2 position = positions[gl_VertexIndex];
+	ndc = position;
+	gl_Position = vec4(position, 0.0, 1.0);
+}
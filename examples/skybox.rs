@@ -0,0 +1,253 @@
+//! Skybox rendering from a cube map, without presentation.
+//!
+//! Each face of the cube map is filled with its own color with `ops::upload_cube_faces`,
+//! then sampled through a `samplerCube` by a full-screen triangle.
+//! The camera looks toward +X, so the center of the image shows the +X face.
+//!
+//! The compiled SPIR-V shaders are shipped next to their GLSL sources,
+//! and can be rebuilt with:
+//! ```sh
+//! glslc examples/shaders/skybox.vert -o examples/shaders/skybox.vert.spv
+//! glslc examples/shaders/skybox.frag -o examples/shaders/skybox.frag.spv
+//! ```
+use std::{
+	sync::Arc,
+	rc::Rc,
+	path::Path
+};
+use magma::{
+	Entry,
+	Instance,
+	Device,
+	image,
+	Image,
+	pipeline::{
+		self,
+		shader,
+		layout,
+		descriptor
+	},
+	framebuffer::{
+		self,
+		render_pass::{
+			Attachment,
+			LoadOp,
+			StoreOp,
+			Subpass
+		},
+		SampleCount
+	},
+	Framebuffer,
+	format::ClearValue,
+	Format,
+	command::{
+		self,
+		Buffer as CommandBuffer
+	},
+	sync::{
+		Task,
+		fence,
+		future::SignalFence
+	},
+	ops,
+	mem
+};
+
+const EXTENT: (u32, u32) = (64, 64);
+const FACE_SIZE: u32 = 16;
+const FORMAT: Format = Format::R8G8B8A8Unorm;
+
+/// Face colors, in the order +X, -X, +Y, -Y, +Z, -Z.
+const FACE_COLORS: [[u8; 4]; 6] = [
+	[255, 0, 0, 255],
+	[0, 255, 255, 255],
+	[0, 255, 0, 255],
+	[255, 0, 255, 255],
+	[0, 0, 255, 255],
+	[255, 255, 0, 255]
+];
+
+/// Camera rotation around the Y axis, from +Z toward +X.
+const YAW: f32 = std::f32::consts::FRAC_PI_2;
+
+/// Vertical field of view.
+const FOV: f32 = std::f32::consts::FRAC_PI_2;
+
+pub fn main() {
+	stderrlog::new().verbosity(3).init().unwrap();
+
+	let entry = Arc::new(Entry::new().expect("unable to load vulkan"));
+	let instance = Arc::new(Instance::new(entry, std::iter::empty::<&std::ffi::CStr>()).expect("unable to create instance"));
+	let physical_device = instance.physical_devices().next().expect("no physical device");
+	let queue_family = physical_device.queue_families().find(|q| q.supports_graphics()).expect("no graphics queue");
+	let (device, mut queues) = Device::new(
+		&physical_device,
+		physical_device.supported_features(),
+		std::iter::empty(),
+		[(queue_family, 1.0)].iter().cloned()
+	).expect("unable to create device");
+	let queue = queues.next().unwrap();
+
+	// Cube map, one color per face.
+	let allocator = mem::Unbounded::new(&device);
+	let builder = image::Unbound::builder(FORMAT)
+		.extent_2d(FACE_SIZE, FACE_SIZE)
+		.cubes(1)
+		.usage(image::Usage {
+			sampled: true,
+			transfer_destination: true,
+			..image::Usage::none()
+		});
+	let sky = image::Bound::allocate(&allocator, &builder, std::iter::once(&queue)).expect("unable to allocate cube map");
+	let sky = Arc::new(sky.create_default_view().expect("unable to create cube view"));
+
+	let faces: Vec<Vec<u8>> = FACE_COLORS.iter().map(|color| {
+		color.iter().cloned().cycle().take((FACE_SIZE * FACE_SIZE * 4) as usize).collect()
+	}).collect();
+	let faces = [&faces[0][..], &faces[1][..], &faces[2][..], &faces[3][..], &faces[4][..], &faces[5][..]];
+	let upload = ops::upload_cube_faces(&queue, &allocator, &faces, sky.image(), image::Layout::ShaderReadOnlyOptimal).expect("unable to upload cube faces");
+	upload.wait_done(None).expect("unable to wait for the upload");
+
+	let builder = image::Unbound::builder(FORMAT)
+		.extent_2d(EXTENT.0, EXTENT.1)
+		.usage(image::Usage {
+			color_attachment: true,
+			transfer_source: true,
+			..image::Usage::none()
+		});
+	let target = image::Bound::allocate(&allocator, &builder, std::iter::once(&queue)).expect("unable to allocate image");
+	let target = Arc::new(target.create_default_view().expect("unable to create image view"));
+
+	let render_pass = create_render_pass(&device);
+	let framebuffer = Arc::new(Framebuffer::new(
+		&device,
+		&render_pass,
+		vec![target.clone()],
+		EXTENT,
+		1
+	).expect("unable to create framebuffer"));
+
+	let vertex_shader = unsafe { load_shader_module(&device, "examples/shaders/skybox.vert.spv") };
+	let fragment_shader = unsafe { load_shader_module(&device, "examples/shaders/skybox.frag.spv") };
+	let stages = unsafe {
+		pipeline::stage::Vertex::new(
+			vertex_shader.entry_point("main", shader::Stage::Vertex),
+			pipeline::stage::Fragment::new(
+				fragment_shader.entry_point("main", shader::Stage::Fragment)
+			)
+		)
+	};
+
+	let fragment_stage = shader::Stages { fragment: true, ..shader::Stages::none() };
+	let set_layout = descriptor::SetLayout::builder()
+		.binding(0, descriptor::Type::CombinedImageSampler, fragment_stage)
+		.build(&device)
+		.expect("unable to create descriptor set layout");
+
+	let camera_range = layout::push_constant::Range::new(fragment_stage, 0, std::mem::size_of::<[[f32; 4]; 4]>() as u32);
+	let layout = layout::Raw::new(&device, &[&set_layout], &[camera_range]).expect("unable to create pipeline layout");
+	let pipeline: Arc<pipeline::Graphics<layout::Raw, (), ()>> = Arc::new(pipeline::Graphics::builder(&stages, layout, render_pass.subpass(0).unwrap())
+		.extent(EXTENT)
+		// The sky is drawn as a single full screen triangle, whatever its winding.
+		.rasterization(pipeline::Rasterization::default()
+			.with_cull_mode(pipeline::rasterization::CullMode::None)
+		)
+		.build(&device)
+		.expect("unable to create pipeline"));
+
+	let sampler = Arc::new(image::Sampler::builder()
+		.filter(image::sampler::Filter::Linear)
+		.address_mode(image::sampler::AddressMode::ClampToEdge)
+		.build(&device)
+		.expect("unable to create sampler"));
+
+	let pool = Arc::new(descriptor::Pool::for_layouts(&device, &[(&set_layout, 1)]).expect("unable to create descriptor pool"));
+	let set = Arc::new(pool.allocate(&set_layout).expect("unable to allocate descriptor set"));
+	unsafe {
+		set.update(&[
+			descriptor::Write::combined_image_sampler(0, &sky, &sampler, image::Layout::ShaderReadOnlyOptimal)
+		]);
+	}
+
+	let camera = inverse_view_projection(YAW, FOV, EXTENT.0 as f32 / EXTENT.1 as f32);
+	let camera_bytes: Vec<u8> = camera.iter().flatten().flat_map(|f| f.to_ne_bytes().to_vec()).collect();
+
+	let command_pool = Rc::new(command::pool::Raw::new(&device, queue.family(), command::pool::Flags::none()).expect("unable to create command pool"));
+	let command_buffer = command_pool.allocate_rc(1, command::pool::Level::Primary).expect("unable to allocate command buffer").pop().unwrap();
+	let mut recorder = command_buffer.begin_local(command::buffer::Usage::OneTimeSubmit).expect("unable to begin command buffer");
+	recorder.push_constants(pipeline.layout(), fragment_stage, 0, &camera_bytes).expect("unable to push the camera");
+	{
+		let mut render_pass = recorder.begin_render_pass(
+			&render_pass,
+			&framebuffer,
+			None,
+			&[ClearValue::BLACK]
+		).expect("unable to begin render pass");
+
+		render_pass.bind_graphics_descriptor_sets(pipeline.layout(), 0, &[set.clone()], &[]).expect("unable to bind descriptor sets");
+		render_pass.bind_pipeline(&pipeline, ()).expect("unable to bind pipeline").draw((), (), 3, 1, 0, 0).expect("unable to record draw");
+	}
+	let executable = recorder.finish().expect("unable to record command buffer");
+
+	let render_finished_fence = fence::Raw::new(&device).expect("unable to create fence");
+	let ((), rendered) = queue.submit(executable)
+		.then_signal_fence(&render_finished_fence)
+		.expect("unable to submit command buffer");
+	rendered.wait(None).expect("unable to wait for the render");
+
+	let output = ops::readback_image(&queue, target.image(), image::Layout::TransferSrcOptimal, (0, 0, 0), (EXTENT.0, EXTENT.1, 1))
+		.expect("unable to read back the image")
+		.wait_data(None)
+		.expect("unable to wait for the readback");
+
+	let center = (((EXTENT.1 / 2) * EXTENT.0 + EXTENT.0 / 2) * 4) as usize;
+	assert_eq!(output[center..(center + 4)], FACE_COLORS[0]);
+	println!("rendered the +X face of the skybox");
+}
+
+/// Inverse view-projection matrix of a camera at the origin, in column-major order.
+///
+/// Maps the normalized device coordinates of the far plane to the world space view directions.
+/// The Y axis of normalized device coordinates points down, the world Y axis points up.
+fn inverse_view_projection(yaw: f32, fov: f32, aspect_ratio: f32) -> [[f32; 4]; 4] {
+	let (sin, cos) = yaw.sin_cos();
+	let rotate = |[x, y, z]: [f32; 3]| [x * cos + z * sin, y, z * cos - x * sin, 0.0];
+
+	let tan = (fov / 2.0).tan();
+	[
+		rotate([tan * aspect_ratio, 0.0, 0.0]),
+		rotate([0.0, -tan, 0.0]),
+		rotate([0.0, 0.0, 1.0]),
+		[0.0; 4]
+	]
+}
+
+fn create_render_pass(device: &Arc<Device>) -> Arc<framebuffer::RenderPass> {
+	let mut builder = framebuffer::RenderPass::builder();
+
+	builder.add_attachment(Attachment {
+		format: FORMAT,
+		samples: SampleCount::S1,
+		load: LoadOp::Clear,
+		store: StoreOp::Store,
+		stencil_load: LoadOp::DontCare,
+		stencil_store: StoreOp::DontCare,
+		initial_layout: image::Layout::Undefined,
+		final_layout: image::Layout::TransferSrcOptimal
+	});
+
+	let subpass = Subpass::new().color_attachment(0, image::Layout::ColorAttachmentOptimal);
+	builder.add(&subpass);
+
+	Arc::new(builder.build(device).expect("unable to build render pass"))
+}
+
+/// Load a shader module.
+///
+/// # Safety
+///
+/// The SPIR-V code is not validated or may require features that are not enabled.
+unsafe fn load_shader_module<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> Arc<shader::Module> {
+	let buffer = std::fs::read(path).expect("unable to read shader file");
+	Arc::new(shader::Module::from_bytes(device, &buffer).expect("unable to load shader module"))
+}
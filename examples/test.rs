@@ -23,12 +23,13 @@ use magma::{
 	swapchain::{
 		self,
 		Surface,
+		FullScreenExclusive,
 		capabilities::{
 			CompositeAlpha,
 			PresentMode
 		},
-		Capabilities,
-		capabilities::ColorSpace
+		capabilities::ColorSpace,
+		surface::SurfaceFormat
 	},
 	image,
 	Image,
@@ -38,8 +39,6 @@ use magma::{
 		VertexInput,
 		input_assembly,
 		InputAssembly,
-		Viewport,
-		Scissor,
 		ColorBlend,
 		color_blend::{
 			self,
@@ -71,7 +70,8 @@ use magma::{
 			SignalFence
 		}
 	},
-	ops
+	ops,
+	mem
 };
 
 use winit::{
@@ -93,9 +93,9 @@ pub fn main() {
 
 	let entry = Arc::new(Entry::new().expect("Unable to load vulkan"));
 
-	let required_extensions = win::required_extensions(&entry);
+	let required_extensions = win::required_extensions(&entry).expect("Unable to query instance extensions");
 
-	for ext in required_extensions {
+	for ext in &required_extensions {
 		println!("extension: {}", ext);
 	}
 
@@ -111,7 +111,7 @@ pub fn main() {
 		println!("device: {}", physical_device.name());
 	}
 
-	let physical_device = Arc::new(instance.physical_devices().last().unwrap());
+	let physical_device = instance.physical_devices().last().unwrap();
 	println!("choosen device: {}", physical_device.name());
 
 	let event_loop = EventLoop::new();
@@ -123,17 +123,17 @@ pub fn main() {
 	let (device, mut queues) = get_device(&physical_device, queue_family);
 	let queue = queues.next().unwrap();
 
-	let surface_capabilities = surface.capabilities(device.physical_device()).unwrap();
-	let (color_format, color_space) = choose_format(&surface_capabilities).expect("No appropriate format found");
+	let surface_formats = device.physical_device().surface_formats(&surface).unwrap();
+	let (color_format, color_space) = choose_format(&surface_formats).expect("No appropriate format found");
 
 	// Load the shader modules.
-	let vertex_shader = unsafe { load_shader_module(&device, "examples/shaders/triangle.vert.spv") };
+	let vertex_shader = unsafe { Arc::new(shader::Module::new(&device, magma::include_spirv!("shaders/triangle.vert.spv")).expect("Unable to load shader module")) };
 	let fragment_shader = unsafe { load_shader_module(&device, "examples/shaders/triangle.frag.spv") };
 	let stages = unsafe {
 		pipeline::stage::Vertex::new(
-			vertex_shader.entry_point("main"),
+			vertex_shader.entry_point("main", shader::Stage::Vertex),
 			pipeline::stage::Fragment::new(
-				fragment_shader.entry_point("main")
+				fragment_shader.entry_point("main", shader::Stage::Fragment)
 			)
 		)
 	};
@@ -142,26 +142,13 @@ pub fn main() {
 
 	let layout = layout::Empty::new(&device).expect("unable to create pipeline layout");
 	
-	let pipeline: Arc<pipeline::Graphics<layout::Empty, (), ()>> = Arc::new(pipeline::Graphics::new(
-		&device,
-		&stages,
-		(), // no vertex input
-		None, // no tesselation
-		[Viewport::new(0.0, 0.0, dimensions.0 as f32, dimensions.1 as f32, 0.0, 1.0)],
-		[Scissor::new(0, 0, dimensions.0, dimensions.1)],
-		pipeline::Rasterization::new(
-			false,
-			false,
-			pipeline::rasterization::PolygonMode::Fill,
-			pipeline::rasterization::CullMode::Back,
-			pipeline::rasterization::FrontFace::Clockwise,
-			None,
-			1.0
-		),
-		pipeline::Multisample::default(), // no multisampling
-		None,
-		None,
-		ColorBlend::new(None, [0.0, 0.0, 0.0, 0.0]).with_attachment(color_blend::Attachment::new(
+	let pipeline: Arc<pipeline::Graphics<layout::Empty, (), ()>> = Arc::new(pipeline::Graphics::builder(&stages, layout, render_pass.subpass(0).unwrap())
+		.extent(dimensions)
+		.rasterization(pipeline::Rasterization::default()
+			.with_cull_mode(pipeline::rasterization::CullMode::Back)
+			.with_front_face(pipeline::rasterization::FrontFace::Clockwise)
+		)
+		.color_blend(ColorBlend::new(None, [0.0, 0.0, 0.0, 0.0]).with_attachment(color_blend::Attachment::new(
 			Some(color_blend::AttachmentBlend::new(
 				BlendFactor::SourceAlpha,
 				BlendFactor::OneMinusSourceAlpha,
@@ -171,10 +158,9 @@ pub fn main() {
 				color_blend::Operation::Add
 			)),
 			color_blend::ColorComponents::rgba()
-		)),
-		layout,
-		render_pass.subpass(0).unwrap()
-	).expect("unable to create pipeline"));
+		)))
+		.build(&device)
+		.expect("unable to create pipeline"));
 
 	let mut renderer = None;
 	let mut render_queue = Some(queue);
@@ -218,7 +204,7 @@ fn get_device<'a>(physical_device: &'a PhysicalDevice, queue_family: QueueFamily
 	};
 
 	Device::new(
-		physical_device.clone(),
+		physical_device,
 		physical_device.supported_features(), // enabled features (all of them?)
 		&device_ext,
 		[(queue_family, 1.0)].iter().cloned()
@@ -226,10 +212,10 @@ fn get_device<'a>(physical_device: &'a PhysicalDevice, queue_family: QueueFamily
 }
 
 // Choose a surface format and color space.
-fn choose_format(surface_capabilities: &Capabilities) -> Option<(Format, ColorSpace)> {
-	for (format, color_space) in &surface_capabilities.supported_formats {
-		if *format == Format::B8G8R8A8Srgb && *color_space == ColorSpace::SrgbNonLinear {
-			return Some((*format, *color_space))
+fn choose_format(surface_formats: &[SurfaceFormat]) -> Option<(Format, ColorSpace)> {
+	for f in surface_formats {
+		if f.format == Format::B8G8R8A8Srgb && f.color_space == ColorSpace::SrgbNonLinear {
+			return Some((f.format, f.color_space))
 		}
 	}
 
@@ -246,7 +232,7 @@ unsafe fn load_shader_module<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> A
 	let mut file = File::open(path).expect("Unable to open shader file");
 	let mut buffer = Vec::new();
 	file.read_to_end(&mut buffer).expect("Unable to read shader file");
-	Arc::new(shader::Module::new(device, &buffer).expect("Unable to load shader module"))
+	Arc::new(shader::Module::from_bytes(device, &buffer).expect("Unable to load shader module"))
 }
 
 fn create_render_pass(device: &Arc<Device>, format: Format) -> Arc<framebuffer::RenderPass> {
@@ -279,7 +265,7 @@ fn create_render_pass(device: &Arc<Device>, format: Format) -> Arc<framebuffer::
 
 pub struct Renderer<W> {
 	swapchain: Swapchain<W>,
-	command_buffers: Vec<command::buffer::LocallyRecorded<'static, command::pool::raw::RcBuffer>>,
+	command_buffers: Vec<command::buffer::LocalExecutable<'static, command::pool::raw::RcBuffer>>,
 	queue: Queue,
 	image_available_semaphore: semaphore::Raw,
 	render_finished_semaphore: semaphore::Raw,
@@ -309,10 +295,11 @@ impl<W: 'static> Renderer<W> {
 			1,
 			image::Usage::color_attachment(),
 			Some(&queue),
-			surface_capabilities.current_transform,
-			CompositeAlpha::Opaque, // ignore alpha component.
+			None, // current transform.
+			Some(CompositeAlpha::Opaque), // ignore alpha component.
 			PresentMode::Fifo, // guaranteed to exist.
 			true,
+			FullScreenExclusive::Default,
 			None
 		).expect("unable to create swapchain");
 	
@@ -339,18 +326,33 @@ impl<W: 'static> Renderer<W> {
 			).expect("unable to create framebuffer"))
 		}).collect();
 	
-		let pool = Rc::new(command::pool::Raw::new(&device, queue.family()).expect("unable to create command pool"));
-		let command_buffers = pool.allocate_rc(framebuffers.len() as u32).expect("unable to allocate command buffers");
+		// A single triangle, drawn with an index buffer.
+		let allocator = mem::staging::Allocator::new(mem::Unbounded::new(device));
+		let index_buffer: Arc<mem::buffer::Typed<[u16; 3]>> = Arc::new(mem::buffer::Typed::new(
+			&allocator,
+			1,
+			mem::buffer::Usage::IndexBuffer,
+			std::iter::once(&queue)
+		).expect("unable to create index buffer"));
+
+		unsafe {
+			let indexes: [[u16; 3]; 1] = [[0, 1, 2]];
+			let ptr = index_buffer.memory_slot().ptr().expect("index buffer is not host visible");
+			std::ptr::copy_nonoverlapping(indexes.as_ptr(), ptr as *mut [u16; 3], 1);
+		}
+
+		let pool = Rc::new(command::pool::Raw::new(&device, queue.family(), command::pool::Flags { reset_command_buffer: true, ..command::pool::Flags::none() }).expect("unable to create command pool"));
+		let command_buffers = pool.allocate_rc(framebuffers.len() as u32, command::pool::Level::Primary).expect("unable to allocate command buffers");
 		let recorded_command_buffers: Vec<_> = command_buffers.into_iter().enumerate().map(|(i, buffer)| {
-			buffer.record_local(|b| {
+			buffer.record_local(command::buffer::Usage::SimultaneousUse, |b| {
 				let mut render_pass = b.begin_render_pass(
 					&render_pass,
 					&framebuffers[i],
-					(0, 0, dimensions.0, dimensions.1),
-					&[ClearValue::f32color(0.0, 0.0, 0.0, 1.0)]
-				);
+					None,
+					&[ClearValue::BLACK]
+				).expect("unable to begin render pass");
 
-				render_pass.bind_pipeline(&pipeline, ()).draw((), (), 3, 1, 0, 0);
+				render_pass.bind_pipeline(&pipeline, ()).expect("unable to bind pipeline").draw_indexed((), (), index_buffer.clone(), 0, 1, 1, 0, 0, 0).expect("unable to record draw");
 			}).expect("unable to record command buffer")
 		}).collect();
 	
@@ -369,18 +371,18 @@ impl<W: 'static> Renderer<W> {
 	}
 
 	pub fn render(&mut self) {
-		let ((next_index, _), image_acquired) = self.swapchain
+		let (acquired, image_acquired) = self.swapchain
 			.acquire_next_image(None)
 			.then_signal_semaphore(&self.image_available_semaphore)
 			.expect("unable to acquire next image");
 
 		let ((), render_finished) = image_acquired
-			.and_then_pipeline_stages_of(self.queue.submit(&self.command_buffers[next_index as usize]), pipeline::stage::Flags::TOP_OF_PIPE)
+			.and_then_pipeline_stages_of(self.queue.submit(&self.command_buffers[acquired.image_index as usize]), pipeline::stage::Flags::TOP_OF_PIPE)
 			.then_signal_semaphore_and_fence(&self.render_finished_semaphore, &self.render_finished_fence)
 			.expect("unable to render");
 
 		let (_, render_finished) = render_finished
-			.and_then(self.queue.present(&self.swapchain, next_index))
+			.and_then(self.queue.present(&self.swapchain, acquired.image_index))
 			.in_parallel()
 			.expect("unable to present");
 
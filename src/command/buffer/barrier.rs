@@ -0,0 +1,476 @@
+//! Pipeline barriers.
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::{
+	marker::PhantomData,
+	ops::Range,
+	fmt
+};
+use crate::{
+	Device,
+	device::Queue,
+	image::{
+		self,
+		ImageProperties,
+		LayoutTracker,
+		view::SubresourceRange
+	},
+	mem::BufferProperties,
+	framebuffer::render_pass::subpass::{
+		PipelineStages,
+		AccessFlagBits
+	}
+};
+use super::{
+	Transitions,
+	current_layout
+};
+
+/// Invalid pipeline barrier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarrierError {
+	/// The range of a barrier is empty or exceeds the buffer, or the mip levels or array layers of the image.
+	OutOfBounds,
+
+	/// The old layout of a tracked image barrier is not the current layout of the image.
+	LayoutMismatch {
+		expected: image::Layout,
+		found: image::Layout
+	},
+
+	/// Images cannot be transitioned to the `Undefined` or `Preinitialized` layouts.
+	InvalidTarget(image::Layout),
+
+	/// The layout of a tracked image is changed on part of its subresources only.
+	PartialTransition,
+
+	/// The source or destination stages are empty.
+	NoStages,
+
+	/// Queue ownership transfers cannot be performed while waiting for events.
+	EventQueueTransfer
+}
+
+impl fmt::Display for BarrierError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfBounds => write!(f, "barrier range out of the resource"),
+			Self::LayoutMismatch { expected, found } => write!(f, "barrier from the {:?} layout of an image in the {:?} layout", found, expected),
+			Self::InvalidTarget(layout) => write!(f, "cannot transition to the {:?} layout", layout),
+			Self::PartialTransition => write!(f, "partial layout transition of a tracked image"),
+			Self::NoStages => write!(f, "empty barrier stages"),
+			Self::EventQueueTransfer => write!(f, "queue ownership transfer in an event wait")
+		}
+	}
+}
+
+impl std::error::Error for BarrierError { }
+
+/// Source and destination queue families of an ownership transfer.
+fn queue_families(src: &Queue, dst: &Queue) -> (u32, u32) {
+	if src.family_index() == dst.family_index() {
+		(vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED)
+	} else {
+		(src.family_index(), dst.family_index())
+	}
+}
+
+/// Memory barrier on a range of a buffer.
+pub struct BufferBarrier<'a> {
+	handle: vk::Buffer,
+	size: u64,
+	range: Range<u64>,
+	src_access: vk::AccessFlags,
+	dst_access: vk::AccessFlags,
+	queue_families: (u32, u32),
+	buffer: PhantomData<&'a ()>
+}
+
+impl<'a> BufferBarrier<'a> {
+	/// Barrier on the given range of the buffer, in bytes.
+	pub fn new<B: BufferProperties>(buffer: &'a B, range: Range<u64>, src_access: AccessFlagBits, dst_access: AccessFlagBits) -> BufferBarrier<'a> {
+		BufferBarrier {
+			handle: buffer.handle(),
+			size: buffer.size(),
+			range,
+			src_access: src_access.into_vulkan(),
+			dst_access: dst_access.into_vulkan(),
+			queue_families: (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED),
+			buffer: PhantomData
+		}
+	}
+
+	/// Barrier on the whole buffer.
+	pub fn whole<B: BufferProperties>(buffer: &'a B, src_access: AccessFlagBits, dst_access: AccessFlagBits) -> BufferBarrier<'a> {
+		Self::new(buffer, 0..buffer.size(), src_access, dst_access)
+	}
+
+	/// Transfer the ownership of the buffer range from the family of the `src` queue to the family of the `dst` queue.
+	///
+	/// The same barrier must be recorded in a buffer submitted to each queue.
+	pub fn with_queue_transfer(mut self, src: &Queue, dst: &Queue) -> BufferBarrier<'a> {
+		self.queue_families = queue_families(src, dst);
+		self
+	}
+
+	fn into_vulkan(&self) -> Result<vk::BufferMemoryBarrier, BarrierError> {
+		if self.range.start >= self.range.end || self.range.end > self.size {
+			return Err(BarrierError::OutOfBounds)
+		}
+
+		Ok(vk::BufferMemoryBarrier {
+			src_access_mask: self.src_access,
+			dst_access_mask: self.dst_access,
+			src_queue_family_index: self.queue_families.0,
+			dst_queue_family_index: self.queue_families.1,
+			buffer: self.handle,
+			offset: self.range.start,
+			size: self.range.end - self.range.start,
+			..Default::default()
+		})
+	}
+}
+
+/// Memory barrier on a subresource range of an image, with a layout transition.
+pub struct ImageBarrier<'a> {
+	handle: vk::Image,
+	tracker: Option<&'a LayoutTracker>,
+	mip_levels: u32,
+	array_layers: u32,
+	range: SubresourceRange,
+	old_layout: image::Layout,
+	new_layout: image::Layout,
+	src_access: vk::AccessFlags,
+	dst_access: vk::AccessFlags,
+	queue_families: (u32, u32)
+}
+
+impl<'a> ImageBarrier<'a> {
+	/// Barrier on the whole image, from `old_layout` to `new_layout`.
+	///
+	/// The access masks are derived from the layouts.
+	pub fn new<I: ImageProperties>(image: &'a I, old_layout: image::Layout, new_layout: image::Layout) -> ImageBarrier<'a> {
+		let (_, src_access, _) = old_layout.transition_masks();
+		let (_, _, dst_access) = new_layout.transition_masks();
+
+		ImageBarrier {
+			handle: image.handle(),
+			tracker: image.layout_tracker(),
+			mip_levels: image.mip_levels(),
+			array_layers: image.array_layers(),
+			range: SubresourceRange {
+				aspects: image.format().aspects(),
+				base_mip_level: 0,
+				level_count: vk::REMAINING_MIP_LEVELS,
+				base_array_layer: 0,
+				layer_count: vk::REMAINING_ARRAY_LAYERS
+			},
+			old_layout,
+			new_layout,
+			src_access,
+			dst_access,
+			queue_families: (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED)
+		}
+	}
+
+	/// Restrict the barrier to the given subresource range.
+	pub fn with_range(mut self, range: SubresourceRange) -> ImageBarrier<'a> {
+		self.range = range;
+		self
+	}
+
+	/// Use the given access masks instead of the ones derived from the layouts.
+	///
+	/// Useful when the image is accessed in ways the layouts do not imply,
+	/// such as storage writes in the `General` layout, or when no layout transition is made.
+	pub fn with_access(mut self, src_access: AccessFlagBits, dst_access: AccessFlagBits) -> ImageBarrier<'a> {
+		self.src_access = src_access.into_vulkan();
+		self.dst_access = dst_access.into_vulkan();
+		self
+	}
+
+	/// Transfer the ownership of the image range from the family of the `src` queue to the family of the `dst` queue.
+	///
+	/// The same barrier must be recorded in a buffer submitted to each queue.
+	pub fn with_queue_transfer(mut self, src: &Queue, dst: &Queue) -> ImageBarrier<'a> {
+		self.queue_families = queue_families(src, dst);
+		self
+	}
+
+	/// Do not check nor track the layout of the image.
+	///
+	/// Used by the acquire barrier of an ownership transfer,
+	/// whose layout transition is already tracked by the release barrier.
+	pub(crate) fn untracked(mut self) -> ImageBarrier<'a> {
+		self.tracker = None;
+		self
+	}
+
+	/// Checks if the barrier covers all the subresources of the image.
+	fn is_whole(&self) -> bool {
+		// `REMAINING_MIP_LEVELS` and `REMAINING_ARRAY_LAYERS` are the maximum count.
+		let covers = |base: u32, count: u32, total: u32| base == 0 && count >= total;
+		covers(self.range.base_mip_level, self.range.level_count, self.mip_levels)
+		&& covers(self.range.base_array_layer, self.range.layer_count, self.array_layers)
+	}
+
+	/// Checks if the barrier range is in the mip levels and array layers of the image.
+	fn is_in_bounds(&self) -> bool {
+		// `REMAINING_MIP_LEVELS` and `REMAINING_ARRAY_LAYERS` cover the remaining subresources.
+		let fits = |base: u32, count: u32, total: u32, remaining: u32| base < total && count > 0 && (count == remaining || base as u64 + count as u64 <= total as u64);
+		fits(self.range.base_mip_level, self.range.level_count, self.mip_levels, vk::REMAINING_MIP_LEVELS)
+		&& fits(self.range.base_array_layer, self.range.layer_count, self.array_layers, vk::REMAINING_ARRAY_LAYERS)
+	}
+
+	/// The old layout of a tracked image is checked against its layout after the given transitions.
+	fn into_vulkan(&self, transitions: &Transitions) -> Result<vk::ImageMemoryBarrier, BarrierError> {
+		if !self.is_in_bounds() {
+			return Err(BarrierError::OutOfBounds)
+		}
+
+		// Transitions from `Undefined` discard the contents and are valid from any layout.
+		if let Some(tracker) = self.tracker {
+			let current = current_layout(transitions, tracker);
+			if self.old_layout != image::Layout::Undefined && self.old_layout != current {
+				return Err(BarrierError::LayoutMismatch {
+					expected: current,
+					found: self.old_layout
+				})
+			}
+		}
+
+		if self.old_layout != self.new_layout {
+			if !self.new_layout.is_transition_target() {
+				return Err(BarrierError::InvalidTarget(self.new_layout))
+			}
+
+			if self.tracker.is_some() && !self.is_whole() {
+				return Err(BarrierError::PartialTransition)
+			}
+		}
+
+		Ok(vk::ImageMemoryBarrier {
+			src_access_mask: self.src_access,
+			dst_access_mask: self.dst_access,
+			old_layout: self.old_layout.into_vulkan(),
+			new_layout: self.new_layout.into_vulkan(),
+			src_queue_family_index: self.queue_families.0,
+			dst_queue_family_index: self.queue_families.1,
+			image: self.handle,
+			subresource_range: self.range.into_vulkan(),
+			..Default::default()
+		})
+	}
+}
+
+/// Pipeline barrier builder.
+///
+/// Also used to wait for events, in which case the barriers apply once the events are set.
+/// Nothing is recorded until `record` is called.
+pub struct Barrier<'r, 'a> {
+	device: &'r Device,
+	buffer: vk::CommandBuffer,
+	transitions: &'r mut Transitions<'a>,
+	events: Vec<vk::Event>,
+	by_region: bool,
+	memory_barriers: Vec<vk::MemoryBarrier>,
+	buffer_barriers: Vec<BufferBarrier<'a>>,
+	image_barriers: Vec<ImageBarrier<'a>>
+}
+
+impl<'r, 'a> Barrier<'r, 'a> {
+	pub(crate) fn new(device: &'r Device, buffer: vk::CommandBuffer, transitions: &'r mut Transitions<'a>) -> Barrier<'r, 'a> {
+		Barrier {
+			device,
+			buffer,
+			transitions,
+			events: Vec::new(),
+			by_region: false,
+			memory_barriers: Vec::new(),
+			buffer_barriers: Vec::new(),
+			image_barriers: Vec::new()
+		}
+	}
+
+	/// Wait for the given events instead of the previous commands.
+	pub(crate) fn with_events(mut self, events: Vec<vk::Event>) -> Barrier<'r, 'a> {
+		self.events = events;
+		self
+	}
+
+	/// Global memory barrier.
+	pub fn memory(mut self, src_access: AccessFlagBits, dst_access: AccessFlagBits) -> Barrier<'r, 'a> {
+		self.memory_barriers.push(vk::MemoryBarrier {
+			src_access_mask: src_access.into_vulkan(),
+			dst_access_mask: dst_access.into_vulkan(),
+			..Default::default()
+		});
+		self
+	}
+
+	pub fn buffer(mut self, barrier: BufferBarrier<'a>) -> Barrier<'r, 'a> {
+		self.buffer_barriers.push(barrier);
+		self
+	}
+
+	pub fn image(mut self, barrier: ImageBarrier<'a>) -> Barrier<'r, 'a> {
+		self.image_barriers.push(barrier);
+		self
+	}
+
+	/// Make the dependency framebuffer-local.
+	///
+	/// Ignored when waiting for events.
+	pub fn by_region(mut self) -> Barrier<'r, 'a> {
+		self.by_region = true;
+		self
+	}
+
+	/// Record the barrier between the given stages.
+	///
+	/// The tracked layouts of the transitioned images are updated when the command buffer is submitted.
+	pub fn record(self, src_stages: PipelineStages, dst_stages: PipelineStages) -> Result<(), BarrierError> {
+		let (src_stages, dst_stages) = (src_stages.into_vulkan(), dst_stages.into_vulkan());
+		if src_stages.is_empty() || dst_stages.is_empty() {
+			return Err(BarrierError::NoStages)
+		}
+
+		let buffer_barriers = self.buffer_barriers.iter().map(BufferBarrier::into_vulkan).collect::<Result<Vec<_>, _>>()?;
+		let image_barriers = self.image_barriers.iter().map(|b| b.into_vulkan(self.transitions)).collect::<Result<Vec<_>, _>>()?;
+
+		if self.events.is_empty() {
+			let dependency_flags = if self.by_region {
+				vk::DependencyFlags::BY_REGION
+			} else {
+				vk::DependencyFlags::empty()
+			};
+
+			unsafe {
+				self.device.handle().cmd_pipeline_barrier(
+					self.buffer,
+					src_stages,
+					dst_stages,
+					dependency_flags,
+					&self.memory_barriers,
+					&buffer_barriers,
+					&image_barriers
+				)
+			}
+		} else {
+			let transfers = buffer_barriers.iter().any(|b| b.src_queue_family_index != b.dst_queue_family_index)
+				|| image_barriers.iter().any(|b| b.src_queue_family_index != b.dst_queue_family_index);
+			if transfers {
+				return Err(BarrierError::EventQueueTransfer)
+			}
+
+			unsafe {
+				self.device.handle().cmd_wait_events(
+					self.buffer,
+					&self.events,
+					src_stages,
+					dst_stages,
+					&self.memory_barriers,
+					&buffer_barriers,
+					&image_barriers
+				)
+			}
+		}
+
+		for barrier in &self.image_barriers {
+			if let Some(tracker) = barrier.tracker {
+				if barrier.old_layout != barrier.new_layout {
+					self.transitions.push((tracker, barrier.new_layout))
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ash::vk::Handle;
+	use super::*;
+
+	fn barrier(tracker: Option<&LayoutTracker>, old_layout: image::Layout, new_layout: image::Layout) -> ImageBarrier<'_> {
+		let (_, src_access, _) = old_layout.transition_masks();
+		let (_, _, dst_access) = new_layout.transition_masks();
+
+		ImageBarrier {
+			handle: vk::Image::from_raw(1),
+			tracker,
+			mip_levels: 4,
+			array_layers: 2,
+			range: SubresourceRange {
+				aspects: image::view::Aspects::color(),
+				base_mip_level: 0,
+				level_count: vk::REMAINING_MIP_LEVELS,
+				base_array_layer: 0,
+				layer_count: vk::REMAINING_ARRAY_LAYERS
+			},
+			old_layout,
+			new_layout,
+			src_access,
+			dst_access,
+			queue_families: (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED)
+		}
+	}
+
+	fn range(base_mip_level: u32, level_count: u32, base_array_layer: u32, layer_count: u32) -> SubresourceRange {
+		SubresourceRange {
+			aspects: image::view::Aspects::color(),
+			base_mip_level,
+			level_count,
+			base_array_layer,
+			layer_count
+		}
+	}
+
+	#[test]
+	fn image_barrier_range_is_in_bounds() {
+		let barrier = |range| barrier(None, image::Layout::General, image::Layout::General).with_range(range).into_vulkan(&Vec::new()).map(|_| ());
+		assert_eq!(barrier(range(0, 4, 0, 2)), Ok(()));
+		assert_eq!(barrier(range(3, 1, 1, 1)), Ok(()));
+		assert_eq!(barrier(range(2, vk::REMAINING_MIP_LEVELS, 1, vk::REMAINING_ARRAY_LAYERS)), Ok(()));
+		assert_eq!(barrier(range(4, vk::REMAINING_MIP_LEVELS, 0, 1)), Err(BarrierError::OutOfBounds));
+		assert_eq!(barrier(range(2, 3, 0, 1)), Err(BarrierError::OutOfBounds));
+		assert_eq!(barrier(range(0, 1, 1, 2)), Err(BarrierError::OutOfBounds));
+		assert_eq!(barrier(range(0, 0, 0, 1)), Err(BarrierError::OutOfBounds));
+		assert_eq!(barrier(range(0, 1, 0, 0)), Err(BarrierError::OutOfBounds));
+	}
+
+	#[test]
+	fn image_barrier_matches_the_tracked_layout() {
+		let tracker = LayoutTracker::new(image::Layout::TransferDstOptimal);
+		let barrier = |old_layout, transitions: &Transitions| barrier(Some(&tracker), old_layout, image::Layout::ShaderReadOnlyOptimal).into_vulkan(transitions).map(|_| ());
+
+		assert_eq!(barrier(image::Layout::TransferDstOptimal, &Vec::new()), Ok(()));
+		assert_eq!(barrier(image::Layout::Undefined, &Vec::new()), Ok(()));
+		assert_eq!(barrier(image::Layout::General, &Vec::new()), Err(BarrierError::LayoutMismatch {
+			expected: image::Layout::TransferDstOptimal,
+			found: image::Layout::General
+		}));
+
+		// Transitions recorded earlier in the command buffer.
+		let transitions = vec![(&tracker, image::Layout::General)];
+		assert_eq!(barrier(image::Layout::General, &transitions), Ok(()));
+		assert_eq!(barrier(image::Layout::TransferDstOptimal, &transitions), Err(BarrierError::LayoutMismatch {
+			expected: image::Layout::General,
+			found: image::Layout::TransferDstOptimal
+		}));
+
+		// Untracked images are not checked.
+		let untracked = self::barrier(None, image::Layout::General, image::Layout::ShaderReadOnlyOptimal);
+		assert!(untracked.into_vulkan(&Vec::new()).is_ok());
+	}
+
+	#[test]
+	fn tracked_images_are_transitioned_whole() {
+		let tracker = LayoutTracker::new(image::Layout::General);
+		let partial = barrier(Some(&tracker), image::Layout::General, image::Layout::TransferSrcOptimal).with_range(range(0, 1, 0, 2));
+		assert_eq!(partial.into_vulkan(&Vec::new()).map(|_| ()), Err(BarrierError::PartialTransition));
+	}
+}
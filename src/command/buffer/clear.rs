@@ -0,0 +1,347 @@
+use std::fmt;
+use ash::vk;
+use crate::{
+	Format,
+	Image,
+	format::ClearColor,
+	framebuffer::{
+		self,
+		rendering::RenderingInfo
+	},
+	image::{
+		self,
+		ImageProperties,
+		view::{
+			Aspects,
+			SubresourceRange
+		}
+	}
+};
+
+/// Invalid clear command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClearError {
+	/// The image was not created with the transfer destination usage.
+	MissingTransferDestinationUsage,
+
+	/// Images are cleared in the `General` or `TransferDstOptimal` layouts.
+	InvalidLayout(image::Layout),
+
+	/// The clear value does not match the format of the image or attachment.
+	IncompatibleFormat(Format),
+
+	/// The aspects of a range are empty or not present in the image format.
+	InvalidAspects,
+
+	/// A range or rectangle is empty or exceeds the mip levels or array layers of the image.
+	OutOfRange,
+
+	/// The current subpass has no color attachment with the given index.
+	MissingColorAttachment(u32),
+
+	/// The current subpass has no depth or stencil attachment.
+	MissingDepthStencilAttachment,
+
+	/// The current subpass was begun with the `SecondaryCommandBuffers` contents.
+	SecondaryContents
+}
+
+impl fmt::Display for ClearError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingTransferDestinationUsage => write!(f, "image is not a transfer destination"),
+			Self::InvalidLayout(layout) => write!(f, "cannot clear an image in the {:?} layout", layout),
+			Self::IncompatibleFormat(format) => write!(f, "clear value incompatible with format {:?}", format),
+			Self::InvalidAspects => write!(f, "invalid aspects"),
+			Self::OutOfRange => write!(f, "range out of the image subresources"),
+			Self::MissingColorAttachment(index) => write!(f, "no color attachment {} in the current subpass", index),
+			Self::MissingDepthStencilAttachment => write!(f, "no depth-stencil attachment in the current subpass"),
+			Self::SecondaryContents => write!(f, "command recorded in a subpass of secondary command buffers")
+		}
+	}
+}
+
+impl std::error::Error for ClearError { }
+
+/// Attachment cleared inside a render pass.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ClearAttachment {
+	/// Color attachment of the current subpass, with its index in the subpass.
+	Color(u32, ClearColor),
+
+	Depth(f32),
+
+	Stencil(u32),
+
+	DepthStencil {
+		depth: f32,
+		stencil: u32
+	}
+}
+
+impl ClearAttachment {
+	pub(crate) fn into_vulkan(self) -> vk::ClearAttachment {
+		let (aspects, color_attachment, clear_value) = match self {
+			ClearAttachment::Color(index, color) => (Aspects::color(), index, vk::ClearValue { color: color.into_vulkan() }),
+			ClearAttachment::Depth(depth) => (Aspects::depth(), 0, vk::ClearValue { depth_stencil: vk::ClearDepthStencilValue { depth, stencil: 0 } }),
+			ClearAttachment::Stencil(stencil) => (Aspects::stencil(), 0, vk::ClearValue { depth_stencil: vk::ClearDepthStencilValue { depth: 0.0, stencil } }),
+			ClearAttachment::DepthStencil { depth, stencil } => (Aspects::new(false, true, true, false), 0, vk::ClearValue { depth_stencil: vk::ClearDepthStencilValue { depth, stencil } })
+		};
+
+		vk::ClearAttachment {
+			aspect_mask: aspects.into_vulkan(),
+			color_attachment,
+			clear_value
+		}
+	}
+}
+
+/// Area of the attachments cleared inside a render pass.
+#[derive(Clone, Copy, Debug)]
+pub struct ClearRect {
+	pub offset: (i32, i32),
+	pub extent: (u32, u32),
+	pub base_array_layer: u32,
+	pub layer_count: u32
+}
+
+impl ClearRect {
+	/// Rectangle of the given offset and extent, on the first array layer.
+	pub fn new((x, y): (i32, i32), (width, height): (u32, u32)) -> ClearRect {
+		ClearRect {
+			offset: (x, y),
+			extent: (width, height),
+			base_array_layer: 0,
+			layer_count: 1
+		}
+	}
+
+	pub(crate) fn into_vulkan(self) -> vk::ClearRect {
+		let ((x, y), (width, height)) = (self.offset, self.extent);
+		vk::ClearRect {
+			rect: vk::Rect2D {
+				offset: vk::Offset2D { x, y },
+				extent: vk::Extent2D { width, height }
+			},
+			base_array_layer: self.base_array_layer,
+			layer_count: self.layer_count
+		}
+	}
+}
+
+/// Attachments of the current subpass of a render pass, or of a dynamic rendering.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SubpassAttachments {
+	color: u32,
+
+	/// Formats of the depth and stencil attachments.
+	depth: Option<Format>,
+	stencil: Option<Format>,
+
+	/// Render area and layer count, unknown in secondary command buffers.
+	area: Option<(i32, i32, u32, u32)>,
+	layer_count: Option<u32>
+}
+
+impl SubpassAttachments {
+	pub fn subpass(render_pass: &framebuffer::RenderPass, index: u32, area: Option<(i32, i32, u32, u32)>, layer_count: Option<u32>) -> SubpassAttachments {
+		let depth_stencil = render_pass.subpass_depth_stencil_format(index);
+		SubpassAttachments {
+			color: render_pass.subpass_color_attachment_count(index).unwrap_or(0),
+			depth: depth_stencil,
+			stencil: depth_stencil,
+			area,
+			layer_count
+		}
+	}
+
+	/// Attachments of another subpass of the same render pass instance.
+	pub fn next_subpass(&self, render_pass: &framebuffer::RenderPass, index: u32) -> SubpassAttachments {
+		SubpassAttachments::subpass(render_pass, index, self.area, self.layer_count)
+	}
+
+	pub fn rendering<I: Image>(rendering: &RenderingInfo<I>) -> SubpassAttachments {
+		SubpassAttachments {
+			color: rendering.color_attachments.len() as u32,
+			depth: rendering.depth_attachment.map(|a| a.view.format()),
+			stencil: rendering.stencil_attachment.map(|a| a.view.format()),
+			area: Some(rendering.render_area),
+			layer_count: Some(rendering.layer_count)
+		}
+	}
+
+	fn check(&self, attachment: &ClearAttachment) -> Result<(), ClearError> {
+		let check_depth = || match self.depth {
+			Some(format) if format.has_depth() => Ok(()),
+			Some(format) => Err(ClearError::IncompatibleFormat(format)),
+			None => Err(ClearError::MissingDepthStencilAttachment)
+		};
+
+		let check_stencil = || match self.stencil {
+			Some(format) if format.has_stencil() => Ok(()),
+			Some(format) => Err(ClearError::IncompatibleFormat(format)),
+			None => Err(ClearError::MissingDepthStencilAttachment)
+		};
+
+		match *attachment {
+			ClearAttachment::Color(index, _) => if index < self.color {
+				Ok(())
+			} else {
+				Err(ClearError::MissingColorAttachment(index))
+			},
+			ClearAttachment::Depth(_) => check_depth(),
+			ClearAttachment::Stencil(_) => check_stencil(),
+			ClearAttachment::DepthStencil { .. } => check_depth().and_then(|_| check_stencil())
+		}
+	}
+
+	fn check_rect(&self, rect: &ClearRect) -> Result<(), ClearError> {
+		let ((x, y), (width, height)) = (rect.offset, rect.extent);
+		if width == 0 || height == 0 || rect.layer_count == 0 {
+			return Err(ClearError::OutOfRange)
+		}
+
+		if let Some((area_x, area_y, area_width, area_height)) = self.area {
+			let fits = |offset: i32, len: u32, area_offset: i32, area_len: u32| offset >= area_offset && offset as i64 + len as i64 <= area_offset as i64 + area_len as i64;
+			if !fits(x, width, area_x, area_width) || !fits(y, height, area_y, area_height) {
+				return Err(ClearError::OutOfRange)
+			}
+		}
+
+		if let Some(layer_count) = self.layer_count {
+			if rect.base_array_layer as u64 + rect.layer_count as u64 > layer_count as u64 {
+				return Err(ClearError::OutOfRange)
+			}
+		}
+
+		Ok(())
+	}
+}
+
+pub(crate) fn check_clear_attachments(subpass: &SubpassAttachments, attachments: &[ClearAttachment], rects: &[ClearRect]) -> Result<(), ClearError> {
+	attachments.iter().try_for_each(|a| subpass.check(a))?;
+	rects.iter().try_for_each(|r| subpass.check_rect(r))
+}
+
+fn check_clear_image<I: ImageProperties>(image: &I, layout: image::Layout, ranges: &[SubresourceRange]) -> Result<(), ClearError> {
+	if !image.usage().transfer_destination {
+		return Err(ClearError::MissingTransferDestinationUsage)
+	}
+
+	if layout != image::Layout::General && layout != image::Layout::TransferDstOptimal {
+		return Err(ClearError::InvalidLayout(layout))
+	}
+
+	for range in ranges {
+		if range.aspects.is_empty() || !image.format().aspects().contains(range.aspects) {
+			return Err(ClearError::InvalidAspects)
+		}
+
+		// `REMAINING_MIP_LEVELS` and `REMAINING_ARRAY_LAYERS` cover the remaining subresources.
+		let fits = |base: u32, count: u32, total: u32, remaining: u32| base < total && count > 0 && (count == remaining || base as u64 + count as u64 <= total as u64);
+		if !fits(range.base_mip_level, range.level_count, image.mip_levels(), vk::REMAINING_MIP_LEVELS)
+		|| !fits(range.base_array_layer, range.layer_count, image.array_layers(), vk::REMAINING_ARRAY_LAYERS) {
+			return Err(ClearError::OutOfRange)
+		}
+	}
+
+	Ok(())
+}
+
+pub(crate) fn check_clear_color_image<I: ImageProperties>(image: &I, layout: image::Layout, color: &ClearColor, ranges: &[SubresourceRange]) -> Result<(), ClearError> {
+	if !color.is_compatible_with(image.format()) {
+		return Err(ClearError::IncompatibleFormat(image.format()))
+	}
+
+	check_clear_image(image, layout, ranges)
+}
+
+pub(crate) fn check_clear_depth_stencil_image<I: ImageProperties>(image: &I, layout: image::Layout, ranges: &[SubresourceRange]) -> Result<(), ClearError> {
+	let format = image.format();
+	if !format.has_depth() && !format.has_stencil() {
+		return Err(ClearError::IncompatibleFormat(format))
+	}
+
+	check_clear_image(image, layout, ranges)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const COLOR: ClearAttachment = ClearAttachment::Color(0, ClearColor::F32([0.0; 4]));
+
+	fn subpass(color: u32, depth: Option<Format>, stencil: Option<Format>) -> SubpassAttachments {
+		SubpassAttachments {
+			color,
+			depth,
+			stencil,
+			area: Some((0, 0, 16, 16)),
+			layer_count: Some(2)
+		}
+	}
+
+	#[test]
+	fn cleared_attachments_are_in_the_subpass() {
+		let color_only = subpass(2, None, None);
+		assert_eq!(color_only.check(&COLOR), Ok(()));
+		assert_eq!(color_only.check(&ClearAttachment::Color(1, ClearColor::U32([0; 4]))), Ok(()));
+		assert_eq!(color_only.check(&ClearAttachment::Color(2, ClearColor::U32([0; 4]))), Err(ClearError::MissingColorAttachment(2)));
+		assert_eq!(color_only.check(&ClearAttachment::Depth(1.0)), Err(ClearError::MissingDepthStencilAttachment));
+		assert_eq!(color_only.check(&ClearAttachment::Stencil(0)), Err(ClearError::MissingDepthStencilAttachment));
+
+		// Dynamic rendering may only have one of the depth and stencil attachments.
+		let depth_only = subpass(0, Some(Format::D32Sfloat), None);
+		assert_eq!(depth_only.check(&COLOR), Err(ClearError::MissingColorAttachment(0)));
+		assert_eq!(depth_only.check(&ClearAttachment::Depth(1.0)), Ok(()));
+		assert_eq!(depth_only.check(&ClearAttachment::Stencil(0)), Err(ClearError::MissingDepthStencilAttachment));
+		assert_eq!(depth_only.check(&ClearAttachment::DepthStencil { depth: 1.0, stencil: 0 }), Err(ClearError::MissingDepthStencilAttachment));
+
+		let depth_stencil = subpass(0, Some(Format::D24Unorm_S8Uint), Some(Format::D24Unorm_S8Uint));
+		assert_eq!(depth_stencil.check(&ClearAttachment::Stencil(0)), Ok(()));
+		assert_eq!(depth_stencil.check(&ClearAttachment::DepthStencil { depth: 1.0, stencil: 0 }), Ok(()));
+	}
+
+	#[test]
+	fn cleared_aspects_are_in_the_attachment_format() {
+		// Render pass subpasses use the same attachment for depth and stencil.
+		let depth_only = subpass(0, Some(Format::D32Sfloat), Some(Format::D32Sfloat));
+		assert_eq!(depth_only.check(&ClearAttachment::Depth(1.0)), Ok(()));
+		assert_eq!(depth_only.check(&ClearAttachment::Stencil(0)), Err(ClearError::IncompatibleFormat(Format::D32Sfloat)));
+		assert_eq!(depth_only.check(&ClearAttachment::DepthStencil { depth: 1.0, stencil: 0 }), Err(ClearError::IncompatibleFormat(Format::D32Sfloat)));
+
+		let stencil_only = subpass(0, Some(Format::S8Uint), Some(Format::S8Uint));
+		assert_eq!(stencil_only.check(&ClearAttachment::Stencil(0)), Ok(()));
+		assert_eq!(stencil_only.check(&ClearAttachment::Depth(1.0)), Err(ClearError::IncompatibleFormat(Format::S8Uint)));
+	}
+
+	#[test]
+	fn clear_rects_are_not_empty() {
+		let subpass = subpass(1, Some(Format::D32Sfloat), None);
+		let rect = ClearRect::new((0, 0), (16, 16));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR, ClearAttachment::Depth(1.0)], &[rect]), Ok(()));
+		assert_eq!(check_clear_attachments(&subpass, &[ClearAttachment::Color(1, ClearColor::F32([0.0; 4]))], &[rect]), Err(ClearError::MissingColorAttachment(1)));
+
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[rect, ClearRect::new((0, 0), (0, 16))]), Err(ClearError::OutOfRange));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect::new((0, 0), (16, 0))]), Err(ClearError::OutOfRange));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect { layer_count: 0, ..rect }]), Err(ClearError::OutOfRange));
+	}
+
+	#[test]
+	fn clear_rects_are_in_the_render_area() {
+		let subpass = subpass(1, None, None);
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect::new((8, 8), (8, 8))]), Ok(()));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect::new((8, 8), (9, 8))]), Err(ClearError::OutOfRange));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect::new((-1, 0), (4, 4))]), Err(ClearError::OutOfRange));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect::new((i32::MAX, 0), (u32::MAX, 4))]), Err(ClearError::OutOfRange));
+
+		let rect = ClearRect::new((0, 0), (16, 16));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect { base_array_layer: 1, ..rect }]), Ok(()));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect { base_array_layer: 1, layer_count: 2, ..rect }]), Err(ClearError::OutOfRange));
+		assert_eq!(check_clear_attachments(&subpass, &[COLOR], &[ClearRect { base_array_layer: u32::MAX, ..rect }]), Err(ClearError::OutOfRange));
+
+		// Secondary command buffers do not know the render area.
+		let inherited = SubpassAttachments { area: None, layer_count: None, ..subpass };
+		assert_eq!(check_clear_attachments(&inherited, &[COLOR], &[ClearRect::new((32, 32), (8, 8))]), Ok(()));
+	}
+}
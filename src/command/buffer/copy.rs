@@ -0,0 +1,476 @@
+use std::fmt;
+use ash::vk;
+use crate::{
+	Format,
+	format::NumericType,
+	image::{
+		ImageProperties,
+		view::Aspects,
+		sampler::Filter
+	},
+	mem::BufferProperties
+};
+
+/// Invalid copy, blit or resolve command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyError {
+	/// The source was not created with the transfer source usage.
+	MissingTransferSourceUsage,
+
+	/// The destination was not created with the transfer destination usage.
+	MissingTransferDestinationUsage,
+
+	/// The aspects of a region are empty or not present in the image format.
+	///
+	/// Copies between buffers and images must also use a single aspect.
+	InvalidAspects,
+
+	/// A region is empty or exceeds the mip levels, array layers or extent of an image.
+	OutOfRange,
+
+	/// A region of a block compressed image is not aligned to the format blocks.
+	///
+	/// Offsets must be multiples of the block dimensions,
+	/// and extents too unless they reach the edge of the mip level.
+	UnalignedRegion,
+
+	/// Images copied into each other must have size compatible formats.
+	IncompatibleFormats(Format, Format),
+
+	/// The format cannot be used as blit source or destination.
+	UnsupportedBlit(Format),
+
+	/// The source format does not support linear filtering.
+	UnsupportedFilter(Format),
+
+	/// Resolves go from a multisampled image to a single sampled image of the same format.
+	InvalidResolve
+}
+
+impl fmt::Display for CopyError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingTransferSourceUsage => write!(f, "source is not a transfer source"),
+			Self::MissingTransferDestinationUsage => write!(f, "destination is not a transfer destination"),
+			Self::InvalidAspects => write!(f, "invalid aspects"),
+			Self::OutOfRange => write!(f, "region out of the image subresources"),
+			Self::UnalignedRegion => write!(f, "region not aligned to the format blocks"),
+			Self::IncompatibleFormats(a, b) => write!(f, "incompatible formats {:?} and {:?}", a, b),
+			Self::UnsupportedBlit(format) => write!(f, "format {:?} does not support blits", format),
+			Self::UnsupportedFilter(format) => write!(f, "format {:?} does not support linear filtering", format),
+			Self::InvalidResolve => write!(f, "invalid resolve")
+		}
+	}
+}
+
+impl std::error::Error for CopyError { }
+
+/// Array layers of a mip level of an image.
+#[derive(Clone, Copy, Debug)]
+pub struct SubresourceLayers {
+	pub aspects: Aspects,
+	pub mip_level: u32,
+	pub base_array_layer: u32,
+	pub layer_count: u32
+}
+
+impl SubresourceLayers {
+	/// All the array layers of the given mip level, with all the aspects of the image format.
+	pub fn mip<I: ImageProperties>(image: &I, mip_level: u32) -> SubresourceLayers {
+		SubresourceLayers {
+			aspects: image.format().aspects(),
+			mip_level,
+			base_array_layer: 0,
+			layer_count: image.array_layers()
+		}
+	}
+
+	pub(crate) fn into_vulkan(self) -> vk::ImageSubresourceLayers {
+		vk::ImageSubresourceLayers {
+			aspect_mask: self.aspects.into_vulkan(),
+			mip_level: self.mip_level,
+			base_array_layer: self.base_array_layer,
+			layer_count: self.layer_count
+		}
+	}
+}
+
+fn offset((x, y, z): (i32, i32, i32)) -> vk::Offset3D {
+	vk::Offset3D { x, y, z }
+}
+
+fn extent((width, height, depth): (u32, u32, u32)) -> vk::Extent3D {
+	vk::Extent3D { width, height, depth }
+}
+
+/// Region copied between a buffer and an image.
+#[derive(Clone, Copy, Debug)]
+pub struct BufferImageCopy {
+	pub buffer_offset: u64,
+
+	/// Number of texels per row in the buffer, or 0 for tightly packed rows.
+	pub row_length: u32,
+
+	/// Number of rows per image in the buffer, or 0 for tightly packed images.
+	pub image_height: u32,
+
+	pub image_subresource: SubresourceLayers,
+	pub offset: (i32, i32, i32),
+	pub extent: (u32, u32, u32)
+}
+
+impl BufferImageCopy {
+	/// Tightly packed copy of the first mip level of the image, with all its layers.
+	///
+	/// Depth-stencil images are copied through their depth aspect.
+	pub fn whole<I: ImageProperties>(image: &I) -> BufferImageCopy {
+		let format = image.format();
+		let aspects = if format.has_depth() {
+			Aspects::depth()
+		} else if format.has_stencil() {
+			Aspects::stencil()
+		} else {
+			Aspects::color()
+		};
+
+		BufferImageCopy {
+			buffer_offset: 0,
+			row_length: 0,
+			image_height: 0,
+			image_subresource: SubresourceLayers {
+				aspects,
+				..SubresourceLayers::mip(image, 0)
+			},
+			offset: (0, 0, 0),
+			extent: image.extent()
+		}
+	}
+
+	pub(crate) fn into_vulkan(self) -> vk::BufferImageCopy {
+		vk::BufferImageCopy {
+			buffer_offset: self.buffer_offset,
+			buffer_row_length: self.row_length,
+			buffer_image_height: self.image_height,
+			image_subresource: self.image_subresource.into_vulkan(),
+			image_offset: offset(self.offset),
+			image_extent: extent(self.extent)
+		}
+	}
+}
+
+/// Region copied between images.
+#[derive(Clone, Copy, Debug)]
+pub struct ImageCopy {
+	pub src_subresource: SubresourceLayers,
+	pub src_offset: (i32, i32, i32),
+	pub dst_subresource: SubresourceLayers,
+	pub dst_offset: (i32, i32, i32),
+	pub extent: (u32, u32, u32)
+}
+
+impl ImageCopy {
+	/// Copy of the first mip level of the image, with all its layers, into the same region of another image.
+	pub fn whole<I: ImageProperties>(image: &I) -> ImageCopy {
+		let subresource = SubresourceLayers::mip(image, 0);
+		ImageCopy {
+			src_subresource: subresource,
+			src_offset: (0, 0, 0),
+			dst_subresource: subresource,
+			dst_offset: (0, 0, 0),
+			extent: image.extent()
+		}
+	}
+
+	pub(crate) fn into_vulkan(self) -> vk::ImageCopy {
+		vk::ImageCopy {
+			src_subresource: self.src_subresource.into_vulkan(),
+			src_offset: offset(self.src_offset),
+			dst_subresource: self.dst_subresource.into_vulkan(),
+			dst_offset: offset(self.dst_offset),
+			extent: extent(self.extent)
+		}
+	}
+
+	pub(crate) fn into_vulkan_resolve(self) -> vk::ImageResolve {
+		vk::ImageResolve {
+			src_subresource: self.src_subresource.into_vulkan(),
+			src_offset: offset(self.src_offset),
+			dst_subresource: self.dst_subresource.into_vulkan(),
+			dst_offset: offset(self.dst_offset),
+			extent: extent(self.extent)
+		}
+	}
+}
+
+/// Region resolved from a multisampled image.
+pub type ImageResolve = ImageCopy;
+
+/// Region blitted between images.
+///
+/// Each region is given by two opposite corners, and is scaled to fit the destination region.
+#[derive(Clone, Copy, Debug)]
+pub struct ImageBlit {
+	pub src_subresource: SubresourceLayers,
+	pub src_offsets: [(i32, i32, i32); 2],
+	pub dst_subresource: SubresourceLayers,
+	pub dst_offsets: [(i32, i32, i32); 2]
+}
+
+impl ImageBlit {
+	/// Blit of the whole first mip level of an image into the whole first mip level of another.
+	pub fn whole<S: ImageProperties, D: ImageProperties>(src: &S, dst: &D) -> ImageBlit {
+		let corner = |(width, height, depth): (u32, u32, u32)| (width as i32, height as i32, depth as i32);
+		ImageBlit {
+			src_subresource: SubresourceLayers::mip(src, 0),
+			src_offsets: [(0, 0, 0), corner(src.extent())],
+			dst_subresource: SubresourceLayers::mip(dst, 0),
+			dst_offsets: [(0, 0, 0), corner(dst.extent())]
+		}
+	}
+
+	pub(crate) fn into_vulkan(self) -> vk::ImageBlit {
+		vk::ImageBlit {
+			src_subresource: self.src_subresource.into_vulkan(),
+			src_offsets: [offset(self.src_offsets[0]), offset(self.src_offsets[1])],
+			dst_subresource: self.dst_subresource.into_vulkan(),
+			dst_offsets: [offset(self.dst_offsets[0]), offset(self.dst_offsets[1])]
+		}
+	}
+}
+
+fn check_subresource<I: ImageProperties>(image: &I, subresource: &SubresourceLayers, single_aspect: bool) -> Result<(), CopyError> {
+	let aspects = subresource.aspects;
+	if aspects.is_empty()
+	|| !image.format().aspects().contains(aspects)
+	|| (single_aspect && aspects.into_vulkan().as_raw().count_ones() != 1) {
+		return Err(CopyError::InvalidAspects)
+	}
+
+	if subresource.mip_level >= image.mip_levels()
+	|| subresource.layer_count == 0
+	|| subresource.base_array_layer + subresource.layer_count > image.array_layers() {
+		return Err(CopyError::OutOfRange)
+	}
+
+	Ok(())
+}
+
+fn check_region<I: ImageProperties>(image: &I, subresource: &SubresourceLayers, offset: (i32, i32, i32), extent: (u32, u32, u32)) -> Result<(), CopyError> {
+	check_level_region(image.format(), image.mip_extent(subresource.mip_level), offset, extent)
+}
+
+/// Checks a region against the extent of a mip level of an image of the given format.
+fn check_level_region(format: Format, (level_width, level_height, level_depth): (u32, u32, u32), (x, y, z): (i32, i32, i32), (width, height, depth): (u32, u32, u32)) -> Result<(), CopyError> {
+	let fits = |offset: i32, len: u32, max: u32| offset >= 0 && len > 0 && offset as u64 + len as u64 <= max as u64;
+	if !fits(x, width, level_width) || !fits(y, height, level_height) || !fits(z, depth, level_depth) {
+		return Err(CopyError::OutOfRange)
+	}
+
+	let (block_width, block_height) = format.block_dimensions();
+	let aligned = |offset: i32, len: u32, max: u32, block: u32| offset as u32 % block == 0 && (len % block == 0 || offset as u32 + len == max);
+	if !aligned(x, width, level_width, block_width) || !aligned(y, height, level_height, block_height) {
+		return Err(CopyError::UnalignedRegion)
+	}
+
+	Ok(())
+}
+
+/// Checks that the corners of a blit region lie within the extent of a mip level.
+///
+/// Unlike copies, blit regions may be empty or mirrored.
+fn check_blit_offsets(offsets: &[(i32, i32, i32); 2], (level_width, level_height, level_depth): (u32, u32, u32)) -> Result<(), CopyError> {
+	let fits = |offset: i32, max: u32| offset >= 0 && offset as u32 <= max;
+	if offsets.iter().all(|&(x, y, z)| fits(x, level_width) && fits(y, level_height) && fits(z, level_depth)) {
+		Ok(())
+	} else {
+		Err(CopyError::OutOfRange)
+	}
+}
+
+/// Checks that the formats of a blit can be converted into each other with the given filter.
+///
+/// Integer formats can only be blitted to integer formats of the same signedness,
+/// and depth/stencil formats only to the same format, with nearest filtering.
+fn check_blit_formats(src: Format, dst: Format, filter: Filter) -> Result<(), CopyError> {
+	if src.has_depth() || src.has_stencil() || dst.has_depth() || dst.has_stencil() {
+		if src != dst {
+			return Err(CopyError::IncompatibleFormats(src, dst))
+		}
+
+		if filter != Filter::Nearest {
+			return Err(CopyError::UnsupportedFilter(src))
+		}
+
+		return Ok(())
+	}
+
+	match (src.numeric_type(), dst.numeric_type()) {
+		(Some(a), Some(b)) if (a == NumericType::Sint) != (b == NumericType::Sint) || (a == NumericType::Uint) != (b == NumericType::Uint) => {
+			Err(CopyError::IncompatibleFormats(src, dst))
+		},
+		_ => Ok(())
+	}
+}
+
+fn check_buffer_region<I: ImageProperties>(image: &I, region: &BufferImageCopy) -> Result<(), CopyError> {
+	check_subresource(image, &region.image_subresource, true)?;
+	check_region(image, &region.image_subresource, region.offset, region.extent)?;
+
+	let format = image.format();
+	let (block_width, block_height) = format.block_dimensions();
+	if region.row_length % block_width != 0
+	|| region.image_height % block_height != 0
+	|| format.size().map(|size| region.buffer_offset % size as u64 != 0).unwrap_or(false) {
+		return Err(CopyError::UnalignedRegion)
+	}
+
+	Ok(())
+}
+
+fn check_source_image<I: ImageProperties>(image: &I) -> Result<(), CopyError> {
+	if image.usage().transfer_source {
+		Ok(())
+	} else {
+		Err(CopyError::MissingTransferSourceUsage)
+	}
+}
+
+fn check_destination_image<I: ImageProperties>(image: &I) -> Result<(), CopyError> {
+	if image.usage().transfer_destination {
+		Ok(())
+	} else {
+		Err(CopyError::MissingTransferDestinationUsage)
+	}
+}
+
+pub(crate) fn check_buffer_to_image<B: BufferProperties, I: ImageProperties>(buffer: &B, image: &I, regions: &[BufferImageCopy]) -> Result<(), CopyError> {
+	if !buffer.usage().transfer_source() {
+		return Err(CopyError::MissingTransferSourceUsage)
+	}
+
+	check_destination_image(image)?;
+	regions.iter().try_for_each(|region| check_buffer_region(image, region))
+}
+
+pub(crate) fn check_image_to_buffer<I: ImageProperties, B: BufferProperties>(image: &I, buffer: &B, regions: &[BufferImageCopy]) -> Result<(), CopyError> {
+	if !buffer.usage().transfer_destination() {
+		return Err(CopyError::MissingTransferDestinationUsage)
+	}
+
+	check_source_image(image)?;
+	regions.iter().try_for_each(|region| check_buffer_region(image, region))
+}
+
+pub(crate) fn check_image_copy<S: ImageProperties, D: ImageProperties>(src: &S, dst: &D, regions: &[ImageCopy]) -> Result<(), CopyError> {
+	check_source_image(src)?;
+	check_destination_image(dst)?;
+
+	let (src_format, dst_format) = (src.format(), dst.format());
+	if src_format.size() != dst_format.size() || src_format.block_dimensions() != dst_format.block_dimensions() {
+		return Err(CopyError::IncompatibleFormats(src_format, dst_format))
+	}
+
+	regions.iter().try_for_each(|region| {
+		check_subresource(src, &region.src_subresource, false)?;
+		check_subresource(dst, &region.dst_subresource, false)?;
+		check_region(src, &region.src_subresource, region.src_offset, region.extent)?;
+		check_region(dst, &region.dst_subresource, region.dst_offset, region.extent)
+	})
+}
+
+pub(crate) fn check_blit<S: ImageProperties, D: ImageProperties>(src: &S, dst: &D, regions: &[ImageBlit], filter: Filter) -> Result<(), CopyError> {
+	check_source_image(src)?;
+	check_destination_image(dst)?;
+
+	let physical_device = src.device().physical_device();
+	let src_features = src.format().properties(physical_device).tiling_features(src.tiling());
+	if !src_features.blit_src() {
+		return Err(CopyError::UnsupportedBlit(src.format()))
+	}
+
+	if filter == Filter::Linear && !src_features.sampled_image_filter_linear() {
+		return Err(CopyError::UnsupportedFilter(src.format()))
+	}
+
+	if !dst.format().properties(physical_device).tiling_features(dst.tiling()).blit_dst() {
+		return Err(CopyError::UnsupportedBlit(dst.format()))
+	}
+
+	check_blit_formats(src.format(), dst.format(), filter)?;
+
+	regions.iter().try_for_each(|region| {
+		check_subresource(src, &region.src_subresource, false)?;
+		check_subresource(dst, &region.dst_subresource, false)?;
+		check_blit_offsets(&region.src_offsets, src.mip_extent(region.src_subresource.mip_level))?;
+		check_blit_offsets(&region.dst_offsets, dst.mip_extent(region.dst_subresource.mip_level))
+	})
+}
+
+pub(crate) fn check_resolve<S: ImageProperties, D: ImageProperties>(src: &S, dst: &D, regions: &[ImageResolve]) -> Result<(), CopyError> {
+	if src.samples().count() <= 1 || dst.samples().count() != 1 || src.format() != dst.format() {
+		return Err(CopyError::InvalidResolve)
+	}
+
+	regions.iter().try_for_each(|region| {
+		check_subresource(src, &region.src_subresource, false)?;
+		check_subresource(dst, &region.dst_subresource, false)?;
+		check_region(src, &region.src_subresource, region.src_offset, region.extent)?;
+		check_region(dst, &region.dst_subresource, region.dst_offset, region.extent)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn copy_regions() {
+		let format = Format::R8G8B8A8Unorm;
+		assert_eq!(check_level_region(format, (16, 16, 1), (0, 0, 0), (16, 16, 1)), Ok(()));
+		assert_eq!(check_level_region(format, (16, 16, 1), (8, 8, 0), (9, 8, 1)), Err(CopyError::OutOfRange));
+		assert_eq!(check_level_region(format, (16, 16, 1), (-1, 0, 0), (1, 1, 1)), Err(CopyError::OutOfRange));
+		assert_eq!(check_level_region(format, (16, 16, 1), (0, 0, 0), (0, 1, 1)), Err(CopyError::OutOfRange));
+		assert_eq!(check_level_region(format, (16, 16, 1), (i32::MAX, 0, 0), (u32::MAX, 1, 1)), Err(CopyError::OutOfRange));
+
+		let compressed = Format::BC1_RGBUnormBlock;
+		assert_eq!(check_level_region(compressed, (10, 10, 1), (4, 4, 0), (6, 6, 1)), Ok(()));
+		assert_eq!(check_level_region(compressed, (10, 10, 1), (2, 0, 0), (4, 4, 1)), Err(CopyError::UnalignedRegion));
+		assert_eq!(check_level_region(compressed, (10, 10, 1), (0, 0, 0), (6, 4, 1)), Err(CopyError::UnalignedRegion));
+	}
+
+	#[test]
+	fn blit_offsets() {
+		assert_eq!(check_blit_offsets(&[(0, 0, 0), (16, 16, 1)], (16, 16, 1)), Ok(()));
+		assert_eq!(check_blit_offsets(&[(16, 16, 1), (0, 0, 0)], (16, 16, 1)), Ok(()));
+		assert_eq!(check_blit_offsets(&[(0, 0, 0), (17, 16, 1)], (16, 16, 1)), Err(CopyError::OutOfRange));
+		assert_eq!(check_blit_offsets(&[(0, -1, 0), (16, 16, 1)], (16, 16, 1)), Err(CopyError::OutOfRange));
+		assert_eq!(check_blit_offsets(&[(0, 0, 0), (8, 8, 2)], (8, 8, 1)), Err(CopyError::OutOfRange));
+	}
+
+	#[test]
+	fn blit_formats() {
+		assert_eq!(check_blit_formats(Format::R8G8B8A8Unorm, Format::R32G32B32A32Sfloat, Filter::Linear), Ok(()));
+		assert_eq!(check_blit_formats(Format::R8G8B8A8Uint, Format::R16G16B16A16Uint, Filter::Nearest), Ok(()));
+		assert_eq!(check_blit_formats(Format::R8G8B8A8Sint, Format::R8G8B8A8Sint, Filter::Nearest), Ok(()));
+
+		assert_eq!(
+			check_blit_formats(Format::R8G8B8A8Uint, Format::R8G8B8A8Unorm, Filter::Nearest),
+			Err(CopyError::IncompatibleFormats(Format::R8G8B8A8Uint, Format::R8G8B8A8Unorm))
+		);
+		assert_eq!(
+			check_blit_formats(Format::R8G8B8A8Uint, Format::R8G8B8A8Sint, Filter::Nearest),
+			Err(CopyError::IncompatibleFormats(Format::R8G8B8A8Uint, Format::R8G8B8A8Sint))
+		);
+		assert_eq!(
+			check_blit_formats(Format::R32G32B32A32Sfloat, Format::R8G8B8A8Sint, Filter::Nearest),
+			Err(CopyError::IncompatibleFormats(Format::R32G32B32A32Sfloat, Format::R8G8B8A8Sint))
+		);
+
+		assert_eq!(check_blit_formats(Format::D32Sfloat, Format::D32Sfloat, Filter::Nearest), Ok(()));
+		assert_eq!(check_blit_formats(Format::D32Sfloat, Format::D32Sfloat, Filter::Linear), Err(CopyError::UnsupportedFilter(Format::D32Sfloat)));
+		assert_eq!(
+			check_blit_formats(Format::D32Sfloat, Format::D16Unorm, Filter::Nearest),
+			Err(CopyError::IncompatibleFormats(Format::D32Sfloat, Format::D16Unorm))
+		);
+	}
+}
@@ -2,18 +2,87 @@ use ash::{
 	vk,
 	version::DeviceV1_0
 };
-use std::collections::HashSet;
+use std::{
+	sync::{
+		Arc,
+		atomic::{
+			AtomicBool,
+			Ordering
+		}
+	},
+	collections::HashSet,
+	fmt
+};
 use crate::{
 	resource,
+	Resource,
 	OomError,
-	DeviceOwned
+	Device,
+	DeviceOwned,
+	framebuffer,
+	Framebuffer,
+	Image,
+	mem::BufferProperties,
+	image::{
+		self,
+		ImageProperties,
+		LayoutTracker
+	},
+	pipeline::{
+		self,
+		descriptor,
+		dynamic_state::flags::{
+			DynamicState,
+			DynamicStates
+		}
+	},
+	device::{
+		Feature,
+		Features,
+		ExtendedFeature,
+		feature::index_type_uint8
+	},
+	mem::buffer::IndexType
 };
+use super::pool::Level;
 
 pub mod local_recorder;
 mod recorder;
+mod secondary;
+mod copy;
+mod clear;
+mod barrier;
 
 pub use local_recorder::LocalRecorder;
 pub use recorder::Recorder;
+pub use secondary::{
+	SecondaryRecorder,
+	ExecutableSecondary,
+	ExecuteError
+};
+pub use barrier::{
+	Barrier,
+	BufferBarrier,
+	ImageBarrier,
+	BarrierError
+};
+pub use clear::{
+	ClearError,
+	ClearAttachment,
+	ClearRect
+};
+pub use copy::{
+	CopyError,
+	SubresourceLayers,
+	BufferImageCopy,
+	ImageCopy,
+	ImageResolve,
+	ImageBlit
+};
+pub(crate) use copy::{
+	check_buffer_to_image,
+	check_image_to_buffer
+};
 
 #[derive(Debug)]
 pub enum CreationError {
@@ -32,7 +101,24 @@ impl From<vk::Result> for CreationError {
 
 #[derive(Debug)]
 pub enum RecordError {
-	OutOfMemory(OomError)
+	OutOfMemory(OomError),
+
+	/// The pool of the buffer has been reset since its allocation.
+	Invalidated,
+
+	/// The buffer has already been recorded,
+	/// and its pool was not created with the `reset_command_buffer` flag.
+	AlreadyRecorded,
+
+	/// Primary buffers are recorded with `begin`,
+	/// secondary buffers with `begin_inside_render_pass`.
+	LevelMismatch,
+
+	/// The inherited subpass is not in the render pass.
+	InvalidSubpass,
+
+	/// The inherited framebuffer is not compatible with the render pass.
+	IncompatibleFramebuffer
 }
 
 impl From<vk::Result> for RecordError {
@@ -45,69 +131,967 @@ impl From<vk::Result> for RecordError {
 	}
 }
 
+/// Maximum number of bytes written by a buffer update command.
+pub const MAX_UPDATE_SIZE: u64 = 65536;
+
+/// Invalid buffer fill or update command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferError {
+	/// The buffer was not created with the `TransferDestination` usage.
+	MissingTransferDestinationUsage,
+
+	/// The offset is not a multiple of 4.
+	MisalignedOffset,
+
+	/// The size is not a multiple of 4.
+	MisalignedSize,
+
+	/// The written range exceeds the buffer.
+	OutOfBounds,
+
+	/// The update is larger than `MAX_UPDATE_SIZE` bytes.
+	TooLarge
+}
+
+impl fmt::Display for TransferError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingTransferDestinationUsage => write!(f, "buffer is not a transfer destination"),
+			Self::MisalignedOffset => write!(f, "offset is not a multiple of 4"),
+			Self::MisalignedSize => write!(f, "size is not a multiple of 4"),
+			Self::OutOfBounds => write!(f, "write out of the buffer"),
+			Self::TooLarge => write!(f, "update larger than {} bytes", MAX_UPDATE_SIZE)
+		}
+	}
+}
+
+impl std::error::Error for TransferError { }
+
+/// Checks that `size` bytes can be written at `offset` in the given buffer by a fill or update command.
+pub(crate) fn check_transfer_write<B: BufferProperties>(buffer: &B, offset: u64, size: u64) -> Result<(), TransferError> {
+	if !buffer.usage().transfer_destination() {
+		return Err(TransferError::MissingTransferDestinationUsage)
+	}
+
+	if offset % 4 != 0 {
+		return Err(TransferError::MisalignedOffset)
+	}
+
+	if size == 0 || size % 4 != 0 {
+		return Err(TransferError::MisalignedSize)
+	}
+
+	match offset.checked_add(size) {
+		Some(end) if end <= buffer.size() => Ok(()),
+		_ => Err(TransferError::OutOfBounds)
+	}
+}
+
+/// Invalid index buffer binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexError {
+	/// 8-bit indexes require the `IndexTypeUint8` extended feature (`VK_EXT_index_type_uint8`).
+	MissingFeature(ExtendedFeature),
+
+	/// The buffer was not created with the `IndexBuffer` usage.
+	MissingIndexBufferUsage,
+
+	/// The offset is not a multiple of the index size.
+	MisalignedOffset,
+
+	/// The offset exceeds the buffer.
+	OutOfBounds
+}
+
+impl fmt::Display for IndexError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingFeature(feature) => write!(f, "missing extended device feature `{}`", feature),
+			Self::MissingIndexBufferUsage => write!(f, "buffer is not an index buffer"),
+			Self::MisalignedOffset => write!(f, "index buffer offset is not a multiple of the index size"),
+			Self::OutOfBounds => write!(f, "index buffer offset out of the buffer")
+		}
+	}
+}
+
+impl std::error::Error for IndexError { }
+
+/// Checks that the given buffer can be bound as an index buffer at `offset`, with indexes of `index_size` bytes.
+pub(crate) fn check_index_buffer<B: BufferProperties>(buffer: &B, offset: u64, index_size: u64) -> Result<(), IndexError> {
+	if !buffer.usage().index_buffer() {
+		return Err(IndexError::MissingIndexBufferUsage)
+	}
+
+	if offset % index_size != 0 {
+		return Err(IndexError::MisalignedOffset)
+	}
+
+	if offset >= buffer.size() {
+		return Err(IndexError::OutOfBounds)
+	}
+
+	Ok(())
+}
+
+/// Invalid vertex buffer binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexBufferError {
+	/// The buffer bound at the given binding was not created with the `VertexBuffer` usage.
+	MissingVertexBufferUsage(u32),
+
+	/// The offset of the buffer bound at the given binding exceeds the buffer.
+	OutOfBounds(u32)
+}
+
+impl fmt::Display for VertexBufferError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingVertexBufferUsage(binding) => write!(f, "buffer bound at binding {} is not a vertex buffer", binding),
+			Self::OutOfBounds(binding) => write!(f, "offset of the vertex buffer bound at binding {} out of the buffer", binding)
+		}
+	}
+}
+
+impl std::error::Error for VertexBufferError { }
+
+/// Checks that the given buffer can be bound as a vertex buffer at `offset`, for the given binding.
+pub(crate) fn check_vertex_buffer<B: BufferProperties>(binding: u32, buffer: &B, offset: u64) -> Result<(), VertexBufferError> {
+	if !buffer.usage().vertex_buffer() {
+		return Err(VertexBufferError::MissingVertexBufferUsage(binding))
+	}
+
+	if offset >= buffer.size() {
+		return Err(VertexBufferError::OutOfBounds(binding))
+	}
+
+	Ok(())
+}
+
+/// Checks that indexes of the given type can be used on the given device.
+pub(crate) fn check_index_type(device: &Device, index_type: IndexType) -> Result<(), IndexError> {
+	if index_type == IndexType::UINT8_EXT {
+		let feature = ExtendedFeature::IndexTypeUint8(index_type_uint8::Feature::IndexTypeUint8);
+		if !device.enabled_extended_features().contains(feature) {
+			return Err(IndexError::MissingFeature(feature))
+		}
+	}
+
+	Ok(())
+}
+
+/// Invalid draw command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawError {
+	Index(IndexError),
+
+	/// A dynamic state of the pipeline has not been set since the beginning of the render pass,
+	/// or has been invalidated by a pipeline in which it is static.
+	MissingDynamicState(DynamicState),
+
+	/// A dynamic state set since the previous draw command is static in the pipeline,
+	/// which would discard its value.
+	StaticState(DynamicState),
+
+	/// The bound descriptor sets do not match the sets of the pipeline layout.
+	IncompatibleDescriptorSets,
+
+	/// The current subpass was begun with the `SecondaryCommandBuffers` contents.
+	SecondaryContents
+}
+
+impl fmt::Display for DrawError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Index(e) => e.fmt(f),
+			Self::MissingDynamicState(state) => write!(f, "dynamic state {:?} of the pipeline is not set", state),
+			Self::StaticState(state) => write!(f, "state {:?} is static in the pipeline", state),
+			Self::IncompatibleDescriptorSets => write!(f, "bound descriptor sets do not match the pipeline layout"),
+			Self::SecondaryContents => write!(f, "command recorded in a subpass of secondary command buffers")
+		}
+	}
+}
+
+impl std::error::Error for DrawError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::Index(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<IndexError> for DrawError {
+	fn from(e: IndexError) -> Self {
+		Self::Index(e)
+	}
+}
+
+/// Checks that every dynamic state of a pipeline is in the set of valid dynamic states.
+pub(crate) fn check_dynamic_states(set: &DynamicStates, pipeline: &DynamicStates) -> Result<(), DrawError> {
+	match pipeline.iter().find(|state| !set.contains(*state)) {
+		Some(state) => Err(DrawError::MissingDynamicState(state)),
+		None => Ok(())
+	}
+}
+
+/// Dynamic states still valid after binding a pipeline with the given dynamic states.
+///
+/// Binding a pipeline in which a state is static invalidates the value set for that state.
+#[inline]
+pub(crate) fn bind_dynamic_states(set: &DynamicStates, pipeline: &DynamicStates) -> DynamicStates {
+	set.intersection(pipeline)
+}
+
+/// Checks that the states set since the previous pipeline binding are dynamic in the pipeline.
+pub(crate) fn check_static_states(pending: &DynamicStates, pipeline: &DynamicStates) -> Result<(), DrawError> {
+	match pending.iter().find(|state| !pipeline.contains(*state)) {
+		Some(state) => Err(DrawError::StaticState(state)),
+		None => Ok(())
+	}
+}
+
+/// Invalid dynamic state command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicStateError {
+	/// The state is static in the bound pipeline.
+	StaticState(DynamicState),
+
+	/// The state requires a device feature that is not enabled,
+	/// such as `WideLines` for line widths other than `1.0`.
+	MissingFeature(Feature),
+
+	/// The current subpass was begun with the `SecondaryCommandBuffers` contents.
+	SecondaryContents
+}
+
+impl fmt::Display for DynamicStateError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::StaticState(state) => write!(f, "state {:?} is static in the bound pipeline", state),
+			Self::MissingFeature(feature) => write!(f, "missing feature {:?}", feature),
+			Self::SecondaryContents => write!(f, "command recorded in a subpass of secondary command buffers")
+		}
+	}
+}
+
+impl std::error::Error for DynamicStateError { }
+
+/// Checks that the given state is dynamic in the bound pipeline.
+pub(crate) fn check_dynamic_state(pipeline: &DynamicStates, state: DynamicState) -> Result<(), DynamicStateError> {
+	if pipeline.contains(state) {
+		Ok(())
+	} else {
+		Err(DynamicStateError::StaticState(state))
+	}
+}
+
+/// Checks that the line width is `1.0`, unless the `WideLines` feature is enabled.
+pub(crate) fn check_line_width(width: f32, wide_lines: bool) -> Result<(), DynamicStateError> {
+	if width == 1.0 || wide_lines {
+		Ok(())
+	} else {
+		Err(DynamicStateError::MissingFeature(Feature::WideLines))
+	}
+}
+
+/// Invalid indirect draw command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawIndirectError {
+	/// The buffer was not created with the `IndirectBuffer` usage.
+	MissingIndirectBufferUsage,
+
+	/// The offset is not a multiple of 4.
+	MisalignedOffset,
+
+	/// The stride is not a multiple of 4, or is smaller than the draw command.
+	InvalidStride,
+
+	/// Multiple draws require the `MultiDrawIndirect` feature.
+	MissingMultiDrawIndirect,
+
+	/// The draw commands exceed the buffer.
+	OutOfBounds,
+
+	/// Indirect draw counts require the `VK_KHR_draw_indirect_count` device extension,
+	/// or the `DrawIndirectCount` extended feature of Vulkan 1.2.
+	MissingDrawIndirectCount,
+
+	Draw(DrawError)
+}
+
+impl fmt::Display for DrawIndirectError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingIndirectBufferUsage => write!(f, "buffer is not an indirect buffer"),
+			Self::MisalignedOffset => write!(f, "offset is not a multiple of 4"),
+			Self::InvalidStride => write!(f, "invalid indirect draw stride"),
+			Self::MissingMultiDrawIndirect => write!(f, "missing device feature `MultiDrawIndirect`"),
+			Self::OutOfBounds => write!(f, "draw commands out of the buffer"),
+			Self::MissingDrawIndirectCount => write!(f, "missing device extension `VK_KHR_draw_indirect_count` or Vulkan 1.2 feature `DrawIndirectCount`"),
+			Self::Draw(e) => e.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for DrawIndirectError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::Draw(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<DrawError> for DrawIndirectError {
+	fn from(e: DrawError) -> Self {
+		Self::Draw(e)
+	}
+}
+
+impl From<IndexError> for DrawIndirectError {
+	fn from(e: IndexError) -> Self {
+		Self::Draw(e.into())
+	}
+}
+
+fn check_indirect_buffer<B: BufferProperties>(buffer: &B, offset: u64) -> Result<(), DrawIndirectError> {
+	if !buffer.usage().indirect_buffer() {
+		return Err(DrawIndirectError::MissingIndirectBufferUsage)
+	}
+
+	if offset % 4 != 0 {
+		return Err(DrawIndirectError::MisalignedOffset)
+	}
+
+	Ok(())
+}
+
+fn check_indirect_range<B: BufferProperties>(buffer: &B, offset: u64, size: u64) -> Result<(), DrawIndirectError> {
+	match offset.checked_add(size) {
+		Some(end) if end <= buffer.size() => Ok(()),
+		_ => Err(DrawIndirectError::OutOfBounds)
+	}
+}
+
+/// Size of `draw_count` commands of type `T` separated by `stride` bytes,
+/// checking the stride.
+fn indirect_commands_size<T>(draw_count: u32, stride: u32) -> Result<u64, DrawIndirectError> {
+	let command_size = std::mem::size_of::<T>() as u64;
+	if draw_count > 1 && (stride % 4 != 0 || (stride as u64) < command_size) {
+		return Err(DrawIndirectError::InvalidStride)
+	}
+
+	if draw_count == 0 {
+		Ok(0)
+	} else {
+		Ok((draw_count as u64 - 1) * stride as u64 + command_size)
+	}
+}
+
+/// Checks that `draw_count` draw commands of type `T` can be read at `offset` in the given buffer,
+/// separated by `stride` bytes.
+/// `features` are the features enabled on the device.
+pub(crate) fn check_draw_indirect<T, B: BufferProperties>(features: &Features, buffer: &B, offset: u64, draw_count: u32, stride: u32) -> Result<(), DrawIndirectError> {
+	check_indirect_buffer(buffer, offset)?;
+
+	if draw_count > 1 && !features.contains(Feature::MultiDrawIndirect) {
+		return Err(DrawIndirectError::MissingMultiDrawIndirect)
+	}
+
+	check_indirect_range(buffer, offset, indirect_commands_size::<T>(draw_count, stride)?)
+}
+
+/// Checks that up to `max_draw_count` draw commands of type `T` can be read at `offset` in the given buffer,
+/// and that the draw count can be read at `count_offset` in the count buffer.
+///
+/// Returns the commands of the `VK_KHR_draw_indirect_count` extension,
+/// or of Vulkan 1.2 if the `DrawIndirectCount` extended feature is enabled.
+pub(crate) fn check_draw_indirect_count<'d, T, B: BufferProperties, C: BufferProperties>(
+	device: &'d Device,
+	buffer: &B,
+	offset: u64,
+	count_buffer: &C,
+	count_offset: u64,
+	max_draw_count: u32,
+	stride: u32
+) -> Result<&'d crate::ffi::DrawIndirectCountFn, DrawIndirectError> {
+	let commands = device.draw_indirect_count().map_err(|_| DrawIndirectError::MissingDrawIndirectCount)?;
+
+	if stride % 4 != 0 || (stride as u64) < std::mem::size_of::<T>() as u64 {
+		return Err(DrawIndirectError::InvalidStride)
+	}
+
+	check_indirect_buffer(buffer, offset)?;
+	check_indirect_range(buffer, offset, indirect_commands_size::<T>(max_draw_count, stride)?)?;
+
+	check_indirect_buffer(count_buffer, count_offset)?;
+	check_indirect_range(count_buffer, count_offset, 4)?;
+
+	Ok(commands)
+}
+
+/// Invalid indirect dispatch command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchIndirectError {
+	/// The buffer was not created with the `IndirectBuffer` usage.
+	MissingIndirectBufferUsage,
+
+	/// The offset is not a multiple of 4.
+	MisalignedOffset,
+
+	/// The dispatch command exceeds the buffer.
+	OutOfBounds
+}
+
+impl fmt::Display for DispatchIndirectError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingIndirectBufferUsage => write!(f, "buffer is not an indirect buffer"),
+			Self::MisalignedOffset => write!(f, "offset is not a multiple of 4"),
+			Self::OutOfBounds => write!(f, "dispatch command out of the buffer")
+		}
+	}
+}
+
+impl std::error::Error for DispatchIndirectError { }
+
+/// Checks that a dispatch command can be read at `offset` in the given buffer.
+pub(crate) fn check_dispatch_indirect<B: BufferProperties>(buffer: &B, offset: u64) -> Result<(), DispatchIndirectError> {
+	if !buffer.usage().indirect_buffer() {
+		return Err(DispatchIndirectError::MissingIndirectBufferUsage)
+	}
+
+	if offset % 4 != 0 {
+		return Err(DispatchIndirectError::MisalignedOffset)
+	}
+
+	match offset.checked_add(std::mem::size_of::<vk::DispatchIndirectCommand>() as u64) {
+		Some(end) if end <= buffer.size() => Ok(()),
+		_ => Err(DispatchIndirectError::OutOfBounds)
+	}
+}
+
+/// Invalid descriptor sets binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindError {
+	/// The sets exceed the sets of the pipeline layout.
+	OutOfLayoutSets,
+
+	/// The descriptor set at the given index was used after its pool was reset.
+	InvalidSet(u32),
+
+	/// The descriptor set at the given index does not match the pipeline layout.
+	IncompatibleSet(u32),
+
+	/// The number of dynamic offsets does not match the number of dynamic descriptors in the sets.
+	DynamicOffsetCount {
+		expected: usize,
+		found: usize
+	},
+
+	/// The dynamic offset at the given index is not a multiple of the minimum offset alignment of its descriptor.
+	MisalignedDynamicOffset(usize),
+
+	Index(IndexError),
+
+	VertexBuffer(VertexBufferError),
+
+	/// The current subpass was begun with the `SecondaryCommandBuffers` contents.
+	SecondaryContents
+}
+
+impl fmt::Display for BindError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfLayoutSets => write!(f, "descriptor sets out of the layout sets"),
+			Self::InvalidSet(index) => write!(f, "descriptor set {} used after its pool was reset", index),
+			Self::IncompatibleSet(index) => write!(f, "descriptor set {} does not match the pipeline layout", index),
+			Self::DynamicOffsetCount { expected, found } => write!(f, "expected {} dynamic offsets, found {}", expected, found),
+			Self::MisalignedDynamicOffset(index) => write!(f, "misaligned dynamic offset {}", index),
+			Self::Index(e) => e.fmt(f),
+			Self::VertexBuffer(e) => e.fmt(f),
+			Self::SecondaryContents => write!(f, "command recorded in a subpass of secondary command buffers")
+		}
+	}
+}
+
+impl std::error::Error for BindError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::Index(e) => Some(e),
+			Self::VertexBuffer(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<IndexError> for BindError {
+	fn from(e: IndexError) -> Self {
+		Self::Index(e)
+	}
+}
+
+impl From<VertexBufferError> for BindError {
+	fn from(e: VertexBufferError) -> Self {
+		Self::VertexBuffer(e)
+	}
+}
+
+/// Checks that the given descriptor sets can be bound at `first_set` with the given layout,
+/// with the given dynamic offsets.
+///
+/// Returns the handles of the sets.
+pub(crate) fn check_descriptor_sets<S: std::ops::Deref<Target=descriptor::Set>>(
+	device: &Device,
+	layout: &pipeline::layout::Description,
+	first_set: u32,
+	sets: &[S],
+	dynamic_offsets: &[u32]
+) -> Result<Vec<vk::DescriptorSet>, BindError> {
+	let limits = device.physical_device().limits();
+	check_set_layouts(
+		layout,
+		first_set,
+		sets.iter().map(|set| (set.is_valid(), set.layout().handle(), set.layout().bindings())),
+		dynamic_offsets,
+		(limits.min_uniform_buffer_offset_alignment(), limits.min_storage_buffer_offset_alignment())
+	)?;
+
+	Ok(sets.iter().map(|s| s.handle()).collect())
+}
+
+/// Checks that sets, given by their validity, layout and bindings, can be bound at `first_set` with the given layout,
+/// with the given dynamic offsets.
+///
+/// `alignments` are the minimum offset alignments of the dynamic uniform and storage buffers.
+fn check_set_layouts<'s, I: ExactSizeIterator<Item=(bool, vk::DescriptorSetLayout, &'s [descriptor::set_layout::Binding])>>(
+	layout: &pipeline::layout::Description,
+	first_set: u32,
+	sets: I,
+	dynamic_offsets: &[u32],
+	(uniform_alignment, storage_alignment): (u64, u64)
+) -> Result<(), BindError> {
+	if first_set as u64 + sets.len() as u64 > layout.set_count() as u64 {
+		return Err(BindError::OutOfLayoutSets)
+	}
+
+	let mut alignments = Vec::new();
+	for (i, (valid, set_layout, bindings)) in sets.enumerate() {
+		let index = first_set + i as u32;
+		if !valid {
+			return Err(BindError::InvalidSet(index))
+		}
+
+		if layout.set_layout(index) != Some(set_layout) {
+			return Err(BindError::IncompatibleSet(index))
+		}
+
+		// Dynamic offsets are ordered by binding number.
+		let mut bindings: Vec<_> = bindings.iter().collect();
+		bindings.sort_by_key(|b| b.binding());
+		for binding in bindings {
+			let alignment = match binding.ty() {
+				descriptor::Type::UniformBufferDynamic => uniform_alignment,
+				descriptor::Type::StorageBufferDynamic => storage_alignment,
+				_ => continue
+			};
+
+			alignments.extend(std::iter::repeat(alignment).take(binding.count() as usize))
+		}
+	}
+
+	if alignments.len() != dynamic_offsets.len() {
+		return Err(BindError::DynamicOffsetCount {
+			expected: alignments.len(),
+			found: dynamic_offsets.len()
+		})
+	}
+
+	for (i, (offset, alignment)) in dynamic_offsets.iter().zip(alignments).enumerate() {
+		if *offset as u64 % alignment != 0 {
+			return Err(BindError::MisalignedDynamicOffset(i))
+		}
+	}
+
+	Ok(())
+}
+
+/// Layouts of the descriptor sets bound in a command buffer, for each bind point.
+#[derive(Default)]
+pub(crate) struct BoundSets {
+	graphics: BindPointSets,
+	compute: BindPointSets
+}
+
+/// Descriptor sets bound at a bind point.
+#[derive(Default)]
+struct BindPointSets {
+	/// Layout of each set, `None` if the set is not bound or has been disturbed.
+	layouts: Vec<Option<vk::DescriptorSetLayout>>,
+
+	/// Push constant ranges of the pipeline layout the last sets were bound with.
+	push_constant_ranges: Vec<pipeline::layout::push_constant::Range>
+}
+
+impl BoundSets {
+	fn get(&self, bind_point: pipeline::BindPoint) -> &BindPointSets {
+		match bind_point {
+			pipeline::BindPoint::Graphics => &self.graphics,
+			pipeline::BindPoint::Compute => &self.compute
+		}
+	}
+
+	fn get_mut(&mut self, bind_point: pipeline::BindPoint) -> &mut BindPointSets {
+		match bind_point {
+			pipeline::BindPoint::Graphics => &mut self.graphics,
+			pipeline::BindPoint::Compute => &mut self.compute
+		}
+	}
+
+	/// Record the binding of `count` sets at `first_set` with the given layout.
+	///
+	/// Pipeline layouts are compatible for a set if they have the same push constant ranges,
+	/// and the same set layouts up to this set.
+	/// Previously bound sets below `first_set` are disturbed when the layout is not compatible for them
+	/// with the one they were bound with, and the sets above the bound ones
+	/// are disturbed when the layout is not compatible for the last bound set.
+	pub(crate) fn bind(&mut self, bind_point: pipeline::BindPoint, layout: &pipeline::layout::Description, first_set: u32, count: u32) {
+		let sets = self.get_mut(bind_point);
+		let bound = first_set as usize..(first_set + count) as usize;
+		if sets.layouts.len() < bound.end {
+			sets.layouts.resize(bound.end, None)
+		}
+
+		let mut compatible = sets.push_constant_ranges.as_slice() == layout.push_constant_ranges();
+		for (i, set) in sets.layouts.iter_mut().enumerate() {
+			let set_layout = layout.set_layout(i as u32);
+			if i < bound.end {
+				compatible &= set_layout.is_some() && *set == set_layout;
+			}
+
+			if bound.contains(&i) {
+				*set = set_layout
+			} else if !compatible {
+				*set = None
+			}
+		}
+
+		sets.push_constant_ranges = layout.push_constant_ranges().to_vec();
+	}
+
+	/// Checks that sets matching all the sets of the given layout are bound.
+	pub(crate) fn is_compatible_with(&self, bind_point: pipeline::BindPoint, layout: &pipeline::layout::Description) -> bool {
+		let sets = self.get(bind_point);
+		layout.set_count() == 0 || (
+			sets.push_constant_ranges.as_slice() == layout.push_constant_ranges()
+			&& (0..layout.set_count()).all(|i| sets.layouts.get(i as usize).cloned().flatten() == layout.set_layout(i))
+		)
+	}
+}
+
 pub type BufferCopy = vk::BufferCopy;
 
+pub type ImageMemoryBarrier = vk::ImageMemoryBarrier;
+
+/// Invalid image layout transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionError {
+	/// The layout of the image is not tracked, the current layout must be given.
+	Untracked,
+
+	/// Images cannot be transitioned to the `Undefined` or `Preinitialized` layouts.
+	InvalidTarget(image::Layout)
+}
+
+impl fmt::Display for TransitionError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Untracked => write!(f, "untracked image layout"),
+			Self::InvalidTarget(layout) => write!(f, "cannot transition to the {:?} layout", layout)
+		}
+	}
+}
+
+impl std::error::Error for TransitionError { }
+
+/// Image layout transitions recorded in a command buffer, in recording order.
+pub(crate) type Transitions<'a> = Vec<(&'a LayoutTracker, image::Layout)>;
+
+/// Layout of a tracked image after the transitions recorded so far.
+pub(crate) fn current_layout(transitions: &Transitions, tracker: &LayoutTracker) -> image::Layout {
+	transitions.iter().rev()
+		.find(|(t, _)| std::ptr::eq(*t, tracker))
+		.map(|(_, layout)| *layout)
+		.unwrap_or_else(|| tracker.get())
+}
+
+/// Stages and barrier of a whole image layout transition.
+///
+/// The access and stage masks are derived from the old and new layouts.
+pub(crate) fn transition_barrier<I: ImageProperties>(image: &I, old_layout: image::Layout, new_layout: image::Layout) -> Result<(vk::PipelineStageFlags, vk::PipelineStageFlags, ImageMemoryBarrier), TransitionError> {
+	if !new_layout.is_transition_target() {
+		return Err(TransitionError::InvalidTarget(new_layout))
+	}
+
+	let (src_stages, src_access_mask, _) = old_layout.transition_masks();
+	let (dst_stages, _, dst_access_mask) = new_layout.transition_masks();
+
+	let barrier = ImageMemoryBarrier {
+		src_access_mask,
+		dst_access_mask,
+		old_layout: old_layout.into_vulkan(),
+		new_layout: new_layout.into_vulkan(),
+		src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+		dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+		image: image.handle(),
+		subresource_range: vk::ImageSubresourceRange {
+			aspect_mask: image.format().aspects().into_vulkan(),
+			base_mip_level: 0,
+			level_count: vk::REMAINING_MIP_LEVELS,
+			base_array_layer: 0,
+			layer_count: vk::REMAINING_ARRAY_LAYERS
+		},
+		..Default::default()
+	};
+
+	Ok((src_stages, dst_stages, barrier))
+}
+
+pub type AccessFlags = vk::AccessFlags;
+
+/// How a recorded command buffer is submitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Usage {
+	/// The buffer is submitted once.
+	///
+	/// It must be given by value to the queue, consuming it.
+	OneTimeSubmit,
+
+	/// The buffer can be submitted many times, by reference,
+	/// including while a previous submission is pending.
+	SimultaneousUse
+}
+
+impl Usage {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::CommandBufferUsageFlags {
+		match self {
+			Usage::OneTimeSubmit => vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+			Usage::SimultaneousUse => vk::CommandBufferUsageFlags::SIMULTANEOUS_USE
+		}
+	}
+}
+
+/// How the commands of a subpass are provided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubpassContents {
+	/// Commands are recorded in the primary buffer.
+	Inline,
+
+	/// Commands are recorded in secondary buffers, executed with `execute_commands`.
+	SecondaryCommandBuffers
+}
+
+impl SubpassContents {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::SubpassContents {
+		match self {
+			SubpassContents::Inline => vk::SubpassContents::INLINE,
+			SubpassContents::SecondaryCommandBuffers => vk::SubpassContents::SECONDARY_COMMAND_BUFFERS
+		}
+	}
+}
+
+/// Render pass instance recorded by a render pass recorder.
+#[derive(Clone)]
+pub(crate) struct Instance {
+	pub render_pass: Arc<framebuffer::RenderPass>,
+
+	/// Null if not known by a secondary buffer.
+	pub framebuffer: vk::Framebuffer,
+
+	/// Contents of the current subpass.
+	pub contents: SubpassContents,
+
+	/// Whether the instance is continued by a secondary buffer, rather than begun by the recorder.
+	pub inherited: bool
+}
+
+/// Checks that commands can be recorded inline in the current subpass of a render pass instance,
+/// `None` for a dynamic rendering.
+#[inline]
+pub(crate) fn is_inline(instance: Option<&Instance>) -> bool {
+	instance.map(|i| i.contents == SubpassContents::Inline).unwrap_or(true)
+}
+
 pub type VulkanBuffer = vk::CommandBuffer;
 
 /// Command buffer trait.
 pub trait Buffer: Sized + DeviceOwned {
 	fn handle(&self) -> VulkanBuffer;
 
-	fn record<'a, F>(self, f: F) -> Result<Recorded<'a, Self>, RecordError> where F: FnOnce(&mut Recorder<'a, Self>) -> (), Self: Send {
-		let infos = vk::CommandBufferBeginInfo {
-			flags: vk::CommandBufferUsageFlags::empty(), // TODO
-			p_inheritance_info: std::ptr::null(), // no inheritance for primary buffers.
-			..Default::default()
-		};
+	fn level(&self) -> Level {
+		Level::Primary
+	}
 
-		unsafe {
-			self.device().handle().begin_command_buffer(self.handle(), &infos)?
-		}
+	/// Checks that the buffer has not been invalidated by a reset of its pool.
+	fn is_valid(&self) -> bool {
+		true
+	}
 
-		let mut recorder = Recorder {
-			buffer: self,
-			resources: HashSet::new()
-		};
+	/// Checks that the buffer can begin recording.
+	///
+	/// A buffer can only be recorded once, unless its pool was created with the `reset_command_buffer` flag.
+	fn can_begin(&self) -> bool {
+		true
+	}
 
-		f(&mut recorder);
+	/// Mark the buffer as recorded.
+	///
+	/// Called once the buffer has begun recording.
+	fn set_recorded(&self) {}
 
-		unsafe {
-			recorder.buffer.device().handle().end_command_buffer(recorder.buffer.handle())?
-		}
+	/// Index of the queue family the buffer is submitted to, if known.
+	fn queue_family_index(&self) -> Option<u32> {
+		None
+	}
 
-		Ok(Recorded {
-			buffer: recorder.buffer,
-			resources: recorder.resources
+	/// Begin recording the buffer.
+	///
+	/// The returned recorder must be finished to get an executable buffer.
+	fn begin<'a>(self, usage: Usage) -> Result<Recorder<'a, Self>, RecordError> where Self: Send {
+		begin(&self, usage, Level::Primary, None)?;
+
+		Ok(Recorder {
+			buffer: self,
+			usage,
+			resources: HashSet::new(),
+			transitions: Vec::new(),
+			bound_sets: BoundSets::default()
 		})
 	}
 
-	fn record_local<'a, F>(self, f: F) -> Result<LocallyRecorded<'a, Self>, RecordError> where F: FnOnce(&mut LocalRecorder<'a, Self>) -> () {
-		let infos = vk::CommandBufferBeginInfo {
-			flags: vk::CommandBufferUsageFlags::empty(), // TODO
-			p_inheritance_info: std::ptr::null(), // no inheritance for primary buffers.
+	/// Begin recording the buffer, with resources that are not `Send`.
+	fn begin_local<'a>(self, usage: Usage) -> Result<LocalRecorder<'a, Self>, RecordError> {
+		begin(&self, usage, Level::Primary, None)?;
+
+		Ok(LocalRecorder {
+			buffer: self,
+			usage,
+			resources: HashSet::new(),
+			transitions: Vec::new(),
+			bound_sets: BoundSets::default()
+		})
+	}
+
+	/// Begin recording a secondary buffer, executed inside the given subpass of a render pass.
+	///
+	/// The buffer can then be executed by primary buffers recording a compatible render pass,
+	/// begun with the `SecondaryCommandBuffers` subpass contents.
+	/// If a framebuffer is given, the buffer can only be executed in render passes using it.
+	fn begin_inside_render_pass<'a, I: Send + Sync + Image + 'static>(
+		self,
+		usage: Usage,
+		render_pass: &Arc<framebuffer::RenderPass>,
+		subpass: u32,
+		framebuffer: Option<&Arc<Framebuffer<I>>>
+	) -> Result<SecondaryRecorder<'a, Self>, RecordError> where Self: Send {
+		if subpass >= render_pass.subpass_count() {
+			return Err(RecordError::InvalidSubpass)
+		}
+
+		if let Some(framebuffer) = framebuffer {
+			if !framebuffer.render_pass().is_compatible_with(render_pass) {
+				return Err(RecordError::IncompatibleFramebuffer)
+			}
+		}
+
+		let inheritance = vk::CommandBufferInheritanceInfo {
+			render_pass: render_pass.handle(),
+			subpass,
+			framebuffer: framebuffer.map(|f| f.handle()).unwrap_or(vk::Framebuffer::null()),
 			..Default::default()
 		};
 
-		unsafe {
-			self.device().handle().begin_command_buffer(self.handle(), &infos)?
-		}
+		begin(&self, usage, Level::Secondary, Some(&inheritance))?;
 
-		let mut recorder = LocalRecorder {
+		let mut recorder = Recorder {
 			buffer: self,
-			resources: HashSet::new()
+			usage,
+			resources: HashSet::new(),
+			transitions: Vec::new(),
+			bound_sets: BoundSets::default()
 		};
 
+		recorder.resources.insert(render_pass.clone().into());
+		if let Some(framebuffer) = framebuffer {
+			recorder.resources.insert(framebuffer.clone().into());
+		}
+
+		Ok(SecondaryRecorder::new(recorder, subpass, Instance {
+			render_pass: render_pass.clone(),
+			framebuffer: inheritance.framebuffer,
+			contents: SubpassContents::Inline,
+			inherited: true
+		}))
+	}
+
+	/// Record the buffer with the given function.
+	fn record<'a, F>(self, usage: Usage, f: F) -> Result<Executable<'a, Self>, RecordError> where F: FnOnce(&mut Recorder<'a, Self>) -> (), Self: Send {
+		let mut recorder = self.begin(usage)?;
 		f(&mut recorder);
+		recorder.finish()
+	}
 
-		unsafe {
-			recorder.buffer.device().handle().end_command_buffer(recorder.buffer.handle())?
-		}
+	fn record_local<'a, F>(self, usage: Usage, f: F) -> Result<LocalExecutable<'a, Self>, RecordError> where F: FnOnce(&mut LocalRecorder<'a, Self>) -> () {
+		let mut recorder = self.begin_local(usage)?;
+		f(&mut recorder);
+		recorder.finish()
+	}
+}
 
-		Ok(LocallyRecorded {
-			buffer: recorder.buffer,
-			resources: recorder.resources
-		})
+/// Begin recording a buffer of the given level.
+///
+/// Secondary buffers continue the render pass given in the inheritance infos.
+fn begin<B: Buffer>(buffer: &B, usage: Usage, level: Level, inheritance: Option<&vk::CommandBufferInheritanceInfo>) -> Result<(), RecordError> {
+	check_begin(buffer.is_valid(), buffer.can_begin(), buffer.level(), level)?;
+
+	let mut flags = usage.into_vulkan();
+	if inheritance.is_some() {
+		flags |= vk::CommandBufferUsageFlags::RENDER_PASS_CONTINUE
 	}
+
+	let infos = vk::CommandBufferBeginInfo {
+		flags,
+		p_inheritance_info: inheritance.map(|i| i as *const _).unwrap_or(std::ptr::null()),
+		..Default::default()
+	};
+
+	unsafe {
+		buffer.device().handle().begin_command_buffer(buffer.handle(), &infos)?
+	}
+
+	buffer.set_recorded();
+	Ok(())
+}
+
+/// Checks that a buffer, valid or invalidated by a pool reset, of the given level can begin recording at the `expected` level.
+///
+/// `can_begin` tells if the buffer was never recorded or can be reset.
+pub(crate) fn check_begin(valid: bool, can_begin: bool, level: Level, expected: Level) -> Result<(), RecordError> {
+	if !valid {
+		return Err(RecordError::Invalidated)
+	}
+
+	if !can_begin {
+		return Err(RecordError::AlreadyRecorded)
+	}
+
+	if level != expected {
+		return Err(RecordError::LevelMismatch)
+	}
+
+	Ok(())
 }
 
 impl<'a, B: Buffer> Buffer for &'a B {
@@ -115,11 +1099,64 @@ impl<'a, B: Buffer> Buffer for &'a B {
 	fn handle(&self) -> VulkanBuffer {
 		(*self).handle()
 	}
+
+	#[inline]
+	fn level(&self) -> Level {
+		(*self).level()
+	}
+
+	#[inline]
+	fn is_valid(&self) -> bool {
+		(*self).is_valid()
+	}
+
+	#[inline]
+	fn can_begin(&self) -> bool {
+		(*self).can_begin()
+	}
+
+	#[inline]
+	fn set_recorded(&self) {
+		(*self).set_recorded()
+	}
+
+	#[inline]
+	fn queue_family_index(&self) -> Option<u32> {
+		(*self).queue_family_index()
+	}
 }
 
-/// Recorded command buffer trait.
+/// Executable command buffer trait.
+///
+/// Implemented by the executable buffers returned when finishing a recording,
+/// and by references to them.
 pub unsafe trait RecordedBuffer {
 	fn handle(&self) -> vk::CommandBuffer;
+
+	fn usage(&self) -> Usage;
+
+	/// Checks if the buffer is submitted by reference, without being consumed.
+	fn is_borrowed(&self) -> bool {
+		false
+	}
+
+	/// Checks that the buffer has not been invalidated by a reset of its pool.
+	fn is_valid(&self) -> bool {
+		true
+	}
+
+	/// Checks if the buffer has already been submitted.
+	///
+	/// A `OneTimeSubmit` buffer cannot be submitted again.
+	fn is_submitted(&self) -> bool {
+		false
+	}
+
+	/// Update the tracked layouts of the images transitioned by this buffer,
+	/// and mark it as submitted.
+	///
+	/// Called once the buffer is submitted.
+	fn commit_layouts(&self) {}
 }
 
 unsafe impl<'a, B: RecordedBuffer> RecordedBuffer for &'a B {
@@ -127,40 +1164,403 @@ unsafe impl<'a, B: RecordedBuffer> RecordedBuffer for &'a B {
 	fn handle(&self) -> VulkanBuffer {
 		(*self).handle()
 	}
+
+	#[inline]
+	fn usage(&self) -> Usage {
+		(*self).usage()
+	}
+
+	#[inline]
+	fn is_borrowed(&self) -> bool {
+		true
+	}
+
+	#[inline]
+	fn is_valid(&self) -> bool {
+		(*self).is_valid()
+	}
+
+	#[inline]
+	fn is_submitted(&self) -> bool {
+		(*self).is_submitted()
+	}
+
+	#[inline]
+	fn commit_layouts(&self) {
+		(*self).commit_layouts()
+	}
 }
 
-pub struct Recorded<'a, B: Buffer> {
-	buffer: B,
-	resources: HashSet<resource::SendRef<'a>>
+pub struct Executable<'a, B: Buffer> {
+	pub(crate) buffer: B,
+	pub(crate) usage: Usage,
+	pub(crate) resources: HashSet<resource::SendRef<'a>>,
+	pub(crate) transitions: Transitions<'a>,
+
+	/// Set once the buffer is submitted.
+	pub(crate) submitted: AtomicBool
 }
 
-impl<'a, B: Buffer> Recorded<'a, B> {
+impl<'a, B: Buffer> Executable<'a, B> {
+	#[inline]
+	pub fn usage(&self) -> Usage {
+		self.usage
+	}
+
+	/// Resources used by the buffer, kept alive until the executable is dropped.
 	pub fn resources(&self) -> &HashSet<resource::SendRef<'a>> {
 		&self.resources
 	}
 }
 
-unsafe impl<'a, B: Buffer> RecordedBuffer for Recorded<'a, B> {
+unsafe impl<'a, B: Buffer> RecordedBuffer for Executable<'a, B> {
 	#[inline]
 	fn handle(&self) -> vk::CommandBuffer {
 		self.buffer.handle()
 	}
+
+	#[inline]
+	fn usage(&self) -> Usage {
+		self.usage
+	}
+
+	#[inline]
+	fn is_valid(&self) -> bool {
+		self.buffer.is_valid()
+	}
+
+	#[inline]
+	fn is_submitted(&self) -> bool {
+		self.submitted.load(Ordering::Relaxed)
+	}
+
+	fn commit_layouts(&self) {
+		for (tracker, layout) in &self.transitions {
+			tracker.set(*layout)
+		}
+
+		self.submitted.store(true, Ordering::Relaxed)
+	}
 }
 
-pub struct LocallyRecorded<'a, B: Buffer> {
-	buffer: B,
-	resources: HashSet<resource::Ref<'a>>
+pub struct LocalExecutable<'a, B: Buffer> {
+	pub(crate) buffer: B,
+	pub(crate) usage: Usage,
+	pub(crate) resources: HashSet<resource::Ref<'a>>,
+	pub(crate) transitions: Transitions<'a>,
+
+	/// Set once the buffer is submitted.
+	pub(crate) submitted: AtomicBool
 }
 
-impl<'a, B: Buffer> LocallyRecorded<'a, B> {
+impl<'a, B: Buffer> LocalExecutable<'a, B> {
+	#[inline]
+	pub fn usage(&self) -> Usage {
+		self.usage
+	}
+
+	/// Resources used by the buffer, kept alive until the executable is dropped.
 	pub fn resources(&self) -> &HashSet<resource::Ref<'a>> {
 		&self.resources
 	}
 }
 
-unsafe impl<'a, B: Buffer> RecordedBuffer for LocallyRecorded<'a, B> {
+unsafe impl<'a, B: Buffer> RecordedBuffer for LocalExecutable<'a, B> {
 	#[inline]
 	fn handle(&self) -> vk::CommandBuffer {
 		self.buffer.handle()
 	}
-}
\ No newline at end of file
+
+	#[inline]
+	fn usage(&self) -> Usage {
+		self.usage
+	}
+
+	#[inline]
+	fn is_valid(&self) -> bool {
+		self.buffer.is_valid()
+	}
+
+	#[inline]
+	fn is_submitted(&self) -> bool {
+		self.submitted.load(Ordering::Relaxed)
+	}
+
+	fn commit_layouts(&self) {
+		for (tracker, layout) in &self.transitions {
+			tracker.set(*layout)
+		}
+
+		self.submitted.store(true, Ordering::Relaxed)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dynamic_states_must_be_set() {
+		let pipeline = DynamicStates::from((DynamicState::Viewport, DynamicState::Scissor));
+
+		assert_eq!(check_dynamic_states(&DynamicStates::empty(), &DynamicStates::empty()), Ok(()));
+		assert_eq!(check_dynamic_states(&pipeline, &pipeline), Ok(()));
+		assert_eq!(check_dynamic_states(&DynamicStates::from(DynamicState::Viewport), &pipeline), Err(DrawError::MissingDynamicState(DynamicState::Scissor)));
+
+		let set = DynamicStates::from((DynamicState::Viewport, DynamicState::Scissor, DynamicState::LineWidth));
+		assert_eq!(check_dynamic_states(&set, &pipeline), Ok(()));
+	}
+
+	#[test]
+	fn static_states_are_invalidated_by_binding() {
+		let set = DynamicStates::from((DynamicState::Viewport, DynamicState::Scissor));
+		let static_scissor = DynamicStates::from(DynamicState::Viewport);
+
+		let set = bind_dynamic_states(&set, &static_scissor);
+		assert_eq!(set, static_scissor);
+
+		let pipeline = DynamicStates::from((DynamicState::Viewport, DynamicState::Scissor));
+		assert_eq!(check_dynamic_states(&set, &pipeline), Err(DrawError::MissingDynamicState(DynamicState::Scissor)));
+	}
+
+	#[test]
+	fn set_states_must_be_dynamic() {
+		let pipeline = DynamicStates::from(DynamicState::Viewport);
+		assert_eq!(check_dynamic_state(&pipeline, DynamicState::Viewport), Ok(()));
+		assert_eq!(check_dynamic_state(&pipeline, DynamicState::Scissor), Err(DynamicStateError::StaticState(DynamicState::Scissor)));
+
+		let pending = DynamicStates::from((DynamicState::Viewport, DynamicState::LineWidth));
+		assert_eq!(check_static_states(&DynamicStates::empty(), &pipeline), Ok(()));
+		assert_eq!(check_static_states(&pending, &pipeline), Err(DrawError::StaticState(DynamicState::LineWidth)));
+	}
+
+	#[test]
+	fn wide_lines_require_the_feature() {
+		assert_eq!(check_line_width(1.0, false), Ok(()));
+		assert_eq!(check_line_width(2.0, true), Ok(()));
+		assert_eq!(check_line_width(2.0, false), Err(DynamicStateError::MissingFeature(Feature::WideLines)));
+	}
+
+	/// Buffer of the given size and usage, without handle.
+	struct TestBuffer(u64, crate::mem::buffer::Usages);
+
+	unsafe impl Resource for TestBuffer {
+		type Handle = vk::Buffer;
+
+		fn handle(&self) -> vk::Buffer {
+			vk::Buffer::null()
+		}
+	}
+
+	unsafe impl crate::mem::Buffer for TestBuffer {
+		// ...
+	}
+
+	unsafe impl BufferProperties for TestBuffer {
+		fn size(&self) -> u64 {
+			self.0
+		}
+
+		fn usage(&self) -> crate::mem::buffer::Usages {
+			self.1
+		}
+
+		fn is_concurrent(&self) -> bool {
+			false
+		}
+	}
+
+	fn indirect_buffer(size: u64) -> TestBuffer {
+		TestBuffer(size, crate::mem::buffer::Usage::IndirectBuffer.into())
+	}
+
+	#[test]
+	fn dispatch_indirect_buffer_range() {
+		// A dispatch command is 12 bytes.
+		assert_eq!(check_dispatch_indirect(&indirect_buffer(16), 4), Ok(()));
+		assert_eq!(check_dispatch_indirect(&indirect_buffer(16), 8), Err(DispatchIndirectError::OutOfBounds));
+		assert_eq!(check_dispatch_indirect(&indirect_buffer(16), u64::MAX - 3), Err(DispatchIndirectError::OutOfBounds));
+		assert_eq!(check_dispatch_indirect(&indirect_buffer(16), 2), Err(DispatchIndirectError::MisalignedOffset));
+
+		let buffer = TestBuffer(16, crate::mem::buffer::Usage::VertexBuffer.into());
+		assert_eq!(check_dispatch_indirect(&buffer, 0), Err(DispatchIndirectError::MissingIndirectBufferUsage));
+	}
+
+	#[test]
+	fn index_buffer_binding() {
+		let buffer = TestBuffer(64, crate::mem::buffer::Usage::IndexBuffer.into());
+		assert_eq!(check_index_buffer(&buffer, 0, 4), Ok(()));
+		assert_eq!(check_index_buffer(&buffer, 60, 4), Ok(()));
+		assert_eq!(check_index_buffer(&buffer, 2, 4), Err(IndexError::MisalignedOffset));
+		assert_eq!(check_index_buffer(&buffer, 64, 4), Err(IndexError::OutOfBounds));
+		assert_eq!(check_index_buffer(&indirect_buffer(64), 0, 4), Err(IndexError::MissingIndexBufferUsage));
+	}
+
+	#[test]
+	fn vertex_buffer_binding() {
+		let buffer = TestBuffer(64, crate::mem::buffer::Usage::VertexBuffer.into());
+		assert_eq!(check_vertex_buffer(1, &buffer, 63), Ok(()));
+		assert_eq!(check_vertex_buffer(1, &buffer, 64), Err(VertexBufferError::OutOfBounds(1)));
+		assert_eq!(check_vertex_buffer(2, &indirect_buffer(64), 0), Err(VertexBufferError::MissingVertexBufferUsage(2)));
+	}
+
+	#[test]
+	fn indirect_commands_size_checks_stride() {
+		// 16 bytes commands.
+		assert_eq!(indirect_commands_size::<vk::DrawIndirectCommand>(0, 0), Ok(0));
+		assert_eq!(indirect_commands_size::<vk::DrawIndirectCommand>(1, 0), Ok(16));
+		assert_eq!(indirect_commands_size::<vk::DrawIndirectCommand>(3, 16), Ok(48));
+		assert_eq!(indirect_commands_size::<vk::DrawIndirectCommand>(3, 20), Ok(56));
+
+		assert_eq!(indirect_commands_size::<vk::DrawIndirectCommand>(2, 12), Err(DrawIndirectError::InvalidStride));
+		assert_eq!(indirect_commands_size::<vk::DrawIndirectCommand>(2, 18), Err(DrawIndirectError::InvalidStride));
+	}
+
+	#[test]
+	fn draw_indirect_buffer_range() {
+		let mut features = Features::none();
+		features.insert(Feature::MultiDrawIndirect);
+		let buffer = indirect_buffer(64);
+
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&features, &buffer, 0, 4, 16), Ok(()));
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&features, &buffer, 16, 3, 16), Ok(()));
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&features, &buffer, 16, 4, 16), Err(DrawIndirectError::OutOfBounds));
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&features, &buffer, 2, 1, 16), Err(DrawIndirectError::MisalignedOffset));
+
+		// No draw reads nothing.
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&features, &buffer, 64, 0, 16), Ok(()));
+	}
+
+	#[test]
+	fn draw_indirect_requirements() {
+		let buffer = indirect_buffer(64);
+		let vertex_buffer = TestBuffer(64, crate::mem::buffer::Usage::VertexBuffer.into());
+
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&Features::none(), &vertex_buffer, 0, 1, 16), Err(DrawIndirectError::MissingIndirectBufferUsage));
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&Features::none(), &buffer, 0, 1, 16), Ok(()));
+		assert_eq!(check_draw_indirect::<vk::DrawIndirectCommand, _>(&Features::none(), &buffer, 0, 2, 16), Err(DrawIndirectError::MissingMultiDrawIndirect));
+	}
+
+	fn set_layout(raw: u64) -> vk::DescriptorSetLayout {
+		use ash::vk::Handle;
+		vk::DescriptorSetLayout::from_raw(raw)
+	}
+
+	fn layout(set_layouts: &[u64]) -> pipeline::layout::Description {
+		pipeline::layout::Description::new(set_layouts.iter().map(|raw| set_layout(*raw)).collect(), Vec::new())
+	}
+
+	#[test]
+	fn bound_sets_are_compatible_with_their_layout() {
+		let mut bound = BoundSets::default();
+		let layout = layout(&[1, 2]);
+		assert!(!bound.is_compatible_with(pipeline::BindPoint::Graphics, &layout));
+
+		bound.bind(pipeline::BindPoint::Graphics, &layout, 0, 1);
+		assert!(!bound.is_compatible_with(pipeline::BindPoint::Graphics, &layout));
+
+		bound.bind(pipeline::BindPoint::Graphics, &layout, 1, 1);
+		assert!(bound.is_compatible_with(pipeline::BindPoint::Graphics, &layout));
+		assert!(!bound.is_compatible_with(pipeline::BindPoint::Compute, &layout));
+
+		// Layouts sharing the first set layouts.
+		assert!(bound.is_compatible_with(pipeline::BindPoint::Graphics, &self::layout(&[1])));
+		assert!(!bound.is_compatible_with(pipeline::BindPoint::Graphics, &self::layout(&[1, 3])));
+		assert!(!bound.is_compatible_with(pipeline::BindPoint::Graphics, &self::layout(&[1, 2, 3])));
+	}
+
+	#[test]
+	fn binding_disturbs_sets_of_incompatible_layouts() {
+		let mut bound = BoundSets::default();
+		bound.bind(pipeline::BindPoint::Graphics, &layout(&[1, 2, 3]), 0, 3);
+
+		// Set 0 is kept, set 2 is disturbed since the layouts differ for set 1.
+		bound.bind(pipeline::BindPoint::Graphics, &layout(&[1, 4, 3]), 1, 1);
+		assert_eq!(bound.graphics.layouts, vec![Some(set_layout(1)), Some(set_layout(4)), None]);
+
+		// Sets below are disturbed when the layouts differ for them.
+		bound.bind(pipeline::BindPoint::Graphics, &layout(&[5, 4, 3]), 2, 1);
+		assert_eq!(bound.graphics.layouts, vec![None, None, Some(set_layout(3))]);
+	}
+
+	#[test]
+	fn binding_keeps_sets_of_compatible_layouts() {
+		let mut bound = BoundSets::default();
+		bound.bind(pipeline::BindPoint::Graphics, &layout(&[1, 2, 3]), 0, 3);
+
+		// Compatible for set 1: set 2 is kept even if the new layout differs for it.
+		bound.bind(pipeline::BindPoint::Graphics, &layout(&[1, 2, 4]), 1, 1);
+		assert_eq!(bound.graphics.layouts, vec![Some(set_layout(1)), Some(set_layout(2)), Some(set_layout(3))]);
+		assert!(bound.is_compatible_with(pipeline::BindPoint::Graphics, &layout(&[1, 2, 3])));
+		assert!(!bound.is_compatible_with(pipeline::BindPoint::Graphics, &layout(&[1, 2, 4])));
+
+		// Bind points are independent.
+		bound.bind(pipeline::BindPoint::Compute, &layout(&[6]), 0, 1);
+		assert!(bound.is_compatible_with(pipeline::BindPoint::Graphics, &layout(&[1, 2, 3])));
+	}
+
+	#[test]
+	fn push_constant_ranges_make_layouts_incompatible() {
+		let ranges = vec![pipeline::layout::push_constant::Range::new(pipeline::shader::Stages::all(), 0, 16)];
+		let with_push_constants = pipeline::layout::Description::new(vec![set_layout(1), set_layout(2)], ranges);
+
+		let mut bound = BoundSets::default();
+		bound.bind(pipeline::BindPoint::Graphics, &layout(&[1, 2]), 0, 2);
+		assert!(!bound.is_compatible_with(pipeline::BindPoint::Graphics, &with_push_constants));
+
+		bound.bind(pipeline::BindPoint::Graphics, &with_push_constants, 1, 1);
+		assert_eq!(bound.graphics.layouts, vec![None, Some(set_layout(2))]);
+	}
+
+	fn bindings(builder: pipeline::descriptor::set_layout::Builder) -> Vec<pipeline::descriptor::set_layout::Binding> {
+		builder.bindings().to_vec()
+	}
+
+	#[test]
+	fn descriptor_sets_match_the_layout() {
+		let layout = layout(&[1, 2]);
+		let stages = pipeline::shader::Stages::all();
+		let bindings = bindings(pipeline::descriptor::SetLayout::builder().binding(0, descriptor::Type::UniformBuffer, stages));
+		let set = |raw| (true, set_layout(raw), bindings.as_slice());
+
+		assert_eq!(check_set_layouts(&layout, 0, vec![set(1), set(2)].into_iter(), &[], (256, 256)), Ok(()));
+		assert_eq!(check_set_layouts(&layout, 1, vec![set(2)].into_iter(), &[], (256, 256)), Ok(()));
+		assert_eq!(check_set_layouts(&layout, 1, vec![set(2), set(3)].into_iter(), &[], (256, 256)), Err(BindError::OutOfLayoutSets));
+		assert_eq!(check_set_layouts(&layout, 0, vec![set(1), set(3)].into_iter(), &[], (256, 256)), Err(BindError::IncompatibleSet(1)));
+		assert_eq!(check_set_layouts(&layout, 0, vec![(false, set_layout(1), bindings.as_slice())].into_iter(), &[], (256, 256)), Err(BindError::InvalidSet(0)));
+	}
+
+	#[test]
+	fn dynamic_offsets_match_the_dynamic_descriptors() {
+		let layout = layout(&[1]);
+		let stages = pipeline::shader::Stages::all();
+		let bindings = bindings(
+			pipeline::descriptor::SetLayout::builder()
+				.binding(1, descriptor::Type::StorageBufferDynamic, stages)
+				.array(0, descriptor::Type::UniformBufferDynamic, 2, stages)
+				.binding(2, descriptor::Type::UniformBuffer, stages)
+		);
+		let sets = || vec![(true, set_layout(1), bindings.as_slice())].into_iter();
+
+		// Offsets are ordered by binding: two uniform buffers, then a storage buffer.
+		assert_eq!(check_set_layouts(&layout, 0, sets(), &[0, 256, 64], (256, 64)), Ok(()));
+		assert_eq!(check_set_layouts(&layout, 0, sets(), &[0, 64, 64], (256, 64)), Err(BindError::MisalignedDynamicOffset(1)));
+		assert_eq!(check_set_layouts(&layout, 0, sets(), &[0, 256], (256, 64)), Err(BindError::DynamicOffsetCount {
+			expected: 3,
+			found: 2
+		}));
+	}
+
+	#[test]
+	fn buffers_of_pools_without_reset_are_recorded_once() {
+		let flags = crate::command::pool::Flags::none();
+		assert!(matches!(check_begin(true, crate::command::pool::raw::can_begin(false, flags), Level::Primary, Level::Primary), Ok(())));
+		assert!(matches!(check_begin(true, crate::command::pool::raw::can_begin(true, flags), Level::Primary, Level::Primary), Err(RecordError::AlreadyRecorded)));
+
+		let flags = crate::command::pool::Flags {
+			reset_command_buffer: true,
+			..flags
+		};
+		assert!(matches!(check_begin(true, crate::command::pool::raw::can_begin(true, flags), Level::Primary, Level::Primary), Ok(())));
+	}
+}
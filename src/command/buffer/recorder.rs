@@ -3,7 +3,10 @@ use ash::{
 	version::DeviceV1_0
 };
 use std::{
-	sync::Arc,
+	sync::{
+		Arc,
+		atomic::AtomicBool
+	},
 	collections::HashSet,
 	marker::PhantomData
 };
@@ -13,32 +16,132 @@ use crate::{
 	framebuffer,
 	Framebuffer,
 	Image,
+	image,
 	pipeline::{
 		self,
 		vertex_input::VertexInput,
-		input_assembly::InputAssembly
+		input_assembly::InputAssembly,
+		dynamic_state::flags::{
+			DynamicState,
+			DynamicStates
+		}
 	},
+	device::Feature,
+	framebuffer::render_pass::subpass::PipelineStages,
+	sync,
+	ffi,
 	format,
 	mem
 };
 use super::{
+	super::query,
 	Buffer,
-	BufferCopy
+	Usage,
+	Barrier,
+	BarrierError,
+	SubpassContents,
+	Instance,
+	ExecutableSecondary,
+	ExecuteError,
+	Executable,
+	RecordError,
+	DrawIndirectError,
+	DispatchIndirectError,
+	check_dispatch_indirect,
+	DrawError,
+	IndexError,
+	VertexBufferError,
+	check_index_buffer,
+	check_vertex_buffer,
+	check_index_type,
+	check_draw_indirect,
+	check_draw_indirect_count,
+	check_dynamic_states,
+	bind_dynamic_states,
+	check_static_states,
+	DynamicStateError,
+	check_line_width,
+	is_inline,
+	BufferCopy,
+	TransferError,
+	MAX_UPDATE_SIZE,
+	check_transfer_write,
+	BufferImageCopy,
+	ImageMemoryBarrier,
+	ImageBlit,
+	ImageCopy,
+	ImageResolve,
+	CopyError,
+	copy,
+	ClearError,
+	ClearAttachment,
+	ClearRect,
+	clear,
+	TransitionError,
+	Transitions,
+	current_layout,
+	check_descriptor_sets,
+	BindError,
+	BoundSets,
+	transition_barrier,
+	AccessFlags
 };
 
 pub struct Recorder<'a, B: Buffer> {
 	pub(crate) buffer: B,
-	pub(crate) resources: HashSet<resource::SendRef<'a>>
+	pub(crate) usage: Usage,
+	pub(crate) resources: HashSet<resource::SendRef<'a>>,
+	pub(crate) transitions: Transitions<'a>,
+	pub(crate) bound_sets: BoundSets
 }
 
 impl<'a, B: Buffer> Recorder<'a, B> {
+	/// Ends the recording.
+	pub fn finish(self) -> Result<Executable<'a, B>, RecordError> {
+		unsafe {
+			self.buffer.device().handle().end_command_buffer(self.buffer.handle())?
+		}
+
+		Ok(Executable {
+			buffer: self.buffer,
+			usage: self.usage,
+			resources: self.resources,
+			transitions: self.transitions,
+			submitted: AtomicBool::new(false)
+		})
+	}
+
+	/// Begin a render pass whose subpasses are recorded inline.
+	///
+	/// The render pass ends when the returned recorder is dropped.
 	pub fn begin_render_pass<'r, I: Send + Sync + Image + 'static, C: pipeline::layout::PushConstants>(
 		&'r mut self,
 		render_pass: &Arc<framebuffer::RenderPass>,
 		framebuffer: &Arc<Framebuffer<I>>,
-		(x, y, width, height): (i32, i32, u32, u32),
+		render_area: Option<(i32, i32, u32, u32)>,
 		clear_values: &[format::ClearValue]
-	) -> RenderPass<'r, 'a, B, pipeline::layout::NoSets<C>> {
+	) -> Result<RenderPass<'r, 'a, B, pipeline::layout::NoSets<C>>, framebuffer::render_pass::BeginError> {
+		self.begin_render_pass_with_contents(render_pass, framebuffer, render_area, clear_values, SubpassContents::Inline)
+	}
+
+	/// Begin a render pass, with the given contents for its first subpass.
+	pub fn begin_render_pass_with_contents<'r, I: Send + Sync + Image + 'static, C: pipeline::layout::PushConstants>(
+		&'r mut self,
+		render_pass: &Arc<framebuffer::RenderPass>,
+		framebuffer: &Arc<Framebuffer<I>>,
+		render_area: Option<(i32, i32, u32, u32)>,
+		clear_values: &[format::ClearValue],
+		contents: SubpassContents
+	) -> Result<RenderPass<'r, 'a, B, pipeline::layout::NoSets<C>>, framebuffer::render_pass::BeginError> {
+		render_pass.check_clear_values(clear_values)?;
+		let clear_values: Vec<_> = clear_values.iter().map(|v| v.into_vulkan()).collect();
+
+		// The whole framebuffer by default.
+		let (x, y, width, height) = render_area.unwrap_or_else(|| {
+			let (width, height) = framebuffer.extent();
+			(0, 0, width, height)
+		});
+
 		let infos = vk::RenderPassBeginInfo {
 			render_pass: render_pass.handle(),
 			framebuffer: framebuffer.handle(),
@@ -47,21 +150,85 @@ impl<'a, B: Buffer> Recorder<'a, B> {
 				extent: vk::Extent2D { width, height }
 			},
 			clear_value_count: clear_values.len() as u32,
-			p_clear_values: clear_values.as_ptr() as *const _,
+			p_clear_values: clear_values.as_ptr(),
 			..Default::default()
 		};
 
 		unsafe {
-			self.buffer.device().handle().cmd_begin_render_pass(self.buffer.handle(), &infos, vk::SubpassContents::INLINE)
+			self.buffer.device().handle().cmd_begin_render_pass(self.buffer.handle(), &infos, contents.into_vulkan())
 		}
 
 		self.resources.insert(render_pass.clone().into());
 		self.resources.insert(framebuffer.clone().into());
 
-		RenderPass {
+		Ok(RenderPass {
 			recorder: self,
-			active_layout: PhantomData
+			active_layout: PhantomData,
+			subpass: 0,
+			subpass_count: render_pass.subpass_count(),
+			attachments: clear::SubpassAttachments::subpass(render_pass, 0, Some((x, y, width, height)), Some(framebuffer.layers())),
+			instance: Some(Instance {
+				render_pass: render_pass.clone(),
+				framebuffer: framebuffer.handle(),
+				contents,
+				inherited: false
+			}),
+			dynamic_states: DynamicStates::empty(),
+			pending_states: DynamicStates::empty()
+		})
+	}
+
+	/// Begin a dynamic rendering into the given attachments.
+	///
+	/// Dynamic rendering must be enabled on the device.
+	/// The rendering ends when the returned recorder is dropped.
+	/// Only pipelines created for dynamic rendering, with the formats of the attachments, can be bound.
+	pub fn begin_rendering<'r, I: Send + Sync + image::ImageProperties + 'static, C: pipeline::layout::PushConstants>(
+		&'r mut self,
+		rendering: &framebuffer::rendering::RenderingInfo<I>
+	) -> Result<RenderPass<'r, 'a, B, pipeline::layout::NoSets<C>>, framebuffer::rendering::BeginError> {
+		framebuffer::rendering::check_support(self.buffer.device())?;
+		rendering.check()?;
+
+		let color_attachments: Vec<_> = rendering.color_attachments.iter().map(|a| a.as_vulkan()).collect();
+		let depth_attachment = rendering.depth_attachment.map(|a| a.as_vulkan());
+		let stencil_attachment = rendering.stencil_attachment.map(|a| a.as_vulkan());
+
+		let (x, y, width, height) = rendering.render_area;
+		let infos = ffi::RenderingInfo {
+			render_area: vk::Rect2D {
+				offset: vk::Offset2D { x, y },
+				extent: vk::Extent2D { width, height }
+			},
+			layer_count: rendering.layer_count,
+			color_attachment_count: color_attachments.len() as u32,
+			p_color_attachments: color_attachments.as_ptr(),
+			p_depth_attachment: depth_attachment.as_ref().map(|a| a as *const _).unwrap_or(std::ptr::null()),
+			p_stencil_attachment: stencil_attachment.as_ref().map(|a| a as *const _).unwrap_or(std::ptr::null()),
+			..Default::default()
+		};
+
+		unsafe {
+			(self.buffer.device().dynamic_rendering().unwrap().cmd_begin_rendering)(self.buffer.handle(), &infos)
 		}
+
+		for attachment in rendering.attachments() {
+			self.resources.insert(attachment.view.clone().into());
+			if let Some(resolve) = &attachment.resolve {
+				self.resources.insert(resolve.view.clone().into());
+			}
+		}
+
+		Ok(RenderPass {
+			recorder: self,
+			active_layout: PhantomData,
+			subpass: 0,
+			subpass_count: 1,
+			attachments: clear::SubpassAttachments::rendering(rendering),
+			instance: None,
+			dynamic_states: DynamicStates::empty(),
+			pending_states: DynamicStates::empty()
+		})
 	}
 
 	pub fn copy_buffer<S: 'a + Send + mem::Buffer, D: 'a + Send + mem::Buffer>(&mut self, src: S, dst: D, regions: &[BufferCopy]) {
@@ -72,35 +239,597 @@ impl<'a, B: Buffer> Recorder<'a, B> {
 		self.resources.insert(src.into());
 		self.resources.insert(dst.into());
 	}
+
+	/// Fill `size` bytes of the buffer at `offset` with the given 4-byte word.
+	///
+	/// The offset and size must be multiples of 4.
+	pub fn fill_buffer<D: 'a + Send + mem::BufferProperties>(&mut self, dst: D, offset: u64, size: u64, value: u32) -> Result<(), TransferError> {
+		check_transfer_write(&dst, offset, size)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_fill_buffer(self.buffer.handle(), dst.handle(), offset, size, value)
+		}
+
+		self.resources.insert(dst.into());
+		Ok(())
+	}
+
+	/// Write the given data in the buffer at `offset`, without staging buffer.
+	///
+	/// The offset and the data length must be multiples of 4,
+	/// and the data must not be larger than `MAX_UPDATE_SIZE` bytes.
+	pub fn update_buffer<D: 'a + Send + mem::BufferProperties>(&mut self, dst: D, offset: u64, data: &[u8]) -> Result<(), TransferError> {
+		if data.len() as u64 > MAX_UPDATE_SIZE {
+			return Err(TransferError::TooLarge)
+		}
+
+		check_transfer_write(&dst, offset, data.len() as u64)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_update_buffer(self.buffer.handle(), dst.handle(), offset, data)
+		}
+
+		self.resources.insert(dst.into());
+		Ok(())
+	}
+
+	/// Copy regions of a buffer into an image in the given layout.
+	///
+	/// The layout must be either `General` or `TransferDstOptimal`.
+	pub fn copy_buffer_to_image<S: 'a + Send + mem::BufferProperties, I: image::ImageProperties>(&mut self, src: S, dst: &'a I, dst_layout: image::Layout, regions: &[BufferImageCopy]) -> Result<(), CopyError> {
+		copy::check_buffer_to_image(&src, dst, regions)?;
+		let regions: Vec<_> = regions.iter().map(|r| r.into_vulkan()).collect();
+
+		unsafe {
+			self.buffer.device().handle().cmd_copy_buffer_to_image(self.buffer.handle(), src.handle(), dst.handle(), dst_layout.into_vulkan(), &regions)
+		}
+
+		self.resources.insert(src.into());
+		Ok(())
+	}
+
+	/// Copy regions of an image in the given layout into a buffer.
+	///
+	/// The layout must be either `General` or `TransferSrcOptimal`.
+	pub fn copy_image_to_buffer<I: image::ImageProperties, D: 'a + Send + mem::BufferProperties>(&mut self, src: &'a I, src_layout: image::Layout, dst: D, regions: &[BufferImageCopy]) -> Result<(), CopyError> {
+		copy::check_image_to_buffer(src, &dst, regions)?;
+		let regions: Vec<_> = regions.iter().map(|r| r.into_vulkan()).collect();
+
+		unsafe {
+			self.buffer.device().handle().cmd_copy_image_to_buffer(self.buffer.handle(), src.handle(), src_layout.into_vulkan(), dst.handle(), &regions)
+		}
+
+		self.resources.insert(dst.into());
+		Ok(())
+	}
+
+	/// Copy regions of an image into another image, without format conversion.
+	///
+	/// The source layout must be either `General` or `TransferSrcOptimal`,
+	/// and the destination layout either `General` or `TransferDstOptimal`.
+	pub fn copy_image<S: image::ImageProperties, D: image::ImageProperties>(&mut self, src: &'a S, src_layout: image::Layout, dst: &'a D, dst_layout: image::Layout, regions: &[ImageCopy]) -> Result<(), CopyError> {
+		copy::check_image_copy(src, dst, regions)?;
+		let regions: Vec<_> = regions.iter().map(|r| r.into_vulkan()).collect();
+
+		unsafe {
+			self.buffer.device().handle().cmd_copy_image(self.buffer.handle(), src.handle(), src_layout.into_vulkan(), dst.handle(), dst_layout.into_vulkan(), &regions)
+		}
+
+		Ok(())
+	}
+
+	/// Copy regions of an image into another image, with scaling and format conversion.
+	///
+	/// The source layout must be either `General` or `TransferSrcOptimal`,
+	/// and the destination layout either `General` or `TransferDstOptimal`.
+	/// Both images may be the same, as long as the regions do not overlap.
+	pub fn blit_image<S: image::ImageProperties, D: image::ImageProperties>(&mut self, src: &'a S, src_layout: image::Layout, dst: &'a D, dst_layout: image::Layout, regions: &[ImageBlit], filter: image::sampler::Filter) -> Result<(), CopyError> {
+		copy::check_blit(src, dst, regions, filter)?;
+		let regions: Vec<_> = regions.iter().map(|r| r.into_vulkan()).collect();
+
+		unsafe {
+			self.buffer.device().handle().cmd_blit_image(self.buffer.handle(), src.handle(), src_layout.into_vulkan(), dst.handle(), dst_layout.into_vulkan(), &regions, filter.into_vulkan())
+		}
+
+		Ok(())
+	}
+
+	/// Resolve regions of a multisampled image into a single sampled image.
+	///
+	/// The source layout must be either `General` or `TransferSrcOptimal`,
+	/// and the destination layout either `General` or `TransferDstOptimal`.
+	pub fn resolve_image<S: image::ImageProperties, D: image::ImageProperties>(&mut self, src: &'a S, src_layout: image::Layout, dst: &'a D, dst_layout: image::Layout, regions: &[ImageResolve]) -> Result<(), CopyError> {
+		copy::check_resolve(src, dst, regions)?;
+		let regions: Vec<_> = regions.iter().map(|r| r.into_vulkan_resolve()).collect();
+
+		unsafe {
+			self.buffer.device().handle().cmd_resolve_image(self.buffer.handle(), src.handle(), src_layout.into_vulkan(), dst.handle(), dst_layout.into_vulkan(), &regions)
+		}
+
+		Ok(())
+	}
+
+	/// Clear regions of a color image in the given layout.
+	///
+	/// The layout must be either `General` or `TransferDstOptimal`,
+	/// and the color must match the numeric type of the image format.
+	pub fn clear_color_image<I: image::ImageProperties>(&mut self, image: &'a I, layout: image::Layout, color: format::ClearColor, ranges: &[image::view::SubresourceRange]) -> Result<(), ClearError> {
+		clear::check_clear_color_image(image, layout, &color, ranges)?;
+		let ranges: Vec<_> = ranges.iter().map(|r| r.into_vulkan()).collect();
+
+		unsafe {
+			self.buffer.device().handle().cmd_clear_color_image(self.buffer.handle(), image.handle(), layout.into_vulkan(), &color.into_vulkan(), &ranges)
+		}
+
+		Ok(())
+	}
+
+	/// Clear regions of a depth-stencil image in the given layout.
+	///
+	/// The layout must be either `General` or `TransferDstOptimal`.
+	pub fn clear_depth_stencil_image<I: image::ImageProperties>(&mut self, image: &'a I, layout: image::Layout, depth: f32, stencil: u32, ranges: &[image::view::SubresourceRange]) -> Result<(), ClearError> {
+		clear::check_clear_depth_stencil_image(image, layout, ranges)?;
+		let ranges: Vec<_> = ranges.iter().map(|r| r.into_vulkan()).collect();
+
+		unsafe {
+			self.buffer.device().handle().cmd_clear_depth_stencil_image(self.buffer.handle(), image.handle(), layout.into_vulkan(), &vk::ClearDepthStencilValue { depth, stencil }, &ranges)
+		}
+
+		Ok(())
+	}
+
+	/// Insert a pipeline barrier with the given image memory barriers.
+	///
+	/// ## Safety
+	///
+	/// The images of the barriers must outlive the execution of the command buffer.
+	pub unsafe fn image_barriers(&mut self, src_stages: pipeline::stage::Flags, dst_stages: pipeline::stage::Flags, barriers: &[ImageMemoryBarrier]) {
+		self.buffer.device().handle().cmd_pipeline_barrier(
+			self.buffer.handle(),
+			src_stages,
+			dst_stages,
+			vk::DependencyFlags::empty(),
+			&[],
+			&[],
+			barriers
+		)
+	}
+
+	/// Transition a tracked image to the given layout.
+	///
+	/// The current layout of the image is its tracked layout,
+	/// or the layout it was last transitioned to in this command buffer.
+	/// The tracked layout is only updated when the command buffer is submitted.
+	pub fn transition<I: image::ImageProperties>(&mut self, image: &'a I, layout: image::Layout) -> Result<(), TransitionError> {
+		let tracker = image.layout_tracker().ok_or(TransitionError::Untracked)?;
+		let old_layout = current_layout(&self.transitions, tracker);
+		self.transition_from(image, old_layout, layout)
+	}
+
+	/// Transition an image from the given layout to another.
+	///
+	/// If the image layout is tracked, the tracked layout is updated when the command buffer is submitted.
+	pub fn transition_from<I: image::ImageProperties>(&mut self, image: &'a I, old_layout: image::Layout, new_layout: image::Layout) -> Result<(), TransitionError> {
+		let (src_stages, dst_stages, barrier) = transition_barrier(image, old_layout, new_layout)?;
+
+		unsafe {
+			self.image_barriers(src_stages, dst_stages, &[barrier])
+		}
+
+		if let Some(tracker) = image.layout_tracker() {
+			self.transitions.push((tracker, new_layout))
+		}
+
+		Ok(())
+	}
+
+	/// Reset the queries of the given range.
+	///
+	/// Queries must be reset before being written.
+	pub fn reset_queries<P: query::Pool>(&mut self, pool: &'a P, range: std::ops::Range<u32>) -> Result<(), query::QueryError> {
+		query::check_range(pool, &range)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_reset_query_pool(self.buffer.handle(), pool.handle(), range.start, range.end - range.start)
+		}
+
+		Ok(())
+	}
+
+	/// Begin the given query.
+	///
+	/// The query must have been reset, and must be ended in the same command buffer.
+	pub fn begin_query<P: query::ScopedPool>(&mut self, pool: &'a P, index: u32) -> Result<(), query::QueryError> {
+		query::check_index(pool, index)?;
+
+		let flags = if pool.is_precise() {
+			vk::QueryControlFlags::PRECISE
+		} else {
+			vk::QueryControlFlags::empty()
+		};
+
+		unsafe {
+			self.buffer.device().handle().cmd_begin_query(self.buffer.handle(), pool.handle(), index, flags)
+		}
+
+		Ok(())
+	}
+
+	pub fn end_query<P: query::ScopedPool>(&mut self, pool: &'a P, index: u32) -> Result<(), query::QueryError> {
+		query::check_index(pool, index)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_end_query(self.buffer.handle(), pool.handle(), index)
+		}
+
+		Ok(())
+	}
+
+	/// Write a timestamp in the given query once all the previous commands reach the given stage.
+	///
+	/// Fails if the queue family of the buffer does not support timestamps.
+	pub fn write_timestamp(&mut self, pool: &'a query::TimestampPool, index: u32, stage: pipeline::stage::Flags) -> Result<(), query::QueryError> {
+		pool.set_queue_family(index, self.buffer.queue_family_index())?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_write_timestamp(self.buffer.handle(), stage, query::Pool::handle(pool), index)
+		}
+
+		Ok(())
+	}
+
+	/// Build a pipeline barrier.
+	///
+	/// Image barriers recorded this way update the tracked layouts of the images.
+	pub fn barrier(&mut self) -> Barrier<'_, 'a> {
+		Barrier::new(self.buffer.device(), self.buffer.handle(), &mut self.transitions)
+	}
+
+	/// Set the event once all the previous commands reach the given stages.
+	pub fn set_event(&mut self, event: &'a sync::Event, stages: PipelineStages) -> Result<(), BarrierError> {
+		let stages = stages.into_vulkan();
+		if stages.is_empty() {
+			return Err(BarrierError::NoStages)
+		}
+
+		unsafe {
+			self.buffer.device().handle().cmd_set_event(self.buffer.handle(), event.handle(), stages)
+		}
+
+		Ok(())
+	}
+
+	/// Reset the event once all the previous commands reach the given stages.
+	pub fn reset_event(&mut self, event: &'a sync::Event, stages: PipelineStages) -> Result<(), BarrierError> {
+		let stages = stages.into_vulkan();
+		if stages.is_empty() {
+			return Err(BarrierError::NoStages)
+		}
+
+		unsafe {
+			self.buffer.device().handle().cmd_reset_event(self.buffer.handle(), event.handle(), stages)
+		}
+
+		Ok(())
+	}
+
+	/// Build a barrier waiting for the given events to be set.
+	///
+	/// The source stages given to `record` must include the stages the events were set at.
+	pub fn wait_events(&mut self, events: &[&'a sync::Event]) -> Barrier<'_, 'a> {
+		let events = events.iter().map(|event| event.handle()).collect();
+		Barrier::new(self.buffer.device(), self.buffer.handle(), &mut self.transitions).with_events(events)
+	}
+
+	/// Insert a global memory barrier.
+	pub fn memory_barrier(&mut self, src_stages: pipeline::stage::Flags, dst_stages: pipeline::stage::Flags, src_access: AccessFlags, dst_access: AccessFlags) {
+		let barrier = vk::MemoryBarrier {
+			src_access_mask: src_access,
+			dst_access_mask: dst_access,
+			..Default::default()
+		};
+
+		unsafe {
+			self.buffer.device().handle().cmd_pipeline_barrier(
+				self.buffer.handle(),
+				src_stages,
+				dst_stages,
+				vk::DependencyFlags::empty(),
+				&[barrier],
+				&[],
+				&[]
+			)
+		}
+	}
+
+	/// Bind a compute pipeline for the following dispatch commands.
+	pub fn bind_compute_pipeline<P: 'static + Send + Sync + pipeline::ComputePipeline>(&mut self, pipeline: &Arc<P>) {
+		unsafe {
+			self.buffer.device().handle().cmd_bind_pipeline(
+				self.buffer.handle(),
+				vk::PipelineBindPoint::COMPUTE,
+				pipeline.handle()
+			)
+		}
+
+		self.resources.insert(pipeline.clone().into());
+	}
+
+	/// Dispatch compute work groups with the bound compute pipeline.
+	///
+	/// The group counts are checked against the device limits in debug builds.
+	pub fn dispatch(&mut self, x: u32, y: u32, z: u32) {
+		if cfg!(debug_assertions) {
+			let max = self.buffer.device().physical_device().limits().max_compute_work_group_count();
+			assert!(x <= max[0] && y <= max[1] && z <= max[2], "dispatch of ({}, {}, {}) work groups exceeds the device limit of {:?}", x, y, z, max);
+		}
+
+		unsafe {
+			self.buffer.device().handle().cmd_dispatch(self.buffer.handle(), x, y, z)
+		}
+	}
+
+	/// Dispatch compute work groups with the bound compute pipeline,
+	/// reading the group counts from the given buffer at `offset`.
+	///
+	/// The buffer must have the indirect buffer usage, and the offset must be a multiple of 4,
+	/// with the whole dispatch command in the buffer.
+	pub fn dispatch_indirect<D: 'a + Send + mem::BufferProperties>(&mut self, buffer: D, offset: u64) -> Result<(), DispatchIndirectError> {
+		check_dispatch_indirect(&buffer, offset)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_dispatch_indirect(self.buffer.handle(), buffer.handle(), offset)
+		}
+
+		self.resources.insert(buffer.into());
+		Ok(())
+	}
+
+	/// Update push constants of the given layout.
+	///
+	/// The update is checked against the push constant ranges of the layout.
+	pub fn push_constants<L: 'a + Send + pipeline::Layout>(&mut self, layout: L, stages: pipeline::shader::Stages, offset: u32, data: &[u8]) -> Result<(), pipeline::layout::push_constant::UpdateError> {
+		layout.description().check_push_constants(stages, offset, data.len() as u32)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_push_constants(self.buffer.handle(), layout.handle(), stages.into_vulkan(), offset, data)
+		}
+
+		self.resources.insert(layout.into());
+		Ok(())
+	}
+
+	/// Bind vertex buffers for the following draw commands, starting at binding `first_binding`.
+	///
+	/// The buffers must have the vertex buffer usage, and the offsets must be in the buffers.
+	pub fn bind_vertex_buffers<V: 'a + Send + Clone + mem::BufferProperties>(&mut self, first_binding: u32, buffers: &[(V, u64)]) -> Result<(), VertexBufferError> {
+		let mut handles = Vec::with_capacity(buffers.len());
+		let mut offsets = Vec::with_capacity(buffers.len());
+		for (binding, (buffer, offset)) in (first_binding..).zip(buffers) {
+			check_vertex_buffer(binding, buffer, *offset)?;
+			handles.push(buffer.handle());
+			offsets.push(*offset);
+		}
+
+		unsafe {
+			self.buffer.device().handle().cmd_bind_vertex_buffers(self.buffer.handle(), first_binding, &handles, &offsets)
+		}
+
+		for (buffer, _) in buffers {
+			self.resources.insert(buffer.clone().into());
+		}
+
+		Ok(())
+	}
+
+	/// Bind an index buffer for the following indexed draw commands.
+	///
+	/// The buffer must have the index buffer usage, and the offset must be in the buffer and a multiple of the index size.
+	/// 8-bit indexes require the `IndexTypeUint8` extended feature.
+	pub fn bind_index_buffer<I: 'a + Send + mem::TypedBuffer + mem::BufferProperties>(&mut self, buffer: I, offset: u64) -> Result<(), IndexError> where I::Item: mem::buffer::IndexInteger {
+		check_index_buffer(&buffer, offset, std::mem::size_of::<I::Item>() as u64)?;
+
+		let index_type = <I::Item as mem::buffer::IndexInteger>::TYPE;
+		check_index_type(self.buffer.device(), index_type)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_bind_index_buffer(self.buffer.handle(), buffer.handle(), offset, index_type)
+		}
+
+		self.resources.insert(buffer.into());
+		Ok(())
+	}
+
+	/// Bind descriptor sets for the following commands of the given bind point, starting at set `first_set` of the given layout.
+	///
+	/// One dynamic offset must be given per dynamic descriptor of the sets, in binding order,
+	/// aligned on the minimum offset alignment of the device for its descriptor type.
+	pub fn bind_descriptor_sets<L: 'a + Send + pipeline::Layout>(&mut self, bind_point: pipeline::BindPoint, layout: L, first_set: u32, sets: &[Arc<pipeline::descriptor::Set>], dynamic_offsets: &[u32]) -> Result<(), BindError> {
+		let handles = check_descriptor_sets(self.buffer.device(), layout.description(), first_set, sets, dynamic_offsets)?;
+
+		unsafe {
+			self.buffer.device().handle().cmd_bind_descriptor_sets(
+				self.buffer.handle(),
+				bind_point.into_vulkan(),
+				layout.handle(),
+				first_set,
+				&handles,
+				dynamic_offsets
+			)
+		}
+
+		self.bound_sets.bind(bind_point, layout.description(), first_set, sets.len() as u32);
+		self.resources.insert(layout.into());
+		for set in sets {
+			self.resources.insert(set.clone().into());
+		}
+
+		Ok(())
+	}
+
+	/// Bind descriptor sets for the following dispatch commands, starting at set `first_set` of the given layout.
+	pub fn bind_compute_descriptor_sets<L: 'a + Send + pipeline::Layout>(&mut self, layout: L, first_set: u32, sets: &[Arc<pipeline::descriptor::Set>], dynamic_offsets: &[u32]) -> Result<(), BindError> {
+		self.bind_descriptor_sets(pipeline::BindPoint::Compute, layout, first_set, sets, dynamic_offsets)
+	}
+
 }
 
-/// Record a render pass.
+/// Record a render pass, or a dynamic rendering.
 /// 
 /// The render pass ends when the `RenderPassRecorder` is dropped.
 pub struct RenderPass<'r, 'a, B: Buffer, L: pipeline::Layout> {
 	recorder: &'r mut Recorder<'a, B>,
-	active_layout: PhantomData<L>
+	active_layout: PhantomData<L>,
+
+	/// Index of the current subpass, and number of subpasses of the render pass.
+	subpass: u32,
+	subpass_count: u32,
+
+	/// Attachments of the current subpass.
+	attachments: clear::SubpassAttachments,
+
+	/// Render pass instance, `None` for a dynamic rendering.
+	instance: Option<Instance>,
+
+	/// Dynamic states set in the render pass, and not invalidated by a pipeline in which they are static.
+	dynamic_states: DynamicStates,
+
+	/// Dynamic states set since the previous draw command.
+	pending_states: DynamicStates
 }
 
 impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
-	fn into_raw_parts(self) -> &'r mut Recorder<'a, B> {
+	fn into_raw_parts(self) -> (&'r mut Recorder<'a, B>, Option<Instance>) {
 		let recorder = unsafe { std::ptr::read(&self.recorder) };
+		let instance = unsafe { std::ptr::read(&self.instance) };
 		std::mem::forget(self);
-		recorder
+		(recorder, instance)
+	}
+
+	/// Render pass recorder of a secondary buffer, continuing the given subpass.
+	pub(crate) fn inherited(recorder: &'r mut Recorder<'a, B>, instance: Instance, subpass: u32) -> RenderPass<'r, 'a, B, L> {
+		RenderPass {
+			recorder,
+			active_layout: PhantomData,
+			subpass,
+			subpass_count: instance.render_pass.subpass_count(),
+			attachments: clear::SubpassAttachments::subpass(&instance.render_pass, subpass, None, None),
+			instance: Some(instance),
+			dynamic_states: DynamicStates::empty(),
+			pending_states: DynamicStates::empty()
+		}
+	}
+
+	/// Index of the current subpass.
+	#[inline]
+	pub fn subpass(&self) -> u32 {
+		self.subpass
+	}
+
+	/// Start the next subpass of the render pass, recorded inline.
+	///
+	/// Pipelines bound afterward must have been created for this subpass.
+	///
+	/// # Panic
+	///
+	/// Panics if the current subpass is the last one,
+	/// or if the render pass is not begun by this recorder.
+	pub fn next_subpass(&mut self) {
+		self.next_subpass_with_contents(SubpassContents::Inline)
+	}
+
+	/// Start the next subpass of the render pass, with the given contents.
+	///
+	/// In a subpass begun with the `SecondaryCommandBuffers` contents,
+	/// commands other than `execute_commands` and `next_subpass` return an error.
+	///
+	/// # Panic
+	///
+	/// Panics if the current subpass is the last one,
+	/// or if the render pass is not begun by this recorder.
+	pub fn next_subpass_with_contents(&mut self, contents: SubpassContents) {
+		assert!(self.subpass + 1 < self.subpass_count, "no subpass after subpass {}", self.subpass);
+		let instance = self.instance.as_mut().filter(|i| !i.inherited).expect("no render pass begun by this recorder");
+		instance.contents = contents;
+		self.attachments = self.attachments.next_subpass(&instance.render_pass, self.subpass + 1);
+
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_next_subpass(self.recorder.buffer.handle(), contents.into_vulkan())
+		}
+
+		self.subpass += 1;
+	}
+
+	/// Clear regions of attachments of the current subpass.
+	pub fn clear_attachments(&mut self, attachments: &[ClearAttachment], rects: &[ClearRect]) -> Result<(), ClearError> {
+		if !is_inline(self.instance.as_ref()) {
+			return Err(ClearError::SecondaryContents)
+		}
+
+		clear::check_clear_attachments(&self.attachments, attachments, rects)?;
+		let attachments: Vec<_> = attachments.iter().map(|a| a.into_vulkan()).collect();
+		let rects: Vec<_> = rects.iter().map(|r| r.into_vulkan()).collect();
+
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_clear_attachments(self.recorder.buffer.handle(), &attachments, &rects)
+		}
+
+		Ok(())
+	}
+
+	/// Execute secondary command buffers in the current subpass.
+	///
+	/// The subpass must have been begun with the `SecondaryCommandBuffers` contents,
+	/// and the secondary buffers recorded for this subpass of a compatible render pass.
+	/// The secondary buffers, and the resources they use, are borrowed for as long as this buffer.
+	pub fn execute_commands<S: Buffer>(&mut self, buffers: &[&'a ExecutableSecondary<'a, S>]) -> Result<(), ExecuteError> {
+		let instance = self.instance.as_ref().ok_or(ExecuteError::DynamicRendering)?;
+		if instance.inherited || instance.contents != SubpassContents::SecondaryCommandBuffers {
+			return Err(ExecuteError::InlineContents)
+		}
+
+		for buffer in buffers {
+			buffer.check_execution(instance, self.subpass)?;
+		}
+
+		let handles: Vec<_> = buffers.iter().map(|b| b.handle()).collect();
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_execute_commands(self.recorder.buffer.handle(), &handles)
+		}
+
+		for buffer in buffers {
+			buffer.set_executed()
+		}
+
+		// The state set by secondary buffers is undefined afterward.
+		self.dynamic_states = DynamicStates::empty();
+		self.pending_states = DynamicStates::empty();
+
+		Ok(())
 	}
 }
 
 impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
+	/// Bind descriptor sets for the following draw commands, changing the active layout.
+	///
+	/// The render pass is given back with the error if the sets cannot be bound.
 	pub fn bind_descriptor_sets<M, T>(
 		self,
 		layout: M,
 		transition: T
-	) -> RenderPass<'r, 'a, B, M>
+	) -> Result<RenderPass<'r, 'a, B, M>, (Self, BindError)>
 	where
 		M: 'a + Send + pipeline::Layout,
 		T: pipeline::layout::set::Transition<L::Sets, M::Sets>
 	{
-		let recorder = self.into_raw_parts();
+		if !is_inline(self.instance.as_ref()) {
+			return Err((self, BindError::SecondaryContents))
+		}
+
+		debug_assert!(transition.first_set() + transition.descriptor_sets().len() as u32 <= layout.description().set_count(), "descriptor sets out of the layout sets");
+
+		let (subpass, subpass_count, attachments, dynamic_states, pending_states) = (self.subpass, self.subpass_count, self.attachments, self.dynamic_states, self.pending_states);
+		let (recorder, instance) = self.into_raw_parts();
 
 		unsafe {
 			recorder.buffer.device().handle().cmd_bind_descriptor_sets(
@@ -113,29 +842,160 @@ impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
 			)
 		};
 
+		recorder.bound_sets.bind(pipeline::BindPoint::Graphics, layout.description(), transition.first_set(), transition.descriptor_sets().len() as u32);
 		recorder.resources.insert(layout.into());
 
-		RenderPass {
+		Ok(RenderPass {
 			recorder,
-			active_layout: PhantomData
+			active_layout: PhantomData,
+			subpass,
+			subpass_count,
+			attachments,
+			instance,
+			dynamic_states,
+			pending_states
+		})
+	}
+
+	/// Bind descriptor sets for the following draw commands, starting at set `first_set` of the given layout.
+	///
+	/// Unlike `bind_descriptor_sets`, the sets are only checked at record time.
+	pub fn bind_graphics_descriptor_sets<M: 'a + Send + pipeline::Layout>(&mut self, layout: M, first_set: u32, sets: &[Arc<pipeline::descriptor::Set>], dynamic_offsets: &[u32]) -> Result<(), BindError> {
+		if !is_inline(self.instance.as_ref()) {
+			return Err(BindError::SecondaryContents)
 		}
+
+		self.recorder.bind_descriptor_sets(pipeline::BindPoint::Graphics, layout, first_set, sets, dynamic_offsets)
 	}
 
-	pub fn draw<P, C, V>(
-		&mut self,
-		pipeline: &Arc<P>,
-		push_constants: C,
-		vertex_input: V,
-		vertex_count: u32,
-		instance_count: u32,
-		first_vertex: u32,
-		first_instance: u32
-	) where
+	/// Bind vertex buffers for the following draw commands, starting at binding `first_binding`.
+	pub fn bind_vertex_buffers<V: 'a + Send + Clone + mem::BufferProperties>(&mut self, first_binding: u32, buffers: &[(V, u64)]) -> Result<(), BindError> {
+		if !is_inline(self.instance.as_ref()) {
+			return Err(BindError::SecondaryContents)
+		}
+
+		Ok(self.recorder.bind_vertex_buffers(first_binding, buffers)?)
+	}
+
+	/// Bind an index buffer for the following indexed draw commands.
+	pub fn bind_index_buffer<I: 'a + Send + mem::TypedBuffer + mem::BufferProperties>(&mut self, buffer: I, offset: u64) -> Result<(), BindError> where I::Item: mem::buffer::IndexInteger {
+		if !is_inline(self.instance.as_ref()) {
+			return Err(BindError::SecondaryContents)
+		}
+
+		Ok(self.recorder.bind_index_buffer(buffer, offset)?)
+	}
+
+	/// Set the viewports starting at `first_viewport`.
+	///
+	/// Dynamic states are checked against the pipelines of the following draw commands:
+	/// every dynamic state of a pipeline must be set,
+	/// and the states set since the previous draw command must be dynamic in its pipeline.
+	pub fn set_viewport(&mut self, first_viewport: u32, viewports: &[pipeline::Viewport]) -> Result<(), DynamicStateError> {
+		self.set_dynamic_state(DynamicState::Viewport)?;
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_set_viewport(
+				self.recorder.buffer.handle(),
+				first_viewport,
+				std::mem::transmute(viewports) // safe thanks to #[repr(transparent)] for Viewport.
+			)
+		}
+
+		Ok(())
+	}
+
+	/// Set the scissors starting at `first_scissor`.
+	pub fn set_scissor(&mut self, first_scissor: u32, scissors: &[pipeline::Scissor]) -> Result<(), DynamicStateError> {
+		self.set_dynamic_state(DynamicState::Scissor)?;
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_set_scissor(
+				self.recorder.buffer.handle(),
+				first_scissor,
+				std::mem::transmute(scissors) // safe thanks to #[repr(transparent)] for Scissor.
+			)
+		}
+
+		Ok(())
+	}
+
+	/// Set the line width.
+	///
+	/// Line widths other than `1.0` require the `WideLines` feature.
+	pub fn set_line_width(&mut self, width: f32) -> Result<(), DynamicStateError> {
+		check_line_width(width, self.recorder.buffer.device().enabled_features().contains(Feature::WideLines))?;
+		self.set_dynamic_state(DynamicState::LineWidth)?;
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_set_line_width(self.recorder.buffer.handle(), width)
+		}
+
+		Ok(())
+	}
+
+	/// Set the depth bias.
+	pub fn set_depth_bias(&mut self, constant_factor: f32, clamp: f32, slope_factor: f32) -> Result<(), DynamicStateError> {
+		self.set_dynamic_state(DynamicState::DepthBias)?;
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_set_depth_bias(self.recorder.buffer.handle(), constant_factor, clamp, slope_factor)
+		}
+
+		Ok(())
+	}
+
+	/// Set the blend constants.
+	pub fn set_blend_constants(&mut self, constants: [f32; 4]) -> Result<(), DynamicStateError> {
+		self.set_dynamic_state(DynamicState::BlendConstants)?;
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_set_blend_constants(self.recorder.buffer.handle(), &constants)
+		}
+
+		Ok(())
+	}
+
+	/// Set the stencil reference.
+	pub fn set_stencil_reference(&mut self, faces: pipeline::stencil_test::Faces, reference: u32) -> Result<(), DynamicStateError> {
+		self.set_dynamic_state(DynamicState::StencilReference)?;
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_set_stencil_reference(self.recorder.buffer.handle(), faces.into_vulkan(), reference)
+		}
+
+		Ok(())
+	}
+
+	/// Mark a dynamic state as set, if the current subpass is recorded inline.
+	fn set_dynamic_state(&mut self, state: DynamicState) -> Result<(), DynamicStateError> {
+		if !is_inline(self.instance.as_ref()) {
+			return Err(DynamicStateError::SecondaryContents)
+		}
+
+		self.dynamic_states.add(state);
+		self.pending_states.add(state);
+		Ok(())
+	}
+
+	/// Bind the pipeline, its push constants and the vertex input for the following draw command.
+	///
+	/// Nothing is recorded if the current subpass is not recorded inline,
+	/// if the bound descriptor sets do not match the pipeline layout,
+	/// or if the dynamic states of the pipeline are not all set.
+	fn bind_graphics_pipeline<P, C, V>(&mut self, pipeline: &Arc<P>, push_constants: C, vertex_input: V) -> Result<(), DrawError> where
 		P: pipeline::GraphicsPipeline,
-		P::Layout: pipeline::layout::CompatibleWith<L>,
 		C: pipeline::layout::push_constant::Setter<<P::Layout as pipeline::Layout>::PushConstants>,
 		V: pipeline::vertex_input::Bind<'a, P::VertexInput>
 	{
+		if !is_inline(self.instance.as_ref()) {
+			return Err(DrawError::SecondaryContents)
+		}
+
+		if !self.recorder.bound_sets.is_compatible_with(pipeline::BindPoint::Graphics, pipeline::Layout::description(pipeline.layout())) {
+			return Err(DrawError::IncompatibleDescriptorSets)
+		}
+
+		let pipeline_dynamic_states = pipeline::GraphicsPipeline::dynamic_states(&**pipeline);
+		check_dynamic_states(&self.dynamic_states, &pipeline_dynamic_states)?;
+		check_static_states(&self.pending_states, &pipeline_dynamic_states)?;
+		self.dynamic_states = bind_dynamic_states(&self.dynamic_states, &pipeline_dynamic_states);
+		self.pending_states = DynamicStates::empty();
+
 		unsafe {
 			self.recorder.buffer.device().handle().cmd_bind_pipeline(
 				self.recorder.buffer.handle(),
@@ -144,6 +1004,8 @@ impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
 			);
 
 			for (range, data) in push_constants.ranges().as_ref() {
+				debug_assert!(pipeline::Layout::description(pipeline.layout()).check_push_constants(range.stages(), range.offset(), range.size()).is_ok(), "push constants out of the layout ranges");
+
 				self.recorder.buffer.device().handle().cmd_push_constants(
 					self.recorder.buffer.handle(),
 					pipeline.layout().handle(),
@@ -162,7 +1024,41 @@ impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
 					offsets.as_ref()
 				);
 			}
+		}
+
+		Ok(())
+	}
+
+	/// The index type must have been checked with `check_index_type`.
+	fn bind_indexes<T: pipeline::input_assembly::Topology, I: mem::IndexBuffer<T>>(&mut self, index_buffer: &I, offset: u64) {
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_bind_index_buffer(
+				self.recorder.buffer.handle(),
+				index_buffer.handle(),
+				offset,
+				index_buffer.index_type()
+			)
+		}
+	}
+
+	pub fn draw<P, C, V>(
+		&mut self,
+		pipeline: &Arc<P>,
+		push_constants: C,
+		vertex_input: V,
+		vertex_count: u32,
+		instance_count: u32,
+		first_vertex: u32,
+		first_instance: u32
+	) -> Result<(), DrawError> where
+		P: pipeline::GraphicsPipeline,
+		P::Layout: pipeline::layout::CompatibleWith<L>,
+		C: pipeline::layout::push_constant::Setter<<P::Layout as pipeline::Layout>::PushConstants>,
+		V: pipeline::vertex_input::Bind<'a, P::VertexInput>
+	{
+		self.bind_graphics_pipeline(pipeline, push_constants, vertex_input)?;
 
+		unsafe {
 			self.recorder.buffer.device().handle().cmd_draw(
 				self.recorder.buffer.handle(),
 				vertex_count,
@@ -171,6 +1067,8 @@ impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
 				first_instance
 			)
 		}
+
+		Ok(())
 	}
 
 	/// Note: when using list topologies (`PointList`, `LineList` and `TriangleList`), 
@@ -189,47 +1087,18 @@ impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
 		first_index: u32,
 		vertex_offset: i32,
 		first_instance: u32
-	) where
+	) -> Result<(), DrawError> where
 		P: pipeline::GraphicsPipeline,
 		P::Layout: pipeline::layout::CompatibleWith<L>,
 		C: pipeline::layout::push_constant::Setter<<P::Layout as pipeline::Layout>::PushConstants>,
 		V: pipeline::vertex_input::Bind<'a, P::VertexInput>,
-		I: 'a + mem::IndexBuffer<<<P::VertexInput as VertexInput>::Assembly as InputAssembly>::Topology>,
+		I: 'a + Send + mem::IndexBuffer<<<P::VertexInput as VertexInput>::Assembly as InputAssembly>::Topology>,
 	{
-		unsafe {
-			self.recorder.buffer.device().handle().cmd_bind_pipeline(
-				self.recorder.buffer.handle(),
-				vk::PipelineBindPoint::GRAPHICS,
-				pipeline.handle()
-			);
-
-			for (range, data) in push_constants.ranges().as_ref() {
-				self.recorder.buffer.device().handle().cmd_push_constants(
-					self.recorder.buffer.handle(),
-					pipeline.layout().handle(),
-					range.0.stage_flags,
-					range.0.offset,
-					std::slice::from_raw_parts(*data, range.0.size as usize)
-				)
-			}
-
-			let (first_binding, vertex_buffers, offsets) = vertex_input.get();
-			if !vertex_buffers.is_empty() {
-				self.recorder.buffer.device().handle().cmd_bind_vertex_buffers(
-					self.recorder.buffer.handle(),
-					first_binding,
-					vertex_buffers.as_vulkan(),
-					offsets.as_ref()
-				);
-			}
-
-			self.recorder.buffer.device().handle().cmd_bind_index_buffer(
-				self.recorder.buffer.handle(),
-				index_buffer.handle(),
-				offset,
-				index_buffer.index_type()
-			);
+		check_index_type(self.recorder.buffer.device(), index_buffer.index_type())?;
+		self.bind_graphics_pipeline(pipeline, push_constants, vertex_input)?;
+		self.bind_indexes(&index_buffer, offset);
 
+		unsafe {
 			self.recorder.buffer.device().handle().cmd_draw_indexed(
 				self.recorder.buffer.handle(),
 				index_count * index_buffer.index_per_item(),
@@ -239,13 +1108,166 @@ impl<'r, 'a, B: Buffer, L: pipeline::Layout> RenderPass<'r, 'a, B, L> {
 				first_instance
 			)
 		}
+
+		self.recorder.resources.insert(index_buffer.into());
+		Ok(())
+	}
+
+	/// Draw with `draw_count` `vk::DrawIndirectCommand` read at `offset` in the given buffer,
+	/// separated by `stride` bytes.
+	pub fn draw_indirect<P, C, V, D>(
+		&mut self,
+		pipeline: &Arc<P>,
+		push_constants: C,
+		vertex_input: V,
+		buffer: D,
+		offset: u64,
+		draw_count: u32,
+		stride: u32
+	) -> Result<(), DrawIndirectError> where
+		P: pipeline::GraphicsPipeline,
+		P::Layout: pipeline::layout::CompatibleWith<L>,
+		C: pipeline::layout::push_constant::Setter<<P::Layout as pipeline::Layout>::PushConstants>,
+		V: pipeline::vertex_input::Bind<'a, P::VertexInput>,
+		D: 'a + Send + mem::BufferProperties
+	{
+		check_draw_indirect::<vk::DrawIndirectCommand, _>(self.recorder.buffer.device().enabled_features(), &buffer, offset, draw_count, stride)?;
+		self.bind_graphics_pipeline(pipeline, push_constants, vertex_input)?;
+
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_draw_indirect(self.recorder.buffer.handle(), buffer.handle(), offset, draw_count, stride)
+		}
+
+		self.recorder.resources.insert(buffer.into());
+		Ok(())
+	}
+
+	/// Draw with `draw_count` `vk::DrawIndexedIndirectCommand` read at `offset` in the given buffer,
+	/// separated by `stride` bytes.
+	///
+	/// Unlike `draw_indexed`, the index counts of the commands are numbers of indexes.
+	pub fn draw_indexed_indirect<P, C, V, I, D>(
+		&mut self,
+		pipeline: &Arc<P>,
+		push_constants: C,
+		vertex_input: V,
+		index_buffer: I,
+		index_offset: u64,
+		buffer: D,
+		offset: u64,
+		draw_count: u32,
+		stride: u32
+	) -> Result<(), DrawIndirectError> where
+		P: pipeline::GraphicsPipeline,
+		P::Layout: pipeline::layout::CompatibleWith<L>,
+		C: pipeline::layout::push_constant::Setter<<P::Layout as pipeline::Layout>::PushConstants>,
+		V: pipeline::vertex_input::Bind<'a, P::VertexInput>,
+		I: 'a + Send + mem::IndexBuffer<<<P::VertexInput as VertexInput>::Assembly as InputAssembly>::Topology>,
+		D: 'a + Send + mem::BufferProperties
+	{
+		check_draw_indirect::<vk::DrawIndexedIndirectCommand, _>(self.recorder.buffer.device().enabled_features(), &buffer, offset, draw_count, stride)?;
+		check_index_type(self.recorder.buffer.device(), index_buffer.index_type())?;
+		self.bind_graphics_pipeline(pipeline, push_constants, vertex_input)?;
+		self.bind_indexes(&index_buffer, index_offset);
+
+		unsafe {
+			self.recorder.buffer.device().handle().cmd_draw_indexed_indirect(self.recorder.buffer.handle(), buffer.handle(), offset, draw_count, stride)
+		}
+
+		self.recorder.resources.insert(index_buffer.into());
+		self.recorder.resources.insert(buffer.into());
+		Ok(())
+	}
+
+	/// Draw with up to `max_draw_count` `vk::DrawIndirectCommand` read at `offset` in the given buffer,
+	/// the actual number of draws being read as a `u32` at `count_offset` in the count buffer.
+	///
+	/// Requires the `VK_KHR_draw_indirect_count` device extension,
+	/// or the `DrawIndirectCount` extended feature of Vulkan 1.2.
+	pub fn draw_indirect_count<P, C, V, D, N>(
+		&mut self,
+		pipeline: &Arc<P>,
+		push_constants: C,
+		vertex_input: V,
+		buffer: D,
+		offset: u64,
+		count_buffer: N,
+		count_offset: u64,
+		max_draw_count: u32,
+		stride: u32
+	) -> Result<(), DrawIndirectError> where
+		P: pipeline::GraphicsPipeline,
+		P::Layout: pipeline::layout::CompatibleWith<L>,
+		C: pipeline::layout::push_constant::Setter<<P::Layout as pipeline::Layout>::PushConstants>,
+		V: pipeline::vertex_input::Bind<'a, P::VertexInput>,
+		D: 'a + Send + mem::BufferProperties,
+		N: 'a + Send + mem::BufferProperties
+	{
+		let device = self.recorder.buffer.device().clone();
+		let commands = check_draw_indirect_count::<vk::DrawIndirectCommand, _, _>(&device, &buffer, offset, &count_buffer, count_offset, max_draw_count, stride)?;
+		self.bind_graphics_pipeline(pipeline, push_constants, vertex_input)?;
+
+		unsafe {
+			(commands.cmd_draw_indirect_count)(self.recorder.buffer.handle(), buffer.handle(), offset, count_buffer.handle(), count_offset, max_draw_count, stride);
+		}
+
+		self.recorder.resources.insert(buffer.into());
+		self.recorder.resources.insert(count_buffer.into());
+		Ok(())
+	}
+
+	/// Draw with up to `max_draw_count` `vk::DrawIndexedIndirectCommand` read at `offset` in the given buffer,
+	/// the actual number of draws being read as a `u32` at `count_offset` in the count buffer.
+	///
+	/// Requires the `VK_KHR_draw_indirect_count` device extension,
+	/// or the `DrawIndirectCount` extended feature of Vulkan 1.2.
+	pub fn draw_indexed_indirect_count<P, C, V, I, D, N>(
+		&mut self,
+		pipeline: &Arc<P>,
+		push_constants: C,
+		vertex_input: V,
+		index_buffer: I,
+		index_offset: u64,
+		buffer: D,
+		offset: u64,
+		count_buffer: N,
+		count_offset: u64,
+		max_draw_count: u32,
+		stride: u32
+	) -> Result<(), DrawIndirectError> where
+		P: pipeline::GraphicsPipeline,
+		P::Layout: pipeline::layout::CompatibleWith<L>,
+		C: pipeline::layout::push_constant::Setter<<P::Layout as pipeline::Layout>::PushConstants>,
+		V: pipeline::vertex_input::Bind<'a, P::VertexInput>,
+		I: 'a + Send + mem::IndexBuffer<<<P::VertexInput as VertexInput>::Assembly as InputAssembly>::Topology>,
+		D: 'a + Send + mem::BufferProperties,
+		N: 'a + Send + mem::BufferProperties
+	{
+		let device = self.recorder.buffer.device().clone();
+		let commands = check_draw_indirect_count::<vk::DrawIndexedIndirectCommand, _, _>(&device, &buffer, offset, &count_buffer, count_offset, max_draw_count, stride)?;
+		check_index_type(self.recorder.buffer.device(), index_buffer.index_type())?;
+		self.bind_graphics_pipeline(pipeline, push_constants, vertex_input)?;
+		self.bind_indexes(&index_buffer, index_offset);
+
+		unsafe {
+			(commands.cmd_draw_indexed_indirect_count)(self.recorder.buffer.handle(), buffer.handle(), offset, count_buffer.handle(), count_offset, max_draw_count, stride);
+		}
+
+		self.recorder.resources.insert(index_buffer.into());
+		self.recorder.resources.insert(buffer.into());
+		self.recorder.resources.insert(count_buffer.into());
+		Ok(())
 	}
 }
 
 impl<'r, 'a, B: Buffer, L: pipeline::Layout> Drop for RenderPass<'r, 'a, B, L> {
 	fn drop(&mut self) {
 		unsafe {
-			self.recorder.buffer.device().handle().cmd_end_render_pass(self.recorder.buffer.handle())
+			match &self.instance {
+				None => (self.recorder.buffer.device().dynamic_rendering().unwrap().cmd_end_rendering)(self.recorder.buffer.handle()),
+				Some(instance) if instance.inherited => (), // ended by the primary buffer.
+				Some(_) => self.recorder.buffer.device().handle().cmd_end_render_pass(self.recorder.buffer.handle())
+			}
 		}
 	}
 }
\ No newline at end of file
@@ -0,0 +1,216 @@
+use ash::vk;
+use std::{
+	collections::HashSet,
+	sync::atomic::{
+		AtomicBool,
+		Ordering
+	},
+	fmt
+};
+use crate::{
+	resource,
+	pipeline
+};
+use super::{
+	Buffer,
+	Usage,
+	Recorder,
+	RecordError,
+	Instance,
+	recorder::RenderPass
+};
+
+/// Error raised when executing secondary command buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecuteError {
+	/// Secondary buffers cannot be executed in a dynamic rendering.
+	DynamicRendering,
+
+	/// The current subpass was not begun with the `SecondaryCommandBuffers` contents.
+	InlineContents,
+
+	/// A secondary buffer was recorded for a render pass incompatible with the current one.
+	IncompatibleRenderPass,
+
+	/// A secondary buffer was recorded for another subpass.
+	SubpassMismatch,
+
+	/// A secondary buffer was recorded for another framebuffer.
+	FramebufferMismatch,
+
+	/// The pool of a secondary buffer has been reset since its allocation.
+	Invalidated,
+
+	/// A `OneTimeSubmit` secondary buffer has already been executed.
+	AlreadyExecuted
+}
+
+impl fmt::Display for ExecuteError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::DynamicRendering => write!(f, "secondary command buffers executed in a dynamic rendering"),
+			Self::InlineContents => write!(f, "subpass contents are inline"),
+			Self::IncompatibleRenderPass => write!(f, "secondary command buffer recorded for an incompatible render pass"),
+			Self::SubpassMismatch => write!(f, "secondary command buffer recorded for another subpass"),
+			Self::FramebufferMismatch => write!(f, "secondary command buffer recorded for another framebuffer"),
+			Self::Invalidated => write!(f, "secondary command buffer invalidated by a pool reset"),
+			Self::AlreadyExecuted => write!(f, "one-time submit secondary command buffer already executed")
+		}
+	}
+}
+
+impl std::error::Error for ExecuteError { }
+
+/// Secondary command buffer recorder, continuing a subpass of a render pass.
+pub struct SecondaryRecorder<'a, B: Buffer> {
+	recorder: Recorder<'a, B>,
+	subpass: u32,
+	instance: Instance
+}
+
+impl<'a, B: Buffer> SecondaryRecorder<'a, B> {
+	pub(crate) fn new(recorder: Recorder<'a, B>, subpass: u32, instance: Instance) -> SecondaryRecorder<'a, B> {
+		SecondaryRecorder {
+			recorder,
+			subpass,
+			instance
+		}
+	}
+
+	/// Index of the inherited subpass.
+	#[inline]
+	pub fn subpass(&self) -> u32 {
+		self.subpass
+	}
+
+	/// Record commands inside the inherited subpass.
+	///
+	/// The subpass cannot be changed, and is not ended when the returned recorder is dropped.
+	pub fn render_pass<C: pipeline::layout::PushConstants>(&mut self) -> RenderPass<'_, 'a, B, pipeline::layout::NoSets<C>> {
+		RenderPass::inherited(&mut self.recorder, self.instance.clone(), self.subpass)
+	}
+
+	/// Ends the recording.
+	pub fn finish(self) -> Result<ExecutableSecondary<'a, B>, RecordError> {
+		let executable = self.recorder.finish()?;
+
+		Ok(ExecutableSecondary {
+			buffer: executable.buffer,
+			usage: executable.usage,
+			resources: executable.resources,
+			subpass: self.subpass,
+			instance: self.instance,
+			executed: AtomicBool::new(false)
+		})
+	}
+}
+
+/// Recorded secondary command buffer.
+pub struct ExecutableSecondary<'a, B: Buffer> {
+	buffer: B,
+	usage: Usage,
+	resources: HashSet<resource::SendRef<'a>>,
+	subpass: u32,
+	instance: Instance,
+
+	/// Set once a `OneTimeSubmit` buffer is executed by a primary buffer.
+	executed: AtomicBool
+}
+
+impl<'a, B: Buffer> ExecutableSecondary<'a, B> {
+	#[inline]
+	pub fn usage(&self) -> Usage {
+		self.usage
+	}
+
+	/// Index of the subpass the buffer is executed in.
+	#[inline]
+	pub fn subpass(&self) -> u32 {
+		self.subpass
+	}
+
+	/// Resources used by the buffer, kept alive until the executable is dropped.
+	pub fn resources(&self) -> &HashSet<resource::SendRef<'a>> {
+		&self.resources
+	}
+
+	#[inline]
+	pub(crate) fn handle(&self) -> vk::CommandBuffer {
+		self.buffer.handle()
+	}
+
+	/// Checks that the buffer can be executed in the given subpass of a render pass instance.
+	pub(crate) fn check_execution(&self, instance: &Instance, subpass: u32) -> Result<(), ExecuteError> {
+		if !self.buffer.is_valid() {
+			return Err(ExecuteError::Invalidated)
+		}
+
+		if !self.instance.render_pass.is_compatible_with(&instance.render_pass) {
+			return Err(ExecuteError::IncompatibleRenderPass)
+		}
+
+		check_inheritance(
+			(self.subpass, self.instance.framebuffer),
+			(subpass, instance.framebuffer),
+			self.usage,
+			self.executed.load(Ordering::Relaxed)
+		)
+	}
+
+	/// Called once the buffer is recorded into a primary buffer.
+	pub(crate) fn set_executed(&self) {
+		self.executed.store(true, Ordering::Relaxed)
+	}
+}
+
+/// Checks that a buffer recorded for the given subpass and framebuffer,
+/// null if unknown, can be executed in the current subpass and framebuffer of a compatible render pass.
+fn check_inheritance(recorded: (u32, vk::Framebuffer), current: (u32, vk::Framebuffer), usage: Usage, executed: bool) -> Result<(), ExecuteError> {
+	if recorded.0 != current.0 {
+		return Err(ExecuteError::SubpassMismatch)
+	}
+
+	if recorded.1 != vk::Framebuffer::null() && recorded.1 != current.1 {
+		return Err(ExecuteError::FramebufferMismatch)
+	}
+
+	if usage == Usage::OneTimeSubmit && executed {
+		return Err(ExecuteError::AlreadyExecuted)
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ash::vk::Handle;
+
+	#[test]
+	fn executed_in_the_recorded_subpass() {
+		let framebuffer = vk::Framebuffer::from_raw(1);
+
+		assert_eq!(check_inheritance((1, framebuffer), (1, framebuffer), Usage::SimultaneousUse, false), Ok(()));
+		assert_eq!(check_inheritance((0, framebuffer), (1, framebuffer), Usage::SimultaneousUse, false), Err(ExecuteError::SubpassMismatch));
+	}
+
+	#[test]
+	fn executed_in_the_recorded_framebuffer() {
+		let framebuffer = vk::Framebuffer::from_raw(1);
+		let other_framebuffer = vk::Framebuffer::from_raw(2);
+
+		assert_eq!(check_inheritance((0, framebuffer), (0, other_framebuffer), Usage::SimultaneousUse, false), Err(ExecuteError::FramebufferMismatch));
+
+		// A buffer recorded without a framebuffer can be executed in any of them.
+		assert_eq!(check_inheritance((0, vk::Framebuffer::null()), (0, other_framebuffer), Usage::SimultaneousUse, false), Ok(()));
+	}
+
+	#[test]
+	fn one_time_submit_executed_once() {
+		let framebuffer = vk::Framebuffer::from_raw(1);
+
+		assert_eq!(check_inheritance((0, framebuffer), (0, framebuffer), Usage::OneTimeSubmit, false), Ok(()));
+		assert_eq!(check_inheritance((0, framebuffer), (0, framebuffer), Usage::OneTimeSubmit, true), Err(ExecuteError::AlreadyExecuted));
+		assert_eq!(check_inheritance((0, framebuffer), (0, framebuffer), Usage::SimultaneousUse, true), Ok(()));
+	}
+}
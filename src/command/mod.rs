@@ -1,8 +1,21 @@
 pub mod pool;
 pub mod buffer;
+pub mod once;
+pub mod query;
 
 pub use pool::Pool;
 pub use buffer::{
 	Buffer,
-	RecordedBuffer
+	RecordedBuffer,
+	Executable,
+	LocalExecutable
+};
+pub use query::{
+	TimestampPool,
+	OcclusionPool,
+	PipelineStatisticsPool
+};
+pub use once::{
+	submit_once,
+	submit_once_pending
 };
\ No newline at end of file
@@ -0,0 +1,136 @@
+//! One-time submissions, for immediate GPU work.
+//!
+//! A primary buffer is recorded with the `OneTimeSubmit` usage, submitted with a fresh fence,
+//! and kept alive with the resources it uses until the fence is signaled.
+use ash::vk;
+use crate::{
+	device::{
+		Queue,
+		queue::{
+			Submission,
+			SubmissionSemaphore,
+			SubmitError
+		}
+	},
+	sync::{
+		fence::WaitError,
+		future::Futures
+	}
+};
+use super::{
+	pool::{
+		self,
+		Pool as _
+	},
+	buffer::{
+		Buffer,
+		Usage,
+		Recorder,
+		LocalRecorder,
+		Executable,
+		LocalExecutable,
+		RecordError
+	}
+};
+
+/// Pending one-time submission.
+///
+/// Owns the submitted buffer and the resources it uses until the submission is done.
+/// Dropping a pending submission blocks until it completes.
+pub type Pending<'a, B, S = ()> = Submission<((), Executable<'a, B>), S>;
+
+/// Pending one-time submission of a buffer recorded with non-`Send` resources.
+pub type LocalPending<'a, B, S = ()> = Submission<((), LocalExecutable<'a, B>), S>;
+
+fn alloc_error(pool::AllocError::OutOfMemory(e): pool::AllocError) -> SubmitError {
+	SubmitError::OutOfMemory(e)
+}
+
+fn record_error(e: RecordError) -> SubmitError {
+	match e {
+		RecordError::OutOfMemory(e) => SubmitError::OutOfMemory(e),
+		RecordError::Invalidated => SubmitError::InvalidatedBuffer,
+		RecordError::AlreadyRecorded => SubmitError::AlreadyRecordedBuffer,
+		// Inheritance errors are only returned when beginning secondary buffers.
+		RecordError::LevelMismatch | RecordError::InvalidSubpass | RecordError::IncompatibleFramebuffer => SubmitError::SecondaryBuffer
+	}
+}
+
+fn wait_error(e: WaitError) -> SubmitError {
+	match e {
+		WaitError::OutOfMemory(e) => SubmitError::OutOfMemory(e),
+		WaitError::DeviceLost => SubmitError::DeviceLost,
+		WaitError::Timeout | WaitError::MismatchedDevices | WaitError::NoFence => unreachable!() // waits without timeout on a single fence.
+	}
+}
+
+/// Record the given primary buffer with `f` and submit it to the queue.
+///
+/// Fails with `SubmitError::SecondaryBuffer` if the buffer is a secondary buffer,
+/// with `SubmitError::InvalidatedBuffer` if its pool has been reset,
+/// and with `SubmitError::AlreadyRecordedBuffer` if it has already been recorded and cannot be reset.
+/// Returns the pending submission and the output of `f`.
+/// The submission also signals `semaphore`, unless it is `()`,
+/// and can then be chained with other tasks.
+pub fn submit_buffer_once<'a, B, S, F, R>(queue: &Queue, buffer: B, semaphore: S, f: F) -> Result<(Pending<'a, B, S>, R), SubmitError> where B: Buffer + Send, S: SubmissionSemaphore, F: FnOnce(&mut Recorder<'a, B>) -> R {
+	submit_buffer_once_after(queue, buffer, (), vk::PipelineStageFlags::TOP_OF_PIPE, semaphore, f)
+}
+
+/// Record the given primary buffer with `f` and submit it to the queue after the given futures,
+/// waited at the given pipeline stages.
+///
+/// The futures are kept alive with the submission.
+pub fn submit_buffer_once_after<'a, B, W, S, F, R>(queue: &Queue, buffer: B, wait: W, wait_stages: vk::PipelineStageFlags, semaphore: S, f: F) -> Result<(Submission<(W, Executable<'a, B>), S>, R), SubmitError> where B: Buffer + Send, W: Futures, S: SubmissionSemaphore, F: FnOnce(&mut Recorder<'a, B>) -> R {
+	let mut recorder = buffer.begin(Usage::OneTimeSubmit).map_err(record_error)?;
+	let output = f(&mut recorder);
+	let executable = recorder.finish().map_err(record_error)?;
+
+	let submission = queue.submit_and_signal(executable, wait, wait_stages, semaphore)?;
+	Ok((submission, output))
+}
+
+/// Record the given primary buffer with `f`, using non-`Send` resources, and submit it to the queue.
+pub fn submit_buffer_once_local<'a, B, S, F, R>(queue: &Queue, buffer: B, semaphore: S, f: F) -> Result<(LocalPending<'a, B, S>, R), SubmitError> where B: Buffer, S: SubmissionSemaphore, F: FnOnce(&mut LocalRecorder<'a, B>) -> R {
+	let mut recorder = buffer.begin_local(Usage::OneTimeSubmit).map_err(record_error)?;
+	let output = f(&mut recorder);
+	let executable = recorder.finish().map_err(record_error)?;
+
+	let submission = queue.submit_and_signal(executable, (), vk::PipelineStageFlags::TOP_OF_PIPE, semaphore)?;
+	Ok((submission, output))
+}
+
+/// Allocate a primary buffer from the pool, record it with `f`, submit it to the queue without waiting.
+///
+/// The buffer is freed once the returned submission is dropped.
+pub fn submit_once_pending<'a, 'p, F, R>(queue: &Queue, pool: &'p pool::Raw, f: F) -> Result<(LocalPending<'a, pool::raw::Buffer<&'p pool::Raw>>, R), SubmitError> where F: FnOnce(&mut LocalRecorder<'a, pool::raw::Buffer<&'p pool::Raw>>) -> R {
+	let buffer = pool.allocate(1, pool::Level::Primary).map_err(alloc_error)?.pop().unwrap();
+	submit_buffer_once_local(queue, buffer, (), f)
+}
+
+/// Allocate a primary buffer from the pool, record it with `f`, submit it to the queue,
+/// and block until it is executed.
+///
+/// Returns the output of `f`. The buffer is freed before returning.
+pub fn submit_once<'p, F, R>(queue: &Queue, pool: &'p pool::Raw, f: F) -> Result<R, SubmitError> where F: FnOnce(&mut LocalRecorder<'_, pool::raw::Buffer<&'p pool::Raw>>) -> R {
+	let (submission, output) = submit_once_pending(queue, pool, f)?;
+	submission.wait_done(None).map_err(wait_error)?;
+	Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::command::buffer::check_begin;
+
+	#[test]
+	fn secondary_buffers_are_not_submitted() {
+		let e = check_begin(true, true, pool::Level::Secondary, pool::Level::Primary).unwrap_err();
+		assert!(matches!(record_error(e), SubmitError::SecondaryBuffer))
+	}
+
+	#[test]
+	fn invalidated_buffers_are_not_submitted() {
+		let e = check_begin(false, true, pool::Level::Primary, pool::Level::Primary).unwrap_err();
+		assert!(matches!(record_error(e), SubmitError::InvalidatedBuffer))
+	}
+}
@@ -20,6 +20,72 @@ pub mod sync;
 pub use raw::Raw;
 pub use sync::SyncPool;
 
+/// Command pool creation flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Flags {
+	/// Buffers allocated from the pool are short-lived.
+	///
+	/// This is a hint to the implementation that it may optimize memory allocations.
+	pub transient: bool,
+
+	/// Buffers allocated from the pool can be individually reset.
+	///
+	/// Without this flag, a buffer can only be recorded once until the whole pool is reset.
+	pub reset_command_buffer: bool
+}
+
+impl Flags {
+	#[inline]
+	pub fn none() -> Flags {
+		Flags {
+			transient: false,
+			reset_command_buffer: false
+		}
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::CommandPoolCreateFlags {
+		let mut flags = vk::CommandPoolCreateFlags::empty();
+
+		if self.transient {
+			flags |= vk::CommandPoolCreateFlags::TRANSIENT
+		}
+
+		if self.reset_command_buffer {
+			flags |= vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER
+		}
+
+		flags
+	}
+}
+
+/// Command buffer level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Level {
+	/// Can be submitted to a queue.
+	Primary,
+
+	/// Can be executed by a primary command buffer.
+	Secondary
+}
+
+impl Level {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::CommandBufferLevel {
+		match self {
+			Level::Primary => vk::CommandBufferLevel::PRIMARY,
+			Level::Secondary => vk::CommandBufferLevel::SECONDARY
+		}
+	}
+}
+
+impl Default for Level {
+	#[inline]
+	fn default() -> Level {
+		Level::Primary
+	}
+}
+
 #[derive(Debug)]
 pub enum CreationError {
 	OutOfMemory(OomError)
@@ -35,6 +101,20 @@ impl From<vk::Result> for CreationError {
 	}
 }
 
+#[derive(Debug)]
+pub enum ResetError {
+	OutOfMemory(OomError)
+}
+
+impl From<vk::Result> for ResetError {
+	fn from(r: vk::Result) -> ResetError {
+		match r {
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => ResetError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
 #[derive(Debug)]
 pub enum AllocError {
 	OutOfMemory(OomError)
@@ -51,13 +131,24 @@ impl From<vk::Result> for AllocError {
 }
 
 pub trait Pool: DeviceOwned {
-	type Buffer<'a>;
+	type Buffer<'a> where Self: 'a;
 
-	fn allocate<'a>(&'a self, count: u32) -> Result<Vec<Self::Buffer<'a>>, AllocError>;
+	fn allocate<'a>(&'a self, count: u32, level: Level) -> Result<Vec<Self::Buffer<'a>>, AllocError>;
 }
 
 pub trait Handle: DeviceOwned {
 	fn handle(&self) -> vk::CommandPool;
 
+	/// Number of times the pool has been reset.
+	///
+	/// Buffers allocated before the last reset are invalid.
+	fn generation(&self) -> u64;
+
+	/// Index of the queue family the buffers of the pool are submitted to.
+	fn queue_family_index(&self) -> u32;
+
+	/// Flags the pool was created with.
+	fn flags(&self) -> Flags;
+
 	unsafe fn free(&self, buffer_handles: &[vk::CommandBuffer]);
 }
\ No newline at end of file
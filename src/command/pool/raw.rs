@@ -3,36 +3,60 @@ use ash::{
 	version::DeviceV1_0
 };
 use std::{
-	sync::Arc,
-	rc::Rc
+	sync::{
+		Arc,
+		atomic::{
+			AtomicBool,
+			AtomicU64,
+			Ordering
+		}
+	},
+	rc::Rc,
+	cell::Cell,
+	marker::PhantomData
 };
 use crate::{
 	instance::physical_device,
 	Device,
 	DeviceOwned,
+	device::MissingExtensionError,
 	command
 };
 use super::{
 	CreationError,
 	AllocError,
+	ResetError,
+	Flags,
+	Level,
 	Pool,
 	Handle
 };
 
 pub type RcBuffer = Buffer<Rc<Raw>>;
 
+/// Command pool.
+///
+/// Command pools are externally synchronized:
+/// a pool can be sent to another thread, but not shared between threads.
 pub struct Raw {
 	device: Arc<Device>,
-	handle: vk::CommandPool
+	handle: vk::CommandPool,
+	queue_family_index: u32,
+	flags: Flags,
+
+	/// Incremented each time the pool is reset.
+	generation: Arc<AtomicU64>,
+
+	not_sync: PhantomData<Cell<()>>
 }
 
 impl Raw {
-	pub fn new(device: &Arc<Device>, queue_family: physical_device::QueueFamily) -> Result<Self, CreationError> {
+	pub fn new(device: &Arc<Device>, queue_family: physical_device::QueueFamily, flags: Flags) -> Result<Self, CreationError> {
 		assert_eq!(device.physical_device().index(), queue_family.physical_device().index());
 		
 		let infos = vk::CommandPoolCreateInfo {
 			queue_family_index: queue_family.index(),
-			flags: vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER, // TODO
+			flags: flags.into_vulkan(),
 			..Default::default()
 		};
 
@@ -42,14 +66,27 @@ impl Raw {
 
 		Ok(Self {
 			device: device.clone(),
-			handle
+			handle,
+			queue_family_index: queue_family.index(),
+			flags,
+			generation: Arc::new(AtomicU64::new(0)),
+			not_sync: PhantomData
 		})
 	}
 
-	pub unsafe fn allocate_into<F, B>(&self, count: u32, f: F) -> Result<Vec<B>, AllocError> where F: Fn(vk::CommandBuffer) -> B {
+	#[inline]
+	pub fn flags(&self) -> Flags {
+		self.flags
+	}
+
+	pub(crate) fn shared_generation(&self) -> &Arc<AtomicU64> {
+		&self.generation
+	}
+
+	pub unsafe fn allocate_into<F, B>(&self, count: u32, level: Level, f: F) -> Result<Vec<B>, AllocError> where F: Fn(vk::CommandBuffer) -> B {
 		let infos = vk::CommandBufferAllocateInfo {
 			command_pool: self.handle,
-			level: vk::CommandBufferLevel::PRIMARY,
+			level: level.into_vulkan(),
 			command_buffer_count: count,
 			..Default::default()
 		};
@@ -61,9 +98,42 @@ impl Raw {
 		Ok(handles.into_iter().map(f).collect())
 	}
 
-	pub fn allocate_rc(self: &Rc<Self>, count: u32) -> Result<Vec<RcBuffer>, AllocError> {
+	pub fn allocate_rc(self: &Rc<Self>, count: u32, level: Level) -> Result<Vec<RcBuffer>, AllocError> {
 		unsafe {
-			self.allocate_into(count, |h| Buffer::new(self.clone(), h))
+			let generation = self.generation();
+			self.allocate_into(count, level, |h| Buffer::new(self.clone(), h, level, generation))
+		}
+	}
+
+	/// Resets the pool, returning all its buffers to the initial state.
+	///
+	/// Every buffer allocated from the pool is invalidated:
+	/// recording or submitting it afterward fails.
+	/// The buffers are still freed when dropped.
+	///
+	/// If `release_resources` is `true`, the memory used by the buffers is returned to the system.
+	///
+	/// # Safety
+	///
+	/// No buffer allocated from the pool must be pending execution.
+	pub unsafe fn reset(&self, release_resources: bool) -> Result<(), ResetError> {
+		let flags = if release_resources {
+			vk::CommandPoolResetFlags::RELEASE_RESOURCES
+		} else {
+			vk::CommandPoolResetFlags::empty()
+		};
+
+		self.device.handle().reset_command_pool(self.handle, flags)?;
+		self.generation.fetch_add(1, Ordering::Relaxed);
+		Ok(())
+	}
+
+	/// Returns unused memory of the pool to the system.
+	///
+	/// Requires Vulkan 1.1 or the `VK_KHR_maintenance1` extension.
+	pub fn trim(&self) -> Result<(), MissingExtensionError> {
+		unsafe {
+			self.device.trim_command_pool(self.handle)
 		}
 	}
 }
@@ -71,9 +141,10 @@ impl Raw {
 impl Pool for Raw {
 	type Buffer<'a> = Buffer<&'a Self>;
 
-	fn allocate(&self, count: u32) -> Result<Vec<Buffer<&Self>>, AllocError> {
+	fn allocate(&self, count: u32, level: Level) -> Result<Vec<Buffer<&Self>>, AllocError> {
 		unsafe {
-			self.allocate_into(count, |h| Buffer::new(self, h))
+			let generation = self.generation();
+			self.allocate_into(count, level, |h| Buffer::new(self, h, level, generation))
 		}
 	}
 }
@@ -83,6 +154,18 @@ impl Handle for Raw {
 		self.handle
 	}
 
+	fn generation(&self) -> u64 {
+		self.generation.load(Ordering::Relaxed)
+	}
+
+	fn queue_family_index(&self) -> u32 {
+		self.queue_family_index
+	}
+
+	fn flags(&self) -> Flags {
+		self.flags
+	}
+
 	unsafe fn free(&self, buffer_handles: &[vk::CommandBuffer]) {
 		self.device.handle().free_command_buffers(self.handle, buffer_handles)
 	}
@@ -93,16 +176,28 @@ impl<P: DeviceOwned + std::ops::Deref<Target=Raw>> Handle for P {
 		self.deref().handle()
 	}
 
+	fn generation(&self) -> u64 {
+		self.deref().generation()
+	}
+
+	fn queue_family_index(&self) -> u32 {
+		self.deref().queue_family_index()
+	}
+
+	fn flags(&self) -> Flags {
+		self.deref().flags()
+	}
+
 	unsafe fn free(&self, buffer_handles: &[vk::CommandBuffer]) {
 		self.deref().free(buffer_handles)
 	}
 }
 
 impl<P: DeviceOwned + std::ops::Deref<Target=Raw>> Pool for P {
-	type Buffer<'a> = Buffer<&'a Raw>;
+	type Buffer<'a> = Buffer<&'a Raw> where Self: 'a;
 
-	fn allocate(&self, count: u32) -> Result<Vec<Buffer<&Raw>>, AllocError> {
-		self.deref().allocate(count)
+	fn allocate(&self, count: u32, level: Level) -> Result<Vec<Buffer<&Raw>>, AllocError> {
+		self.deref().allocate(count, level)
 	}
 }
 
@@ -122,14 +217,24 @@ impl Drop for Raw {
 
 pub struct Buffer<P: Handle> {
 	pool: P,
-	handle: vk::CommandBuffer
+	handle: vk::CommandBuffer,
+	level: Level,
+
+	/// Generation of the pool when the buffer was allocated.
+	generation: u64,
+
+	/// Set once the buffer has begun recording.
+	recorded: AtomicBool
 }
 
 impl<P: Handle> Buffer<P> {
-	pub(crate) fn new(pool: P, handle: vk::CommandBuffer) -> Self {
+	pub(crate) fn new(pool: P, handle: vk::CommandBuffer, level: Level, generation: u64) -> Self {
 		Buffer {
 			pool,
-			handle
+			handle,
+			level,
+			generation,
+			recorded: AtomicBool::new(false)
 		}
 	}
 
@@ -141,19 +246,52 @@ impl<P: Handle> Buffer<P> {
 	}
 
 	pub unsafe fn map_pool<F, Q: Handle>(self, f: F) -> Buffer<Q> where F: FnOnce(P) -> Q {
+		let level = self.level;
+		let generation = self.generation;
+		let recorded = self.recorded.load(Ordering::Relaxed);
 		let (pool, handle) = self.into_raw_parts();
 
 		Buffer {
 			pool: f(pool),
-			handle
+			handle,
+			level,
+			generation,
+			recorded: AtomicBool::new(recorded)
 		}
 	}
 }
 
+/// Checks that a buffer, already recorded or not, can begin recording.
+///
+/// Buffers can only be recorded again if their pool was created with the `reset_command_buffer` flag.
+pub(crate) fn can_begin(recorded: bool, flags: Flags) -> bool {
+	!recorded || flags.reset_command_buffer
+}
+
 impl<P: Handle> command::Buffer for Buffer<P> {
 	fn handle(&self) -> vk::CommandBuffer {
 		self.handle
 	}
+
+	fn level(&self) -> Level {
+		self.level
+	}
+
+	fn is_valid(&self) -> bool {
+		self.pool.generation() == self.generation
+	}
+
+	fn can_begin(&self) -> bool {
+		can_begin(self.recorded.load(Ordering::Relaxed), self.pool.flags())
+	}
+
+	fn set_recorded(&self) {
+		self.recorded.store(true, Ordering::Relaxed)
+	}
+
+	fn queue_family_index(&self) -> Option<u32> {
+		Some(self.pool.queue_family_index())
+	}
 }
 
 impl<P: Handle> DeviceOwned for Buffer<P> {
@@ -165,7 +303,7 @@ impl<P: Handle> DeviceOwned for Buffer<P> {
 impl<P: Handle> Drop for Buffer<P> {
 	fn drop(&mut self) {
 		unsafe {
-			self.pool.device().handle().free_command_buffers(self.pool.handle(), &[self.handle])
+			self.pool.free(&[self.handle])
 		}
 	}
 }
\ No newline at end of file
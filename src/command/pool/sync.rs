@@ -3,18 +3,27 @@ use ash::{
 	version::DeviceV1_0
 };
 use std::{
-	sync::Arc
+	sync::{
+		Arc,
+		atomic::{
+			AtomicU64,
+			Ordering
+		}
+	}
 };
 use crossbeam_queue::SegQueue;
 use crate::{
 	instance::physical_device,
 	Device,
 	DeviceOwned,
-	command
+	device::MissingExtensionError
 };
 use super::{
 	CreationError,
 	AllocError,
+	ResetError,
+	Flags,
+	Level,
 	Pool,
 	Handle,
 	Raw,
@@ -24,21 +33,26 @@ use super::{
 pub struct SendHandle {
 	device: Arc<Device>,
 	handle: vk::CommandPool,
+	queue_family_index: u32,
+	flags: Flags,
+	generation: Arc<AtomicU64>,
 	free_queue: Arc<SegQueue<vk::CommandBuffer>>
 }
 
 unsafe impl Send for SendHandle {}
 
 /// A command pool with `Send` buffers.
+///
+/// Buffers dropped in other threads are freed the next time the pool allocates.
 pub struct SyncPool {
 	raw: Raw,
 	free_queue: Arc<SegQueue<vk::CommandBuffer>>
 }
 
 impl SyncPool {
-	pub fn new(device: &Arc<Device>, queue_family: physical_device::QueueFamily) -> Result<Self, CreationError> {
+	pub fn new(device: &Arc<Device>, queue_family: physical_device::QueueFamily, flags: Flags) -> Result<Self, CreationError> {
 		Ok(SyncPool {
-			raw: Raw::new(device, queue_family)?,
+			raw: Raw::new(device, queue_family, flags)?,
 			free_queue: Arc::new(SegQueue::new())
 		})
 	}
@@ -47,6 +61,9 @@ impl SyncPool {
 		SendHandle {
 			device: self.raw.device().clone(),
 			handle: self.raw.handle(),
+			queue_family_index: self.raw.queue_family_index(),
+			flags: self.raw.flags(),
+			generation: self.raw.shared_generation().clone(),
 			free_queue: self.free_queue.clone()
 		}
 	}
@@ -56,15 +73,31 @@ impl SyncPool {
 			unsafe { self.raw.free(&[handle]) }
 		}
 	}
+
+	/// Resets the pool, invalidating every buffer allocated from it.
+	///
+	/// # Safety
+	///
+	/// No buffer allocated from the pool must be pending execution.
+	pub unsafe fn reset(&self, release_resources: bool) -> Result<(), ResetError> {
+		self.process_deallocations();
+		self.raw.reset(release_resources)
+	}
+
+	pub fn trim(&self) -> Result<(), MissingExtensionError> {
+		self.process_deallocations();
+		self.raw.trim()
+	}
 }
 
 impl Pool for SyncPool {
 	type Buffer<'a> = Buffer;
 
-	fn allocate(&self, count: u32) -> Result<Vec<Buffer>, AllocError> {
+	fn allocate(&self, count: u32, level: Level) -> Result<Vec<Buffer>, AllocError> {
 		self.process_deallocations();
 		unsafe {
-			self.raw.allocate_into(count, |h| Buffer::new(self.send_handle(), h))
+			let generation = self.raw.generation();
+			self.raw.allocate_into(count, level, |h| Buffer::new(self.send_handle(), h, level, generation))
 		}
 	}
 }
@@ -92,6 +125,18 @@ impl super::Handle for SendHandle {
 		self.handle
 	}
 
+	fn generation(&self) -> u64 {
+		self.generation.load(Ordering::Relaxed)
+	}
+
+	fn queue_family_index(&self) -> u32 {
+		self.queue_family_index
+	}
+
+	fn flags(&self) -> Flags {
+		self.flags
+	}
+
 	unsafe fn free(&self, buffer_handles: &[vk::CommandBuffer]) {
 		for handle in buffer_handles {
 			self.free_queue.push(*handle)
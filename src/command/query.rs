@@ -0,0 +1,543 @@
+//! Query pools.
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::{
+	sync::{
+		Arc,
+		atomic::{
+			AtomicU32,
+			Ordering
+		}
+	},
+	ops::Range,
+	fmt
+};
+use crate::{
+	OomError,
+	Device,
+	DeviceOwned,
+	DeviceLost,
+	instance::PhysicalDevice,
+	device::Feature
+};
+
+#[derive(Debug)]
+pub enum CreationError {
+	OutOfMemory(OomError),
+
+	/// A device feature required by the queries is not enabled.
+	MissingFeature(Feature)
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum QueryError {
+	OutOfMemory(OomError),
+	DeviceLost,
+
+	/// The queries are out of the pool.
+	OutOfRange,
+
+	/// The queue family of the command buffer does not support timestamps.
+	Unsupported,
+
+	/// A query result is not available yet.
+	NotReady
+}
+
+impl From<vk::Result> for QueryError {
+	fn from(r: vk::Result) -> QueryError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => QueryError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => QueryError::OutOfMemory(OomError::Device),
+			vk::Result::ERROR_DEVICE_LOST => QueryError::DeviceLost,
+			_ => unreachable!()
+		}
+	}
+}
+
+impl From<DeviceLost> for QueryError {
+	fn from(_: DeviceLost) -> QueryError {
+		QueryError::DeviceLost
+	}
+}
+
+impl std::error::Error for QueryError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for QueryError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::DeviceLost => write!(f, "device lost"),
+			Self::OutOfRange => write!(f, "queries out of the pool"),
+			Self::Unsupported => write!(f, "timestamps are not supported by the queue family"),
+			Self::NotReady => write!(f, "query result not available")
+		}
+	}
+}
+
+/// Query pool.
+pub trait Pool: DeviceOwned {
+	fn handle(&self) -> vk::QueryPool;
+
+	/// Number of queries in the pool.
+	fn count(&self) -> u32;
+}
+
+/// Pool of queries recorded between a `begin_query` and an `end_query` command.
+pub trait ScopedPool: Pool {
+	/// Checks if the queries are begun with the `PRECISE` control flag.
+	fn is_precise(&self) -> bool {
+		false
+	}
+}
+
+pub(crate) fn check_index<P: Pool + ?Sized>(pool: &P, index: u32) -> Result<(), QueryError> {
+	if index < pool.count() {
+		Ok(())
+	} else {
+		Err(QueryError::OutOfRange)
+	}
+}
+
+pub(crate) fn check_range<P: Pool + ?Sized>(pool: &P, range: &Range<u32>) -> Result<(), QueryError> {
+	if range.start > range.end || range.end > pool.count() {
+		Err(QueryError::OutOfRange)
+	} else {
+		Ok(())
+	}
+}
+
+fn create_pool(device: &Device, query_type: vk::QueryType, count: u32, pipeline_statistics: vk::QueryPipelineStatisticFlags) -> Result<vk::QueryPool, CreationError> {
+	let infos = vk::QueryPoolCreateInfo {
+		query_type,
+		query_count: count,
+		pipeline_statistics,
+		..Default::default()
+	};
+
+	unsafe {
+		Ok(device.handle().create_query_pool(&infos, None)?)
+	}
+}
+
+/// Results of the queries of the given range, with their availability.
+///
+/// Each query produces `values` 64-bit integers, given to `f` with the index of the query.
+fn query_results<P: Pool + ?Sized, T, F>(pool: &P, range: Range<u32>, values: usize, flags: vk::QueryResultFlags, f: F) -> Result<Vec<(T, bool)>, QueryError> where F: Fn(u32, &[u64]) -> T {
+	check_range(pool, &range)?;
+	let device = pool.device();
+	device.check_lost()?;
+
+	let count = (range.end - range.start) as usize;
+	if count == 0 {
+		return Ok(Vec::new())
+	}
+
+	// Each result is followed by its availability.
+	let stride = values + 1;
+	let mut data = vec![0u64; count * stride];
+	let result = unsafe {
+		device.handle().fp_v1_0().get_query_pool_results(
+			device.handle().handle(),
+			pool.handle(),
+			range.start,
+			count as u32,
+			data.len() * std::mem::size_of::<u64>(),
+			data.as_mut_ptr() as *mut _,
+			(stride * std::mem::size_of::<u64>()) as u64,
+			flags | vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WITH_AVAILABILITY
+		)
+	};
+
+	// `NOT_READY` is returned when some results are unavailable, without waiting.
+	match result {
+		vk::Result::SUCCESS | vk::Result::NOT_READY => (),
+		e => device.track_lost(Err(e))?
+	}
+
+	Ok(data.chunks(stride).zip(range).map(|(result, index)| {
+		(f(index, &result[..values]), result[values] != 0)
+	}).collect())
+}
+
+fn wait_flags(wait: bool) -> vk::QueryResultFlags {
+	if wait {
+		vk::QueryResultFlags::WAIT
+	} else {
+		vk::QueryResultFlags::empty()
+	}
+}
+
+fn available<T>(results: Vec<(T, bool)>) -> Vec<Option<T>> {
+	results.into_iter().map(|(value, available)| if available { Some(value) } else { None }).collect()
+}
+
+/// Pool of timestamp queries.
+pub struct TimestampPool {
+	device: Arc<Device>,
+	handle: vk::QueryPool,
+
+	/// Valid bits of the timestamp written by each query, set when the timestamp write is recorded.
+	valid_bits: Vec<AtomicU32>
+}
+
+impl TimestampPool {
+	pub fn new(device: &Arc<Device>, count: u32) -> Result<TimestampPool, CreationError> {
+		let handle = create_pool(device, vk::QueryType::TIMESTAMP, count, vk::QueryPipelineStatisticFlags::empty())?;
+
+		Ok(TimestampPool {
+			device: device.clone(),
+			handle,
+			valid_bits: (0..count).map(|_| AtomicU32::new(64)).collect()
+		})
+	}
+
+	/// Record the valid bits of the timestamp written by the given query,
+	/// for a command buffer submitted to the given queue family.
+	pub(crate) fn set_queue_family(&self, index: u32, queue_family_index: Option<u32>) -> Result<(), QueryError> {
+		check_index(self, index)?;
+
+		if let Some(family_index) = queue_family_index {
+			let bits = self.device.physical_device().queue_family(family_index).unwrap().timestamp_valid_bits();
+			if bits == 0 {
+				return Err(QueryError::Unsupported)
+			}
+
+			self.valid_bits[index as usize].store(bits, Ordering::Relaxed)
+		}
+
+		Ok(())
+	}
+
+	/// Timestamps written by the queries of the given range.
+	///
+	/// If `wait` is `true`, blocks until all the results are available.
+	/// Otherwise unavailable results are `None`.
+	/// Only the valid bits of the timestamps are kept.
+	pub fn results(&self, range: Range<u32>, wait: bool) -> Result<Vec<Option<u64>>, QueryError> {
+		let results = query_results(self, range, 1, wait_flags(wait), |index, values| {
+			values[0] & mask(self.valid_bits[index as usize].load(Ordering::Relaxed))
+		})?;
+
+		Ok(available(results))
+	}
+
+	/// Time elapsed between the timestamps of the `begin` and `end` queries, in milliseconds.
+	///
+	/// Does not wait for the results, and returns `QueryError::NotReady` if one of them is not available.
+	pub fn elapsed_ms(&self, begin: u32, end: u32, physical_device: &PhysicalDevice) -> Result<f64, QueryError> {
+		check_index(self, begin)?;
+		check_index(self, end)?;
+
+		let begin_value = self.results(begin..(begin + 1), false)?[0].ok_or(QueryError::NotReady)?;
+		let end_value = self.results(end..(end + 1), false)?[0].ok_or(QueryError::NotReady)?;
+
+		let bits = self.valid_bits[begin as usize].load(Ordering::Relaxed).min(self.valid_bits[end as usize].load(Ordering::Relaxed));
+		if bits == 0 {
+			return Err(QueryError::Unsupported)
+		}
+
+		// The timestamps may wrap around their valid bits.
+		let ticks = end_value.wrapping_sub(begin_value) & mask(bits);
+		Ok(ticks as f64 * physical_device.limits().timestamp_period() as f64 / 1_000_000.0)
+	}
+}
+
+/// Mask of the given number of low bits.
+fn mask(bits: u32) -> u64 {
+	if bits >= 64 {
+		u64::MAX
+	} else {
+		(1 << bits) - 1
+	}
+}
+
+impl Pool for TimestampPool {
+	#[inline]
+	fn handle(&self) -> vk::QueryPool {
+		self.handle
+	}
+
+	#[inline]
+	fn count(&self) -> u32 {
+		self.valid_bits.len() as u32
+	}
+}
+
+impl DeviceOwned for TimestampPool {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for TimestampPool {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_query_pool(self.handle, None)
+		}
+	}
+}
+
+/// Pool of occlusion queries, counting the samples passing the depth and stencil tests.
+pub struct OcclusionPool {
+	device: Arc<Device>,
+	handle: vk::QueryPool,
+	count: u32,
+	precise: bool
+}
+
+impl OcclusionPool {
+	/// Create a pool of `count` occlusion queries.
+	///
+	/// Precise queries return the exact number of samples, and require the `OcclusionQueryPrecise` feature.
+	/// Otherwise the results are only meaningful as zero or non-zero.
+	pub fn new(device: &Arc<Device>, count: u32, precise: bool) -> Result<OcclusionPool, CreationError> {
+		if precise && !device.enabled_features().contains(Feature::OcclusionQueryPrecise) {
+			return Err(CreationError::MissingFeature(Feature::OcclusionQueryPrecise))
+		}
+
+		let handle = create_pool(device, vk::QueryType::OCCLUSION, count, vk::QueryPipelineStatisticFlags::empty())?;
+
+		Ok(OcclusionPool {
+			device: device.clone(),
+			handle,
+			count,
+			precise
+		})
+	}
+
+	/// Sample counts of the queries of the given range.
+	///
+	/// If `wait` is `true`, blocks until all the results are available.
+	/// Otherwise unavailable results are `None`.
+	pub fn results(&self, range: Range<u32>, wait: bool) -> Result<Vec<Option<u64>>, QueryError> {
+		Ok(available(query_results(self, range, 1, wait_flags(wait), |_, values| values[0])?))
+	}
+
+	/// Sample counts of the queries of the given range, with their availability.
+	///
+	/// The counts of unavailable queries are intermediate values.
+	pub fn partial_results(&self, range: Range<u32>) -> Result<Vec<(u64, bool)>, QueryError> {
+		query_results(self, range, 1, vk::QueryResultFlags::PARTIAL, |_, values| values[0])
+	}
+}
+
+impl Pool for OcclusionPool {
+	#[inline]
+	fn handle(&self) -> vk::QueryPool {
+		self.handle
+	}
+
+	#[inline]
+	fn count(&self) -> u32 {
+		self.count
+	}
+}
+
+impl ScopedPool for OcclusionPool {
+	#[inline]
+	fn is_precise(&self) -> bool {
+		self.precise
+	}
+}
+
+impl DeviceOwned for OcclusionPool {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for OcclusionPool {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_query_pool(self.handle, None)
+		}
+	}
+}
+
+macro_rules! pipeline_statistics {
+	($($elem:ident => $val:expr,)+) => (
+		/// Statistics counted by a `PipelineStatisticsPool`.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		#[allow(missing_docs)]
+		pub struct PipelineStatisticFlags {
+			$(
+				pub $elem: bool,
+			)+
+		}
+
+		impl PipelineStatisticFlags {
+			/// Builds a `PipelineStatisticFlags` struct with all the statistics set.
+			pub fn all() -> PipelineStatisticFlags {
+				PipelineStatisticFlags {
+					$(
+						$elem: true,
+					)+
+				}
+			}
+
+			/// Builds a `PipelineStatisticFlags` struct with none of the statistics set.
+			pub fn none() -> PipelineStatisticFlags {
+				PipelineStatisticFlags {
+					$(
+						$elem: false,
+					)+
+				}
+			}
+
+			/// Number of counted statistics.
+			pub fn count(&self) -> usize {
+				let mut count = 0;
+				$(
+					if self.$elem { count += 1 }
+				)+
+				count
+			}
+
+			#[inline]
+			pub(crate) fn into_vulkan(self) -> vk::QueryPipelineStatisticFlags {
+				let mut result = vk::QueryPipelineStatisticFlags::empty();
+				$(
+					if self.$elem { result |= $val }
+				)+
+				result
+			}
+		}
+
+		/// Results of a pipeline statistics query.
+		///
+		/// The statistics that are not counted by the pool are `None`.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+		#[allow(missing_docs)]
+		pub struct PipelineStatistics {
+			$(
+				pub $elem: Option<u64>,
+			)+
+		}
+
+		impl PipelineStatistics {
+			/// Statistics from the values written by a query, in the order of the flags.
+			fn new(flags: PipelineStatisticFlags, values: &[u64]) -> PipelineStatistics {
+				let mut values = values.iter().cloned();
+				PipelineStatistics {
+					$(
+						$elem: if flags.$elem { values.next() } else { None },
+					)+
+				}
+			}
+		}
+	);
+}
+
+// Statistics are written in the order of their bits.
+pipeline_statistics! {
+	input_assembly_vertices => vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES,
+	input_assembly_primitives => vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES,
+	vertex_shader_invocations => vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS,
+	geometry_shader_invocations => vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS,
+	geometry_shader_primitives => vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES,
+	clipping_invocations => vk::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS,
+	clipping_primitives => vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES,
+	fragment_shader_invocations => vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS,
+	tessellation_control_shader_patches => vk::QueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES,
+	tessellation_evaluation_shader_invocations => vk::QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS,
+	compute_shader_invocations => vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS,
+}
+
+/// Pool of pipeline statistics queries.
+pub struct PipelineStatisticsPool {
+	device: Arc<Device>,
+	handle: vk::QueryPool,
+	count: u32,
+	statistics: PipelineStatisticFlags
+}
+
+impl PipelineStatisticsPool {
+	/// Create a pool of `count` queries counting the given statistics.
+	///
+	/// Requires the `PipelineStatisticsQuery` feature.
+	pub fn new(device: &Arc<Device>, count: u32, statistics: PipelineStatisticFlags) -> Result<PipelineStatisticsPool, CreationError> {
+		if !device.enabled_features().contains(Feature::PipelineStatisticsQuery) {
+			return Err(CreationError::MissingFeature(Feature::PipelineStatisticsQuery))
+		}
+
+		let handle = create_pool(device, vk::QueryType::PIPELINE_STATISTICS, count, statistics.into_vulkan())?;
+
+		Ok(PipelineStatisticsPool {
+			device: device.clone(),
+			handle,
+			count,
+			statistics
+		})
+	}
+
+	/// Statistics counted by the queries.
+	#[inline]
+	pub fn statistics(&self) -> PipelineStatisticFlags {
+		self.statistics
+	}
+
+	/// Statistics of the queries of the given range.
+	///
+	/// If `wait` is `true`, blocks until all the results are available.
+	/// Otherwise unavailable results are `None`.
+	pub fn results(&self, range: Range<u32>, wait: bool) -> Result<Vec<Option<PipelineStatistics>>, QueryError> {
+		let statistics = self.statistics;
+		Ok(available(query_results(self, range, statistics.count(), wait_flags(wait), |_, values| PipelineStatistics::new(statistics, values))?))
+	}
+
+	/// Statistics of the queries of the given range, with their availability.
+	///
+	/// The statistics of unavailable queries are intermediate values.
+	pub fn partial_results(&self, range: Range<u32>) -> Result<Vec<(PipelineStatistics, bool)>, QueryError> {
+		let statistics = self.statistics;
+		query_results(self, range, statistics.count(), vk::QueryResultFlags::PARTIAL, |_, values| PipelineStatistics::new(statistics, values))
+	}
+}
+
+impl Pool for PipelineStatisticsPool {
+	#[inline]
+	fn handle(&self) -> vk::QueryPool {
+		self.handle
+	}
+
+	#[inline]
+	fn count(&self) -> u32 {
+		self.count
+	}
+}
+
+impl ScopedPool for PipelineStatisticsPool {}
+
+impl DeviceOwned for PipelineStatisticsPool {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for PipelineStatisticsPool {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_query_pool(self.handle, None)
+		}
+	}
+}
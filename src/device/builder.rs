@@ -0,0 +1,112 @@
+use std::sync::Arc;
+use crate::instance::{
+	PhysicalDevice,
+	physical_device::QueueFamily
+};
+use super::{
+	Device,
+	Queues,
+	CreationError,
+	Extension,
+	Extensions,
+	Feature,
+	Features,
+	ExtendedFeature,
+	ExtendedFeatures
+};
+
+/// Device builder.
+///
+/// ```ignore
+/// let (device, queues) = Device::builder(&physical_device)
+/// 	.enable(Feature::SamplerAnisotropy)
+/// 	.enable(Feature::FillModeNonSolid)
+/// 	.queue(queue_family, &[1.0, 0.5])
+/// 	.extension(device::Extension::KhrSwapchain)
+/// 	.build()?;
+/// ```
+///
+/// Queues are returned in the order they are requested.
+/// Requesting queues of the same family multiple times is allowed.
+pub struct Builder {
+	physical_device: PhysicalDevice,
+	features: Features,
+	extended_features: ExtendedFeatures,
+	extensions: Extensions,
+	queues: Vec<(u32, f32)>
+}
+
+impl Builder {
+	#[inline]
+	pub(crate) fn new(physical_device: &PhysicalDevice) -> Builder {
+		Builder {
+			physical_device: physical_device.clone(),
+			features: Features::none(),
+			extended_features: ExtendedFeatures::none(),
+			extensions: Extensions::none(),
+			queues: Vec::new()
+		}
+	}
+
+	/// Enables the given feature.
+	#[inline]
+	pub fn enable(mut self, feature: Feature) -> Builder {
+		self.features.insert(feature);
+		self
+	}
+
+	/// Enables the given features.
+	#[inline]
+	pub fn features(mut self, features: &Features) -> Builder {
+		self.features = self.features.union(features);
+		self
+	}
+
+	/// Enables the given extended feature.
+	///
+	/// The device extension providing the feature must be enabled,
+	/// unless the device supports the Vulkan version in which it is core,
+	/// otherwise `build` fails with `CreationError::MissingExtendedFeatureExtension`.
+	#[inline]
+	pub fn enable_extended(mut self, feature: ExtendedFeature) -> Builder {
+		match feature {
+			ExtendedFeature::DescriptorIndexing(feature) => self.extended_features.descriptor_indexing.insert(feature),
+			ExtendedFeature::TimelineSemaphore(feature) => self.extended_features.timeline_semaphore.insert(feature),
+			ExtendedFeature::Vulkan12(feature) => self.extended_features.vulkan_1_2.insert(feature),
+			ExtendedFeature::DynamicRendering(feature) => self.extended_features.dynamic_rendering.insert(feature),
+			ExtendedFeature::IndexTypeUint8(feature) => self.extended_features.index_type_uint8.insert(feature),
+			ExtendedFeature::PortabilitySubset(feature) => self.extended_features.portability_subset.insert(feature)
+		}
+		self
+	}
+
+	/// Enables the given device extension.
+	#[inline]
+	pub fn extension(mut self, ext: Extension) -> Builder {
+		self.extensions.insert(ext);
+		self
+	}
+
+	/// Requests one queue of the given family for each given priority.
+	///
+	/// # Panic
+	///
+	/// Panics if the queue family does not belong to the physical device of the builder.
+	#[inline]
+	pub fn queue(mut self, queue_family: QueueFamily, priorities: &[f32]) -> Builder {
+		assert!(*queue_family.physical_device() == self.physical_device, "queue family of another physical device");
+		self.queues.extend(priorities.iter().map(|priority| (queue_family.index(), *priority)));
+		self
+	}
+
+	/// Creates the device and its queues.
+	#[inline]
+	pub fn build(self) -> Result<(Arc<Device>, Queues), CreationError> {
+		let physical_device = &self.physical_device;
+		let queues = self.queues.iter().map(|(index, priority)| {
+			(physical_device.queue_family(*index).unwrap(), *priority)
+		});
+
+		Device::with_extended_features(physical_device, &self.features, &self.extended_features, &self.extensions, queues)
+	}
+}
@@ -0,0 +1,164 @@
+use std::{
+	sync::Arc,
+	time::Duration
+};
+use parking_lot::Mutex;
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use crate::{
+	DeviceLost,
+	DeviceOwned,
+	sync::{
+		fence,
+		future::SignalFence
+	}
+};
+use super::{
+	Device,
+	queue::{
+		Submission,
+		SubmissionSemaphore
+	}
+};
+
+/// Default number of pending entries above which a warning is logged.
+pub const DEFAULT_WARNING_THRESHOLD: usize = 4096;
+
+/// Time a dropped queue waits for its pending entries before leaking them.
+pub const DROP_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Entry {
+	fence: vk::Fence,
+
+	/// Only kept to be dropped.
+	_resource: Box<dyn Send>
+}
+
+/// Queue of resources to drop once the GPU is done with them.
+///
+/// Each resource is kept alive until a fence is signaled,
+/// and dropped by the first call to `collect` following that.
+/// Dropping the queue waits for the pending entries up to `DROP_TIMEOUT`,
+/// and leaks them if their fences are still not signaled.
+pub struct DeletionQueue {
+	device: Arc<Device>,
+	entries: Mutex<Vec<Entry>>,
+	warning_threshold: usize
+}
+
+impl DeletionQueue {
+	pub fn new(device: &Arc<Device>) -> DeletionQueue {
+		Self::with_warning_threshold(device, DEFAULT_WARNING_THRESHOLD)
+	}
+
+	/// Create a queue logging a warning whenever the number of pending entries exceeds the given threshold,
+	/// which usually means that some fence is never signaled.
+	pub fn with_warning_threshold(device: &Arc<Device>, warning_threshold: usize) -> DeletionQueue {
+		DeletionQueue {
+			device: device.clone(),
+			entries: Mutex::new(Vec::new()),
+			warning_threshold
+		}
+	}
+
+	/// Number of pending entries.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.entries.lock().len()
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Take the future, and drop it along with the resource once its fence is signaled.
+	///
+	/// The queue owns the future so that its fence is not reset, recycled or destroyed
+	/// before the resource is collected.
+	pub fn defer_drop<R: 'static + Send, F: 'static + Send + SignalFence>(&self, resource: R, after: F) {
+		let fence = *after.fence();
+		self.push(fence, Box::new((after, resource)))
+	}
+
+	/// Take the submission, and drop it along with its payload once it is done.
+	///
+	/// This releases the submitted command buffer and the resources it retains without blocking.
+	pub fn defer_submission<P: 'static + Send, S: 'static + Send + SubmissionSemaphore>(&self, submission: Submission<P, S>) {
+		let fence = *SignalFence::fence(&submission);
+		self.push(fence, Box::new(submission))
+	}
+
+	fn push(&self, fence: vk::Fence, resource: Box<dyn Send>) {
+		let mut entries = self.entries.lock();
+		entries.push(Entry { fence, _resource: resource });
+
+		if entries.len() == self.warning_threshold + 1 {
+			warn!("{} resources pending deletion, some fence may never be signaled", entries.len())
+		}
+	}
+
+	/// Drop every resource whose fence is signaled.
+	///
+	/// Returns the number of dropped resources.
+	pub fn collect(&self) -> Result<usize, DeviceLost> {
+		self.device.check_lost()?;
+
+		let (done, result) = {
+			let mut entries = self.entries.lock();
+			let mut done = Vec::new();
+			let mut result = Ok(());
+			let mut i = 0;
+			while i < entries.len() {
+				let signaled = unsafe {
+					self.device.track_lost(self.device.handle().get_fence_status(entries[i].fence))
+				};
+
+				match signaled {
+					Ok(true) => done.push(entries.swap_remove(i)),
+					Ok(false) => i += 1,
+					Err(e) => {
+						result = Err(e);
+						break
+					}
+				}
+			}
+
+			(done, result)
+		};
+
+		// Resources are dropped outside of the lock, since they may defer other resources.
+		let count = done.len();
+		drop(done);
+		result?;
+		Ok(count)
+	}
+}
+
+impl DeviceOwned for DeletionQueue {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for DeletionQueue {
+	fn drop(&mut self) {
+		// The resources must outlive their use by the GPU.
+		let entries = std::mem::take(self.entries.get_mut());
+		let fences: Vec<_> = entries.iter().map(|entry| entry.fence).collect();
+		if !fences.is_empty() {
+			let timeout = fence::timeout_nanos(Some(DROP_TIMEOUT));
+			match unsafe { self.device.track_lost(self.device.handle().wait_for_fences(&fences, true, timeout)) } {
+				Ok(()) => (),
+				Err(vk::Result::TIMEOUT) => {
+					// Leaked rather than dropped while the GPU may still use them.
+					error!("{} resources pending deletion leaked: fences not signaled after {:?}", entries.len(), DROP_TIMEOUT);
+					std::mem::forget(entries)
+				},
+				Err(e) => error!("unable to wait for pending deletions: {}", e)
+			}
+		}
+	}
+}
@@ -17,4 +17,19 @@ extensions! {
 	ext_debug_utils: ExtDebugUtils => b"VK_EXT_debug_utils\0",
 	khr_multiview: KhrMultiview => b"VK_KHR_multiview\0",
 	ext_full_screen_exclusive: ExtFullScreenExclusive => b"VK_EXT_full_screen_exclusive\0",
+	ext_descriptor_indexing: ExtDescriptorIndexing => b"VK_EXT_descriptor_indexing\0",
+	khr_timeline_semaphore: KhrTimelineSemaphore => b"VK_KHR_timeline_semaphore\0",
+	ext_memory_budget: ExtMemoryBudget => b"VK_EXT_memory_budget\0",
+	khr_external_memory: KhrExternalMemory => b"VK_KHR_external_memory\0",
+	khr_external_memory_fd: KhrExternalMemoryFd => b"VK_KHR_external_memory_fd\0",
+	khr_external_semaphore: KhrExternalSemaphore => b"VK_KHR_external_semaphore\0",
+	khr_external_semaphore_fd: KhrExternalSemaphoreFd => b"VK_KHR_external_semaphore_fd\0",
+	khr_external_fence: KhrExternalFence => b"VK_KHR_external_fence\0",
+	khr_external_fence_fd: KhrExternalFenceFd => b"VK_KHR_external_fence_fd\0",
+	ext_index_type_uint8: ExtIndexTypeUint8 => b"VK_EXT_index_type_uint8\0",
+	khr_create_renderpass2: KhrCreateRenderpass2 => b"VK_KHR_create_renderpass2\0",
+	khr_depth_stencil_resolve: KhrDepthStencilResolve => b"VK_KHR_depth_stencil_resolve\0",
+	khr_dynamic_rendering: KhrDynamicRendering => b"VK_KHR_dynamic_rendering\0",
+	khr_draw_indirect_count: KhrDrawIndirectCount => b"VK_KHR_draw_indirect_count\0",
+	khr_portability_subset: KhrPortabilitySubset => b"VK_KHR_portability_subset\0",
 }
@@ -1,7 +1,11 @@
 use std::{
 	fmt
 };
-use crate::Unbuildable;
+use crate::{
+	Unbuildable,
+	instance::ApiVersion
+};
+use super::Extension;
 
 features! {
 	ash::vk::PhysicalDeviceFeatures, ash::vk::TRUE,
@@ -61,3 +65,211 @@ features! {
 	variable_multisample_rate: VariableMultisampleRate => variable_multisample_rate: "Variable Multisample Rate",
 	inherited_queries: InheritedQueries => inherited_queries: "Inherited Queries",
 }
+
+/// Descriptor indexing features (`VK_EXT_descriptor_indexing`, core in Vulkan 1.2).
+pub mod descriptor_indexing {
+	use std::fmt;
+	use crate::Unbuildable;
+
+	features! {
+		ash::vk::PhysicalDeviceDescriptorIndexingFeatures, ash::vk::TRUE,
+		shader_input_attachment_array_dynamic_indexing: ShaderInputAttachmentArrayDynamicIndexing => shader_input_attachment_array_dynamic_indexing: "Dynamic Shader Input Attachment Array Indexing",
+		shader_uniform_texel_buffer_array_dynamic_indexing: ShaderUniformTexelBufferArrayDynamicIndexing => shader_uniform_texel_buffer_array_dynamic_indexing: "Dynamic Shader Uniform Texel Buffer Array Indexing",
+		shader_storage_texel_buffer_array_dynamic_indexing: ShaderStorageTexelBufferArrayDynamicIndexing => shader_storage_texel_buffer_array_dynamic_indexing: "Dynamic Shader Storage Texel Buffer Array Indexing",
+		shader_uniform_buffer_array_non_uniform_indexing: ShaderUniformBufferArrayNonUniformIndexing => shader_uniform_buffer_array_non_uniform_indexing: "Non Uniform Shader Uniform Buffer Array Indexing",
+		shader_sampled_image_array_non_uniform_indexing: ShaderSampledImageArrayNonUniformIndexing => shader_sampled_image_array_non_uniform_indexing: "Non Uniform Shader Sampled Image Array Indexing",
+		shader_storage_buffer_array_non_uniform_indexing: ShaderStorageBufferArrayNonUniformIndexing => shader_storage_buffer_array_non_uniform_indexing: "Non Uniform Shader Storage Buffer Array Indexing",
+		shader_storage_image_array_non_uniform_indexing: ShaderStorageImageArrayNonUniformIndexing => shader_storage_image_array_non_uniform_indexing: "Non Uniform Shader Storage Image Array Indexing",
+		shader_input_attachment_array_non_uniform_indexing: ShaderInputAttachmentArrayNonUniformIndexing => shader_input_attachment_array_non_uniform_indexing: "Non Uniform Shader Input Attachment Array Indexing",
+		shader_uniform_texel_buffer_array_non_uniform_indexing: ShaderUniformTexelBufferArrayNonUniformIndexing => shader_uniform_texel_buffer_array_non_uniform_indexing: "Non Uniform Shader Uniform Texel Buffer Array Indexing",
+		shader_storage_texel_buffer_array_non_uniform_indexing: ShaderStorageTexelBufferArrayNonUniformIndexing => shader_storage_texel_buffer_array_non_uniform_indexing: "Non Uniform Shader Storage Texel Buffer Array Indexing",
+		descriptor_binding_uniform_buffer_update_after_bind: DescriptorBindingUniformBufferUpdateAfterBind => descriptor_binding_uniform_buffer_update_after_bind: "Uniform Buffer Update After Bind",
+		descriptor_binding_sampled_image_update_after_bind: DescriptorBindingSampledImageUpdateAfterBind => descriptor_binding_sampled_image_update_after_bind: "Sampled Image Update After Bind",
+		descriptor_binding_storage_image_update_after_bind: DescriptorBindingStorageImageUpdateAfterBind => descriptor_binding_storage_image_update_after_bind: "Storage Image Update After Bind",
+		descriptor_binding_storage_buffer_update_after_bind: DescriptorBindingStorageBufferUpdateAfterBind => descriptor_binding_storage_buffer_update_after_bind: "Storage Buffer Update After Bind",
+		descriptor_binding_uniform_texel_buffer_update_after_bind: DescriptorBindingUniformTexelBufferUpdateAfterBind => descriptor_binding_uniform_texel_buffer_update_after_bind: "Uniform Texel Buffer Update After Bind",
+		descriptor_binding_storage_texel_buffer_update_after_bind: DescriptorBindingStorageTexelBufferUpdateAfterBind => descriptor_binding_storage_texel_buffer_update_after_bind: "Storage Texel Buffer Update After Bind",
+		descriptor_binding_update_unused_while_pending: DescriptorBindingUpdateUnusedWhilePending => descriptor_binding_update_unused_while_pending: "Update Unused Descriptors While Pending",
+		descriptor_binding_partially_bound: DescriptorBindingPartiallyBound => descriptor_binding_partially_bound: "Partially Bound Descriptors",
+		descriptor_binding_variable_descriptor_count: DescriptorBindingVariableDescriptorCount => descriptor_binding_variable_descriptor_count: "Variable Descriptor Count",
+		runtime_descriptor_array: RuntimeDescriptorArray => runtime_descriptor_array: "Runtime Descriptor Array",
+	}
+}
+
+/// Timeline semaphore features (`VK_KHR_timeline_semaphore`, core in Vulkan 1.2).
+pub mod timeline_semaphore {
+	use std::fmt;
+	use crate::Unbuildable;
+
+	features! {
+		ash::vk::PhysicalDeviceTimelineSemaphoreFeatures, ash::vk::TRUE,
+		timeline_semaphore: TimelineSemaphore => timeline_semaphore: "Timeline Semaphore",
+	}
+}
+
+/// Vulkan 1.2 features that are not provided by another block.
+///
+/// Only chained on devices supporting Vulkan 1.2.
+pub mod vulkan_1_2 {
+	use std::fmt;
+	use crate::Unbuildable;
+
+	features! {
+		ash::vk::PhysicalDeviceVulkan12Features, ash::vk::TRUE,
+		draw_indirect_count: DrawIndirectCount => draw_indirect_count: "Draw Indirect Count",
+	}
+}
+
+/// Dynamic rendering features (`VK_KHR_dynamic_rendering`, core in Vulkan 1.3).
+pub mod dynamic_rendering {
+	use std::fmt;
+	use crate::Unbuildable;
+
+	features! {
+		crate::ffi::PhysicalDeviceDynamicRenderingFeatures, ash::vk::TRUE,
+		dynamic_rendering: DynamicRendering => dynamic_rendering: "Dynamic Rendering",
+	}
+}
+
+/// 8-bit index type features (`VK_EXT_index_type_uint8`).
+pub mod index_type_uint8 {
+	use std::fmt;
+	use crate::Unbuildable;
+
+	features! {
+		ash::vk::PhysicalDeviceIndexTypeUint8FeaturesEXT, ash::vk::TRUE,
+		index_type_uint8: IndexTypeUint8 => index_type_uint8: "8-bit Index Type",
+	}
+}
+
+/// Portability subset features (`VK_KHR_portability_subset`).
+///
+/// Implementations providing this extension, layered over other APIs,
+/// only support the listed Vulkan functionalities when the corresponding feature is enabled.
+pub mod portability_subset {
+	use std::fmt;
+	use crate::Unbuildable;
+
+	features! {
+		crate::ffi::PhysicalDevicePortabilitySubsetFeatures, ash::vk::TRUE,
+		constant_alpha_color_blend_factors: ConstantAlphaColorBlendFactors => constant_alpha_color_blend_factors: "Constant Alpha Color Blend Factors",
+		events: Events => events: "Events",
+		image_view_format_reinterpretation: ImageViewFormatReinterpretation => image_view_format_reinterpretation: "Image View Format Reinterpretation",
+		image_view_format_swizzle: ImageViewFormatSwizzle => image_view_format_swizzle: "Image View Format Swizzle",
+		image_view_2d_on_3d_image: ImageView2DOn3DImage => image_view2_d_on3_d_image: "2D Image View on 3D Image",
+		multisample_array_image: MultisampleArrayImage => multisample_array_image: "Multisample Array Image",
+		mutable_comparison_samplers: MutableComparisonSamplers => mutable_comparison_samplers: "Mutable Comparison Samplers",
+		point_polygons: PointPolygons => point_polygons: "Point Polygons",
+		sampler_mip_lod_bias: SamplerMipLodBias => sampler_mip_lod_bias: "Sampler Mip LOD Bias",
+		separate_stencil_mask_ref: SeparateStencilMaskRef => separate_stencil_mask_ref: "Separate Stencil Mask Reference",
+		shader_sample_rate_interpolation_functions: ShaderSampleRateInterpolationFunctions => shader_sample_rate_interpolation_functions: "Shader Sample Rate Interpolation Functions",
+		tessellation_isolines: TessellationIsolines => tessellation_isolines: "Tessellation Isolines",
+		tessellation_point_mode: TessellationPointMode => tessellation_point_mode: "Tessellation Point Mode",
+		triangle_fans: TriangleFans => triangle_fans: "Triangle Fans",
+		vertex_attribute_access_beyond_stride: VertexAttributeAccessBeyondStride => vertex_attribute_access_beyond_stride: "Vertex Attribute Access Beyond Stride",
+	}
+}
+
+/// Feature of an extended feature block.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExtendedFeature {
+	DescriptorIndexing(descriptor_indexing::Feature),
+	TimelineSemaphore(timeline_semaphore::Feature),
+	Vulkan12(vulkan_1_2::Feature),
+	DynamicRendering(dynamic_rendering::Feature),
+	IndexTypeUint8(index_type_uint8::Feature),
+	PortabilitySubset(portability_subset::Feature)
+}
+
+impl ExtendedFeature {
+	/// Device extension providing the feature,
+	/// and the Vulkan version from which it is provided without the extension, if any.
+	pub fn provided_by(&self) -> (Extension, Option<ApiVersion>) {
+		match self {
+			ExtendedFeature::DescriptorIndexing(_) => (Extension::ExtDescriptorIndexing, Some(ApiVersion::V1_2)),
+			ExtendedFeature::TimelineSemaphore(_) => (Extension::KhrTimelineSemaphore, Some(ApiVersion::V1_2)),
+			// The extension provides the same commands, but not the feature itself.
+			ExtendedFeature::Vulkan12(vulkan_1_2::Feature::DrawIndirectCount) => (Extension::KhrDrawIndirectCount, Some(ApiVersion::V1_2)),
+			ExtendedFeature::DynamicRendering(_) => (Extension::KhrDynamicRendering, Some(ApiVersion::V1_3)),
+			ExtendedFeature::IndexTypeUint8(_) => (Extension::ExtIndexTypeUint8, None),
+			ExtendedFeature::PortabilitySubset(_) => (Extension::KhrPortabilitySubset, None)
+		}
+	}
+}
+
+impl fmt::Display for ExtendedFeature {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ExtendedFeature::DescriptorIndexing(feature) => feature.fmt(f),
+			ExtendedFeature::TimelineSemaphore(feature) => feature.fmt(f),
+			ExtendedFeature::Vulkan12(feature) => feature.fmt(f),
+			ExtendedFeature::DynamicRendering(feature) => feature.fmt(f),
+			ExtendedFeature::IndexTypeUint8(feature) => feature.fmt(f),
+			ExtendedFeature::PortabilitySubset(feature) => feature.fmt(f)
+		}
+	}
+}
+
+/// Features that are not part of the Vulkan 1.0 core features,
+/// queried and enabled through `VkPhysicalDeviceFeatures2`.
+///
+/// The device extension providing each block must be enabled,
+/// unless the device supports the Vulkan version in which it is core.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Extended {
+	pub descriptor_indexing: descriptor_indexing::Features,
+	pub timeline_semaphore: timeline_semaphore::Features,
+	pub vulkan_1_2: vulkan_1_2::Features,
+	pub dynamic_rendering: dynamic_rendering::Features,
+	pub index_type_uint8: index_type_uint8::Features,
+	pub portability_subset: portability_subset::Features
+}
+
+impl Extended {
+	/// Returns a set of extended features with every feature set to `false`.
+	#[inline]
+	pub fn none() -> Extended {
+		Extended {
+			descriptor_indexing: descriptor_indexing::Features::none(),
+			timeline_semaphore: timeline_semaphore::Features::none(),
+			vulkan_1_2: vulkan_1_2::Features::none(),
+			dynamic_rendering: dynamic_rendering::Features::none(),
+			index_type_uint8: index_type_uint8::Features::none(),
+			portability_subset: portability_subset::Features::none()
+		}
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.iter().next().is_none()
+	}
+
+	#[inline]
+	pub fn contains(&self, feature: ExtendedFeature) -> bool {
+		match feature {
+			ExtendedFeature::DescriptorIndexing(feature) => self.descriptor_indexing.contains(feature),
+			ExtendedFeature::TimelineSemaphore(feature) => self.timeline_semaphore.contains(feature),
+			ExtendedFeature::Vulkan12(feature) => self.vulkan_1_2.contains(feature),
+			ExtendedFeature::DynamicRendering(feature) => self.dynamic_rendering.contains(feature),
+			ExtendedFeature::IndexTypeUint8(feature) => self.index_type_uint8.contains(feature),
+			ExtendedFeature::PortabilitySubset(feature) => self.portability_subset.contains(feature)
+		}
+	}
+
+	/// Iterates over the features of the set.
+	pub fn iter(&self) -> impl '_ + Iterator<Item=ExtendedFeature> {
+		(&self.descriptor_indexing).into_iter().map(ExtendedFeature::DescriptorIndexing)
+			.chain((&self.timeline_semaphore).into_iter().map(ExtendedFeature::TimelineSemaphore))
+			.chain((&self.vulkan_1_2).into_iter().map(ExtendedFeature::Vulkan12))
+			.chain((&self.dynamic_rendering).into_iter().map(ExtendedFeature::DynamicRendering))
+			.chain((&self.index_type_uint8).into_iter().map(ExtendedFeature::IndexTypeUint8))
+			.chain((&self.portability_subset).into_iter().map(ExtendedFeature::PortabilitySubset))
+	}
+}
+
+impl Default for Extended {
+	#[inline]
+	fn default() -> Extended {
+		Extended::none()
+	}
+}
@@ -7,9 +7,20 @@ use ash::{
 	vk,
 	version::DeviceV1_0
 };
+#[cfg(unix)]
+use std::os::unix::io::{
+	OwnedFd,
+	AsRawFd,
+	IntoRawFd,
+	FromRawFd
+};
 use crate::{
 	OomError,
 	instance::physical_device::MemoryType,
+	mem::{
+		ExternalMemoryHandleType,
+		ExternalMemoryError
+	},
 	Device,
 	DeviceOwned
 };
@@ -17,6 +28,13 @@ use crate::{
 #[derive(Debug)]
 pub enum MapError {
 	NotHostVisible,
+
+	/// The memory is already mapped.
+	AlreadyMapped,
+
+	/// The mapped range is empty or exceeds the memory size.
+	OutOfBounds,
+
 	OutOfMemory(OomError),
 	MemoryMapFailed
 }
@@ -25,6 +43,8 @@ impl fmt::Display for MapError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			Self::NotHostVisible => write!(f, "memory is not visible to host"),
+			Self::AlreadyMapped => write!(f, "memory is already mapped"),
+			Self::OutOfBounds => write!(f, "mapped range is out of bounds"),
 			Self::OutOfMemory(e) => e.fmt(f),
 			Self::MemoryMapFailed => write!(f, "memory map failed")
 		}
@@ -63,17 +83,90 @@ pub struct Memory {
 	memory_type_index: u32,
 	
 	/// Size (in bytes) of the memory region.
-	size: u64
+	size: u64,
+
+	/// Handle types with which the memory can be exported.
+	export_handle_types: vk::ExternalMemoryHandleTypeFlags
 }
 
 impl Memory {
 	#[inline]
 	pub(crate) fn new(device: &Arc<Device>, memory_type: MemoryType, size: u64, handle: vk::DeviceMemory) -> Memory {
+		device.track_heap_usage(memory_type.heap().index(), size, true);
+
 		Memory {
 			handle,
 			device: device.clone(),
 			memory_type_index: memory_type.index(),
-			size
+			size,
+			export_handle_types: vk::ExternalMemoryHandleTypeFlags::empty()
+		}
+	}
+
+	/// Marks the memory as exportable with the given handle type.
+	#[inline]
+	pub(crate) fn exportable(mut self, handle_type: ExternalMemoryHandleType) -> Memory {
+		self.export_handle_types |= handle_type.into_vulkan();
+		self
+	}
+
+	/// Import memory from a file descriptor exported by a compatible Vulkan implementation.
+	///
+	/// The `VK_KHR_external_memory_fd` device extension must be enabled.
+	/// On success the Vulkan implementation takes ownership of the file descriptor.
+	#[cfg(unix)]
+	pub fn import_fd(device: &Arc<Device>, fd: OwnedFd, size: u64, memory_type: MemoryType) -> Result<Memory, ExternalMemoryError> {
+		device.ext_khr_external_memory_fd()?;
+
+		let import_infos = vk::ImportMemoryFdInfoKHR {
+			handle_type: ExternalMemoryHandleType::OpaqueFd.into_vulkan(),
+			fd: fd.as_raw_fd(),
+			..Default::default()
+		};
+
+		let infos = vk::MemoryAllocateInfo {
+			p_next: &import_infos as *const vk::ImportMemoryFdInfoKHR as *const c_void,
+			allocation_size: size,
+			memory_type_index: memory_type.index(),
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle.allocate_memory(&infos, None)?
+		};
+
+		// The implementation now owns the file descriptor.
+		let _ = fd.into_raw_fd();
+
+		Ok(Memory::new(device, memory_type, size, handle))
+	}
+
+	/// Export the memory as a file descriptor.
+	///
+	/// The memory must have been allocated with `Device::allocate_exportable_memory`
+	/// using the `ExternalMemoryHandleType::OpaqueFd` handle type.
+	/// Each call returns a new file descriptor referencing the memory.
+	#[cfg(unix)]
+	pub fn export_fd(&self) -> Result<OwnedFd, ExternalMemoryError> {
+		let ext = self.device.ext_khr_external_memory_fd()?;
+		let handle_type = ExternalMemoryHandleType::OpaqueFd.into_vulkan();
+
+		if !self.export_handle_types.contains(handle_type) {
+			return Err(ExternalMemoryError::NotExportable)
+		}
+
+		let infos = vk::MemoryGetFdInfoKHR {
+			memory: self.handle,
+			handle_type,
+			..Default::default()
+		};
+
+		let mut fd = -1;
+		unsafe {
+			match ext.get_memory_fd_khr(self.device.handle.handle(), &infos, &mut fd) {
+				vk::Result::SUCCESS => Ok(OwnedFd::from_raw_fd(fd)),
+				r => Err(r.into())
+			}
 		}
 	}
 
@@ -126,6 +219,8 @@ impl Drop for Memory {
 		unsafe {
 			self.device.handle.free_memory(self.handle, None)
 		}
+
+		self.device.track_heap_usage(self.memory_type().heap().index(), self.size, false)
 	}
 }
 
@@ -146,11 +241,10 @@ impl MappedMemory {
 		&self.memory
 	}
 
-	fn into_raw_parts(&self) -> (Memory, *mut c_void) {
-		let memory = unsafe { std::ptr::read(&self.memory) };
-		let ptr = self.ptr;
-		std::mem::forget(self);
-		(memory, ptr)
+	fn into_raw_parts(self) -> (Memory, *mut c_void) {
+		let this = std::mem::ManuallyDrop::new(self);
+		let memory = unsafe { std::ptr::read(&this.memory) };
+		(memory, this.ptr)
 	}
 
 	/// Unmap the memory from host address space.
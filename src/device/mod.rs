@@ -3,11 +3,20 @@ use ash::{
 	vk,
 	version::{
 		InstanceV1_0,
-		DeviceV1_0
+		DeviceV1_0,
+		DeviceV1_1
 	}
 };
 use std::{
-	sync::Arc,
+	sync::{
+		Arc,
+		atomic::{
+			AtomicBool,
+			AtomicU64,
+			Ordering
+		}
+	},
+	ffi::c_void,
 	fmt,
 	hash::{
 		Hash,
@@ -16,8 +25,15 @@ use std::{
 };
 use crate::{
 	OomError,
+	DeviceLost,
 	Instance,
+	mem,
+	sync::{
+		self,
+		fence::WaitError
+	},
 	instance::{
+		ApiVersion,
 		PhysicalDevice,
 		physical_device::{
 			QueueFamily,
@@ -30,6 +46,8 @@ pub mod extension;
 pub mod feature;
 pub mod queue;
 pub mod memory;
+pub mod deletion;
+mod builder;
 
 pub use extension::{
 	Extension,
@@ -37,10 +55,20 @@ pub use extension::{
 };
 pub use feature::{
 	Feature,
-	Features
+	Features,
+	ExtendedFeature,
+	Extended as ExtendedFeatures
 };
 use feature::IntoFFiFeatures;
+use feature::descriptor_indexing::IntoFFiFeatures as _;
+use feature::timeline_semaphore::IntoFFiFeatures as _;
+use feature::vulkan_1_2::IntoFFiFeatures as _;
+use feature::dynamic_rendering::IntoFFiFeatures as _;
+use feature::index_type_uint8::IntoFFiFeatures as _;
+use feature::portability_subset::IntoFFiFeatures as _;
 pub use queue::Queue;
+pub use deletion::DeletionQueue;
+pub use builder::Builder;
 pub use memory::{
 	Memory,
 	MappedMemory
@@ -53,6 +81,11 @@ pub enum CreationError {
 	InitializationFailed,
 	MissingExtension(Extension),
 	MissingFeature(Feature),
+	MissingExtendedFeature(ExtendedFeature),
+
+	/// The device extension providing the extended feature is not required,
+	/// and the device does not support a Vulkan version providing it.
+	MissingExtendedFeatureExtension(ExtendedFeature, Extension),
 	TooManyObjets,
 	TooManyQueuesForFamily(u32, u32),
 	DeviceLost
@@ -67,10 +100,13 @@ impl fmt::Display for CreationError {
 		match self {
 			OutOfMemory(OomError::Host) => write!(f, "out of host memory"),
 			OutOfMemory(OomError::Device) => write!(f, "out of device memory"),
+			OutOfMemory(OomError::Unexpected(r)) => write!(f, "unexpected error: {}", r),
 			InvalidQueuePriority(p) => write!(f, "invalid queue priority `{}`", p),
 			InitializationFailed => write!(f, "device initialization failed"),
 			MissingExtension(e) => write!(f, "missing device extension `{}`", e),
 			MissingFeature(t) => write!(f, "missing device feature `{}`", t),
+			MissingExtendedFeature(t) => write!(f, "missing extended device feature `{}`", t),
+			MissingExtendedFeatureExtension(t, e) => write!(f, "extended device feature `{}` requires the device extension `{}`", t, e),
 			TooManyObjets => write!(f, "too many objets"),
 			TooManyQueuesForFamily(index, max) => write!(f, "too many queues (>= {}) for the same queue family ({})", max, index),
 			DeviceLost => write!(f, "device lost")
@@ -147,13 +183,50 @@ impl fmt::Display for MissingExtensionError {
 pub struct Device {
 	pub(crate) handle: ash::Device,
 	instance: Arc<Instance>,
-	physical_device_index: u32,
+	physical_device: PhysicalDevice,
 	loaded_extensions: Extensions,
-	ext_khr_swapchain: OnceCell<ash::extensions::khr::Swapchain>
+	enabled_features: Features,
+	enabled_extended_features: ExtendedFeatures,
+
+	/// Memory allocated through this device in each heap, in bytes.
+	heap_usage: Vec<AtomicU64>,
+
+	/// Set once the device is lost.
+	lost: AtomicBool,
+
+	/// Recycled synchronization primitives.
+	fence_pool: sync::FencePool,
+	semaphore_pool: sync::SemaphorePool,
+
+	ext_khr_swapchain: OnceCell<ash::extensions::khr::Swapchain>,
+	ext_khr_get_memory_requirements2: OnceCell<vk::KhrGetMemoryRequirements2Fn>,
+	ext_khr_external_memory_fd: OnceCell<vk::KhrExternalMemoryFdFn>,
+	ext_khr_external_semaphore_fd: OnceCell<vk::KhrExternalSemaphoreFdFn>,
+	ext_khr_external_fence_fd: OnceCell<vk::KhrExternalFenceFdFn>,
+	ext_khr_maintenance1: OnceCell<vk::KhrMaintenance1Fn>,
+	ext_khr_draw_indirect_count: OnceCell<vk::KhrDrawIndirectCountFn>,
+	ext_ext_full_screen_exclusive: OnceCell<vk::ExtFullScreenExclusiveFn>,
+	dynamic_rendering: OnceCell<Option<crate::ffi::DynamicRenderingFn>>,
+	timeline_semaphore: OnceCell<Option<crate::ffi::TimelineSemaphoreFn>>,
+	draw_indirect_count: OnceCell<Option<crate::ffi::DrawIndirectCountFn>>
 }
 
 impl Device {
-	pub fn new<'a, E: IntoIterator<Item=Extension>, Q: IntoIterator<Item=(QueueFamily<'a>, f32)>>(physical_device: PhysicalDevice, features: &Features, required_extensions: E, requested_queues: Q) -> Result<(Arc<Device>, Queues), CreationError> {
+	/// Creates a device builder for the given physical device.
+	#[inline]
+	pub fn builder(physical_device: &PhysicalDevice) -> Builder {
+		Builder::new(physical_device)
+	}
+
+	pub fn new<'a, E: IntoIterator<Item=Extension>, Q: IntoIterator<Item=(QueueFamily<'a>, f32)>>(physical_device: &PhysicalDevice, features: &Features, required_extensions: E, requested_queues: Q) -> Result<(Arc<Device>, Queues), CreationError> {
+		Self::with_extended_features(physical_device, features, &ExtendedFeatures::none(), required_extensions, requested_queues)
+	}
+
+	/// Create a new device enabling features that are not part of the Vulkan 1.0 core features.
+	///
+	/// The device extensions providing the requested extended features must be required,
+	/// unless the device supports Vulkan 1.2.
+	pub fn with_extended_features<'a, E: IntoIterator<Item=Extension>, Q: IntoIterator<Item=(QueueFamily<'a>, f32)>>(physical_device: &PhysicalDevice, features: &Features, extended_features: &ExtendedFeatures, required_extensions: E, requested_queues: Q) -> Result<(Arc<Device>, Queues), CreationError> {
 		let instance = physical_device.instance();
 
 		let mut requested_queues_by_family = Vec::new();
@@ -173,8 +246,8 @@ impl Device {
 			queues_index_iter.push((queue_family.index(), family_requested_queues.len() as u32));
 			family_requested_queues.push(priority);
 
-			if family_requested_queues.len() > queue_family.queue_count() as usize {
-				return Err(CreationError::TooManyQueuesForFamily(queue_family.index(), queue_family.queue_count()))
+			if family_requested_queues.len() > queue_family.count() as usize {
+				return Err(CreationError::TooManyQueuesForFamily(queue_family.index(), queue_family.count()))
 			}
 		}
 
@@ -191,24 +264,100 @@ impl Device {
 			}
 		}).collect();
 
+		let supported_extensions = physical_device.supported_extensions().map_err(CreationError::OutOfMemory)?;
+
 		let mut loaded_extensions = Extensions::none();
 		let mut extension_names = Vec::new();
 		for ext in required_extensions {
+			if !supported_extensions.contains(ext) {
+				return Err(CreationError::MissingExtension(ext))
+			}
+
+			if loaded_extensions.contains(ext) {
+				continue
+			}
+
 			loaded_extensions.insert(ext);
 			extension_names.push(ext.c_name().as_ptr())
 		}
 
+		if let Some(missing) = (&features.difference(physical_device.supported_features())).into_iter().next() {
+			return Err(CreationError::MissingFeature(missing))
+		}
+
+		if let Some(missing) = extended_features.iter().find(|feature| {
+			!physical_device.supported_extended_features().map(|supported| supported.contains(*feature)).unwrap_or(false)
+		}) {
+			return Err(CreationError::MissingExtendedFeature(missing))
+		}
+
+		let api_version = std::cmp::min(instance.api_version(), physical_device.api_version());
+		for feature in extended_features.iter() {
+			let (ext, core_version) = feature.provided_by();
+			if !loaded_extensions.contains(ext) && core_version.map(|version| api_version < version).unwrap_or(true) {
+				return Err(CreationError::MissingExtendedFeatureExtension(feature, ext))
+			}
+		}
+
 		let ffi_features = features.into_ffi();
+		let mut ffi_descriptor_indexing = (&extended_features.descriptor_indexing).into_ffi();
+		let mut ffi_timeline_semaphore = (&extended_features.timeline_semaphore).into_ffi();
+		let mut ffi_vulkan_1_2 = (&extended_features.vulkan_1_2).into_ffi();
+		let mut ffi_dynamic_rendering = (&extended_features.dynamic_rendering).into_ffi();
+		let mut ffi_index_type_uint8 = (&extended_features.index_type_uint8).into_ffi();
+		let mut ffi_portability_subset = (&extended_features.portability_subset).into_ffi();
+		let mut ffi_features2 = vk::PhysicalDeviceFeatures2 {
+			features: ffi_features,
+			..Default::default()
+		};
+
+		// Only the non-empty blocks are chained, since the others may belong to unsupported extensions.
+		// The Vulkan 1.2 features cannot be chained with the blocks promoted to Vulkan 1.2,
+		// which are then enabled through the Vulkan 1.2 features.
+		let mut p_next: *mut c_void = std::ptr::null_mut();
+		if !extended_features.vulkan_1_2.is_empty() {
+			merge_vulkan_1_2_features(&mut ffi_vulkan_1_2, &ffi_descriptor_indexing, &ffi_timeline_semaphore);
+			ffi_vulkan_1_2.p_next = p_next;
+			p_next = &mut ffi_vulkan_1_2 as *mut _ as *mut c_void;
+		} else {
+			if !extended_features.descriptor_indexing.is_empty() {
+				ffi_descriptor_indexing.p_next = p_next;
+				p_next = &mut ffi_descriptor_indexing as *mut _ as *mut c_void;
+			}
+			if !extended_features.timeline_semaphore.is_empty() {
+				ffi_timeline_semaphore.p_next = p_next;
+				p_next = &mut ffi_timeline_semaphore as *mut _ as *mut c_void;
+			}
+		}
+		if !extended_features.dynamic_rendering.is_empty() {
+			ffi_dynamic_rendering.p_next = p_next;
+			p_next = &mut ffi_dynamic_rendering as *mut _ as *mut c_void;
+		}
+		if !extended_features.index_type_uint8.is_empty() {
+			ffi_index_type_uint8.p_next = p_next;
+			p_next = &mut ffi_index_type_uint8 as *mut _ as *mut c_void;
+		}
+		if !extended_features.portability_subset.is_empty() {
+			ffi_portability_subset.p_next = p_next;
+			p_next = &mut ffi_portability_subset as *mut _ as *mut c_void;
+		}
+		ffi_features2.p_next = p_next;
 
-		let infos = vk::DeviceCreateInfo {
+		let mut infos = vk::DeviceCreateInfo {
 			queue_create_info_count: queue_create_infos.len() as u32,
 			p_queue_create_infos: queue_create_infos.as_ptr(),
 			enabled_extension_count: extension_names.len() as u32,
 			pp_enabled_extension_names: extension_names.as_ptr(),
-			p_enabled_features: &ffi_features as *const vk::PhysicalDeviceFeatures,
 			..Default::default()
 		};
 
+		if extended_features.is_empty() {
+			infos.p_enabled_features = &ffi_features as *const vk::PhysicalDeviceFeatures;
+		} else {
+			infos.p_enabled_features = std::ptr::null();
+			infos.p_next = &ffi_features2 as *const vk::PhysicalDeviceFeatures2 as *const c_void;
+		}
+
 		let handle = unsafe {
 			instance.handle.create_device(physical_device.handle(), &infos, None)?
 		};
@@ -216,9 +365,25 @@ impl Device {
 		let device = Arc::new(Device {
 			handle,
 			instance: instance.clone(),
-			physical_device_index: physical_device.index(),
+			physical_device: physical_device.clone(),
 			loaded_extensions,
-			ext_khr_swapchain: OnceCell::new()
+			enabled_features: features.clone(),
+			enabled_extended_features: extended_features.clone(),
+			heap_usage: physical_device.memory_heaps().map(|_| AtomicU64::new(0)).collect(),
+			lost: AtomicBool::new(false),
+			fence_pool: sync::FencePool::new(),
+			semaphore_pool: sync::SemaphorePool::new(),
+			ext_khr_swapchain: OnceCell::new(),
+			ext_khr_get_memory_requirements2: OnceCell::new(),
+			ext_khr_external_memory_fd: OnceCell::new(),
+			ext_khr_external_semaphore_fd: OnceCell::new(),
+			ext_khr_external_fence_fd: OnceCell::new(),
+			ext_khr_maintenance1: OnceCell::new(),
+			ext_khr_draw_indirect_count: OnceCell::new(),
+			ext_ext_full_screen_exclusive: OnceCell::new(),
+			dynamic_rendering: OnceCell::new(),
+			timeline_semaphore: OnceCell::new(),
+			draw_indirect_count: OnceCell::new()
 		});
 
 		let queues = Queues {
@@ -233,19 +398,89 @@ impl Device {
 		&self.handle
 	}
 
+	/// Fences recycled by `sync::fence::Pooled`.
+	#[inline]
+	pub fn fence_pool(&self) -> &sync::FencePool {
+		&self.fence_pool
+	}
+
+	/// Semaphores recycled by `sync::semaphore::Pooled`.
+	#[inline]
+	pub fn semaphore_pool(&self) -> &sync::SemaphorePool {
+		&self.semaphore_pool
+	}
+
+	/// Extensions enabled on this device.
+	#[inline]
+	pub fn loaded_extensions(&self) -> &Extensions {
+		&self.loaded_extensions
+	}
+
+	/// Core features enabled on this device.
+	#[inline]
+	pub fn enabled_features(&self) -> &Features {
+		&self.enabled_features
+	}
+
+	/// Extended features enabled on this device.
+	#[inline]
+	pub fn enabled_extended_features(&self) -> &ExtendedFeatures {
+		&self.enabled_extended_features
+	}
+
+	#[inline]
+	pub fn physical_device(&self) -> &PhysicalDevice {
+		&self.physical_device
+	}
+
+	/// Vulkan API version usable with this device.
+	///
+	/// This is the lowest version between the instance and the physical device versions.
 	#[inline]
-	pub fn physical_device(&self) -> PhysicalDevice {
-		PhysicalDevice::new(&self.instance, self.physical_device_index)
+	pub fn api_version(&self) -> ApiVersion {
+		std::cmp::min(self.instance.api_version(), self.physical_device.api_version())
+	}
+
+	/// Checks if the device supports dedicated allocations.
+	///
+	/// Dedicated allocations are core in Vulkan 1.1,
+	/// and otherwise provided by the `VK_KHR_get_memory_requirements2` and
+	/// `VK_KHR_dedicated_allocation` extensions.
+	#[inline]
+	pub fn supports_dedicated_allocation(&self) -> bool {
+		self.api_version() >= ApiVersion::V1_1 || (self.loaded_extensions.khr_get_memory_requirements2 && self.loaded_extensions.khr_dedicated_allocation)
 	}
 
 	/// Allocate some device memory.
 	pub fn allocate_memory(self: &Arc<Self>, memory_type: MemoryType, size: u64) -> Result<Memory, AllocationError> {
-		let infos = vk::MemoryAllocateInfo {
+		self.allocate_memory_for(memory_type, size, None)
+	}
+
+	/// Allocate some device memory, dedicated to the given resource if any.
+	pub(crate) fn allocate_memory_for(self: &Arc<Self>, memory_type: MemoryType, size: u64, dedicated: Option<mem::DedicatedResource>) -> Result<Memory, AllocationError> {
+		let mut infos = vk::MemoryAllocateInfo {
 			allocation_size: size,
 			memory_type_index: memory_type.index(),
 			..Default::default()
 		};
 
+		let dedicated_infos = dedicated.map(|resource| {
+			match resource {
+				mem::DedicatedResource::Buffer(buffer) => vk::MemoryDedicatedAllocateInfo {
+					buffer,
+					..Default::default()
+				},
+				mem::DedicatedResource::Image(image) => vk::MemoryDedicatedAllocateInfo {
+					image,
+					..Default::default()
+				}
+			}
+		});
+
+		if let Some(dedicated_infos) = &dedicated_infos {
+			infos.p_next = dedicated_infos as *const vk::MemoryDedicatedAllocateInfo as *const c_void;
+		}
+
 		let handle = unsafe {
 			self.handle.allocate_memory(&infos, None)?
 		};
@@ -253,6 +488,306 @@ impl Device {
 		Ok(Memory::new(self, memory_type, size, handle))
 	}
 
+	/// Allocate some device memory that can be exported with the given handle type.
+	///
+	/// The device extension providing the handle type must be enabled.
+	pub fn allocate_exportable_memory(self: &Arc<Self>, memory_type: MemoryType, size: u64, handle_type: mem::ExternalMemoryHandleType) -> Result<Memory, mem::ExternalMemoryError> {
+		let ext = handle_type.required_extension();
+		if !self.loaded_extensions.contains(ext) {
+			return Err(mem::ExternalMemoryError::MissingExtension(ext))
+		}
+
+		let export_infos = vk::ExportMemoryAllocateInfo {
+			handle_types: handle_type.into_vulkan(),
+			..Default::default()
+		};
+
+		let infos = vk::MemoryAllocateInfo {
+			p_next: &export_infos as *const vk::ExportMemoryAllocateInfo as *const c_void,
+			allocation_size: size,
+			memory_type_index: memory_type.index(),
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			self.handle.allocate_memory(&infos, None).map_err(AllocationError::from)?
+		};
+
+		Ok(Memory::new(self, memory_type, size, handle).exportable(handle_type))
+	}
+
+	/// Checks if the device has been lost.
+	///
+	/// Once a `VK_ERROR_DEVICE_LOST` error has been reported by any operation on the device,
+	/// the subsequent submissions and waits fail immediately.
+	#[inline]
+	pub fn is_lost(&self) -> bool {
+		self.lost.load(Ordering::Acquire)
+	}
+
+	/// Fails if the device has been lost.
+	#[inline]
+	pub(crate) fn check_lost(&self) -> Result<(), DeviceLost> {
+		if self.is_lost() {
+			Err(DeviceLost::Lost)
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Marks the device as lost if the given result is `VK_ERROR_DEVICE_LOST`.
+	#[inline]
+	pub(crate) fn track_lost<T>(&self, result: Result<T, vk::Result>) -> Result<T, vk::Result> {
+		if let Err(vk::Result::ERROR_DEVICE_LOST) = result {
+			if !self.lost.swap(true, Ordering::AcqRel) {
+				error!("device lost")
+			}
+		}
+
+		result
+	}
+
+	/// Blocks until every queue of the device is idle.
+	pub fn wait_idle(&self) -> Result<(), WaitError> {
+		self.check_lost()?;
+
+		unsafe {
+			self.track_lost(self.handle.device_wait_idle())?
+		}
+
+		Ok(())
+	}
+
+	/// Records an allocation or a deallocation in the given heap.
+	#[inline]
+	pub(crate) fn track_heap_usage(&self, heap_index: u32, size: u64, allocated: bool) {
+		let usage = &self.heap_usage[heap_index as usize];
+		if allocated {
+			usage.fetch_add(size, Ordering::Relaxed);
+		} else {
+			usage.fetch_sub(size, Ordering::Relaxed);
+		}
+	}
+
+	/// Retrieves the current memory budget and usage of each heap.
+	///
+	/// The driver is queried when the `VK_EXT_memory_budget` extension is enabled.
+	/// Otherwise, the budget is estimated from the heap sizes and the allocations
+	/// made through this device.
+	pub fn memory_budget(&self) -> mem::Budget {
+		let physical_device = self.physical_device();
+
+		if self.loaded_extensions.ext_memory_budget {
+			let mut budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+			let mut properties = vk::PhysicalDeviceMemoryProperties2 {
+				p_next: &mut budget_properties as *mut _ as *mut c_void,
+				..Default::default()
+			};
+
+			let available = unsafe {
+				self.instance.get_physical_device_memory_properties2(physical_device.handle(), &mut properties)
+			};
+
+			if available {
+				let heaps = (0..properties.memory_properties.memory_heap_count as usize).map(|i| {
+					mem::HeapBudget {
+						budget: budget_properties.heap_budget[i],
+						usage: budget_properties.heap_usage[i]
+					}
+				}).collect();
+
+				return mem::Budget::new(heaps, true)
+			}
+		}
+
+		let heaps = physical_device.memory_heaps().map(|heap| {
+			mem::HeapBudget {
+				budget: heap.size(),
+				usage: self.heap_usage[heap.index() as usize].load(Ordering::Relaxed)
+			}
+		}).collect();
+
+		mem::Budget::new(heaps, false)
+	}
+
+	pub fn ext_khr_get_memory_requirements2(&self) -> Result<&vk::KhrGetMemoryRequirements2Fn, MissingExtensionError> {
+		self.ext_khr_get_memory_requirements2.get_or_try_init(|| {
+			if self.loaded_extensions.khr_get_memory_requirements2 {
+				Ok(vk::KhrGetMemoryRequirements2Fn::load(|name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrGetMemoryRequirements2))
+			}
+		})
+	}
+
+	/// Fills the given `VkMemoryRequirements2` structure chain for a buffer.
+	///
+	/// Uses Vulkan 1.1 if available, or the `VK_KHR_get_memory_requirements2` extension.
+	/// Returns `false` if neither is available.
+	pub(crate) unsafe fn get_buffer_memory_requirements2(&self, info: &vk::BufferMemoryRequirementsInfo2, requirements: &mut vk::MemoryRequirements2) -> bool {
+		if self.api_version() >= ApiVersion::V1_1 {
+			self.handle.get_buffer_memory_requirements2(info, requirements);
+			true
+		} else if let Ok(ext) = self.ext_khr_get_memory_requirements2() {
+			ext.get_buffer_memory_requirements2_khr(self.handle.handle(), info, requirements);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Fills the given `VkMemoryRequirements2` structure chain for an image.
+	///
+	/// Uses Vulkan 1.1 if available, or the `VK_KHR_get_memory_requirements2` extension.
+	/// Returns `false` if neither is available.
+	pub(crate) unsafe fn get_image_memory_requirements2(&self, info: &vk::ImageMemoryRequirementsInfo2, requirements: &mut vk::MemoryRequirements2) -> bool {
+		if self.api_version() >= ApiVersion::V1_1 {
+			self.handle.get_image_memory_requirements2(info, requirements);
+			true
+		} else if let Ok(ext) = self.ext_khr_get_memory_requirements2() {
+			ext.get_image_memory_requirements2_khr(self.handle.handle(), info, requirements);
+			true
+		} else {
+			false
+		}
+	}
+
+	pub fn ext_khr_external_memory_fd(&self) -> Result<&vk::KhrExternalMemoryFdFn, MissingExtensionError> {
+		self.ext_khr_external_memory_fd.get_or_try_init(|| {
+			if self.loaded_extensions.khr_external_memory_fd {
+				Ok(vk::KhrExternalMemoryFdFn::load(|name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrExternalMemoryFd))
+			}
+		})
+	}
+
+	pub fn ext_khr_external_semaphore_fd(&self) -> Result<&vk::KhrExternalSemaphoreFdFn, MissingExtensionError> {
+		self.ext_khr_external_semaphore_fd.get_or_try_init(|| {
+			if self.loaded_extensions.khr_external_semaphore_fd {
+				Ok(vk::KhrExternalSemaphoreFdFn::load(|name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrExternalSemaphoreFd))
+			}
+		})
+	}
+
+	pub fn ext_khr_external_fence_fd(&self) -> Result<&vk::KhrExternalFenceFdFn, MissingExtensionError> {
+		self.ext_khr_external_fence_fd.get_or_try_init(|| {
+			if self.loaded_extensions.khr_external_fence_fd {
+				Ok(vk::KhrExternalFenceFdFn::load(|name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrExternalFenceFd))
+			}
+		})
+	}
+
+	pub fn ext_khr_maintenance1(&self) -> Result<&vk::KhrMaintenance1Fn, MissingExtensionError> {
+		self.ext_khr_maintenance1.get_or_try_init(|| {
+			if self.loaded_extensions.khr_maintenance1 {
+				Ok(vk::KhrMaintenance1Fn::load(|name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrMaintenance1))
+			}
+		})
+	}
+
+	/// Trims the given command pool.
+	///
+	/// Uses Vulkan 1.1 if available, or the `VK_KHR_maintenance1` extension.
+	pub(crate) unsafe fn trim_command_pool(&self, pool: vk::CommandPool) -> Result<(), MissingExtensionError> {
+		if self.api_version() >= ApiVersion::V1_1 {
+			self.handle.trim_command_pool(pool, vk::CommandPoolTrimFlags::empty());
+		} else {
+			self.ext_khr_maintenance1()?.trim_command_pool_khr(self.handle.handle(), pool, vk::CommandPoolTrimFlags::empty());
+		}
+
+		Ok(())
+	}
+
+	pub fn ext_khr_draw_indirect_count(&self) -> Result<&vk::KhrDrawIndirectCountFn, MissingExtensionError> {
+		self.ext_khr_draw_indirect_count.get_or_try_init(|| {
+			if self.loaded_extensions.khr_draw_indirect_count {
+				Ok(vk::KhrDrawIndirectCountFn::load(|name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrDrawIndirectCount))
+			}
+		})
+	}
+
+	pub fn ext_ext_full_screen_exclusive(&self) -> Result<&vk::ExtFullScreenExclusiveFn, MissingExtensionError> {
+		self.ext_ext_full_screen_exclusive.get_or_try_init(|| {
+			if self.loaded_extensions.ext_full_screen_exclusive {
+				Ok(vk::ExtFullScreenExclusiveFn::load(|name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::ExtFullScreenExclusive))
+			}
+		})
+	}
+
+	/// Dynamic rendering commands.
+	///
+	/// Uses Vulkan 1.3 if available, or the `VK_KHR_dynamic_rendering` extension.
+	pub(crate) fn dynamic_rendering(&self) -> Result<&crate::ffi::DynamicRenderingFn, MissingExtensionError> {
+		self.dynamic_rendering.get_or_init(|| {
+			let core = self.api_version() >= ApiVersion::V1_3;
+			if core || self.loaded_extensions.khr_dynamic_rendering {
+				crate::ffi::DynamicRenderingFn::load(core, |name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				})
+			} else {
+				None
+			}
+		}).as_ref().ok_or(MissingExtensionError(Extension::KhrDynamicRendering))
+	}
+
+	/// Timeline semaphore commands.
+	///
+	/// Uses Vulkan 1.2 if available, or the `VK_KHR_timeline_semaphore` extension.
+	pub(crate) fn timeline_semaphore(&self) -> Result<&crate::ffi::TimelineSemaphoreFn, MissingExtensionError> {
+		self.timeline_semaphore.get_or_init(|| {
+			let core = self.api_version() >= ApiVersion::V1_2;
+			if core || self.loaded_extensions.khr_timeline_semaphore {
+				crate::ffi::TimelineSemaphoreFn::load(core, |name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				})
+			} else {
+				None
+			}
+		}).as_ref().ok_or(MissingExtensionError(Extension::KhrTimelineSemaphore))
+	}
+
+	/// Indirect draw count commands.
+	///
+	/// Uses the `VK_KHR_draw_indirect_count` extension if enabled,
+	/// or Vulkan 1.2 if the `DrawIndirectCount` extended feature is enabled.
+	pub(crate) fn draw_indirect_count(&self) -> Result<&crate::ffi::DrawIndirectCountFn, MissingExtensionError> {
+		self.draw_indirect_count.get_or_init(|| {
+			let core = self.api_version() >= ApiVersion::V1_2 && self.enabled_extended_features.vulkan_1_2.contains(feature::vulkan_1_2::Feature::DrawIndirectCount);
+			if self.loaded_extensions.khr_draw_indirect_count || core {
+				crate::ffi::DrawIndirectCountFn::load(!self.loaded_extensions.khr_draw_indirect_count, |name| unsafe {
+					std::mem::transmute(self.instance.handle.fp_v1_0().get_device_proc_addr(self.handle.handle(), name.as_ptr()))
+				})
+			} else {
+				None
+			}
+		}).as_ref().ok_or(MissingExtensionError(Extension::KhrDrawIndirectCount))
+	}
+
 	pub fn ext_khr_swapchain(&self) -> Result<&ash::extensions::khr::Swapchain, MissingExtensionError> {
 		self.ext_khr_swapchain.get_or_try_init(|| {
 			if self.loaded_extensions.khr_swapchain {
@@ -264,6 +799,44 @@ impl Device {
 	}
 }
 
+/// Enables the descriptor indexing and timeline semaphore features through the Vulkan 1.2 features.
+fn merge_vulkan_1_2_features(
+	features: &mut vk::PhysicalDeviceVulkan12Features,
+	descriptor_indexing: &vk::PhysicalDeviceDescriptorIndexingFeatures,
+	timeline_semaphore: &vk::PhysicalDeviceTimelineSemaphoreFeatures
+) {
+	features.shader_input_attachment_array_dynamic_indexing = descriptor_indexing.shader_input_attachment_array_dynamic_indexing;
+	features.shader_uniform_texel_buffer_array_dynamic_indexing = descriptor_indexing.shader_uniform_texel_buffer_array_dynamic_indexing;
+	features.shader_storage_texel_buffer_array_dynamic_indexing = descriptor_indexing.shader_storage_texel_buffer_array_dynamic_indexing;
+	features.shader_uniform_buffer_array_non_uniform_indexing = descriptor_indexing.shader_uniform_buffer_array_non_uniform_indexing;
+	features.shader_sampled_image_array_non_uniform_indexing = descriptor_indexing.shader_sampled_image_array_non_uniform_indexing;
+	features.shader_storage_buffer_array_non_uniform_indexing = descriptor_indexing.shader_storage_buffer_array_non_uniform_indexing;
+	features.shader_storage_image_array_non_uniform_indexing = descriptor_indexing.shader_storage_image_array_non_uniform_indexing;
+	features.shader_input_attachment_array_non_uniform_indexing = descriptor_indexing.shader_input_attachment_array_non_uniform_indexing;
+	features.shader_uniform_texel_buffer_array_non_uniform_indexing = descriptor_indexing.shader_uniform_texel_buffer_array_non_uniform_indexing;
+	features.shader_storage_texel_buffer_array_non_uniform_indexing = descriptor_indexing.shader_storage_texel_buffer_array_non_uniform_indexing;
+	features.descriptor_binding_uniform_buffer_update_after_bind = descriptor_indexing.descriptor_binding_uniform_buffer_update_after_bind;
+	features.descriptor_binding_sampled_image_update_after_bind = descriptor_indexing.descriptor_binding_sampled_image_update_after_bind;
+	features.descriptor_binding_storage_image_update_after_bind = descriptor_indexing.descriptor_binding_storage_image_update_after_bind;
+	features.descriptor_binding_storage_buffer_update_after_bind = descriptor_indexing.descriptor_binding_storage_buffer_update_after_bind;
+	features.descriptor_binding_uniform_texel_buffer_update_after_bind = descriptor_indexing.descriptor_binding_uniform_texel_buffer_update_after_bind;
+	features.descriptor_binding_storage_texel_buffer_update_after_bind = descriptor_indexing.descriptor_binding_storage_texel_buffer_update_after_bind;
+	features.descriptor_binding_update_unused_while_pending = descriptor_indexing.descriptor_binding_update_unused_while_pending;
+	features.descriptor_binding_partially_bound = descriptor_indexing.descriptor_binding_partially_bound;
+	features.descriptor_binding_variable_descriptor_count = descriptor_indexing.descriptor_binding_variable_descriptor_count;
+	features.runtime_descriptor_array = descriptor_indexing.runtime_descriptor_array;
+	features.timeline_semaphore = timeline_semaphore.timeline_semaphore;
+}
+
+impl Drop for Device {
+	fn drop(&mut self) {
+		unsafe {
+			self.fence_pool.clear(&self.handle);
+			self.semaphore_pool.clear(&self.handle)
+		}
+	}
+}
+
 impl PartialEq for Device {
 	fn eq(&self, other: &Device) -> bool {
 		self as *const _ == other as *const _
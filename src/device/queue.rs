@@ -1,5 +1,6 @@
 use std::{
-	sync::Arc
+	sync::Arc,
+	time::Duration
 };
 use parking_lot::Mutex;
 use ash::{
@@ -8,6 +9,7 @@ use ash::{
 };
 use crate::{
 	OomError,
+	DeviceLost,
 	instance::physical_device::QueueFamily,
 	command,
 	pipeline,
@@ -24,7 +26,22 @@ use super::Device;
 #[derive(Debug)]
 pub enum SubmitError {
 	OutOfMemory(OomError),
-	DeviceLost
+	DeviceLost,
+
+	/// The pool of the command buffer has been reset since the buffer was allocated.
+	InvalidatedBuffer,
+
+	/// The command buffer has already been recorded, and its pool does not allow resetting it.
+	AlreadyRecordedBuffer,
+
+	/// A `OneTimeSubmit` command buffer is submitted by reference instead of being consumed.
+	BorrowedOneTimeSubmit,
+
+	/// A `OneTimeSubmit` command buffer has already been submitted.
+	AlreadySubmitted,
+
+	/// A secondary command buffer is recorded to be submitted.
+	SecondaryBuffer
 }
 
 impl From<vk::Result> for SubmitError {
@@ -38,6 +55,24 @@ impl From<vk::Result> for SubmitError {
 	}
 }
 
+impl From<sync::semaphore::CreationError> for SubmitError {
+	fn from(sync::semaphore::CreationError::OutOfMemory(e): sync::semaphore::CreationError) -> SubmitError {
+		SubmitError::OutOfMemory(e)
+	}
+}
+
+impl From<sync::fence::CreationError> for SubmitError {
+	fn from(sync::fence::CreationError::OutOfMemory(e): sync::fence::CreationError) -> SubmitError {
+		SubmitError::OutOfMemory(e)
+	}
+}
+
+impl From<DeviceLost> for SubmitError {
+	fn from(_: DeviceLost) -> SubmitError {
+		SubmitError::DeviceLost
+	}
+}
+
 impl std::error::Error for SubmitError {
 	// ...
 }
@@ -47,6 +82,11 @@ impl fmt::Display for SubmitError {
 		match self {
 			Self::OutOfMemory(e) => e.fmt(f),
 			Self::DeviceLost => write!(f, "device lost"),
+			Self::InvalidatedBuffer => write!(f, "command buffer invalidated by a pool reset"),
+			Self::AlreadyRecordedBuffer => write!(f, "command buffer already recorded"),
+			Self::BorrowedOneTimeSubmit => write!(f, "one-time submit command buffer submitted by reference"),
+			Self::AlreadySubmitted => write!(f, "one-time submit command buffer already submitted"),
+			Self::SecondaryBuffer => write!(f, "secondary command buffer recorded for submission"),
 		}
 	}
 }
@@ -55,6 +95,8 @@ impl fmt::Display for SubmitError {
 pub enum PresentError {
 	OutOfMemory(OomError),
 	DeviceLost,
+	SurfaceLost,
+	OutOfDate,
 	MissingDeviceExtension(device::MissingExtensionError),
 }
 
@@ -64,12 +106,20 @@ impl From<device::MissingExtensionError> for PresentError {
 	}
 }
 
+impl From<DeviceLost> for PresentError {
+	fn from(_: DeviceLost) -> PresentError {
+		PresentError::DeviceLost
+	}
+}
+
 impl From<vk::Result> for PresentError {
 	fn from(r: vk::Result) -> PresentError {
 		match r {
 			vk::Result::ERROR_OUT_OF_HOST_MEMORY => PresentError::OutOfMemory(OomError::Host),
 			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => PresentError::OutOfMemory(OomError::Device),
 			vk::Result::ERROR_DEVICE_LOST => PresentError::DeviceLost,
+			vk::Result::ERROR_SURFACE_LOST_KHR => PresentError::SurfaceLost,
+			vk::Result::ERROR_OUT_OF_DATE_KHR => PresentError::OutOfDate,
 			_ => unreachable!()
 		}
 	}
@@ -84,6 +134,8 @@ impl fmt::Display for PresentError {
 		match self {
 			Self::OutOfMemory(e) => e.fmt(f),
 			Self::DeviceLost => write!(f, "device lost"),
+			Self::SurfaceLost => write!(f, "surface lost"),
+			Self::OutOfDate => write!(f, "swapchain out of date"),
 			Self::MissingDeviceExtension(e) => e.fmt(f)
 		}
 	}
@@ -119,6 +171,18 @@ impl Queue {
 		self.device.physical_device().queue_family(self.queue_family_index).unwrap()
 	}
 
+	/// Blocks until the queue is idle.
+	pub fn wait_idle(&self) -> Result<(), sync::fence::WaitError> {
+		self.device.check_lost()?;
+
+		unsafe {
+			let handle = self.handle.lock();
+			self.device.track_lost(self.device.handle().queue_wait_idle(*handle))?
+		}
+
+		Ok(())
+	}
+
 	pub fn submit<'a, B: 'a + command::RecordedBuffer>(&'a self, buffer: B) -> Submit<'a, B> {
 		// TODO check inner buffer queue access.
 
@@ -128,6 +192,111 @@ impl Queue {
 		}
 	}
 
+	/// Submits the given command buffer after the given futures,
+	/// and returns a future signaled when the buffer has been executed.
+	///
+	/// The execution of the buffer waits on the semaphores signaled by `wait` at the given pipeline stages.
+	/// If `wait` only signals a fence, the host blocks until the fence is signaled before submitting.
+	/// Use `()` to submit without waiting.
+	///
+	/// The returned submission also signals `semaphore`, unless it is `()`,
+	/// and can then be chained with other tasks using `and_then`.
+	/// The semaphore must be unsignaled, with no pending operation.
+	pub fn submit_and_signal<B: command::RecordedBuffer, W: sync::future::Futures, S: SubmissionSemaphore>(&self, buffer: B, wait: W, wait_stages: pipeline::stage::Flags, semaphore: S) -> Result<Submission<(W, B), S>, SubmitError> {
+		self.submit_with_timelines(buffer, wait, wait_stages, sync::Timelines::new(), semaphore)
+	}
+
+	/// Submits the given command buffer after the given futures, like `submit_and_signal`,
+	/// also waiting and signaling the values of the given timeline semaphores.
+	///
+	/// The timeline semaphores are kept alive until the submission is done.
+	pub fn submit_with_timelines<B: command::RecordedBuffer, W: sync::future::Futures, S: SubmissionSemaphore>(&self, buffer: B, wait: W, wait_stages: pipeline::stage::Flags, timelines: sync::Timelines, semaphore: S) -> Result<Submission<(W, B), S>, SubmitError> {
+		self.device.check_lost()?;
+
+		check_submitted_buffer(&buffer)?;
+
+		let (wait_semaphores, waits_semaphores) = match wait.signal_semaphores() {
+			Some(semaphores) => (semaphores, true),
+			None => {
+				let fences = wait.signal_fences();
+				if !fences.is_empty() {
+					unsafe {
+						self.device.track_lost(self.device.handle().wait_for_fences(fences, true, u64::MAX))?
+					}
+				}
+
+				(&[][..], false)
+			}
+		};
+
+		let fence = sync::fence::Pooled::new(&self.device)?;
+
+		// Binary semaphores come first, with an ignored value.
+		let waits: Vec<_> = wait_semaphores.iter().map(|s| (*s, wait_stages, 0))
+			.chain(timelines.waits.iter().map(|(s, value, stages)| (*s.handle(), *stages, *value)))
+			.collect();
+		let signals: Vec<_> = semaphore.handle().iter().map(|s| (**s, 0))
+			.chain(timelines.signals.iter().map(|(s, value)| (*s.handle(), *value)))
+			.collect();
+
+		unsafe {
+			self.submit_raw(buffer.handle(), &waits, &signals, !timelines.is_empty(), *sync::Fence::handle(&fence))?
+		}
+		fence.set_submitted();
+
+		if waits_semaphores {
+			sync::future::Futures::waited(&wait);
+		}
+		semaphore.signaled();
+		buffer.commit_layouts();
+
+		Ok(Submission {
+			payload: (wait, buffer),
+			semaphore,
+			fence,
+			timelines
+		})
+	}
+
+	/// Submits a command buffer waiting and signaling the given semaphores.
+	///
+	/// Waited semaphores are given with their wait stages, and each semaphore with its timeline value,
+	/// ignored for binary semaphores.
+	/// The values are only given to Vulkan if `timeline` is `true`.
+	unsafe fn submit_raw(&self, buffer: vk::CommandBuffer, waits: &[(vk::Semaphore, pipeline::stage::Flags, u64)], signals: &[(vk::Semaphore, u64)], timeline: bool, fence: vk::Fence) -> Result<(), SubmitError> {
+		let wait_semaphores: Vec<_> = waits.iter().map(|(s, _, _)| *s).collect();
+		let wait_stage_masks: Vec<_> = waits.iter().map(|(_, stages, _)| *stages).collect();
+		let wait_values: Vec<_> = waits.iter().map(|(_, _, value)| *value).collect();
+		let signal_semaphores: Vec<_> = signals.iter().map(|(s, _)| *s).collect();
+		let signal_values: Vec<_> = signals.iter().map(|(_, value)| *value).collect();
+
+		let timeline_infos = vk::TimelineSemaphoreSubmitInfo {
+			wait_semaphore_value_count: wait_values.len() as u32,
+			p_wait_semaphore_values: wait_values.as_ptr(),
+			signal_semaphore_value_count: signal_values.len() as u32,
+			p_signal_semaphore_values: signal_values.as_ptr(),
+			..Default::default()
+		};
+
+		let infos = vk::SubmitInfo {
+			p_next: if timeline { &timeline_infos as *const _ as *const std::ffi::c_void } else { std::ptr::null() },
+			wait_semaphore_count: wait_semaphores.len() as u32,
+			p_wait_semaphores: wait_semaphores.as_ptr(),
+			p_wait_dst_stage_mask: wait_stage_masks.as_ptr(),
+
+			command_buffer_count: 1,
+			p_command_buffers: &buffer,
+
+			signal_semaphore_count: signal_semaphores.len() as u32,
+			p_signal_semaphores: signal_semaphores.as_ptr(),
+			..Default::default()
+		};
+
+		let handle = self.handle.lock();
+		self.device.track_lost(self.device.handle().queue_submit(*handle, &[infos], fence))?;
+		Ok(())
+	}
+
 	pub fn present<'a, W>(&'a self, swapchain: &'a crate::Swapchain<W>, index: u32) -> Present<'a, W> {
 		Present {
 			queue: self,
@@ -143,6 +312,23 @@ impl DeviceOwned for Queue {
 	}
 }
 
+/// Checks that the given command buffer can be submitted.
+fn check_submitted_buffer<B: command::RecordedBuffer>(buffer: &B) -> Result<(), SubmitError> {
+	if !buffer.is_valid() {
+		return Err(SubmitError::InvalidatedBuffer)
+	}
+
+	if buffer.is_borrowed() && buffer.usage() == command::buffer::Usage::OneTimeSubmit {
+		return Err(SubmitError::BorrowedOneTimeSubmit)
+	}
+
+	if buffer.usage() == command::buffer::Usage::OneTimeSubmit && buffer.is_submitted() {
+		return Err(SubmitError::AlreadySubmitted)
+	}
+
+	Ok(())
+}
+
 pub struct Submit<'a, B: command::RecordedBuffer> {
 	queue: &'a Queue,
 	buffer: B
@@ -173,16 +359,188 @@ unsafe impl<'a, B: command::RecordedBuffer> task::WaitPipelineStages for Submit<
 			..Default::default()
 		};
 
+		let device = &self.queue.device;
+		device.check_lost()?;
+
+		check_submitted_buffer(&self.buffer)?;
+
 		unsafe {
 			let handle = self.queue.handle.lock();
-			self.queue.device.handle().queue_submit(*handle, &[infos], signal_fence.unwrap_or(vk::Fence::null()))?;
+			device.track_lost(device.handle().queue_submit(*handle, &[infos], signal_fence.unwrap_or(vk::Fence::null())))?;
+		}
+
+		self.buffer.commit_layouts();
+		Ok(((), self.buffer))
+	}
+}
+
+/// The timeline semaphores are waited at all the pipeline stages.
+unsafe impl<'a, B: command::RecordedBuffer> task::WaitTimeline for Submit<'a, B> {
+	type Output = ();
+	type Error = SubmitError;
+	type Payload = B;
+
+	fn execute(
+		self,
+		wait_timelines: &[(vk::Semaphore, u64)],
+		signal_semaphores: Option<&[vk::Semaphore]>,
+		signal_fence: Option<vk::Fence>,
+	) -> Result<((), Self::Payload), SubmitError> {
+		self.queue.device.check_lost()?;
+		check_submitted_buffer(&self.buffer)?;
+
+		let waits: Vec<_> = wait_timelines.iter().map(|(s, value)| (*s, pipeline::stage::Flags::ALL_COMMANDS, *value)).collect();
+		let signals: Vec<_> = signal_semaphores.unwrap_or(&[]).iter().map(|s| (*s, 0)).collect();
+
+		unsafe {
+			self.queue.submit_raw(self.buffer.handle(), &waits, &signals, true, signal_fence.unwrap_or(vk::Fence::null()))?
 		}
 
+		self.buffer.commit_layouts();
 		Ok(((), self.buffer))
 	}
 }
 
 impl<'a, B: command::RecordedBuffer> task::SignalSemaphore for Submit<'a, B> {}
+
+/// Semaphore signaled by a submission.
+///
+/// `()` is used for submissions only signaling their fence,
+/// which cannot be chained with tasks waiting on a semaphore.
+pub trait SubmissionSemaphore {
+	fn handle(&self) -> Option<&vk::Semaphore>;
+
+	/// Called once a submission signaling the semaphore is submitted.
+	fn signaled(&self) {}
+
+	/// Called once a wait on the semaphore is submitted, consuming its signal.
+	fn waited(&self) {}
+}
+
+impl SubmissionSemaphore for () {
+	#[inline]
+	fn handle(&self) -> Option<&vk::Semaphore> {
+		None
+	}
+}
+
+impl SubmissionSemaphore for sync::semaphore::Raw {
+	#[inline]
+	fn handle(&self) -> Option<&vk::Semaphore> {
+		Some(sync::Semaphore::handle(self))
+	}
+}
+
+impl SubmissionSemaphore for sync::semaphore::Pooled {
+	#[inline]
+	fn handle(&self) -> Option<&vk::Semaphore> {
+		Some(sync::Semaphore::handle(self))
+	}
+
+	#[inline]
+	fn signaled(&self) {
+		self.set_signaled()
+	}
+
+	#[inline]
+	fn waited(&self) {
+		self.set_waited()
+	}
+}
+
+/// Command buffer submission.
+///
+/// Owns the semaphore `S` and fence signaled upon completion,
+/// and keeps the submitted command buffer and the waited futures alive until the fence is signaled.
+/// Dropping a pending submission blocks until it completes.
+#[must_use]
+pub struct Submission<P, S: SubmissionSemaphore = ()> {
+	payload: P,
+	semaphore: S,
+	fence: sync::fence::Pooled,
+	timelines: sync::Timelines
+}
+
+impl<P, S: SubmissionSemaphore> Submission<P, S> {
+	#[inline]
+	pub fn payload(&self) -> &P {
+		&self.payload
+	}
+
+	/// Blocks until the submission is done, without consuming it.
+	#[inline]
+	pub fn wait_done(&self, timeout: Option<Duration>) -> Result<(), sync::fence::WaitError> {
+		sync::Fence::wait(&self.fence, timeout)
+	}
+
+	/// Blocks until the submission is done, and returns the payload.
+	///
+	/// The submitted command buffer, and the resources it uses, can then be reused or released.
+	/// A `OneTimeSubmit` command buffer is marked as submitted and cannot be submitted again.
+	/// The submission is given back if the wait fails.
+	pub fn into_payload(self, timeout: Option<Duration>) -> Result<P, (Self, sync::fence::WaitError)> {
+		if let Err(e) = self.wait_done(timeout) {
+			return Err((self, e))
+		}
+
+		let mut this = std::mem::ManuallyDrop::new(self);
+		unsafe {
+			std::ptr::drop_in_place(&mut this.semaphore);
+			std::ptr::drop_in_place(&mut this.fence);
+			std::ptr::drop_in_place(&mut this.timelines);
+			Ok(std::ptr::read(&this.payload))
+		}
+	}
+
+	/// Hand the submission over to the given deletion queue,
+	/// which releases the payload once the submission is done, without blocking.
+	#[inline]
+	pub fn defer(self, deletion_queue: &device::DeletionQueue) where P: 'static + Send, S: 'static + Send {
+		deletion_queue.defer_submission(self)
+	}
+}
+
+unsafe impl<P, S: SubmissionSemaphore> sync::Future for Submission<P, S> {
+	fn signal_semaphore(&self) -> Option<&vk::Semaphore> {
+		self.semaphore.handle()
+	}
+
+	fn signal_fence(&self) -> Option<&vk::Fence> {
+		Some(sync::Fence::handle(&self.fence))
+	}
+
+	fn waited(&self) {
+		self.semaphore.waited()
+	}
+}
+
+impl<P> sync::future::SignalSemaphore for Submission<P, sync::semaphore::Raw> {}
+impl<P> sync::future::SignalSemaphore for Submission<P, sync::semaphore::Pooled> {}
+
+impl<P, S: SubmissionSemaphore> DeviceOwned for Submission<P, S> {
+	fn device(&self) -> &Arc<Device> {
+		self.fence.device()
+	}
+}
+
+impl<P, S: SubmissionSemaphore> sync::future::SignalFence for Submission<P, S> {
+	fn wait(self, timeout: Option<Duration>) -> Result<(), sync::fence::WaitError> {
+		sync::Fence::wait(&self.fence, timeout)
+	}
+
+	fn is_signaled(&self) -> Result<bool, DeviceLost> {
+		sync::Fence::is_signaled(&self.fence)
+	}
+}
+
+impl<P, S: SubmissionSemaphore> Drop for Submission<P, S> {
+	fn drop(&mut self) {
+		// The payload must outlive the execution of the command buffer.
+		if let Err(e) = sync::Fence::wait(&self.fence, None) {
+			error!("unable to wait for submission: {}", e)
+		}
+	}
+}
 impl<'a, B: command::RecordedBuffer> task::SignalFence for Submit<'a, B> {}
 
 pub struct Present<'a, W> {
@@ -202,7 +560,9 @@ unsafe impl<'a, W> task::Wait for Present<'a, W> {
 		_signal_semaphores: Option<&[vk::Semaphore]>,
 		_signal_fence: Option<vk::Fence>,
 	) -> Result<(bool, ()), PresentError> {
-		let ext_khr_swapchain = self.queue.device.ext_khr_swapchain()?;
+		let device = &self.queue.device;
+		device.check_lost()?;
+		let ext_khr_swapchain = device.ext_khr_swapchain()?;
 
 		let mut result = vk::Result::SUCCESS;
 
@@ -218,11 +578,11 @@ unsafe impl<'a, W> task::Wait for Present<'a, W> {
 
 		let suboptimal = unsafe {
 			let handle = self.queue.handle.lock();
-			ext_khr_swapchain.queue_present(*handle, &infos)?
+			device.track_lost(ext_khr_swapchain.queue_present(*handle, &infos))?
 		};
 
-		if result != vk::Result::SUCCESS {
-			return Err(result.into())
+		if result != vk::Result::SUCCESS && result != vk::Result::SUBOPTIMAL_KHR {
+			return Err(device.track_lost::<()>(Err(result)).unwrap_err().into())
 		}
 
 		Ok((suboptimal, ()))
@@ -0,0 +1,286 @@
+//! Vulkan definitions that are not provided by `ash`.
+use std::{
+	ffi::{
+		CStr,
+		c_void
+	},
+	ptr
+};
+use ash::vk;
+
+/// `VkStructureType` values of `VK_KHR_dynamic_rendering` (core in Vulkan 1.3)
+/// and `VK_KHR_portability_subset`.
+pub(crate) mod structure_type {
+	use ash::vk::StructureType;
+
+	pub const RENDERING_INFO: StructureType = StructureType::from_raw(1000044000);
+	pub const RENDERING_ATTACHMENT_INFO: StructureType = StructureType::from_raw(1000044001);
+	pub const PIPELINE_RENDERING_CREATE_INFO: StructureType = StructureType::from_raw(1000044002);
+	pub const PHYSICAL_DEVICE_DYNAMIC_RENDERING_FEATURES: StructureType = StructureType::from_raw(1000044003);
+	pub const PHYSICAL_DEVICE_PORTABILITY_SUBSET_FEATURES: StructureType = StructureType::from_raw(1000163000);
+}
+
+/// `VkRenderingInfo`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct RenderingInfo {
+	pub s_type: vk::StructureType,
+	pub p_next: *const c_void,
+	pub flags: vk::Flags,
+	pub render_area: vk::Rect2D,
+	pub layer_count: u32,
+	pub view_mask: u32,
+	pub color_attachment_count: u32,
+	pub p_color_attachments: *const RenderingAttachmentInfo,
+	pub p_depth_attachment: *const RenderingAttachmentInfo,
+	pub p_stencil_attachment: *const RenderingAttachmentInfo
+}
+
+impl Default for RenderingInfo {
+	fn default() -> RenderingInfo {
+		RenderingInfo {
+			s_type: structure_type::RENDERING_INFO,
+			p_next: ptr::null(),
+			flags: 0,
+			render_area: vk::Rect2D::default(),
+			layer_count: 0,
+			view_mask: 0,
+			color_attachment_count: 0,
+			p_color_attachments: ptr::null(),
+			p_depth_attachment: ptr::null(),
+			p_stencil_attachment: ptr::null()
+		}
+	}
+}
+
+/// `VkRenderingAttachmentInfo`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct RenderingAttachmentInfo {
+	pub s_type: vk::StructureType,
+	pub p_next: *const c_void,
+	pub image_view: vk::ImageView,
+	pub image_layout: vk::ImageLayout,
+	pub resolve_mode: vk::ResolveModeFlags,
+	pub resolve_image_view: vk::ImageView,
+	pub resolve_image_layout: vk::ImageLayout,
+	pub load_op: vk::AttachmentLoadOp,
+	pub store_op: vk::AttachmentStoreOp,
+	pub clear_value: vk::ClearValue
+}
+
+impl Default for RenderingAttachmentInfo {
+	fn default() -> RenderingAttachmentInfo {
+		RenderingAttachmentInfo {
+			s_type: structure_type::RENDERING_ATTACHMENT_INFO,
+			p_next: ptr::null(),
+			image_view: vk::ImageView::null(),
+			image_layout: vk::ImageLayout::UNDEFINED,
+			resolve_mode: vk::ResolveModeFlags::NONE,
+			resolve_image_view: vk::ImageView::null(),
+			resolve_image_layout: vk::ImageLayout::UNDEFINED,
+			load_op: vk::AttachmentLoadOp::LOAD,
+			store_op: vk::AttachmentStoreOp::STORE,
+			clear_value: vk::ClearValue::default()
+		}
+	}
+}
+
+/// `VkPipelineRenderingCreateInfo`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct PipelineRenderingCreateInfo {
+	pub s_type: vk::StructureType,
+	pub p_next: *const c_void,
+	pub view_mask: u32,
+	pub color_attachment_count: u32,
+	pub p_color_attachment_formats: *const vk::Format,
+	pub depth_attachment_format: vk::Format,
+	pub stencil_attachment_format: vk::Format
+}
+
+impl Default for PipelineRenderingCreateInfo {
+	fn default() -> PipelineRenderingCreateInfo {
+		PipelineRenderingCreateInfo {
+			s_type: structure_type::PIPELINE_RENDERING_CREATE_INFO,
+			p_next: ptr::null(),
+			view_mask: 0,
+			color_attachment_count: 0,
+			p_color_attachment_formats: ptr::null(),
+			depth_attachment_format: vk::Format::UNDEFINED,
+			stencil_attachment_format: vk::Format::UNDEFINED
+		}
+	}
+}
+
+/// `VkPhysicalDeviceDynamicRenderingFeatures`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct PhysicalDeviceDynamicRenderingFeatures {
+	pub s_type: vk::StructureType,
+	pub p_next: *mut c_void,
+	pub dynamic_rendering: vk::Bool32
+}
+
+impl Default for PhysicalDeviceDynamicRenderingFeatures {
+	fn default() -> PhysicalDeviceDynamicRenderingFeatures {
+		PhysicalDeviceDynamicRenderingFeatures {
+			s_type: structure_type::PHYSICAL_DEVICE_DYNAMIC_RENDERING_FEATURES,
+			p_next: ptr::null_mut(),
+			dynamic_rendering: vk::FALSE
+		}
+	}
+}
+
+/// `VkPhysicalDevicePortabilitySubsetFeaturesKHR`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct PhysicalDevicePortabilitySubsetFeatures {
+	pub s_type: vk::StructureType,
+	pub p_next: *mut c_void,
+	pub constant_alpha_color_blend_factors: vk::Bool32,
+	pub events: vk::Bool32,
+	pub image_view_format_reinterpretation: vk::Bool32,
+	pub image_view_format_swizzle: vk::Bool32,
+	pub image_view2_d_on3_d_image: vk::Bool32,
+	pub multisample_array_image: vk::Bool32,
+	pub mutable_comparison_samplers: vk::Bool32,
+	pub point_polygons: vk::Bool32,
+	pub sampler_mip_lod_bias: vk::Bool32,
+	pub separate_stencil_mask_ref: vk::Bool32,
+	pub shader_sample_rate_interpolation_functions: vk::Bool32,
+	pub tessellation_isolines: vk::Bool32,
+	pub tessellation_point_mode: vk::Bool32,
+	pub triangle_fans: vk::Bool32,
+	pub vertex_attribute_access_beyond_stride: vk::Bool32
+}
+
+impl Default for PhysicalDevicePortabilitySubsetFeatures {
+	fn default() -> PhysicalDevicePortabilitySubsetFeatures {
+		PhysicalDevicePortabilitySubsetFeatures {
+			s_type: structure_type::PHYSICAL_DEVICE_PORTABILITY_SUBSET_FEATURES,
+			p_next: ptr::null_mut(),
+			constant_alpha_color_blend_factors: vk::FALSE,
+			events: vk::FALSE,
+			image_view_format_reinterpretation: vk::FALSE,
+			image_view_format_swizzle: vk::FALSE,
+			image_view2_d_on3_d_image: vk::FALSE,
+			multisample_array_image: vk::FALSE,
+			mutable_comparison_samplers: vk::FALSE,
+			point_polygons: vk::FALSE,
+			sampler_mip_lod_bias: vk::FALSE,
+			separate_stencil_mask_ref: vk::FALSE,
+			shader_sample_rate_interpolation_functions: vk::FALSE,
+			tessellation_isolines: vk::FALSE,
+			tessellation_point_mode: vk::FALSE,
+			triangle_fans: vk::FALSE,
+			vertex_attribute_access_beyond_stride: vk::FALSE
+		}
+	}
+}
+
+/// Dynamic rendering commands, from Vulkan 1.3 or `VK_KHR_dynamic_rendering`.
+pub(crate) struct DynamicRenderingFn {
+	pub cmd_begin_rendering: unsafe extern "system" fn(vk::CommandBuffer, *const RenderingInfo),
+	pub cmd_end_rendering: unsafe extern "system" fn(vk::CommandBuffer)
+}
+
+impl DynamicRenderingFn {
+	/// Load the commands with the given loader.
+	///
+	/// The core commands are loaded if `core` is `true`, the extension commands otherwise.
+	/// Returns `None` if a command is not provided.
+	pub fn load<F: FnMut(&CStr) -> *const c_void>(core: bool, mut f: F) -> Option<DynamicRenderingFn> {
+		let (begin, end): (&[u8], &[u8]) = if core {
+			(b"vkCmdBeginRendering\0", b"vkCmdEndRendering\0")
+		} else {
+			(b"vkCmdBeginRenderingKHR\0", b"vkCmdEndRenderingKHR\0")
+		};
+
+		let begin = f(CStr::from_bytes_with_nul(begin).unwrap());
+		let end = f(CStr::from_bytes_with_nul(end).unwrap());
+
+		if begin.is_null() || end.is_null() {
+			None
+		} else {
+			unsafe {
+				Some(DynamicRenderingFn {
+					cmd_begin_rendering: std::mem::transmute(begin),
+					cmd_end_rendering: std::mem::transmute(end)
+				})
+			}
+		}
+	}
+}
+
+/// Indirect draw count commands, from Vulkan 1.2 or `VK_KHR_draw_indirect_count`.
+pub(crate) struct DrawIndirectCountFn {
+	pub cmd_draw_indirect_count: unsafe extern "system" fn(vk::CommandBuffer, vk::Buffer, vk::DeviceSize, vk::Buffer, vk::DeviceSize, u32, u32),
+	pub cmd_draw_indexed_indirect_count: unsafe extern "system" fn(vk::CommandBuffer, vk::Buffer, vk::DeviceSize, vk::Buffer, vk::DeviceSize, u32, u32)
+}
+
+impl DrawIndirectCountFn {
+	/// Load the commands with the given loader.
+	///
+	/// The core commands are loaded if `core` is `true`, the extension commands otherwise.
+	/// Returns `None` if a command is not provided.
+	pub fn load<F: FnMut(&CStr) -> *const c_void>(core: bool, mut f: F) -> Option<DrawIndirectCountFn> {
+		let (draw, draw_indexed): (&[u8], &[u8]) = if core {
+			(b"vkCmdDrawIndirectCount\0", b"vkCmdDrawIndexedIndirectCount\0")
+		} else {
+			(b"vkCmdDrawIndirectCountKHR\0", b"vkCmdDrawIndexedIndirectCountKHR\0")
+		};
+
+		let draw = f(CStr::from_bytes_with_nul(draw).unwrap());
+		let draw_indexed = f(CStr::from_bytes_with_nul(draw_indexed).unwrap());
+
+		if draw.is_null() || draw_indexed.is_null() {
+			None
+		} else {
+			unsafe {
+				Some(DrawIndirectCountFn {
+					cmd_draw_indirect_count: std::mem::transmute(draw),
+					cmd_draw_indexed_indirect_count: std::mem::transmute(draw_indexed)
+				})
+			}
+		}
+	}
+}
+
+/// Timeline semaphore commands, from Vulkan 1.2 or `VK_KHR_timeline_semaphore`.
+#[derive(Clone, Copy)]
+pub(crate) struct TimelineSemaphoreFn {
+	pub get_semaphore_counter_value: unsafe extern "system" fn(vk::Device, vk::Semaphore, *mut u64) -> vk::Result,
+	pub wait_semaphores: unsafe extern "system" fn(vk::Device, *const vk::SemaphoreWaitInfo, u64) -> vk::Result,
+	pub signal_semaphore: unsafe extern "system" fn(vk::Device, *const vk::SemaphoreSignalInfo) -> vk::Result
+}
+
+impl TimelineSemaphoreFn {
+	/// Load the commands with the given loader.
+	///
+	/// The core commands are loaded if `core` is `true`, the extension commands otherwise.
+	/// Returns `None` if a command is not provided.
+	pub fn load<F: FnMut(&CStr) -> *const c_void>(core: bool, mut f: F) -> Option<TimelineSemaphoreFn> {
+		let (get, wait, signal): (&[u8], &[u8], &[u8]) = if core {
+			(b"vkGetSemaphoreCounterValue\0", b"vkWaitSemaphores\0", b"vkSignalSemaphore\0")
+		} else {
+			(b"vkGetSemaphoreCounterValueKHR\0", b"vkWaitSemaphoresKHR\0", b"vkSignalSemaphoreKHR\0")
+		};
+
+		let get = f(CStr::from_bytes_with_nul(get).unwrap());
+		let wait = f(CStr::from_bytes_with_nul(wait).unwrap());
+		let signal = f(CStr::from_bytes_with_nul(signal).unwrap());
+
+		if get.is_null() || wait.is_null() || signal.is_null() {
+			None
+		} else {
+			unsafe {
+				Some(TimelineSemaphoreFn {
+					get_semaphore_counter_value: std::mem::transmute(get),
+					wait_semaphores: std::mem::transmute(wait),
+					signal_semaphore: std::mem::transmute(signal)
+				})
+			}
+		}
+	}
+}
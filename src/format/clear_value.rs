@@ -1,15 +1,179 @@
 use ash::vk;
+use super::{
+	Format,
+	NumericType
+};
 
-#[derive(Clone, Copy, Default)]
-#[repr(transparent)]
-pub struct ClearValue(vk::ClearValue);
+/// Value used to clear an attachment.
+///
+/// The variant must match the format of the cleared attachment.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ClearValue {
+	/// Color of a floating point, normalized or scaled format.
+	ColorF32([f32; 4]),
+
+	/// Color of a signed integer format.
+	ColorI32([i32; 4]),
+
+	/// Color of an unsigned integer format.
+	ColorU32([u32; 4]),
+
+	DepthStencil {
+		depth: f32,
+		stencil: u32
+	}
+}
 
 impl ClearValue {
+	/// Opaque black.
+	pub const BLACK: ClearValue = ClearValue::ColorF32([0.0, 0.0, 0.0, 1.0]);
+
+	/// Farthest depth, with a zero stencil.
+	pub const FAR: ClearValue = ClearValue::DepthStencil { depth: 1.0, stencil: 0 };
+
 	pub fn f32color(red: f32, green: f32, blue: f32, alpha: f32) -> ClearValue {
-		ClearValue(vk::ClearValue {
-			color: vk::ClearColorValue {
-				float32: [red, green, blue, alpha]
+		ClearValue::ColorF32([red, green, blue, alpha])
+	}
+
+	/// Checks if the clear value can be used for an attachment of the given format.
+	///
+	/// Formats with an unknown numeric type accept any color value.
+	pub fn is_compatible_with(&self, format: Format) -> bool {
+		if format.has_depth() || format.has_stencil() {
+			return match self {
+				ClearValue::DepthStencil { .. } => true,
+				_ => false
 			}
-		})
+		}
+
+		match *self {
+			ClearValue::ColorF32(color) => ClearColor::F32(color).is_compatible_with(format),
+			ClearValue::ColorI32(color) => ClearColor::I32(color).is_compatible_with(format),
+			ClearValue::ColorU32(color) => ClearColor::U32(color).is_compatible_with(format),
+			ClearValue::DepthStencil { .. } => false
+		}
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ClearValue {
+		match self {
+			ClearValue::ColorF32(float32) => vk::ClearValue {
+				color: vk::ClearColorValue { float32 }
+			},
+			ClearValue::ColorI32(int32) => vk::ClearValue {
+				color: vk::ClearColorValue { int32 }
+			},
+			ClearValue::ColorU32(uint32) => vk::ClearValue {
+				color: vk::ClearColorValue { uint32 }
+			},
+			ClearValue::DepthStencil { depth, stencil } => vk::ClearValue {
+				depth_stencil: vk::ClearDepthStencilValue { depth, stencil }
+			}
+		}
+	}
+}
+
+impl Default for ClearValue {
+	#[inline]
+	fn default() -> ClearValue {
+		ClearValue::ColorF32([0.0; 4])
+	}
+}
+
+impl From<[f32; 4]> for ClearValue {
+	#[inline]
+	fn from(color: [f32; 4]) -> ClearValue {
+		ClearValue::ColorF32(color)
+	}
+}
+
+impl From<[i32; 4]> for ClearValue {
+	#[inline]
+	fn from(color: [i32; 4]) -> ClearValue {
+		ClearValue::ColorI32(color)
+	}
+}
+
+impl From<[u32; 4]> for ClearValue {
+	#[inline]
+	fn from(color: [u32; 4]) -> ClearValue {
+		ClearValue::ColorU32(color)
+	}
+}
+
+/// Color used to clear a color image or attachment.
+///
+/// The variant must match the numeric type of the cleared image format.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ClearColor {
+	/// Color of a floating point, normalized or scaled format.
+	F32([f32; 4]),
+
+	/// Color of a signed integer format.
+	I32([i32; 4]),
+
+	/// Color of an unsigned integer format.
+	U32([u32; 4])
+}
+
+impl ClearColor {
+	/// Opaque black.
+	pub const BLACK: ClearColor = ClearColor::F32([0.0, 0.0, 0.0, 1.0]);
+
+	/// Checks if the color can be used to clear an image of the given format.
+	///
+	/// Formats with an unknown numeric type accept any color.
+	pub fn is_compatible_with(&self, format: Format) -> bool {
+		if format.has_depth() || format.has_stencil() {
+			return false
+		}
+
+		match (self, format.numeric_type()) {
+			(_, None) => true,
+			(ClearColor::I32(_), Some(numeric)) => numeric == NumericType::Sint,
+			(ClearColor::U32(_), Some(numeric)) => numeric == NumericType::Uint,
+			(ClearColor::F32(_), Some(numeric)) => numeric != NumericType::Sint && numeric != NumericType::Uint
+		}
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ClearColorValue {
+		match self {
+			ClearColor::F32(float32) => vk::ClearColorValue { float32 },
+			ClearColor::I32(int32) => vk::ClearColorValue { int32 },
+			ClearColor::U32(uint32) => vk::ClearColorValue { uint32 }
+		}
+	}
+}
+
+impl From<ClearColor> for ClearValue {
+	#[inline]
+	fn from(color: ClearColor) -> ClearValue {
+		match color {
+			ClearColor::F32(color) => ClearValue::ColorF32(color),
+			ClearColor::I32(color) => ClearValue::ColorI32(color),
+			ClearColor::U32(color) => ClearValue::ColorU32(color)
+		}
+	}
+}
+
+impl From<[f32; 4]> for ClearColor {
+	#[inline]
+	fn from(color: [f32; 4]) -> ClearColor {
+		ClearColor::F32(color)
+	}
+}
+
+impl From<[i32; 4]> for ClearColor {
+	#[inline]
+	fn from(color: [i32; 4]) -> ClearColor {
+		ClearColor::I32(color)
+	}
+}
+
+impl From<[u32; 4]> for ClearColor {
+	#[inline]
+	fn from(color: [u32; 4]) -> ClearColor {
+		ClearColor::U32(color)
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+use ash::vk;
+
+macro_rules! format_features {
+	($($name:ident: $cst:ident => $flag:path,)*) => {
+		/// Set of features supported by a format.
+		#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+		pub struct Features(vk::FormatFeatureFlags);
+
+		impl Features {
+			$(
+				pub const $cst: Features = Features($flag);
+			)*
+
+			/// Returns an empty set of features.
+			#[inline]
+			pub fn none() -> Features {
+				Features(vk::FormatFeatureFlags::empty())
+			}
+
+			#[inline]
+			pub fn is_empty(&self) -> bool {
+				self.0.is_empty()
+			}
+
+			/// Checks that all the features of `other` are in this set.
+			#[inline]
+			pub fn contains(&self, other: Features) -> bool {
+				self.0.contains(other.0)
+			}
+
+			$(
+				#[inline]
+				pub fn $name(&self) -> bool {
+					self.0.contains($flag)
+				}
+			)*
+
+			#[inline]
+			pub(crate) fn from_vulkan(flags: vk::FormatFeatureFlags) -> Features {
+				Features(flags)
+			}
+		}
+
+		impl fmt::Debug for Features {
+			#[allow(unused_assignments)]
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				write!(f, "[")?;
+
+				let mut first = true;
+
+				$(
+					if self.$name() {
+						if !first { write!(f, ", ")? }
+						else { first = false; }
+						f.write_str(stringify!($name))?;
+					}
+				)*
+
+				write!(f, "]")
+			}
+		}
+	};
+}
+
+format_features! {
+	sampled_image: SAMPLED_IMAGE => vk::FormatFeatureFlags::SAMPLED_IMAGE,
+	storage_image: STORAGE_IMAGE => vk::FormatFeatureFlags::STORAGE_IMAGE,
+	storage_image_atomic: STORAGE_IMAGE_ATOMIC => vk::FormatFeatureFlags::STORAGE_IMAGE_ATOMIC,
+	uniform_texel_buffer: UNIFORM_TEXEL_BUFFER => vk::FormatFeatureFlags::UNIFORM_TEXEL_BUFFER,
+	storage_texel_buffer: STORAGE_TEXEL_BUFFER => vk::FormatFeatureFlags::STORAGE_TEXEL_BUFFER,
+	storage_texel_buffer_atomic: STORAGE_TEXEL_BUFFER_ATOMIC => vk::FormatFeatureFlags::STORAGE_TEXEL_BUFFER_ATOMIC,
+	vertex_buffer: VERTEX_BUFFER => vk::FormatFeatureFlags::VERTEX_BUFFER,
+	color_attachment: COLOR_ATTACHMENT => vk::FormatFeatureFlags::COLOR_ATTACHMENT,
+	color_attachment_blend: COLOR_ATTACHMENT_BLEND => vk::FormatFeatureFlags::COLOR_ATTACHMENT_BLEND,
+	depth_stencil_attachment: DEPTH_STENCIL_ATTACHMENT => vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT,
+	blit_src: BLIT_SRC => vk::FormatFeatureFlags::BLIT_SRC,
+	blit_dst: BLIT_DST => vk::FormatFeatureFlags::BLIT_DST,
+	sampled_image_filter_linear: SAMPLED_IMAGE_FILTER_LINEAR => vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+	transfer_src: TRANSFER_SRC => vk::FormatFeatureFlags::TRANSFER_SRC,
+	transfer_dst: TRANSFER_DST => vk::FormatFeatureFlags::TRANSFER_DST,
+	midpoint_chroma_samples: MIDPOINT_CHROMA_SAMPLES => vk::FormatFeatureFlags::MIDPOINT_CHROMA_SAMPLES,
+	sampled_image_ycbcr_conversion_linear_filter: SAMPLED_IMAGE_YCBCR_CONVERSION_LINEAR_FILTER => vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_LINEAR_FILTER,
+	sampled_image_ycbcr_conversion_separate_reconstruction_filter: SAMPLED_IMAGE_YCBCR_CONVERSION_SEPARATE_RECONSTRUCTION_FILTER => vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_SEPARATE_RECONSTRUCTION_FILTER,
+	sampled_image_ycbcr_conversion_chroma_reconstruction_explicit: SAMPLED_IMAGE_YCBCR_CONVERSION_CHROMA_RECONSTRUCTION_EXPLICIT => vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_CHROMA_RECONSTRUCTION_EXPLICIT,
+	sampled_image_ycbcr_conversion_chroma_reconstruction_explicit_forceable: SAMPLED_IMAGE_YCBCR_CONVERSION_CHROMA_RECONSTRUCTION_EXPLICIT_FORCEABLE => vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_CHROMA_RECONSTRUCTION_EXPLICIT_FORCEABLE,
+	disjoint: DISJOINT => vk::FormatFeatureFlags::DISJOINT,
+	cosited_chroma_samples: COSITED_CHROMA_SAMPLES => vk::FormatFeatureFlags::COSITED_CHROMA_SAMPLES,
+	sampled_image_filter_minmax: SAMPLED_IMAGE_FILTER_MINMAX => vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_MINMAX,
+	img_sampled_image_filter_cubic: IMG_SAMPLED_IMAGE_FILTER_CUBIC => vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_CUBIC_IMG,
+	khr_acceleration_structure_vertex_buffer: KHR_ACCELERATION_STRUCTURE_VERTEX_BUFFER => vk::FormatFeatureFlags::ACCELERATION_STRUCTURE_VERTEX_BUFFER_KHR,
+	ext_fragment_density_map: EXT_FRAGMENT_DENSITY_MAP => vk::FormatFeatureFlags::FRAGMENT_DENSITY_MAP_EXT,
+}
+
+impl std::ops::BitOr for Features {
+	type Output = Features;
+
+	fn bitor(self, rhs: Self) -> Features {
+		Features(self.0 | rhs.0)
+	}
+}
+
+impl std::ops::BitOrAssign for Features {
+	fn bitor_assign(&mut self, rhs: Self) {
+		self.0 |= rhs.0
+	}
+}
@@ -1,28 +1,133 @@
+use std::{
+	convert::TryFrom,
+	num::NonZeroU32,
+	fmt
+};
 use ash::{
 	vk,
 	version::InstanceV1_0
 };
-use crate::instance::PhysicalDevice;
+use crate::{
+	instance::PhysicalDevice,
+	image::{
+		Tiling,
+		view::Aspects
+	}
+};
 
 mod clear_value;
+mod features;
 
-pub use clear_value::ClearValue;
+pub use clear_value::{
+	ClearValue,
+	ClearColor
+};
+pub use features::Features;
 
 /// The properties of an image format that are supported by a physical device.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct FormatProperties {
+	linear_tiling_features: Features,
+	optimal_tiling_features: Features,
+	buffer_features: Features
+}
+
+impl FormatProperties {
+	#[inline]
+	pub(crate) fn from_vulkan(properties: vk::FormatProperties) -> FormatProperties {
+		FormatProperties {
+			linear_tiling_features: Features::from_vulkan(properties.linear_tiling_features),
+			optimal_tiling_features: Features::from_vulkan(properties.optimal_tiling_features),
+			buffer_features: Features::from_vulkan(properties.buffer_features)
+		}
+	}
+
 	/// Features available for images with linear tiling.
-	pub linear_tiling_features: FormatFeatures,
+	#[inline]
+	pub fn linear_tiling_features(&self) -> Features {
+		self.linear_tiling_features
+	}
 
 	/// Features available for images with optimal tiling.
-	pub optimal_tiling_features: FormatFeatures,
+	#[inline]
+	pub fn optimal_tiling_features(&self) -> Features {
+		self.optimal_tiling_features
+	}
+
+	/// Features available for images with the given tiling.
+	#[inline]
+	pub fn tiling_features(&self, tiling: Tiling) -> Features {
+		match tiling {
+			Tiling::Linear => self.linear_tiling_features,
+			Tiling::Optimal => self.optimal_tiling_features
+		}
+	}
 
 	/// Features available for buffers.
-	pub buffer_features: FormatFeatures,
+	#[inline]
+	pub fn buffer_features(&self) -> Features {
+		self.buffer_features
+	}
+}
+
+/// Vulkan format value unknown to this crate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownFormat(pub i32);
+
+impl fmt::Display for UnknownFormat {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "unknown format {}", self.0)
+	}
+}
+
+impl std::error::Error for UnknownFormat { }
+
+impl TryFrom<vk::Format> for Format {
+	type Error = UnknownFormat;
+
+	#[inline]
+	fn try_from(format: vk::Format) -> Result<Format, UnknownFormat> {
+		Format::from_vulkan(format).ok_or(UnknownFormat(format.as_raw()))
+	}
+}
+
+impl From<Format> for vk::Format {
+	#[inline]
+	fn from(format: Format) -> vk::Format {
+		format.into_vulkan()
+	}
+}
+
+/// Numeric type of the components of a format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NumericType {
+	/// Unsigned integer normalized to `[0, 1]`.
+	Unorm,
+
+	/// Signed integer normalized to `[-1, 1]`.
+	Snorm,
+
+	/// Unsigned integer converted to float.
+	Uscaled,
+
+	/// Signed integer converted to float.
+	Sscaled,
+
+	Uint,
+	Sint,
+
+	/// Unsigned floating point.
+	Ufloat,
+
+	/// Signed floating point.
+	Sfloat,
+
+	/// Unsigned integer normalized to `[0, 1]`, in the sRGB color space.
+	Srgb
 }
 
 macro_rules! formats {
-	($($name:ident => $vk:ident [$bdim:expr] [$sz:expr]),+) => (
+	($($name:ident => $vk:ident [$numeric:ident] [$bdim:expr] [$sz:expr]),+) => (
 		/// An enumeration of all the possible formats.
 		#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 		#[repr(i32)]
@@ -30,8 +135,18 @@ macro_rules! formats {
 		#[allow(non_camel_case_types)]
 		pub enum Format {
 			$($name = vk::Format::$vk.as_raw(),)+
+
+			/// Format unknown to this crate, such as a format introduced by an extension,
+			/// given by its raw Vulkan value.
+			///
+			/// Formats known to this crate are never represented with this variant when returned by the crate.
+			Other(i32)
 		}
 
+		/// Every format known to this crate.
+		#[cfg(test)]
+		const KNOWN_FORMATS: &[Format] = &[$(Format::$name),+];
+
 		impl Format {
 			/// Returns the size in bytes of an element of this format.
 			/// For block based formats this will be the size of a single block.
@@ -42,21 +157,38 @@ macro_rules! formats {
 					$(
 						Format::$name => $sz,
 					)+
+					Format::Other(_) => None
 				}
 			}
 
 			/// Returns (width, heigh) of the dimensions for block based formats.
-			/// For non block formats will return (1,1)
+			/// For non block formats and unknown formats will return (1,1)
 			#[inline]
 			pub const fn block_dimensions(&self) -> (u32, u32) {
 				match *self {
 					$(
 						Format::$name => $bdim,
 					)+
+					Format::Other(_) => (1, 1)
+				}
+			}
+
+			/// Numeric type of the components of this format.
+			///
+			/// For depth-stencil formats, this is the numeric type of the depth component.
+			/// Returns `None` for unknown formats.
+			#[inline]
+			pub const fn numeric_type(&self) -> Option<NumericType> {
+				match *self {
+					$(
+						Format::$name => Some(NumericType::$numeric),
+					)+
+					Format::Other(_) => None
 				}
 			}
 
-			/// Returns the `Format` corresponding to a Vulkan constant.
+			/// Returns the `Format` corresponding to a Vulkan constant,
+			/// or `None` if the format is unknown to this crate.
 			pub(crate) const fn from_vulkan(val: vk::Format) -> Option<Format> {
 				match val {
 					$(
@@ -66,14 +198,28 @@ macro_rules! formats {
 				}
 			}
 
+			/// Returns the `Format` corresponding to a Vulkan constant,
+			/// using `Other` for formats unknown to this crate.
+			pub(crate) const fn from_vulkan_lossless(val: vk::Format) -> Format {
+				match Format::from_vulkan(val) {
+					Some(format) => format,
+					None => Format::Other(val.as_raw())
+				}
+			}
+
 			/// Returns the Vulkan constant corresponding to the `Format`.
 			pub(crate) const fn into_vulkan(self) -> vk::Format {
-				vk::Format::from_raw(self as i32)
+				match self {
+					$(
+						Format::$name => vk::Format::$vk,
+					)+
+					Format::Other(raw) => vk::Format::from_raw(raw)
+				}
 			}
 
 			/// Retrieves the properties of a format when used by a certain device.
 			#[inline]
-			pub fn properties(&self, device: PhysicalDevice) -> FormatProperties {
+			pub fn properties(&self, device: &PhysicalDevice) -> FormatProperties {
 				let vk_properties = unsafe {
 					device.instance().handle.get_physical_device_format_properties(
 						device.handle(),
@@ -81,266 +227,490 @@ macro_rules! formats {
 					)
 				};
 
-				FormatProperties {
-					linear_tiling_features: FormatFeatures::from_vulkan(vk_properties.linear_tiling_features),
-					optimal_tiling_features: FormatFeatures::from_vulkan(vk_properties.optimal_tiling_features),
-					buffer_features: FormatFeatures::from_vulkan(vk_properties.buffer_features),
-				}
+				FormatProperties::from_vulkan(vk_properties)
 			}
 		}
 	);
 }
 
 formats! {
-	R4G4UnormPack8 => R4G4_UNORM_PACK8 [(1, 1)] [Some(1)],
-	R4G4B4A4UnormPack16 => R4G4B4A4_UNORM_PACK16 [(1, 1)] [Some(2)],
-	B4G4R4A4UnormPack16 => B4G4R4A4_UNORM_PACK16 [(1, 1)] [Some(2)],
-	R5G6B5UnormPack16 => R5G6B5_UNORM_PACK16 [(1, 1)] [Some(2)],
-	B5G6R5UnormPack16 => B5G6R5_UNORM_PACK16 [(1, 1)] [Some(2)],
-	R5G5B5A1UnormPack16 => R5G5B5A1_UNORM_PACK16 [(1, 1)] [Some(2)],
-	B5G5R5A1UnormPack16 => B5G5R5A1_UNORM_PACK16 [(1, 1)] [Some(2)],
-	A1R5G5B5UnormPack16 => A1R5G5B5_UNORM_PACK16 [(1, 1)] [Some(2)],
-	R8Unorm => R8_UNORM [(1, 1)] [Some(1)],
-	R8Snorm => R8_SNORM [(1, 1)] [Some(1)],
-	R8Uscaled => R8_USCALED [(1, 1)] [Some(1)],
-	R8Sscaled => R8_SSCALED [(1, 1)] [Some(1)],
-	R8Uint => R8_UINT [(1, 1)] [Some(1)],
-	R8Sint => R8_SINT [(1, 1)] [Some(1)],
-	R8Srgb => R8_SRGB [(1, 1)] [Some(1)],
-	R8G8Unorm => R8G8_UNORM [(1, 1)] [Some(2)],
-	R8G8Snorm => R8G8_SNORM [(1, 1)] [Some(2)],
-	R8G8Uscaled => R8G8_USCALED [(1, 1)] [Some(2)],
-	R8G8Sscaled => R8G8_SSCALED [(1, 1)] [Some(2)],
-	R8G8Uint => R8G8_UINT [(1, 1)] [Some(2)],
-	R8G8Sint => R8G8_SINT [(1, 1)] [Some(2)],
-	R8G8Srgb => R8G8_SRGB [(1, 1)] [Some(2)],
-	R8G8B8Unorm => R8G8B8_UNORM [(1, 1)] [Some(3)],
-	R8G8B8Snorm => R8G8B8_SNORM [(1, 1)] [Some(3)],
-	R8G8B8Uscaled => R8G8B8_USCALED [(1, 1)] [Some(3)],
-	R8G8B8Sscaled => R8G8B8_SSCALED [(1, 1)] [Some(3)],
-	R8G8B8Uint => R8G8B8_UINT [(1, 1)] [Some(3)],
-	R8G8B8Sint => R8G8B8_SINT [(1, 1)] [Some(3)],
-	R8G8B8Srgb => R8G8B8_SRGB [(1, 1)] [Some(3)],
-	B8G8R8Unorm => B8G8R8_UNORM [(1, 1)] [Some(3)],
-	B8G8R8Snorm => B8G8R8_SNORM [(1, 1)] [Some(3)],
-	B8G8R8Uscaled => B8G8R8_USCALED [(1, 1)] [Some(3)],
-	B8G8R8Sscaled => B8G8R8_SSCALED [(1, 1)] [Some(3)],
-	B8G8R8Uint => B8G8R8_UINT [(1, 1)] [Some(3)],
-	B8G8R8Sint => B8G8R8_SINT [(1, 1)] [Some(3)],
-	B8G8R8Srgb => B8G8R8_SRGB [(1, 1)] [Some(3)],
-	R8G8B8A8Unorm => R8G8B8A8_UNORM [(1, 1)] [Some(4)],
-	R8G8B8A8Snorm => R8G8B8A8_SNORM [(1, 1)] [Some(4)],
-	R8G8B8A8Uscaled => R8G8B8A8_USCALED [(1, 1)] [Some(4)],
-	R8G8B8A8Sscaled => R8G8B8A8_SSCALED [(1, 1)] [Some(4)],
-	R8G8B8A8Uint => R8G8B8A8_UINT [(1, 1)] [Some(4)],
-	R8G8B8A8Sint => R8G8B8A8_SINT [(1, 1)] [Some(4)],
-	R8G8B8A8Srgb => R8G8B8A8_SRGB [(1, 1)] [Some(4)],
-	B8G8R8A8Unorm => B8G8R8A8_UNORM [(1, 1)] [Some(4)],
-	B8G8R8A8Snorm => B8G8R8A8_SNORM [(1, 1)] [Some(4)],
-	B8G8R8A8Uscaled => B8G8R8A8_USCALED [(1, 1)] [Some(4)],
-	B8G8R8A8Sscaled => B8G8R8A8_SSCALED [(1, 1)] [Some(4)],
-	B8G8R8A8Uint => B8G8R8A8_UINT [(1, 1)] [Some(4)],
-	B8G8R8A8Sint => B8G8R8A8_SINT [(1, 1)] [Some(4)],
-	B8G8R8A8Srgb => B8G8R8A8_SRGB [(1, 1)] [Some(4)],
-	A8B8G8R8UnormPack32 => A8B8G8R8_UNORM_PACK32 [(1, 1)] [Some(4)],
-	A8B8G8R8SnormPack32 => A8B8G8R8_SNORM_PACK32 [(1, 1)] [Some(4)],
-	A8B8G8R8UscaledPack32 => A8B8G8R8_USCALED_PACK32 [(1, 1)] [Some(4)],
-	A8B8G8R8SscaledPack32 => A8B8G8R8_SSCALED_PACK32 [(1, 1)] [Some(4)],
-	A8B8G8R8UintPack32 => A8B8G8R8_UINT_PACK32 [(1, 1)] [Some(4)],
-	A8B8G8R8SintPack32 => A8B8G8R8_SINT_PACK32 [(1, 1)] [Some(4)],
-	A8B8G8R8SrgbPack32 => A8B8G8R8_SRGB_PACK32 [(1, 1)] [Some(4)],
-	A2R10G10B10UnormPack32 => A2R10G10B10_UNORM_PACK32 [(1, 1)] [Some(4)],
-	A2R10G10B10SnormPack32 => A2R10G10B10_SNORM_PACK32 [(1, 1)] [Some(4)],
-	A2R10G10B10UscaledPack32 => A2R10G10B10_USCALED_PACK32 [(1, 1)] [Some(4)],
-	A2R10G10B10SscaledPack32 => A2R10G10B10_SSCALED_PACK32 [(1, 1)] [Some(4)],
-	A2R10G10B10UintPack32 => A2R10G10B10_UINT_PACK32 [(1, 1)] [Some(4)],
-	A2R10G10B10SintPack32 => A2R10G10B10_SINT_PACK32 [(1, 1)] [Some(4)],
-	A2B10G10R10UnormPack32 => A2B10G10R10_UNORM_PACK32 [(1, 1)] [Some(4)],
-	A2B10G10R10SnormPack32 => A2B10G10R10_SNORM_PACK32 [(1, 1)] [Some(4)],
-	A2B10G10R10UscaledPack32 => A2B10G10R10_USCALED_PACK32 [(1, 1)] [Some(4)],
-	A2B10G10R10SscaledPack32 => A2B10G10R10_SSCALED_PACK32 [(1, 1)] [Some(4)],
-	A2B10G10R10UintPack32 => A2B10G10R10_UINT_PACK32 [(1, 1)] [Some(4)],
-	A2B10G10R10SintPack32 => A2B10G10R10_SINT_PACK32 [(1, 1)] [Some(4)],
-	R16Unorm => R16_UNORM [(1, 1)] [Some(2)],
-	R16Snorm => R16_SNORM [(1, 1)] [Some(2)],
-	R16Uscaled => R16_USCALED [(1, 1)] [Some(2)],
-	R16Sscaled => R16_SSCALED [(1, 1)] [Some(2)],
-	R16Uint => R16_UINT [(1, 1)] [Some(2)],
-	R16Sint => R16_SINT [(1, 1)] [Some(2)],
-	R16Sfloat => R16_SFLOAT [(1, 1)] [Some(2)],
-	R16G16Unorm => R16G16_UNORM [(1, 1)] [Some(4)],
-	R16G16Snorm => R16G16_SNORM [(1, 1)] [Some(4)],
-	R16G16Uscaled => R16G16_USCALED [(1, 1)] [Some(4)],
-	R16G16Sscaled => R16G16_SSCALED [(1, 1)] [Some(4)],
-	R16G16Uint => R16G16_UINT [(1, 1)] [Some(4)],
-	R16G16Sint => R16G16_SINT [(1, 1)] [Some(4)],
-	R16G16Sfloat => R16G16_SFLOAT [(1, 1)] [Some(4)],
-	R16G16B16Unorm => R16G16B16_UNORM [(1, 1)] [Some(6)],
-	R16G16B16Snorm => R16G16B16_SNORM [(1, 1)] [Some(6)],
-	R16G16B16Uscaled => R16G16B16_USCALED [(1, 1)] [Some(6)],
-	R16G16B16Sscaled => R16G16B16_SSCALED [(1, 1)] [Some(6)],
-	R16G16B16Uint => R16G16B16_UINT [(1, 1)] [Some(6)],
-	R16G16B16Sint => R16G16B16_SINT [(1, 1)] [Some(6)],
-	R16G16B16Sfloat => R16G16B16_SFLOAT [(1, 1)] [Some(6)],
-	R16G16B16A16Unorm => R16G16B16A16_UNORM [(1, 1)] [Some(8)],
-	R16G16B16A16Snorm => R16G16B16A16_SNORM [(1, 1)] [Some(8)],
-	R16G16B16A16Uscaled => R16G16B16A16_USCALED [(1, 1)] [Some(8)],
-	R16G16B16A16Sscaled => R16G16B16A16_SSCALED [(1, 1)] [Some(8)],
-	R16G16B16A16Uint => R16G16B16A16_UINT [(1, 1)] [Some(8)],
-	R16G16B16A16Sint => R16G16B16A16_SINT [(1, 1)] [Some(8)],
-	R16G16B16A16Sfloat => R16G16B16A16_SFLOAT [(1, 1)] [Some(8)],
-	R32Uint => R32_UINT [(1, 1)] [Some(4)],
-	R32Sint => R32_SINT [(1, 1)] [Some(4)],
-	R32Sfloat => R32_SFLOAT [(1, 1)] [Some(4)],
-	R32G32Uint => R32G32_UINT [(1, 1)] [Some(8)],
-	R32G32Sint => R32G32_SINT [(1, 1)] [Some(8)],
-	R32G32Sfloat => R32G32_SFLOAT [(1, 1)] [Some(8)],
-	R32G32B32Uint => R32G32B32_UINT [(1, 1)] [Some(12)],
-	R32G32B32Sint => R32G32B32_SINT [(1, 1)] [Some(12)],
-	R32G32B32Sfloat => R32G32B32_SFLOAT [(1, 1)] [Some(12)],
-	R32G32B32A32Uint => R32G32B32A32_UINT [(1, 1)] [Some(16)],
-	R32G32B32A32Sint => R32G32B32A32_SINT [(1, 1)] [Some(16)],
-	R32G32B32A32Sfloat => R32G32B32A32_SFLOAT [(1, 1)] [Some(16)],
-	R64Uint => R64_UINT [(1, 1)] [Some(8)],
-	R64Sint => R64_SINT [(1, 1)] [Some(8)],
-	R64Sfloat => R64_SFLOAT [(1, 1)] [Some(8)],
-	R64G64Uint => R64G64_UINT [(1, 1)] [Some(16)],
-	R64G64Sint => R64G64_SINT [(1, 1)] [Some(16)],
-	R64G64Sfloat => R64G64_SFLOAT [(1, 1)] [Some(16)],
-	R64G64B64Uint => R64G64B64_UINT [(1, 1)] [Some(24)],
-	R64G64B64Sint => R64G64B64_SINT [(1, 1)] [Some(24)],
-	R64G64B64Sfloat => R64G64B64_SFLOAT [(1, 1)] [Some(24)],
-	R64G64B64A64Uint => R64G64B64A64_UINT [(1, 1)] [Some(32)],
-	R64G64B64A64Sint => R64G64B64A64_SINT [(1, 1)] [Some(32)],
-	R64G64B64A64Sfloat => R64G64B64A64_SFLOAT [(1, 1)] [Some(32)],
-	B10G11R11UfloatPack32 => B10G11R11_UFLOAT_PACK32 [(1, 1)] [Some(4)],
-	E5B9G9R9UfloatPack32 => E5B9G9R9_UFLOAT_PACK32 [(1, 1)] [Some(4)],
-	D16Unorm => D16_UNORM [(1, 1)] [Some(2)],
-	X8_D24UnormPack32 => X8_D24_UNORM_PACK32 [(1, 1)] [Some(4)],
-	D32Sfloat => D32_SFLOAT [(1, 1)] [Some(4)],
-	S8Uint => S8_UINT [(1, 1)] [Some(1)],
-	D16Unorm_S8Uint => D16_UNORM_S8_UINT [(1, 1)] [None],
-	D24Unorm_S8Uint => D24_UNORM_S8_UINT [(1, 1)] [None],
-	D32Sfloat_S8Uint => D32_SFLOAT_S8_UINT [(1, 1)] [None],
-	BC1_RGBUnormBlock => BC1_RGB_UNORM_BLOCK [(4, 4)] [Some(8)],
-	BC1_RGBSrgbBlock => BC1_RGB_SRGB_BLOCK [(4, 4)] [Some(8)],
-	BC1_RGBAUnormBlock => BC1_RGBA_UNORM_BLOCK [(4, 4)] [Some(8)],
-	BC1_RGBASrgbBlock => BC1_RGBA_SRGB_BLOCK [(4, 4)] [Some(8)],
-	BC2UnormBlock => BC2_UNORM_BLOCK [(4, 4)] [Some(16)],
-	BC2SrgbBlock => BC2_SRGB_BLOCK [(4, 4)] [Some(16)],
-	BC3UnormBlock => BC3_UNORM_BLOCK [(4, 4)] [Some(16)],
-	BC3SrgbBlock => BC3_SRGB_BLOCK [(4, 4)] [Some(16)],
-	BC4UnormBlock => BC4_UNORM_BLOCK [(4, 4)] [Some(8)],
-	BC4SnormBlock => BC4_SNORM_BLOCK [(4, 4)] [Some(8)],
-	BC5UnormBlock => BC5_UNORM_BLOCK [(4, 4)] [Some(16)],
-	BC5SnormBlock => BC5_SNORM_BLOCK [(4, 4)] [Some(16)],
-	BC6HUfloatBlock => BC6H_UFLOAT_BLOCK [(4, 4)] [Some(16)],
-	BC6HSfloatBlock => BC6H_SFLOAT_BLOCK [(4, 4)] [Some(16)],
-	BC7UnormBlock => BC7_UNORM_BLOCK [(4, 4)] [Some(16)],
-	BC7SrgbBlock => BC7_SRGB_BLOCK [(4, 4)] [Some(16)],
-	ETC2_R8G8B8UnormBlock => ETC2_R8G8B8_UNORM_BLOCK [(4, 4)] [Some(8)],
-	ETC2_R8G8B8SrgbBlock => ETC2_R8G8B8_SRGB_BLOCK [(4, 4)] [Some(8)],
-	ETC2_R8G8B8A1UnormBlock => ETC2_R8G8B8A1_UNORM_BLOCK [(4, 4)] [Some(8)],
-	ETC2_R8G8B8A1SrgbBlock => ETC2_R8G8B8A1_SRGB_BLOCK [(4, 4)] [Some(8)],
-	ETC2_R8G8B8A8UnormBlock => ETC2_R8G8B8A8_UNORM_BLOCK [(4, 4)] [Some(16)],
-	ETC2_R8G8B8A8SrgbBlock => ETC2_R8G8B8A8_SRGB_BLOCK [(4, 4)] [Some(16)],
-	EAC_R11UnormBlock => EAC_R11_UNORM_BLOCK [(4, 4)] [Some(8)],
-	EAC_R11SnormBlock => EAC_R11_SNORM_BLOCK [(4, 4)] [Some(8)],
-	EAC_R11G11UnormBlock => EAC_R11G11_UNORM_BLOCK [(4, 4)] [Some(16)],
-	EAC_R11G11SnormBlock => EAC_R11G11_SNORM_BLOCK [(4, 4)] [Some(16)],
-	ASTC_4x4UnormBlock => ASTC_4X4_UNORM_BLOCK [(4, 4)] [Some(16)],
-	ASTC_4x4SrgbBlock => ASTC_4X4_SRGB_BLOCK [(4, 4)] [Some(16)],
-	ASTC_5x4UnormBlock => ASTC_5X4_UNORM_BLOCK [(5, 4)] [Some(16)],
-	ASTC_5x4SrgbBlock => ASTC_5X4_SRGB_BLOCK [(5, 4)] [Some(16)],
-	ASTC_5x5UnormBlock => ASTC_5X5_UNORM_BLOCK [(5, 5)] [Some(16)],
-	ASTC_5x5SrgbBlock => ASTC_5X5_SRGB_BLOCK [(5, 5)] [Some(16)],
-	ASTC_6x5UnormBlock => ASTC_6X5_UNORM_BLOCK [(6, 5)] [Some(16)],
-	ASTC_6x5SrgbBlock => ASTC_6X5_SRGB_BLOCK [(6, 5)] [Some(16)],
-	ASTC_6x6UnormBlock => ASTC_6X6_UNORM_BLOCK [(6, 6)] [Some(16)],
-	ASTC_6x6SrgbBlock => ASTC_6X6_SRGB_BLOCK [(6, 6)] [Some(16)],
-	ASTC_8x5UnormBlock => ASTC_8X5_UNORM_BLOCK [(8, 5)] [Some(16)],
-	ASTC_8x5SrgbBlock => ASTC_8X5_SRGB_BLOCK [(8, 5)] [Some(16)],
-	ASTC_8x6UnormBlock => ASTC_8X6_UNORM_BLOCK [(8, 6)] [Some(16)],
-	ASTC_8x6SrgbBlock => ASTC_8X6_SRGB_BLOCK [(8, 6)] [Some(16)],
-	ASTC_8x8UnormBlock => ASTC_8X8_UNORM_BLOCK [(8, 8)] [Some(16)],
-	ASTC_8x8SrgbBlock => ASTC_8X8_SRGB_BLOCK [(8, 8)] [Some(16)],
-	ASTC_10x5UnormBlock => ASTC_10X5_UNORM_BLOCK [(10, 5)] [Some(16)],
-	ASTC_10x5SrgbBlock => ASTC_10X5_SRGB_BLOCK [(10, 5)] [Some(16)],
-	ASTC_10x6UnormBlock => ASTC_10X6_UNORM_BLOCK [(10, 6)] [Some(16)],
-	ASTC_10x6SrgbBlock => ASTC_10X6_SRGB_BLOCK [(10, 6)] [Some(16)],
-	ASTC_10x8UnormBlock => ASTC_10X8_UNORM_BLOCK [(10, 8)] [Some(16)],
-	ASTC_10x8SrgbBlock => ASTC_10X8_SRGB_BLOCK [(10, 8)] [Some(16)],
-	ASTC_10x10UnormBlock => ASTC_10X10_UNORM_BLOCK [(10, 10)] [Some(16)],
-	ASTC_10x10SrgbBlock => ASTC_10X10_SRGB_BLOCK [(10, 10)] [Some(16)],
-	ASTC_12x10UnormBlock => ASTC_12X10_UNORM_BLOCK [(12, 10)] [Some(16)],
-	ASTC_12x10SrgbBlock => ASTC_12X10_SRGB_BLOCK [(12, 10)] [Some(16)],
-	ASTC_12x12UnormBlock => ASTC_12X12_UNORM_BLOCK [(12, 12)] [Some(16)],
-	ASTC_12x12SrgbBlock => ASTC_12X12_SRGB_BLOCK [(12, 12)] [Some(16)]
+	R4G4UnormPack8 => R4G4_UNORM_PACK8 [Unorm] [(1, 1)] [Some(1)],
+	R4G4B4A4UnormPack16 => R4G4B4A4_UNORM_PACK16 [Unorm] [(1, 1)] [Some(2)],
+	B4G4R4A4UnormPack16 => B4G4R4A4_UNORM_PACK16 [Unorm] [(1, 1)] [Some(2)],
+	R5G6B5UnormPack16 => R5G6B5_UNORM_PACK16 [Unorm] [(1, 1)] [Some(2)],
+	B5G6R5UnormPack16 => B5G6R5_UNORM_PACK16 [Unorm] [(1, 1)] [Some(2)],
+	R5G5B5A1UnormPack16 => R5G5B5A1_UNORM_PACK16 [Unorm] [(1, 1)] [Some(2)],
+	B5G5R5A1UnormPack16 => B5G5R5A1_UNORM_PACK16 [Unorm] [(1, 1)] [Some(2)],
+	A1R5G5B5UnormPack16 => A1R5G5B5_UNORM_PACK16 [Unorm] [(1, 1)] [Some(2)],
+	R8Unorm => R8_UNORM [Unorm] [(1, 1)] [Some(1)],
+	R8Snorm => R8_SNORM [Snorm] [(1, 1)] [Some(1)],
+	R8Uscaled => R8_USCALED [Uscaled] [(1, 1)] [Some(1)],
+	R8Sscaled => R8_SSCALED [Sscaled] [(1, 1)] [Some(1)],
+	R8Uint => R8_UINT [Uint] [(1, 1)] [Some(1)],
+	R8Sint => R8_SINT [Sint] [(1, 1)] [Some(1)],
+	R8Srgb => R8_SRGB [Srgb] [(1, 1)] [Some(1)],
+	R8G8Unorm => R8G8_UNORM [Unorm] [(1, 1)] [Some(2)],
+	R8G8Snorm => R8G8_SNORM [Snorm] [(1, 1)] [Some(2)],
+	R8G8Uscaled => R8G8_USCALED [Uscaled] [(1, 1)] [Some(2)],
+	R8G8Sscaled => R8G8_SSCALED [Sscaled] [(1, 1)] [Some(2)],
+	R8G8Uint => R8G8_UINT [Uint] [(1, 1)] [Some(2)],
+	R8G8Sint => R8G8_SINT [Sint] [(1, 1)] [Some(2)],
+	R8G8Srgb => R8G8_SRGB [Srgb] [(1, 1)] [Some(2)],
+	R8G8B8Unorm => R8G8B8_UNORM [Unorm] [(1, 1)] [Some(3)],
+	R8G8B8Snorm => R8G8B8_SNORM [Snorm] [(1, 1)] [Some(3)],
+	R8G8B8Uscaled => R8G8B8_USCALED [Uscaled] [(1, 1)] [Some(3)],
+	R8G8B8Sscaled => R8G8B8_SSCALED [Sscaled] [(1, 1)] [Some(3)],
+	R8G8B8Uint => R8G8B8_UINT [Uint] [(1, 1)] [Some(3)],
+	R8G8B8Sint => R8G8B8_SINT [Sint] [(1, 1)] [Some(3)],
+	R8G8B8Srgb => R8G8B8_SRGB [Srgb] [(1, 1)] [Some(3)],
+	B8G8R8Unorm => B8G8R8_UNORM [Unorm] [(1, 1)] [Some(3)],
+	B8G8R8Snorm => B8G8R8_SNORM [Snorm] [(1, 1)] [Some(3)],
+	B8G8R8Uscaled => B8G8R8_USCALED [Uscaled] [(1, 1)] [Some(3)],
+	B8G8R8Sscaled => B8G8R8_SSCALED [Sscaled] [(1, 1)] [Some(3)],
+	B8G8R8Uint => B8G8R8_UINT [Uint] [(1, 1)] [Some(3)],
+	B8G8R8Sint => B8G8R8_SINT [Sint] [(1, 1)] [Some(3)],
+	B8G8R8Srgb => B8G8R8_SRGB [Srgb] [(1, 1)] [Some(3)],
+	R8G8B8A8Unorm => R8G8B8A8_UNORM [Unorm] [(1, 1)] [Some(4)],
+	R8G8B8A8Snorm => R8G8B8A8_SNORM [Snorm] [(1, 1)] [Some(4)],
+	R8G8B8A8Uscaled => R8G8B8A8_USCALED [Uscaled] [(1, 1)] [Some(4)],
+	R8G8B8A8Sscaled => R8G8B8A8_SSCALED [Sscaled] [(1, 1)] [Some(4)],
+	R8G8B8A8Uint => R8G8B8A8_UINT [Uint] [(1, 1)] [Some(4)],
+	R8G8B8A8Sint => R8G8B8A8_SINT [Sint] [(1, 1)] [Some(4)],
+	R8G8B8A8Srgb => R8G8B8A8_SRGB [Srgb] [(1, 1)] [Some(4)],
+	B8G8R8A8Unorm => B8G8R8A8_UNORM [Unorm] [(1, 1)] [Some(4)],
+	B8G8R8A8Snorm => B8G8R8A8_SNORM [Snorm] [(1, 1)] [Some(4)],
+	B8G8R8A8Uscaled => B8G8R8A8_USCALED [Uscaled] [(1, 1)] [Some(4)],
+	B8G8R8A8Sscaled => B8G8R8A8_SSCALED [Sscaled] [(1, 1)] [Some(4)],
+	B8G8R8A8Uint => B8G8R8A8_UINT [Uint] [(1, 1)] [Some(4)],
+	B8G8R8A8Sint => B8G8R8A8_SINT [Sint] [(1, 1)] [Some(4)],
+	B8G8R8A8Srgb => B8G8R8A8_SRGB [Srgb] [(1, 1)] [Some(4)],
+	A8B8G8R8UnormPack32 => A8B8G8R8_UNORM_PACK32 [Unorm] [(1, 1)] [Some(4)],
+	A8B8G8R8SnormPack32 => A8B8G8R8_SNORM_PACK32 [Snorm] [(1, 1)] [Some(4)],
+	A8B8G8R8UscaledPack32 => A8B8G8R8_USCALED_PACK32 [Uscaled] [(1, 1)] [Some(4)],
+	A8B8G8R8SscaledPack32 => A8B8G8R8_SSCALED_PACK32 [Sscaled] [(1, 1)] [Some(4)],
+	A8B8G8R8UintPack32 => A8B8G8R8_UINT_PACK32 [Uint] [(1, 1)] [Some(4)],
+	A8B8G8R8SintPack32 => A8B8G8R8_SINT_PACK32 [Sint] [(1, 1)] [Some(4)],
+	A8B8G8R8SrgbPack32 => A8B8G8R8_SRGB_PACK32 [Srgb] [(1, 1)] [Some(4)],
+	A2R10G10B10UnormPack32 => A2R10G10B10_UNORM_PACK32 [Unorm] [(1, 1)] [Some(4)],
+	A2R10G10B10SnormPack32 => A2R10G10B10_SNORM_PACK32 [Snorm] [(1, 1)] [Some(4)],
+	A2R10G10B10UscaledPack32 => A2R10G10B10_USCALED_PACK32 [Uscaled] [(1, 1)] [Some(4)],
+	A2R10G10B10SscaledPack32 => A2R10G10B10_SSCALED_PACK32 [Sscaled] [(1, 1)] [Some(4)],
+	A2R10G10B10UintPack32 => A2R10G10B10_UINT_PACK32 [Uint] [(1, 1)] [Some(4)],
+	A2R10G10B10SintPack32 => A2R10G10B10_SINT_PACK32 [Sint] [(1, 1)] [Some(4)],
+	A2B10G10R10UnormPack32 => A2B10G10R10_UNORM_PACK32 [Unorm] [(1, 1)] [Some(4)],
+	A2B10G10R10SnormPack32 => A2B10G10R10_SNORM_PACK32 [Snorm] [(1, 1)] [Some(4)],
+	A2B10G10R10UscaledPack32 => A2B10G10R10_USCALED_PACK32 [Uscaled] [(1, 1)] [Some(4)],
+	A2B10G10R10SscaledPack32 => A2B10G10R10_SSCALED_PACK32 [Sscaled] [(1, 1)] [Some(4)],
+	A2B10G10R10UintPack32 => A2B10G10R10_UINT_PACK32 [Uint] [(1, 1)] [Some(4)],
+	A2B10G10R10SintPack32 => A2B10G10R10_SINT_PACK32 [Sint] [(1, 1)] [Some(4)],
+	R16Unorm => R16_UNORM [Unorm] [(1, 1)] [Some(2)],
+	R16Snorm => R16_SNORM [Snorm] [(1, 1)] [Some(2)],
+	R16Uscaled => R16_USCALED [Uscaled] [(1, 1)] [Some(2)],
+	R16Sscaled => R16_SSCALED [Sscaled] [(1, 1)] [Some(2)],
+	R16Uint => R16_UINT [Uint] [(1, 1)] [Some(2)],
+	R16Sint => R16_SINT [Sint] [(1, 1)] [Some(2)],
+	R16Sfloat => R16_SFLOAT [Sfloat] [(1, 1)] [Some(2)],
+	R16G16Unorm => R16G16_UNORM [Unorm] [(1, 1)] [Some(4)],
+	R16G16Snorm => R16G16_SNORM [Snorm] [(1, 1)] [Some(4)],
+	R16G16Uscaled => R16G16_USCALED [Uscaled] [(1, 1)] [Some(4)],
+	R16G16Sscaled => R16G16_SSCALED [Sscaled] [(1, 1)] [Some(4)],
+	R16G16Uint => R16G16_UINT [Uint] [(1, 1)] [Some(4)],
+	R16G16Sint => R16G16_SINT [Sint] [(1, 1)] [Some(4)],
+	R16G16Sfloat => R16G16_SFLOAT [Sfloat] [(1, 1)] [Some(4)],
+	R16G16B16Unorm => R16G16B16_UNORM [Unorm] [(1, 1)] [Some(6)],
+	R16G16B16Snorm => R16G16B16_SNORM [Snorm] [(1, 1)] [Some(6)],
+	R16G16B16Uscaled => R16G16B16_USCALED [Uscaled] [(1, 1)] [Some(6)],
+	R16G16B16Sscaled => R16G16B16_SSCALED [Sscaled] [(1, 1)] [Some(6)],
+	R16G16B16Uint => R16G16B16_UINT [Uint] [(1, 1)] [Some(6)],
+	R16G16B16Sint => R16G16B16_SINT [Sint] [(1, 1)] [Some(6)],
+	R16G16B16Sfloat => R16G16B16_SFLOAT [Sfloat] [(1, 1)] [Some(6)],
+	R16G16B16A16Unorm => R16G16B16A16_UNORM [Unorm] [(1, 1)] [Some(8)],
+	R16G16B16A16Snorm => R16G16B16A16_SNORM [Snorm] [(1, 1)] [Some(8)],
+	R16G16B16A16Uscaled => R16G16B16A16_USCALED [Uscaled] [(1, 1)] [Some(8)],
+	R16G16B16A16Sscaled => R16G16B16A16_SSCALED [Sscaled] [(1, 1)] [Some(8)],
+	R16G16B16A16Uint => R16G16B16A16_UINT [Uint] [(1, 1)] [Some(8)],
+	R16G16B16A16Sint => R16G16B16A16_SINT [Sint] [(1, 1)] [Some(8)],
+	R16G16B16A16Sfloat => R16G16B16A16_SFLOAT [Sfloat] [(1, 1)] [Some(8)],
+	R32Uint => R32_UINT [Uint] [(1, 1)] [Some(4)],
+	R32Sint => R32_SINT [Sint] [(1, 1)] [Some(4)],
+	R32Sfloat => R32_SFLOAT [Sfloat] [(1, 1)] [Some(4)],
+	R32G32Uint => R32G32_UINT [Uint] [(1, 1)] [Some(8)],
+	R32G32Sint => R32G32_SINT [Sint] [(1, 1)] [Some(8)],
+	R32G32Sfloat => R32G32_SFLOAT [Sfloat] [(1, 1)] [Some(8)],
+	R32G32B32Uint => R32G32B32_UINT [Uint] [(1, 1)] [Some(12)],
+	R32G32B32Sint => R32G32B32_SINT [Sint] [(1, 1)] [Some(12)],
+	R32G32B32Sfloat => R32G32B32_SFLOAT [Sfloat] [(1, 1)] [Some(12)],
+	R32G32B32A32Uint => R32G32B32A32_UINT [Uint] [(1, 1)] [Some(16)],
+	R32G32B32A32Sint => R32G32B32A32_SINT [Sint] [(1, 1)] [Some(16)],
+	R32G32B32A32Sfloat => R32G32B32A32_SFLOAT [Sfloat] [(1, 1)] [Some(16)],
+	R64Uint => R64_UINT [Uint] [(1, 1)] [Some(8)],
+	R64Sint => R64_SINT [Sint] [(1, 1)] [Some(8)],
+	R64Sfloat => R64_SFLOAT [Sfloat] [(1, 1)] [Some(8)],
+	R64G64Uint => R64G64_UINT [Uint] [(1, 1)] [Some(16)],
+	R64G64Sint => R64G64_SINT [Sint] [(1, 1)] [Some(16)],
+	R64G64Sfloat => R64G64_SFLOAT [Sfloat] [(1, 1)] [Some(16)],
+	R64G64B64Uint => R64G64B64_UINT [Uint] [(1, 1)] [Some(24)],
+	R64G64B64Sint => R64G64B64_SINT [Sint] [(1, 1)] [Some(24)],
+	R64G64B64Sfloat => R64G64B64_SFLOAT [Sfloat] [(1, 1)] [Some(24)],
+	R64G64B64A64Uint => R64G64B64A64_UINT [Uint] [(1, 1)] [Some(32)],
+	R64G64B64A64Sint => R64G64B64A64_SINT [Sint] [(1, 1)] [Some(32)],
+	R64G64B64A64Sfloat => R64G64B64A64_SFLOAT [Sfloat] [(1, 1)] [Some(32)],
+	B10G11R11UfloatPack32 => B10G11R11_UFLOAT_PACK32 [Ufloat] [(1, 1)] [Some(4)],
+	E5B9G9R9UfloatPack32 => E5B9G9R9_UFLOAT_PACK32 [Ufloat] [(1, 1)] [Some(4)],
+	D16Unorm => D16_UNORM [Unorm] [(1, 1)] [Some(2)],
+	X8_D24UnormPack32 => X8_D24_UNORM_PACK32 [Unorm] [(1, 1)] [Some(4)],
+	D32Sfloat => D32_SFLOAT [Sfloat] [(1, 1)] [Some(4)],
+	S8Uint => S8_UINT [Uint] [(1, 1)] [Some(1)],
+	D16Unorm_S8Uint => D16_UNORM_S8_UINT [Unorm] [(1, 1)] [None],
+	D24Unorm_S8Uint => D24_UNORM_S8_UINT [Unorm] [(1, 1)] [None],
+	D32Sfloat_S8Uint => D32_SFLOAT_S8_UINT [Sfloat] [(1, 1)] [None],
+	BC1_RGBUnormBlock => BC1_RGB_UNORM_BLOCK [Unorm] [(4, 4)] [Some(8)],
+	BC1_RGBSrgbBlock => BC1_RGB_SRGB_BLOCK [Srgb] [(4, 4)] [Some(8)],
+	BC1_RGBAUnormBlock => BC1_RGBA_UNORM_BLOCK [Unorm] [(4, 4)] [Some(8)],
+	BC1_RGBASrgbBlock => BC1_RGBA_SRGB_BLOCK [Srgb] [(4, 4)] [Some(8)],
+	BC2UnormBlock => BC2_UNORM_BLOCK [Unorm] [(4, 4)] [Some(16)],
+	BC2SrgbBlock => BC2_SRGB_BLOCK [Srgb] [(4, 4)] [Some(16)],
+	BC3UnormBlock => BC3_UNORM_BLOCK [Unorm] [(4, 4)] [Some(16)],
+	BC3SrgbBlock => BC3_SRGB_BLOCK [Srgb] [(4, 4)] [Some(16)],
+	BC4UnormBlock => BC4_UNORM_BLOCK [Unorm] [(4, 4)] [Some(8)],
+	BC4SnormBlock => BC4_SNORM_BLOCK [Snorm] [(4, 4)] [Some(8)],
+	BC5UnormBlock => BC5_UNORM_BLOCK [Unorm] [(4, 4)] [Some(16)],
+	BC5SnormBlock => BC5_SNORM_BLOCK [Snorm] [(4, 4)] [Some(16)],
+	BC6HUfloatBlock => BC6H_UFLOAT_BLOCK [Ufloat] [(4, 4)] [Some(16)],
+	BC6HSfloatBlock => BC6H_SFLOAT_BLOCK [Sfloat] [(4, 4)] [Some(16)],
+	BC7UnormBlock => BC7_UNORM_BLOCK [Unorm] [(4, 4)] [Some(16)],
+	BC7SrgbBlock => BC7_SRGB_BLOCK [Srgb] [(4, 4)] [Some(16)],
+	ETC2_R8G8B8UnormBlock => ETC2_R8G8B8_UNORM_BLOCK [Unorm] [(4, 4)] [Some(8)],
+	ETC2_R8G8B8SrgbBlock => ETC2_R8G8B8_SRGB_BLOCK [Srgb] [(4, 4)] [Some(8)],
+	ETC2_R8G8B8A1UnormBlock => ETC2_R8G8B8A1_UNORM_BLOCK [Unorm] [(4, 4)] [Some(8)],
+	ETC2_R8G8B8A1SrgbBlock => ETC2_R8G8B8A1_SRGB_BLOCK [Srgb] [(4, 4)] [Some(8)],
+	ETC2_R8G8B8A8UnormBlock => ETC2_R8G8B8A8_UNORM_BLOCK [Unorm] [(4, 4)] [Some(16)],
+	ETC2_R8G8B8A8SrgbBlock => ETC2_R8G8B8A8_SRGB_BLOCK [Srgb] [(4, 4)] [Some(16)],
+	EAC_R11UnormBlock => EAC_R11_UNORM_BLOCK [Unorm] [(4, 4)] [Some(8)],
+	EAC_R11SnormBlock => EAC_R11_SNORM_BLOCK [Snorm] [(4, 4)] [Some(8)],
+	EAC_R11G11UnormBlock => EAC_R11G11_UNORM_BLOCK [Unorm] [(4, 4)] [Some(16)],
+	EAC_R11G11SnormBlock => EAC_R11G11_SNORM_BLOCK [Snorm] [(4, 4)] [Some(16)],
+	ASTC_4x4UnormBlock => ASTC_4X4_UNORM_BLOCK [Unorm] [(4, 4)] [Some(16)],
+	ASTC_4x4SrgbBlock => ASTC_4X4_SRGB_BLOCK [Srgb] [(4, 4)] [Some(16)],
+	ASTC_5x4UnormBlock => ASTC_5X4_UNORM_BLOCK [Unorm] [(5, 4)] [Some(16)],
+	ASTC_5x4SrgbBlock => ASTC_5X4_SRGB_BLOCK [Srgb] [(5, 4)] [Some(16)],
+	ASTC_5x5UnormBlock => ASTC_5X5_UNORM_BLOCK [Unorm] [(5, 5)] [Some(16)],
+	ASTC_5x5SrgbBlock => ASTC_5X5_SRGB_BLOCK [Srgb] [(5, 5)] [Some(16)],
+	ASTC_6x5UnormBlock => ASTC_6X5_UNORM_BLOCK [Unorm] [(6, 5)] [Some(16)],
+	ASTC_6x5SrgbBlock => ASTC_6X5_SRGB_BLOCK [Srgb] [(6, 5)] [Some(16)],
+	ASTC_6x6UnormBlock => ASTC_6X6_UNORM_BLOCK [Unorm] [(6, 6)] [Some(16)],
+	ASTC_6x6SrgbBlock => ASTC_6X6_SRGB_BLOCK [Srgb] [(6, 6)] [Some(16)],
+	ASTC_8x5UnormBlock => ASTC_8X5_UNORM_BLOCK [Unorm] [(8, 5)] [Some(16)],
+	ASTC_8x5SrgbBlock => ASTC_8X5_SRGB_BLOCK [Srgb] [(8, 5)] [Some(16)],
+	ASTC_8x6UnormBlock => ASTC_8X6_UNORM_BLOCK [Unorm] [(8, 6)] [Some(16)],
+	ASTC_8x6SrgbBlock => ASTC_8X6_SRGB_BLOCK [Srgb] [(8, 6)] [Some(16)],
+	ASTC_8x8UnormBlock => ASTC_8X8_UNORM_BLOCK [Unorm] [(8, 8)] [Some(16)],
+	ASTC_8x8SrgbBlock => ASTC_8X8_SRGB_BLOCK [Srgb] [(8, 8)] [Some(16)],
+	ASTC_10x5UnormBlock => ASTC_10X5_UNORM_BLOCK [Unorm] [(10, 5)] [Some(16)],
+	ASTC_10x5SrgbBlock => ASTC_10X5_SRGB_BLOCK [Srgb] [(10, 5)] [Some(16)],
+	ASTC_10x6UnormBlock => ASTC_10X6_UNORM_BLOCK [Unorm] [(10, 6)] [Some(16)],
+	ASTC_10x6SrgbBlock => ASTC_10X6_SRGB_BLOCK [Srgb] [(10, 6)] [Some(16)],
+	ASTC_10x8UnormBlock => ASTC_10X8_UNORM_BLOCK [Unorm] [(10, 8)] [Some(16)],
+	ASTC_10x8SrgbBlock => ASTC_10X8_SRGB_BLOCK [Srgb] [(10, 8)] [Some(16)],
+	ASTC_10x10UnormBlock => ASTC_10X10_UNORM_BLOCK [Unorm] [(10, 10)] [Some(16)],
+	ASTC_10x10SrgbBlock => ASTC_10X10_SRGB_BLOCK [Srgb] [(10, 10)] [Some(16)],
+	ASTC_12x10UnormBlock => ASTC_12X10_UNORM_BLOCK [Unorm] [(12, 10)] [Some(16)],
+	ASTC_12x10SrgbBlock => ASTC_12X10_SRGB_BLOCK [Srgb] [(12, 10)] [Some(16)],
+	ASTC_12x12UnormBlock => ASTC_12X12_UNORM_BLOCK [Unorm] [(12, 12)] [Some(16)],
+	ASTC_12x12SrgbBlock => ASTC_12X12_SRGB_BLOCK [Srgb] [(12, 12)] [Some(16)]
 }
 
-/// The features supported by images with a particular format.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
-#[allow(missing_docs)]
-pub struct FormatFeatures {
-	pub sampled_image: bool,
-	pub storage_image: bool,
-	pub storage_image_atomic: bool,
-	pub uniform_texel_buffer: bool,
-	pub storage_texel_buffer: bool,
-	pub storage_texel_buffer_atomic: bool,
-	pub vertex_buffer: bool,
-	pub color_attachment: bool,
-	pub color_attachment_blend: bool,
-	pub depth_stencil_attachment: bool,
-	pub blit_src: bool,
-	pub blit_dst: bool,
-	pub sampled_image_filter_linear: bool,
-	pub transfer_src: bool,
-	pub transfer_dst: bool,
-	pub midpoint_chroma_samples: bool,
-	pub sampled_image_ycbcr_conversion_linear_filter: bool,
-	pub sampled_image_ycbcr_conversion_separate_reconstruction_filter: bool,
-	pub sampled_image_ycbcr_conversion_chroma_reconstruction_explicit: bool,
-	pub sampled_image_ycbcr_conversion_chroma_reconstruction_explicit_forceable: bool,
-	pub disjoint: bool,
-	pub cosited_chroma_samples: bool,
-	pub sampled_image_filter_minmax: bool,
-	pub img_sampled_image_filter_cubic: bool,
-	pub khr_acceleration_structure_vertex_buffer: bool,
-	pub ext_fragment_density_map: bool,
+macro_rules! srgb_pairs {
+	($($unorm:ident <=> $srgb:ident),+) => (
+		impl Format {
+			/// Returns the sRGB format with the same layout as this UNORM format, if any.
+			///
+			/// sRGB formats are their own sRGB equivalent.
+			#[inline]
+			pub const fn srgb_equivalent(&self) -> Option<Format> {
+				match *self {
+					$(
+						Format::$unorm | Format::$srgb => Some(Format::$srgb),
+					)+
+					_ => None
+				}
+			}
+
+			/// Returns the UNORM format with the same layout as this sRGB format, if any.
+			///
+			/// UNORM formats having an sRGB equivalent are their own UNORM equivalent.
+			#[inline]
+			pub const fn unorm_equivalent(&self) -> Option<Format> {
+				match *self {
+					$(
+						Format::$unorm | Format::$srgb => Some(Format::$unorm),
+					)+
+					_ => None
+				}
+			}
+		}
+	);
+}
+
+srgb_pairs! {
+	R8Unorm <=> R8Srgb,
+	R8G8Unorm <=> R8G8Srgb,
+	R8G8B8Unorm <=> R8G8B8Srgb,
+	B8G8R8Unorm <=> B8G8R8Srgb,
+	R8G8B8A8Unorm <=> R8G8B8A8Srgb,
+	B8G8R8A8Unorm <=> B8G8R8A8Srgb,
+	A8B8G8R8UnormPack32 <=> A8B8G8R8SrgbPack32,
+	BC1_RGBUnormBlock <=> BC1_RGBSrgbBlock,
+	BC1_RGBAUnormBlock <=> BC1_RGBASrgbBlock,
+	BC2UnormBlock <=> BC2SrgbBlock,
+	BC3UnormBlock <=> BC3SrgbBlock,
+	BC7UnormBlock <=> BC7SrgbBlock,
+	ETC2_R8G8B8UnormBlock <=> ETC2_R8G8B8SrgbBlock,
+	ETC2_R8G8B8A1UnormBlock <=> ETC2_R8G8B8A1SrgbBlock,
+	ETC2_R8G8B8A8UnormBlock <=> ETC2_R8G8B8A8SrgbBlock,
+	ASTC_4x4UnormBlock <=> ASTC_4x4SrgbBlock,
+	ASTC_5x4UnormBlock <=> ASTC_5x4SrgbBlock,
+	ASTC_5x5UnormBlock <=> ASTC_5x5SrgbBlock,
+	ASTC_6x5UnormBlock <=> ASTC_6x5SrgbBlock,
+	ASTC_6x6UnormBlock <=> ASTC_6x6SrgbBlock,
+	ASTC_8x5UnormBlock <=> ASTC_8x5SrgbBlock,
+	ASTC_8x6UnormBlock <=> ASTC_8x6SrgbBlock,
+	ASTC_8x8UnormBlock <=> ASTC_8x8SrgbBlock,
+	ASTC_10x5UnormBlock <=> ASTC_10x5SrgbBlock,
+	ASTC_10x6UnormBlock <=> ASTC_10x6SrgbBlock,
+	ASTC_10x8UnormBlock <=> ASTC_10x8SrgbBlock,
+	ASTC_10x10UnormBlock <=> ASTC_10x10SrgbBlock,
+	ASTC_12x10UnormBlock <=> ASTC_12x10SrgbBlock,
+	ASTC_12x12UnormBlock <=> ASTC_12x12SrgbBlock
 }
 
-impl FormatFeatures {
+impl Format {
+	/// Checks if the format stores its color components in the sRGB color space.
+	#[inline]
+	pub fn is_srgb(&self) -> bool {
+		self.numeric_type() == Some(NumericType::Srgb)
+	}
+
+	/// Checks if the format has a depth component.
+	#[inline]
+	pub fn has_depth(&self) -> bool {
+		match self {
+			Format::D16Unorm
+			| Format::X8_D24UnormPack32
+			| Format::D32Sfloat
+			| Format::D16Unorm_S8Uint
+			| Format::D24Unorm_S8Uint
+			| Format::D32Sfloat_S8Uint => true,
+			_ => false
+		}
+	}
+
+	/// Checks if the format has a stencil component.
+	#[inline]
+	pub fn has_stencil(&self) -> bool {
+		match self {
+			Format::S8Uint
+			| Format::D16Unorm_S8Uint
+			| Format::D24Unorm_S8Uint
+			| Format::D32Sfloat_S8Uint => true,
+			_ => false
+		}
+	}
+
+	/// Returns the size in bytes of a texel of this format.
+	///
+	/// Returns `None` for block compressed formats, whose texels have no individual size,
+	/// and for formats without a defined element size such as combined depth-stencil formats.
+	/// See `size` for the size of a block.
+	#[inline]
+	pub fn texel_size(&self) -> Option<NonZeroU32> {
+		if self.is_compressed() {
+			None
+		} else {
+			self.size().and_then(|size| NonZeroU32::new(size as u32))
+		}
+	}
+
+	/// Checks if the format is block compressed.
+	#[inline]
+	pub fn is_compressed(&self) -> bool {
+		self.block_dimensions() != (1, 1)
+	}
+
+	/// Size in bytes of tightly packed data of the given extent (width, height, depth).
+	///
+	/// Partial blocks at the edges are counted as whole blocks.
+	/// Returns `None` if the format has no defined element size.
 	#[inline]
-	#[rustfmt::skip]
-	pub(crate) fn from_vulkan(val: vk::FormatFeatureFlags) -> FormatFeatures {
-		FormatFeatures {
-			sampled_image: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE),
-			storage_image: val.contains(vk::FormatFeatureFlags::STORAGE_IMAGE),
-			storage_image_atomic: val.contains(vk::FormatFeatureFlags::STORAGE_IMAGE_ATOMIC),
-			uniform_texel_buffer: val.contains(vk::FormatFeatureFlags::UNIFORM_TEXEL_BUFFER),
-			storage_texel_buffer: val.contains(vk::FormatFeatureFlags::STORAGE_TEXEL_BUFFER),
-			storage_texel_buffer_atomic: val.contains(vk::FormatFeatureFlags::STORAGE_TEXEL_BUFFER_ATOMIC),
-			vertex_buffer: val.contains(vk::FormatFeatureFlags::VERTEX_BUFFER),
-			color_attachment: val.contains(vk::FormatFeatureFlags::COLOR_ATTACHMENT),
-			color_attachment_blend: val.contains(vk::FormatFeatureFlags::COLOR_ATTACHMENT_BLEND),
-			depth_stencil_attachment: val.contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT),
-			blit_src: val.contains(vk::FormatFeatureFlags::BLIT_SRC),
-			blit_dst: val.contains(vk::FormatFeatureFlags::BLIT_DST),
-			sampled_image_filter_linear: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR),
-			transfer_src: val.contains(vk::FormatFeatureFlags::TRANSFER_SRC),
-			transfer_dst: val.contains(vk::FormatFeatureFlags::TRANSFER_DST),
-			midpoint_chroma_samples: val.contains(vk::FormatFeatureFlags::MIDPOINT_CHROMA_SAMPLES),
-			sampled_image_ycbcr_conversion_linear_filter: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_LINEAR_FILTER),
-			sampled_image_ycbcr_conversion_separate_reconstruction_filter: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_SEPARATE_RECONSTRUCTION_FILTER),
-			sampled_image_ycbcr_conversion_chroma_reconstruction_explicit: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_CHROMA_RECONSTRUCTION_EXPLICIT),
-			sampled_image_ycbcr_conversion_chroma_reconstruction_explicit_forceable: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_YCBCR_CONVERSION_CHROMA_RECONSTRUCTION_EXPLICIT_FORCEABLE),
-			disjoint: val.contains(vk::FormatFeatureFlags::DISJOINT),
-			cosited_chroma_samples: val.contains(vk::FormatFeatureFlags::COSITED_CHROMA_SAMPLES),
-			sampled_image_filter_minmax: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_MINMAX),
-			img_sampled_image_filter_cubic: val.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_CUBIC_IMG),
-			khr_acceleration_structure_vertex_buffer: val.contains(vk::FormatFeatureFlags::ACCELERATION_STRUCTURE_VERTEX_BUFFER_KHR),
-			ext_fragment_density_map: val.contains(vk::FormatFeatureFlags::FRAGMENT_DENSITY_MAP_EXT),
+	pub fn data_size(&self, (width, height, depth): (u32, u32, u32)) -> Option<u64> {
+		let (block_width, block_height) = self.block_dimensions();
+		let blocks = ((width + block_width - 1) / block_width) as u64 * ((height + block_height - 1) / block_height) as u64 * depth as u64;
+		self.size().map(|size| blocks * size as u64)
+	}
+
+	/// Aspects of images of this format.
+	#[inline]
+	pub fn aspects(&self) -> Aspects {
+		if self.has_depth() || self.has_stencil() {
+			Aspects::new(false, self.has_depth(), self.has_stencil(), false)
+		} else {
+			Aspects::color()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rgba8_family() {
+		let unorm = Format::R8G8B8A8Unorm;
+		let srgb = Format::R8G8B8A8Srgb;
+
+		for format in &[unorm, srgb, Format::R8G8B8A8Snorm, Format::R8G8B8A8Uint, Format::R8G8B8A8Sint] {
+			assert_eq!(format.size(), Some(4));
+			assert_eq!(format.texel_size(), NonZeroU32::new(4));
+			assert_eq!(format.block_dimensions(), (1, 1));
+			assert_eq!(format.aspects(), Aspects::color());
+			assert!(!format.is_compressed());
+			assert!(!format.has_depth() && !format.has_stencil());
+		}
+
+		assert_eq!(unorm.numeric_type(), Some(NumericType::Unorm));
+		assert_eq!(srgb.numeric_type(), Some(NumericType::Srgb));
+		assert_eq!(Format::R8G8B8A8Snorm.numeric_type(), Some(NumericType::Snorm));
+		assert_eq!(Format::R8G8B8A8Uint.numeric_type(), Some(NumericType::Uint));
+		assert_eq!(Format::R8G8B8A8Sint.numeric_type(), Some(NumericType::Sint));
+		assert!(!unorm.is_srgb() && srgb.is_srgb());
+
+		assert_eq!(unorm.srgb_equivalent(), Some(srgb));
+		assert_eq!(srgb.srgb_equivalent(), Some(srgb));
+		assert_eq!(unorm.unorm_equivalent(), Some(unorm));
+		assert_eq!(srgb.unorm_equivalent(), Some(unorm));
+		assert_eq!(Format::R8G8B8A8Uint.srgb_equivalent(), None);
+	}
+
+	#[test]
+	fn bgra8_family() {
+		let unorm = Format::B8G8R8A8Unorm;
+		let srgb = Format::B8G8R8A8Srgb;
+
+		for format in &[unorm, srgb] {
+			assert_eq!(format.texel_size(), NonZeroU32::new(4));
+			assert_eq!(format.aspects(), Aspects::color());
+			assert!(!format.is_compressed());
+		}
+
+		assert!(srgb.is_srgb());
+		assert_eq!(unorm.srgb_equivalent(), Some(srgb));
+		assert_eq!(srgb.unorm_equivalent(), Some(unorm));
+		assert_ne!(unorm.srgb_equivalent(), Format::R8G8B8A8Unorm.srgb_equivalent());
+	}
+
+	#[test]
+	fn depth_stencil_family() {
+		assert_eq!(Format::D16Unorm.texel_size(), NonZeroU32::new(2));
+		assert_eq!(Format::X8_D24UnormPack32.texel_size(), NonZeroU32::new(4));
+		assert_eq!(Format::D32Sfloat.texel_size(), NonZeroU32::new(4));
+		assert_eq!(Format::S8Uint.texel_size(), NonZeroU32::new(1));
+
+		for format in &[Format::D16Unorm, Format::X8_D24UnormPack32, Format::D32Sfloat] {
+			assert!(format.has_depth() && !format.has_stencil());
+			assert_eq!(format.aspects(), Aspects::new(false, true, false, false));
+		}
+
+		assert!(!Format::S8Uint.has_depth() && Format::S8Uint.has_stencil());
+		assert_eq!(Format::S8Uint.aspects(), Aspects::new(false, false, true, false));
+
+		for format in &[Format::D16Unorm_S8Uint, Format::D24Unorm_S8Uint, Format::D32Sfloat_S8Uint] {
+			assert!(format.has_depth() && format.has_stencil());
+			assert_eq!(format.aspects(), Aspects::new(false, true, true, false));
+			assert_eq!(format.texel_size(), None);
+			assert_eq!(format.srgb_equivalent(), None);
+		}
+
+		assert_eq!(Format::D32Sfloat.numeric_type(), Some(NumericType::Sfloat));
+		assert_eq!(Format::D24Unorm_S8Uint.numeric_type(), Some(NumericType::Unorm));
+	}
+
+	#[test]
+	fn bc_family() {
+		let bc = [
+			(Format::BC1_RGBUnormBlock, 8, NumericType::Unorm),
+			(Format::BC1_RGBSrgbBlock, 8, NumericType::Srgb),
+			(Format::BC1_RGBAUnormBlock, 8, NumericType::Unorm),
+			(Format::BC1_RGBASrgbBlock, 8, NumericType::Srgb),
+			(Format::BC2UnormBlock, 16, NumericType::Unorm),
+			(Format::BC2SrgbBlock, 16, NumericType::Srgb),
+			(Format::BC3UnormBlock, 16, NumericType::Unorm),
+			(Format::BC3SrgbBlock, 16, NumericType::Srgb),
+			(Format::BC4UnormBlock, 8, NumericType::Unorm),
+			(Format::BC4SnormBlock, 8, NumericType::Snorm),
+			(Format::BC5UnormBlock, 16, NumericType::Unorm),
+			(Format::BC5SnormBlock, 16, NumericType::Snorm),
+			(Format::BC6HUfloatBlock, 16, NumericType::Ufloat),
+			(Format::BC6HSfloatBlock, 16, NumericType::Sfloat),
+			(Format::BC7UnormBlock, 16, NumericType::Unorm),
+			(Format::BC7SrgbBlock, 16, NumericType::Srgb)
+		];
+
+		for (format, block_size, numeric_type) in &bc {
+			assert!(format.is_compressed());
+			assert_eq!(format.block_dimensions(), (4, 4));
+			assert_eq!(format.size(), Some(*block_size));
+			assert_eq!(format.texel_size(), None);
+			assert_eq!(format.numeric_type(), Some(*numeric_type));
+			assert_eq!(format.aspects(), Aspects::color());
 		}
+
+		assert_eq!(Format::BC1_RGBAUnormBlock.srgb_equivalent(), Some(Format::BC1_RGBASrgbBlock));
+		assert_eq!(Format::BC7SrgbBlock.unorm_equivalent(), Some(Format::BC7UnormBlock));
+		assert_eq!(Format::BC4UnormBlock.srgb_equivalent(), None);
+		assert_eq!(Format::BC6HUfloatBlock.srgb_equivalent(), None);
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn round_trip_known_formats() {
+		// Every core 1.0 format but `UNDEFINED`.
+		assert_eq!(KNOWN_FORMATS.len(), 184);
+
+		for format in KNOWN_FORMATS {
+			let vk_format: vk::Format = (*format).into();
+			assert_eq!(Format::try_from(vk_format), Ok(*format));
+			assert_eq!(Format::from_vulkan_lossless(vk_format), *format);
+		}
+	}
+
+	#[test]
+	fn round_trip_unknown_formats() {
+		// `G8B8G8R8_422_UNORM`, introduced by `VK_KHR_sampler_ycbcr_conversion`.
+		let raw = 1000156000;
+		let vk_format = vk::Format::from_raw(raw);
+
+		assert_eq!(Format::try_from(vk_format), Err(UnknownFormat(raw)));
+		assert_eq!(Format::try_from(vk::Format::UNDEFINED), Err(UnknownFormat(0)));
+
+		let format = Format::from_vulkan_lossless(vk_format);
+		assert_eq!(format, Format::Other(raw));
+		assert_eq!(vk::Format::from(format), vk_format);
+		assert_eq!(format.size(), None);
+		assert_eq!(format.numeric_type(), None);
+	}
+
+	#[test]
+	fn bc1_data_size() {
+		let format = Format::BC1_RGBAUnormBlock;
+
+		// 8 bytes per 4x4 block.
+		assert_eq!(format.data_size((16, 16, 1)), Some(128));
+		assert_eq!(format.data_size((8, 8, 1)), Some(32));
+		assert_eq!(format.data_size((4, 4, 1)), Some(8));
+
+		// Partial blocks at the edges count as whole blocks.
+		assert_eq!(format.data_size((2, 2, 1)), Some(8));
+		assert_eq!(format.data_size((1, 1, 1)), Some(8));
+		assert_eq!(format.data_size((10, 6, 1)), Some(48));
+		assert_eq!(format.data_size((10, 6, 2)), Some(96));
+	}
+}
@@ -9,17 +9,22 @@ use std::{
 use crate::{
 	OomError,
 	Device,
-	image,
+	image::{
+		self,
+		ImageProperties
+	},
 	Image,
-	Resource
+	Resource,
+	format::Format
 };
 pub mod render_pass;
+pub mod rendering;
 pub use render_pass::{
 	RenderPass,
 	RenderPassBuilder
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SampleCount(vk::SampleCountFlags);
 
 #[derive(Debug, Clone, Copy)]
@@ -46,15 +51,104 @@ impl TryFrom<u8> for SampleCount {
 }
 
 impl SampleCount {
+	pub const S1: SampleCount = SampleCount(vk::SampleCountFlags::TYPE_1);
+	pub const S2: SampleCount = SampleCount(vk::SampleCountFlags::TYPE_2);
+	pub const S4: SampleCount = SampleCount(vk::SampleCountFlags::TYPE_4);
+	pub const S8: SampleCount = SampleCount(vk::SampleCountFlags::TYPE_8);
+	pub const S16: SampleCount = SampleCount(vk::SampleCountFlags::TYPE_16);
+	pub const S32: SampleCount = SampleCount(vk::SampleCountFlags::TYPE_32);
+	pub const S64: SampleCount = SampleCount(vk::SampleCountFlags::TYPE_64);
+
+	/// Number of samples.
+	#[inline]
+	pub fn count(&self) -> u8 {
+		self.0.as_raw() as u8
+	}
+
+	#[inline]
+	pub(crate) fn from_vulkan(flags: vk::SampleCountFlags) -> SampleCount {
+		SampleCount(flags)
+	}
+
 	#[inline]
 	pub(crate) fn into_vulkan(self) -> vk::SampleCountFlags {
 		self.0
 	}
 }
 
+impl Default for SampleCount {
+	#[inline]
+	fn default() -> SampleCount {
+		SampleCount::S1
+	}
+}
+
+/// Set of sample counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleCounts(vk::SampleCountFlags);
+
+impl SampleCounts {
+	#[inline]
+	pub(crate) fn from_vulkan(flags: vk::SampleCountFlags) -> SampleCounts {
+		SampleCounts(flags)
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	#[inline]
+	pub fn contains(&self, count: SampleCount) -> bool {
+		self.0.contains(count.0)
+	}
+
+	/// Highest sample count of the set.
+	#[inline]
+	pub fn max(&self) -> Option<SampleCount> {
+		let raw = self.0.as_raw();
+		if raw == 0 {
+			None
+		} else {
+			let highest_bit = 31 - raw.leading_zeros();
+			Some(SampleCount(vk::SampleCountFlags::from_raw(1 << highest_bit)))
+		}
+	}
+}
+
 #[derive(Debug)]
 pub enum CreationError {
-	OutOfMemory(OomError)
+	OutOfMemory(OomError),
+
+	/// The number of views differs from the number of attachments of the render pass.
+	AttachmentCountMismatch {
+		expected: u32,
+		given: u32
+	},
+
+	/// The view of the given index does not have the format of its attachment.
+	FormatMismatch {
+		attachment: u32,
+		expected: Format,
+		given: Format
+	},
+
+	/// The view of the given index does not have the sample count of its attachment.
+	SampleCountMismatch {
+		attachment: u32,
+		expected: SampleCount,
+		given: SampleCount
+	},
+
+	/// The view of the given index has more than one mip level,
+	/// or does not cover the extent or layers of the framebuffer.
+	InvalidView(u32),
+
+	/// The extent or layer count is zero or exceeds the device limits.
+	InvalidExtent {
+		extent: (u32, u32),
+		layers: u32
+	}
 }
 
 impl From<vk::Result> for CreationError {
@@ -67,14 +161,71 @@ impl From<vk::Result> for CreationError {
 	}
 }
 
+/// Properties of a view checked against its render pass attachment.
+struct ViewProperties {
+	format: Format,
+	samples: SampleCount,
+
+	/// Extent of the first mip level of the view.
+	extent: (u32, u32),
+
+	level_count: u32,
+	layer_count: u32
+}
+
+/// Checks that the views match the attachments of a render pass,
+/// and cover the given framebuffer extent and layers.
+fn check_views(attachments: &render_pass::Attachments, views: &[ViewProperties], size: (u32, u32), layers: u32) -> Result<(), CreationError> {
+	if views.len() as u32 != attachments.len() {
+		return Err(CreationError::AttachmentCountMismatch {
+			expected: attachments.len(),
+			given: views.len() as u32
+		})
+	}
+
+	for (index, view) in views.iter().enumerate() {
+		let index = index as u32;
+
+		let expected_format = attachments.format(index);
+		if view.format != expected_format {
+			return Err(CreationError::FormatMismatch {
+				attachment: index,
+				expected: expected_format,
+				given: view.format
+			})
+		}
+
+		let expected_samples = attachments.samples(index);
+		if view.samples != expected_samples {
+			return Err(CreationError::SampleCountMismatch {
+				attachment: index,
+				expected: expected_samples,
+				given: view.samples
+			})
+		}
+
+		if view.level_count != 1 || view.extent.0 < size.0 || view.extent.1 < size.1 || view.layer_count < layers {
+			return Err(CreationError::InvalidView(index))
+		}
+	}
+
+	Ok(())
+}
+
 pub struct Framebuffer<I: Image> {
 	device: Arc<Device>,
 	render_pass: Arc<RenderPass>,
 	views: Vec<Arc<image::View<I>>>,
+	extent: (u32, u32),
+	layers: u32,
 	handle: vk::Framebuffer
 }
 
-impl<I: Image> Framebuffer<I> {
+impl<I: ImageProperties> Framebuffer<I> {
+	/// Create a framebuffer.
+	///
+	/// Each view must have the format and sample count of the matching attachment of the render pass,
+	/// a single mip level, and cover the given extent and number of layers.
 	pub fn new(
 		device: &Arc<Device>,
 		render_pass: &Arc<RenderPass>,
@@ -82,6 +233,31 @@ impl<I: Image> Framebuffer<I> {
 		size: (u32, u32),
 		layers: u32
 	) -> Result<Framebuffer<I>, CreationError> {
+		let limits = device.physical_device().limits();
+		if size.0 == 0 || size.1 == 0 || layers == 0
+		|| size.0 > limits.max_framebuffer_width()
+		|| size.1 > limits.max_framebuffer_height()
+		|| layers > limits.max_framebuffer_layers() {
+			return Err(CreationError::InvalidExtent {
+				extent: size,
+				layers
+			})
+		}
+
+		let view_properties: Vec<_> = views.iter().map(|view| {
+			let range = view.subresource_range();
+			let (width, height, _) = view.image().mip_extent(range.base_mip_level);
+			ViewProperties {
+				format: view.format(),
+				samples: view.image().samples(),
+				extent: (width, height),
+				level_count: range.level_count,
+				layer_count: range.layer_count
+			}
+		}).collect();
+
+		check_views(render_pass.attachments(), &view_properties, size, layers)?;
+
 		let vk_attachments: Vec<_> = views.iter().map(|v| v.handle()).collect();
 
 		let infos = vk::FramebufferCreateInfo {
@@ -102,10 +278,15 @@ impl<I: Image> Framebuffer<I> {
 			device: device.clone(),
 			render_pass: render_pass.clone(),
 			views: views,
+			extent: size,
+			layers,
 			handle
 		})
 	}
 
+}
+
+impl<I: Image> Framebuffer<I> {
 	pub fn views(&self) -> &[Arc<image::View<I>>] {
 		&self.views
 	}
@@ -113,6 +294,15 @@ impl<I: Image> Framebuffer<I> {
 	pub fn render_pass(&self) -> &Arc<RenderPass> {
 		&self.render_pass
 	}
+
+	/// Width and height of the framebuffer.
+	pub fn extent(&self) -> (u32, u32) {
+		self.extent
+	}
+
+	pub fn layers(&self) -> u32 {
+		self.layers
+	}
 }
 
 unsafe impl<I: Image> crate::Resource for Framebuffer<I> {
@@ -129,4 +319,115 @@ impl<I: Image> Drop for Framebuffer<I> {
 			self.device.handle().destroy_framebuffer(self.handle, None)
 		}
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use render_pass::{
+		Attachment,
+		Attachments,
+		LoadOp,
+		StoreOp
+	};
+
+	fn attachments(attachments: &[(Format, SampleCount)]) -> Attachments {
+		let mut result = Attachments::new();
+		for (format, samples) in attachments {
+			result.add(Attachment {
+				format: *format,
+				samples: *samples,
+				load: LoadOp::Clear,
+				store: StoreOp::Store,
+				stencil_load: LoadOp::DontCare,
+				stencil_store: StoreOp::DontCare,
+				initial_layout: image::Layout::Undefined,
+				final_layout: image::Layout::ColorAttachmentOptimal
+			});
+		}
+
+		result
+	}
+
+	fn view(format: Format, samples: SampleCount, extent: (u32, u32)) -> ViewProperties {
+		ViewProperties {
+			format,
+			samples,
+			extent,
+			level_count: 1,
+			layer_count: 1
+		}
+	}
+
+	#[test]
+	fn matching_views() {
+		let attachments = attachments(&[(Format::B8G8R8A8Srgb, SampleCount::S1), (Format::D32Sfloat, SampleCount::S1)]);
+		let views = [
+			view(Format::B8G8R8A8Srgb, SampleCount::S1, (800, 600)),
+			view(Format::D32Sfloat, SampleCount::S1, (1024, 1024))
+		];
+
+		assert!(check_views(&attachments, &views, (800, 600), 1).is_ok())
+	}
+
+	#[test]
+	fn attachment_count_mismatch() {
+		let attachments = attachments(&[(Format::B8G8R8A8Srgb, SampleCount::S1), (Format::D32Sfloat, SampleCount::S1)]);
+		let views = [view(Format::B8G8R8A8Srgb, SampleCount::S1, (800, 600))];
+
+		match check_views(&attachments, &views, (800, 600), 1) {
+			Err(CreationError::AttachmentCountMismatch { expected: 2, given: 1 }) => (),
+			r => panic!("unexpected result {:?}", r)
+		}
+	}
+
+	#[test]
+	fn extent_mismatch() {
+		let attachments = attachments(&[(Format::B8G8R8A8Srgb, SampleCount::S1), (Format::D32Sfloat, SampleCount::S1)]);
+		let views = [
+			view(Format::B8G8R8A8Srgb, SampleCount::S1, (800, 600)),
+			view(Format::D32Sfloat, SampleCount::S1, (800, 599))
+		];
+
+		match check_views(&attachments, &views, (800, 600), 1) {
+			Err(CreationError::InvalidView(1)) => (),
+			r => panic!("unexpected result {:?}", r)
+		}
+	}
+
+	#[test]
+	fn layer_count_mismatch() {
+		let attachments = attachments(&[(Format::B8G8R8A8Srgb, SampleCount::S1)]);
+		let views = [view(Format::B8G8R8A8Srgb, SampleCount::S1, (800, 600))];
+
+		match check_views(&attachments, &views, (800, 600), 2) {
+			Err(CreationError::InvalidView(0)) => (),
+			r => panic!("unexpected result {:?}", r)
+		}
+	}
+
+	#[test]
+	fn sample_count_mismatch() {
+		let attachments = attachments(&[(Format::B8G8R8A8Srgb, SampleCount::S4), (Format::D32Sfloat, SampleCount::S4)]);
+		let views = [
+			view(Format::B8G8R8A8Srgb, SampleCount::S4, (800, 600)),
+			view(Format::D32Sfloat, SampleCount::S1, (800, 600))
+		];
+
+		match check_views(&attachments, &views, (800, 600), 1) {
+			Err(CreationError::SampleCountMismatch { attachment: 1, expected: SampleCount::S4, given: SampleCount::S1 }) => (),
+			r => panic!("unexpected result {:?}", r)
+		}
+	}
+
+	#[test]
+	fn format_mismatch() {
+		let attachments = attachments(&[(Format::B8G8R8A8Srgb, SampleCount::S1)]);
+		let views = [view(Format::B8G8R8A8Unorm, SampleCount::S1, (800, 600))];
+
+		match check_views(&attachments, &views, (800, 600), 1) {
+			Err(CreationError::FormatMismatch { attachment: 0, expected: Format::B8G8R8A8Srgb, given: Format::B8G8R8A8Unorm }) => (),
+			r => panic!("unexpected result {:?}", r)
+		}
+	}
+}
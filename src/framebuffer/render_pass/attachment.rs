@@ -1,7 +1,10 @@
 use ash::vk;
 use crate::{
 	format::Format,
-	image,
+	image::{
+		self,
+		ImageProperties
+	},
 	framebuffer::SampleCount
 };
 use super::{
@@ -37,6 +40,30 @@ pub struct Attachment {
 }
 
 impl Attachment {
+	/// Describes an attachment with the format and sample count of the given view.
+	///
+	/// The stencil component, if any, uses the same load and store operations.
+	pub fn from_view<I: ImageProperties>(view: &image::View<I>, load: LoadOp, store: StoreOp, initial_layout: image::Layout, final_layout: image::Layout) -> Attachment {
+		Attachment {
+			format: view.format(),
+			samples: view.image().samples(),
+			load,
+			store,
+			stencil_load: load,
+			stencil_store: store,
+			initial_layout,
+			final_layout
+		}
+	}
+
+	/// Describes a depth-stencil attachment matching the given view,
+	/// cleared at the start of the render pass and left in the `DepthStencilAttachmentOptimal` layout.
+	///
+	/// The content is not stored, as it is usually not needed after the render pass.
+	pub fn depth_stencil<I: ImageProperties>(view: &image::View<I>) -> Attachment {
+		Attachment::from_view(view, LoadOp::Clear, StoreOp::DontCare, image::Layout::Undefined, image::Layout::DepthStencilAttachmentOptimal)
+	}
+
 	#[inline]
 	pub(crate) fn into_vulkan(&self) -> vk::AttachmentDescription {
 		vk::AttachmentDescription {
@@ -61,6 +88,11 @@ impl UnformatedReference {
 		UnformatedReference(index)
 	}
 
+	#[inline]
+	pub fn index(&self) -> u32 {
+		self.0
+	}
+
 	pub fn with_layout(self, layout: image::Layout) -> Reference {
 		Reference(
 			vk::AttachmentReference {
@@ -71,9 +103,27 @@ impl UnformatedReference {
 	}
 }
 
+/// Reference to the attachment of the given index, in the layout used by the subpass.
 #[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
 pub struct Reference(vk::AttachmentReference);
 
+impl Reference {
+	pub fn new(index: u32, layout: image::Layout) -> Reference {
+		UnformatedReference::new(index).with_layout(layout)
+	}
+
+	#[inline]
+	pub fn index(&self) -> u32 {
+		self.0.attachment
+	}
+
+	#[inline]
+	pub fn layout(&self) -> image::Layout {
+		image::Layout::from_vulkan(self.0.layout).unwrap()
+	}
+}
+
 #[derive(Clone, Debug)]
 pub struct Attachments(Vec<vk::AttachmentDescription>);
 
@@ -95,6 +145,40 @@ impl Attachments {
 		UnformatedReference::new(index)
 	}
 
+	/// Format of the attachment of the given index.
+	///
+	/// # Panic
+	///
+	/// Panics if there is no attachment of the given index.
+	#[inline]
+	pub(crate) fn format(&self, index: u32) -> Format {
+		Format::from_vulkan_lossless(self.0[index as usize].format)
+	}
+
+	/// Final layout of the attachment of the given index.
+	#[inline]
+	pub(crate) fn final_layout(&self, index: u32) -> Option<image::Layout> {
+		self.0.get(index as usize).and_then(|desc| image::Layout::from_vulkan(desc.final_layout))
+	}
+
+	/// Checks if the color, depth or stencil component of the attachment of the given index is cleared.
+	#[inline]
+	pub(crate) fn is_cleared(&self, index: u32) -> bool {
+		self.0.get(index as usize).map(|desc| {
+			desc.load_op == vk::AttachmentLoadOp::CLEAR || desc.stencil_load_op == vk::AttachmentLoadOp::CLEAR
+		}).unwrap_or(false)
+	}
+
+	/// Sample count of the attachment of the given index.
+	///
+	/// # Panic
+	///
+	/// Panics if there is no attachment of the given index.
+	#[inline]
+	pub(crate) fn samples(&self, index: u32) -> SampleCount {
+		SampleCount::from_vulkan(self.0[index as usize].samples)
+	}
+
 	#[inline]
 	pub(crate) fn as_ptr(&self) -> *const vk::AttachmentDescription {
 		self.0.as_ptr()
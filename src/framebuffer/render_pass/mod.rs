@@ -2,15 +2,24 @@ use ash::{
 	vk,
 	version::DeviceV1_0
 };
-use std::sync::Arc;
+use std::{
+	sync::Arc,
+	fmt
+};
 use crate::{
 	OomError,
 	Device,
-	DeviceOwned
+	DeviceOwned,
+	format::{
+		Format,
+		ClearValue
+	},
+	image,
+	framebuffer::SampleCount
 };
 
 pub mod subpass;
-mod attachment;
+pub mod attachment;
 
 pub use subpass::{
 	Subpass,
@@ -90,6 +99,31 @@ impl StoreOp {
 #[derive(Debug)]
 pub enum CreationError {
 	OutOfMemory(OomError),
+
+	/// The color and depth-stencil attachments of the given subpass have different sample counts.
+	SampleCountMismatch(u32),
+
+	/// An attachment reference of the given subpass is out of the attachments list,
+	/// or an attachment preserved by the subpass is also used by it.
+	InvalidAttachment(u32),
+
+	/// A resolve attachment of the given subpass is not single sampled,
+	/// does not match the format of its color attachment,
+	/// or its color attachment is not multisampled.
+	InvalidResolve(u32),
+
+	/// A reference of the given subpass uses a layout incompatible with its use or with the format of the attachment.
+	InvalidLayout {
+		subpass: u32,
+		attachment: u32
+	},
+
+	/// The attachment of the given index has an `Undefined` or `Preinitialized` final layout.
+	InvalidFinalLayout(u32),
+
+	/// The dependency of the given index refers to an undefined subpass,
+	/// or its source subpass comes after its destination subpass.
+	InvalidDependency(u32)
 }
 
 impl From<vk::Result> for CreationError {
@@ -103,41 +137,286 @@ impl From<vk::Result> for CreationError {
 	}
 }
 
+/// Error raised when beginning a render pass.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BeginError {
+	/// The number of clear values differs from the number of attachments.
+	///
+	/// Clear values are indexed by attachment, the values of attachments that are not cleared are ignored.
+	ClearValueCountMismatch {
+		expected: u32,
+		given: u32
+	},
+
+	/// The clear value of the given cleared attachment does not match its format.
+	IncompatibleClearValue(u32)
+}
+
+impl std::error::Error for BeginError { }
+
+impl fmt::Display for BeginError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::ClearValueCountMismatch { expected, given } => write!(f, "{} clear values given for {} attachments", given, expected),
+			Self::IncompatibleClearValue(index) => write!(f, "clear value of attachment {} does not match its format", index)
+		}
+	}
+}
+
+/// Checks that there is one clear value per attachment,
+/// matching the format of the attachment if it is cleared.
+fn check_clear_values(attachments: &Attachments, clear_values: &[ClearValue]) -> Result<(), BeginError> {
+	if clear_values.len() as u32 != attachments.len() {
+		return Err(BeginError::ClearValueCountMismatch {
+			expected: attachments.len(),
+			given: clear_values.len() as u32
+		})
+	}
+
+	for (index, value) in clear_values.iter().enumerate() {
+		let index = index as u32;
+		if attachments.is_cleared(index) {
+			if !value.is_compatible_with(attachments.format(index)) {
+				return Err(BeginError::IncompatibleClearValue(index))
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Checks if two render passes, given by their attachments and subpasses, are compatible.
+fn is_compatible(a: (&Attachments, &[SubpassInfo]), b: (&Attachments, &[SubpassInfo])) -> bool {
+	let attachment = |attachments: &Attachments, index: u32| {
+		if index == vk::ATTACHMENT_UNUSED {
+			None
+		} else {
+			Some((attachments.format(index), attachments.samples(index)))
+		}
+	};
+
+	a.1.len() == b.1.len() && a.1.iter().zip(b.1).all(|(a_subpass, b_subpass)| {
+		a_subpass.references.len() == b_subpass.references.len() && a_subpass.references.iter().zip(&b_subpass.references).all(|(&(a_use, a_index), &(b_use, b_index))| {
+			a_use == b_use && attachment(a.0, a_index) == attachment(b.0, b_index)
+		})
+	})
+}
+
+/// Use of an attachment reference in a subpass.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Use {
+	Color,
+	DepthStencil,
+	Input,
+	Resolve
+}
+
+impl Use {
+	/// Checks that the layout can be used for this use, with an attachment of the given format.
+	fn is_compatible(self, layout: image::Layout, format: Format) -> bool {
+		let depth_stencil = format.has_depth() || format.has_stencil();
+		match (self, layout) {
+			(_, image::Layout::General) => true,
+			(Use::Color, image::Layout::ColorAttachmentOptimal) | (Use::Resolve, image::Layout::ColorAttachmentOptimal) => !depth_stencil,
+			(Use::DepthStencil, image::Layout::DepthStencilAttachmentOptimal) | (Use::DepthStencil, image::Layout::DepthStencilReadOnlyOptimal) => depth_stencil,
+			(Use::Input, image::Layout::ShaderReadOnlyOptimal) => true,
+			(Use::Input, image::Layout::DepthStencilReadOnlyOptimal) => depth_stencil,
+			_ => false
+		}
+	}
+}
+
+/// Attachments used by a subpass.
+struct SubpassInfo {
+	/// Sample count of the color and depth-stencil attachments, if any.
+	samples: Option<SampleCount>,
+	color_attachments: u32,
+	input_attachments: u32,
+
+	/// Format of the depth-stencil attachment, if any.
+	depth_stencil: Option<Format>,
+
+	/// Use and attachment index of every reference of the subpass, in declaration order.
+	references: Vec<(Use, u32)>
+}
+
+/// Color, depth-stencil, input and resolve references of a subpass.
+fn references_of<'s>(subpass: &'s SubpassRef) -> impl 's + Iterator<Item=(Use, &'s attachment::Reference)> {
+	subpass.color_attachments.iter().map(|r| (Use::Color, r))
+		.chain(subpass.depth_stencil.iter().map(|r| (Use::DepthStencil, r)))
+		.chain(subpass.input_attachments.iter().map(|r| (Use::Input, r)))
+		.chain(subpass.resolve_attachments.iter().map(|r| (Use::Resolve, r)))
+}
+
+/// Render pass builder.
+///
+/// Attachments are added first, then the subpasses referencing them by index.
 pub struct RenderPassBuilder<'a> {
-	attachments: &'a Attachments,
-	subpasses: Vec<vk::SubpassDescription>,
-	dependencies: Vec<vk::SubpassDependency>
+	attachments: Attachments,
+	subpasses: Vec<SubpassRef<'a>>,
+	dependencies: Vec<subpass::Dependency>
 }
 
 impl<'a> RenderPassBuilder<'a> {
-	pub fn new(attachments: &'a Attachments) -> RenderPassBuilder<'a> {
+	/// Creates a builder starting with the given attachments.
+	pub fn new(attachments: &Attachments) -> RenderPassBuilder<'a> {
 		RenderPassBuilder {
-			attachments,
+			attachments: attachments.clone(),
 			subpasses: Vec::new(),
 			dependencies: Vec::new()
 		}
 	}
 
+	/// Add an attachment, returning a reference to be used by subpasses.
+	pub fn add_attachment(&mut self, attachment: Attachment) -> attachment::UnformatedReference {
+		self.attachments.add(attachment)
+	}
+
+	/// Add a subpass, returning its index.
 	pub fn add<S>(&mut self, subpass: S) -> u32 where S: Into<SubpassRef<'a>> {
-		// TODO check attachment references.
 		let index = self.subpasses.len() as u32;
-		self.subpasses.push(subpass.into().into_vulkan());
+		self.subpasses.push(subpass.into());
 		index
 	}
 
+	/// Add a dependency between two subpasses, or between a subpass and the commands outside of the render pass.
 	pub fn add_dependency(&mut self, dependency: subpass::Dependency) {
-		// TODO check subpass references.
-		self.dependencies.push(dependency.into_vulkan())
+		self.dependencies.push(dependency)
+	}
+
+	/// Checks the attachments of each subpass.
+	///
+	/// References must be in the attachments list, with a layout compatible with their use.
+	/// The color and depth-stencil attachments of a subpass must have the same sample count.
+	/// Resolve attachments must be single sampled and have the format of their multisampled color attachment.
+	fn check_subpasses(&self) -> Result<Vec<SubpassInfo>, CreationError> {
+		for index in 0..self.attachments.len() {
+			if !self.attachments.final_layout(index).map(|l| l.is_transition_target()).unwrap_or(false) {
+				return Err(CreationError::InvalidFinalLayout(index))
+			}
+		}
+
+		let mut subpasses = Vec::with_capacity(self.subpasses.len());
+		for (index, subpass) in self.subpasses.iter().enumerate() {
+			let index = index as u32;
+
+			if !subpass.resolve_attachments.is_empty() && subpass.resolve_attachments.len() != subpass.color_attachments.len() {
+				return Err(CreationError::InvalidResolve(index))
+			}
+
+			let mut used: Vec<(u32, image::Layout)> = Vec::new();
+			for (usage, reference) in references_of(subpass) {
+				if reference.index() == vk::ATTACHMENT_UNUSED {
+					continue
+				}
+
+				if reference.index() >= self.attachments.len() {
+					return Err(CreationError::InvalidAttachment(index))
+				}
+
+				let invalid_layout = CreationError::InvalidLayout {
+					subpass: index,
+					attachment: reference.index()
+				};
+
+				if !usage.is_compatible(reference.layout(), self.attachments.format(reference.index())) {
+					return Err(invalid_layout)
+				}
+
+				// An attachment used more than once in a subpass must always be in the same layout.
+				match used.iter().find(|(i, _)| *i == reference.index()) {
+					Some((_, layout)) if *layout != reference.layout() => return Err(invalid_layout),
+					Some(_) => (),
+					None => used.push((reference.index(), reference.layout()))
+				}
+			}
+
+			for preserved in subpass.preserve_attachments {
+				if *preserved >= self.attachments.len() as usize || used.iter().any(|(i, _)| *i as usize == *preserved) {
+					return Err(CreationError::InvalidAttachment(index))
+				}
+			}
+
+			let mut samples = None;
+			for reference in subpass.color_attachments.iter().chain(&subpass.depth_stencil) {
+				if reference.index() == vk::ATTACHMENT_UNUSED {
+					continue
+				}
+
+				let attachment_samples = self.attachments.samples(reference.index());
+				match samples {
+					Some(s) if s != attachment_samples => return Err(CreationError::SampleCountMismatch(index)),
+					_ => samples = Some(attachment_samples)
+				}
+			}
+
+			for (color, resolve) in subpass.color_attachments.iter().zip(subpass.resolve_attachments) {
+				if resolve.index() == vk::ATTACHMENT_UNUSED {
+					continue
+				}
+
+				if color.index() == vk::ATTACHMENT_UNUSED
+				|| samples.map(|s| s == SampleCount::S1).unwrap_or(true)
+				|| self.attachments.samples(resolve.index()) != SampleCount::S1
+				|| self.attachments.format(color.index()) != self.attachments.format(resolve.index()) {
+					return Err(CreationError::InvalidResolve(index))
+				}
+			}
+
+			subpasses.push(SubpassInfo {
+				samples,
+				color_attachments: subpass.color_attachments.len() as u32,
+				input_attachments: subpass.input_attachments.len() as u32,
+				depth_stencil: subpass.depth_stencil.filter(|r| r.index() != vk::ATTACHMENT_UNUSED).map(|r| self.attachments.format(r.index())),
+				references: references_of(subpass).map(|(usage, r)| (usage, r.index())).collect()
+			})
+		}
+
+		for (index, dependency) in self.dependencies.iter().enumerate() {
+			if !dependency.is_valid(self.subpasses.len() as u32) {
+				return Err(CreationError::InvalidDependency(index as u32))
+			}
+		}
+
+		Ok(subpasses)
+	}
+
+	/// Dependencies given to the builder, plus a by-region dependency for each pair of subpasses
+	/// where the second reads an attachment written by the first as input attachment,
+	/// if no dependency is given between them.
+	fn dependencies(&self) -> Vec<subpass::Dependency> {
+		let mut dependencies = self.dependencies.clone();
+
+		for (dst, subpass) in self.subpasses.iter().enumerate() {
+			for input in subpass.input_attachments {
+				for (src, previous) in self.subpasses[..dst].iter().enumerate() {
+					let writes = previous.color_attachments.iter().chain(&previous.depth_stencil).any(|r| r.index() == input.index());
+					let (src, dst) = (src as u32, dst as u32);
+					if writes && !dependencies.iter().any(|d| d.source_subpass == src && d.destination_subpass == dst) {
+						dependencies.push(subpass::Dependency::input_attachments(src, dst))
+					}
+				}
+			}
+		}
+
+		dependencies
 	}
 
 	pub fn build(self, device: &Arc<Device>) -> Result<RenderPass, CreationError> {
+		let subpasses = self.check_subpasses()?;
+
+		// Subpass descriptions point into `self.subpasses` and `preserve_attachments`.
+		let preserve_attachments: Vec<Vec<u32>> = self.subpasses.iter().map(|s| s.preserve_attachments.iter().map(|i| *i as u32).collect()).collect();
+		let vk_subpasses: Vec<_> = self.subpasses.iter().zip(&preserve_attachments).map(|(s, p)| s.as_vulkan(p)).collect();
+		let vk_dependencies: Vec<_> = self.dependencies().into_iter().map(subpass::Dependency::into_vulkan).collect();
+
 		let infos = vk::RenderPassCreateInfo {
 			attachment_count: self.attachments.len(),
 			p_attachments: self.attachments.as_ptr(),
-			subpass_count: self.subpasses.len() as u32,
-			p_subpasses: self.subpasses.as_ptr(),
-			dependency_count: self.dependencies.len() as u32,
-			p_dependencies: self.dependencies.as_ptr(),
+			subpass_count: vk_subpasses.len() as u32,
+			p_subpasses: vk_subpasses.as_ptr(),
+			dependency_count: vk_dependencies.len() as u32,
+			p_dependencies: vk_dependencies.as_ptr(),
 			..Default::default()
 		};
 
@@ -148,7 +427,8 @@ impl<'a> RenderPassBuilder<'a> {
 		Ok(RenderPass {
 			device: device.clone(),
 			handle,
-			subpass_count: infos.subpass_count
+			attachments: self.attachments,
+			subpasses
 		})
 	}
 }
@@ -156,13 +436,83 @@ impl<'a> RenderPassBuilder<'a> {
 pub struct RenderPass {
 	device: Arc<Device>,
 	handle: vk::RenderPass,
-	subpass_count: u32
+	attachments: Attachments,
+
+	/// Attachments used by each subpass.
+	subpasses: Vec<SubpassInfo>
 }
 
 impl RenderPass {
+	/// Creates a render pass builder without attachments.
+	pub fn builder<'a>() -> RenderPassBuilder<'a> {
+		RenderPassBuilder::new(&Attachments::new())
+	}
+
+	#[inline]
+	pub fn attachments(&self) -> &Attachments {
+		&self.attachments
+	}
+
+	#[inline]
+	pub fn subpass_count(&self) -> u32 {
+		self.subpasses.len() as u32
+	}
+
+	/// Sample count of the color and depth-stencil attachments of the given subpass.
+	///
+	/// Returns `None` if the subpass has no such attachment, or does not exist.
+	#[inline]
+	pub fn subpass_samples(&self, index: u32) -> Option<SampleCount> {
+		self.subpasses.get(index as usize).and_then(|s| s.samples)
+	}
+
+	/// Number of color attachments of the given subpass, if it exists.
+	#[inline]
+	pub fn subpass_color_attachment_count(&self, index: u32) -> Option<u32> {
+		self.subpasses.get(index as usize).map(|s| s.color_attachments)
+	}
+
+	/// Number of input attachments of the given subpass, if it exists.
+	#[inline]
+	pub fn subpass_input_attachment_count(&self, index: u32) -> Option<u32> {
+		self.subpasses.get(index as usize).map(|s| s.input_attachments)
+	}
+
+	/// Checks if the given subpass has a depth-stencil attachment.
+	#[inline]
+	pub fn subpass_has_depth_stencil(&self, index: u32) -> bool {
+		self.subpass_depth_stencil_format(index).is_some()
+	}
+
+	/// Format of the depth-stencil attachment of the given subpass.
+	///
+	/// Returns `None` if the subpass has no such attachment, or does not exist.
+	#[inline]
+	pub fn subpass_depth_stencil_format(&self, index: u32) -> Option<Format> {
+		self.subpasses.get(index as usize).and_then(|s| s.depth_stencil)
+	}
+
+	/// Checks that the given clear values can be used to begin the render pass.
+	///
+	/// There must be one clear value per attachment, matching the format of the attachment if it is cleared.
+	pub fn check_clear_values(&self, clear_values: &[ClearValue]) -> Result<(), BeginError> {
+		check_clear_values(&self.attachments, clear_values)
+	}
+
+	/// Checks if the render pass is compatible with another one.
+	///
+	/// Compatible render passes have the same subpasses,
+	/// referencing attachments with the same formats and sample counts.
+	/// Their layouts and load and store operations may differ.
+	/// Framebuffers, pipelines and secondary command buffers created for a render pass
+	/// can be used with any compatible render pass.
+	pub fn is_compatible_with(&self, other: &RenderPass) -> bool {
+		self.handle == other.handle || is_compatible((&self.attachments, &self.subpasses), (&other.attachments, &other.subpasses))
+	}
+
 	#[inline]
 	pub fn subpass(self: &Arc<Self>, index: u32) -> Option<subpass::Reference> {
-		if index < self.subpass_count {
+		if index < self.subpass_count() {
 			Some(subpass::Reference::new(self, index))
 		} else {
 			None
@@ -190,4 +540,160 @@ impl Drop for RenderPass {
 			self.device.handle().destroy_render_pass(self.handle, None)
 		}
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn attachment(format: Format, load: LoadOp) -> Attachment {
+		Attachment {
+			format,
+			samples: SampleCount::S1,
+			load,
+			store: StoreOp::Store,
+			stencil_load: LoadOp::DontCare,
+			stencil_store: StoreOp::DontCare,
+			initial_layout: image::Layout::Undefined,
+			final_layout: image::Layout::General
+		}
+	}
+
+	fn attachments() -> Attachments {
+		let mut attachments = Attachments::new();
+		attachments.add(attachment(Format::R8G8B8A8Unorm, LoadOp::Clear));
+		attachments.add(attachment(Format::R8G8B8A8Uint, LoadOp::Load));
+		attachments.add(attachment(Format::D32Sfloat, LoadOp::Clear));
+		attachments
+	}
+
+	#[test]
+	fn one_clear_value_per_attachment() {
+		let attachments = attachments();
+
+		assert_eq!(check_clear_values(&attachments, &[ClearValue::BLACK, ClearValue::BLACK]), Err(BeginError::ClearValueCountMismatch {
+			expected: 3,
+			given: 2
+		}));
+
+		assert_eq!(check_clear_values(&attachments, &[ClearValue::BLACK, ClearValue::BLACK, ClearValue::DepthStencil { depth: 1.0, stencil: 0 }]), Ok(()));
+	}
+
+	#[test]
+	fn clear_values_match_cleared_formats() {
+		let attachments = attachments();
+		let depth = ClearValue::DepthStencil { depth: 1.0, stencil: 0 };
+
+		// The second attachment is not cleared, its clear value is ignored.
+		assert_eq!(check_clear_values(&attachments, &[ClearValue::ColorF32([0.5; 4]), depth, depth]), Ok(()));
+
+		assert_eq!(check_clear_values(&attachments, &[ClearValue::ColorU32([0; 4]), ClearValue::BLACK, depth]), Err(BeginError::IncompatibleClearValue(0)));
+		assert_eq!(check_clear_values(&attachments, &[ClearValue::BLACK, ClearValue::BLACK, ClearValue::BLACK]), Err(BeginError::IncompatibleClearValue(2)));
+	}
+
+	#[test]
+	fn unknown_attachment_formats() {
+		// `G8B8G8R8_422_UNORM`, introduced by `VK_KHR_sampler_ycbcr_conversion`.
+		let format = Format::Other(1000156000);
+		let mut attachments = Attachments::new();
+		attachments.add(attachment(format, LoadOp::Clear));
+		assert_eq!(attachments.format(0), format);
+
+		// Checked without panicking.
+		let _ = check_clear_values(&attachments, &[ClearValue::BLACK]);
+
+		let subpasses = [Subpass::new().color_attachment(0, image::Layout::ColorAttachmentOptimal)];
+		let infos = render_pass(&attachments, &subpasses);
+		assert!(is_compatible((&attachments, &infos), (&attachments, &infos)));
+	}
+
+	fn render_pass(attachments: &Attachments, subpasses: &[Subpass]) -> Vec<SubpassInfo> {
+		let mut builder = RenderPassBuilder::new(attachments);
+		for subpass in subpasses {
+			builder.add(subpass);
+		}
+
+		builder.check_subpasses().unwrap()
+	}
+
+	#[test]
+	fn compatible_render_passes() {
+		let attachments = attachments();
+		let subpasses = [
+			Subpass::new()
+				.color_attachment(0, image::Layout::ColorAttachmentOptimal)
+				.depth_stencil_attachment(2, image::Layout::DepthStencilAttachmentOptimal),
+			Subpass::new()
+				.color_attachment(1, image::Layout::ColorAttachmentOptimal)
+				.input_attachment(0, image::Layout::ShaderReadOnlyOptimal)
+		];
+		let infos = render_pass(&attachments, &subpasses);
+
+		// Load and store operations, and layouts, may differ.
+		let mut other_attachments = Attachments::new();
+		other_attachments.add(attachment(Format::R8G8B8A8Unorm, LoadOp::DontCare));
+		other_attachments.add(attachment(Format::R8G8B8A8Uint, LoadOp::Clear));
+		other_attachments.add(attachment(Format::D32Sfloat, LoadOp::Load));
+		let other_subpasses = [
+			Subpass::new()
+				.color_attachment(0, image::Layout::General)
+				.depth_stencil_attachment(2, image::Layout::General),
+			Subpass::new()
+				.color_attachment(1, image::Layout::General)
+				.input_attachment(0, image::Layout::General)
+		];
+		let other_infos = render_pass(&other_attachments, &other_subpasses);
+
+		assert!(is_compatible((&attachments, &infos), (&attachments, &infos)));
+		assert!(is_compatible((&attachments, &infos), (&other_attachments, &other_infos)));
+	}
+
+	#[test]
+	fn incompatible_render_passes() {
+		let attachments = attachments();
+		let subpasses = [
+			Subpass::new()
+				.color_attachment(0, image::Layout::ColorAttachmentOptimal)
+				.depth_stencil_attachment(2, image::Layout::DepthStencilAttachmentOptimal)
+		];
+		let infos = render_pass(&attachments, &subpasses);
+
+		// Different format.
+		let mut other_attachments = Attachments::new();
+		other_attachments.add(attachment(Format::R8G8B8A8Uint, LoadOp::Clear));
+		other_attachments.add(attachment(Format::R8G8B8A8Uint, LoadOp::Load));
+		other_attachments.add(attachment(Format::D32Sfloat, LoadOp::Clear));
+		let other_infos = render_pass(&other_attachments, &subpasses);
+		assert!(!is_compatible((&attachments, &infos), (&other_attachments, &other_infos)));
+
+		// Different sample count.
+		let mut other_attachments = Attachments::new();
+		other_attachments.add(Attachment { samples: SampleCount::S4, ..attachment(Format::R8G8B8A8Unorm, LoadOp::Clear) });
+		other_attachments.add(attachment(Format::R8G8B8A8Uint, LoadOp::Load));
+		other_attachments.add(Attachment { samples: SampleCount::S4, ..attachment(Format::D32Sfloat, LoadOp::Clear) });
+		let other_infos = render_pass(&other_attachments, &subpasses);
+		assert!(!is_compatible((&attachments, &infos), (&other_attachments, &other_infos)));
+
+		// Different references.
+		let other_subpasses = [
+			Subpass::new()
+				.color_attachment(1, image::Layout::ColorAttachmentOptimal)
+				.depth_stencil_attachment(2, image::Layout::DepthStencilAttachmentOptimal)
+		];
+		let other_infos = render_pass(&attachments, &other_subpasses);
+		assert!(!is_compatible((&attachments, &infos), (&attachments, &other_infos)));
+
+		// Missing depth-stencil attachment.
+		let other_subpasses = [
+			Subpass::new()
+				.color_attachment(0, image::Layout::ColorAttachmentOptimal)
+		];
+		let other_infos = render_pass(&attachments, &other_subpasses);
+		assert!(!is_compatible((&attachments, &infos), (&attachments, &other_infos)));
+
+		// Different subpass count.
+		let other_subpasses = [subpasses[0].clone(), subpasses[0].clone()];
+		let other_infos = render_pass(&attachments, &other_subpasses);
+		assert!(!is_compatible((&attachments, &infos), (&attachments, &other_infos)));
+	}
+}
@@ -3,6 +3,7 @@ use std::{
 	ops,
 	sync::Arc
 };
+use crate::image;
 use super::{
 	RenderPass,
 	Attachments,
@@ -170,10 +171,11 @@ access_flags! {
 ///
 /// # Restrictions
 ///
-/// All these restrictions are checked when the `RenderPass` object is created.
-/// TODO: that'r not the case ^
+/// All these restrictions are checked when the `RenderPass` object is created,
+/// except for the color attachments limit and the first use of input attachments.
 ///
 /// - The number of color attachments must be less than the limit of the physical device.
+/// - Each reference must use a layout compatible with its use and the attachment format.
 /// - All the attachments in `color_attachments` and `depth_stencil` must have the same
 ///   samples count.
 /// - If any attachment is used as both an input attachment and a color or
@@ -211,6 +213,50 @@ pub struct Subpass {
 }
 
 impl Subpass {
+	/// Subpass without attachments.
+	pub fn new() -> Subpass {
+		Subpass {
+			color_attachments: Vec::new(),
+			depth_stencil: None,
+			input_attachments: Vec::new(),
+			resolve_attachments: Vec::new(),
+			preserve_attachments: Vec::new()
+		}
+	}
+
+	/// Add the attachment of the given index as color attachment.
+	pub fn color_attachment(mut self, index: u32, layout: image::Layout) -> Self {
+		self.color_attachments.push(attachment::Reference::new(index, layout));
+		self
+	}
+
+	/// Use the attachment of the given index as depth-stencil attachment.
+	pub fn depth_stencil_attachment(mut self, index: u32, layout: image::Layout) -> Self {
+		self.depth_stencil = Some(attachment::Reference::new(index, layout));
+		self
+	}
+
+	/// Add the attachment of the given index as input attachment.
+	///
+	/// If a previous subpass writes to the attachment, a by-region dependency is added
+	/// when building the render pass, unless one is given explicitly.
+	pub fn input_attachment(mut self, index: u32, layout: image::Layout) -> Self {
+		self.input_attachments.push(attachment::Reference::new(index, layout));
+		self
+	}
+
+	/// Add a resolve attachment for the color attachment of the same rank.
+	pub fn resolve_attachment(mut self, index: u32, layout: image::Layout) -> Self {
+		self.resolve_attachments.push(attachment::Reference::new(index, layout));
+		self
+	}
+
+	/// Preserve the content of the attachment of the given index during the subpass.
+	pub fn preserve_attachment(mut self, index: u32) -> Self {
+		self.preserve_attachments.push(index as usize);
+		self
+	}
+
 	pub fn as_ref(&self) -> SubpassRef {
 		SubpassRef {
 			color_attachments: self.color_attachments.as_ref(),
@@ -226,10 +272,11 @@ impl Subpass {
 ///
 /// # Restrictions
 ///
-/// All these restrictions are checked when the `RenderPass` object is created.
-/// TODO: that'r not the case ^
+/// All these restrictions are checked when the `RenderPass` object is created,
+/// except for the color attachments limit and the first use of input attachments.
 ///
 /// - The number of color attachments must be less than the limit of the physical device.
+/// - Each reference must use a layout compatible with its use and the attachment format.
 /// - All the attachments in `color_attachments` and `depth_stencil` must have the same
 ///   samples count.
 /// - If any attachment is used as both an input attachment and a color or
@@ -267,12 +314,12 @@ pub struct SubpassRef<'r> {
 }
 
 impl<'r> SubpassRef<'r> {
-	/// Returns the vulkan subpass description.
-	/// 
+	/// Returns the vulkan subpass description, using the given preserved attachment indices.
+	///
 	/// # Safety
-	/// 
-	/// User must ensure that the returned object does not outlive the input lifetime.
-	pub(crate) fn into_vulkan(self) -> vk::SubpassDescription {
+	///
+	/// User must ensure that the returned object does not outlive `self` and `preserve_attachments`.
+	pub(crate) fn as_vulkan(&self, preserve_attachments: &[u32]) -> vk::SubpassDescription {
 		vk::SubpassDescription {
 			flags: vk::SubpassDescriptionFlags::empty(), // TODO
 			pipeline_bind_point: vk::PipelineBindPoint::GRAPHICS,
@@ -291,8 +338,8 @@ impl<'r> SubpassRef<'r> {
 
 			p_depth_stencil_attachment: self.depth_stencil.as_ref().map(|r| r as *const attachment::Reference as *const _).unwrap_or(std::ptr::null()),
 
-			preserve_attachment_count: self.preserve_attachments.len() as u32,
-			p_preserve_attachments: self.preserve_attachments.as_ptr() as *const _
+			preserve_attachment_count: preserve_attachments.len() as u32,
+			p_preserve_attachments: preserve_attachments.as_ptr()
 		}
 	}
 }
@@ -303,6 +350,9 @@ impl<'a> From<&'a Subpass> for SubpassRef<'a> {
 	}
 }
 
+/// Index designating the commands outside of the render pass in a `Dependency`.
+pub const EXTERNAL: u32 = vk::SUBPASS_EXTERNAL;
+
 /// Describes a dependency between two subpasses of a render pass.
 ///
 /// The implementation is allowed to change the order of the passes within a render pass, unless
@@ -311,9 +361,13 @@ impl<'a> From<&'a Subpass> for SubpassRef<'a> {
 #[derive(Debug, Clone)]
 pub struct Dependency {
 	/// Index of the subpass that writes the data that `destination_subpass` is going to use.
+	///
+	/// `EXTERNAL` designates the commands submitted before the render pass.
 	pub source_subpass: u32,
 
 	/// Index of the subpass that reads the data that `source_subpass` wrote.
+	///
+	/// `EXTERNAL` designates the commands submitted after the render pass.
 	pub destination_subpass: u32,
 
 	/// The pipeline stages that must be finished on the previous subpass before the destination
@@ -344,6 +398,47 @@ pub struct Dependency {
 }
 
 impl Dependency {
+	/// By-region dependency making the color and depth-stencil outputs of `source_subpass`
+	/// readable as input attachments by the fragment shader of `destination_subpass`.
+	pub fn input_attachments(source_subpass: u32, destination_subpass: u32) -> Dependency {
+		Dependency {
+			source_subpass,
+			destination_subpass,
+			source_stages: PipelineStages {
+				color_attachment_output: true,
+				early_fragment_tests: true,
+				late_fragment_tests: true,
+				..PipelineStages::none()
+			},
+			destination_stages: PipelineStages {
+				fragment_shader: true,
+				..PipelineStages::none()
+			},
+			source_access: AccessFlagBits {
+				color_attachment_write: true,
+				depth_stencil_attachment_write: true,
+				..AccessFlagBits::none()
+			},
+			destination_access: AccessFlagBits {
+				input_attachment_read: true,
+				..AccessFlagBits::none()
+			},
+			by_region: true
+		}
+	}
+
+	/// Checks that the subpass indices are valid for a render pass with the given number of subpasses.
+	///
+	/// Source and destination cannot both be `EXTERNAL`,
+	/// and the source subpass cannot come after the destination subpass.
+	pub(crate) fn is_valid(&self, subpass_count: u32) -> bool {
+		let valid_index = |i| i == EXTERNAL || i < subpass_count;
+		valid_index(self.source_subpass)
+			&& valid_index(self.destination_subpass)
+			&& !(self.source_subpass == EXTERNAL && self.destination_subpass == EXTERNAL)
+			&& (self.source_subpass == EXTERNAL || self.destination_subpass == EXTERNAL || self.source_subpass <= self.destination_subpass)
+	}
+
 	pub(crate) fn into_vulkan(self) -> vk::SubpassDependency {
 		vk::SubpassDependency {
 			src_subpass: self.source_subpass,
@@ -363,7 +458,7 @@ impl Dependency {
 
 pub struct Subpasses<'a> {
 	attachments: &'a Attachments,
-	subpasses: Vec<vk::SubpassDescription>
+	subpasses: Vec<SubpassRef<'a>>
 }
 
 impl<'a> Subpasses<'a> {
@@ -379,7 +474,7 @@ impl<'a> Subpasses<'a> {
 	}
 
 	pub fn add(&mut self, subpass: SubpassRef<'a>) {
-		self.subpasses.push(subpass.into_vulkan())
+		self.subpasses.push(subpass)
 	}
 }
 
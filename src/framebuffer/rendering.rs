@@ -0,0 +1,444 @@
+//! Dynamic rendering (`VK_KHR_dynamic_rendering`, core in Vulkan 1.3).
+//!
+//! Renders directly into image views, without render pass nor framebuffer objects.
+//! Graphics pipelines used with dynamic rendering must be created with
+//! `pipeline::Graphics::builder_for_rendering`.
+use std::{
+	sync::Arc,
+	fmt
+};
+use ash::vk;
+use crate::{
+	ffi,
+	Device,
+	device::{
+		self,
+		ExtendedFeature,
+		feature::dynamic_rendering
+	},
+	image::{
+		self,
+		ImageProperties
+	},
+	Image,
+	Resource,
+	format::{
+		self,
+		Format
+	}
+};
+use super::{
+	SampleCount,
+	render_pass::{
+		LoadOp,
+		StoreOp
+	}
+};
+
+/// Error raised when dynamic rendering is not enabled on a device.
+#[derive(Debug, Clone, Copy)]
+pub enum SupportError {
+	/// The device does not support Vulkan 1.3, and the `VK_KHR_dynamic_rendering` extension is not enabled.
+	MissingExtension(device::Extension),
+
+	/// The `DynamicRendering` extended feature is not enabled.
+	MissingFeature(ExtendedFeature)
+}
+
+impl std::error::Error for SupportError {}
+
+impl fmt::Display for SupportError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			SupportError::MissingExtension(e) => write!(f, "missing device extension `{}`", e),
+			SupportError::MissingFeature(feature) => write!(f, "missing extended device feature `{}`", feature)
+		}
+	}
+}
+
+/// Checks that dynamic rendering is enabled on the given device.
+pub fn check_support(device: &Device) -> Result<(), SupportError> {
+	device.dynamic_rendering().map_err(|e| SupportError::MissingExtension(e.0))?;
+
+	let feature = ExtendedFeature::DynamicRendering(dynamic_rendering::Feature::DynamicRendering);
+	if !device.enabled_extended_features().contains(feature) {
+		return Err(SupportError::MissingFeature(feature))
+	}
+
+	Ok(())
+}
+
+#[derive(Debug)]
+pub enum BeginError {
+	Unsupported(SupportError),
+
+	/// The attachments do not all have the same sample count.
+	SampleCountMismatch,
+
+	/// An attachment is resolved while being single sampled,
+	/// or its resolve view is multisampled or has a different format.
+	InvalidResolve,
+
+	/// The render area is empty or exceeds the extent of an attachment,
+	/// or the layer count is zero or exceeds the layers of an attachment.
+	InvalidArea,
+
+	/// The depth attachment format has no depth aspect, the stencil attachment format no stencil aspect,
+	/// or a color attachment has a depth or stencil format.
+	InvalidFormat(Format),
+
+	/// The layout of an attachment, or of its resolve view, cannot be used for its attachment type.
+	InvalidLayout(image::Layout),
+
+	/// The clear value of a cleared attachment does not match the format of its view.
+	IncompatibleClearValue
+}
+
+impl From<SupportError> for BeginError {
+	fn from(e: SupportError) -> BeginError {
+		BeginError::Unsupported(e)
+	}
+}
+
+impl std::error::Error for BeginError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			BeginError::Unsupported(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for BeginError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			BeginError::Unsupported(e) => e.fmt(f),
+			BeginError::SampleCountMismatch => write!(f, "rendering attachments with different sample counts"),
+			BeginError::InvalidResolve => write!(f, "invalid rendering attachment resolve"),
+			BeginError::InvalidArea => write!(f, "render area empty or out of the attachments"),
+			BeginError::InvalidFormat(format) => write!(f, "invalid attachment format {:?}", format),
+			BeginError::InvalidLayout(layout) => write!(f, "invalid attachment layout {:?}", layout),
+			BeginError::IncompatibleClearValue => write!(f, "clear value incompatible with the attachment format")
+		}
+	}
+}
+
+/// How the samples of a multisampled attachment are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResolveMode {
+	SampleZero,
+	Average,
+	Min,
+	Max
+}
+
+impl ResolveMode {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ResolveModeFlags {
+		match self {
+			ResolveMode::SampleZero => vk::ResolveModeFlags::SAMPLE_ZERO,
+			ResolveMode::Average => vk::ResolveModeFlags::AVERAGE,
+			ResolveMode::Min => vk::ResolveModeFlags::MIN,
+			ResolveMode::Max => vk::ResolveModeFlags::MAX
+		}
+	}
+}
+
+/// Single sampled view receiving the resolved samples of an attachment at the end of the rendering.
+pub struct Resolve<I: Image> {
+	pub view: Arc<image::View<I>>,
+	pub layout: image::Layout,
+	pub mode: ResolveMode
+}
+
+/// Image view rendered into.
+pub struct RenderingAttachment<I: Image> {
+	pub view: Arc<image::View<I>>,
+
+	/// Layout of the view during the rendering.
+	pub layout: image::Layout,
+
+	pub load: LoadOp,
+	pub store: StoreOp,
+
+	/// Value used when `load` is `LoadOp::Clear`.
+	pub clear_value: format::ClearValue,
+
+	pub resolve: Option<Resolve<I>>
+}
+
+impl<I: Image> RenderingAttachment<I> {
+	/// Attachment loaded and stored, without resolve.
+	pub fn new(view: &Arc<image::View<I>>, layout: image::Layout) -> RenderingAttachment<I> {
+		RenderingAttachment {
+			view: view.clone(),
+			layout,
+			load: LoadOp::Load,
+			store: StoreOp::Store,
+			clear_value: format::ClearValue::default(),
+			resolve: None
+		}
+	}
+
+	/// Clear the attachment with the given value at the start of the rendering.
+	pub fn cleared(mut self, clear_value: format::ClearValue) -> RenderingAttachment<I> {
+		self.load = LoadOp::Clear;
+		self.clear_value = clear_value;
+		self
+	}
+
+	pub fn with_store(mut self, store: StoreOp) -> RenderingAttachment<I> {
+		self.store = store;
+		self
+	}
+
+	pub fn with_resolve(mut self, view: &Arc<image::View<I>>, layout: image::Layout, mode: ResolveMode) -> RenderingAttachment<I> {
+		self.resolve = Some(Resolve {
+			view: view.clone(),
+			layout,
+			mode
+		});
+		self
+	}
+
+	pub(crate) fn as_vulkan(&self) -> ffi::RenderingAttachmentInfo {
+		let (resolve_mode, resolve_image_view, resolve_image_layout) = match &self.resolve {
+			Some(resolve) => (resolve.mode.into_vulkan(), resolve.view.handle(), resolve.layout.into_vulkan()),
+			None => (vk::ResolveModeFlags::NONE, vk::ImageView::null(), vk::ImageLayout::UNDEFINED)
+		};
+
+		ffi::RenderingAttachmentInfo {
+			image_view: self.view.handle(),
+			image_layout: self.layout.into_vulkan(),
+			resolve_mode,
+			resolve_image_view,
+			resolve_image_layout,
+			load_op: self.load.into_vulkan(),
+			store_op: self.store.into_vulkan(),
+			clear_value: self.clear_value.into_vulkan(),
+			..Default::default()
+		}
+	}
+}
+
+impl<I: ImageProperties> RenderingAttachment<I> {
+	fn samples(&self) -> SampleCount {
+		self.view.image().samples()
+	}
+
+	fn check_resolve(&self) -> Result<(), BeginError> {
+		if let Some(resolve) = &self.resolve {
+			if self.samples() == SampleCount::S1
+			|| resolve.view.image().samples() != SampleCount::S1
+			|| resolve.view.format() != self.view.format() {
+				return Err(BeginError::InvalidResolve)
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Dynamic rendering parameters.
+pub struct RenderingInfo<'a, I: Image> {
+	pub color_attachments: &'a [RenderingAttachment<I>],
+	pub depth_attachment: Option<&'a RenderingAttachment<I>>,
+	pub stencil_attachment: Option<&'a RenderingAttachment<I>>,
+
+	/// Offset and extent of the rendered area.
+	pub render_area: (i32, i32, u32, u32),
+	pub layer_count: u32
+}
+
+impl<'a, I: Image> RenderingInfo<'a, I> {
+	/// Iterates over the color, depth and stencil attachments.
+	pub fn attachments(&self) -> impl '_ + Iterator<Item=&'a RenderingAttachment<I>> {
+		self.color_attachments.iter().chain(self.depth_attachment).chain(self.stencil_attachment)
+	}
+}
+
+impl<'a, I: ImageProperties> RenderingInfo<'a, I> {
+	pub(crate) fn check(&self) -> Result<(), BeginError> {
+		let (_, _, width, height) = self.render_area;
+		if width == 0 || height == 0 || self.layer_count == 0 {
+			return Err(BeginError::InvalidArea)
+		}
+
+		let uses = self.color_attachments.iter().map(|a| (AttachmentType::Color, a))
+			.chain(self.depth_attachment.map(|a| (AttachmentType::Depth, a)))
+			.chain(self.stencil_attachment.map(|a| (AttachmentType::Stencil, a)));
+
+		let mut samples = None;
+		for (ty, attachment) in uses {
+			if *samples.get_or_insert(attachment.samples()) != attachment.samples() {
+				return Err(BeginError::SampleCountMismatch)
+			}
+
+			attachment.check_resolve()?;
+
+			if attachment.load == LoadOp::Clear && !attachment.clear_value.is_compatible_with(attachment.view.format()) {
+				return Err(BeginError::IncompatibleClearValue)
+			}
+
+			check_view(ty, &ViewProperties::new(&attachment.view, attachment.layout), self.render_area, self.layer_count)?;
+			if let Some(resolve) = &attachment.resolve {
+				check_view(ty, &ViewProperties::new(&resolve.view, resolve.layout), self.render_area, self.layer_count)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Type of a dynamic rendering attachment.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum AttachmentType {
+	Color,
+	Depth,
+	Stencil
+}
+
+/// Properties of an attachment view checked when beginning a rendering.
+struct ViewProperties {
+	format: Format,
+	layout: image::Layout,
+
+	/// Extent of the first mip level of the view.
+	extent: (u32, u32),
+
+	layer_count: u32
+}
+
+impl ViewProperties {
+	fn new<I: ImageProperties>(view: &image::View<I>, layout: image::Layout) -> ViewProperties {
+		let range = view.subresource_range();
+		let (width, height, _) = view.image().mip_extent(range.base_mip_level);
+		ViewProperties {
+			format: view.format(),
+			layout,
+			extent: (width, height),
+			layer_count: range.layer_count
+		}
+	}
+}
+
+fn check_view(ty: AttachmentType, view: &ViewProperties, (x, y, width, height): (i32, i32, u32, u32), layer_count: u32) -> Result<(), BeginError> {
+	let format = view.format;
+	let valid_format = match ty {
+		AttachmentType::Color => !format.has_depth() && !format.has_stencil(),
+		AttachmentType::Depth => format.has_depth(),
+		AttachmentType::Stencil => format.has_stencil()
+	};
+
+	if !valid_format {
+		return Err(BeginError::InvalidFormat(format))
+	}
+
+	let valid_layout = match (ty, view.layout) {
+		(_, image::Layout::General) => true,
+		(AttachmentType::Color, image::Layout::ColorAttachmentOptimal) => true,
+		(AttachmentType::Depth, image::Layout::DepthStencilAttachmentOptimal)
+		| (AttachmentType::Depth, image::Layout::DepthStencilReadOnlyOptimal)
+		| (AttachmentType::Stencil, image::Layout::DepthStencilAttachmentOptimal)
+		| (AttachmentType::Stencil, image::Layout::DepthStencilReadOnlyOptimal) => true,
+		_ => false
+	};
+
+	if !valid_layout {
+		return Err(BeginError::InvalidLayout(view.layout))
+	}
+
+	let fits = |offset: i32, len: u32, max: u32| offset >= 0 && offset as u64 + len as u64 <= max as u64;
+	if !fits(x, width, view.extent.0) || !fits(y, height, view.extent.1) || layer_count > view.layer_count {
+		return Err(BeginError::InvalidArea)
+	}
+
+	Ok(())
+}
+
+/// Attachment formats of a graphics pipeline used with dynamic rendering.
+///
+/// The formats must match the ones of the views given when beginning the rendering.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RenderingFormats {
+	pub color_formats: Vec<Format>,
+	pub depth_format: Option<Format>,
+	pub stencil_format: Option<Format>
+}
+
+impl RenderingFormats {
+	pub fn new(color_formats: &[Format]) -> RenderingFormats {
+		RenderingFormats {
+			color_formats: color_formats.to_vec(),
+			depth_format: None,
+			stencil_format: None
+		}
+	}
+
+	pub fn with_depth_format(mut self, format: Format) -> RenderingFormats {
+		self.depth_format = Some(format);
+		self
+	}
+
+	pub fn with_stencil_format(mut self, format: Format) -> RenderingFormats {
+		self.stencil_format = Some(format);
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn view(format: Format, layout: image::Layout) -> ViewProperties {
+		ViewProperties {
+			format,
+			layout,
+			extent: (64, 32),
+			layer_count: 2
+		}
+	}
+
+	#[test]
+	fn attachment_formats() {
+		let color = view(Format::R8G8B8A8Unorm, image::Layout::ColorAttachmentOptimal);
+		assert!(check_view(AttachmentType::Color, &color, (0, 0, 64, 32), 1).is_ok());
+
+		let depth = view(Format::D32Sfloat, image::Layout::DepthStencilAttachmentOptimal);
+		assert!(check_view(AttachmentType::Depth, &depth, (0, 0, 64, 32), 1).is_ok());
+		assert!(matches!(check_view(AttachmentType::Stencil, &depth, (0, 0, 64, 32), 1), Err(BeginError::InvalidFormat(Format::D32Sfloat))));
+		assert!(matches!(check_view(AttachmentType::Color, &depth, (0, 0, 64, 32), 1), Err(BeginError::InvalidFormat(Format::D32Sfloat))));
+
+		let color_as_depth = view(Format::R8G8B8A8Unorm, image::Layout::DepthStencilAttachmentOptimal);
+		assert!(matches!(check_view(AttachmentType::Depth, &color_as_depth, (0, 0, 64, 32), 1), Err(BeginError::InvalidFormat(Format::R8G8B8A8Unorm))));
+
+		let depth_stencil = view(Format::D24Unorm_S8Uint, image::Layout::DepthStencilReadOnlyOptimal);
+		assert!(check_view(AttachmentType::Depth, &depth_stencil, (0, 0, 64, 32), 1).is_ok());
+		assert!(check_view(AttachmentType::Stencil, &depth_stencil, (0, 0, 64, 32), 1).is_ok());
+	}
+
+	#[test]
+	fn attachment_layouts() {
+		assert!(check_view(AttachmentType::Color, &view(Format::R8G8B8A8Unorm, image::Layout::General), (0, 0, 64, 32), 1).is_ok());
+		assert!(check_view(AttachmentType::Depth, &view(Format::D32Sfloat, image::Layout::General), (0, 0, 64, 32), 1).is_ok());
+
+		for &layout in &[image::Layout::Undefined, image::Layout::Preinitialized, image::Layout::PresentSrc, image::Layout::DepthStencilAttachmentOptimal] {
+			let color = view(Format::R8G8B8A8Unorm, layout);
+			assert!(matches!(check_view(AttachmentType::Color, &color, (0, 0, 64, 32), 1), Err(BeginError::InvalidLayout(l)) if l == layout));
+		}
+
+		let depth = view(Format::D32Sfloat, image::Layout::ColorAttachmentOptimal);
+		assert!(matches!(check_view(AttachmentType::Depth, &depth, (0, 0, 64, 32), 1), Err(BeginError::InvalidLayout(image::Layout::ColorAttachmentOptimal))));
+	}
+
+	#[test]
+	fn render_area_fits_the_attachments() {
+		let color = view(Format::R8G8B8A8Unorm, image::Layout::ColorAttachmentOptimal);
+		assert!(check_view(AttachmentType::Color, &color, (32, 16, 32, 16), 2).is_ok());
+		assert!(matches!(check_view(AttachmentType::Color, &color, (32, 16, 33, 16), 1), Err(BeginError::InvalidArea)));
+		assert!(matches!(check_view(AttachmentType::Color, &color, (0, 0, 64, 33), 1), Err(BeginError::InvalidArea)));
+		assert!(matches!(check_view(AttachmentType::Color, &color, (-1, 0, 16, 16), 1), Err(BeginError::InvalidArea)));
+		assert!(matches!(check_view(AttachmentType::Color, &color, (i32::MAX, 0, u32::MAX, 16), 1), Err(BeginError::InvalidArea)));
+		assert!(matches!(check_view(AttachmentType::Color, &color, (0, 0, 64, 32), 3), Err(BeginError::InvalidArea)));
+	}
+}
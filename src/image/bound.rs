@@ -0,0 +1,225 @@
+use std::{
+	sync::Arc,
+	fmt
+};
+use ash::vk;
+use crate::{
+	Device,
+	DeviceOwned,
+	Format,
+	format,
+	framebuffer::SampleCount,
+	mem::{
+		self,
+		Slot,
+		Allocator
+	},
+	sync
+};
+use super::{
+	view,
+	View,
+	Image,
+	ImageProperties,
+	Unbound,
+	Builder,
+	CreationError,
+	BindError,
+	Usage,
+	Flags,
+	Type,
+	Tiling,
+	LayoutTracker
+};
+
+/// Depth formats tried by `Bound::depth_attachment`, by order of preference.
+const DEPTH_FORMATS: [Format; 3] = [
+	Format::D32Sfloat,
+	Format::D32Sfloat_S8Uint,
+	Format::D24Unorm_S8Uint
+];
+
+#[derive(Debug)]
+pub enum AllocationError {
+	/// None of the candidate formats is supported by the device.
+	UnsupportedFormat,
+	Creation(CreationError),
+	Bind(BindError),
+	Memory(mem::Error),
+	View(view::CreationError)
+}
+
+impl fmt::Display for AllocationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::UnsupportedFormat => write!(f, "no supported format"),
+			Self::Creation(e) => write!(f, "image creation failed: {}", e),
+			Self::Bind(e) => write!(f, "bind failed: {}", e),
+			Self::Memory(e) => write!(f, "memory error: {}", e),
+			Self::View(e) => write!(f, "view creation failed: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for AllocationError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::UnsupportedFormat => None,
+			Self::Creation(e) => Some(e),
+			Self::Bind(e) => Some(e),
+			Self::Memory(e) => Some(e),
+			Self::View(e) => Some(e)
+		}
+	}
+}
+
+impl From<CreationError> for AllocationError {
+	fn from(e: CreationError) -> Self {
+		Self::Creation(e)
+	}
+}
+
+impl From<BindError> for AllocationError {
+	fn from(e: BindError) -> Self {
+		Self::Bind(e)
+	}
+}
+
+impl From<mem::Error> for AllocationError {
+	fn from(e: mem::Error) -> Self {
+		Self::Memory(e)
+	}
+}
+
+impl From<view::CreationError> for AllocationError {
+	fn from(e: view::CreationError) -> Self {
+		Self::View(e)
+	}
+}
+
+/// Bound image.
+pub struct Bound<S: Slot> {
+	inner: Unbound,
+	slot: S
+}
+
+impl<S: Slot> Bound<S> {
+	pub(crate) fn new(inner: Unbound, slot: S) -> Self {
+		Bound {
+			inner,
+			slot
+		}
+	}
+
+	/// Create an image bound to memory allocated by the given allocator.
+	pub fn allocate<A: Allocator<Slot=S>, Q: Into<sync::SharingQueues>>(allocator: &A, builder: &Builder, sharing_queues: Q) -> Result<Self, AllocationError> {
+		let inner = builder.build(allocator.device(), sharing_queues)?;
+		let slot = allocator.allocate_image(inner.memory_requirements(), inner.tiling(), inner.usage())?;
+		unsafe {
+			inner.bind(slot).map_err(|(_, e)| e.into())
+		}
+	}
+
+	/// Create a 2D depth attachment and its view.
+	///
+	/// The format is the first of `D32Sfloat`, `D32Sfloat_S8Uint` and `D24Unorm_S8Uint`
+	/// usable as depth-stencil attachment with optimal tiling.
+	/// The view covers the depth aspect, and the stencil aspect if the format has one.
+	pub fn depth_attachment<A: Allocator<Slot=S>, Q: Into<sync::SharingQueues>>(allocator: &A, (width, height): (u32, u32), samples: SampleCount, sharing_queues: Q) -> Result<View<Self>, AllocationError> {
+		let format = allocator.device().physical_device().find_supported_format(
+			&DEPTH_FORMATS,
+			Tiling::Optimal,
+			format::Features::DEPTH_STENCIL_ATTACHMENT
+		).ok_or(AllocationError::UnsupportedFormat)?;
+
+		let builder = Unbound::builder(format)
+			.extent_2d(width, height)
+			.samples(samples)
+			.usage(Usage {
+				depth_stencil_attachment: true,
+				..Usage::none()
+			});
+
+		let image = Self::allocate(allocator, &builder, sharing_queues)?;
+		Ok(image.create_default_view()?)
+	}
+
+	pub fn memory_slot(&self) -> &S {
+		&self.slot
+	}
+
+	/// Releases the image and returns its memory slot.
+	pub fn unbind(self) -> S {
+		self.slot
+	}
+}
+
+unsafe impl<S: Slot> crate::Resource for Bound<S> {
+	type Handle = vk::Image;
+
+	fn handle(&self) -> vk::Image {
+		self.inner.handle()
+	}
+}
+
+unsafe impl<S: Slot> Image for Bound<S> {
+	fn handle(&self) -> vk::Image {
+		self.inner.handle()
+	}
+
+	fn is_lazily_allocated(&self) -> bool {
+		self.slot.memory().memory_type().is_lazily_allocated()
+	}
+
+	fn layout_tracker(&self) -> Option<&LayoutTracker> {
+		self.inner.layout_tracker()
+	}
+}
+
+unsafe impl<S: Slot> ImageProperties for Bound<S> {
+	fn format(&self) -> Format {
+		self.inner.format()
+	}
+
+	fn ty(&self) -> Type {
+		self.inner.ty()
+	}
+
+	fn extent(&self) -> (u32, u32, u32) {
+		self.inner.extent()
+	}
+
+	fn mip_levels(&self) -> u32 {
+		self.inner.mip_levels()
+	}
+
+	fn array_layers(&self) -> u32 {
+		self.inner.array_layers()
+	}
+
+	fn samples(&self) -> SampleCount {
+		self.inner.samples()
+	}
+
+	fn tiling(&self) -> Tiling {
+		self.inner.tiling()
+	}
+
+	fn usage(&self) -> Usage {
+		self.inner.usage()
+	}
+
+	fn flags(&self) -> Flags {
+		self.inner.flags()
+	}
+
+	fn is_concurrent(&self) -> bool {
+		self.inner.is_concurrent()
+	}
+}
+
+impl<S: Slot> DeviceOwned for Bound<S> {
+	fn device(&self) -> &Arc<Device> {
+		self.inner.device()
+	}
+}
@@ -0,0 +1,60 @@
+use ash::vk;
+
+/// Image creation flags.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Flags(vk::ImageCreateFlags);
+
+impl Flags {
+	/// The image is backed by sparse memory binding.
+	pub const SPARSE_BINDING: Flags = Flags(vk::ImageCreateFlags::SPARSE_BINDING);
+
+	/// The image can be partially backed by sparse memory binding.
+	pub const SPARSE_RESIDENCY: Flags = Flags(vk::ImageCreateFlags::SPARSE_RESIDENCY);
+
+	/// The image can be backed by sparse memory also backing another image.
+	pub const SPARSE_ALIASED: Flags = Flags(vk::ImageCreateFlags::SPARSE_ALIASED);
+
+	/// Views of the image can have a different format than the image.
+	pub const MUTABLE_FORMAT: Flags = Flags(vk::ImageCreateFlags::MUTABLE_FORMAT);
+
+	/// Cube and cube array views can be created from the image.
+	pub const CUBE_COMPATIBLE: Flags = Flags(vk::ImageCreateFlags::CUBE_COMPATIBLE);
+
+	/// 2D and 2D array views can be created from the 3D image.
+	pub const ARRAY_2D_COMPATIBLE: Flags = Flags(vk::ImageCreateFlags::TYPE_2D_ARRAY_COMPATIBLE);
+
+	/// No flags.
+	#[inline]
+	pub fn none() -> Flags {
+		Flags(vk::ImageCreateFlags::empty())
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	#[inline]
+	pub fn contains(&self, other: Flags) -> bool {
+		self.0.contains(other.0)
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ImageCreateFlags {
+		self.0
+	}
+}
+
+impl std::ops::BitOr for Flags {
+	type Output = Flags;
+
+	fn bitor(self, rhs: Self) -> Flags {
+		Flags(self.0 | rhs.0)
+	}
+}
+
+impl std::ops::BitOrAssign for Flags {
+	fn bitor_assign(&mut self, rhs: Self) {
+		self.0 |= rhs.0
+	}
+}
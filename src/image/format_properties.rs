@@ -0,0 +1,81 @@
+use std::{
+	error::Error,
+	fmt
+};
+use ash::vk;
+use crate::{
+	OomError,
+	framebuffer::SampleCounts
+};
+
+#[derive(Debug)]
+pub enum FormatPropertiesError {
+	/// The combination of format, type, tiling, usage and flags is not supported by the device.
+	Unsupported,
+	OutOfMemory(OomError)
+}
+
+impl Error for FormatPropertiesError {
+	fn source(&self) -> Option<&(dyn 'static + Error)> {
+		match self {
+			FormatPropertiesError::OutOfMemory(oom) => Some(oom),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for FormatPropertiesError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			FormatPropertiesError::Unsupported => write!(f, "unsupported image format parameters"),
+			FormatPropertiesError::OutOfMemory(oom) => oom.fmt(f)
+		}
+	}
+}
+
+impl From<vk::Result> for FormatPropertiesError {
+	fn from(r: vk::Result) -> FormatPropertiesError {
+		match r {
+			vk::Result::ERROR_FORMAT_NOT_SUPPORTED => FormatPropertiesError::Unsupported,
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => FormatPropertiesError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => FormatPropertiesError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+/// Limits of images created with a given format, type, tiling, usage and flags.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageFormatProperties {
+	/// Maximum extent (width, height, depth).
+	pub max_extent: (u32, u32, u32),
+
+	/// Maximum number of mipmap levels.
+	pub max_mip_levels: u32,
+
+	/// Maximum number of array layers.
+	pub max_array_layers: u32,
+
+	/// Supported sample counts.
+	pub sample_counts: SampleCounts,
+
+	/// Upper bound on the total image size in bytes, including all its subresources.
+	pub max_resource_size: u64
+}
+
+impl ImageFormatProperties {
+	#[inline]
+	pub(crate) fn from_vulkan(properties: vk::ImageFormatProperties) -> ImageFormatProperties {
+		ImageFormatProperties {
+			max_extent: (
+				properties.max_extent.width,
+				properties.max_extent.height,
+				properties.max_extent.depth
+			),
+			max_mip_levels: properties.max_mip_levels,
+			max_array_layers: properties.max_array_layers,
+			sample_counts: SampleCounts::from_vulkan(properties.sample_counts),
+			max_resource_size: properties.max_resource_size
+		}
+	}
+}
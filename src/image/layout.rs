@@ -1,3 +1,7 @@
+use std::sync::atomic::{
+	AtomicI32,
+	Ordering
+};
 use ash::vk;
 
 /// Layout of an image.
@@ -33,4 +37,112 @@ impl Layout {
 	pub(crate) fn into_vulkan(self) -> vk::ImageLayout {
 		vk::ImageLayout::from_raw(self as i32)
 	}
+
+	#[inline]
+	pub(crate) fn from_vulkan(layout: vk::ImageLayout) -> Option<Layout> {
+		match layout {
+			vk::ImageLayout::UNDEFINED => Some(Layout::Undefined),
+			vk::ImageLayout::GENERAL => Some(Layout::General),
+			vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL => Some(Layout::ColorAttachmentOptimal),
+			vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL => Some(Layout::DepthStencilAttachmentOptimal),
+			vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL => Some(Layout::DepthStencilReadOnlyOptimal),
+			vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL => Some(Layout::ShaderReadOnlyOptimal),
+			vk::ImageLayout::TRANSFER_SRC_OPTIMAL => Some(Layout::TransferSrcOptimal),
+			vk::ImageLayout::TRANSFER_DST_OPTIMAL => Some(Layout::TransferDstOptimal),
+			vk::ImageLayout::PREINITIALIZED => Some(Layout::Preinitialized),
+			vk::ImageLayout::PRESENT_SRC_KHR => Some(Layout::PresentSrc),
+			_ => None
+		}
+	}
+
+	/// Can images be transitioned to this layout.
+	///
+	/// Images can only start in the `Undefined` and `Preinitialized` layouts.
+	#[inline]
+	pub fn is_transition_target(&self) -> bool {
+		!matches!(self, Layout::Undefined | Layout::Preinitialized)
+	}
+
+	/// Pipeline stages and accesses of an image in this layout.
+	///
+	/// Returns the stages, the write accesses to make available when leaving the layout,
+	/// and the accesses to make visible when entering the layout.
+	pub(crate) fn transition_masks(self) -> (vk::PipelineStageFlags, vk::AccessFlags, vk::AccessFlags) {
+		match self {
+			Layout::Undefined => (
+				vk::PipelineStageFlags::TOP_OF_PIPE,
+				vk::AccessFlags::empty(),
+				vk::AccessFlags::empty()
+			),
+			Layout::Preinitialized => (
+				vk::PipelineStageFlags::HOST,
+				vk::AccessFlags::HOST_WRITE,
+				vk::AccessFlags::empty()
+			),
+			Layout::General => (
+				vk::PipelineStageFlags::ALL_COMMANDS,
+				vk::AccessFlags::MEMORY_WRITE,
+				vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE
+			),
+			Layout::ColorAttachmentOptimal => (
+				vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+				vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+				vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+			),
+			Layout::DepthStencilAttachmentOptimal => (
+				vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
+				vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+				vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE
+			),
+			Layout::DepthStencilReadOnlyOptimal => (
+				vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS | vk::PipelineStageFlags::FRAGMENT_SHADER,
+				vk::AccessFlags::empty(),
+				vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ | vk::AccessFlags::SHADER_READ
+			),
+			Layout::ShaderReadOnlyOptimal => (
+				vk::PipelineStageFlags::VERTEX_SHADER | vk::PipelineStageFlags::FRAGMENT_SHADER | vk::PipelineStageFlags::COMPUTE_SHADER,
+				vk::AccessFlags::empty(),
+				vk::AccessFlags::SHADER_READ
+			),
+			Layout::TransferSrcOptimal => (
+				vk::PipelineStageFlags::TRANSFER,
+				vk::AccessFlags::empty(),
+				vk::AccessFlags::TRANSFER_READ
+			),
+			Layout::TransferDstOptimal => (
+				vk::PipelineStageFlags::TRANSFER,
+				vk::AccessFlags::TRANSFER_WRITE,
+				vk::AccessFlags::TRANSFER_WRITE
+			),
+			// Presentation is synchronized with semaphores.
+			Layout::PresentSrc => (
+				vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+				vk::AccessFlags::empty(),
+				vk::AccessFlags::empty()
+			)
+		}
+	}
+}
+
+/// Tracked layout of a whole image.
+///
+/// The tracked layout is only updated when a command buffer transitioning the image is submitted.
+#[derive(Debug)]
+pub struct LayoutTracker(AtomicI32);
+
+impl LayoutTracker {
+	pub(crate) fn new(layout: Layout) -> LayoutTracker {
+		LayoutTracker(AtomicI32::new(layout as i32))
+	}
+
+	/// Layout of the image once all the submitted command buffers are executed.
+	#[inline]
+	pub fn get(&self) -> Layout {
+		Layout::from_vulkan(vk::ImageLayout::from_raw(self.0.load(Ordering::Acquire))).unwrap()
+	}
+
+	#[inline]
+	pub(crate) fn set(&self, layout: Layout) {
+		self.0.store(layout as i32, Ordering::Release)
+	}
 }
\ No newline at end of file
@@ -1,27 +1,81 @@
-use ash::vk;
+use std::sync::Arc;
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
 use crate::{
 	DeviceOwned,
-	Format
+	Format,
+	framebuffer::SampleCount
 };
 
 mod usage;
 mod layout;
+mod tiling;
+mod ty;
+mod flags;
+mod format_properties;
+mod subresource_layout;
+mod unbound;
+mod bound;
 pub mod view;
+pub mod sampler;
 
 pub use usage::Usage;
-pub use layout::Layout;
+pub use layout::{
+	Layout,
+	LayoutTracker
+};
+pub use tiling::Tiling;
+pub use ty::Type;
+pub use flags::Flags;
+pub use format_properties::{
+	ImageFormatProperties,
+	FormatPropertiesError
+};
+pub use subresource_layout::{
+	SubresourceLayout,
+	SubresourceLayoutError
+};
+pub use unbound::{
+	Unbound,
+	Builder,
+	Mips,
+	CreationError,
+	BindError
+};
+pub use bound::{
+	Bound,
+	AllocationError
+};
 pub use view::View;
+pub use sampler::Sampler;
 
 pub unsafe trait Image: DeviceOwned {
 	fn handle(&self) -> vk::Image;
 
+	/// Checks if the image is bound to lazily allocated memory.
+	///
+	/// The content of such images only lives during a render pass,
+	/// so they must only be used as transient attachments with a `DONT_CARE` store operation.
+	fn is_lazily_allocated(&self) -> bool {
+		false
+	}
+
+	/// Tracked layout of the image, if any.
+	///
+	/// Tracked images can be transitioned without specifying their current layout.
+	fn layout_tracker(&self) -> Option<&LayoutTracker> {
+		None
+	}
+
 	fn into_view(
 		self,
 		ty: view::Type,
 		format: Format,
 		components: view::ComponentMapping,
 		subresource_range: view::SubresourceRange
-	) -> Result<View<Self>, view::CreationError> where Self: Sized {
+	) -> Result<View<Self>, view::CreationError> where Self: Sized + ImageProperties {
 		View::new(
 			self,
 			ty,
@@ -37,7 +91,7 @@ pub unsafe trait Image: DeviceOwned {
 		format: Format,
 		components: view::ComponentMapping,
 		subresource_range: view::SubresourceRange
-	) -> Result<View<&Self>, view::CreationError> {
+	) -> Result<View<&Self>, view::CreationError> where Self: ImageProperties {
 		View::new(
 			self,
 			ty,
@@ -47,6 +101,63 @@ pub unsafe trait Image: DeviceOwned {
 		)
 	}
 
+	/// Create a view of the whole image, with its own format and aspects.
+	///
+	/// 2D images are viewed as `D2`, or `D2Array` if they have multiple layers.
+	/// Cube compatible images are viewed as `Cube`, or `CubeArray` if they have more than 6 layers,
+	/// which requires the `ImageCubeArray` device feature.
+	fn create_default_view(self) -> Result<View<Self>, view::CreationError> where Self: Sized + ImageProperties {
+		let ty = default_view_type(self.ty(), self.flags(), self.array_layers());
+		let subresource_range = view::SubresourceRange {
+			aspects: self.format().aspects(),
+			base_mip_level: 0,
+			level_count: self.mip_levels(),
+			base_array_layer: 0,
+			layer_count: self.array_layers()
+		};
+
+		let format = self.format();
+		View::new(self, ty, format, view::ComponentMapping::default(), subresource_range)
+	}
+
+	/// Create a view of a single mip level of the image, covering all its layers.
+	///
+	/// Useful to render into a given mip level.
+	fn create_mip_view(self, level: u32) -> Result<View<Self>, view::CreationError> where Self: Sized + ImageProperties {
+		let ty = default_view_type(self.ty(), self.flags(), self.array_layers());
+		let subresource_range = view::SubresourceRange {
+			aspects: self.format().aspects(),
+			base_mip_level: level,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count: self.array_layers()
+		};
+
+		let format = self.format();
+		View::new(self, ty, format, view::ComponentMapping::default(), subresource_range)
+	}
+
+	/// Create a view of a single array layer of a mip level of the image.
+	///
+	/// Useful to render into a single face of a cube map.
+	fn create_layer_view(self, level: u32, layer: u32) -> Result<View<Self>, view::CreationError> where Self: Sized + ImageProperties {
+		let ty = match self.ty() {
+			Type::D1 => view::Type::D1,
+			_ => view::Type::D2
+		};
+
+		let subresource_range = view::SubresourceRange {
+			aspects: self.format().aspects(),
+			base_mip_level: level,
+			level_count: 1,
+			base_array_layer: layer,
+			layer_count: 1
+		};
+
+		let format = self.format();
+		View::new(self, ty, format, view::ComponentMapping::default(), subresource_range)
+	}
+
 	// requires #![feature(arbitrary_self_types)]
 	// fn view<I>(
 	// 	self: &I,
@@ -69,4 +180,195 @@ unsafe impl<'a, T: ?Sized + Image> Image for &'a T {
 	fn handle(&self) -> vk::Image {
 		(*self).handle()
 	}
-}
\ No newline at end of file
+
+	fn is_lazily_allocated(&self) -> bool {
+		(*self).is_lazily_allocated()
+	}
+
+	fn layout_tracker(&self) -> Option<&LayoutTracker> {
+		(*self).layout_tracker()
+	}
+}
+
+unsafe impl<T: ?Sized + Image> Image for Arc<T> {
+	fn handle(&self) -> vk::Image {
+		self.as_ref().handle()
+	}
+
+	fn is_lazily_allocated(&self) -> bool {
+		self.as_ref().is_lazily_allocated()
+	}
+
+	fn layout_tracker(&self) -> Option<&LayoutTracker> {
+		self.as_ref().layout_tracker()
+	}
+}
+
+fn default_view_type(ty: Type, flags: Flags, array_layers: u32) -> view::Type {
+	if flags.contains(Flags::CUBE_COMPATIBLE) {
+		return if array_layers == 6 {
+			view::Type::Cube
+		} else {
+			view::Type::CubeArray
+		}
+	}
+
+	match (ty, array_layers) {
+		(Type::D1, 1) => view::Type::D1,
+		(Type::D1, _) => view::Type::D1Array,
+		(Type::D2, 1) => view::Type::D2,
+		(Type::D2, _) => view::Type::D2Array,
+		(Type::D3, _) => view::Type::D
+	}
+}
+
+/// Image with known creation parameters.
+pub unsafe trait ImageProperties: Image {
+	fn format(&self) -> Format;
+
+	fn ty(&self) -> Type;
+
+	/// Extent of the image (width, height, depth).
+	fn extent(&self) -> (u32, u32, u32);
+
+	fn mip_levels(&self) -> u32;
+
+	fn array_layers(&self) -> u32;
+
+	fn samples(&self) -> SampleCount;
+
+	fn tiling(&self) -> Tiling;
+
+	/// Usage the image was created with.
+	fn usage(&self) -> Usage;
+
+	/// Flags the image was created with.
+	fn flags(&self) -> Flags;
+
+	/// Checks if the image is shared concurrently by several queue families.
+	fn is_concurrent(&self) -> bool;
+
+	/// Retrieves the memory layout of a subresource of a linearly tiled image.
+	///
+	/// The layout gives the position of the texel rows in the image memory,
+	/// for direct host access to images bound to host visible memory,
+	/// for instance allocated with a `mem::staging::Allocator`.
+	fn subresource_layout(&self, aspects: view::Aspects, mip_level: u32, array_layer: u32) -> Result<SubresourceLayout, SubresourceLayoutError> {
+		if self.tiling() != Tiling::Linear {
+			return Err(SubresourceLayoutError::NotLinear)
+		}
+
+		let aspect_mask = aspects.into_vulkan();
+		if aspect_mask.as_raw().count_ones() != 1 || !self.format().aspects().contains(aspects) {
+			return Err(SubresourceLayoutError::InvalidAspects)
+		}
+
+		if mip_level >= self.mip_levels() || array_layer >= self.array_layers() {
+			return Err(SubresourceLayoutError::OutOfRange)
+		}
+
+		let layout = unsafe {
+			self.device().handle().get_image_subresource_layout(self.handle(), vk::ImageSubresource {
+				aspect_mask,
+				mip_level,
+				array_layer
+			})
+		};
+
+		Ok(SubresourceLayout::from_vulkan(layout))
+	}
+
+	/// Extent of the given mip level.
+	fn mip_extent(&self, level: u32) -> (u32, u32, u32) {
+		let (width, height, depth) = self.extent();
+		(
+			std::cmp::max(1, width >> level),
+			std::cmp::max(1, height >> level),
+			std::cmp::max(1, depth >> level)
+		)
+	}
+}
+
+unsafe impl<'a, T: ?Sized + ImageProperties> ImageProperties for &'a T {
+	fn format(&self) -> Format {
+		(*self).format()
+	}
+
+	fn ty(&self) -> Type {
+		(*self).ty()
+	}
+
+	fn extent(&self) -> (u32, u32, u32) {
+		(*self).extent()
+	}
+
+	fn mip_levels(&self) -> u32 {
+		(*self).mip_levels()
+	}
+
+	fn array_layers(&self) -> u32 {
+		(*self).array_layers()
+	}
+
+	fn samples(&self) -> SampleCount {
+		(*self).samples()
+	}
+
+	fn tiling(&self) -> Tiling {
+		(*self).tiling()
+	}
+
+	fn usage(&self) -> Usage {
+		(*self).usage()
+	}
+
+	fn flags(&self) -> Flags {
+		(*self).flags()
+	}
+
+	fn is_concurrent(&self) -> bool {
+		(*self).is_concurrent()
+	}
+}
+
+unsafe impl<T: ?Sized + ImageProperties> ImageProperties for Arc<T> {
+	fn format(&self) -> Format {
+		self.as_ref().format()
+	}
+
+	fn ty(&self) -> Type {
+		self.as_ref().ty()
+	}
+
+	fn extent(&self) -> (u32, u32, u32) {
+		self.as_ref().extent()
+	}
+
+	fn mip_levels(&self) -> u32 {
+		self.as_ref().mip_levels()
+	}
+
+	fn array_layers(&self) -> u32 {
+		self.as_ref().array_layers()
+	}
+
+	fn samples(&self) -> SampleCount {
+		self.as_ref().samples()
+	}
+
+	fn tiling(&self) -> Tiling {
+		self.as_ref().tiling()
+	}
+
+	fn usage(&self) -> Usage {
+		self.as_ref().usage()
+	}
+
+	fn flags(&self) -> Flags {
+		self.as_ref().flags()
+	}
+
+	fn is_concurrent(&self) -> bool {
+		self.as_ref().is_concurrent()
+	}
+}
@@ -0,0 +1,421 @@
+use std::{
+	sync::Arc,
+	ops::RangeInclusive,
+	hash::{
+		Hash,
+		Hasher
+	},
+	fmt
+};
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use crate::{
+	Device,
+	DeviceOwned,
+	OomError,
+	ops::Compare,
+	device::Feature
+};
+
+/// Value of the maximum LOD that does not clamp the computed LOD.
+pub const LOD_CLAMP_NONE: f32 = vk::LOD_CLAMP_NONE;
+
+#[derive(Debug)]
+pub enum CreationError {
+	/// Anisotropic filtering was requested, but the `sampler_anisotropy` feature is not enabled.
+	AnisotropyNotEnabled,
+
+	/// The requested anisotropy is not a number greater or equal to 1.
+	InvalidAnisotropy(f32),
+
+	/// The minimum LOD is greater than the maximum LOD.
+	InvalidLodRange,
+
+	/// Unnormalized coordinates are used with incompatible parameters.
+	InvalidUnnormalizedCoordinates,
+
+	OutOfMemory(OomError)
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::AnisotropyNotEnabled => write!(f, "sampler anisotropy feature not enabled"),
+			Self::InvalidAnisotropy(a) => write!(f, "invalid anisotropy {}", a),
+			Self::InvalidLodRange => write!(f, "invalid LOD range"),
+			Self::InvalidUnnormalizedCoordinates => write!(f, "invalid use of unnormalized coordinates"),
+			Self::OutOfMemory(e) => e.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(i32)]
+pub enum Filter {
+	Nearest = vk::Filter::NEAREST.as_raw(),
+	Linear = vk::Filter::LINEAR.as_raw()
+}
+
+impl Filter {
+	pub(crate) fn into_vulkan(self) -> vk::Filter {
+		vk::Filter::from_raw(self as i32)
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(i32)]
+pub enum MipmapMode {
+	Nearest = vk::SamplerMipmapMode::NEAREST.as_raw(),
+	Linear = vk::SamplerMipmapMode::LINEAR.as_raw()
+}
+
+impl MipmapMode {
+	pub(crate) fn into_vulkan(self) -> vk::SamplerMipmapMode {
+		vk::SamplerMipmapMode::from_raw(self as i32)
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(i32)]
+pub enum AddressMode {
+	Repeat = vk::SamplerAddressMode::REPEAT.as_raw(),
+	MirroredRepeat = vk::SamplerAddressMode::MIRRORED_REPEAT.as_raw(),
+	ClampToEdge = vk::SamplerAddressMode::CLAMP_TO_EDGE.as_raw(),
+	ClampToBorder = vk::SamplerAddressMode::CLAMP_TO_BORDER.as_raw()
+}
+
+impl AddressMode {
+	pub(crate) fn into_vulkan(self) -> vk::SamplerAddressMode {
+		vk::SamplerAddressMode::from_raw(self as i32)
+	}
+}
+
+/// Color used outside of the image with the `ClampToBorder` address mode.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(i32)]
+pub enum BorderColor {
+	TransparentBlack = vk::BorderColor::FLOAT_TRANSPARENT_BLACK.as_raw(),
+	IntTransparentBlack = vk::BorderColor::INT_TRANSPARENT_BLACK.as_raw(),
+	OpaqueBlack = vk::BorderColor::FLOAT_OPAQUE_BLACK.as_raw(),
+	IntOpaqueBlack = vk::BorderColor::INT_OPAQUE_BLACK.as_raw(),
+	OpaqueWhite = vk::BorderColor::FLOAT_OPAQUE_WHITE.as_raw(),
+	IntOpaqueWhite = vk::BorderColor::INT_OPAQUE_WHITE.as_raw()
+}
+
+impl BorderColor {
+	pub(crate) fn into_vulkan(self) -> vk::BorderColor {
+		vk::BorderColor::from_raw(self as i32)
+	}
+}
+
+/// Sampler builder.
+///
+/// Builders are hashable and comparable (floats are compared bitwise),
+/// so they can be used as keys of a sampler cache.
+#[derive(Clone, Copy, Debug)]
+pub struct Builder {
+	mag_filter: Filter,
+	min_filter: Filter,
+	mipmap_mode: MipmapMode,
+	address_mode: (AddressMode, AddressMode, AddressMode),
+	mip_lod_bias: f32,
+	anisotropy: Option<f32>,
+	compare: Option<Compare>,
+	min_lod: f32,
+	max_lod: f32,
+	border_color: BorderColor,
+	unnormalized_coordinates: bool
+}
+
+impl Builder {
+	/// Nearest filtering, repeating addressing and no LOD clamping.
+	pub fn new() -> Builder {
+		Builder {
+			mag_filter: Filter::Nearest,
+			min_filter: Filter::Nearest,
+			mipmap_mode: MipmapMode::Nearest,
+			address_mode: (AddressMode::Repeat, AddressMode::Repeat, AddressMode::Repeat),
+			mip_lod_bias: 0.0,
+			anisotropy: None,
+			compare: None,
+			min_lod: 0.0,
+			max_lod: LOD_CLAMP_NONE,
+			border_color: BorderColor::TransparentBlack,
+			unnormalized_coordinates: false
+		}
+	}
+
+	pub fn mag_filter(mut self, filter: Filter) -> Self {
+		self.mag_filter = filter;
+		self
+	}
+
+	pub fn min_filter(mut self, filter: Filter) -> Self {
+		self.min_filter = filter;
+		self
+	}
+
+	/// Set both the magnification and minification filters.
+	pub fn filter(self, filter: Filter) -> Self {
+		self.mag_filter(filter).min_filter(filter)
+	}
+
+	pub fn mipmap_mode(mut self, mode: MipmapMode) -> Self {
+		self.mipmap_mode = mode;
+		self
+	}
+
+	/// Set the address mode of all the coordinates.
+	pub fn address_mode(mut self, mode: AddressMode) -> Self {
+		self.address_mode = (mode, mode, mode);
+		self
+	}
+
+	/// Set the address mode of each coordinate.
+	pub fn address_mode_uvw(mut self, u: AddressMode, v: AddressMode, w: AddressMode) -> Self {
+		self.address_mode = (u, v, w);
+		self
+	}
+
+	pub fn mip_lod_bias(mut self, bias: f32) -> Self {
+		self.mip_lod_bias = bias;
+		self
+	}
+
+	/// Enable anisotropic filtering.
+	///
+	/// The value is clamped to the `max_sampler_anisotropy` limit of the device.
+	pub fn anisotropy(mut self, max_anisotropy: f32) -> Self {
+		self.anisotropy = Some(max_anisotropy);
+		self
+	}
+
+	/// Range the computed LOD is clamped to.
+	///
+	/// Use `LOD_CLAMP_NONE` as upper bound to disable the clamping.
+	pub fn lod(mut self, range: RangeInclusive<f32>) -> Self {
+		self.min_lod = *range.start();
+		self.max_lod = *range.end();
+		self
+	}
+
+	pub fn border_color(mut self, color: BorderColor) -> Self {
+		self.border_color = color;
+		self
+	}
+
+	/// Enable depth comparison against the reference value, for shadow maps.
+	pub fn compare(mut self, op: Compare) -> Self {
+		self.compare = Some(op);
+		self
+	}
+
+	/// Address texels with coordinates in `[0, width)` instead of `[0, 1)`.
+	pub fn unnormalized_coordinates(mut self) -> Self {
+		self.unnormalized_coordinates = true;
+		self
+	}
+
+	fn validate(&self, device: &Device) -> Result<Option<f32>, CreationError> {
+		if !(self.min_lod <= self.max_lod) {
+			return Err(CreationError::InvalidLodRange)
+		}
+
+		let anisotropy = match self.anisotropy {
+			Some(anisotropy) => {
+				if !device.enabled_features().contains(Feature::SamplerAnisotropy) {
+					return Err(CreationError::AnisotropyNotEnabled)
+				}
+
+				if !(anisotropy >= 1.0) {
+					return Err(CreationError::InvalidAnisotropy(anisotropy))
+				}
+
+				let max = device.physical_device().limits().max_sampler_anisotropy();
+				Some(anisotropy.min(max))
+			},
+			None => None
+		};
+
+		if self.unnormalized_coordinates {
+			let clamped = |mode| mode == AddressMode::ClampToEdge || mode == AddressMode::ClampToBorder;
+			if self.mag_filter != self.min_filter
+			|| self.mipmap_mode != MipmapMode::Nearest
+			|| self.min_lod != 0.0
+			|| self.max_lod != 0.0
+			|| !clamped(self.address_mode.0)
+			|| !clamped(self.address_mode.1)
+			|| anisotropy.is_some()
+			|| self.compare.is_some() {
+				return Err(CreationError::InvalidUnnormalizedCoordinates)
+			}
+		}
+
+		Ok(anisotropy)
+	}
+
+	pub fn build(&self, device: &Arc<Device>) -> Result<Sampler, CreationError> {
+		let anisotropy = self.validate(device)?;
+
+		let infos = vk::SamplerCreateInfo {
+			mag_filter: self.mag_filter.into_vulkan(),
+			min_filter: self.min_filter.into_vulkan(),
+			mipmap_mode: self.mipmap_mode.into_vulkan(),
+			address_mode_u: self.address_mode.0.into_vulkan(),
+			address_mode_v: self.address_mode.1.into_vulkan(),
+			address_mode_w: self.address_mode.2.into_vulkan(),
+			mip_lod_bias: self.mip_lod_bias,
+			anisotropy_enable: if anisotropy.is_some() { vk::TRUE } else { vk::FALSE },
+			max_anisotropy: anisotropy.unwrap_or(1.0),
+			compare_enable: if self.compare.is_some() { vk::TRUE } else { vk::FALSE },
+			compare_op: self.compare.unwrap_or_default().into_vulkan(),
+			min_lod: self.min_lod,
+			max_lod: self.max_lod,
+			border_color: self.border_color.into_vulkan(),
+			unnormalized_coordinates: if self.unnormalized_coordinates { vk::TRUE } else { vk::FALSE },
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle.create_sampler(&infos, None)?
+		};
+
+		Ok(Sampler {
+			device: device.clone(),
+			handle,
+			infos: Builder {
+				anisotropy,
+				..*self
+			}
+		})
+	}
+}
+
+impl Default for Builder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl PartialEq for Builder {
+	fn eq(&self, other: &Self) -> bool {
+		self.mag_filter == other.mag_filter
+			&& self.min_filter == other.min_filter
+			&& self.mipmap_mode == other.mipmap_mode
+			&& self.address_mode == other.address_mode
+			&& self.mip_lod_bias.to_bits() == other.mip_lod_bias.to_bits()
+			&& self.anisotropy.map(f32::to_bits) == other.anisotropy.map(f32::to_bits)
+			&& self.compare == other.compare
+			&& self.min_lod.to_bits() == other.min_lod.to_bits()
+			&& self.max_lod.to_bits() == other.max_lod.to_bits()
+			&& self.border_color == other.border_color
+			&& self.unnormalized_coordinates == other.unnormalized_coordinates
+	}
+}
+
+impl Eq for Builder {}
+
+impl Hash for Builder {
+	fn hash<H: Hasher>(&self, h: &mut H) {
+		self.mag_filter.hash(h);
+		self.min_filter.hash(h);
+		self.mipmap_mode.hash(h);
+		self.address_mode.hash(h);
+		self.mip_lod_bias.to_bits().hash(h);
+		self.anisotropy.map(f32::to_bits).hash(h);
+		self.compare.hash(h);
+		self.min_lod.to_bits().hash(h);
+		self.max_lod.to_bits().hash(h);
+		self.border_color.hash(h);
+		self.unnormalized_coordinates.hash(h);
+	}
+}
+
+/// Sampler.
+///
+/// Two samplers are equal if they belong to the same device and have the same parameters.
+pub struct Sampler {
+	device: Arc<Device>,
+	handle: vk::Sampler,
+
+	/// Parameters of the sampler, with the effective anisotropy.
+	infos: Builder
+}
+
+impl Sampler {
+	#[inline]
+	pub fn builder() -> Builder {
+		Builder::new()
+	}
+
+	#[inline]
+	pub fn handle(&self) -> vk::Sampler {
+		self.handle
+	}
+
+	/// Parameters of the sampler.
+	///
+	/// The anisotropy is the one actually used, after clamping.
+	#[inline]
+	pub fn infos(&self) -> &Builder {
+		&self.infos
+	}
+
+}
+
+impl PartialEq for Sampler {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.device, &other.device) && self.infos == other.infos
+	}
+}
+
+impl Eq for Sampler {}
+
+impl Hash for Sampler {
+	fn hash<H: Hasher>(&self, h: &mut H) {
+		self.infos.hash(h)
+	}
+}
+
+unsafe impl crate::Resource for Sampler {
+	type Handle = vk::Sampler;
+
+	fn handle(&self) -> vk::Sampler {
+		self.handle
+	}
+}
+
+impl DeviceOwned for Sampler {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for Sampler {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle.destroy_sampler(self.handle, None)
+		}
+	}
+}
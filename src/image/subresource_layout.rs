@@ -0,0 +1,59 @@
+use std::fmt;
+use ash::vk;
+
+/// Memory layout of a subresource of a linearly tiled image.
+///
+/// Offsets and pitches are in bytes, relative to the start of the image memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubresourceLayout {
+	/// Offset of the subresource.
+	pub offset: u64,
+
+	/// Size of the subresource, including the row padding.
+	pub size: u64,
+
+	/// Distance between the start of two consecutive rows.
+	pub row_pitch: u64,
+
+	/// Distance between the start of two consecutive array layers.
+	pub array_pitch: u64,
+
+	/// Distance between the start of two consecutive depth slices.
+	pub depth_pitch: u64
+}
+
+impl SubresourceLayout {
+	pub(crate) fn from_vulkan(layout: vk::SubresourceLayout) -> SubresourceLayout {
+		SubresourceLayout {
+			offset: layout.offset,
+			size: layout.size,
+			row_pitch: layout.row_pitch,
+			array_pitch: layout.array_pitch,
+			depth_pitch: layout.depth_pitch
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubresourceLayoutError {
+	/// The image is not linearly tiled.
+	NotLinear,
+
+	/// The aspects must be a single aspect of the image format.
+	InvalidAspects,
+
+	/// The mip level or array layer is out of the image.
+	OutOfRange
+}
+
+impl fmt::Display for SubresourceLayoutError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::NotLinear => write!(f, "image is not linearly tiled"),
+			Self::InvalidAspects => write!(f, "invalid aspects"),
+			Self::OutOfRange => write!(f, "subresource out of the image")
+		}
+	}
+}
+
+impl std::error::Error for SubresourceLayoutError { }
@@ -0,0 +1,21 @@
+use ash::vk;
+
+/// Tiling of an image.
+///
+/// Describes how the texels of an image are arranged in memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Tiling {
+	/// Implementation dependent arrangement, for efficient memory accesses.
+	Optimal = vk::ImageTiling::OPTIMAL.as_raw(),
+
+	/// Row-major arrangement, with possible padding at the end of each row.
+	Linear = vk::ImageTiling::LINEAR.as_raw()
+}
+
+impl Tiling {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ImageTiling {
+		vk::ImageTiling::from_raw(self as i32)
+	}
+}
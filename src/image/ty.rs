@@ -0,0 +1,17 @@
+use ash::vk;
+
+/// Dimensionality of an image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Type {
+	D1 = vk::ImageType::TYPE_1D.as_raw(),
+	D2 = vk::ImageType::TYPE_2D.as_raw(),
+	D3 = vk::ImageType::TYPE_3D.as_raw()
+}
+
+impl Type {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ImageType {
+		vk::ImageType::from_raw(self as i32)
+	}
+}
@@ -0,0 +1,728 @@
+use std::{
+	sync::Arc,
+	ffi::c_void,
+	fmt
+};
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use crate::{
+	Device,
+	DeviceOwned,
+	Format,
+	OomError,
+	sync,
+	framebuffer::SampleCount,
+	mem::{
+		MemoryRequirements,
+		DedicatedResource,
+		ExternalMemoryHandleType,
+		Slot
+	}
+};
+use super::{
+	Bound,
+	Usage,
+	Flags,
+	Type,
+	Tiling,
+	Layout,
+	LayoutTracker,
+	FormatPropertiesError
+};
+
+#[derive(Debug)]
+pub enum CreationError {
+	/// One of the dimensions of the extent is zero.
+	EmptyExtent,
+
+	/// The image must have at least one array layer.
+	NoArrayLayers,
+
+	/// The usage of the image is empty.
+	NoUsage,
+
+	/// Transient attachments can only be used as color, depth-stencil or input attachments.
+	InvalidTransientUsage(Usage),
+
+	/// More mip levels than the extent allows.
+	TooManyMipLevels {
+		requested: u32,
+		max: u32
+	},
+
+	/// Cube compatible images must be 2D.
+	CubeIncompatibleType(Type),
+
+	/// Cube compatible images must have a square extent.
+	NonSquareCube,
+
+	/// Cube compatible images must have a multiple of 6 array layers.
+	InvalidCubeLayerCount(u32),
+
+	/// 3D images must have exactly one array layer.
+	LayeredVolume,
+
+	/// Multisampled images must be optimally tiled 2D attachments with a single mip level.
+	InvalidMultisampling(SampleCount),
+
+	/// The format does not support the features required by the usage with the requested tiling,
+	/// such as `STORAGE_IMAGE` for storage images.
+	UnsupportedUsage(Usage),
+
+	/// The sample count is not supported by the device for the image format and usage.
+	UnsupportedSampleCount(SampleCount),
+
+	/// The combination of format, type, tiling, usage and flags is not supported by the device,
+	/// or the extent, mip levels or array layers exceed its limits.
+	///
+	/// Linearly tiled images typically only support transfers, and sometimes sampling.
+	UnsupportedParameters,
+
+	OutOfMemory(OomError)
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::EmptyExtent => write!(f, "empty image extent"),
+			Self::NoArrayLayers => write!(f, "no array layers"),
+			Self::NoUsage => write!(f, "empty image usage"),
+			Self::InvalidTransientUsage(usage) => write!(f, "invalid transient attachment usage {:?}", usage),
+			Self::TooManyMipLevels { requested, max } => write!(f, "too many mip levels ({} requested, at most {})", requested, max),
+			Self::CubeIncompatibleType(ty) => write!(f, "{:?} images cannot be cube compatible", ty),
+			Self::NonSquareCube => write!(f, "cube compatible images must be square"),
+			Self::InvalidCubeLayerCount(n) => write!(f, "cube compatible images must have a multiple of 6 array layers (got {})", n),
+			Self::LayeredVolume => write!(f, "3D images cannot have multiple array layers"),
+			Self::InvalidMultisampling(samples) => write!(f, "invalid use of {} samples", samples.count()),
+			Self::UnsupportedUsage(usage) => write!(f, "usage {:?} not supported by the format", usage),
+			Self::UnsupportedSampleCount(samples) => write!(f, "unsupported sample count {}", samples.count()),
+			Self::UnsupportedParameters => write!(f, "unsupported image parameters"),
+			Self::OutOfMemory(e) => e.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum BindError {
+	OutOfMemory(OomError)
+}
+
+impl fmt::Display for BindError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for BindError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e)
+		}
+	}
+}
+
+impl From<vk::Result> for BindError {
+	fn from(r: vk::Result) -> BindError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => BindError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => BindError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+/// Number of mip levels of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mips {
+	/// Full mip chain, down to a 1x1 level.
+	Auto,
+
+	/// Given number of levels.
+	Count(u32)
+}
+
+impl Mips {
+	/// Maximum number of mip levels for the given extent.
+	#[inline]
+	pub fn max_count((width, height, depth): (u32, u32, u32)) -> u32 {
+		let max = std::cmp::max(width, std::cmp::max(height, depth));
+		32 - max.leading_zeros()
+	}
+
+	/// Number of mip levels for the given extent.
+	#[inline]
+	pub fn count(self, extent: (u32, u32, u32)) -> u32 {
+		match self {
+			Mips::Auto => Self::max_count(extent),
+			Mips::Count(n) => n
+		}
+	}
+}
+
+impl From<u32> for Mips {
+	fn from(n: u32) -> Mips {
+		Mips::Count(n)
+	}
+}
+
+/// Image builder.
+#[derive(Debug, Clone, Copy)]
+pub struct Builder {
+	format: Format,
+	ty: Type,
+	extent: (u32, u32, u32),
+	mips: Mips,
+	array_layers: u32,
+	samples: SampleCount,
+	tiling: Tiling,
+	usage: Usage,
+	flags: Flags,
+	track_layout: bool,
+	external: Option<ExternalMemoryHandleType>
+}
+
+impl Builder {
+	/// Single layered 2D image of the given format, with a single mip level and sample.
+	pub fn new(format: Format) -> Builder {
+		Builder {
+			format,
+			ty: Type::D2,
+			extent: (1, 1, 1),
+			mips: Mips::Count(1),
+			array_layers: 1,
+			samples: SampleCount::S1,
+			tiling: Tiling::Optimal,
+			usage: Usage::none(),
+			flags: Flags::none(),
+			track_layout: true,
+			external: None
+		}
+	}
+
+	pub fn extent_1d(mut self, width: u32) -> Self {
+		self.ty = Type::D1;
+		self.extent = (width, 1, 1);
+		self
+	}
+
+	pub fn extent_2d(mut self, width: u32, height: u32) -> Self {
+		self.ty = Type::D2;
+		self.extent = (width, height, 1);
+		self
+	}
+
+	pub fn extent_3d(mut self, width: u32, height: u32, depth: u32) -> Self {
+		self.ty = Type::D3;
+		self.extent = (width, height, depth);
+		self
+	}
+
+	pub fn mip_levels<M: Into<Mips>>(mut self, mips: M) -> Self {
+		self.mips = mips.into();
+		self
+	}
+
+	pub fn array_layers(mut self, array_layers: u32) -> Self {
+		self.array_layers = array_layers;
+		self
+	}
+
+	pub fn samples(mut self, samples: SampleCount) -> Self {
+		self.samples = samples;
+		self
+	}
+
+	pub fn tiling(mut self, tiling: Tiling) -> Self {
+		self.tiling = tiling;
+		self
+	}
+
+	pub fn usage(mut self, usage: Usage) -> Self {
+		self.usage = usage;
+		self
+	}
+
+	pub fn flags(mut self, flags: Flags) -> Self {
+		self.flags |= flags;
+		self
+	}
+
+	/// Allows cube and cube array views of the image.
+	///
+	/// The image must be 2D and square, with a multiple of 6 array layers,
+	/// otherwise `build` fails with `CreationError::InvalidCubeLayerCount`.
+	/// Use `cubes` to set both the flag and the array layers.
+	pub fn cube_compatible(self) -> Self {
+		self.flags(Flags::CUBE_COMPATIBLE)
+	}
+
+	/// Make a cube map, or an array of `count` cube maps, with 6 array layers per cube.
+	pub fn cubes(self, count: u32) -> Self {
+		self.array_layers(6 * count).cube_compatible()
+	}
+
+	/// Allows views of the image with a different format.
+	pub fn mutable_format(self) -> Self {
+		self.flags(Flags::MUTABLE_FORMAT)
+	}
+
+	/// Do not track the layout of the image.
+	///
+	/// Transitions of untracked images must specify the current layout of the image.
+	pub fn untracked_layout(mut self) -> Self {
+		self.track_layout = false;
+		self
+	}
+
+	/// Allows binding the image to external memory of the given handle type.
+	pub fn external(mut self, handle_type: ExternalMemoryHandleType) -> Self {
+		self.external = Some(handle_type);
+		self
+	}
+
+	/// Number of mip levels of the built image.
+	#[inline]
+	pub fn mip_level_count(&self) -> u32 {
+		self.mips.count(self.extent)
+	}
+
+	/// Checks the image parameters, and returns the number of mip levels.
+	fn validate(&self) -> Result<u32, CreationError> {
+		let (width, height, depth) = self.extent;
+		if width == 0 || height == 0 || depth == 0 {
+			return Err(CreationError::EmptyExtent)
+		}
+
+		if self.array_layers == 0 {
+			return Err(CreationError::NoArrayLayers)
+		}
+
+		if self.usage.to_vulkan().is_empty() {
+			return Err(CreationError::NoUsage)
+		}
+
+		if self.usage.transient_attachment
+		&& (self.usage.transfer_source || self.usage.transfer_destination || self.usage.sampled || self.usage.storage) {
+			return Err(CreationError::InvalidTransientUsage(self.usage))
+		}
+
+		let mip_levels = self.mip_level_count();
+		let max = Mips::max_count(self.extent);
+		if mip_levels == 0 || mip_levels > max {
+			return Err(CreationError::TooManyMipLevels {
+				requested: mip_levels,
+				max
+			})
+		}
+
+		if self.ty == Type::D3 && self.array_layers > 1 {
+			return Err(CreationError::LayeredVolume)
+		}
+
+		if self.flags.contains(Flags::CUBE_COMPATIBLE) {
+			if self.ty != Type::D2 {
+				return Err(CreationError::CubeIncompatibleType(self.ty))
+			}
+
+			if width != height {
+				return Err(CreationError::NonSquareCube)
+			}
+
+			if self.array_layers % 6 != 0 {
+				return Err(CreationError::InvalidCubeLayerCount(self.array_layers))
+			}
+		}
+
+		if self.samples.count() > 1 {
+			let attachment = self.usage.color_attachment
+				|| self.usage.depth_stencil_attachment
+				|| self.usage.input_attachment
+				|| self.usage.transient_attachment;
+
+			if !attachment
+			|| self.ty != Type::D2
+			|| self.tiling != Tiling::Optimal
+			|| mip_levels > 1
+			|| self.flags.contains(Flags::CUBE_COMPATIBLE) {
+				return Err(CreationError::InvalidMultisampling(self.samples))
+			}
+		}
+
+		Ok(mip_levels)
+	}
+
+	/// Checks the sample count against the framebuffer and sampling limits of the device.
+	fn check_samples(&self, device: &Device) -> Result<(), CreationError> {
+		if self.samples == SampleCount::S1 {
+			return Ok(())
+		}
+
+		let limits = device.physical_device().limits();
+		let has_depth = self.format.has_depth();
+		let has_stencil = self.format.has_stencil();
+		let color = !has_depth && !has_stencil;
+
+		let supported = (!self.usage.color_attachment || limits.framebuffer_color_sample_counts().contains(self.samples))
+			&& (!(self.usage.depth_stencil_attachment && has_depth) || limits.framebuffer_depth_sample_counts().contains(self.samples))
+			&& (!(self.usage.depth_stencil_attachment && has_stencil) || limits.framebuffer_stencil_sample_counts().contains(self.samples))
+			&& (!(self.usage.sampled && color) || limits.sampled_image_color_sample_counts().contains(self.samples))
+			&& (!(self.usage.sampled && has_depth) || limits.sampled_image_depth_sample_counts().contains(self.samples))
+			&& (!(self.usage.sampled && has_stencil) || limits.sampled_image_stencil_sample_counts().contains(self.samples))
+			&& (!self.usage.storage || limits.storage_image_sample_counts().contains(self.samples));
+
+		if supported {
+			Ok(())
+		} else {
+			Err(CreationError::UnsupportedSampleCount(self.samples))
+		}
+	}
+
+	/// Checks the image parameters against the format limits of the device.
+	fn check_format_properties(&self, device: &Device, mip_levels: u32) -> Result<(), CreationError> {
+		let properties = match device.physical_device().image_format_properties(self.format, self.ty, self.tiling, self.usage, self.flags) {
+			Ok(properties) => properties,
+			Err(FormatPropertiesError::Unsupported) => return Err(CreationError::UnsupportedParameters),
+			Err(FormatPropertiesError::OutOfMemory(e)) => return Err(CreationError::OutOfMemory(e))
+		};
+
+		let (width, height, depth) = self.extent;
+		let (max_width, max_height, max_depth) = properties.max_extent;
+		if width > max_width
+		|| height > max_height
+		|| depth > max_depth
+		|| mip_levels > properties.max_mip_levels
+		|| self.array_layers > properties.max_array_layers
+		|| !properties.sample_counts.contains(self.samples) {
+			return Err(CreationError::UnsupportedParameters)
+		}
+
+		Ok(())
+	}
+
+	/// Create the image.
+	///
+	/// Linearly tiled images are checked against the format limits of the device.
+	///
+	/// The image is created in the `Undefined` layout, and must be bound to memory before use.
+	pub fn build<S: Into<sync::SharingQueues>>(&self, device: &Arc<Device>, sharing_queues: S) -> Result<Unbound, CreationError> {
+		let mip_levels = self.validate()?;
+		if !self.format.properties(device.physical_device()).tiling_features(self.tiling).contains(self.usage.format_features()) {
+			return Err(CreationError::UnsupportedUsage(self.usage))
+		}
+
+		self.check_samples(device)?;
+		if self.tiling == Tiling::Linear {
+			self.check_format_properties(device, mip_levels)?
+		}
+
+		let sharing_queues = sharing_queues.into();
+		let (sh_mode, sh_count, sh_indices) = sharing_queues.as_vulkan();
+
+		let external_infos = self.external.map(|handle_type| vk::ExternalMemoryImageCreateInfo {
+			handle_types: handle_type.into_vulkan(),
+			..Default::default()
+		});
+
+		let infos = vk::ImageCreateInfo {
+			p_next: match &external_infos {
+				Some(external_infos) => external_infos as *const vk::ExternalMemoryImageCreateInfo as *const c_void,
+				None => std::ptr::null()
+			},
+			flags: self.flags.into_vulkan(),
+			image_type: self.ty.into_vulkan(),
+			format: self.format.into_vulkan(),
+			extent: vk::Extent3D {
+				width: self.extent.0,
+				height: self.extent.1,
+				depth: self.extent.2
+			},
+			mip_levels,
+			array_layers: self.array_layers,
+			samples: self.samples.into_vulkan(),
+			tiling: self.tiling.into_vulkan(),
+			usage: self.usage.to_vulkan(),
+			sharing_mode: sh_mode,
+			queue_family_index_count: sh_count,
+			p_queue_family_indices: sh_indices,
+			initial_layout: vk::ImageLayout::UNDEFINED,
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle.create_image(&infos, None)?
+		};
+
+		Ok(Unbound {
+			handle,
+			device: device.clone(),
+			format: self.format,
+			ty: self.ty,
+			extent: self.extent,
+			mip_levels,
+			array_layers: self.array_layers,
+			samples: self.samples,
+			tiling: self.tiling,
+			usage: self.usage,
+			flags: self.flags,
+			concurrent: sharing_queues.is_concurrent(),
+			layout: if self.track_layout {
+				Some(LayoutTracker::new(Layout::Undefined))
+			} else {
+				None
+			}
+		})
+	}
+}
+
+/// Image not yet bound to memory.
+///
+/// It does not implement `Image` since it cannot be used by any command or view before memory is bound with `bind`.
+pub struct Unbound {
+	handle: vk::Image,
+	device: Arc<Device>,
+	format: Format,
+	ty: Type,
+	extent: (u32, u32, u32),
+	mip_levels: u32,
+	array_layers: u32,
+	samples: SampleCount,
+	tiling: Tiling,
+	usage: Usage,
+	flags: Flags,
+	concurrent: bool,
+	layout: Option<LayoutTracker>
+}
+
+impl Unbound {
+	/// Start building an image of the given format.
+	#[inline]
+	pub fn builder(format: Format) -> Builder {
+		Builder::new(format)
+	}
+
+	#[inline]
+	pub fn handle(&self) -> vk::Image {
+		self.handle
+	}
+
+	#[inline]
+	pub fn layout_tracker(&self) -> Option<&LayoutTracker> {
+		self.layout.as_ref()
+	}
+
+	#[inline]
+	pub fn format(&self) -> Format {
+		self.format
+	}
+
+	#[inline]
+	pub fn ty(&self) -> Type {
+		self.ty
+	}
+
+	#[inline]
+	pub fn extent(&self) -> (u32, u32, u32) {
+		self.extent
+	}
+
+	#[inline]
+	pub fn mip_levels(&self) -> u32 {
+		self.mip_levels
+	}
+
+	#[inline]
+	pub fn array_layers(&self) -> u32 {
+		self.array_layers
+	}
+
+	#[inline]
+	pub fn samples(&self) -> SampleCount {
+		self.samples
+	}
+
+	#[inline]
+	pub fn tiling(&self) -> Tiling {
+		self.tiling
+	}
+
+	#[inline]
+	pub fn usage(&self) -> Usage {
+		self.usage
+	}
+
+	#[inline]
+	pub fn flags(&self) -> Flags {
+		self.flags
+	}
+
+	/// Checks if the image is shared concurrently by several queue families.
+	#[inline]
+	pub fn is_concurrent(&self) -> bool {
+		self.concurrent
+	}
+
+	/// Memory requirements of the image.
+	///
+	/// If the device supports dedicated allocations,
+	/// the requirements also tell if the image prefers or requires one.
+	#[inline]
+	pub fn memory_requirements(&self) -> MemoryRequirements {
+		unsafe {
+			if self.device.supports_dedicated_allocation() {
+				let info = vk::ImageMemoryRequirementsInfo2 {
+					image: self.handle,
+					..Default::default()
+				};
+
+				let mut dedicated = vk::MemoryDedicatedRequirements::default();
+				let mut mr = vk::MemoryRequirements2 {
+					p_next: &mut dedicated as *mut vk::MemoryDedicatedRequirements as *mut c_void,
+					..Default::default()
+				};
+
+				if self.device.get_image_memory_requirements2(&info, &mut mr) {
+					return MemoryRequirements::from_vulkan(mr.memory_requirements, &dedicated, DedicatedResource::Image(self.handle))
+				}
+			}
+
+			let mr = self.device.handle.get_image_memory_requirements(self.handle);
+			MemoryRequirements::new(mr)
+		}
+	}
+
+	#[inline]
+	pub unsafe fn bind<S: Slot>(self, slot: S) -> Result<Bound<S>, (Self, BindError)> {
+		let memory = slot.memory();
+
+		// We check for correctness in debug mode.
+		debug_assert!({
+			let mem_reqs = self.memory_requirements();
+			mem_reqs.size() <= (memory.size() - slot.offset()) as u64
+				&& (slot.offset() as u64 % mem_reqs.alignment()) == 0
+				&& mem_reqs.contains_memory_type_index(memory.memory_type().index())
+		});
+
+		match self.device.handle.bind_image_memory(self.handle, memory.handle(), slot.offset()) {
+			Ok(()) => (),
+			Err(e) => return Err((self, e.into()))
+		}
+
+		Ok(Bound::new(self, slot))
+	}
+}
+
+unsafe impl crate::Resource for Unbound {
+	type Handle = vk::Image;
+
+	fn handle(&self) -> vk::Image {
+		self.handle
+	}
+}
+
+impl DeviceOwned for Unbound {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for Unbound {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle.destroy_image(self.handle, None);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn attachment() -> Usage {
+		Usage {
+			color_attachment: true,
+			..Usage::none()
+		}
+	}
+
+	#[test]
+	fn empty_extent() {
+		let builder = Builder::new(Format::R8G8B8A8Unorm).usage(attachment());
+		assert!(matches!(builder.extent_2d(0, 16).validate(), Err(CreationError::EmptyExtent)));
+		assert!(matches!(builder.extent_3d(16, 16, 0).validate(), Err(CreationError::EmptyExtent)));
+		assert!(matches!(builder.extent_2d(16, 16).array_layers(0).validate(), Err(CreationError::NoArrayLayers)));
+	}
+
+	#[test]
+	fn invalid_usage() {
+		let builder = Builder::new(Format::R8G8B8A8Unorm).extent_2d(16, 16);
+		assert!(matches!(builder.validate(), Err(CreationError::NoUsage)));
+
+		let transient = Usage {
+			transient_attachment: true,
+			..attachment()
+		};
+		assert!(matches!(builder.usage(transient).validate(), Ok(1)));
+
+		let sampled = Usage {
+			sampled: true,
+			..transient
+		};
+		assert!(matches!(builder.usage(sampled).validate(), Err(CreationError::InvalidTransientUsage(_))));
+	}
+
+	#[test]
+	fn mip_levels() {
+		let builder = Builder::new(Format::R8G8B8A8Unorm).extent_2d(16, 8).usage(attachment());
+		assert!(matches!(builder.mip_levels(Mips::Auto).validate(), Ok(5)));
+		assert!(matches!(builder.mip_levels(6).validate(), Err(CreationError::TooManyMipLevels { requested: 6, max: 5 })));
+		assert!(matches!(builder.mip_levels(0).validate(), Err(CreationError::TooManyMipLevels { requested: 0, .. })));
+	}
+
+	#[test]
+	fn invalid_cubes() {
+		let builder = Builder::new(Format::R8G8B8A8Unorm).usage(attachment());
+		assert!(matches!(builder.extent_2d(16, 16).cubes(2).validate(), Ok(1)));
+		assert!(matches!(builder.extent_2d(16, 8).cubes(1).validate(), Err(CreationError::NonSquareCube)));
+		assert!(matches!(builder.extent_2d(16, 16).array_layers(4).cube_compatible().validate(), Err(CreationError::InvalidCubeLayerCount(4))));
+		assert!(matches!(builder.extent_3d(16, 16, 16).cube_compatible().validate(), Err(CreationError::CubeIncompatibleType(Type::D3))));
+		assert!(matches!(builder.extent_3d(16, 16, 16).array_layers(2).validate(), Err(CreationError::LayeredVolume)));
+	}
+
+	#[test]
+	fn invalid_multisampling() {
+		let builder = Builder::new(Format::R8G8B8A8Unorm).extent_2d(16, 16).samples(SampleCount::S4);
+		assert!(matches!(builder.usage(attachment()).validate(), Ok(1)));
+
+		let sampled = Usage {
+			sampled: true,
+			..Usage::none()
+		};
+		assert!(matches!(builder.usage(sampled).validate(), Err(CreationError::InvalidMultisampling(SampleCount::S4))));
+		assert!(matches!(builder.usage(attachment()).mip_levels(2).validate(), Err(CreationError::InvalidMultisampling(_))));
+		assert!(matches!(builder.usage(attachment()).tiling(Tiling::Linear).validate(), Err(CreationError::InvalidMultisampling(_))));
+		assert!(matches!(builder.usage(attachment()).extent_3d(16, 16, 2).validate(), Err(CreationError::InvalidMultisampling(_))));
+	}
+}
@@ -1,5 +1,6 @@
 use ash::vk;
 use std::ops::BitOr;
+use crate::format;
 
 /// Describes how an image is going to be used. This is **not** just an optimization.
 ///
@@ -144,6 +145,36 @@ impl Usage {
 		}
 	}
 
+	/// Builds a Usage with storage set to true and the rest to false.
+	#[inline]
+	pub fn storage() -> Usage {
+		Usage {
+			storage: true,
+			.. Usage::none()
+		}
+	}
+
+	/// Format features required for this usage.
+	///
+	/// Transfer features are not included, as they are implied by any other feature before Vulkan 1.1.
+	#[inline]
+	pub(crate) fn format_features(&self) -> format::Features {
+		let mut features = format::Features::none();
+		if self.sampled {
+			features |= format::Features::SAMPLED_IMAGE
+		}
+		if self.storage {
+			features |= format::Features::STORAGE_IMAGE
+		}
+		if self.color_attachment {
+			features |= format::Features::COLOR_ATTACHMENT
+		}
+		if self.depth_stencil_attachment {
+			features |= format::Features::DEPTH_STENCIL_ATTACHMENT
+		}
+		features
+	}
+
 	#[inline]
 	pub(crate) fn to_vulkan(&self) -> vk::ImageUsageFlags {
 		let mut result = vk::ImageUsageFlags::empty();
@@ -1,20 +1,67 @@
+use std::{
+	sync::Arc,
+	fmt
+};
 use ash::{
 	vk,
 	version::DeviceV1_0
 };
 use crate::{
+	Device,
+	DeviceOwned,
+	device::Feature,
 	OomError,
 	Format
 };
 use super::{
-	Image
+	Image,
+	ImageProperties,
+	Flags
 };
 
 #[derive(Debug)]
 pub enum CreationError {
+	/// The view type is not compatible with the image type, flags or layer count.
+	IncompatibleType(Type),
+
+	/// The view format differs from the image format,
+	/// and the image was not created with `MUTABLE_FORMAT` or the formats are not size compatible.
+	IncompatibleFormat(Format),
+
+	/// The aspects are empty or not present in the image format.
+	InvalidAspects,
+
+	/// The subresource range exceeds the mip levels or array layers of the image.
+	OutOfRange,
+
+	/// Cube array views require the `ImageCubeArray` device feature.
+	CubeArrayNotEnabled,
+
 	OutOfMemory(OomError)
 }
 
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::IncompatibleType(ty) => write!(f, "incompatible view type {:?}", ty),
+			Self::IncompatibleFormat(format) => write!(f, "incompatible view format {:?}", format),
+			Self::InvalidAspects => write!(f, "invalid aspects"),
+			Self::OutOfRange => write!(f, "subresource range out of image bounds"),
+			Self::CubeArrayNotEnabled => write!(f, "image cube array feature not enabled"),
+			Self::OutOfMemory(e) => e.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
 impl From<vk::Result> for CreationError {
 	fn from(r: vk::Result) -> CreationError {
 		match r {
@@ -25,7 +72,7 @@ impl From<vk::Result> for CreationError {
 	}
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(i32)]
 pub enum Type {
 	D1 = vk::ImageViewType::TYPE_1D.as_raw(),
@@ -97,7 +144,7 @@ impl ComponentMapping {
 	}
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Aspects {
 	color: bool,
 	depth: bool,
@@ -121,6 +168,25 @@ impl Aspects {
 		Self::new(true, false, false, false)
 	}
 
+	pub fn depth() -> Self {
+		Self::new(false, true, false, false)
+	}
+
+	pub fn stencil() -> Self {
+		Self::new(false, false, true, false)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		!(self.color || self.depth || self.stencil || self.metadata)
+	}
+
+	pub fn contains(&self, other: Aspects) -> bool {
+		(self.color || !other.color)
+			&& (self.depth || !other.depth)
+			&& (self.stencil || !other.stencil)
+			&& (self.metadata || !other.metadata)
+	}
+
 	pub(crate) fn into_vulkan(self) -> vk::ImageAspectFlags {
 		let mut flags = vk::ImageAspectFlags::empty();
 
@@ -167,10 +233,16 @@ impl SubresourceRange {
 
 pub struct View<I: Image> {
 	image: I,
-	handle: vk::ImageView
+	handle: vk::ImageView,
+	ty: Type,
+	format: Format,
+	subresource_range: SubresourceRange
 }
 
-impl<I: Image> View<I> {
+impl<I: ImageProperties> View<I> {
+	/// Create a new view of the given image.
+	///
+	/// The view keeps the image alive. Use an `Arc` to share the image between views.
 	pub fn new(
 		image: I,
 		ty: Type,
@@ -178,6 +250,21 @@ impl<I: Image> View<I> {
 		components: ComponentMapping,
 		subresource_range: SubresourceRange
 	) -> Result<View<I>, CreationError> {
+		check_type(&image, ty, &subresource_range)?;
+
+		let image_format = image.format();
+		if format != image_format && (
+			!image.flags().contains(Flags::MUTABLE_FORMAT)
+			|| format.size() != image_format.size()
+			|| format.block_dimensions() != image_format.block_dimensions()
+		) {
+			return Err(CreationError::IncompatibleFormat(format))
+		}
+
+		if subresource_range.aspects.is_empty() || !image_format.aspects().contains(subresource_range.aspects) {
+			return Err(CreationError::InvalidAspects)
+		}
+
 		let infos = vk::ImageViewCreateInfo {
 			image: image.handle(),
 			view_type: ty.into_vulkan(),
@@ -193,19 +280,145 @@ impl<I: Image> View<I> {
 
 		Ok(View {
 			image,
-			handle
+			handle,
+			ty,
+			format,
+			subresource_range
 		})
 	}
+}
+
+impl<I: Image> View<I> {
+	pub fn handle(&self) -> vk::ImageView {
+		self.handle
+	}
+
+	pub fn image(&self) -> &I {
+		&self.image
+	}
 
-	pub(crate) fn handle(&self) -> vk::ImageView {
+	pub fn ty(&self) -> Type {
+		self.ty
+	}
+
+	pub fn format(&self) -> Format {
+		self.format
+	}
+
+	pub fn subresource_range(&self) -> &SubresourceRange {
+		&self.subresource_range
+	}
+}
+
+/// Checks that the view type and subresource range are compatible with the image.
+fn check_type<I: ImageProperties>(image: &I, ty: Type, range: &SubresourceRange) -> Result<(), CreationError> {
+	let image_ty = image.ty();
+	let flags = image.flags();
+
+	// Slices of 3D images are viewed as array layers.
+	let available_layers = if image_ty == super::Type::D3 && (ty == Type::D2 || ty == Type::D2Array) {
+		image.extent().2
+	} else {
+		image.array_layers()
+	};
+
+	let layers = check_range(range, image.mip_levels(), available_layers)?;
+
+	let d2_compatible = image_ty == super::Type::D2
+		|| (image_ty == super::Type::D3 && flags.contains(Flags::ARRAY_2D_COMPATIBLE));
+	let cube_compatible = image_ty == super::Type::D2 && flags.contains(Flags::CUBE_COMPATIBLE);
+
+	let compatible = match ty {
+		Type::D1 => image_ty == super::Type::D1 && layers == 1,
+		Type::D1Array => image_ty == super::Type::D1,
+		Type::D2 => d2_compatible && layers == 1,
+		Type::D2Array => d2_compatible,
+		Type::D => image_ty == super::Type::D3 && layers == 1,
+		Type::Cube => cube_compatible && layers == 6,
+		Type::CubeArray => cube_compatible && layers % 6 == 0
+	};
+
+	if !compatible {
+		return Err(CreationError::IncompatibleType(ty))
+	}
+
+	if ty == Type::CubeArray && !image.device().enabled_features().contains(Feature::ImageCubeArray) {
+		return Err(CreationError::CubeArrayNotEnabled)
+	}
+
+	Ok(())
+}
+
+/// Checks that the subresource range is in the given mip levels and array layers,
+/// and returns the number of viewed array layers.
+fn check_range(range: &SubresourceRange, mip_levels: u32, array_layers: u32) -> Result<u32, CreationError> {
+	// `REMAINING_MIP_LEVELS` and `REMAINING_ARRAY_LAYERS` cover the remaining subresources.
+	let remaining = |base: u32, count: u32, total: u32, all: u32| if count == all {
+		total.saturating_sub(base)
+	} else {
+		count
+	};
+	let levels = remaining(range.base_mip_level, range.level_count, mip_levels, vk::REMAINING_MIP_LEVELS);
+	let layers = remaining(range.base_array_layer, range.layer_count, array_layers, vk::REMAINING_ARRAY_LAYERS);
+
+	if levels == 0
+	|| layers == 0
+	|| range.base_mip_level as u64 + levels as u64 > mip_levels as u64
+	|| range.base_array_layer as u64 + layers as u64 > array_layers as u64 {
+		return Err(CreationError::OutOfRange)
+	}
+
+	Ok(layers)
+}
+
+unsafe impl<I: Image> crate::Resource for View<I> {
+	type Handle = vk::ImageView;
+
+	fn handle(&self) -> vk::ImageView {
 		self.handle
 	}
 }
 
+impl<I: Image> DeviceOwned for View<I> {
+	fn device(&self) -> &Arc<Device> {
+		self.image.device()
+	}
+}
+
 impl<I: Image> Drop for View<I> {
 	fn drop(&mut self) {
 		unsafe {
 			self.image.device().handle().destroy_image_view(self.handle, None)
 		}
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn range(base_mip_level: u32, level_count: u32, base_array_layer: u32, layer_count: u32) -> SubresourceRange {
+		SubresourceRange {
+			aspects: Aspects::color(),
+			base_mip_level,
+			level_count,
+			base_array_layer,
+			layer_count
+		}
+	}
+
+	#[test]
+	fn remaining_subresources() {
+		assert!(matches!(check_range(&range(1, vk::REMAINING_MIP_LEVELS, 2, vk::REMAINING_ARRAY_LAYERS), 4, 6), Ok(4)));
+		assert!(matches!(check_range(&range(4, vk::REMAINING_MIP_LEVELS, 0, 1), 4, 6), Err(CreationError::OutOfRange)));
+		assert!(matches!(check_range(&range(0, 1, 6, vk::REMAINING_ARRAY_LAYERS), 4, 6), Err(CreationError::OutOfRange)));
+	}
+
+	#[test]
+	fn overflowing_ranges() {
+		assert!(matches!(check_range(&range(0, 4, 0, 6), 4, 6), Ok(6)));
+		assert!(matches!(check_range(&range(2, u32::MAX - 1, 0, 1), 4, 6), Err(CreationError::OutOfRange)));
+		assert!(matches!(check_range(&range(0, 1, u32::MAX, 2), 4, 6), Err(CreationError::OutOfRange)));
+		assert!(matches!(check_range(&range(0, 0, 0, 1), 4, 6), Err(CreationError::OutOfRange)));
+	}
+}
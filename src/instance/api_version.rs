@@ -0,0 +1,116 @@
+use std::fmt;
+use ash::vk;
+
+/// Vulkan API version.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum ApiVersion {
+	V1_0,
+	V1_1,
+	V1_2,
+	V1_3
+}
+
+impl ApiVersion {
+	/// Returns the highest known API version lower or equal to the given Vulkan version number.
+	///
+	/// The patch number and the variant bits are ignored.
+	#[inline]
+	pub(crate) fn from_vulkan(version: u32) -> ApiVersion {
+		// The 3 highest bits encode the API variant since Vulkan 1.2.175.
+		let major = vk::version_major(version) & 0x7f;
+		match (major, vk::version_minor(version)) {
+			(1, 0) | (0, _) => ApiVersion::V1_0,
+			(1, 1) => ApiVersion::V1_1,
+			(1, 2) => ApiVersion::V1_2,
+			_ => ApiVersion::V1_3
+		}
+	}
+
+	/// Returns the API version supported by a loader,
+	/// given the result of `vkEnumerateInstanceVersion`.
+	///
+	/// A Vulkan 1.0 loader does not provide `vkEnumerateInstanceVersion`,
+	/// in which case `None` is given and `V1_0` is returned.
+	#[inline]
+	pub(crate) fn from_loader_version(version: Option<u32>) -> ApiVersion {
+		version.map(Self::from_vulkan).unwrap_or(ApiVersion::V1_0)
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> u32 {
+		match self {
+			ApiVersion::V1_0 => vk::make_version(1, 0, 0),
+			ApiVersion::V1_1 => vk::make_version(1, 1, 0),
+			ApiVersion::V1_2 => vk::make_version(1, 2, 0),
+			ApiVersion::V1_3 => vk::make_version(1, 3, 0)
+		}
+	}
+}
+
+impl Default for ApiVersion {
+	#[inline]
+	fn default() -> ApiVersion {
+		ApiVersion::V1_0
+	}
+}
+
+impl fmt::Display for ApiVersion {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ApiVersion::V1_0 => write!(f, "1.0"),
+			ApiVersion::V1_1 => write!(f, "1.1"),
+			ApiVersion::V1_2 => write!(f, "1.2"),
+			ApiVersion::V1_3 => write!(f, "1.3")
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn variant(variant: u32, version: u32) -> u32 {
+		(variant << 29) | version
+	}
+
+	#[test]
+	fn missing_loader_version_is_1_0() {
+		assert_eq!(ApiVersion::from_loader_version(None), ApiVersion::V1_0)
+	}
+
+	#[test]
+	fn known_versions() {
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 0, 0))), ApiVersion::V1_0);
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 1, 0))), ApiVersion::V1_1);
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 2, 0))), ApiVersion::V1_2);
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 3, 0))), ApiVersion::V1_3);
+	}
+
+	#[test]
+	fn patch_is_ignored() {
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 0, 4095))), ApiVersion::V1_0);
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 1, 121))), ApiVersion::V1_1);
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 2, 189))), ApiVersion::V1_2);
+	}
+
+	#[test]
+	fn variant_is_ignored() {
+		assert_eq!(ApiVersion::from_loader_version(Some(variant(1, vk::make_version(1, 0, 0)))), ApiVersion::V1_0);
+		assert_eq!(ApiVersion::from_loader_version(Some(variant(7, vk::make_version(1, 2, 198)))), ApiVersion::V1_2);
+	}
+
+	#[test]
+	fn packed_values() {
+		// Values as returned by actual loaders.
+		assert_eq!(ApiVersion::from_loader_version(Some(0x0040_0000)), ApiVersion::V1_0);
+		assert_eq!(ApiVersion::from_loader_version(Some(0x0040_1079)), ApiVersion::V1_1);
+		assert_eq!(ApiVersion::from_loader_version(Some(0x0040_20c6)), ApiVersion::V1_2);
+		assert_eq!(ApiVersion::from_loader_version(Some(0x0040_30e7)), ApiVersion::V1_3);
+	}
+
+	#[test]
+	fn future_versions_are_latest_known() {
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(1, 4, 0))), ApiVersion::V1_3);
+		assert_eq!(ApiVersion::from_loader_version(Some(vk::make_version(2, 0, 0))), ApiVersion::V1_3);
+	}
+}
@@ -0,0 +1,107 @@
+use std::ffi::CString;
+use ash::vk;
+use super::ApiVersion;
+
+/// Application information given to the Vulkan implementation.
+///
+/// Drivers may use the application and engine names and versions
+/// to apply application specific profiles.
+///
+/// ```ignore
+/// let info = ApplicationInfo::new()
+/// 	.name("my-game")
+/// 	.version(0, 3, 1)
+/// 	.engine("waonder")
+/// 	.engine_version(1, 0, 0)
+/// 	.api_version(ApiVersion::V1_1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ApplicationInfo {
+	name: Option<CString>,
+	version: u32,
+	engine_name: Option<CString>,
+	engine_version: u32,
+	api_version: ApiVersion
+}
+
+impl ApplicationInfo {
+	/// Creates an empty application info.
+	#[inline]
+	pub fn new() -> ApplicationInfo {
+		Self::default()
+	}
+
+	/// Sets the application name.
+	///
+	/// # Panic
+	///
+	/// Panics if the name contains a nul byte.
+	#[inline]
+	pub fn name(mut self, name: &str) -> ApplicationInfo {
+		self.name = Some(CString::new(name).expect("application name contains a nul byte"));
+		self
+	}
+
+	/// Sets the application version.
+	#[inline]
+	pub fn version(mut self, major: u32, minor: u32, patch: u32) -> ApplicationInfo {
+		self.version = vk::make_version(major, minor, patch);
+		self
+	}
+
+	/// Sets the engine name.
+	///
+	/// # Panic
+	///
+	/// Panics if the name contains a nul byte.
+	#[inline]
+	pub fn engine(mut self, name: &str) -> ApplicationInfo {
+		self.engine_name = Some(CString::new(name).expect("engine name contains a nul byte"));
+		self
+	}
+
+	/// Sets the engine version.
+	#[inline]
+	pub fn engine_version(mut self, major: u32, minor: u32, patch: u32) -> ApplicationInfo {
+		self.engine_version = vk::make_version(major, minor, patch);
+		self
+	}
+
+	/// Sets the Vulkan API version used by the application.
+	///
+	/// Defaults to `ApiVersion::V1_0`.
+	#[inline]
+	pub fn api_version(mut self, version: ApiVersion) -> ApplicationInfo {
+		self.api_version = version;
+		self
+	}
+
+	#[inline]
+	pub fn application_name(&self) -> Option<&str> {
+		self.name.as_ref().map(|name| name.to_str().unwrap())
+	}
+
+	#[inline]
+	pub fn engine_name(&self) -> Option<&str> {
+		self.engine_name.as_ref().map(|name| name.to_str().unwrap())
+	}
+
+	#[inline]
+	pub fn requested_api_version(&self) -> ApiVersion {
+		self.api_version
+	}
+
+	/// Returns the Vulkan application info.
+	///
+	/// The returned value borrows the names stored in `self`.
+	pub(crate) fn as_vulkan(&self) -> vk::ApplicationInfo {
+		vk::ApplicationInfo {
+			p_application_name: self.name.as_ref().map(|name| name.as_ptr()).unwrap_or(std::ptr::null()),
+			application_version: self.version,
+			p_engine_name: self.engine_name.as_ref().map(|name| name.as_ptr()).unwrap_or(std::ptr::null()),
+			engine_version: self.engine_version,
+			api_version: self.api_version.into_vulkan(),
+			..Default::default()
+		}
+	}
+}
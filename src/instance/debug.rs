@@ -0,0 +1,318 @@
+use std::{
+	sync::Arc,
+	ffi::{
+		CStr,
+		c_void
+	},
+	os::raw::c_char,
+	borrow::Cow,
+	error::Error,
+	fmt
+};
+use ash::vk;
+use crate::{
+	OomError,
+	Instance
+};
+use super::{
+	Extension,
+	MissingExtensionError
+};
+
+#[derive(Debug)]
+pub enum CreationError {
+	MissingExtension(Extension),
+	OutOfMemory(OomError),
+
+	/// Error code not expected from the messenger creation, reported by the implementation or a layer.
+	Unknown(vk::Result)
+}
+
+impl Error for CreationError {
+	fn source(&self) -> Option<&(dyn 'static + Error)> {
+		match self {
+			CreationError::OutOfMemory(oom) => Some(oom),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			CreationError::MissingExtension(ext) => write!(f, "missing instance extension `{}`", ext),
+			CreationError::OutOfMemory(oom) => oom.fmt(f),
+			CreationError::Unknown(r) => write!(f, "unexpected error {:?}", r)
+		}
+	}
+}
+
+impl From<MissingExtensionError> for CreationError {
+	fn from(MissingExtensionError(ext): MissingExtensionError) -> CreationError {
+		CreationError::MissingExtension(ext)
+	}
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			r => CreationError::Unknown(r)
+		}
+	}
+}
+
+/// Severity of a debug message.
+///
+/// Used both to filter the messages reported by a messenger
+/// and to describe the severity of a reported message.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Severity(vk::DebugUtilsMessageSeverityFlagsEXT);
+
+impl Severity {
+	/// Diagnostic messages from the loader, layers and drivers.
+	pub const VERBOSE: Severity = Severity(vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE);
+
+	/// Informational messages, such as resource details.
+	pub const INFO: Severity = Severity(vk::DebugUtilsMessageSeverityFlagsEXT::INFO);
+
+	/// Use of Vulkan that may expose an application bug.
+	pub const WARNING: Severity = Severity(vk::DebugUtilsMessageSeverityFlagsEXT::WARNING);
+
+	/// Invalid use of Vulkan that may cause undefined behavior.
+	pub const ERROR: Severity = Severity(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR);
+
+	/// Every severity level.
+	#[inline]
+	pub fn all() -> Severity {
+		Self::VERBOSE | Self::INFO | Self::WARNING | Self::ERROR
+	}
+
+	#[inline]
+	pub fn is_verbose(&self) -> bool {
+		self.0.contains(vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE)
+	}
+
+	#[inline]
+	pub fn is_info(&self) -> bool {
+		self.0.contains(vk::DebugUtilsMessageSeverityFlagsEXT::INFO)
+	}
+
+	#[inline]
+	pub fn is_warning(&self) -> bool {
+		self.0.contains(vk::DebugUtilsMessageSeverityFlagsEXT::WARNING)
+	}
+
+	#[inline]
+	pub fn is_error(&self) -> bool {
+		self.0.contains(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR)
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::DebugUtilsMessageSeverityFlagsEXT {
+		self.0
+	}
+}
+
+impl std::ops::BitOr for Severity {
+	type Output = Severity;
+
+	fn bitor(self, rhs: Self) -> Severity {
+		Severity(self.0 | rhs.0)
+	}
+}
+
+/// Type of debug message.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MessageType(vk::DebugUtilsMessageTypeFlagsEXT);
+
+impl MessageType {
+	/// Event unrelated to the specification or performances.
+	pub const GENERAL: MessageType = MessageType(vk::DebugUtilsMessageTypeFlagsEXT::GENERAL);
+
+	/// Something violating the specification or indicating a possible mistake.
+	pub const VALIDATION: MessageType = MessageType(vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION);
+
+	/// Potentially non-optimal use of Vulkan.
+	pub const PERFORMANCE: MessageType = MessageType(vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE);
+
+	/// Every message type.
+	#[inline]
+	pub fn all() -> MessageType {
+		Self::GENERAL | Self::VALIDATION | Self::PERFORMANCE
+	}
+
+	#[inline]
+	pub fn is_general(&self) -> bool {
+		self.0.contains(vk::DebugUtilsMessageTypeFlagsEXT::GENERAL)
+	}
+
+	#[inline]
+	pub fn is_validation(&self) -> bool {
+		self.0.contains(vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION)
+	}
+
+	#[inline]
+	pub fn is_performance(&self) -> bool {
+		self.0.contains(vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE)
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::DebugUtilsMessageTypeFlagsEXT {
+		self.0
+	}
+}
+
+impl std::ops::BitOr for MessageType {
+	type Output = MessageType;
+
+	fn bitor(self, rhs: Self) -> MessageType {
+		MessageType(self.0 | rhs.0)
+	}
+}
+
+/// Debug message.
+pub struct Message<'a> {
+	id_name: Cow<'a, str>,
+	id_number: i32,
+	text: Cow<'a, str>
+}
+
+impl<'a> Message<'a> {
+	unsafe fn from_vulkan(data: &'a vk::DebugUtilsMessengerCallbackDataEXT) -> Message<'a> {
+		Message {
+			id_name: c_str_lossy(data.p_message_id_name),
+			id_number: data.message_id_number,
+			text: c_str_lossy(data.p_message)
+		}
+	}
+
+	/// Name of the message identifier.
+	///
+	/// Empty if the message has no name.
+	#[inline]
+	pub fn id_name(&self) -> &str {
+		&self.id_name
+	}
+
+	/// Message identifier number.
+	#[inline]
+	pub fn id_number(&self) -> i32 {
+		self.id_number
+	}
+
+	/// Message text.
+	#[inline]
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+}
+
+unsafe fn c_str_lossy<'a>(ptr: *const c_char) -> Cow<'a, str> {
+	if ptr.is_null() {
+		Cow::Borrowed("")
+	} else {
+		CStr::from_ptr(ptr).to_string_lossy()
+	}
+}
+
+/// Debug messenger callback.
+pub type Callback = dyn Fn(Severity, MessageType, &Message) + Send + Sync;
+
+/// Debug messenger.
+///
+/// Reports the messages emitted by the loader, the validation layers and the drivers
+/// through the `VK_EXT_debug_utils` extension.
+/// The messenger is unregistered when dropped.
+pub struct DebugMessenger {
+	instance: Arc<Instance>,
+	handle: vk::DebugUtilsMessengerEXT,
+
+	/// The callback is double boxed so it can be passed as user data to Vulkan with a thin pointer.
+	_callback: Box<Box<Callback>>
+}
+
+impl DebugMessenger {
+	/// Create a new debug messenger forwarding messages to the `log` crate.
+	///
+	/// Errors are logged with `error!`, warnings with `warn!`,
+	/// informational messages with `info!` and verbose messages with `trace!`.
+	pub fn new(instance: &Arc<Instance>, severity: Severity, ty: MessageType) -> Result<DebugMessenger, CreationError> {
+		Self::with_callback(instance, severity, ty, log_message)
+	}
+
+	/// Create a new debug messenger calling the given closure for each message.
+	///
+	/// Only messages matching both the `severity` and `ty` filters are reported.
+	pub fn with_callback<F>(instance: &Arc<Instance>, severity: Severity, ty: MessageType, callback: F) -> Result<DebugMessenger, CreationError> where F: 'static + Fn(Severity, MessageType, &Message) + Send + Sync {
+		let ext_debug_utils = instance.ext_ext_debug_utils()?;
+
+		let callback: Box<Box<Callback>> = Box::new(Box::new(callback));
+
+		let infos = vk::DebugUtilsMessengerCreateInfoEXT {
+			message_severity: severity.into_vulkan(),
+			message_type: ty.into_vulkan(),
+			pfn_user_callback: Some(messenger_callback),
+			p_user_data: &*callback as *const Box<Callback> as *mut c_void,
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			ext_debug_utils.create_debug_utils_messenger(&infos, None)?
+		};
+
+		Ok(DebugMessenger {
+			instance: instance.clone(),
+			handle,
+			_callback: callback
+		})
+	}
+
+	#[inline]
+	pub fn instance(&self) -> &Arc<Instance> {
+		&self.instance
+	}
+}
+
+impl Drop for DebugMessenger {
+	fn drop(&mut self) {
+		unsafe {
+			// The extension was loaded when the messenger was created.
+			self.instance.ext_ext_debug_utils().unwrap().destroy_debug_utils_messenger(self.handle, None)
+		}
+	}
+}
+
+fn log_message(severity: Severity, ty: MessageType, message: &Message) {
+	if severity.is_error() {
+		error!("[{:?}] {} ({}): {}", ty.into_vulkan(), message.id_name(), message.id_number(), message.text())
+	} else if severity.is_warning() {
+		warn!("[{:?}] {} ({}): {}", ty.into_vulkan(), message.id_name(), message.id_number(), message.text())
+	} else if severity.is_info() {
+		info!("[{:?}] {} ({}): {}", ty.into_vulkan(), message.id_name(), message.id_number(), message.text())
+	} else {
+		trace!("[{:?}] {} ({}): {}", ty.into_vulkan(), message.id_name(), message.id_number(), message.text())
+	}
+}
+
+unsafe extern "system" fn messenger_callback(
+	severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+	ty: vk::DebugUtilsMessageTypeFlagsEXT,
+	data: *const vk::DebugUtilsMessengerCallbackDataEXT,
+	user_data: *mut c_void
+) -> vk::Bool32 {
+	let callback = &*(user_data as *const Box<Callback>);
+	let message = Message::from_vulkan(&*data);
+
+	// Unwinding through the Vulkan implementation is undefined behavior.
+	let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+		callback(Severity(severity), MessageType(ty), &message)
+	}));
+
+	if result.is_err() {
+		error!("debug messenger callback panicked")
+	}
+
+	// The application must always return `VK_FALSE`.
+	vk::FALSE
+}
@@ -4,7 +4,7 @@ use std::{
 };
 use crate::Unbuildable;
 
-extensions! {
+raw_extensions! {
 	debug_report: DebugReport => b"VK_EXT_debug_report\0",
 	khr_surface: KhrSurface => b"VK_KHR_surface\0",
 	khr_display: KhrDisplay => b"VK_KHR_display\0",
@@ -20,4 +20,26 @@ extensions! {
 	ext_swapchain_colorspace: ExtSwapchainColorspace => b"VK_EXT_swapchain_colorspace\0",
 	khr_get_physical_device_properties2: KhrGetPhysicalDeviceproperties2 => b"VK_KHR_get_physical_device_properties2\0",
 	khr_get_surface_capabilities2: KhrGetSurfaceCapabilities2 => b"VK_KHR_get_surface_capabilities2\0",
+	khr_external_semaphore_capabilities: KhrExternalSemaphoreCapabilities => b"VK_KHR_external_semaphore_capabilities\0",
+	khr_external_fence_capabilities: KhrExternalFenceCapabilities => b"VK_KHR_external_fence_capabilities\0",
+}
+
+/// Instance extension, known or not by this crate.
+pub trait IntoExtension {
+	/// Name of the extension.
+	fn c_name(&self) -> &CStr;
+}
+
+impl IntoExtension for Extension {
+	#[inline]
+	fn c_name(&self) -> &CStr {
+		Extension::c_name(self)
+	}
+}
+
+impl<'a> IntoExtension for &'a CStr {
+	#[inline]
+	fn c_name(&self) -> &CStr {
+		self
+	}
 }
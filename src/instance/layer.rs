@@ -8,6 +8,26 @@ validation_layers! {
 	khronos_validation: KhronosValidation => b"VK_LAYER_KHRONOS_validation\0",
 }
 
+/// Validation layer, known or not by this crate.
+pub trait IntoValidationLayer {
+	/// Name of the validation layer.
+	fn c_name(&self) -> &CStr;
+}
+
+impl IntoValidationLayer for ValidationLayer {
+	#[inline]
+	fn c_name(&self) -> &CStr {
+		ValidationLayer::c_name(self)
+	}
+}
+
+impl<'a> IntoValidationLayer for &'a CStr {
+	#[inline]
+	fn c_name(&self) -> &CStr {
+		self
+	}
+}
+
 // pub struct InstanceValidationLayer<'a> {
 // 	entry: &'a Entry,
 // 	props: vk::LayerProperties
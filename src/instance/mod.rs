@@ -4,28 +4,38 @@ use ash::{
 	vk,
 	version::{
 		EntryV1_0,
-		InstanceV1_0
+		InstanceV1_0,
+		InstanceV1_1
 	}
 };
 use crate::{
 	OomError,
 	Entry,
+	EntryError,
 	device
 };
 
 pub mod layer;
 pub mod extension;
 pub mod physical_device;
+pub mod debug;
+mod application_info;
+mod api_version;
 
 pub use layer::{
 	ValidationLayer,
-	ValidationLayers
+	ValidationLayers,
+	IntoValidationLayer
 };
 pub use extension::{
 	Extension,
-	Extensions
+	Extensions,
+	IntoExtension
 };
 pub use physical_device::PhysicalDevice;
+pub use debug::DebugMessenger;
+pub use application_info::ApplicationInfo;
+pub use api_version::ApiVersion;
 
 #[derive(Debug)]
 pub enum CreationError {
@@ -33,8 +43,27 @@ pub enum CreationError {
 	OutOfMemory(OomError),
 	InitializationFailed,
 	MissingValidationLayer(ValidationLayer),
+	MissingRawValidationLayer(String),
 	MissingExtension(Extension),
-	IncompatibleDriver
+	MissingRawExtension(String),
+	UnsupportedApiVersion(ApiVersion),
+	IncompatibleDriver,
+	EntryError(EntryError),
+
+	/// A validation layer disappeared between the availability check and the instance creation.
+	LayerNotPresent,
+
+	/// An extension disappeared between the availability check and the instance creation.
+	ExtensionNotPresent,
+
+	/// Error code not allowed by the specification.
+	Unexpected(vk::Result)
+}
+
+impl From<EntryError> for CreationError {
+	fn from(e: EntryError) -> CreationError {
+		CreationError::EntryError(e)
+	}
 }
 
 impl From<ash::InstanceError> for CreationError {
@@ -52,10 +81,10 @@ impl From<vk::Result> for CreationError {
 			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
 			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
 			vk::Result::ERROR_INITIALIZATION_FAILED => CreationError::InitializationFailed,
-			vk::Result::ERROR_LAYER_NOT_PRESENT => panic!("unchecked missing layer"),
-			vk::Result::ERROR_EXTENSION_NOT_PRESENT => panic!("unchecked missing extension"),
+			vk::Result::ERROR_LAYER_NOT_PRESENT => CreationError::LayerNotPresent,
+			vk::Result::ERROR_EXTENSION_NOT_PRESENT => CreationError::ExtensionNotPresent,
 			vk::Result::ERROR_INCOMPATIBLE_DRIVER => CreationError::IncompatibleDriver,
-			_ => unreachable!()
+			r => CreationError::Unexpected(r)
 		}
 	}
 }
@@ -66,64 +95,87 @@ pub struct MissingExtensionError(pub Extension);
 pub struct Instance {
 	entry: Arc<Entry>,
 	pub(crate) handle: ash::Instance,
+	api_version: ApiVersion,
 	loaded_extensions: Extensions,
 	physical_devices_info: Vec<PhysicalDeviceInfo>,
 	ext_khr_surface: OnceCell<ash::extensions::khr::Surface>,
 	ext_khr_xcb_surface: OnceCell<ash::extensions::khr::XcbSurface>,
 	ext_khr_xlib_surface: OnceCell<ash::extensions::khr::XlibSurface>,
-	ext_khr_wayland_surface: OnceCell<ash::extensions::khr::WaylandSurface>
+	ext_khr_wayland_surface: OnceCell<ash::extensions::khr::WaylandSurface>,
+	ext_khr_win32_surface: OnceCell<ash::extensions::khr::Win32Surface>,
+	ext_khr_android_surface: OnceCell<ash::extensions::khr::AndroidSurface>,
+	ext_mvk_macos_surface: OnceCell<ash::extensions::mvk::MacOSSurface>,
+	ext_mvk_ios_surface: OnceCell<ash::extensions::mvk::IOSSurface>,
+	ext_ext_debug_utils: OnceCell<ash::extensions::ext::DebugUtils>,
+	ext_khr_get_physical_device_properties2: OnceCell<vk::KhrGetPhysicalDeviceProperties2Fn>,
+	ext_khr_external_semaphore_capabilities: OnceCell<vk::KhrExternalSemaphoreCapabilitiesFn>,
+	ext_khr_external_fence_capabilities: OnceCell<vk::KhrExternalFenceCapabilitiesFn>
 }
 
 impl Instance {
 	/// Create a new instance.
-	pub fn new<E: IntoIterator<Item=Extension>>(entry: Arc<Entry>, required_extensions: E) -> Result<Instance, CreationError> {
-		Self::with_validation_layers(entry, required_extensions, std::iter::empty())
+	pub fn new<E: IntoIterator>(entry: Arc<Entry>, required_extensions: E) -> Result<Instance, CreationError> where E::Item: IntoExtension {
+		Self::with_validation_layers(entry, required_extensions, std::iter::empty::<ValidationLayer>())
 	}
 	
 	/// Create a new instance with the given validation layers.
-	pub fn with_validation_layers<E: IntoIterator<Item=Extension>, L: IntoIterator<Item=ValidationLayer>>(entry: Arc<Entry>, required_extensions: E, validation_layers: L) -> Result<Instance, CreationError> {
+	pub fn with_validation_layers<E: IntoIterator, L: IntoIterator>(entry: Arc<Entry>, required_extensions: E, validation_layers: L) -> Result<Instance, CreationError> where E::Item: IntoExtension, L::Item: IntoValidationLayer {
+		Self::with_info(entry, &ApplicationInfo::default(), required_extensions, validation_layers)
+	}
+
+	/// Create a new instance with the given application info and validation layers.
+	pub fn with_info<E: IntoIterator, L: IntoIterator>(entry: Arc<Entry>, info: &ApplicationInfo, required_extensions: E, validation_layers: L) -> Result<Instance, CreationError> where E::Item: IntoExtension, L::Item: IntoValidationLayer {
 		unsafe {
-			let available_extensions = entry.extensions();
-			let available_layers = entry.validation_layers();
+			let api_version = info.requested_api_version();
+			if api_version > entry.api_version()? {
+				return Err(CreationError::UnsupportedApiVersion(api_version))
+			}
+
+			let available_extensions = entry.extensions()?;
+			let available_layers = entry.validation_layers()?;
 
 			let mut loaded_extensions = Extensions::none();
-			let mut extension_names = Vec::new();
 			for ext in required_extensions {
-				if !available_extensions.contains(ext) {
-					return Err(CreationError::MissingExtension(ext))
+				let c_name = ext.c_name();
+				if !available_extensions.contains_raw(c_name) {
+					return Err(match Extension::from_c_name(c_name) {
+						Some(ext) => CreationError::MissingExtension(ext),
+						None => CreationError::MissingRawExtension(c_name.to_string_lossy().into_owned())
+					})
 				}
 
-				loaded_extensions.insert(ext);
-				extension_names.push(ext.c_name().as_ptr())
+				loaded_extensions.insert_raw(c_name);
 			}
 
+			let extension_names: Vec<_> = loaded_extensions.c_names().map(|c_name| c_name.as_ptr()).collect();
+
 			let mut enabled_layers = ValidationLayers::none();
-			let mut layer_names = Vec::new();
 			
 			#[cfg(debug_assertions)]
 			{
 				if available_layers.contains(ValidationLayer::KhronosValidation) {
 					log::info!("enabling khronos validation layer");
 					enabled_layers.khronos_validation = true;
-					layer_names.push(ValidationLayer::KhronosValidation.c_name().as_ptr())
 				} else {
 					log::warn!("khronos validation layer is unavailable");
 				}
 			}
 
 			for layer in validation_layers {
-				if !available_layers.contains(layer) {
-					return Err(CreationError::MissingValidationLayer(layer))
+				let c_name = layer.c_name();
+				if !available_layers.contains_raw(c_name) {
+					return Err(match ValidationLayer::from_c_name(c_name) {
+						Some(layer) => CreationError::MissingValidationLayer(layer),
+						None => CreationError::MissingRawValidationLayer(c_name.to_string_lossy().into_owned())
+					})
 				}
 
-				enabled_layers.insert(layer);
-				layer_names.push(layer.c_name().as_ptr())
+				enabled_layers.insert_raw(c_name);
 			}
 
-			let app_info = vk::ApplicationInfo {
-				api_version: vk::make_version(1, 0, 0),
-				..Default::default()
-			};
+			let layer_names: Vec<_> = enabled_layers.c_names().map(|c_name| c_name.as_ptr()).collect();
+
+			let app_info = info.as_vulkan();
 
 			let infos = vk::InstanceCreateInfo {
 				p_application_info: &app_info,
@@ -136,7 +188,15 @@ impl Instance {
 
 			let handle = entry.handle.create_instance(&infos, None)?;
 
-			let physical_devices_info: Vec<_> = handle.enumerate_physical_devices().unwrap().into_iter().map(|pd| {
+			let physical_devices = match handle.enumerate_physical_devices() {
+				Ok(physical_devices) => physical_devices,
+				Err(e) => {
+					handle.destroy_instance(None);
+					return Err(e.into())
+				}
+			};
+
+			let physical_devices_info: Vec<_> = physical_devices.into_iter().map(|pd| {
 				let properties = handle.get_physical_device_properties(pd);
 				let supported_features = handle.get_physical_device_features(pd).into();
 				let memory_properties = handle.get_physical_device_memory_properties(pd);
@@ -147,19 +207,30 @@ impl Instance {
 					properties,
 					supported_features,
 					memory_properties,
-					queue_family_properties
+					queue_family_properties,
+					supported_extensions: OnceCell::new(),
+					supported_extended_features: OnceCell::new()
 				}
 			}).collect();
 
 			let instance = Instance {
 				entry,
 				handle,
+				api_version,
 				loaded_extensions,
 				physical_devices_info,
 				ext_khr_surface: OnceCell::new(),
 				ext_khr_xcb_surface: OnceCell::new(),
 				ext_khr_xlib_surface: OnceCell::new(),
-				ext_khr_wayland_surface: OnceCell::new()
+				ext_khr_wayland_surface: OnceCell::new(),
+				ext_khr_win32_surface: OnceCell::new(),
+				ext_khr_android_surface: OnceCell::new(),
+				ext_mvk_macos_surface: OnceCell::new(),
+				ext_mvk_ios_surface: OnceCell::new(),
+				ext_ext_debug_utils: OnceCell::new(),
+				ext_khr_get_physical_device_properties2: OnceCell::new(),
+				ext_khr_external_semaphore_capabilities: OnceCell::new(),
+				ext_khr_external_fence_capabilities: OnceCell::new()
 			};
 
 			Ok(instance)
@@ -171,9 +242,15 @@ impl Instance {
 		&self.entry
 	}
 
+	/// Vulkan API version used by the instance.
+	#[inline]
+	pub fn api_version(&self) -> ApiVersion {
+		self.api_version
+	}
+
 	/// Get the list of physical devices.
 	#[inline]
-	pub fn physical_devices<'a>(self: &'a Arc<Self>) -> impl 'a + Iterator<Item=PhysicalDevice<'a>> {
+	pub fn physical_devices(self: &Arc<Self>) -> impl '_ + Iterator<Item=PhysicalDevice> {
 		let len = self.physical_devices_info.len() as u32;
 		(0..len).into_iter().map(move |i| {
 			PhysicalDevice::new(self, i)
@@ -182,7 +259,7 @@ impl Instance {
 
 	/// Get the physical device of the given index.
 	#[inline]
-	pub fn physical_device<'a>(self: &'a Arc<Self>, index: u32) -> Option<PhysicalDevice<'a>> {
+	pub fn physical_device(self: &Arc<Self>, index: u32) -> Option<PhysicalDevice> {
 		if (index as usize) < self.physical_devices_info.len() {
 			Some(PhysicalDevice::new(self, index))
 		} else {
@@ -190,6 +267,24 @@ impl Instance {
 		}
 	}
 
+	/// Picks the physical device with the highest score.
+	///
+	/// Devices for which `score` returns `None` are discarded.
+	/// Ties are broken in favor of the device with the lowest index.
+	pub fn pick_physical_device<F: Fn(&PhysicalDevice) -> Option<u32>>(self: &Arc<Self>, score: F) -> Option<PhysicalDevice> {
+		let mut best: Option<(u32, PhysicalDevice)> = None;
+		for physical_device in self.physical_devices() {
+			if let Some(s) = score(&physical_device) {
+				match best {
+					Some((best_score, _)) if best_score >= s => (),
+					_ => best = Some((s, physical_device))
+				}
+			}
+		}
+
+		best.map(|(_, physical_device)| physical_device)
+	}
+
 	#[inline]
 	pub fn loaded_extensions(&self) -> &Extensions {
 		&self.loaded_extensions
@@ -234,6 +329,156 @@ impl Instance {
 			}
 		})
 	}
+
+	pub fn ext_khr_win32_surface(&self) -> Result<&ash::extensions::khr::Win32Surface, MissingExtensionError> {
+		self.ext_khr_win32_surface.get_or_try_init(|| {
+			if self.loaded_extensions.khr_win32_surface {
+				Ok(ash::extensions::khr::Win32Surface::new(&self.entry.handle, &self.handle))
+			} else {
+				Err(MissingExtensionError(Extension::KhrWin32Surface))
+			}
+		})
+	}
+
+	pub fn ext_khr_android_surface(&self) -> Result<&ash::extensions::khr::AndroidSurface, MissingExtensionError> {
+		self.ext_khr_android_surface.get_or_try_init(|| {
+			if self.loaded_extensions.khr_android_surface {
+				Ok(ash::extensions::khr::AndroidSurface::new(&self.entry.handle, &self.handle))
+			} else {
+				Err(MissingExtensionError(Extension::KhrAndroidSurface))
+			}
+		})
+	}
+
+	pub fn ext_mvk_macos_surface(&self) -> Result<&ash::extensions::mvk::MacOSSurface, MissingExtensionError> {
+		self.ext_mvk_macos_surface.get_or_try_init(|| {
+			if self.loaded_extensions.mvk_macos_surface {
+				Ok(ash::extensions::mvk::MacOSSurface::new(&self.entry.handle, &self.handle))
+			} else {
+				Err(MissingExtensionError(Extension::MkvMacosSurface))
+			}
+		})
+	}
+
+	pub fn ext_mvk_ios_surface(&self) -> Result<&ash::extensions::mvk::IOSSurface, MissingExtensionError> {
+		self.ext_mvk_ios_surface.get_or_try_init(|| {
+			if self.loaded_extensions.mvk_ios_surface {
+				Ok(ash::extensions::mvk::IOSSurface::new(&self.entry.handle, &self.handle))
+			} else {
+				Err(MissingExtensionError(Extension::MkvIosSurface))
+			}
+		})
+	}
+
+	pub fn ext_ext_debug_utils(&self) -> Result<&ash::extensions::ext::DebugUtils, MissingExtensionError> {
+		self.ext_ext_debug_utils.get_or_try_init(|| {
+			if self.loaded_extensions.ext_debug_utils {
+				Ok(ash::extensions::ext::DebugUtils::new(&self.entry.handle, &self.handle))
+			} else {
+				Err(MissingExtensionError(Extension::ExtDebugUtils))
+			}
+		})
+	}
+
+	pub fn ext_khr_get_physical_device_properties2(&self) -> Result<&vk::KhrGetPhysicalDeviceProperties2Fn, MissingExtensionError> {
+		self.ext_khr_get_physical_device_properties2.get_or_try_init(|| {
+			if self.loaded_extensions.khr_get_physical_device_properties2 {
+				Ok(vk::KhrGetPhysicalDeviceProperties2Fn::load(|name| unsafe {
+					std::mem::transmute(self.entry.handle.get_instance_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrGetPhysicalDeviceproperties2))
+			}
+		})
+	}
+
+	pub fn ext_khr_external_semaphore_capabilities(&self) -> Result<&vk::KhrExternalSemaphoreCapabilitiesFn, MissingExtensionError> {
+		self.ext_khr_external_semaphore_capabilities.get_or_try_init(|| {
+			if self.loaded_extensions.khr_external_semaphore_capabilities {
+				Ok(vk::KhrExternalSemaphoreCapabilitiesFn::load(|name| unsafe {
+					std::mem::transmute(self.entry.handle.get_instance_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrExternalSemaphoreCapabilities))
+			}
+		})
+	}
+
+	pub fn ext_khr_external_fence_capabilities(&self) -> Result<&vk::KhrExternalFenceCapabilitiesFn, MissingExtensionError> {
+		self.ext_khr_external_fence_capabilities.get_or_try_init(|| {
+			if self.loaded_extensions.khr_external_fence_capabilities {
+				Ok(vk::KhrExternalFenceCapabilitiesFn::load(|name| unsafe {
+					std::mem::transmute(self.entry.handle.get_instance_proc_addr(self.handle.handle(), name.as_ptr()))
+				}))
+			} else {
+				Err(MissingExtensionError(Extension::KhrExternalFenceCapabilities))
+			}
+		})
+	}
+
+	/// Fills the given `VkPhysicalDeviceFeatures2` structure chain.
+	///
+	/// Uses Vulkan 1.1 if enabled, or the `VK_KHR_get_physical_device_properties2` extension.
+	/// Returns `false` if neither is available.
+	pub(crate) unsafe fn get_physical_device_features2(&self, physical_device: vk::PhysicalDevice, features: &mut vk::PhysicalDeviceFeatures2) -> bool {
+		if self.api_version >= ApiVersion::V1_1 {
+			self.handle.get_physical_device_features2(physical_device, features);
+			true
+		} else if let Ok(ext) = self.ext_khr_get_physical_device_properties2() {
+			ext.get_physical_device_features2_khr(physical_device, features);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Fills the given `VkExternalSemaphoreProperties` structure.
+	///
+	/// Uses Vulkan 1.1 if enabled, or the `VK_KHR_external_semaphore_capabilities` extension.
+	/// Returns `false` if neither is available.
+	pub(crate) unsafe fn get_physical_device_external_semaphore_properties(&self, physical_device: vk::PhysicalDevice, info: &vk::PhysicalDeviceExternalSemaphoreInfo, properties: &mut vk::ExternalSemaphoreProperties) -> bool {
+		if self.api_version >= ApiVersion::V1_1 {
+			self.handle.get_physical_device_external_semaphore_properties(physical_device, info, properties);
+			true
+		} else if let Ok(ext) = self.ext_khr_external_semaphore_capabilities() {
+			ext.get_physical_device_external_semaphore_properties_khr(physical_device, info, properties);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Fills the given `VkExternalFenceProperties` structure.
+	///
+	/// Uses Vulkan 1.1 if enabled, or the `VK_KHR_external_fence_capabilities` extension.
+	/// Returns `false` if neither is available.
+	pub(crate) unsafe fn get_physical_device_external_fence_properties(&self, physical_device: vk::PhysicalDevice, info: &vk::PhysicalDeviceExternalFenceInfo, properties: &mut vk::ExternalFenceProperties) -> bool {
+		if self.api_version >= ApiVersion::V1_1 {
+			self.handle.get_physical_device_external_fence_properties(physical_device, info, properties);
+			true
+		} else if let Ok(ext) = self.ext_khr_external_fence_capabilities() {
+			ext.get_physical_device_external_fence_properties_khr(physical_device, info, properties);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Fills the given `VkPhysicalDeviceMemoryProperties2` structure chain.
+	///
+	/// Uses Vulkan 1.1 if enabled, or the `VK_KHR_get_physical_device_properties2` extension.
+	/// Returns `false` if neither is available.
+	pub(crate) unsafe fn get_physical_device_memory_properties2(&self, physical_device: vk::PhysicalDevice, properties: &mut vk::PhysicalDeviceMemoryProperties2) -> bool {
+		if self.api_version >= ApiVersion::V1_1 {
+			self.handle.get_physical_device_memory_properties2(physical_device, properties);
+			true
+		} else if let Ok(ext) = self.ext_khr_get_physical_device_properties2() {
+			ext.get_physical_device_memory_properties2_khr(physical_device, properties);
+			true
+		} else {
+			false
+		}
+	}
 }
 
 impl Drop for Instance {
@@ -249,5 +494,7 @@ pub(crate) struct PhysicalDeviceInfo {
 	properties: vk::PhysicalDeviceProperties,
 	supported_features: device::Features,
 	memory_properties: vk::PhysicalDeviceMemoryProperties,
-	queue_family_properties: Vec<vk::QueueFamilyProperties>
+	queue_family_properties: Vec<vk::QueueFamilyProperties>,
+	supported_extensions: OnceCell<device::Extensions>,
+	supported_extended_features: OnceCell<Option<device::ExtendedFeatures>>
 }
@@ -0,0 +1,46 @@
+use std::fmt;
+use ash::vk;
+
+/// Type of physical device.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DeviceType {
+	/// Typically a separate GPU connected to the host.
+	DiscreteGpu,
+
+	/// Typically a GPU embedded in or tightly coupled with the host.
+	IntegratedGpu,
+
+	/// Virtual node in a virtualization environment.
+	VirtualGpu,
+
+	/// Running on the same processors as the host.
+	Cpu,
+
+	/// Does not match any other type.
+	Other
+}
+
+impl DeviceType {
+	#[inline]
+	pub(crate) fn from_vulkan(ty: vk::PhysicalDeviceType) -> DeviceType {
+		match ty {
+			vk::PhysicalDeviceType::DISCRETE_GPU => DeviceType::DiscreteGpu,
+			vk::PhysicalDeviceType::INTEGRATED_GPU => DeviceType::IntegratedGpu,
+			vk::PhysicalDeviceType::VIRTUAL_GPU => DeviceType::VirtualGpu,
+			vk::PhysicalDeviceType::CPU => DeviceType::Cpu,
+			_ => DeviceType::Other
+		}
+	}
+}
+
+impl fmt::Display for DeviceType {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			DeviceType::DiscreteGpu => write!(f, "discrete GPU"),
+			DeviceType::IntegratedGpu => write!(f, "integrated GPU"),
+			DeviceType::VirtualGpu => write!(f, "virtual GPU"),
+			DeviceType::Cpu => write!(f, "CPU"),
+			DeviceType::Other => write!(f, "other")
+		}
+	}
+}
@@ -1,14 +1,8 @@
 use ash::vk;
-
-pub struct SampleCountFlags(vk::SampleCountFlags);
-
-impl From<vk::SampleCountFlags> for SampleCountFlags {
-	fn from(f: vk::SampleCountFlags) -> Self {
-		SampleCountFlags(f)
-	}
-}
+use crate::framebuffer::SampleCounts;
 
 /// Limits of a physical device.
+#[derive(Clone, Copy)]
 pub struct Limits<'a> {
 	limits: &'a vk::PhysicalDeviceLimits,
 }
@@ -35,10 +29,10 @@ macro_rules! limits_impl {
 			self.limits.$target != 0
 		}
 	};
-	(@fn $name:ident: SampleCountFlags => $target:ident) => {
+	(@fn $name:ident: SampleCounts => $target:ident) => {
 		#[inline]
-		pub fn $name(&self) -> SampleCountFlags {
-			SampleCountFlags(self.limits.$target)
+		pub fn $name(&self) -> SampleCounts {
+			SampleCounts::from_vulkan(self.limits.$target)
 		}
 	};
 	(@fn $name:ident: $t:ty => $target:ident) => {
@@ -130,16 +124,16 @@ limits_impl! {
 	max_framebuffer_width: u32 => max_framebuffer_width,
 	max_framebuffer_height: u32 => max_framebuffer_height,
 	max_framebuffer_layers: u32 => max_framebuffer_layers,
-	framebuffer_color_sample_counts: SampleCountFlags => framebuffer_color_sample_counts,
-	framebuffer_depth_sample_counts: SampleCountFlags => framebuffer_depth_sample_counts,
-	framebuffer_stencil_sample_counts: SampleCountFlags => framebuffer_stencil_sample_counts,
-	framebuffer_no_attachments_sample_counts: SampleCountFlags => framebuffer_no_attachments_sample_counts,
+	framebuffer_color_sample_counts: SampleCounts => framebuffer_color_sample_counts,
+	framebuffer_depth_sample_counts: SampleCounts => framebuffer_depth_sample_counts,
+	framebuffer_stencil_sample_counts: SampleCounts => framebuffer_stencil_sample_counts,
+	framebuffer_no_attachments_sample_counts: SampleCounts => framebuffer_no_attachments_sample_counts,
 	max_color_attachments: u32 => max_color_attachments,
-	sampled_image_color_sample_counts: SampleCountFlags => sampled_image_color_sample_counts,
-	sampled_image_integer_sample_counts: SampleCountFlags => sampled_image_integer_sample_counts,
-	sampled_image_depth_sample_counts: SampleCountFlags => sampled_image_depth_sample_counts,
-	sampled_image_stencil_sample_counts: SampleCountFlags => sampled_image_stencil_sample_counts,
-	storage_image_sample_counts: SampleCountFlags => storage_image_sample_counts,
+	sampled_image_color_sample_counts: SampleCounts => sampled_image_color_sample_counts,
+	sampled_image_integer_sample_counts: SampleCounts => sampled_image_integer_sample_counts,
+	sampled_image_depth_sample_counts: SampleCounts => sampled_image_depth_sample_counts,
+	sampled_image_stencil_sample_counts: SampleCounts => sampled_image_stencil_sample_counts,
+	storage_image_sample_counts: SampleCounts => storage_image_sample_counts,
 	max_sample_mask_words: u32 => max_sample_mask_words,
 	timestamp_compute_and_graphics: bool => timestamp_compute_and_graphics,
 	timestamp_period: f32 => timestamp_period,
@@ -157,3 +151,36 @@ limits_impl! {
 	optimal_buffer_copy_row_pitch_alignment: u64 => optimal_buffer_copy_row_pitch_alignment,
 	non_coherent_atom_size: u64 => non_coherent_atom_size,
 }
+
+impl<'a> Limits<'a> {
+	/// Rounds the given offset up to the minimum uniform buffer offset alignment.
+	#[inline]
+	pub fn align_uniform_offset(&self, offset: u64) -> u64 {
+		align_up(offset, self.min_uniform_buffer_offset_alignment())
+	}
+
+	/// Rounds the given offset up to the minimum storage buffer offset alignment.
+	#[inline]
+	pub fn align_storage_offset(&self, offset: u64) -> u64 {
+		align_up(offset, self.min_storage_buffer_offset_alignment())
+	}
+
+	/// Rounds the given offset up to the minimum texel buffer offset alignment.
+	#[inline]
+	pub fn align_texel_offset(&self, offset: u64) -> u64 {
+		align_up(offset, self.min_texel_buffer_offset_alignment())
+	}
+
+	/// Rounds the given size up to a multiple of the non-coherent atom size.
+	#[inline]
+	pub fn align_non_coherent_size(&self, size: u64) -> u64 {
+		align_up(size, self.non_coherent_atom_size())
+	}
+}
+
+/// Rounds `value` up to a multiple of `alignment`, which must be a power of two.
+#[inline]
+fn align_up(value: u64, alignment: u64) -> u64 {
+	debug_assert!(alignment.is_power_of_two());
+	(value + alignment - 1) & !(alignment - 1)
+}
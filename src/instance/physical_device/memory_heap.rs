@@ -0,0 +1,49 @@
+use ash::vk;
+use super::PhysicalDevice;
+
+/// Memory heap of a physical device, from which memory types are allocated.
+#[derive(Clone, Copy)]
+pub struct MemoryHeap<'a> {
+	physical_device: &'a PhysicalDevice,
+	index: u32,
+	memory_heap: vk::MemoryHeap
+}
+
+impl<'a> MemoryHeap<'a> {
+	#[inline]
+	pub(crate) fn new(physical_device: &'a PhysicalDevice, index: u32) -> MemoryHeap<'a> {
+		MemoryHeap {
+			physical_device,
+			index,
+			memory_heap: physical_device.info().memory_properties.memory_heaps[index as usize]
+		}
+	}
+
+	#[inline]
+	pub fn physical_device(&self) -> &'a PhysicalDevice {
+		self.physical_device
+	}
+
+	#[inline]
+	pub fn index(&self) -> u32 {
+		self.index
+	}
+
+	/// Size of the heap in bytes.
+	#[inline]
+	pub fn size(&self) -> u64 {
+		self.memory_heap.size
+	}
+
+	/// Returns true if the heap is located on the device.
+	#[inline]
+	pub fn is_device_local(&self) -> bool {
+		self.memory_heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL)
+	}
+
+	/// Returns true if the heap is replicated on each physical device of a device group.
+	#[inline]
+	pub fn is_multi_instance(&self) -> bool {
+		self.memory_heap.flags.contains(vk::MemoryHeapFlags::MULTI_INSTANCE)
+	}
+}
@@ -1,20 +1,80 @@
 use ash::vk;
 use crate::mem::MemoryRequirements;
-use super::PhysicalDevice;
+use super::{
+	PhysicalDevice,
+	MemoryHeap
+};
 
+/// Properties of a memory type.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Properties(vk::MemoryPropertyFlags);
+
+impl Properties {
+	/// Memory located on the device.
+	pub const DEVICE_LOCAL: Properties = Properties(vk::MemoryPropertyFlags::DEVICE_LOCAL);
+
+	/// Memory that can be mapped for host access.
+	pub const HOST_VISIBLE: Properties = Properties(vk::MemoryPropertyFlags::HOST_VISIBLE);
+
+	/// Host and device writes are visible without flushing or invalidating.
+	pub const HOST_COHERENT: Properties = Properties(vk::MemoryPropertyFlags::HOST_COHERENT);
+
+	/// Memory cached on the host.
+	pub const HOST_CACHED: Properties = Properties(vk::MemoryPropertyFlags::HOST_CACHED);
+
+	/// Memory lazily allocated by the implementation.
+	pub const LAZILY_ALLOCATED: Properties = Properties(vk::MemoryPropertyFlags::LAZILY_ALLOCATED);
+
+	/// No properties.
+	#[inline]
+	pub fn none() -> Properties {
+		Properties(vk::MemoryPropertyFlags::empty())
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	#[inline]
+	pub fn contains(&self, other: Properties) -> bool {
+		self.0.contains(other.0)
+	}
+
+	#[inline]
+	pub(crate) fn from_vulkan(flags: vk::MemoryPropertyFlags) -> Properties {
+		Properties(flags)
+	}
+}
+
+impl std::ops::BitOr for Properties {
+	type Output = Properties;
+
+	fn bitor(self, rhs: Self) -> Properties {
+		Properties(self.0 | rhs.0)
+	}
+}
+
+impl std::ops::BitOrAssign for Properties {
+	fn bitor_assign(&mut self, rhs: Self) {
+		self.0 |= rhs.0
+	}
+}
+
+#[derive(Clone, Copy)]
 pub struct MemoryType<'a> {
-	physical_device: PhysicalDevice<'a>,
+	physical_device: &'a PhysicalDevice,
 	index: u32,
 	memory_type: vk::MemoryType
 }
 
 impl<'a> MemoryType<'a> {
 	#[inline]
-	pub(crate) fn new(physical_device: PhysicalDevice<'a>, index: u32) -> MemoryType<'a> {
+	pub(crate) fn new(physical_device: &'a PhysicalDevice, index: u32) -> MemoryType<'a> {
 		MemoryType {
 			physical_device,
 			index,
-			memory_type: physical_device.p.memory_properties.memory_types[index as usize]
+			memory_type: physical_device.info().memory_properties.memory_types[index as usize]
 		}
 	}
 
@@ -23,9 +83,16 @@ impl<'a> MemoryType<'a> {
 		self.index
 	}
 
+	/// Heap from which memory of this type is allocated.
+	#[inline]
+	pub fn heap(&self) -> MemoryHeap<'a> {
+		MemoryHeap::new(self.physical_device, self.memory_type.heap_index)
+	}
+
+	/// Size of the heap of this memory type.
 	#[inline]
 	pub fn size(&self) -> u64 {
-		self.physical_device.p.memory_properties.memory_heaps[self.memory_type.heap_index as usize].size
+		self.heap().size()
 	}
 
 	#[inline]
@@ -33,6 +100,12 @@ impl<'a> MemoryType<'a> {
 		reqs.contains_memory_type_index(self.index)
 	}
 
+	/// Properties of the memory type.
+	#[inline]
+	pub fn properties(&self) -> Properties {
+		Properties::from_vulkan(self.memory_type.property_flags)
+	}
+
 	/// Returns true if the memory type is located on the device.
 	/// 
 	/// This is the most efficient for GPU accesses.
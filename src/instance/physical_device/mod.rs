@@ -1,6 +1,7 @@
 use std::{
 	sync::Arc,
-	ffi::CStr,
+	ffi::c_void,
+	fmt,
 	hash::{
 		Hash,
 		Hasher
@@ -11,39 +12,74 @@ use ash::{
 	version::InstanceV1_0
 };
 
-use crate::device;
+use crate::{
+	OomError,
+	device,
+	Format,
+	format,
+	image,
+	sync::external::{
+		ExternalSemaphoreHandleType,
+		ExternalFenceHandleType,
+		ExternalProperties
+	},
+	swapchain::{
+		Surface,
+		surface
+	}
+};
 use super::{
 	Instance,
+	ApiVersion,
 	PhysicalDeviceInfo
 };
 
 mod limits;
 mod memory_type;
+mod memory_heap;
 mod queue_family;
+mod device_type;
+mod requirements;
 
 pub use limits::Limits;
-pub use memory_type::MemoryType;
-pub use queue_family::QueueFamily;
-
-#[derive(Clone, Copy)]
-pub struct PhysicalDevice<'a> {
-	instance: &'a Arc<Instance>,
-	index: u32,
-	p: &'a PhysicalDeviceInfo
+pub use device_type::DeviceType;
+pub use requirements::Requirements;
+pub use memory_type::{
+	MemoryType,
+	Properties as MemoryProperties
+};
+pub use memory_heap::MemoryHeap;
+pub use queue_family::{
+	QueueFamily,
+	Capabilities as QueueCapabilities
+};
+
+/// Physical device.
+///
+/// Holds a reference to its instance so it can be stored independently,
+/// and is cheap to clone.
+#[derive(Clone)]
+pub struct PhysicalDevice {
+	instance: Arc<Instance>,
+	index: u32
 }
 
-impl<'a> PhysicalDevice<'a> {
+impl PhysicalDevice {
 	#[inline]
-	pub(crate) fn new(instance: &'a Arc<Instance>, index: u32) -> PhysicalDevice<'a> {
-		let info = &instance.physical_devices_info[index as usize];
+	pub(crate) fn new(instance: &Arc<Instance>, index: u32) -> PhysicalDevice {
+		assert!((index as usize) < instance.physical_devices_info.len());
 
 		PhysicalDevice {
-			instance,
-			index,
-			p: info
+			instance: instance.clone(),
+			index
 		}
 	}
 
+	#[inline]
+	pub(crate) fn info(&self) -> &PhysicalDeviceInfo {
+		&self.instance.physical_devices_info[self.index as usize]
+	}
+
 	#[inline]
 	pub fn index(&self) -> u32 {
 		self.index
@@ -51,74 +87,407 @@ impl<'a> PhysicalDevice<'a> {
 
 	#[inline]
 	pub(crate) fn handle(&self) -> vk::PhysicalDevice {
-		self.p.handle
+		self.info().handle
 	}
 
 	#[inline]
-	pub fn instance(&self) -> &'a Arc<Instance> {
-		self.instance
+	pub fn instance(&self) -> &Arc<Instance> {
+		&self.instance
 	}
 
-	#[inline]
+	/// Name of the device.
+	///
+	/// The name reported by the driver is truncated to its first nul character,
+	/// and to its longest valid UTF-8 prefix.
 	pub fn name(&self) -> &str {
-		unsafe {
-			let c_name = CStr::from_ptr(self.p.properties.device_name.as_ptr());
-			c_name.to_str().expect("physical device name is not UTF-8 encoded")
+		let buffer = &self.info().properties.device_name;
+		let bytes = unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, buffer.len()) };
+		let bytes = match bytes.iter().position(|b| *b == 0) {
+			Some(len) => &bytes[..len],
+			None => bytes
+		};
+
+		match std::str::from_utf8(bytes) {
+			Ok(name) => name,
+			Err(e) => std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap()
 		}
 	}
 
+	/// Vendor identifier.
+	#[inline]
+	pub fn vendor_id(&self) -> u32 {
+		self.info().properties.vendor_id
+	}
+
+	/// Device identifier, unique among the devices of the vendor.
+	#[inline]
+	pub fn device_id(&self) -> u32 {
+		self.info().properties.device_id
+	}
+
+	#[inline]
+	pub fn device_type(&self) -> DeviceType {
+		DeviceType::from_vulkan(self.info().properties.device_type)
+	}
+
+	/// Vendor specified version of the driver.
+	#[inline]
+	pub fn driver_version(&self) -> u32 {
+		self.info().properties.driver_version
+	}
+
+	/// Universally unique identifier of the device pipeline caches.
+	#[inline]
+	pub fn pipeline_cache_uuid(&self) -> [u8; 16] {
+		self.info().properties.pipeline_cache_uuid
+	}
+
+	/// Highest Vulkan API version supported by the device.
+	#[inline]
+	pub fn api_version(&self) -> ApiVersion {
+		ApiVersion::from_vulkan(self.info().properties.api_version)
+	}
+
+	#[inline]
+	pub fn queue_families(&self) -> impl '_ + Iterator<Item=QueueFamily<'_>> {
+		self.info().queue_family_properties.iter().enumerate().map(move |(i, qf)| {
+			QueueFamily::new(self, i as u32, qf)
+		})
+	}
+
+	#[inline]
+	pub fn queue_family(&self, id: u32) -> Option<QueueFamily> {
+		self.info().queue_family_properties.get(id as usize).map(move |qf| QueueFamily::new(self, id, qf))
+	}
+
+	/// Finds the first queue family with the given capabilities.
+	#[inline]
+	pub fn find_queue_family(&self, capabilities: QueueCapabilities) -> Option<QueueFamily> {
+		self.queue_families().find(|queue_family| queue_family.capabilities().contains(capabilities))
+	}
+
+	/// Finds a queue family dedicated to transfer operations,
+	/// supporting neither graphics nor compute operations.
+	///
+	/// Such queue families are typically backed by DMA engines
+	/// and can perform uploads concurrently to rendering.
+	#[inline]
+	pub fn find_dedicated_transfer_family(&self) -> Option<QueueFamily> {
+		self.queue_families().find(|queue_family| {
+			queue_family.supports_transfer() && !queue_family.supports_graphics() && !queue_family.supports_compute()
+		})
+	}
+
+	/// Checks if the queue family of the given index supports presentation on the given surface.
+	///
+	/// The `KHR_Surface` extension must be enabled or a missing extension error is returned.
+	/// Returns an `InvalidQueueFamily` error if there is no queue family with the given index.
+	///
+	/// # Panic
+	///
+	/// Panics if the device and the surface don't belong to the same instance.
+	pub fn surface_support<W>(&self, queue_family_index: u32, surface: &Surface<W>) -> Result<bool, surface::CapabilitiesError> {
+		let queue_family = self.queue_family(queue_family_index).ok_or(surface::CapabilitiesError::InvalidQueueFamily(queue_family_index))?;
+		surface.is_supported(queue_family)
+	}
+
+	/// Iterates over the queue families supporting presentation on the given surface.
+	///
+	/// The `KHR_Surface` extension must be enabled or a missing extension error is returned.
 	#[inline]
-	pub fn queue_families(&self) -> impl 'a + Iterator<Item=QueueFamily<'a>> {
-		let this = *self;
-		self.p.queue_family_properties.iter().enumerate().map(move |(i, qf)| {
-			QueueFamily::new(this, i as u32, qf)
+	pub fn queue_families_supporting<'a, W>(&'a self, surface: &'a Surface<W>) -> impl 'a + Iterator<Item=Result<QueueFamily<'a>, surface::CapabilitiesError>> {
+		self.queue_families().filter_map(move |queue_family| {
+			match surface.is_supported(queue_family) {
+				Ok(true) => Some(Ok(queue_family)),
+				Ok(false) => None,
+				Err(e) => Some(Err(e))
+			}
 		})
 	}
 
+	/// Retrieves the capabilities of the given surface when used by this device.
+	///
+	/// # Panic
+	///
+	/// Panics if the device and the surface don't belong to the same instance.
 	#[inline]
-	pub fn queue_family(&self, id: u32) -> Option<QueueFamily<'a>> {
-		let this = *self;
-		self.p.queue_family_properties.get(id as usize).map(move |qf| QueueFamily::new(this, id, qf))
+	pub fn surface_capabilities<W>(&self, surface: &Surface<W>) -> Result<surface::Capabilities, surface::CapabilitiesError> {
+		surface.capabilities(self)
+	}
+
+	/// Retrieves the formats and color spaces supported by the given surface when used by this device.
+	///
+	/// # Panic
+	///
+	/// Panics if the device and the surface don't belong to the same instance.
+	#[inline]
+	pub fn surface_formats<W>(&self, surface: &Surface<W>) -> Result<Vec<surface::SurfaceFormat>, surface::CapabilitiesError> {
+		surface.formats(self)
+	}
+
+	/// Retrieves the present modes supported by the given surface when used by this device.
+	///
+	/// # Panic
+	///
+	/// Panics if the device and the surface don't belong to the same instance.
+	#[inline]
+	pub fn surface_present_modes<W>(&self, surface: &Surface<W>) -> Result<surface::PresentModes, surface::CapabilitiesError> {
+		surface.present_modes(self)
+	}
+
+	/// Retrieves the properties of the given format when used by this device.
+	#[inline]
+	pub fn format_properties(&self, format: Format) -> format::FormatProperties {
+		format.properties(self)
+	}
+
+	/// Finds the first format of `candidates` supporting the `required_features`
+	/// with the given tiling.
+	///
+	/// This is typically used to select a depth format.
+	pub fn find_supported_format(&self, candidates: &[Format], tiling: image::Tiling, required_features: format::Features) -> Option<Format> {
+		candidates.iter().cloned().find(|format| {
+			self.format_properties(*format).tiling_features(tiling).contains(required_features)
+		})
+	}
+
+	/// Retrieves the limits of images created with the given parameters on this device.
+	///
+	/// Returns `FormatPropertiesError::Unsupported` if such images are not supported at all.
+	pub fn image_format_properties(
+		&self,
+		format: Format,
+		ty: image::Type,
+		tiling: image::Tiling,
+		usage: image::Usage,
+		flags: image::Flags
+	) -> Result<image::ImageFormatProperties, image::FormatPropertiesError> {
+		let properties = unsafe {
+			self.instance.handle.get_physical_device_image_format_properties(
+				self.info().handle,
+				format.into_vulkan(),
+				ty.into_vulkan(),
+				tiling.into_vulkan(),
+				usage.to_vulkan(),
+				flags.into_vulkan()
+			)?
+		};
+
+		Ok(image::ImageFormatProperties::from_vulkan(properties))
+	}
+
+	/// Checks if semaphores of this device can be exported and imported with the given handle type.
+	///
+	/// Requires Vulkan 1.1 or the `VK_KHR_external_semaphore_capabilities` instance extension,
+	/// otherwise no handle type is supported.
+	pub fn external_semaphore_properties(&self, handle_type: ExternalSemaphoreHandleType) -> ExternalProperties {
+		let info = vk::PhysicalDeviceExternalSemaphoreInfo {
+			handle_type: handle_type.into_vulkan(),
+			..Default::default()
+		};
+
+		let mut properties = vk::ExternalSemaphoreProperties::default();
+		if unsafe { !self.instance.get_physical_device_external_semaphore_properties(self.info().handle, &info, &mut properties) } {
+			return ExternalProperties::default()
+		}
+
+		let features = properties.external_semaphore_features;
+		ExternalProperties {
+			exportable: features.contains(vk::ExternalSemaphoreFeatureFlags::EXTERNAL_SEMAPHORE_FEATURE_EXPORTABLE),
+			importable: features.contains(vk::ExternalSemaphoreFeatureFlags::EXTERNAL_SEMAPHORE_FEATURE_IMPORTABLE)
+		}
+	}
+
+	/// Checks if fences of this device can be exported and imported with the given handle type.
+	///
+	/// Requires Vulkan 1.1 or the `VK_KHR_external_fence_capabilities` instance extension,
+	/// otherwise no handle type is supported.
+	pub fn external_fence_properties(&self, handle_type: ExternalFenceHandleType) -> ExternalProperties {
+		let info = vk::PhysicalDeviceExternalFenceInfo {
+			handle_type: handle_type.into_vulkan(),
+			..Default::default()
+		};
+
+		let mut properties = vk::ExternalFenceProperties::default();
+		if unsafe { !self.instance.get_physical_device_external_fence_properties(self.info().handle, &info, &mut properties) } {
+			return ExternalProperties::default()
+		}
+
+		let features = properties.external_fence_features;
+		ExternalProperties {
+			exportable: features.contains(vk::ExternalFenceFeatureFlags::EXTERNAL_FENCE_FEATURE_EXPORTABLE),
+			importable: features.contains(vk::ExternalFenceFeatureFlags::EXTERNAL_FENCE_FEATURE_IMPORTABLE)
+		}
+	}
+
+	/// Device extensions supported by this device.
+	///
+	/// Extensions unknown to this crate are ignored.
+	pub fn supported_extensions(&self) -> Result<&device::Extensions, OomError> {
+		let instance = &self.instance;
+		let handle = self.info().handle;
+		self.info().supported_extensions.get_or_try_init(|| {
+			let mut extensions = device::Extensions::none();
+			let properties = unsafe {
+				instance.handle.enumerate_device_extension_properties(handle)?
+			};
+
+			for ext_prop in properties {
+				let c_name = match crate::c_name_from_array(&ext_prop.extension_name) {
+					Some(c_name) => c_name,
+					None => {
+						warn!("ignoring device extension with malformed name");
+						continue
+					}
+				};
+
+				match device::Extension::from_c_name(c_name) {
+					Some(ext) => extensions.insert(ext),
+					None => log::trace!("ignoring unknown device extension `{}`", c_name.to_string_lossy())
+				}
+			}
+
+			Ok(extensions)
+		})
+	}
+
+	/// Extended features supported by this device.
+	///
+	/// Returns `None` if the instance neither uses Vulkan 1.1 nor has the
+	/// `VK_KHR_get_physical_device_properties2` extension enabled.
+	/// Blocks whose device extension is not supported are reported empty.
+	pub fn supported_extended_features(&self) -> Option<&device::ExtendedFeatures> {
+		let this = self;
+		self.info().supported_extended_features.get_or_init(|| {
+			let supported_extensions = this.supported_extensions().ok();
+			let is_supported = |version, ext| this.api_version().min(this.instance.api_version()) >= version || supported_extensions.map(|exts| exts.contains(ext)).unwrap_or(false);
+
+			let mut descriptor_indexing = vk::PhysicalDeviceDescriptorIndexingFeatures::default();
+			let mut timeline_semaphore = vk::PhysicalDeviceTimelineSemaphoreFeatures::default();
+			let mut vulkan_1_2 = vk::PhysicalDeviceVulkan12Features::default();
+			let mut dynamic_rendering = crate::ffi::PhysicalDeviceDynamicRenderingFeatures::default();
+			let mut index_type_uint8 = vk::PhysicalDeviceIndexTypeUint8FeaturesEXT::default();
+			let mut portability_subset = crate::ffi::PhysicalDevicePortabilitySubsetFeatures::default();
+			let mut features = vk::PhysicalDeviceFeatures2::default();
+
+			let mut p_next: *mut c_void = std::ptr::null_mut();
+			if is_supported(ApiVersion::V1_2, device::Extension::ExtDescriptorIndexing) {
+				descriptor_indexing.p_next = p_next;
+				p_next = &mut descriptor_indexing as *mut _ as *mut c_void;
+			}
+			if is_supported(ApiVersion::V1_2, device::Extension::KhrTimelineSemaphore) {
+				timeline_semaphore.p_next = p_next;
+				p_next = &mut timeline_semaphore as *mut _ as *mut c_void;
+			}
+			if this.api_version().min(this.instance.api_version()) >= ApiVersion::V1_2 {
+				vulkan_1_2.p_next = p_next;
+				p_next = &mut vulkan_1_2 as *mut _ as *mut c_void;
+			}
+			if is_supported(ApiVersion::V1_3, device::Extension::KhrDynamicRendering) {
+				dynamic_rendering.p_next = p_next;
+				p_next = &mut dynamic_rendering as *mut _ as *mut c_void;
+			}
+			if supported_extensions.map(|exts| exts.contains(device::Extension::ExtIndexTypeUint8)).unwrap_or(false) {
+				index_type_uint8.p_next = p_next;
+				p_next = &mut index_type_uint8 as *mut _ as *mut c_void;
+			}
+			if supported_extensions.map(|exts| exts.contains(device::Extension::KhrPortabilitySubset)).unwrap_or(false) {
+				portability_subset.p_next = p_next;
+				p_next = &mut portability_subset as *mut _ as *mut c_void;
+			}
+			features.p_next = p_next;
+
+			let available = unsafe {
+				this.instance.get_physical_device_features2(this.info().handle, &mut features)
+			};
+
+			if available {
+				Some(device::ExtendedFeatures {
+					descriptor_indexing: descriptor_indexing.into(),
+					timeline_semaphore: timeline_semaphore.into(),
+					vulkan_1_2: vulkan_1_2.into(),
+					dynamic_rendering: dynamic_rendering.into(),
+					index_type_uint8: index_type_uint8.into(),
+					portability_subset: portability_subset.into()
+				})
+			} else {
+				None
+			}
+		}).as_ref()
 	}
 
 	#[inline]
 	pub fn supported_features(&self) -> &device::Features {
-		&self.p.supported_features
+		&self.info().supported_features
+	}
+
+	#[inline]
+	pub(crate) fn vulkan_memory_properties(&self) -> &vk::PhysicalDeviceMemoryProperties {
+		&self.info().memory_properties
 	}
 
 	#[inline]
 	pub fn memory_type_count(&self) -> u32 {
-		self.p.memory_properties.memory_type_count
+		self.info().memory_properties.memory_type_count
 	}
 
 	#[inline]
-	pub fn memory_type(&self, index: u32) -> Option<MemoryType<'a>> {
+	pub fn memory_type(&self, index: u32) -> Option<MemoryType> {
 		if index < self.memory_type_count() {
-			Some(MemoryType::new(*self, index))
+			Some(MemoryType::new(self, index))
 		} else {
 			None
 		}
 	}
 
 	#[inline]
-	pub fn memory_types(&self) -> impl 'a + Iterator<Item=MemoryType<'a>> {
-		let this = *self;
+	pub fn memory_types(&self) -> impl '_ + Iterator<Item=MemoryType<'_>> {
 		let len = self.memory_type_count();
-		(0u32..len).into_iter().map(move |i| MemoryType::new(this, i))
+		(0u32..len).into_iter().map(move |i| MemoryType::new(self, i))
 	}
 
-	pub fn limits(&self) -> Limits<'a> {
-		Limits::from_vk_limits(&self.p.properties.limits)
+	#[inline]
+	pub fn memory_heap_count(&self) -> u32 {
+		self.info().memory_properties.memory_heap_count
+	}
+
+	#[inline]
+	pub fn memory_heap(&self, index: u32) -> Option<MemoryHeap> {
+		if index < self.memory_heap_count() {
+			Some(MemoryHeap::new(self, index))
+		} else {
+			None
+		}
+	}
+
+	#[inline]
+	pub fn memory_heaps(&self) -> impl '_ + Iterator<Item=MemoryHeap<'_>> {
+		let len = self.memory_heap_count();
+		(0u32..len).into_iter().map(move |i| MemoryHeap::new(self, i))
+	}
+
+	/// Limits of the device.
+	#[inline]
+	pub fn limits(&self) -> Limits {
+		Limits::from_vk_limits(&self.info().properties.limits)
+	}
+}
+
+impl fmt::Display for PhysicalDevice {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} ({})", self.name(), self.device_type())
 	}
 }
 
-impl<'a> PartialEq for PhysicalDevice<'a> {
+impl PartialEq for PhysicalDevice {
 	fn eq(&self, other: &Self) -> bool {
 		self.index() == other.index() && Arc::ptr_eq(self.instance(), other.instance())
 	}
 }
 
-impl<'a> Hash for PhysicalDevice<'a> {
+impl Eq for PhysicalDevice {}
+
+impl Hash for PhysicalDevice {
 	fn hash<H: Hasher>(&self, h: &mut H) {
 		self.index().hash(h);
 	}
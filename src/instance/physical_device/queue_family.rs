@@ -16,16 +16,77 @@ use crate::{
 };
 use super::PhysicalDevice;
 
+/// Capabilities of a queue family.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Capabilities(vk::QueueFlags);
+
+impl Capabilities {
+	/// Graphics operations.
+	pub const GRAPHICS: Capabilities = Capabilities(vk::QueueFlags::GRAPHICS);
+
+	/// Compute operations.
+	pub const COMPUTE: Capabilities = Capabilities(vk::QueueFlags::COMPUTE);
+
+	/// Transfer operations.
+	pub const TRANSFER: Capabilities = Capabilities(vk::QueueFlags::TRANSFER);
+
+	/// Sparse resource memory management operations.
+	pub const SPARSE_BINDING: Capabilities = Capabilities(vk::QueueFlags::SPARSE_BINDING);
+
+	/// No capabilities.
+	#[inline]
+	pub fn none() -> Capabilities {
+		Capabilities(vk::QueueFlags::empty())
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	#[inline]
+	pub fn contains(&self, other: Capabilities) -> bool {
+		self.0.contains(other.0)
+	}
+
+	/// Capabilities described by the given queue flags.
+	///
+	/// Queues supporting graphics or compute operations always support transfer operations,
+	/// even if not reported by the flags.
+	#[inline]
+	pub(crate) fn from_vulkan(flags: vk::QueueFlags) -> Capabilities {
+		if flags.intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE) {
+			Capabilities(flags | vk::QueueFlags::TRANSFER)
+		} else {
+			Capabilities(flags)
+		}
+	}
+}
+
+impl std::ops::BitOr for Capabilities {
+	type Output = Capabilities;
+
+	fn bitor(self, rhs: Self) -> Capabilities {
+		Capabilities(self.0 | rhs.0)
+	}
+}
+
+impl std::ops::BitOrAssign for Capabilities {
+	fn bitor_assign(&mut self, rhs: Self) {
+		self.0 |= rhs.0
+	}
+}
+
 #[derive(Clone, Copy)]
 pub struct QueueFamily<'a> {
-	physical_device: PhysicalDevice<'a>,
+	physical_device: &'a PhysicalDevice,
 	index: u32,
 	props: &'a vk::QueueFamilyProperties
 }
 
 impl<'a> QueueFamily<'a> {
 	#[inline]
-	pub(crate) fn new(physical_device: PhysicalDevice<'a>, index: u32, props: &'a vk::QueueFamilyProperties) -> QueueFamily<'a> {
+	pub(crate) fn new(physical_device: &'a PhysicalDevice, index: u32, props: &'a vk::QueueFamilyProperties) -> QueueFamily<'a> {
 		QueueFamily {
 			physical_device,
 			index,
@@ -35,13 +96,41 @@ impl<'a> QueueFamily<'a> {
 
 	/// Number of queues in this family.
 	#[inline]
-	pub fn queue_count(&self) -> u32 {
+	pub fn count(&self) -> u32 {
 		self.props.queue_count
 	}
 
+	/// Number of queues in this family.
+	#[deprecated(note = "use `count` instead")]
+	#[inline]
+	pub fn queue_count(&self) -> u32 {
+		self.count()
+	}
+
+	/// Capabilities of the queues of this family.
+	#[inline]
+	pub fn capabilities(&self) -> Capabilities {
+		Capabilities::from_vulkan(self.props.queue_flags)
+	}
+
+	/// Number of meaningful bits in the timestamps written by the queues of this family.
+	///
+	/// Timestamps are not supported if zero.
+	#[inline]
+	pub fn timestamp_valid_bits(&self) -> u32 {
+		self.props.timestamp_valid_bits
+	}
+
+	/// Minimum granularity (width, height, depth) of image transfer operations on the queues of this family.
+	#[inline]
+	pub fn min_image_transfer_granularity(&self) -> (u32, u32, u32) {
+		let granularity = self.props.min_image_transfer_granularity;
+		(granularity.width, granularity.height, granularity.depth)
+	}
+
 	/// Physical device this queue family is attached to.
 	#[inline]
-	pub fn physical_device(&self) -> PhysicalDevice<'a> {
+	pub fn physical_device(&self) -> &'a PhysicalDevice {
 		self.physical_device
 	}
 
@@ -54,7 +143,7 @@ impl<'a> QueueFamily<'a> {
 	/// Queue family supports graphics operations.
 	#[inline]
 	pub fn supports_graphics(&self) -> bool {
-		self.props.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+		self.capabilities().contains(Capabilities::GRAPHICS)
 	}
 
 	/// Queue family supports presentation on the given surface.
@@ -68,19 +157,19 @@ impl<'a> QueueFamily<'a> {
 	/// Queue family supports compute operations.
 	#[inline]
 	pub fn supports_compute(&self) -> bool {
-		self.props.queue_flags.contains(vk::QueueFlags::COMPUTE)
+		self.capabilities().contains(Capabilities::COMPUTE)
 	}
 
 	/// Queue family supports transfer operations.
 	#[inline]
 	pub fn supports_transfer(&self) -> bool {
-		self.props.queue_flags.contains(vk::QueueFlags::TRANSFER)
+		self.capabilities().contains(Capabilities::TRANSFER)
 	}
 
 	/// Queue family supports sparse resource memory management operations.
 	#[inline]
 	pub fn supports_sparse_binding(&self) -> bool {
-		self.props.queue_flags.contains(vk::QueueFlags::SPARSE_BINDING)
+		self.capabilities().contains(Capabilities::SPARSE_BINDING)
 	}
 }
 
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use crate::{
+	Instance,
+	device,
+	swapchain::Surface
+};
+use super::{
+	PhysicalDevice,
+	QueueFamily,
+	QueueCapabilities,
+	DeviceType
+};
+
+/// Common physical device requirements.
+///
+/// ```ignore
+/// let physical_device = Requirements::default()
+/// 	.graphics()
+/// 	.present_to(&surface)
+/// 	.extension(device::Extension::KhrSwapchain)
+/// 	.pick(&instance)
+/// 	.expect("no suitable physical device");
+/// ```
+///
+/// Among the devices meeting the requirements,
+/// discrete GPUs are preferred over integrated GPUs, then virtual GPUs, then CPUs.
+pub struct Requirements<'s> {
+	queue_capabilities: QueueCapabilities,
+	extensions: device::Extensions,
+	features: device::Features,
+	presentation: Option<Box<dyn 's + Fn(QueueFamily) -> bool>>
+}
+
+impl<'s> Requirements<'s> {
+	/// No requirements.
+	#[inline]
+	pub fn new() -> Requirements<'s> {
+		Requirements {
+			queue_capabilities: QueueCapabilities::none(),
+			extensions: device::Extensions::none(),
+			features: device::Features::none(),
+			presentation: None
+		}
+	}
+
+	/// Requires a queue family supporting graphics operations.
+	#[inline]
+	pub fn graphics(mut self) -> Requirements<'s> {
+		self.queue_capabilities |= QueueCapabilities::GRAPHICS;
+		self
+	}
+
+	/// Requires a queue family supporting compute operations.
+	#[inline]
+	pub fn compute(mut self) -> Requirements<'s> {
+		self.queue_capabilities |= QueueCapabilities::COMPUTE;
+		self
+	}
+
+	/// Requires a queue family supporting transfer operations.
+	#[inline]
+	pub fn transfer(mut self) -> Requirements<'s> {
+		self.queue_capabilities |= QueueCapabilities::TRANSFER;
+		self
+	}
+
+	/// Requires a queue family supporting presentation on the given surface.
+	///
+	/// Queue families for which the support cannot be queried are considered unsupported.
+	#[inline]
+	pub fn present_to<W>(mut self, surface: &'s Surface<W>) -> Requirements<'s> {
+		self.presentation = Some(Box::new(move |queue_family| {
+			queue_family.supports_presentation(surface).unwrap_or(false)
+		}));
+		self
+	}
+
+	/// Requires the given device extension.
+	#[inline]
+	pub fn extension(mut self, ext: device::Extension) -> Requirements<'s> {
+		self.extensions.insert(ext);
+		self
+	}
+
+	/// Requires the given device features.
+	#[inline]
+	pub fn features(mut self, features: &device::Features) -> Requirements<'s> {
+		self.features = self.features.union(features);
+		self
+	}
+
+	/// Checks if the given physical device meets the requirements.
+	///
+	/// The required capabilities and the presentation support
+	/// may be provided by different queue families.
+	pub fn is_met_by(&self, physical_device: &PhysicalDevice) -> bool {
+		let queue_capabilities = physical_device.queue_families().fold(QueueCapabilities::none(), |capabilities, queue_family| {
+			capabilities | queue_family.capabilities()
+		});
+
+		if !queue_capabilities.contains(self.queue_capabilities) {
+			return false
+		}
+
+		if let Some(presentation) = &self.presentation {
+			if !physical_device.queue_families().any(|queue_family| presentation(queue_family)) {
+				return false
+			}
+		}
+
+		if !self.extensions.is_empty() {
+			match physical_device.supported_extensions() {
+				Ok(supported) => {
+					if !self.extensions.difference(supported).is_empty() {
+						return false
+					}
+				},
+				Err(_) => return false
+			}
+		}
+
+		self.features.difference(physical_device.supported_features()).is_empty()
+	}
+
+	/// Score of the given physical device, or `None` if it does not meet the requirements.
+	pub fn score(&self, physical_device: &PhysicalDevice) -> Option<u32> {
+		if self.is_met_by(physical_device) {
+			Some(match physical_device.device_type() {
+				DeviceType::DiscreteGpu => 4,
+				DeviceType::IntegratedGpu => 3,
+				DeviceType::VirtualGpu => 2,
+				DeviceType::Cpu => 1,
+				DeviceType::Other => 0
+			})
+		} else {
+			None
+		}
+	}
+
+	/// Picks the best physical device of the given instance meeting the requirements.
+	#[inline]
+	pub fn pick(&self, instance: &Arc<Instance>) -> Option<PhysicalDevice> {
+		instance.pick_physical_device(|physical_device| self.score(physical_device))
+	}
+}
+
+impl<'s> Default for Requirements<'s> {
+	#[inline]
+	fn default() -> Requirements<'s> {
+		Requirements::new()
+	}
+}
@@ -7,6 +7,7 @@ extern crate static_assertions;
 use std::{
 	error::Error,
 	ffi::CStr,
+	os::raw::c_char,
 	fmt
 };
 use once_cell::sync::OnceCell;
@@ -17,6 +18,7 @@ use ash::{
 
 #[macro_use]
 mod set;
+mod ffi;
 pub mod resource;
 pub mod ops;
 pub mod sync;
@@ -44,8 +46,44 @@ pub use swapchain::Swapchain;
 pub use image::Image;
 pub use framebuffer::Framebuffer;
 
+/// Error raised when querying the Vulkan loader.
+#[derive(Debug)]
+pub enum EntryError {
+	OutOfMemory(OomError),
+	Unexpected(vk::Result)
+}
+
+impl Error for EntryError {
+	fn source(&self) -> Option<&(dyn 'static + Error)> {
+		match self {
+			EntryError::OutOfMemory(oom) => Some(oom),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for EntryError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			EntryError::OutOfMemory(oom) => oom.fmt(f),
+			EntryError::Unexpected(r) => write!(f, "unexpected loader error `{}`", r)
+		}
+	}
+}
+
+impl From<vk::Result> for EntryError {
+	fn from(r: vk::Result) -> EntryError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => EntryError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => EntryError::OutOfMemory(OomError::Device),
+			r => EntryError::Unexpected(r)
+		}
+	}
+}
+
 pub struct Entry {
 	handle: ash::Entry,
+	api_version: OnceCell<instance::ApiVersion>,
 	extensions: OnceCell<instance::Extensions>,
 	layers: OnceCell<instance::ValidationLayers>
 }
@@ -54,54 +92,94 @@ impl Entry {
 	pub fn new() -> Result<Entry, ash::LoadingError> {
 		Ok(Entry {
 			handle: ash::Entry::new()?,
+			api_version: OnceCell::new(),
 			extensions: OnceCell::new(),
 			layers: OnceCell::new()
 		})
 	}
 
-	pub fn validation_layers<'a>(&'a self) -> &instance::ValidationLayers {
-		self.layers.get_or_init(|| unsafe {
+	/// Highest Vulkan API version supported by the loader.
+	pub fn api_version(&self) -> Result<instance::ApiVersion, EntryError> {
+		self.api_version.get_or_try_init(|| {
+			let version = self.handle.try_enumerate_instance_version()?;
+			let api_version = instance::ApiVersion::from_loader_version(version);
+			log::info!("loader supports Vulkan {}", api_version);
+			Ok(api_version)
+		}).map(|v| *v)
+	}
+
+	/// Validation layers available on this system.
+	///
+	/// Layers with a malformed name are ignored.
+	pub fn validation_layers(&self) -> Result<&instance::ValidationLayers, EntryError> {
+		self.layers.get_or_try_init(|| {
 			let mut layers = instance::ValidationLayers::none();
-			for layer_prop in self.handle.enumerate_instance_layer_properties().unwrap() {
-				let c_name = CStr::from_ptr(layer_prop.layer_name.as_ptr());
+			for layer_prop in self.handle.enumerate_instance_layer_properties()? {
+				let c_name = match c_name_from_array(&layer_prop.layer_name) {
+					Some(c_name) => c_name,
+					None => {
+						warn!("ignoring validation layer with malformed name");
+						continue
+					}
+				};
+
 				match instance::ValidationLayer::from_c_name(c_name) {
 					Some(layer) => {
 						log::info!("available validation layer `{}`", layer);
 						layers.insert(layer)
 					},
 					None => {
-						let name = c_name.to_str().expect("validation layer name is not UTF-8 encoded");
-						warn!("unknown validation layer `{}`", name)
+						log::info!("available validation layer `{}` (unknown)", c_name.to_string_lossy());
+						layers.insert_raw(c_name)
 					}
 				}
 			}
 
-			layers
+			Ok(layers)
 		})
 	}
 
-	pub fn extensions(&self) -> &instance::Extensions {
-		self.extensions.get_or_init(|| unsafe {
+	/// Instance extensions available on this system.
+	///
+	/// Extensions with a malformed name are ignored.
+	pub fn extensions(&self) -> Result<&instance::Extensions, EntryError> {
+		self.extensions.get_or_try_init(|| {
 			let mut extensions = instance::Extensions::none();
-			for ext_prop in self.handle.enumerate_instance_extension_properties().unwrap() {
-				let c_name = CStr::from_ptr(ext_prop.extension_name.as_ptr());
+			for ext_prop in self.handle.enumerate_instance_extension_properties()? {
+				let c_name = match c_name_from_array(&ext_prop.extension_name) {
+					Some(c_name) => c_name,
+					None => {
+						warn!("ignoring instance extension with malformed name");
+						continue
+					}
+				};
+
 				match instance::Extension::from_c_name(c_name) {
 					Some(ext) => {
 						log::info!("available instance extension `{}`", ext);
 						extensions.insert(ext)
 					},
 					None => {
-						let name = c_name.to_str().expect("instance extension name is not UTF-8 encoded");
-						warn!("unknown instance extension `{}`", name)
+						log::info!("available instance extension `{}` (unknown)", c_name.to_string_lossy());
+						extensions.insert_raw(c_name)
 					}
 				}
 			}
 
-			extensions
+			Ok(extensions)
 		})
 	}
 }
 
+/// Reads a nul-terminated string from a fixed size buffer filled by the Vulkan implementation.
+///
+/// Returns `None` if the buffer does not contain any nul character.
+pub(crate) fn c_name_from_array(buffer: &[c_char]) -> Option<&CStr> {
+	let bytes = unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, buffer.len()) };
+	let len = bytes.iter().position(|b| *b == 0)?;
+	CStr::from_bytes_with_nul(&bytes[..=len]).ok()
+}
+
 /// Out of memory error.
 #[derive(Debug)]
 pub enum OomError {
@@ -109,7 +187,10 @@ pub enum OomError {
 	Host,
 
 	/// Device is out of memory.
-	Device
+	Device,
+
+	/// The implementation returned an error code not expected from this operation.
+	Unexpected(vk::Result)
 }
 
 impl Error for OomError { }
@@ -118,7 +199,8 @@ impl fmt::Display for OomError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			OomError::Host => write!(f, "host is out of memory"),
-			OomError::Device => write!(f, "device is out of memory")
+			OomError::Device => write!(f, "device is out of memory"),
+			OomError::Unexpected(r) => write!(f, "unexpected error: {}", r)
 		}
 	}
 }
@@ -128,10 +210,81 @@ impl From<vk::Result> for OomError {
 		match r {
 			vk::Result::ERROR_OUT_OF_HOST_MEMORY => OomError::Host,
 			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => OomError::Device,
-			_ => unreachable!()
+			r => OomError::Unexpected(r)
+		}
+	}
+}
+
+/// The logical device has been lost.
+///
+/// Once lost, a device stays lost and must be recreated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceLost {
+	/// The device has been lost.
+	Lost,
+
+	/// The implementation returned an error code not expected from this operation.
+	Unexpected(vk::Result)
+}
+
+impl Error for DeviceLost { }
+
+impl fmt::Display for DeviceLost {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			DeviceLost::Lost => write!(f, "device lost"),
+			DeviceLost::Unexpected(r) => write!(f, "unexpected error: {}", r)
+		}
+	}
+}
+
+impl From<vk::Result> for DeviceLost {
+	fn from(r: vk::Result) -> DeviceLost {
+		match r {
+			vk::Result::ERROR_DEVICE_LOST => DeviceLost::Lost,
+			r => DeviceLost::Unexpected(r)
 		}
 	}
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Unbuildable(());
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn c_chars(bytes: &[u8]) -> Vec<c_char> {
+		bytes.iter().map(|b| *b as c_char).collect()
+	}
+
+	#[test]
+	fn nul_terminated_name() {
+		let buffer = c_chars(b"VK_KHR_surface\0\0\0\0");
+		assert_eq!(c_name_from_array(&buffer).unwrap().to_bytes(), b"VK_KHR_surface")
+	}
+
+	#[test]
+	fn name_filling_the_buffer() {
+		let buffer = c_chars(b"VK_KHR_surface\0");
+		assert_eq!(c_name_from_array(&buffer).unwrap().to_bytes(), b"VK_KHR_surface")
+	}
+
+	#[test]
+	fn empty_name() {
+		let buffer = c_chars(b"\0VK_KHR_surface\0");
+		assert_eq!(c_name_from_array(&buffer).unwrap().to_bytes(), b"")
+	}
+
+	#[test]
+	fn missing_nul() {
+		// The nul character following the slice must not be read.
+		let buffer = c_chars(b"VK_KHR_surface\0");
+		assert!(c_name_from_array(&buffer[..buffer.len() - 1]).is_none())
+	}
+
+	#[test]
+	fn empty_buffer() {
+		assert!(c_name_from_array(&[]).is_none())
+	}
+}
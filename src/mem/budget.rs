@@ -0,0 +1,50 @@
+/// Memory budget and usage of a heap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeapBudget {
+	/// Estimated amount of memory (in bytes) the process can allocate from the heap
+	/// before allocations may fail or cause performance degradation.
+	pub budget: u64,
+
+	/// Estimated amount of memory (in bytes) currently used by the process in the heap.
+	pub usage: u64
+}
+
+/// Memory budget of each heap of a device.
+///
+/// When the `VK_EXT_memory_budget` extension is enabled, the numbers are reported
+/// by the driver and account for every allocation of the process.
+/// Otherwise, the budget of each heap is its size and the usage only accounts
+/// for the memory allocated through the device by this crate.
+#[derive(Clone, Debug)]
+pub struct Budget {
+	heaps: Vec<HeapBudget>,
+	reported_by_driver: bool
+}
+
+impl Budget {
+	#[inline]
+	pub(crate) fn new(heaps: Vec<HeapBudget>, reported_by_driver: bool) -> Budget {
+		Budget {
+			heaps,
+			reported_by_driver
+		}
+	}
+
+	/// Budget of each heap, ordered by heap index.
+	#[inline]
+	pub fn heaps(&self) -> &[HeapBudget] {
+		&self.heaps
+	}
+
+	/// Budget of the heap of the given index.
+	#[inline]
+	pub fn heap(&self, index: u32) -> Option<&HeapBudget> {
+		self.heaps.get(index as usize)
+	}
+
+	/// Checks if the numbers are reported by the driver through `VK_EXT_memory_budget`.
+	#[inline]
+	pub fn is_reported_by_driver(&self) -> bool {
+		self.reported_by_driver
+	}
+}
@@ -9,6 +9,7 @@ use ash::{
 use crate::{
 	Device,
 	DeviceOwned,
+	sync,
 	mem::{
 		Slot,
 		HostVisible,
@@ -17,7 +18,11 @@ use crate::{
 		Buffer
 	}
 };
-use super::Typed;
+use super::{
+	Typed,
+	Usages,
+	vec::Error
+};
 
 /// Bound buffer.
 pub struct Bound<S: Slot> {
@@ -33,6 +38,36 @@ impl<S: Slot> Bound<S> {
 		}
 	}
 
+	/// Create a buffer bound to memory allocated by the given allocator.
+	pub fn allocate<A: Allocator<Slot=S>, U: Into<Usages>, Q: Into<sync::SharingQueues>>(allocator: &A, size: u64, usage: U, sharing_queues: Q) -> Result<Self, Error> {
+		Self::allocate_aligned(allocator, size, usage.into(), sharing_queues.into(), 1)
+	}
+
+	/// Create a buffer bound to memory allocated by the given allocator,
+	/// with an offset aligned to `align`.
+	pub(crate) fn allocate_aligned<A: Allocator<Slot=S>>(allocator: &A, size: u64, usage: Usages, sharing_queues: sync::SharingQueues, align: u64) -> Result<Self, Error> {
+		let inner = buffer::Unbound::new(allocator.device(), size, usage, sharing_queues)?;
+
+		let limits = allocator.device().physical_device().limits();
+		let mut memory_requirements = inner.memory_requirements().align_to(align);
+		if usage.uniform_texel_buffer() || usage.storage_texel_buffer() {
+			memory_requirements = memory_requirements.align_to(limits.min_texel_buffer_offset_alignment());
+		}
+
+		if usage.storage_buffer() {
+			memory_requirements = memory_requirements.align_to(limits.min_storage_buffer_offset_alignment());
+		}
+
+		if usage.uniform_buffer() {
+			memory_requirements = memory_requirements.align_to(limits.min_uniform_buffer_offset_alignment());
+		}
+
+		let slot = allocator.allocate(memory_requirements)?;
+		unsafe {
+			inner.bind(slot).map_err(|(_, e)| e.into())
+		}
+	}
+
 	pub fn memory_slot(&self) -> &S {
 		&self.slot
 	}
@@ -66,6 +101,20 @@ unsafe impl<S: Slot> Buffer for Bound<S> {
 	// ...
 }
 
+unsafe impl<S: Slot> buffer::BufferProperties for Bound<S> {
+	fn size(&self) -> u64 {
+		self.inner.len()
+	}
+
+	fn usage(&self) -> Usages {
+		self.inner.usage()
+	}
+
+	fn is_concurrent(&self) -> bool {
+		self.inner.is_concurrent()
+	}
+}
+
 impl<S: Slot> DeviceOwned for Bound<S> {
 	fn device(&self) -> &Arc<Device> {
 		self.inner.device()
@@ -16,6 +16,35 @@ pub unsafe trait IndexBuffer<T: Topology>: Buffer {
 	fn index_per_item(&self) -> u32;
 }
 
+mod sealed {
+	pub trait Sealed {}
+
+	impl Sealed for u8 {}
+	impl Sealed for u16 {}
+	impl Sealed for u32 {}
+}
+
+/// Integer type that can be used as vertex index.
+///
+/// This trait is sealed and only implemented by `u16` and `u32`,
+/// and by `u8` which requires the `IndexTypeUint8` extended feature (`VK_EXT_index_type_uint8`).
+pub unsafe trait IndexInteger: sealed::Sealed + Copy {
+	/// Vulkan index type.
+	const TYPE: IndexType;
+}
+
+unsafe impl IndexInteger for u8 {
+	const TYPE: IndexType = IndexType::UINT8_EXT;
+}
+
+unsafe impl IndexInteger for u16 {
+	const TYPE: IndexType = IndexType::UINT16;
+}
+
+unsafe impl IndexInteger for u32 {
+	const TYPE: IndexType = IndexType::UINT32;
+}
+
 /// Buffer item type that can be used as index in an index buffer.
 pub unsafe trait Index<T: Topology> {
 	/// Vulkan index type.
@@ -25,22 +54,46 @@ pub unsafe trait Index<T: Topology> {
 	const COUNT: u32;
 }
 
-unsafe impl Index<topology::PointList> for u8 {
-	const TYPE: IndexType = IndexType::UINT8_EXT;
+macro_rules! indexes {
+	($($topology:ident: $count:literal),*) => {
+		$(
+			unsafe impl<I: IndexInteger> Index<topology::$topology> for [I; $count] {
+				const TYPE: IndexType = I::TYPE;
 
-	const COUNT: u32 = 1;
+				const COUNT: u32 = $count;
+			}
+		)*
+	};
 }
 
-unsafe impl Index<topology::PointList> for u16 {
-	const TYPE: IndexType = IndexType::UINT16;
+macro_rules! single_indexes {
+	($($topology:ident),*) => {
+		$(
+			unsafe impl<I: IndexInteger> Index<topology::$topology> for I {
+				const TYPE: IndexType = I::TYPE;
 
-	const COUNT: u32 = 1;
+				const COUNT: u32 = 1;
+			}
+		)*
+	};
 }
 
-unsafe impl Index<topology::PointList> for u32 {
-	const TYPE: IndexType = IndexType::UINT32;
+// List items are whole primitives.
+indexes! {
+	LineList: 2,
+	TriangleList: 3,
+	LineListWithAdjacency: 4,
+	TriangleListWithAdjacency: 6
+}
 
-	const COUNT: u32 = 1;
+single_indexes! {
+	PointList,
+	LineStrip,
+	TriangleStrip,
+	TriangleFan,
+	LineStripWithAdjacency,
+	TriangleStripWithAdjacency,
+	PatchList
 }
 
 unsafe impl<T: Topology, B: TypedBuffer> IndexBuffer<T> for B where B::Item: Index<T> {
@@ -51,4 +104,4 @@ unsafe impl<T: Topology, B: TypedBuffer> IndexBuffer<T> for B where B::Item: Ind
 	fn index_per_item(&self) -> u32 {
 		B::Item::COUNT
 	}
-}
\ No newline at end of file
+}
@@ -7,6 +7,7 @@ mod unbound;
 mod bound;
 mod typed;
 mod index;
+mod view;
 pub mod vec;
 
 pub use usage::*;
@@ -14,6 +15,7 @@ pub use unbound::*;
 pub use bound::*;
 pub use typed::*;
 pub use index::*;
+pub use view::*;
 pub use vec::Vec;
 
 /// Buffer.
@@ -29,6 +31,32 @@ unsafe impl<B: Buffer> Buffer for Proxy<B> {
 	// ...
 }
 
+/// Buffer with known size and usage.
+pub unsafe trait BufferProperties: Buffer {
+	/// Size of the buffer in bytes.
+	fn size(&self) -> u64;
+
+	/// Usages the buffer was created with.
+	fn usage(&self) -> Usages;
+
+	/// Checks if the buffer is shared concurrently by several queue families.
+	fn is_concurrent(&self) -> bool;
+}
+
+unsafe impl<B: std::ops::Deref> BufferProperties for B where B::Target: BufferProperties {
+	fn size(&self) -> u64 {
+		self.deref().size()
+	}
+
+	fn usage(&self) -> Usages {
+		self.deref().usage()
+	}
+
+	fn is_concurrent(&self) -> bool {
+		self.deref().is_concurrent()
+	}
+}
+
 /// Typed buffer.
 pub unsafe trait TypedBuffer: Buffer {
 	/// Buffer item type.
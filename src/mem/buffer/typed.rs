@@ -10,6 +10,7 @@ use ash::{
 use crate::{
 	Device,
 	DeviceOwned,
+	sync,
 	mem::{
 		Slot,
 		HostVisible,
@@ -19,6 +20,10 @@ use crate::{
 		TypedBuffer
 	}
 };
+use super::{
+	Usages,
+	vec
+};
 
 /// Typed buffer.
 pub struct Typed<T> {
@@ -29,8 +34,26 @@ pub struct Typed<T> {
 }
 
 impl<T> Typed<T> {
+	/// Size of an item in bytes.
+	///
+	/// Fails to compile for zero-sized types, which cannot be stored in buffers.
+	const ITEM_SIZE: u64 = {
+		assert!(std::mem::size_of::<T>() != 0, "zero-sized types cannot be stored in buffers");
+		std::mem::size_of::<T>() as u64
+	};
+
+	/// Create a buffer of `len` items, bound to memory allocated by the given allocator.
+	///
+	/// Returns [`vec::Error::SizeOverflow`] if the byte size of the buffer does not fit in a `u64`.
+	pub fn new<A: Allocator, U: Into<Usages>, Q: Into<sync::SharingQueues>>(allocator: &A, len: u64, usage: U, sharing_queues: Q) -> Result<Self, vec::Error> where A::Slot: Send {
+		let size = len.checked_mul(Self::ITEM_SIZE).ok_or(vec::Error::SizeOverflow)?;
+		let align = std::mem::align_of::<T>() as u64;
+		let bound = buffer::Bound::allocate_aligned(allocator, size, usage.into(), sharing_queues.into(), align)?;
+		Ok(unsafe { bound.into_typed() })
+	}
+
 	pub(crate) unsafe fn from_raw_parts(inner: buffer::Unbound, slot: Box<dyn Send + Slot>) -> Self {
-		let len = inner.len() / std::mem::size_of::<T>() as u64;
+		let len = inner.len() / Self::ITEM_SIZE;
 		Self {
 			inner,
 			slot,
@@ -49,6 +72,11 @@ impl<T> Typed<T> {
 	pub fn len(&self) -> u64 {
 		self.len
 	}
+
+	/// Returns the size of the buffer in bytes.
+	pub fn byte_size(&self) -> u64 {
+		self.inner.len()
+	}
 }
 
 unsafe impl<T> crate::Resource for Typed<T> {
@@ -63,6 +91,20 @@ unsafe impl<T> Buffer for Typed<T> {
 	// ...
 }
 
+unsafe impl<T> buffer::BufferProperties for Typed<T> {
+	fn size(&self) -> u64 {
+		self.inner.len()
+	}
+
+	fn usage(&self) -> Usages {
+		self.inner.usage()
+	}
+
+	fn is_concurrent(&self) -> bool {
+		self.inner.is_concurrent()
+	}
+}
+
 unsafe impl<T> TypedBuffer for Typed<T> {
 	type Item = T;
 }
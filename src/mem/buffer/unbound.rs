@@ -1,5 +1,6 @@
 use std::{
 	sync::Arc,
+	ffi::c_void,
 	fmt
 };
 use ash::{
@@ -13,6 +14,8 @@ use crate::{
 	OomError,
 	mem::{
 		MemoryRequirements,
+		DedicatedResource,
+		ExternalMemoryHandleType,
 		Slot
 	}
 };
@@ -92,23 +95,43 @@ impl From<vk::Result> for BindError {
 	}
 }
 
+/// Buffer without bound memory.
+///
+/// It does not implement `Buffer` since it cannot be used by any command before memory is bound with `bind`.
 pub struct Unbound {
 	handle: vk::Buffer,
 	device: Arc<Device>,
 	size: u64,
-	usage: Usages
+	usage: Usages,
+	concurrent: bool
 }
 
 impl Unbound {
 	/// Create a raw, uninitialized buffer of the given size.
 	pub fn new<U: Into<Usages>, S: Into<sync::SharingQueues>>(device: &Arc<Device>, size: u64, usage: U, sharing_queues: S) -> Result<Self, CreationError> {
-		let usage = usage.into();
+		Self::new_with(device, size, usage.into(), sharing_queues.into(), None)
+	}
+
+	/// Create a raw, uninitialized buffer that can be bound to external memory of the given handle type.
+	pub fn new_external<U: Into<Usages>, S: Into<sync::SharingQueues>>(device: &Arc<Device>, size: u64, usage: U, sharing_queues: S, handle_type: ExternalMemoryHandleType) -> Result<Self, CreationError> {
+		Self::new_with(device, size, usage.into(), sharing_queues.into(), Some(handle_type))
+	}
+
+	fn new_with(device: &Arc<Device>, size: u64, usage: Usages, sharing_queues: sync::SharingQueues, external: Option<ExternalMemoryHandleType>) -> Result<Self, CreationError> {
 		assert!(!usage.is_empty());
 
-		let sharing_queues = sharing_queues.into();
 		let (sh_mode, sh_count, sh_indices) = sharing_queues.as_vulkan();
 
+		let external_infos = external.map(|handle_type| vk::ExternalMemoryBufferCreateInfo {
+			handle_types: handle_type.into_vulkan(),
+			..Default::default()
+		});
+
 		let infos = vk::BufferCreateInfo {
+			p_next: match &external_infos {
+				Some(external_infos) => external_infos as *const vk::ExternalMemoryBufferCreateInfo as *const c_void,
+				None => std::ptr::null()
+			},
 			size,
 			usage: usage.into_vulkan(),
 			sharing_mode: sh_mode,
@@ -125,7 +148,8 @@ impl Unbound {
 			handle,
 			device: device.clone(),
 			size,
-			usage
+			usage,
+			concurrent: sharing_queues.is_concurrent()
 		})
 	}
 
@@ -138,11 +162,43 @@ impl Unbound {
 		self.size
 	}
 
+	#[inline]
+	pub fn usage(&self) -> Usages {
+		self.usage
+	}
+
+	/// Checks if the buffer is shared concurrently by several queue families.
+	#[inline]
+	pub fn is_concurrent(&self) -> bool {
+		self.concurrent
+	}
+
+	/// Memory requirements of the buffer.
+	///
+	/// If the device supports dedicated allocations,
+	/// the requirements also tell if the buffer prefers or requires one.
 	#[inline]
 	pub fn memory_requirements(&self) -> MemoryRequirements {
 		unsafe {
+			if self.device.supports_dedicated_allocation() {
+				let info = vk::BufferMemoryRequirementsInfo2 {
+					buffer: self.handle,
+					..Default::default()
+				};
+
+				let mut dedicated = vk::MemoryDedicatedRequirements::default();
+				let mut mr = vk::MemoryRequirements2 {
+					p_next: &mut dedicated as *mut vk::MemoryDedicatedRequirements as *mut c_void,
+					..Default::default()
+				};
+
+				if self.device.get_buffer_memory_requirements2(&info, &mut mr) {
+					return MemoryRequirements::from_vulkan(mr.memory_requirements, &dedicated, DedicatedResource::Buffer(self.handle))
+				}
+			}
+
 			let mr = self.device.handle.get_buffer_memory_requirements(self.handle);
-			MemoryRequirements(mr)
+			MemoryRequirements::new(mr)
 		}
 	}
 
@@ -183,6 +239,14 @@ impl Unbound {
 	}
 }
 
+unsafe impl crate::Resource for Unbound {
+	type Handle = vk::Buffer;
+
+	fn handle(&self) -> vk::Buffer {
+		self.handle
+	}
+}
+
 impl DeviceOwned for Unbound {
 	fn device(&self) -> &Arc<Device> {
 		&self.device
@@ -33,7 +33,10 @@ use crate::{
 pub enum Error {
 	BufferCreation(buffer::CreationError),
 	Bind(buffer::BindError),
-	Memory(mem::Error)
+	Memory(mem::Error),
+
+	/// The byte size of the buffer does not fit in a `u64`.
+	SizeOverflow
 }
 
 impl fmt::Display for Error {
@@ -41,7 +44,8 @@ impl fmt::Display for Error {
 		match self {
 			Error::BufferCreation(e) => write!(f, "buffer creation failed: {}", e),
 			Error::Bind(e) => write!(f, "bind failed: {}", e),
-			Error::Memory(e) => write!(f, "memory error: {}", e)
+			Error::Memory(e) => write!(f, "memory error: {}", e),
+			Error::SizeOverflow => write!(f, "buffer size overflow")
 		}
 	}
 }
@@ -51,7 +55,8 @@ impl std::error::Error for Error {
 		match self {
 			Error::BufferCreation(e) => Some(e),
 			Error::Bind(e) => Some(e),
-			Error::Memory(e) => Some(e)
+			Error::Memory(e) => Some(e),
+			Error::SizeOverflow => None
 		}
 	}
 }
@@ -0,0 +1,216 @@
+use std::{
+	sync::Arc,
+	fmt
+};
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use crate::{
+	Device,
+	DeviceOwned,
+	OomError,
+	Format,
+	format,
+	mem::{
+		Buffer,
+		buffer::BufferProperties
+	}
+};
+
+/// Kind of texel buffer a view is created for.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TexelBufferType {
+	/// Read-only texel buffer, requires the `UniformTexelBuffer` buffer usage.
+	Uniform,
+
+	/// Read-write texel buffer, requires the `StorageTexelBuffer` buffer usage.
+	Storage
+}
+
+impl TexelBufferType {
+	/// Format feature required to view a buffer as this kind of texel buffer.
+	#[inline]
+	pub fn required_format_features(&self) -> format::Features {
+		match self {
+			Self::Uniform => format::Features::UNIFORM_TEXEL_BUFFER,
+			Self::Storage => format::Features::STORAGE_TEXEL_BUFFER
+		}
+	}
+
+	/// Checks that the given buffer usages allow this kind of texel buffer.
+	#[inline]
+	pub fn is_allowed_by(&self, usage: super::Usages) -> bool {
+		match self {
+			Self::Uniform => usage.uniform_texel_buffer(),
+			Self::Storage => usage.storage_texel_buffer()
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum ViewCreationError {
+	/// The format does not support the required texel buffer feature.
+	UnsupportedFormat(Format),
+
+	/// The buffer was not created with the usage required by the texel buffer type.
+	MissingUsage(TexelBufferType),
+
+	/// The viewed range is not included in the buffer.
+	OutOfBounds,
+
+	/// The offset is not a multiple of `minTexelBufferOffsetAlignment`.
+	MisalignedOffset,
+
+	/// The range holds more than `maxTexelBufferElements` texels.
+	TooManyElements,
+
+	OutOfMemory(OomError)
+}
+
+impl fmt::Display for ViewCreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::UnsupportedFormat(format) => write!(f, "format {:?} is not supported for this texel buffer", format),
+			Self::MissingUsage(ty) => write!(f, "buffer usage does not allow {:?} texel buffers", ty),
+			Self::OutOfBounds => write!(f, "texel buffer range out of bounds"),
+			Self::MisalignedOffset => write!(f, "misaligned texel buffer offset"),
+			Self::TooManyElements => write!(f, "too many texel buffer elements"),
+			Self::OutOfMemory(e) => e.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for ViewCreationError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<vk::Result> for ViewCreationError {
+	fn from(r: vk::Result) -> ViewCreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => ViewCreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => ViewCreationError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+/// Buffer view, used to access a buffer as a texel buffer.
+///
+/// The view keeps its buffer alive.
+pub struct View<B: Buffer + DeviceOwned> {
+	buffer: Arc<B>,
+	handle: vk::BufferView,
+	ty: TexelBufferType,
+	format: Format
+}
+
+impl<B: Buffer + DeviceOwned> View<B> {
+	/// Create a view of `range` bytes of the given buffer, starting at `offset`.
+	///
+	/// If `range` is `None`, the view extends to the end of the buffer.
+	/// The buffer must have been created with the usage corresponding to `ty`.
+	pub fn new(buffer: Arc<B>, ty: TexelBufferType, format: Format, offset: u64, range: Option<u64>) -> Result<View<B>, ViewCreationError> where B: BufferProperties {
+		if !ty.is_allowed_by(buffer.usage()) {
+			return Err(ViewCreationError::MissingUsage(ty))
+		}
+
+		let size = buffer.size();
+		if offset >= size {
+			return Err(ViewCreationError::OutOfBounds)
+		}
+
+		let effective_range = match range {
+			Some(range) => {
+				if range == 0 || range > size - offset {
+					return Err(ViewCreationError::OutOfBounds)
+				}
+
+				range
+			},
+			None => size - offset
+		};
+
+		let physical_device = buffer.device().physical_device();
+		if !physical_device.format_properties(format).buffer_features().contains(ty.required_format_features()) {
+			return Err(ViewCreationError::UnsupportedFormat(format))
+		}
+
+		let limits = physical_device.limits();
+		if offset % limits.min_texel_buffer_offset_alignment() != 0 {
+			return Err(ViewCreationError::MisalignedOffset)
+		}
+
+		if let Some(texel_size) = format.size() {
+			if effective_range / texel_size as u64 > limits.max_texel_buffer_elements() as u64 {
+				return Err(ViewCreationError::TooManyElements)
+			}
+		}
+
+		let infos = vk::BufferViewCreateInfo {
+			buffer: buffer.handle(),
+			format: format.into_vulkan(),
+			offset,
+			range: range.unwrap_or(vk::WHOLE_SIZE),
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			buffer.device().handle().create_buffer_view(&infos, None)?
+		};
+
+		Ok(View {
+			buffer,
+			handle,
+			ty,
+			format
+		})
+	}
+
+	#[inline]
+	pub fn handle(&self) -> vk::BufferView {
+		self.handle
+	}
+
+	#[inline]
+	pub fn buffer(&self) -> &Arc<B> {
+		&self.buffer
+	}
+
+	#[inline]
+	pub fn ty(&self) -> TexelBufferType {
+		self.ty
+	}
+
+	#[inline]
+	pub fn format(&self) -> Format {
+		self.format
+	}
+}
+
+unsafe impl<B: Buffer + DeviceOwned> crate::Resource for View<B> {
+	type Handle = vk::BufferView;
+
+	fn handle(&self) -> vk::BufferView {
+		self.handle
+	}
+}
+
+impl<B: Buffer + DeviceOwned> DeviceOwned for View<B> {
+	fn device(&self) -> &Arc<Device> {
+		self.buffer.device()
+	}
+}
+
+impl<B: Buffer + DeviceOwned> Drop for View<B> {
+	fn drop(&mut self) {
+		unsafe {
+			self.buffer.device().handle().destroy_buffer_view(self.handle, None)
+		}
+	}
+}
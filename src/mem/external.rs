@@ -0,0 +1,97 @@
+use std::fmt;
+use ash::vk;
+use crate::{
+	OomError,
+	device::{
+		self,
+		Extension,
+		MissingExtensionError
+	}
+};
+
+/// Type of external memory handle.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ExternalMemoryHandleType {
+	/// POSIX file descriptor only meaningful to Vulkan implementations compatible with this one.
+	///
+	/// Requires the `VK_KHR_external_memory_fd` device extension.
+	OpaqueFd
+}
+
+impl ExternalMemoryHandleType {
+	/// Device extension required to use this handle type.
+	#[inline]
+	pub fn required_extension(&self) -> Extension {
+		match self {
+			Self::OpaqueFd => Extension::KhrExternalMemoryFd
+		}
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ExternalMemoryHandleTypeFlags {
+		match self {
+			Self::OpaqueFd => vk::ExternalMemoryHandleTypeFlags::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD
+		}
+	}
+}
+
+/// External memory export or import error.
+#[derive(Debug)]
+pub enum ExternalMemoryError {
+	MissingExtension(Extension),
+	OutOfMemory(OomError),
+	InvalidExternalHandle,
+	TooManyObjects,
+
+	/// The memory was not allocated to be exported with the requested handle type.
+	NotExportable
+}
+
+impl fmt::Display for ExternalMemoryError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingExtension(ext) => write!(f, "missing device extension `{}`", ext),
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::InvalidExternalHandle => write!(f, "invalid external handle"),
+			Self::TooManyObjects => write!(f, "too many objects"),
+			Self::NotExportable => write!(f, "memory is not exportable with this handle type")
+		}
+	}
+}
+
+impl std::error::Error for ExternalMemoryError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<MissingExtensionError> for ExternalMemoryError {
+	fn from(MissingExtensionError(ext): MissingExtensionError) -> ExternalMemoryError {
+		ExternalMemoryError::MissingExtension(ext)
+	}
+}
+
+impl From<device::AllocationError> for ExternalMemoryError {
+	fn from(e: device::AllocationError) -> ExternalMemoryError {
+		match e {
+			device::AllocationError::OutOfMemory(oom) => ExternalMemoryError::OutOfMemory(oom),
+			device::AllocationError::InvalidExternalHandle => ExternalMemoryError::InvalidExternalHandle,
+			device::AllocationError::InvalidOpaqueCaptureAddress => unreachable!()
+		}
+	}
+}
+
+impl From<vk::Result> for ExternalMemoryError {
+	fn from(r: vk::Result) -> ExternalMemoryError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => ExternalMemoryError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => ExternalMemoryError::OutOfMemory(OomError::Device),
+			vk::Result::ERROR_INVALID_EXTERNAL_HANDLE => ExternalMemoryError::InvalidExternalHandle,
+			vk::Result::ERROR_TOO_MANY_OBJECTS => ExternalMemoryError::TooManyObjects,
+			_ => unreachable!()
+		}
+	}
+}
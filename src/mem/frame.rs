@@ -0,0 +1,306 @@
+use std::{
+	sync::Arc,
+	ffi::c_void,
+	fmt
+};
+use ash::vk;
+use crate::{
+	Device,
+	DeviceOwned,
+	Resource,
+	device,
+	instance::physical_device::MemoryProperties,
+	sync::SharingQueues
+};
+use super::{
+	Slot,
+	buffer::{
+		Usages,
+		Unbound,
+		Bound,
+		vec::Error as BufferError
+	}
+};
+
+/// Frame allocation error.
+#[derive(Debug)]
+pub enum AllocationError {
+	/// The frame block is full and the allocator does not grow.
+	OutOfFrameMemory,
+
+	/// A new block could not be created.
+	Grow(BufferError)
+}
+
+impl fmt::Display for AllocationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfFrameMemory => write!(f, "out of frame memory"),
+			Self::Grow(e) => write!(f, "unable to grow frame memory: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for AllocationError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::Grow(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<BufferError> for AllocationError {
+	fn from(e: BufferError) -> Self {
+		Self::Grow(e)
+	}
+}
+
+/// What to do when the block of a frame is full.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Overflow {
+	/// Allocate a new block for the frame.
+	///
+	/// Blocks are kept across resets, so once the biggest frame has been allocated,
+	/// no more memory is allocated.
+	Grow,
+
+	/// Return `AllocationError::OutOfFrameMemory`.
+	Fail
+}
+
+/// Slice of frame memory.
+///
+/// The slice is valid until its frame is reset.
+#[derive(Clone, Copy)]
+pub struct FrameSlice {
+	buffer: vk::Buffer,
+	offset: u64,
+	size: u64,
+	ptr: *mut c_void
+}
+
+unsafe impl Send for FrameSlice {}
+unsafe impl Sync for FrameSlice {}
+
+impl FrameSlice {
+	/// Buffer containing the slice.
+	#[inline]
+	pub fn buffer(&self) -> vk::Buffer {
+		self.buffer
+	}
+
+	/// Offset of the slice in the buffer.
+	#[inline]
+	pub fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	#[inline]
+	pub fn size(&self) -> u64 {
+		self.size
+	}
+
+	/// Mapped pointer to the slice.
+	#[inline]
+	pub fn ptr(&self) -> *mut c_void {
+		self.ptr
+	}
+}
+
+/// Block of a frame.
+struct Block {
+	buffer: Bound<device::MappedMemory>,
+	size: u64,
+
+	/// Allocated bytes.
+	used: u64
+}
+
+impl Block {
+	fn new(device: &Arc<Device>, size: u64, usage: Usages, sharing_queues: &SharingQueues) -> Result<Block, BufferError> {
+		let buffer = Unbound::new(device, size, usage, sharing_queues.clone())?;
+		let memory_requirements = buffer.memory_requirements();
+		let memory_type = memory_requirements.find_memory_type(
+			device.physical_device(),
+			MemoryProperties::HOST_VISIBLE | MemoryProperties::HOST_COHERENT,
+			MemoryProperties::none()
+		).ok_or(super::Error::NoSuitableMemoryType)?;
+
+		let memory = device.allocate_memory(memory_type, memory_requirements.size()).map_err(super::Error::from)?;
+		let memory = memory.map(0, None).map_err(super::Error::from)?;
+
+		let buffer = unsafe {
+			buffer.bind(memory).map_err(|(_, e)| e)?
+		};
+
+		Ok(Block {
+			buffer,
+			size,
+			used: 0
+		})
+	}
+
+	fn allocate(&mut self, size: u64, align: u64) -> Option<FrameSlice> {
+		let offset = (self.used + align - 1) / align * align;
+		if offset + size <= self.size {
+			self.used = offset + size;
+			let slot = self.buffer.memory_slot();
+			Some(FrameSlice {
+				buffer: self.buffer.handle(),
+				offset,
+				size,
+				ptr: unsafe { (slot.ptr() as *mut u8).add(offset as usize) as *mut c_void }
+			})
+		} else {
+			None
+		}
+	}
+}
+
+/// Linear allocator for transient per-frame data.
+///
+/// Owns a host visible, host coherent and persistently mapped buffer for each frame in flight.
+/// Memory is allocated linearly in the buffer of the current frame,
+/// and reclaimed at once when the frame is reset.
+///
+/// ```ignore
+/// // Once the fence of the frame is signaled.
+/// frame_allocator.reset(frame_index);
+/// let slice = frame_allocator.allocate(size_of::<Uniforms>() as u64, 16)?;
+/// ```
+pub struct FrameAllocator {
+	device: Arc<Device>,
+	usage: Usages,
+	sharing_queues: SharingQueues,
+	block_size: u64,
+	overflow: Overflow,
+
+	/// Minimum alignment of the allocations, required by the buffer usage.
+	min_alignment: u64,
+
+	/// Blocks of each frame.
+	frames: Vec<Vec<Block>>,
+
+	/// Current frame index.
+	current: usize
+}
+
+impl FrameAllocator {
+	/// Create a new frame allocator for `frame_count` frames in flight,
+	/// with buffers of `block_size` bytes for each frame.
+	pub fn new<U: Into<Usages>, S: Into<SharingQueues>>(device: &Arc<Device>, frame_count: usize, block_size: u64, usage: U, sharing_queues: S, overflow: Overflow) -> Result<FrameAllocator, BufferError> {
+		assert!(frame_count > 0);
+		let usage = usage.into();
+		let sharing_queues = sharing_queues.into();
+
+		let limits = device.physical_device().limits();
+		let mut min_alignment = 1;
+		if usage.uniform_texel_buffer() || usage.storage_texel_buffer() {
+			min_alignment = std::cmp::max(min_alignment, limits.min_texel_buffer_offset_alignment());
+		}
+
+		if usage.storage_buffer() {
+			min_alignment = std::cmp::max(min_alignment, limits.min_storage_buffer_offset_alignment());
+		}
+
+		if usage.uniform_buffer() {
+			min_alignment = std::cmp::max(min_alignment, limits.min_uniform_buffer_offset_alignment());
+		}
+
+		let mut frames = Vec::with_capacity(frame_count);
+		for _ in 0..frame_count {
+			frames.push(vec![Block::new(device, block_size, usage, &sharing_queues)?])
+		}
+
+		Ok(FrameAllocator {
+			device: device.clone(),
+			usage,
+			sharing_queues,
+			block_size,
+			overflow,
+			min_alignment,
+			frames,
+			current: 0
+		})
+	}
+
+	/// Number of frames in flight.
+	#[inline]
+	pub fn frame_count(&self) -> usize {
+		self.frames.len()
+	}
+
+	/// Index of the frame in which memory is allocated.
+	#[inline]
+	pub fn current_frame(&self) -> usize {
+		self.current
+	}
+
+	/// Reclaim all the memory allocated for the given frame, and make it the current frame.
+	///
+	/// The previous slices of the frame must not be in use by the device anymore,
+	/// typically because the fence of the frame has been signaled.
+	pub fn reset(&mut self, frame_index: usize) {
+		for block in &mut self.frames[frame_index] {
+			block.used = 0
+		}
+
+		self.current = frame_index
+	}
+
+	/// Allocate a slice of memory for the current frame.
+	///
+	/// The actual alignment is at least the offset alignment required by the buffer usage
+	/// (such as `minUniformBufferOffsetAlignment`).
+	pub fn allocate(&mut self, size: u64, align: u64) -> Result<FrameSlice, AllocationError> {
+		assert!(align.is_power_of_two());
+		let align = std::cmp::max(align, self.min_alignment);
+
+		let blocks = &mut self.frames[self.current];
+		for block in blocks.iter_mut() {
+			if let Some(slice) = block.allocate(size, align) {
+				return Ok(slice)
+			}
+		}
+
+		match self.overflow {
+			Overflow::Grow => {
+				let block_size = std::cmp::max(self.block_size, size);
+				let mut block = Block::new(&self.device, block_size, self.usage, &self.sharing_queues)?;
+				let slice = block.allocate(size, align).unwrap();
+				blocks.push(block);
+				Ok(slice)
+			},
+			Overflow::Fail => Err(AllocationError::OutOfFrameMemory)
+		}
+	}
+}
+
+impl DeviceOwned for FrameAllocator {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+unsafe impl Slot for device::MappedMemory {
+	#[inline]
+	fn memory(&self) -> &device::Memory {
+		self.as_memory()
+	}
+
+	#[inline]
+	fn offset(&self) -> u64 {
+		0
+	}
+
+	#[inline]
+	fn size(&self) -> u64 {
+		self.as_memory().size()
+	}
+
+	#[inline]
+	fn ptr(&self) -> Option<*mut c_void> {
+		Some(self.ptr())
+	}
+}
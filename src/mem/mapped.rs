@@ -0,0 +1,345 @@
+use std::{
+	sync::{
+		Arc,
+		atomic::{
+			AtomicBool,
+			Ordering
+		}
+	},
+	ops::{
+		Range,
+		Deref,
+		DerefMut
+	},
+	marker::PhantomData,
+	fmt
+};
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use crate::{
+	Device,
+	DeviceOwned,
+	OomError,
+	device::{
+		self,
+		memory::MapError
+	}
+};
+
+#[derive(Debug)]
+pub enum WriteError {
+	/// The written range exceeds the mapped range.
+	OutOfBounds,
+
+	/// The write offset is not aligned to the written type.
+	Misaligned
+}
+
+impl fmt::Display for WriteError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfBounds => write!(f, "write out of the mapped range"),
+			Self::Misaligned => write!(f, "misaligned write")
+		}
+	}
+}
+
+impl std::error::Error for WriteError { }
+
+/// Rounds the given memory range to `atom_size` without exceeding `memory_size`.
+///
+/// This is the range to flush or invalidate, as required for non-coherent memory.
+#[inline]
+fn align_non_coherent_range(range: Range<u64>, atom_size: u64, memory_size: u64) -> Range<u64> {
+	let start = range.start / atom_size * atom_size;
+	let end = std::cmp::min((range.end + atom_size - 1) / atom_size * atom_size, memory_size);
+	start..end
+}
+
+/// Host visible device memory.
+///
+/// Only memory of a host visible type can be wrapped into this type,
+/// so mapping never fails because of the memory type.
+pub struct HostVisibleMemory {
+	memory: device::Memory,
+
+	/// Is the memory type host coherent.
+	coherent: bool,
+
+	/// Is the memory currently mapped.
+	mapped: AtomicBool
+}
+
+impl HostVisibleMemory {
+	/// Wraps the given memory if its type is host visible.
+	#[inline]
+	pub fn try_from(memory: device::Memory) -> Result<HostVisibleMemory, device::Memory> {
+		let memory_type = memory.memory_type();
+		if memory_type.is_host_visible() {
+			let coherent = memory_type.is_host_coherent();
+			Ok(HostVisibleMemory {
+				memory,
+				coherent,
+				mapped: AtomicBool::new(false)
+			})
+		} else {
+			Err(memory)
+		}
+	}
+
+	#[inline]
+	pub fn as_memory(&self) -> &device::Memory {
+		&self.memory
+	}
+
+	#[inline]
+	pub fn into_memory(self) -> device::Memory {
+		self.memory
+	}
+
+	#[inline]
+	pub fn size(&self) -> u64 {
+		self.memory.size()
+	}
+
+	/// Checks if the memory type is host coherent.
+	///
+	/// Writes to non-coherent memory must be flushed to be visible to the device,
+	/// and device writes must be invalidated to be visible to the host.
+	#[inline]
+	pub fn is_coherent(&self) -> bool {
+		self.coherent
+	}
+
+	/// Range of non-coherent memory covering the given range.
+	#[inline]
+	fn atom_range(&self, range: Range<u64>) -> Range<u64> {
+		let atom_size = self.device().physical_device().limits().non_coherent_atom_size();
+		align_non_coherent_range(range, atom_size, self.size())
+	}
+
+	/// Checks if the memory is currently mapped.
+	#[inline]
+	pub fn is_mapped(&self) -> bool {
+		self.mapped.load(Ordering::Acquire)
+	}
+
+	/// Map the given range of the memory to host address space.
+	///
+	/// The memory is unmapped when the returned guard is dropped.
+	/// The memory can only be mapped once at a time:
+	/// mapping already mapped memory returns `MapError::AlreadyMapped`.
+	pub fn map(&self, range: Range<u64>) -> Result<MappedMemory<'_>, MapError> {
+		if range.start >= range.end || range.end > self.size() {
+			return Err(MapError::OutOfBounds)
+		}
+
+		if self.mapped.swap(true, Ordering::AcqRel) {
+			return Err(MapError::AlreadyMapped)
+		}
+
+		// Non-coherent memory is mapped by whole atoms so any flushed range is inside the mapping.
+		let mapped_range = if self.coherent {
+			range.clone()
+		} else {
+			self.atom_range(range.clone())
+		};
+
+		let ptr = unsafe {
+			self.device().handle.map_memory(
+				self.memory.handle(),
+				mapped_range.start,
+				mapped_range.end - mapped_range.start,
+				vk::MemoryMapFlags::empty()
+			)
+		};
+
+		match ptr {
+			Ok(ptr) => Ok(MappedMemory {
+				memory: self,
+				offset: range.start,
+				ptr: unsafe { (ptr as *mut u8).add((range.start - mapped_range.start) as usize) },
+				len: (range.end - range.start) as usize,
+				data: PhantomData
+			}),
+			Err(e) => {
+				self.mapped.store(false, Ordering::Release);
+				Err(e.into())
+			}
+		}
+	}
+}
+
+impl DeviceOwned for HostVisibleMemory {
+	fn device(&self) -> &Arc<Device> {
+		self.memory.device()
+	}
+}
+
+/// Mapped host visible memory range.
+///
+/// Dereferences to the mapped bytes.
+/// The memory is unmapped when dropped.
+pub struct MappedMemory<'a> {
+	memory: &'a HostVisibleMemory,
+
+	/// Offset of the mapped range in the memory.
+	offset: u64,
+
+	ptr: *mut u8,
+	len: usize,
+	data: PhantomData<&'a mut [u8]>
+}
+
+unsafe impl<'a> Send for MappedMemory<'a> {}
+unsafe impl<'a> Sync for MappedMemory<'a> {}
+
+impl<'a> MappedMemory<'a> {
+	#[inline]
+	pub fn memory(&self) -> &'a HostVisibleMemory {
+		self.memory
+	}
+
+	/// Offset of the mapped range in the memory.
+	#[inline]
+	pub fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	/// Make the host writes in the given range (relative to the mapped range) visible to the device.
+	///
+	/// The range is extended to `nonCoherentAtomSize` boundaries.
+	/// Does nothing if the memory is host coherent.
+	///
+	/// ## Panics
+	///
+	/// Panics if the range is not inside of the mapped range.
+	pub fn flush(&self, range: Range<usize>) -> Result<(), OomError> {
+		if let Some(range) = self.vulkan_range(range) {
+			unsafe {
+				self.memory.device().handle.flush_mapped_memory_ranges(&[range])?
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Make the device writes in the given range (relative to the mapped range) visible to the host.
+	///
+	/// The range is extended to `nonCoherentAtomSize` boundaries.
+	/// Does nothing if the memory is host coherent.
+	///
+	/// ## Panics
+	///
+	/// Panics if the range is not inside of the mapped range.
+	pub fn invalidate(&self, range: Range<usize>) -> Result<(), OomError> {
+		if let Some(range) = self.vulkan_range(range) {
+			unsafe {
+				self.memory.device().handle.invalidate_mapped_memory_ranges(&[range])?
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Memory range to flush or invalidate, if the memory is not coherent.
+	fn vulkan_range(&self, range: Range<usize>) -> Option<vk::MappedMemoryRange> {
+		assert!(range.start <= range.end && range.end <= self.len, "range out of the mapped range");
+
+		if self.memory.coherent || range.start == range.end {
+			None
+		} else {
+			let range = self.memory.atom_range((self.offset + range.start as u64)..(self.offset + range.end as u64));
+			Some(vk::MappedMemoryRange {
+				memory: self.memory.memory.handle(),
+				offset: range.start,
+				size: range.end - range.start,
+				..Default::default()
+			})
+		}
+	}
+
+	/// Copy the given slice at the given offset (in bytes) of the mapped range.
+	///
+	/// The offset must be aligned to `T`
+	/// and the written bytes must be inside of the mapped range.
+	pub fn write_slice<T: Copy>(&mut self, offset: usize, data: &[T]) -> Result<(), WriteError> {
+		let size = std::mem::size_of_val(data);
+		match offset.checked_add(size) {
+			Some(end) if end <= self.len => (),
+			_ => return Err(WriteError::OutOfBounds)
+		}
+
+		if (self.ptr as usize + offset) % std::mem::align_of::<T>() != 0 {
+			return Err(WriteError::Misaligned)
+		}
+
+		unsafe {
+			std::ptr::copy_nonoverlapping(data.as_ptr() as *const u8, self.ptr.add(offset), size)
+		}
+
+		Ok(())
+	}
+}
+
+impl<'a> Deref for MappedMemory<'a> {
+	type Target = [u8];
+
+	#[inline]
+	fn deref(&self) -> &[u8] {
+		unsafe {
+			std::slice::from_raw_parts(self.ptr, self.len)
+		}
+	}
+}
+
+impl<'a> DerefMut for MappedMemory<'a> {
+	#[inline]
+	fn deref_mut(&mut self) -> &mut [u8] {
+		unsafe {
+			std::slice::from_raw_parts_mut(self.ptr, self.len)
+		}
+	}
+}
+
+impl<'a> Drop for MappedMemory<'a> {
+	fn drop(&mut self) {
+		unsafe {
+			self.memory.device().handle.unmap_memory(self.memory.memory.handle())
+		}
+
+		self.memory.mapped.store(false, Ordering::Release)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::align_non_coherent_range;
+
+	#[test]
+	fn range_at_start() {
+		assert_eq!(align_non_coherent_range(0..10, 64, 1024), 0..64);
+		assert_eq!(align_non_coherent_range(3..64, 64, 1024), 0..64);
+	}
+
+	#[test]
+	fn range_at_end() {
+		assert_eq!(align_non_coherent_range(1000..1024, 64, 1024), 960..1024);
+		assert_eq!(align_non_coherent_range(1020..1024, 256, 1024), 768..1024);
+	}
+
+	#[test]
+	fn atom_aligned_range() {
+		assert_eq!(align_non_coherent_range(128..256, 64, 1024), 128..256);
+		assert_eq!(align_non_coherent_range(0..1024, 64, 1024), 0..1024);
+	}
+
+	#[test]
+	fn clamped_to_memory_size() {
+		// The memory size is not a multiple of the atom size.
+		assert_eq!(align_non_coherent_range(900..1000, 64, 1000), 896..1000);
+		assert_eq!(align_non_coherent_range(990..1000, 256, 1000), 768..1000);
+	}
+}
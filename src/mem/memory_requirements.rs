@@ -1,20 +1,96 @@
 use ash::vk;
 use crate::instance::{
 	PhysicalDevice,
-	physical_device::MemoryType
+	physical_device::{
+		MemoryType,
+		MemoryProperties
+	}
 };
 
-pub struct MemoryRequirements(pub(crate) vk::MemoryRequirements);
+/// Resource for which a dedicated allocation can be made.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum DedicatedResource {
+	Buffer(vk::Buffer),
+	Image(vk::Image)
+}
+
+#[derive(Clone, Copy)]
+pub struct MemoryRequirements {
+	inner: vk::MemoryRequirements,
+	prefers_dedicated: bool,
+	requires_dedicated: bool,
+
+	/// Resource the requirements are for, if it may benefit from a dedicated allocation.
+	dedicated_resource: Option<DedicatedResource>
+}
 
 impl MemoryRequirements {
+	#[inline]
+	pub(crate) fn new(inner: vk::MemoryRequirements) -> MemoryRequirements {
+		MemoryRequirements {
+			inner,
+			prefers_dedicated: false,
+			requires_dedicated: false,
+			dedicated_resource: None
+		}
+	}
+
+	#[inline]
+	pub(crate) fn from_vulkan(inner: vk::MemoryRequirements, dedicated: &vk::MemoryDedicatedRequirements, resource: DedicatedResource) -> MemoryRequirements {
+		let prefers_dedicated = dedicated.prefers_dedicated_allocation == vk::TRUE;
+		let requires_dedicated = dedicated.requires_dedicated_allocation == vk::TRUE;
+
+		MemoryRequirements {
+			inner,
+			prefers_dedicated: prefers_dedicated || requires_dedicated,
+			requires_dedicated,
+			dedicated_resource: if prefers_dedicated || requires_dedicated {
+				Some(resource)
+			} else {
+				None
+			}
+		}
+	}
+
+	#[inline]
+	fn with_inner(&self, inner: vk::MemoryRequirements) -> MemoryRequirements {
+		MemoryRequirements {
+			inner,
+			..*self
+		}
+	}
+
 	#[inline]
 	pub fn size(&self) -> u64 {
-		self.0.size
+		self.inner.size
 	}
 
 	#[inline]
 	pub fn alignment(&self) -> u64 {
-		self.0.alignment
+		self.inner.alignment
+	}
+
+	/// Checks if the resource would perform better with its own allocation.
+	///
+	/// Always `false` if the device does not support dedicated allocations
+	/// (Vulkan 1.1 or the `VK_KHR_dedicated_allocation` extension).
+	#[inline]
+	pub fn prefers_dedicated(&self) -> bool {
+		self.prefers_dedicated
+	}
+
+	/// Checks if the resource must be bound to its own allocation.
+	///
+	/// Always `false` if the device does not support dedicated allocations
+	/// (Vulkan 1.1 or the `VK_KHR_dedicated_allocation` extension).
+	#[inline]
+	pub fn requires_dedicated(&self) -> bool {
+		self.requires_dedicated
+	}
+
+	#[inline]
+	pub(crate) fn dedicated_resource(&self) -> Option<DedicatedResource> {
+		self.dedicated_resource
 	}
 
 	/// Creates new memory requirements aligned to `self.alignment()` but also on `align`.
@@ -30,45 +106,292 @@ impl MemoryRequirements {
 			panic!("alignment value must be a power of 2.")
 		}
 		
-		MemoryRequirements(vk::MemoryRequirements {
+		self.with_inner(vk::MemoryRequirements {
 			size: self.size(),
 			alignment: std::cmp::max(self.alignment(), align), // works because alignemnt values are powers of two.
 			memory_type_bits: self.memory_type_bits()
 		})
 	}
 
+	/// Combine the requirements of two resources sharing the same allocation.
+	///
+	/// Returns the combined requirements and the offset, relative to the
+	/// beginning of the allocation, at which the second resource must be bound.
+	/// The first resource is bound at offset 0.
+	///
+	/// Returns `None` if no memory type is supported by both resources,
+	/// or if any of them requires a dedicated allocation.
+	pub fn union(&self, other: &MemoryRequirements) -> Option<(MemoryRequirements, u64)> {
+		let memory_type_bits = self.memory_type_bits() & other.memory_type_bits();
+		if memory_type_bits == 0 || self.requires_dedicated() || other.requires_dedicated() {
+			return None
+		}
+
+		let offset = (self.size() + other.alignment() - 1) / other.alignment() * other.alignment();
+
+		let combined = MemoryRequirements::new(vk::MemoryRequirements {
+			size: offset + other.size(),
+			alignment: std::cmp::max(self.alignment(), other.alignment()), // works because alignemnt values are powers of two.
+			memory_type_bits
+		});
+
+		Some((combined, offset))
+	}
+
+	/// Combine the requirements of any number of resources sharing the same allocation.
+	///
+	/// Returns the combined requirements and the offset at which each resource must be bound.
+	/// Returns `None` if the list is empty, if no memory type is supported by every resource,
+	/// or if any of them requires a dedicated allocation.
+	pub fn pack(requirements: &[&MemoryRequirements]) -> Option<(MemoryRequirements, Vec<u64>)> {
+		let (first, rest) = requirements.split_first()?;
+		if first.requires_dedicated() {
+			return None
+		}
+
+		let mut combined = MemoryRequirements::new(first.inner);
+		let mut offsets = Vec::with_capacity(requirements.len());
+		offsets.push(0);
+
+		for r in rest {
+			let (new_combined, offset) = combined.union(r)?;
+			combined = new_combined;
+			offsets.push(offset)
+		}
+
+		Some((combined, offsets))
+	}
+
 	#[inline]
 	pub fn memory_type_bits(&self) -> u32 {
-		self.0.memory_type_bits
+		self.inner.memory_type_bits
 	}
 
 	#[inline]
 	pub fn contains_memory_type_index(&self, index: u32) -> bool {
-		self.0.memory_type_bits & (1u32 << index) != 0
+		self.inner.memory_type_bits & (1u32 << index) != 0
 	}
 
 	#[inline]
-	pub fn filter_memory_types<F>(&self, physical_device: PhysicalDevice, f: F) -> MemoryRequirements where F: Fn(MemoryType) -> bool {
-		let mut bits = self.memory_type_bits();
-		let mut index = 0;
+	pub fn filter_memory_types<F>(&self, physical_device: &PhysicalDevice, f: F) -> MemoryRequirements where F: Fn(MemoryType) -> bool {
+		self.filter_memory_properties(physical_device.vulkan_memory_properties(), |index, _| {
+			f(physical_device.memory_type(index).expect("no such memory type"))
+		})
+	}
+
+	/// Restrict the memory types to the ones of the given memory properties
+	/// for which `f` returns `true`.
+	///
+	/// `f` is called with the index and properties of each memory type.
+	fn filter_memory_properties<F>(&self, memory_properties: &vk::PhysicalDeviceMemoryProperties, f: F) -> MemoryRequirements where F: Fn(u32, MemoryProperties) -> bool {
+		let memory_types = &memory_properties.memory_types[..memory_properties.memory_type_count as usize];
 		let mut new_memory_type_bits = 0;
 
-		while bits != 0 {
-			if bits & 1 != 0 {
-				let memory_type = physical_device.memory_type(index).expect("no such memory type");
-				if f(memory_type) {
-					new_memory_type_bits |= 1u32 << index;
-				}
+		for (index, memory_type) in memory_types.iter().enumerate() {
+			let index = index as u32;
+			if self.contains_memory_type_index(index) && f(index, MemoryProperties::from_vulkan(memory_type.property_flags)) {
+				new_memory_type_bits |= 1u32 << index;
 			}
-
-			index += 1;
-			bits >>= 1;
 		}
 
-		MemoryRequirements(vk::MemoryRequirements {
+		self.with_inner(vk::MemoryRequirements {
 			size: self.size(),
 			alignment: self.alignment(),
 			memory_type_bits: new_memory_type_bits
 		})
 	}
-}
\ No newline at end of file
+
+	/// Find the preferred memory type satisfying these requirements.
+	///
+	/// Returns the first memory type having both the `required` and `preferred` properties,
+	/// or if none, the first memory type having the `required` properties.
+	#[inline]
+	pub fn find_memory_type<'a>(&self, physical_device: &'a PhysicalDevice, required: MemoryProperties, preferred: MemoryProperties) -> Option<MemoryType<'a>> {
+		let index = self.find_memory_type_index(physical_device.vulkan_memory_properties(), required, preferred)?;
+		physical_device.memory_type(index)
+	}
+
+	/// Find the index of the preferred memory type satisfying these requirements
+	/// in the given memory properties, as `find_memory_type`.
+	fn find_memory_type_index(&self, memory_properties: &vk::PhysicalDeviceMemoryProperties, required: MemoryProperties, preferred: MemoryProperties) -> Option<u32> {
+		let memory_types = &memory_properties.memory_types[..memory_properties.memory_type_count as usize];
+		let candidates = || memory_types.iter().enumerate().filter_map(move |(index, memory_type)| {
+			let properties = MemoryProperties::from_vulkan(memory_type.property_flags);
+			if self.contains_memory_type_index(index as u32) && properties.contains(required) {
+				Some((index as u32, properties))
+			} else {
+				None
+			}
+		});
+
+		let preferred = required | preferred;
+		candidates().find(|(_, properties)| properties.contains(preferred)).or_else(|| candidates().next()).map(|(index, _)| index)
+	}
+
+	/// Restrict the memory types to the ones suitable for a transient attachment.
+	///
+	/// Lazily allocated memory types are used if any,
+	/// otherwise device local memory types are used if any.
+	pub fn for_transient_attachment(&self, physical_device: &PhysicalDevice) -> MemoryRequirements {
+		self.for_transient_attachment_in(physical_device.vulkan_memory_properties())
+	}
+
+	fn for_transient_attachment_in(&self, memory_properties: &vk::PhysicalDeviceMemoryProperties) -> MemoryRequirements {
+		let lazy = self.filter_memory_properties(memory_properties, |_, properties| properties.contains(MemoryProperties::LAZILY_ALLOCATED));
+		if lazy.memory_type_bits() != 0 {
+			return lazy
+		}
+
+		let local = self.filter_memory_properties(memory_properties, |_, properties| properties.contains(MemoryProperties::DEVICE_LOCAL));
+		if local.memory_type_bits() != 0 {
+			local
+		} else {
+			*self
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Memory properties with the given memory types, all in the first heap.
+	fn memory_properties(memory_types: &[vk::MemoryPropertyFlags]) -> vk::PhysicalDeviceMemoryProperties {
+		let mut properties = vk::PhysicalDeviceMemoryProperties {
+			memory_type_count: memory_types.len() as u32,
+			memory_heap_count: 1,
+			..Default::default()
+		};
+
+		for (i, flags) in memory_types.iter().enumerate() {
+			properties.memory_types[i] = vk::MemoryType {
+				property_flags: *flags,
+				heap_index: 0
+			}
+		}
+
+		properties
+	}
+
+	/// Discrete GPU like memory types.
+	fn discrete_memory_properties() -> vk::PhysicalDeviceMemoryProperties {
+		memory_properties(&[
+			vk::MemoryPropertyFlags::DEVICE_LOCAL,
+			vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+			vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT | vk::MemoryPropertyFlags::HOST_CACHED,
+			vk::MemoryPropertyFlags::DEVICE_LOCAL | vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT
+		])
+	}
+
+	fn requirements(size: u64, alignment: u64, memory_type_bits: u32) -> MemoryRequirements {
+		MemoryRequirements::new(vk::MemoryRequirements {
+			size,
+			alignment,
+			memory_type_bits
+		})
+	}
+
+	#[test]
+	fn union_with_mismatched_alignment() {
+		let a = requirements(100, 4, 0b111);
+		let b = requirements(64, 256, 0b110);
+
+		let (combined, offset) = a.union(&b).unwrap();
+		assert_eq!(offset, 256);
+		assert_eq!(combined.size(), 320);
+		assert_eq!(combined.alignment(), 256);
+		assert_eq!(combined.memory_type_bits(), 0b110);
+	}
+
+	#[test]
+	fn union_with_disjoint_memory_types() {
+		let a = requirements(100, 4, 0b001);
+		let b = requirements(64, 4, 0b110);
+		assert!(a.union(&b).is_none())
+	}
+
+	#[test]
+	fn pack_with_mismatched_alignment() {
+		let a = requirements(10, 1, 0b11);
+		let b = requirements(10, 16, 0b11);
+		let c = requirements(10, 64, 0b10);
+
+		let (combined, offsets) = MemoryRequirements::pack(&[&a, &b, &c]).unwrap();
+		assert_eq!(offsets, vec![0, 16, 64]);
+		assert_eq!(combined.size(), 74);
+		assert_eq!(combined.alignment(), 64);
+		assert_eq!(combined.memory_type_bits(), 0b10);
+	}
+
+	#[test]
+	fn pack_with_disjoint_memory_types() {
+		let a = requirements(10, 1, 0b011);
+		let b = requirements(10, 1, 0b110);
+		let c = requirements(10, 1, 0b001);
+		assert!(MemoryRequirements::pack(&[&a, &b, &c]).is_none());
+		assert!(MemoryRequirements::pack(&[]).is_none())
+	}
+
+	#[test]
+	fn find_preferred_memory_type() {
+		let properties = discrete_memory_properties();
+		let r = requirements(64, 4, 0b1111);
+		assert_eq!(r.find_memory_type_index(&properties, MemoryProperties::HOST_VISIBLE, MemoryProperties::HOST_CACHED), Some(2));
+		assert_eq!(r.find_memory_type_index(&properties, MemoryProperties::HOST_VISIBLE, MemoryProperties::DEVICE_LOCAL), Some(3));
+		assert_eq!(r.find_memory_type_index(&properties, MemoryProperties::DEVICE_LOCAL, MemoryProperties::none()), Some(0));
+	}
+
+	#[test]
+	fn find_required_memory_type_without_preferred() {
+		let properties = discrete_memory_properties();
+		let r = requirements(64, 4, 0b0011);
+		assert_eq!(r.find_memory_type_index(&properties, MemoryProperties::HOST_VISIBLE, MemoryProperties::HOST_CACHED), Some(1));
+	}
+
+	#[test]
+	fn find_no_memory_type() {
+		let properties = discrete_memory_properties();
+		assert_eq!(requirements(64, 4, 0b0001).find_memory_type_index(&properties, MemoryProperties::HOST_VISIBLE, MemoryProperties::none()), None);
+		assert_eq!(requirements(64, 4, 0b1111).find_memory_type_index(&properties, MemoryProperties::LAZILY_ALLOCATED, MemoryProperties::none()), None);
+
+		// Bits beyond the memory type count are ignored.
+		assert_eq!(requirements(64, 4, 0b10000).find_memory_type_index(&properties, MemoryProperties::none(), MemoryProperties::none()), None);
+	}
+
+	#[test]
+	fn filter_memory_types_by_properties() {
+		let properties = discrete_memory_properties();
+		let r = requirements(64, 4, 0b1110);
+		let host_visible = r.filter_memory_properties(&properties, |_, p| p.contains(MemoryProperties::HOST_VISIBLE));
+		assert_eq!(host_visible.memory_type_bits(), 0b1110);
+
+		let device_local = r.filter_memory_properties(&properties, |_, p| p.contains(MemoryProperties::DEVICE_LOCAL));
+		assert_eq!(device_local.memory_type_bits(), 0b1000);
+		assert_eq!(device_local.size(), 64);
+		assert_eq!(device_local.alignment(), 4);
+	}
+
+	#[test]
+	fn transient_attachment_prefers_lazily_allocated() {
+		let properties = memory_properties(&[
+			vk::MemoryPropertyFlags::DEVICE_LOCAL,
+			vk::MemoryPropertyFlags::DEVICE_LOCAL | vk::MemoryPropertyFlags::LAZILY_ALLOCATED,
+			vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT
+		]);
+
+		let r = requirements(64, 4, 0b111);
+		assert_eq!(r.for_transient_attachment_in(&properties).memory_type_bits(), 0b010);
+	}
+
+	#[test]
+	fn transient_attachment_falls_back_to_device_local() {
+		// No lazily allocated memory type.
+		let properties = discrete_memory_properties();
+
+		let r = requirements(64, 4, 0b1111);
+		assert_eq!(r.for_transient_attachment_in(&properties).memory_type_bits(), 0b1001);
+
+		let r = requirements(64, 4, 0b0110);
+		assert_eq!(r.for_transient_attachment_in(&properties).memory_type_bits(), 0b0110);
+	}
+}
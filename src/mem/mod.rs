@@ -4,25 +4,55 @@ use std::{
 };
 use crate::{
 	DeviceOwned,
-	device
+	device,
+	image
 };
 
 pub mod buffer;
 mod memory_requirements;
+mod budget;
+mod unbounded;
+mod mapped;
+mod external;
+pub mod frame;
 pub mod staging;
 
 pub use buffer::{
 	Buffer,
 	TypedBuffer,
+	BufferProperties,
 	IndexBuffer,
 	Buffers,
 	LocalBuffers
 };
 pub use memory_requirements::MemoryRequirements;
+pub(crate) use memory_requirements::DedicatedResource;
+pub use budget::{
+	Budget,
+	HeapBudget
+};
+pub use external::{
+	ExternalMemoryHandleType,
+	ExternalMemoryError
+};
+pub use mapped::{
+	HostVisibleMemory,
+	MappedMemory,
+	WriteError
+};
+pub use frame::{
+	FrameAllocator,
+	FrameSlice
+};
+pub use unbounded::{
+	Unbounded,
+	Allocation
+};
 
 #[derive(Debug)]
 pub enum Error {
 	OutOfMemory,
+	NoSuitableMemoryType,
 	Map(device::memory::MapError),
 	Allocation(device::AllocationError)
 }
@@ -31,6 +61,7 @@ impl fmt::Display for Error {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			Error::OutOfMemory => write!(f, "out of memory."),
+			Error::NoSuitableMemoryType => write!(f, "no suitable memory type."),
 			Error::Map(e) => write!(f, "map error: {}", e),
 			Error::Allocation(e) => write!(f, "allocation error: {}", e)
 		}
@@ -41,6 +72,7 @@ impl std::error::Error for Error {
 	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
 		match self {
 			Error::OutOfMemory => None,
+			Error::NoSuitableMemoryType => None,
 			Error::Map(e) => Some(e),
 			Error::Allocation(e) => Some(e)
 		}
@@ -77,6 +109,15 @@ pub unsafe trait Allocator: 'static + DeviceOwned {
 	/// Allocate some memory.
 	fn allocate(&self, memory_requirements: MemoryRequirements) -> Result<Self::Slot, Error>;
 
+	/// Allocate some memory for an image with the given tiling and usage.
+	///
+	/// Allocators may use the tiling to keep optimal tiling images apart from buffers and linear images,
+	/// and the usage to pick a more suitable memory type, such as lazily allocated memory for transient attachments.
+	/// The default implementation ignores both and uses `allocate`.
+	fn allocate_image(&self, memory_requirements: MemoryRequirements, _tiling: image::Tiling, _usage: image::Usage) -> Result<Self::Slot, Error> {
+		self.allocate(memory_requirements)
+	}
+
 	/// Reallocate host-visible memory.
 	fn reallocate(&self, slot: HostVisible<Self::Slot>, memory_requirements: MemoryRequirements) -> Result<HostVisible<Self::Slot>, Error>;
 }
@@ -93,6 +134,11 @@ unsafe impl<A: 'static + std::ops::Deref + DeviceOwned> Allocator for A where A:
 		std::ops::Deref::deref(self).allocate(memory_requirements)
 	}
 
+	/// Allocate some memory for an image with the given tiling and usage.
+	fn allocate_image(&self, memory_requirements: MemoryRequirements, tiling: image::Tiling, usage: image::Usage) -> Result<Self::Slot, Error> {
+		std::ops::Deref::deref(self).allocate_image(memory_requirements, tiling, usage)
+	}
+
 	/// Reallocate host-visible memory.
 	fn reallocate(&self, slot: HostVisible<Self::Slot>, memory_requirements: MemoryRequirements) -> Result<HostVisible<Self::Slot>, Error> {
 		std::ops::Deref::deref(self).reallocate(slot, memory_requirements)
@@ -1,10 +1,400 @@
-pub enum Strategy {
-	Linear
+use std::{
+	sync::{
+		Arc,
+		Weak
+	},
+	ffi::c_void
+};
+use parking_lot::Mutex;
+use crate::{
+	Device,
+	DeviceOwned,
+	device,
+	image,
+	instance::physical_device::MemoryType
+};
+use super::{
+	Error,
+	MemoryRequirements,
+	HostVisible,
+	Slot,
+	DedicatedResource
+};
+
+const MIB: u64 = 1024 * 1024;
+
+/// Default block size for a heap of the given size.
+///
+/// Small heaps get small blocks so a single block does not exhaust them.
+fn default_block_size(heap_size: u64) -> u64 {
+	let block_size = if heap_size >= 4096 * MIB {
+		256 * MIB
+	} else if heap_size >= 1024 * MIB {
+		128 * MIB
+	} else {
+		64 * MIB
+	};
+
+	std::cmp::min(block_size, heap_size)
+}
+
+#[inline]
+fn align_up(value: u64, align: u64) -> u64 {
+	(value + align - 1) / align * align
+}
+
+/// Memory of a block.
+enum BlockMemory {
+	Device(device::Memory),
+	Mapped(device::MappedMemory)
+}
+
+/// Large device memory allocation subdivided into allocations.
+struct Block {
+	memory: BlockMemory,
+
+	/// Free ranges `(offset, size)`, sorted by offset and never adjacent.
+	free: Mutex<Vec<(u64, u64)>>
+}
+
+impl Block {
+	fn new(device: &Arc<Device>, memory_type: MemoryType, size: u64, dedicated: Option<DedicatedResource>) -> Result<Block, Error> {
+		let memory = device.allocate_memory_for(memory_type, size, dedicated)?;
+
+		// Host visible blocks are persistently mapped.
+		let memory = if memory_type.is_host_visible() {
+			BlockMemory::Mapped(memory.map(0, None)?)
+		} else {
+			BlockMemory::Device(memory)
+		};
+
+		Ok(Block {
+			memory,
+			free: Mutex::new(vec![(0, size)])
+		})
+	}
+
+	#[inline]
+	fn memory(&self) -> &device::Memory {
+		match &self.memory {
+			BlockMemory::Device(memory) => memory,
+			BlockMemory::Mapped(mapped) => mapped.as_memory()
+		}
+	}
+
+	#[inline]
+	fn ptr(&self) -> Option<*mut c_void> {
+		match &self.memory {
+			BlockMemory::Device(_) => None,
+			BlockMemory::Mapped(mapped) => Some(mapped.ptr())
+		}
+	}
+
+	/// Reserve a range of the block with the given size and alignment.
+	///
+	/// Returns the reserved range and the aligned offset inside of it.
+	fn reserve(&self, size: u64, alignment: u64) -> Option<((u64, u64), u64)> {
+		let mut free = self.free.lock();
+
+		for i in 0..free.len() {
+			let (range_offset, range_size) = free[i];
+			let offset = align_up(range_offset, alignment);
+			let end = offset + size;
+
+			if end <= range_offset + range_size {
+				let reserved_size = end - range_offset;
+				if reserved_size == range_size {
+					free.remove(i);
+				} else {
+					free[i] = (end, range_size - reserved_size);
+				}
+
+				return Some(((range_offset, reserved_size), offset))
+			}
+		}
+
+		None
+	}
+
+	/// Give back a range previously returned by `reserve`.
+	fn release(&self, (offset, size): (u64, u64)) {
+		let mut free = self.free.lock();
+
+		let i = match free.binary_search_by_key(&offset, |(o, _)| *o) {
+			Ok(_) => {
+				error!("memory range at offset {} already freed", offset);
+				return
+			},
+			Err(i) => i
+		};
+
+		let merge_prev = i > 0 && free[i - 1].0 + free[i - 1].1 == offset;
+		let merge_next = i < free.len() && offset + size == free[i].0;
+
+		match (merge_prev, merge_next) {
+			(true, true) => {
+				free[i - 1].1 += size + free[i].1;
+				free.remove(i);
+			},
+			(true, false) => free[i - 1].1 += size,
+			(false, true) => free[i] = (offset, size + free[i].1),
+			(false, false) => free.insert(i, (offset, size))
+		}
+	}
 }
 
-/// Allocator without logical memory limit.
+/// Blocks of a memory type.
+///
+/// Blocks are owned by their allocations, and freed once they are all dropped.
+#[derive(Default)]
+struct Pool {
+	/// Blocks hosting buffers and linear images.
+	linear: Vec<Weak<Block>>,
+
+	/// Blocks hosting optimal tiling images.
+	optimal: Vec<Weak<Block>>
+}
+
+/// Suballocating allocator without logical memory limit.
+///
+/// Device memory is allocated by large blocks for each memory type,
+/// which are then subdivided into `Allocation`s.
+/// Requests larger than the block size get their own block.
+///
+/// Linear resources (buffers and linear images) and optimal tiling images are
+/// never allocated in the same block, so the `bufferImageGranularity` limit
+/// never applies between neighbouring allocations.
+///
+/// Resources preferring or requiring a dedicated allocation
+/// (see `MemoryRequirements::prefers_dedicated`) always get their own allocation.
 ///
 /// The allocator is still limited by the actual physical memory size.
+/// Blocks are freed as soon as every allocation in the block is dropped.
 pub struct Unbounded {
-	// ...
+	device: Arc<Device>,
+
+	/// Block size of each memory type.
+	block_sizes: Vec<u64>,
+
+	/// Pool of each memory type.
+	pools: Vec<Mutex<Pool>>
+}
+
+assert_impl_all!(Unbounded: Send, Sync);
+assert_impl_all!(Allocation: Send, Sync);
+
+impl Unbounded {
+	/// Create a new allocator with the default block sizes.
+	///
+	/// The block size of each memory type depends on the size of its heap,
+	/// from 64MiB for heaps smaller than 1GiB to 256MiB for heaps of 4GiB and more.
+	pub fn new(device: &Arc<Device>) -> Unbounded {
+		let block_sizes = device.physical_device().memory_types().map(|memory_type| {
+			default_block_size(memory_type.heap().size())
+		}).collect();
+
+		Self::with_block_sizes(device, block_sizes)
+	}
+
+	/// Create a new allocator using the same block size for every memory type.
+	pub fn with_block_size(device: &Arc<Device>, block_size: u64) -> Unbounded {
+		assert!(block_size > 0);
+		let block_sizes = vec![block_size; device.physical_device().memory_type_count() as usize];
+		Self::with_block_sizes(device, block_sizes)
+	}
+
+	fn with_block_sizes(device: &Arc<Device>, block_sizes: Vec<u64>) -> Unbounded {
+		let pools = block_sizes.iter().map(|_| Mutex::new(Pool::default())).collect();
+
+		Unbounded {
+			device: device.clone(),
+			block_sizes,
+			pools
+		}
+	}
+
+	/// Block size of the given memory type.
+	#[inline]
+	pub fn block_size(&self, memory_type: MemoryType) -> u64 {
+		self.block_sizes[memory_type.index() as usize]
+	}
+
+	/// Allocate some memory for an optimal tiling image.
+	pub fn allocate_optimal(&self, memory_requirements: MemoryRequirements) -> Result<Allocation, Error> {
+		self.allocate_in(memory_requirements, false)
+	}
+
+	fn allocate_in(&self, memory_requirements: MemoryRequirements, linear: bool) -> Result<Allocation, Error> {
+		let size = std::cmp::max(memory_requirements.size(), 1);
+		let alignment = std::cmp::max(memory_requirements.alignment(), 1);
+		let mut last_error = Error::NoSuitableMemoryType;
+
+		// Memory types are ordered by preference by the implementation.
+		for memory_type in self.device.physical_device().memory_types() {
+			if !memory_requirements.contains_memory_type_index(memory_type.index()) {
+				continue
+			}
+
+			let result = match memory_requirements.dedicated_resource() {
+				Some(resource) => self.allocate_dedicated(memory_type, size, resource),
+				None => self.allocate_from(memory_type, size, alignment, linear)
+			};
+
+			match result {
+				Ok(allocation) => return Ok(allocation),
+				Err(e) => last_error = e
+			}
+		}
+
+		Err(last_error)
+	}
+
+	fn allocate_from(&self, memory_type: MemoryType, size: u64, alignment: u64, linear: bool) -> Result<Allocation, Error> {
+		let mut pool = self.pools[memory_type.index() as usize].lock();
+		let blocks = if linear {
+			&mut pool.linear
+		} else {
+			&mut pool.optimal
+		};
+
+		// Forget the freed blocks.
+		blocks.retain(|block| block.strong_count() > 0);
+
+		for block in blocks.iter().filter_map(Weak::upgrade) {
+			if let Some((range, offset)) = block.reserve(size, alignment) {
+				return Ok(Allocation::new(block, range, offset, size))
+			}
+		}
+
+		let block_size = std::cmp::max(self.block_size(memory_type), size);
+		let block = Arc::new(Block::new(&self.device, memory_type, block_size, None)?);
+		let (range, offset) = block.reserve(size, alignment).unwrap();
+		blocks.push(Arc::downgrade(&block));
+
+		Ok(Allocation::new(block, range, offset, size))
+	}
+
+	/// Allocate a block dedicated to the given resource.
+	///
+	/// The block is not shared with other allocations.
+	fn allocate_dedicated(&self, memory_type: MemoryType, size: u64, resource: DedicatedResource) -> Result<Allocation, Error> {
+		let block = Arc::new(Block::new(&self.device, memory_type, size, Some(resource))?);
+		let (range, offset) = block.reserve(size, 1).unwrap();
+
+		Ok(Allocation::new(block, range, offset, size))
+	}
+}
+
+unsafe impl super::Allocator for Unbounded {
+	type Slot = Allocation;
+
+	fn prepare(&self, _memory_requirements: MemoryRequirements) {
+		// nothing to prepare.
+	}
+
+	/// Allocate some memory for a buffer or a linear tiling image.
+	fn allocate(&self, memory_requirements: MemoryRequirements) -> Result<Allocation, Error> {
+		self.allocate_in(memory_requirements, true)
+	}
+
+	/// Allocate some memory for an image.
+	///
+	/// Optimal tiling images are allocated apart from buffers and linear tiling images.
+	/// Transient attachments are allocated in lazily allocated memory if available,
+	/// falling back to device local memory.
+	fn allocate_image(&self, memory_requirements: MemoryRequirements, tiling: image::Tiling, usage: image::Usage) -> Result<Allocation, Error> {
+		let memory_requirements = if usage.transient_attachment {
+			memory_requirements.for_transient_attachment(self.device.physical_device())
+		} else {
+			memory_requirements
+		};
+
+		match tiling {
+			image::Tiling::Optimal => self.allocate_optimal(memory_requirements),
+			image::Tiling::Linear => self.allocate_in(memory_requirements, true)
+		}
+	}
+
+	/// Reallocate host-visible memory.
+	///
+	/// The new allocation is made in a host visible memory type,
+	/// and the content of the previous allocation is copied into it.
+	fn reallocate(&self, slot: HostVisible<Allocation>, memory_requirements: MemoryRequirements) -> Result<HostVisible<Allocation>, Error> {
+		let memory_requirements = memory_requirements.filter_memory_types(self.device.physical_device(), |memory_type| memory_type.is_host_visible());
+		if memory_requirements.memory_type_bits() == 0 {
+			return Err(Error::NoSuitableMemoryType)
+		}
+
+		let new_slot = HostVisible::try_from(self.allocate(memory_requirements)?)
+			.map_err(|_| Error::Map(device::memory::MapError::NotHostVisible))?;
+		let len = std::cmp::min(slot.size(), new_slot.size());
+
+		unsafe {
+			std::ptr::copy_nonoverlapping(slot.ptr() as *const u8, new_slot.ptr() as *mut u8, len as usize);
+		}
+
+		Ok(new_slot)
+	}
+}
+
+impl DeviceOwned for Unbounded {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+/// Memory slice allocated by an `Unbounded` allocator.
+///
+/// The memory is given back to the allocator when dropped.
+pub struct Allocation {
+	block: Arc<Block>,
+
+	/// Range reserved in the block, including the alignment padding.
+	range: (u64, u64),
+
+	/// Aligned offset of the allocation in the block.
+	offset: u64,
+
+	/// Requested size.
+	size: u64
+}
+
+impl Allocation {
+	#[inline]
+	fn new(block: Arc<Block>, range: (u64, u64), offset: u64, size: u64) -> Allocation {
+		Allocation {
+			block,
+			range,
+			offset,
+			size
+		}
+	}
+}
+
+unsafe impl Slot for Allocation {
+	#[inline]
+	fn memory(&self) -> &device::Memory {
+		self.block.memory()
+	}
+
+	#[inline]
+	fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	#[inline]
+	fn size(&self) -> u64 {
+		self.size
+	}
+
+	#[inline]
+	fn ptr(&self) -> Option<*mut c_void> {
+		self.block.ptr().map(|ptr| unsafe { (ptr as *mut u8).add(self.offset as usize) as *mut c_void })
+	}
+}
+
+impl Drop for Allocation {
+	fn drop(&mut self) {
+		self.block.release(self.range)
+	}
 }
@@ -1,6 +1,40 @@
 use ash::vk;
 
-#[derive(Clone, Copy, Debug)]
+mod upload;
+mod readback;
+mod mipmaps;
+mod ownership;
+
+pub use upload::{
+	UploadError,
+	Upload,
+	upload,
+	upload_image,
+	upload_mip_chain,
+	upload_cube_faces
+};
+
+pub use readback::{
+	ReadbackError,
+	Readback,
+	readback_buffer,
+	readback_typed,
+	readback_image
+};
+
+pub use mipmaps::{
+	MipmapError,
+	generate_mipmaps
+};
+
+pub use ownership::{
+	OwnershipError,
+	OwnershipTransfer,
+	transfer_buffer_ownership,
+	transfer_image_ownership
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[repr(i32)]
 pub enum Compare {
 	Never = vk::CompareOp::NEVER.as_raw(),
@@ -62,4 +96,4 @@ impl Default for Logic {
 			std::mem::transmute(vk::LogicOp::default().as_raw())
 		}
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+use ash::vk;
+use crate::{
+	Format,
+	image::{
+		self,
+		ImageProperties
+	},
+	command::{
+		self,
+		Buffer as CommandBuffer,
+		buffer::{
+			Recorder,
+			CopyError,
+			SubresourceLayers
+		}
+	}
+};
+
+#[derive(Debug)]
+pub enum MipmapError {
+	/// The image format does not support linear filtering.
+	UnsupportedFilter(Format),
+
+	/// The image format cannot be used as blit source and destination.
+	UnsupportedBlit(Format),
+
+	/// The image was not created with the transfer source and destination usages.
+	MissingTransferUsage,
+
+	/// A blit between two levels is invalid.
+	Blit(CopyError)
+}
+
+impl From<CopyError> for MipmapError {
+	fn from(e: CopyError) -> MipmapError {
+		MipmapError::Blit(e)
+	}
+}
+
+impl fmt::Display for MipmapError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::UnsupportedFilter(format) => write!(f, "format {:?} does not support linear filtering", format),
+			Self::UnsupportedBlit(format) => write!(f, "format {:?} does not support blits", format),
+			Self::MissingTransferUsage => write!(f, "missing transfer source or destination image usage"),
+			Self::Blit(e) => write!(f, "invalid blit: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for MipmapError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Blit(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+/// Record the generation of all the mip levels of an image from its first level.
+///
+/// Each level is blitted from the previous one with linear filtering, for all the array layers.
+/// The whole image must be in the `TransferDstOptimal` layout,
+/// and is left in the `ShaderReadOnlyOptimal` layout, visible to fragment shaders.
+/// If the image layout is tracked, the tracked layout is updated when the command buffer is submitted.
+pub fn generate_mipmaps<'a, B: CommandBuffer, I: ImageProperties>(recorder: &mut Recorder<'a, B>, image: &'a I) -> Result<(), MipmapError> {
+	let format = image.format();
+	let features = format.properties(image.device().physical_device()).optimal_tiling_features();
+	if !features.sampled_image_filter_linear() {
+		return Err(MipmapError::UnsupportedFilter(format))
+	}
+
+	if !features.blit_src() || !features.blit_dst() {
+		return Err(MipmapError::UnsupportedBlit(format))
+	}
+
+	let usage = image.usage();
+	if !usage.transfer_source || !usage.transfer_destination {
+		return Err(MipmapError::MissingTransferUsage)
+	}
+
+	let aspect_mask = format.aspects().into_vulkan();
+	let layer_count = image.array_layers();
+	let mip_levels = image.mip_levels();
+
+	let barrier = |level, old_layout, new_layout, src_access_mask, dst_access_mask| command::buffer::ImageMemoryBarrier {
+		src_access_mask,
+		dst_access_mask,
+		old_layout,
+		new_layout,
+		src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+		dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+		image: image.handle(),
+		subresource_range: vk::ImageSubresourceRange {
+			aspect_mask,
+			base_mip_level: level,
+			level_count: 1,
+			base_array_layer: 0,
+			layer_count
+		},
+		..Default::default()
+	};
+
+	let corner = |(width, height, depth): (u32, u32, u32)| (width as i32, height as i32, depth as i32);
+
+	let (mut width, mut height, mut depth) = image.extent();
+	for level in 1..mip_levels {
+		let next = (
+			std::cmp::max(1, width >> 1),
+			std::cmp::max(1, height >> 1),
+			std::cmp::max(1, depth >> 1)
+		);
+
+		unsafe {
+			recorder.image_barriers(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::TRANSFER, &[
+				barrier(level - 1, vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::TRANSFER_READ)
+			]);
+		}
+
+		recorder.blit_image(image, image::Layout::TransferSrcOptimal, image, image::Layout::TransferDstOptimal, &[command::buffer::ImageBlit {
+			src_subresource: SubresourceLayers::mip(image, level - 1),
+			src_offsets: [(0, 0, 0), corner((width, height, depth))],
+			dst_subresource: SubresourceLayers::mip(image, level),
+			dst_offsets: [(0, 0, 0), corner(next)]
+		}], image::sampler::Filter::Linear)?;
+
+		unsafe {
+			recorder.image_barriers(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, &[
+				barrier(level - 1, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, vk::AccessFlags::TRANSFER_READ, vk::AccessFlags::SHADER_READ)
+			]);
+		}
+
+		width = next.0;
+		height = next.1;
+		depth = next.2;
+	}
+
+	// The last level is only written.
+	unsafe {
+		recorder.image_barriers(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, &[
+			barrier(mip_levels - 1, vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ)
+		]);
+	}
+
+	if let Some(tracker) = image.layout_tracker() {
+		recorder.transitions.push((tracker, image::Layout::ShaderReadOnlyOptimal))
+	}
+
+	Ok(())
+}
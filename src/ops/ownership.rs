@@ -0,0 +1,244 @@
+use std::fmt;
+use ash::vk;
+use crate::{
+	OomError,
+	DeviceOwned,
+	image::{
+		self,
+		ImageProperties
+	},
+	device::{
+		Queue,
+		queue::{
+			Submission,
+			SubmitError
+		}
+	},
+	command::{
+		once,
+		buffer::{
+			Recorder,
+			Executable,
+			BufferBarrier,
+			ImageBarrier,
+			BarrierError
+		},
+		pool::{
+			self,
+			Raw as RawPool
+		}
+	},
+	sync::semaphore,
+	framebuffer::render_pass::subpass::{
+		PipelineStages,
+		AccessFlagBits
+	},
+	mem::BufferProperties
+};
+
+/// Ownership transfer error.
+#[derive(Debug)]
+pub enum OwnershipError {
+	OutOfMemory(OomError),
+
+	/// The resource is shared concurrently by several queue families,
+	/// and can be used by any of them without ownership transfer.
+	Concurrent,
+
+	/// The release or acquire barrier is invalid.
+	Barrier(BarrierError),
+
+	Submit(SubmitError)
+}
+
+impl fmt::Display for OwnershipError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::Concurrent => write!(f, "resource shared concurrently"),
+			Self::Barrier(e) => write!(f, "invalid ownership transfer barrier: {}", e),
+			Self::Submit(e) => write!(f, "unable to submit ownership transfer: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for OwnershipError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			Self::Concurrent => None,
+			Self::Barrier(e) => Some(e),
+			Self::Submit(e) => Some(e)
+		}
+	}
+}
+
+impl From<pool::CreationError> for OwnershipError {
+	fn from(pool::CreationError::OutOfMemory(e): pool::CreationError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<pool::AllocError> for OwnershipError {
+	fn from(pool::AllocError::OutOfMemory(e): pool::AllocError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<semaphore::CreationError> for OwnershipError {
+	fn from(semaphore::CreationError::OutOfMemory(e): semaphore::CreationError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<BarrierError> for OwnershipError {
+	fn from(e: BarrierError) -> Self {
+		Self::Barrier(e)
+	}
+}
+
+impl From<SubmitError> for OwnershipError {
+	fn from(e: SubmitError) -> Self {
+		Self::Submit(e)
+	}
+}
+
+type OneShotBuffer = pool::raw::Buffer<RawPool>;
+
+/// Pending ownership transfer.
+///
+/// Acquire submission on the destination queue, waiting on the release submission on the source queue.
+/// Both one-shot command buffers are kept alive until the transfer is done.
+/// The transfer signals a semaphore, and can be chained with the tasks using the resource on the destination queue.
+///
+/// Image layout transitions between queues of the same family are a single submission
+/// on the source queue, waiting on no release.
+pub type OwnershipTransfer<'a> = Submission<(Option<once::Pending<'a, OneShotBuffer, semaphore::Raw>>, Executable<'a, OneShotBuffer>), semaphore::Raw>;
+
+/// Allocate a one-shot command buffer for the family of the given queue.
+fn one_shot_buffer(queue: &Queue) -> Result<OneShotBuffer, OwnershipError> {
+	let pool = RawPool::new(queue.device(), queue.family(), pool::Flags { transient: true, ..pool::Flags::none() })?;
+	let handle = unsafe {
+		pool.allocate_into(1, pool::Level::Primary, |h| h)?.pop().unwrap()
+	};
+
+	// The buffer owns its pool, destroyed once the buffer is freed.
+	Ok(pool::raw::Buffer::new(pool, handle, pool::Level::Primary, 0))
+}
+
+/// Submit the release barrier to `from`, then the acquire barrier to `to` after it.
+///
+/// `record` records the barrier with the given access masks and stages,
+/// and is told whether it records the release or the acquire barrier.
+fn transfer<'a, F>(from: &Queue, to: &Queue, record: F) -> Result<OwnershipTransfer<'a>, OwnershipError> where F: Fn(&mut Recorder<'a, OneShotBuffer>, bool, AccessFlagBits, AccessFlagBits, PipelineStages, PipelineStages) -> Result<(), BarrierError> {
+	// Writes on the source queue are made available by the release,
+	// and visible to any access on the destination queue by the acquire.
+	let (release, released) = once::submit_buffer_once(from, one_shot_buffer(from)?, semaphore::Raw::new(from.device())?, |recorder| {
+		record(
+			recorder,
+			true,
+			AccessFlagBits { memory_write: true, ..AccessFlagBits::none() },
+			AccessFlagBits::none(),
+			PipelineStages { all_commands: true, ..PipelineStages::none() },
+			PipelineStages { bottom_of_pipe: true, ..PipelineStages::none() }
+		)
+	})?;
+	released?;
+
+	let (acquire, acquired) = once::submit_buffer_once_after(to, one_shot_buffer(to)?, Some(release), vk::PipelineStageFlags::ALL_COMMANDS, semaphore::Raw::new(to.device())?, |recorder| {
+		record(
+			recorder,
+			false,
+			AccessFlagBits::none(),
+			AccessFlagBits { memory_read: true, memory_write: true, ..AccessFlagBits::none() },
+			PipelineStages { top_of_pipe: true, ..PipelineStages::none() },
+			PipelineStages { all_commands: true, ..PipelineStages::none() }
+		)
+	})?;
+	acquired?;
+
+	Ok(acquire)
+}
+
+/// Transfer the ownership of a whole buffer, shared exclusively, from the family of `from` to the family of `to`.
+///
+/// Submits a release barrier to `from`, and an acquire barrier to `to`
+/// waiting on a semaphore signaled by the release.
+/// Returns `None` without submitting anything if both queues are of the same family.
+///
+/// Fails with `OwnershipError::Concurrent` if the buffer is shared concurrently.
+pub fn transfer_buffer_ownership<'a, B: BufferProperties>(buffer: &'a B, from: &Queue, to: &Queue) -> Result<Option<OwnershipTransfer<'a>>, OwnershipError> {
+	if buffer.is_concurrent() {
+		return Err(OwnershipError::Concurrent)
+	}
+
+	if from.family_index() == to.family_index() {
+		return Ok(None)
+	}
+
+	transfer(from, to, |recorder, _, src_access, dst_access, src_stages, dst_stages| {
+		recorder.barrier()
+			.buffer(BufferBarrier::whole(buffer, src_access, dst_access).with_queue_transfer(from, to))
+			.record(src_stages, dst_stages)
+	}).map(Some)
+}
+
+/// Transfer the ownership of a whole image, shared exclusively, from the family of `from` to the family of `to`.
+///
+/// The image is also transitioned from `old_layout` to `new_layout`,
+/// since the layout transition is performed by the same barriers.
+/// If both queues are of the same family, no ownership is transferred:
+/// the image is only transitioned by a single barrier submitted to `from`,
+/// whose semaphore is then waited by the tasks using the image on `to`.
+/// Returns `None` without submitting anything if both queues are of the same family
+/// and the layouts are the same.
+///
+/// Fails with `OwnershipError::Concurrent` if the image is shared concurrently.
+pub fn transfer_image_ownership<'a, I: ImageProperties>(image: &'a I, from: &Queue, to: &Queue, old_layout: image::Layout, new_layout: image::Layout) -> Result<Option<OwnershipTransfer<'a>>, OwnershipError> {
+	if image.is_concurrent() {
+		return Err(OwnershipError::Concurrent)
+	}
+
+	if from.family_index() == to.family_index() {
+		return if old_layout == new_layout {
+			Ok(None)
+		} else {
+			transition(image, from, old_layout, new_layout).map(Some)
+		}
+	}
+
+	// Checked before submitting the release.
+	if old_layout != new_layout && !new_layout.is_transition_target() {
+		return Err(OwnershipError::Barrier(BarrierError::InvalidTarget(new_layout)))
+	}
+
+	transfer(from, to, |recorder, release, src_access, dst_access, src_stages, dst_stages| {
+		let barrier = ImageBarrier::new(image, old_layout, new_layout).with_queue_transfer(from, to);
+		let barrier = if release {
+			barrier
+		} else {
+			barrier.untracked()
+		};
+
+		recorder.barrier()
+			.image(barrier.with_access(src_access, dst_access))
+			.record(src_stages, dst_stages)
+	}).map(Some)
+}
+
+/// Submit a single barrier to `queue`, transitioning the image from `old_layout` to `new_layout`.
+fn transition<'a, I: ImageProperties>(image: &'a I, queue: &Queue, old_layout: image::Layout, new_layout: image::Layout) -> Result<OwnershipTransfer<'a>, OwnershipError> {
+	// Writes before the barrier are made available and visible to any access after it.
+	let src_access = AccessFlagBits { memory_write: true, ..AccessFlagBits::none() };
+	let dst_access = AccessFlagBits { memory_read: true, memory_write: true, ..AccessFlagBits::none() };
+	let all_commands = PipelineStages { all_commands: true, ..PipelineStages::none() };
+
+	let (transition, transitioned) = once::submit_buffer_once_after(queue, one_shot_buffer(queue)?, None, vk::PipelineStageFlags::TOP_OF_PIPE, semaphore::Raw::new(queue.device())?, |recorder| {
+		recorder.barrier()
+			.image(ImageBarrier::new(image, old_layout, new_layout).with_access(src_access, dst_access))
+			.record(all_commands, all_commands)
+	})?;
+	transitioned?;
+
+	Ok(transition)
+}
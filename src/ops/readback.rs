@@ -0,0 +1,420 @@
+use std::{
+	sync::Arc,
+	ops::Range,
+	marker::PhantomData,
+	time::Duration,
+	fmt
+};
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use crate::{
+	OomError,
+	Device,
+	DeviceLost,
+	DeviceOwned,
+	Format,
+	image::{
+		self,
+		ImageProperties,
+		view::Aspects
+	},
+	device::{
+		self,
+		Queue,
+		queue::SubmitError
+	},
+	command::{
+		self,
+		once,
+		buffer::{
+			BufferImageCopy,
+			SubresourceLayers,
+			CopyError
+		},
+		pool::{
+			self,
+			Raw as RawPool
+		}
+	},
+	instance::physical_device::MemoryProperties,
+	sync::{
+		self,
+		future::{
+			Futures,
+			SignalFence
+		}
+	},
+	mem::{
+		self,
+		BufferProperties,
+		buffer::{
+			self,
+			Usage,
+			Unbound,
+			Bound,
+			Typed
+		}
+	}
+};
+
+/// Readback error.
+#[derive(Debug)]
+pub enum ReadbackError {
+	/// The staging buffer could not be created.
+	Staging(buffer::vec::Error),
+
+	/// The buffer was not created with the `TransferSource` usage.
+	MissingTransferSourceUsage,
+
+	/// The read range is empty or exceeds the buffer.
+	InvalidRange,
+
+	/// The format has no defined texel size.
+	UnsupportedFormat(Format),
+
+	/// The image content is undefined in the given layout.
+	UndefinedLayout(image::Layout),
+
+	OutOfMemory(OomError),
+
+	/// The image cannot be copied from.
+	Copy(CopyError),
+
+	Submit(SubmitError)
+}
+
+impl fmt::Display for ReadbackError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Staging(e) => write!(f, "unable to create staging buffer: {}", e),
+			Self::MissingTransferSourceUsage => write!(f, "buffer is not a transfer source"),
+			Self::InvalidRange => write!(f, "invalid readback range"),
+			Self::UnsupportedFormat(format) => write!(f, "format {:?} cannot be read back", format),
+			Self::UndefinedLayout(layout) => write!(f, "image content undefined in layout {:?}", layout),
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::Copy(e) => write!(f, "unable to copy from image: {}", e),
+			Self::Submit(e) => write!(f, "unable to submit readback: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for ReadbackError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::Staging(e) => Some(e),
+			Self::OutOfMemory(e) => Some(e),
+			Self::Copy(e) => Some(e),
+			Self::Submit(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<buffer::vec::Error> for ReadbackError {
+	fn from(e: buffer::vec::Error) -> Self {
+		Self::Staging(e)
+	}
+}
+
+impl From<pool::CreationError> for ReadbackError {
+	fn from(pool::CreationError::OutOfMemory(e): pool::CreationError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<pool::AllocError> for ReadbackError {
+	fn from(pool::AllocError::OutOfMemory(e): pool::AllocError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<CopyError> for ReadbackError {
+	fn from(e: CopyError) -> Self {
+		Self::Copy(e)
+	}
+}
+
+impl From<SubmitError> for ReadbackError {
+	fn from(e: SubmitError) -> Self {
+		Self::Submit(e)
+	}
+}
+
+type Staging = Arc<Bound<device::MappedMemory>>;
+
+/// Pending readback.
+///
+/// The data is only available once the readback is done, through `into_data`.
+/// Dropping a pending readback blocks until it completes.
+#[must_use]
+pub struct Readback<'a, T: Copy = u8> {
+	submission: once::LocalPending<'a, pool::raw::Buffer<RawPool>>,
+	staging: Staging,
+
+	/// Number of read items.
+	len: usize,
+	t: PhantomData<T>
+}
+
+impl<'a, T: Copy> Readback<'a, T> {
+	/// Number of read items.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Returns the read data.
+	///
+	/// Fails with `UnwrapError::Unsignaled` if the readback is not done yet.
+	pub fn into_data(self) -> Result<Vec<T>, sync::fence::UnwrapError<Self>> {
+		match self.submission.is_signaled() {
+			Ok(true) => Ok(self.read()),
+			Ok(false) => Err(sync::fence::UnwrapError::Unsignaled(self)),
+			Err(DeviceLost::Lost) => Err(sync::fence::UnwrapError::DeviceLost),
+			Err(DeviceLost::Unexpected(r)) => Err(sync::fence::UnwrapError::Unexpected(r))
+		}
+	}
+
+	/// Blocks until the readback is done, and returns the read data.
+	pub fn wait_data(self, timeout: Option<Duration>) -> Result<Vec<T>, sync::fence::WaitError> {
+		self.submission.wait_done(timeout)?;
+		Ok(self.read())
+	}
+
+	/// Read the staging buffer.
+	///
+	/// The submission must be done.
+	fn read(&self) -> Vec<T> {
+		let memory = self.staging.memory_slot();
+
+		if !memory.as_memory().memory_type().is_host_coherent() {
+			let range = vk::MappedMemoryRange {
+				memory: memory.as_memory().handle(),
+				offset: 0,
+				size: vk::WHOLE_SIZE,
+				..Default::default()
+			};
+
+			unsafe {
+				// The staging memory is small compared to the device memory,
+				// failing to invalidate it is not worth an error variant.
+				if let Err(e) = memory.as_memory().device().handle().invalidate_mapped_memory_ranges(&[range]) {
+					error!("unable to invalidate readback memory: {}", OomError::from(e))
+				}
+			}
+		}
+
+		let mut data = Vec::with_capacity(self.len);
+		unsafe {
+			std::ptr::copy_nonoverlapping(memory.ptr() as *const T, data.as_mut_ptr(), self.len);
+			data.set_len(self.len);
+		}
+
+		data
+	}
+}
+
+unsafe impl<'a, T: Copy> Futures for Readback<'a, T> {
+	fn signal_semaphores(&self) -> Option<&[vk::Semaphore]> {
+		self.submission.signal_semaphores()
+	}
+
+	fn signal_fence(&self) -> Option<&vk::Fence> {
+		Futures::signal_fence(&self.submission)
+	}
+
+	fn waited(&self) {
+		Futures::waited(&self.submission)
+	}
+}
+
+impl<'a, T: Copy> DeviceOwned for Readback<'a, T> {
+	fn device(&self) -> &Arc<Device> {
+		self.submission.device()
+	}
+}
+
+/// Waiting through this trait discards the data.
+impl<'a, T: Copy> SignalFence for Readback<'a, T> {
+	fn wait(self, timeout: Option<Duration>) -> Result<(), sync::fence::WaitError> {
+		self.submission.wait(timeout)
+	}
+
+	fn is_signaled(&self) -> Result<bool, DeviceLost> {
+		self.submission.is_signaled()
+	}
+}
+
+/// Create a host visible staging buffer of the given size, preferably in host cached memory.
+fn staging_buffer(queue: &Queue, size: u64) -> Result<Staging, buffer::vec::Error> {
+	let device = queue.device();
+	let buffer = Unbound::new(device, size, Usage::TransferDestination, std::iter::once(queue))?;
+	let memory_requirements = buffer.memory_requirements();
+	let memory_type = memory_requirements.find_memory_type(
+		device.physical_device(),
+		MemoryProperties::HOST_VISIBLE,
+		MemoryProperties::HOST_CACHED
+	).ok_or(mem::Error::NoSuitableMemoryType)?;
+
+	let memory = device.allocate_memory(memory_type, memory_requirements.size()).map_err(mem::Error::from)?;
+	let memory = memory.map(0, None).map_err(mem::Error::from)?;
+
+	let buffer = unsafe {
+		buffer.bind(memory).map_err(|(_, e)| e)?
+	};
+
+	Ok(Arc::new(buffer))
+}
+
+/// Allocate a one-shot command buffer for the family of the given queue.
+fn one_shot_buffer(queue: &Queue) -> Result<pool::raw::Buffer<RawPool>, ReadbackError> {
+	let pool = RawPool::new(queue.device(), queue.family(), pool::Flags { transient: true, ..pool::Flags::none() })?;
+	let handle = unsafe {
+		pool.allocate_into(1, pool::Level::Primary, |h| h)?.pop().unwrap()
+	};
+
+	// The buffer owns its pool, destroyed once the buffer is freed.
+	Ok(pool::raw::Buffer::new(pool, handle, pool::Level::Primary, 0))
+}
+
+fn readback_range<'a, T: Copy, B: 'a + BufferProperties>(queue: &Queue, buffer: B, range: Range<u64>) -> Result<Readback<'a, T>, ReadbackError> {
+	if !buffer.usage().transfer_source() {
+		return Err(ReadbackError::MissingTransferSourceUsage)
+	}
+
+	if range.start >= range.end || range.end > buffer.size() {
+		return Err(ReadbackError::InvalidRange)
+	}
+
+	let size = range.end - range.start;
+	let staging = staging_buffer(queue, size)?;
+
+	let (submission, ()) = once::submit_buffer_once_local(queue, one_shot_buffer(queue)?, (), |recorder| {
+		recorder.copy_buffer(buffer, staging.clone(), &[command::buffer::BufferCopy {
+			src_offset: range.start,
+			dst_offset: 0,
+			size
+		}]);
+
+		recorder.memory_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::HOST, vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::HOST_READ)
+	})?;
+
+	Ok(Readback {
+		submission,
+		staging,
+		len: (size / std::mem::size_of::<T>() as u64) as usize,
+		t: PhantomData
+	})
+}
+
+/// Read back the given byte range of a buffer.
+///
+/// The buffer must have been created with the `TransferSource` usage,
+/// and must not be written by the device before the readback is done.
+pub fn readback_buffer<'a, B: 'a + BufferProperties>(queue: &Queue, buffer: B, range: Range<u64>) -> Result<Readback<'a>, ReadbackError> {
+	readback_range(queue, buffer, range)
+}
+
+/// Read back the given range of items of a typed buffer.
+pub fn readback_typed<'a, T: Copy>(queue: &Queue, buffer: &'a Typed<T>, range: Range<u64>) -> Result<Readback<'a, T>, ReadbackError> {
+	let item_size = std::mem::size_of::<T>() as u64;
+	readback_range(queue, buffer, (range.start * item_size)..(range.end * item_size))
+}
+
+/// Read back a region of the first mip level and array layer of the color aspect of an image.
+///
+/// The image is expected in the given `layout` when the readback executes,
+/// and is transitioned back to it afterward.
+/// The layout must define the image content: it cannot be `Undefined` or `Preinitialized`.
+/// It must have been created with the `transfer_source` usage.
+///
+/// The returned texels are tightly packed, row after row, in the format of the image.
+pub fn readback_image<'a, I: ImageProperties>(queue: &Queue, image: &'a I, layout: image::Layout, (x, y, z): (i32, i32, i32), (width, height, depth): (u32, u32, u32)) -> Result<Readback<'a>, ReadbackError> {
+	if layout == image::Layout::Undefined || layout == image::Layout::Preinitialized {
+		return Err(ReadbackError::UndefinedLayout(layout))
+	}
+
+	let format = image.format();
+	let size = format.data_size((width, height, depth)).ok_or(ReadbackError::UnsupportedFormat(format))?;
+	if size == 0 {
+		return Err(ReadbackError::InvalidRange)
+	}
+
+	let staging = staging_buffer(queue, size)?;
+
+	// A zero row length and image height means tightly packed texels.
+	let region = BufferImageCopy {
+		buffer_offset: 0,
+		row_length: 0,
+		image_height: 0,
+		image_subresource: SubresourceLayers {
+			aspects: Aspects::color(),
+			mip_level: 0,
+			base_array_layer: 0,
+			layer_count: 1
+		},
+		offset: (x, y, z),
+		extent: (width, height, depth)
+	};
+
+	// Checked before submitting, since an invalid copy would only be reported once the submission is dropped.
+	command::buffer::check_image_to_buffer(image, &staging, &[region])?;
+
+	let subresource_range = vk::ImageSubresourceRange {
+		aspect_mask: vk::ImageAspectFlags::COLOR,
+		base_mip_level: 0,
+		level_count: 1,
+		base_array_layer: 0,
+		layer_count: 1
+	};
+
+	let (submission, copy) = once::submit_buffer_once_local(queue, one_shot_buffer(queue)?, (), |recorder| {
+		unsafe {
+			recorder.image_barriers(vk::PipelineStageFlags::ALL_COMMANDS, vk::PipelineStageFlags::TRANSFER, &[command::buffer::ImageMemoryBarrier {
+				src_access_mask: vk::AccessFlags::MEMORY_WRITE,
+				dst_access_mask: vk::AccessFlags::TRANSFER_READ,
+				old_layout: layout.into_vulkan(),
+				new_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+				src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				image: image.handle(),
+				subresource_range,
+				..Default::default()
+			}]);
+		}
+
+		let copy = recorder.copy_image_to_buffer(image, image::Layout::TransferSrcOptimal, staging.clone(), &[region]);
+
+		unsafe {
+			recorder.image_barriers(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::ALL_COMMANDS, &[command::buffer::ImageMemoryBarrier {
+				src_access_mask: vk::AccessFlags::empty(),
+				dst_access_mask: vk::AccessFlags::empty(),
+				old_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+				new_layout: layout.into_vulkan(),
+				src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				image: image.handle(),
+				subresource_range,
+				..Default::default()
+			}]);
+		}
+
+		if let Some(tracker) = image.layout_tracker() {
+			recorder.transitions.push((tracker, layout))
+		}
+
+		recorder.memory_barrier(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::HOST, vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::HOST_READ);
+		copy
+	})?;
+
+	copy?;
+
+	Ok(Readback {
+		submission,
+		staging,
+		len: size as usize,
+		t: PhantomData
+	})
+}
@@ -0,0 +1,416 @@
+use std::{
+	sync::Arc,
+	fmt
+};
+use ash::vk;
+use crate::{
+	OomError,
+	DeviceOwned,
+	Format,
+	image::{
+		self,
+		ImageProperties,
+		view::Aspects
+	},
+	device::{
+		Queue,
+		queue::SubmitError
+	},
+	command::{
+		self,
+		once,
+		buffer::{
+			BufferImageCopy,
+			SubresourceLayers,
+			CopyError
+		},
+		pool::{
+			self,
+			Raw as RawPool
+		}
+	},
+	sync::semaphore,
+	mem::{
+		self,
+		Allocator,
+		Slot,
+		buffer::{
+			self,
+			Usage,
+			Usages,
+			Unbound,
+			Bound
+		}
+	}
+};
+
+/// Upload error.
+#[derive(Debug)]
+pub enum UploadError {
+	/// The staging or destination buffer could not be created.
+	Buffer(buffer::vec::Error),
+	OutOfMemory(OomError),
+
+	/// There is no data to upload.
+	EmptyData,
+
+	/// The image format has no defined element size.
+	UnsupportedFormat(Format),
+
+	/// The data size does not match the uploaded mip levels.
+	InvalidDataSize {
+		expected: u64,
+		given: u64
+	},
+
+	/// The image cannot be copied into.
+	Copy(CopyError),
+
+	Submit(SubmitError)
+}
+
+impl fmt::Display for UploadError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Buffer(e) => write!(f, "unable to create upload buffer: {}", e),
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::EmptyData => write!(f, "no data to upload"),
+			Self::UnsupportedFormat(format) => write!(f, "format {:?} cannot be uploaded", format),
+			Self::InvalidDataSize { expected, given } => write!(f, "expected {} bytes of data, got {}", expected, given),
+			Self::Copy(e) => write!(f, "unable to copy into image: {}", e),
+			Self::Submit(e) => write!(f, "unable to submit upload: {}", e)
+		}
+	}
+}
+
+impl std::error::Error for UploadError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::Buffer(e) => Some(e),
+			Self::OutOfMemory(e) => Some(e),
+			Self::Copy(e) => Some(e),
+			Self::Submit(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<buffer::vec::Error> for UploadError {
+	fn from(e: buffer::vec::Error) -> Self {
+		Self::Buffer(e)
+	}
+}
+
+impl From<pool::CreationError> for UploadError {
+	fn from(pool::CreationError::OutOfMemory(e): pool::CreationError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<pool::AllocError> for UploadError {
+	fn from(pool::AllocError::OutOfMemory(e): pool::AllocError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<semaphore::CreationError> for UploadError {
+	fn from(semaphore::CreationError::OutOfMemory(e): semaphore::CreationError) -> Self {
+		Self::OutOfMemory(e)
+	}
+}
+
+impl From<CopyError> for UploadError {
+	fn from(e: CopyError) -> Self {
+		Self::Copy(e)
+	}
+}
+
+impl From<SubmitError> for UploadError {
+	fn from(e: SubmitError) -> Self {
+		Self::Submit(e)
+	}
+}
+
+/// Pending upload.
+///
+/// Owns the staging buffer and the one-shot command buffer until the upload is done.
+/// Dropping a pending upload blocks until it completes.
+pub type Upload<'a> = once::Pending<'a, pool::raw::Buffer<RawPool>, semaphore::Raw>;
+
+/// Create a host visible and host coherent staging buffer filled with the given data.
+fn staging_buffer<A: Allocator>(queue: &Queue, allocator: &A, data: &[u8]) -> Result<Bound<A::Slot>, buffer::vec::Error> {
+	let inner = Unbound::new(allocator.device(), data.len() as u64, Usage::TransferSource, std::iter::once(queue))?;
+	let memory_requirements = inner.memory_requirements().filter_memory_types(allocator.device().physical_device(), |memory_type| {
+		memory_type.is_host_visible() && memory_type.is_host_coherent()
+	});
+
+	let slot = allocator.allocate(memory_requirements)?;
+	match slot.ptr() {
+		Some(ptr) => unsafe {
+			std::ptr::copy_nonoverlapping(data.as_ptr(), ptr as *mut u8, data.len())
+		},
+		None => return Err(mem::Error::Map(crate::device::memory::MapError::NotHostVisible).into())
+	}
+
+	unsafe {
+		inner.bind(slot).map_err(|(_, e)| e.into())
+	}
+}
+
+/// Allocate a one-shot command buffer for the family of the given queue.
+fn one_shot_buffer(queue: &Queue) -> Result<pool::raw::Buffer<RawPool>, UploadError> {
+	let pool = RawPool::new(queue.device(), queue.family(), pool::Flags { transient: true, ..pool::Flags::none() })?;
+	let handle = unsafe {
+		pool.allocate_into(1, pool::Level::Primary, |h| h)?.pop().unwrap()
+	};
+
+	// The buffer owns its pool, destroyed once the buffer is freed.
+	Ok(pool::raw::Buffer::new(pool, handle, pool::Level::Primary, 0))
+}
+
+/// Upload the given data into a new buffer.
+///
+/// The data is copied into a host visible staging buffer,
+/// then copied into a new buffer with the `dst_usage` and `TransferDestination` usages
+/// by a command buffer submitted to the given queue.
+/// A transfer queue is ideal.
+///
+/// Returns the destination buffer, only shared by the family of the given queue,
+/// and the pending upload which can be waited on or chained.
+/// The destination buffer must not be used before the upload is done.
+pub fn upload<A: Allocator, U: Into<Usages>>(queue: &Queue, allocator: &A, data: &[u8], dst_usage: U) -> Result<(Arc<Bound<A::Slot>>, Upload<'static>), UploadError> where A::Slot: Send + Sync {
+	if data.is_empty() {
+		return Err(UploadError::EmptyData)
+	}
+
+	let size = data.len() as u64;
+
+	let staging = staging_buffer(queue, allocator, data)?;
+	let dst = Arc::new(Bound::allocate(allocator, size, Usage::TransferDestination | dst_usage.into(), std::iter::once(queue))?);
+
+	let (submission, ()) = once::submit_buffer_once(queue, one_shot_buffer(queue)?, semaphore::Raw::new(queue.device())?, |recorder| {
+		recorder.copy_buffer(staging, dst.clone(), &[command::buffer::BufferCopy {
+			src_offset: 0,
+			dst_offset: 0,
+			size
+		}])
+	})?;
+
+	Ok((dst, submission))
+}
+
+/// Upload the given data into the first mip level and array layer of the color aspect of an image.
+///
+/// The image is transitioned from the `Undefined` layout to `TransferDstOptimal`,
+/// filled from a host visible staging buffer,
+/// then transitioned to `final_layout`, which becomes its tracked layout once submitted.
+/// The previous content of the image is discarded.
+///
+/// All the mip levels of the first array layer are transitioned,
+/// so that the other levels can then be filled with `generate_mipmaps`.
+///
+/// The image must have been created with the `transfer_destination` usage,
+/// and its data must be tightly packed.
+/// If the image is used by another queue family afterward, it must be shared with the family of the given queue.
+pub fn upload_image<'a, A: Allocator, I: ImageProperties>(queue: &Queue, allocator: &A, data: &[u8], image: &'a I, (width, height, depth): (u32, u32, u32), final_layout: image::Layout) -> Result<Upload<'a>, UploadError> where A::Slot: Send {
+	let subresource = SubresourceLayers {
+		aspects: Aspects::color(),
+		mip_level: 0,
+		base_array_layer: 0,
+		layer_count: 1
+	};
+
+	upload_regions(queue, allocator, data, image, image.mip_levels(), 1, &[BufferImageCopy {
+		buffer_offset: 0,
+		row_length: 0,
+		image_height: 0,
+		image_subresource: subresource,
+		offset: (0, 0, 0),
+		extent: (width, height, depth)
+	}], final_layout)
+}
+
+/// Upload a whole mip chain into the color aspect of an image.
+///
+/// The data contains every mip level in order, starting from the first,
+/// each level containing all the array layers of the image tightly packed.
+/// Like in KTX files, each level starts at an offset aligned to 4 bytes and to the format element size.
+/// Block compressed levels are counted in whole blocks,
+/// including the partial blocks at the edges of the levels.
+///
+/// The image is transitioned and filled as in `upload_image`.
+pub fn upload_mip_chain<'a, A: Allocator, I: ImageProperties>(queue: &Queue, allocator: &A, data: &[u8], image: &'a I, final_layout: image::Layout) -> Result<Upload<'a>, UploadError> where A::Slot: Send {
+	let (offsets, size) = mip_chain_offsets(image.format(), image.extent(), image.mip_levels(), image.array_layers())?;
+	if data.len() as u64 != size {
+		return Err(UploadError::InvalidDataSize { expected: size, given: data.len() as u64 })
+	}
+
+	let regions: Vec<_> = offsets.into_iter().enumerate().map(|(level, offset)| {
+		let level = level as u32;
+		BufferImageCopy {
+			buffer_offset: offset,
+			row_length: 0,
+			image_height: 0,
+			image_subresource: SubresourceLayers {
+				aspects: Aspects::color(),
+				..SubresourceLayers::mip(image, level)
+			},
+			offset: (0, 0, 0),
+			extent: image.mip_extent(level)
+		}
+	}).collect();
+
+	upload_regions(queue, allocator, data, image, image.mip_levels(), image.array_layers(), &regions, final_layout)
+}
+
+/// Offset of each level of a mip chain laid out as in `upload_mip_chain`,
+/// and the total size of the chain.
+fn mip_chain_offsets(format: Format, (width, height, depth): (u32, u32, u32), mip_levels: u32, array_layers: u32) -> Result<(Vec<u64>, u64), UploadError> {
+	let element_size = format.size().ok_or(UploadError::UnsupportedFormat(format))? as u64;
+	let mut alignment = element_size;
+	while alignment % 4 != 0 {
+		alignment += element_size
+	}
+
+	let mut offset = 0;
+	let mut offsets = Vec::with_capacity(mip_levels as usize);
+	for level in 0..mip_levels {
+		let extent = (
+			std::cmp::max(1, width >> level),
+			std::cmp::max(1, height >> level),
+			std::cmp::max(1, depth >> level)
+		);
+
+		offset = (offset + alignment - 1) / alignment * alignment;
+		offsets.push(offset);
+		offset += format.data_size(extent).unwrap() * array_layers as u64;
+	}
+
+	Ok((offsets, offset))
+}
+
+/// Upload the faces of the first mip level of a cube map, in the order +X, -X, +Y, -Y, +Z, -Z.
+///
+/// Each face is uploaded into the array layer of the same index,
+/// and must contain the whole tightly packed first mip level.
+///
+/// The image is transitioned and filled as in `upload_image`.
+pub fn upload_cube_faces<'a, A: Allocator, I: ImageProperties>(queue: &Queue, allocator: &A, faces: &[&[u8]; 6], image: &'a I, final_layout: image::Layout) -> Result<Upload<'a>, UploadError> where A::Slot: Send {
+	let format = image.format();
+	let face_size = format.data_size(image.extent()).ok_or(UploadError::UnsupportedFormat(format))?;
+	if let Some(face) = faces.iter().find(|face| face.len() as u64 != face_size) {
+		return Err(UploadError::InvalidDataSize { expected: face_size, given: face.len() as u64 })
+	}
+
+	let data = faces.concat();
+	let regions: Vec<_> = (0..6).map(|layer| BufferImageCopy {
+		buffer_offset: layer as u64 * face_size,
+		row_length: 0,
+		image_height: 0,
+		image_subresource: SubresourceLayers {
+			aspects: Aspects::color(),
+			mip_level: 0,
+			base_array_layer: layer,
+			layer_count: 1
+		},
+		offset: (0, 0, 0),
+		extent: image.extent()
+	}).collect();
+
+	upload_regions(queue, allocator, &data, image, 1, 6, &regions, final_layout)
+}
+
+/// Upload the given data into regions of the first mip levels and array layers of the color aspect of an image.
+fn upload_regions<'a, A: Allocator, I: ImageProperties>(queue: &Queue, allocator: &A, data: &[u8], image: &'a I, level_count: u32, layer_count: u32, regions: &[BufferImageCopy], final_layout: image::Layout) -> Result<Upload<'a>, UploadError> where A::Slot: Send {
+	if data.is_empty() {
+		return Err(UploadError::EmptyData)
+	}
+
+	let staging = staging_buffer(queue, allocator, data)?;
+
+	// Checked before submitting, since an invalid copy would only be reported once the submission is dropped.
+	command::buffer::check_buffer_to_image(&staging, image, regions)?;
+
+	let subresource_range = vk::ImageSubresourceRange {
+		aspect_mask: vk::ImageAspectFlags::COLOR,
+		base_mip_level: 0,
+		level_count,
+		base_array_layer: 0,
+		layer_count
+	};
+
+	let (submission, copy) = once::submit_buffer_once(queue, one_shot_buffer(queue)?, semaphore::Raw::new(queue.device())?, |recorder| {
+		unsafe {
+			recorder.image_barriers(vk::PipelineStageFlags::TOP_OF_PIPE, vk::PipelineStageFlags::TRANSFER, &[command::buffer::ImageMemoryBarrier {
+				src_access_mask: vk::AccessFlags::empty(),
+				dst_access_mask: vk::AccessFlags::TRANSFER_WRITE,
+				old_layout: vk::ImageLayout::UNDEFINED,
+				new_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+				src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				image: image.handle(),
+				subresource_range,
+				..Default::default()
+			}]);
+		}
+
+		let copy = recorder.copy_buffer_to_image(staging, image, image::Layout::TransferDstOptimal, regions);
+
+		// The image may be used by any following command.
+		unsafe {
+			recorder.image_barriers(vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::ALL_COMMANDS, &[command::buffer::ImageMemoryBarrier {
+				src_access_mask: vk::AccessFlags::TRANSFER_WRITE,
+				dst_access_mask: vk::AccessFlags::MEMORY_READ,
+				old_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+				new_layout: final_layout.into_vulkan(),
+				src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+				image: image.handle(),
+				subresource_range,
+				..Default::default()
+			}]);
+		}
+
+		if let Some(tracker) = image.layout_tracker() {
+			recorder.transitions.push((tracker, final_layout))
+		}
+
+		copy
+	})?;
+
+	copy?;
+	Ok(submission)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bc1_mip_chain_offsets() {
+		// 10x6 BC1 texture: 3x2, 2x1, 1x1 and 1x1 blocks of 8 bytes.
+		let (offsets, size) = mip_chain_offsets(Format::BC1_RGBAUnormBlock, (10, 6, 1), 4, 1).unwrap();
+		assert_eq!(offsets, vec![0, 48, 64, 72]);
+		assert_eq!(size, 80);
+
+		let (offsets, size) = mip_chain_offsets(Format::BC1_RGBAUnormBlock, (10, 6, 1), 4, 2).unwrap();
+		assert_eq!(offsets, vec![0, 96, 128, 144]);
+		assert_eq!(size, 160);
+	}
+
+	#[test]
+	fn mip_chain_offsets_alignment() {
+		// Levels of 3 bytes per texel formats are aligned to 12 bytes.
+		let (offsets, size) = mip_chain_offsets(Format::R8G8B8Unorm, (3, 1, 1), 2, 1).unwrap();
+		assert_eq!(offsets, vec![0, 12]);
+		assert_eq!(size, 15);
+	}
+
+	#[test]
+	fn mip_chain_offsets_unsupported_format() {
+		match mip_chain_offsets(Format::D24Unorm_S8Uint, (4, 4, 1), 1, 1) {
+			Err(UploadError::UnsupportedFormat(Format::D24Unorm_S8Uint)) => (),
+			_ => panic!("expected an unsupported format error")
+		}
+	}
+}
@@ -0,0 +1,180 @@
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::{
+	sync::Arc,
+	path::Path,
+	convert::TryInto,
+	fmt,
+	io
+};
+use crate::{
+	OomError,
+	Device,
+	DeviceOwned,
+	instance::PhysicalDevice
+};
+
+/// Size of the pipeline cache header, version one.
+const HEADER_SIZE: usize = 32;
+
+#[derive(Debug)]
+pub enum FileError {
+	Io(io::Error),
+	OutOfMemory(OomError)
+}
+
+impl From<io::Error> for FileError {
+	fn from(e: io::Error) -> Self {
+		FileError::Io(e)
+	}
+}
+
+impl From<OomError> for FileError {
+	fn from(e: OomError) -> Self {
+		FileError::OutOfMemory(e)
+	}
+}
+
+impl std::error::Error for FileError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Io(e) => Some(e),
+			Self::OutOfMemory(e) => Some(e)
+		}
+	}
+}
+
+impl fmt::Display for FileError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "pipeline cache I/O error: {}", e),
+			Self::OutOfMemory(e) => e.fmt(f)
+		}
+	}
+}
+
+fn oom_error(r: vk::Result) -> OomError {
+	match r {
+		vk::Result::ERROR_OUT_OF_HOST_MEMORY => OomError::Host,
+		vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => OomError::Device,
+		_ => unreachable!()
+	}
+}
+
+/// Checks that the given cache data has been produced by the same driver for the same physical device.
+pub fn is_compatible(physical_device: &PhysicalDevice, data: &[u8]) -> bool {
+	if data.len() < HEADER_SIZE {
+		return false
+	}
+
+	// Header fields are little-endian regardless of the host byte order.
+	let field = |i: usize| u32::from_le_bytes(data[(i * 4)..(i * 4 + 4)].try_into().unwrap());
+
+	let header_size = field(0) as usize;
+	header_size >= HEADER_SIZE
+		&& header_size <= data.len()
+		&& field(1) == vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32
+		&& field(2) == physical_device.vendor_id()
+		&& field(3) == physical_device.device_id()
+		&& data[16..32] == physical_device.pipeline_cache_uuid()
+}
+
+/// Pipeline cache.
+pub struct Cache {
+	device: Arc<Device>,
+	handle: vk::PipelineCache
+}
+
+impl Cache {
+	/// Create a pipeline cache, possibly filled with data retrieved by `Cache::data`.
+	///
+	/// The initial data is ignored if it has not been produced for this device.
+	pub fn new(device: &Arc<Device>, initial_data: Option<&[u8]>) -> Result<Cache, OomError> {
+		let initial_data = match initial_data {
+			Some(data) if is_compatible(device.physical_device(), data) => data,
+			Some(_) => {
+				warn!("incompatible pipeline cache data, starting with an empty cache");
+				&[]
+			},
+			None => &[]
+		};
+
+		let infos = vk::PipelineCacheCreateInfo {
+			initial_data_size: initial_data.len(),
+			p_initial_data: initial_data.as_ptr() as *const _,
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle().create_pipeline_cache(&infos, None).map_err(oom_error)?
+		};
+
+		Ok(Cache {
+			device: device.clone(),
+			handle
+		})
+	}
+
+	/// Create a pipeline cache from the data stored in the given file.
+	///
+	/// If the file does not exist or is incompatible with the device, the cache starts empty.
+	pub fn load_from_file<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> Result<Cache, FileError> {
+		let data = match std::fs::read(path) {
+			Ok(data) => Some(data),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+			Err(e) => return Err(e.into())
+		};
+
+		Ok(Self::new(device, data.as_deref())?)
+	}
+
+	/// Store the data of the cache in the given file, to be loaded by `Cache::load_from_file`.
+	pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), FileError> {
+		let data = self.data()?;
+		std::fs::write(path, data)?;
+		Ok(())
+	}
+
+	#[inline]
+	pub fn handle(&self) -> vk::PipelineCache {
+		self.handle
+	}
+
+	/// Data of the cache, starting with the header checked when creating a cache from it.
+	pub fn data(&self) -> Result<Vec<u8>, OomError> {
+		unsafe {
+			self.device.handle().get_pipeline_cache_data(self.handle).map_err(oom_error)
+		}
+	}
+
+	/// Merge the content of the given caches into this cache, and return it.
+	///
+	/// Taking the cache by value guarantees that no pipeline is being created with it during the merge.
+	pub fn merge(self, others: &[&Cache]) -> Result<Cache, OomError> {
+		let handles: Vec<_> = others.iter().map(|c| c.handle).collect();
+		let result = unsafe {
+			self.device.handle().fp_v1_0().merge_pipeline_caches(self.device.handle().handle(), self.handle, handles.len() as u32, handles.as_ptr())
+		};
+
+		match result {
+			vk::Result::SUCCESS => Ok(self),
+			r => Err(oom_error(r))
+		}
+	}
+}
+
+impl DeviceOwned for Cache {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for Cache {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_pipeline_cache(self.handle, None)
+		}
+	}
+}
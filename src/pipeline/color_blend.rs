@@ -53,7 +53,7 @@ impl BlendFactor {
 	}
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct ColorComponents {
 	red: bool,
 	green: bool,
@@ -62,6 +62,23 @@ pub struct ColorComponents {
 }
 
 impl ColorComponents {
+	pub fn new(red: bool, green: bool, blue: bool, alpha: bool) -> Self {
+		Self {
+			red,
+			green,
+			blue,
+			alpha
+		}
+	}
+
+	pub fn none() -> Self {
+		Self::new(false, false, false, false)
+	}
+
+	pub fn rgb() -> Self {
+		Self::new(true, true, true, false)
+	}
+
 	pub fn rgba() -> Self {
 		Self {
 			red: true,
@@ -94,6 +111,7 @@ impl ColorComponents {
 	}
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct AttachmentBlend {
 	pub source_color_factor: BlendFactor,
 	pub target_color_factor: BlendFactor,
@@ -133,10 +151,80 @@ impl AttachmentBlend {
 	}
 }
 
+#[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct Attachment(vk::PipelineColorBlendAttachmentState);
 
 impl Attachment {
+	/// No blending, all the components are written.
+	pub fn disabled() -> Attachment {
+		Self::new(None, ColorComponents::rgba())
+	}
+
+	/// Standard alpha blending: `src.rgb * src.a + dst.rgb * (1 - src.a)`.
+	pub fn alpha_blend() -> Attachment {
+		Self::new(Some(AttachmentBlend::new(
+			BlendFactor::SourceAlpha,
+			BlendFactor::OneMinusSourceAlpha,
+			Operation::Add,
+			BlendFactor::One,
+			BlendFactor::OneMinusSourceAlpha,
+			Operation::Add
+		)), ColorComponents::rgba())
+	}
+
+	/// Additive blending: `src.rgb * src.a + dst.rgb`.
+	pub fn additive() -> Attachment {
+		Self::new(Some(AttachmentBlend::new(
+			BlendFactor::SourceAlpha,
+			BlendFactor::One,
+			Operation::Add,
+			BlendFactor::One,
+			BlendFactor::One,
+			Operation::Add
+		)), ColorComponents::rgba())
+	}
+
+	/// Blending of colors with premultiplied alpha: `src.rgb + dst.rgb * (1 - src.a)`.
+	pub fn premultiplied_alpha() -> Attachment {
+		Self::new(Some(AttachmentBlend::new(
+			BlendFactor::One,
+			BlendFactor::OneMinusSourceAlpha,
+			Operation::Add,
+			BlendFactor::One,
+			BlendFactor::OneMinusSourceAlpha,
+			Operation::Add
+		)), ColorComponents::rgba())
+	}
+
+	/// Enable blending with the given color factors and operation.
+	pub fn with_color_blend(mut self, source: BlendFactor, target: BlendFactor, operation: Operation) -> Self {
+		self.0.blend_enable = vk::TRUE;
+		self.0.src_color_blend_factor = source.into_vulkan();
+		self.0.dst_color_blend_factor = target.into_vulkan();
+		self.0.color_blend_op = operation.into_vulkan();
+		self
+	}
+
+	/// Enable blending with the given alpha factors and operation.
+	pub fn with_alpha_blend(mut self, source: BlendFactor, target: BlendFactor, operation: Operation) -> Self {
+		self.0.blend_enable = vk::TRUE;
+		self.0.src_alpha_blend_factor = source.into_vulkan();
+		self.0.dst_alpha_blend_factor = target.into_vulkan();
+		self.0.alpha_blend_op = operation.into_vulkan();
+		self
+	}
+
+	pub fn with_write_mask(mut self, components: ColorComponents) -> Self {
+		self.0.color_write_mask = components.into_vulkan();
+		self
+	}
+
+	#[inline]
+	pub fn is_blend_enabled(&self) -> bool {
+		self.0.blend_enable == vk::TRUE
+	}
+
 	pub fn new(
 		blend: Option<AttachmentBlend>,
 		color_write_components: ColorComponents
@@ -155,6 +243,20 @@ impl Attachment {
 	}
 }
 
+impl PartialEq for Attachment {
+	fn eq(&self, other: &Attachment) -> bool {
+		let (a, b) = (&self.0, &other.0);
+		a.blend_enable == b.blend_enable
+			&& a.src_color_blend_factor == b.src_color_blend_factor
+			&& a.dst_color_blend_factor == b.dst_color_blend_factor
+			&& a.color_blend_op == b.color_blend_op
+			&& a.src_alpha_blend_factor == b.src_alpha_blend_factor
+			&& a.dst_alpha_blend_factor == b.dst_alpha_blend_factor
+			&& a.alpha_blend_op == b.alpha_blend_op
+			&& a.color_write_mask == b.color_write_mask
+	}
+}
+
 pub struct ColorBlend {
 	attachments: Vec<Attachment>,
 	inner: vk::PipelineColorBlendStateCreateInfo
@@ -187,6 +289,47 @@ impl ColorBlend {
 		self
 	}
 
+	/// Color blend state writing all the components of the given number of attachments, without blending.
+	pub fn opaque(attachment_count: u32) -> ColorBlend {
+		Self::replicated(Attachment::disabled(), attachment_count)
+	}
+
+	/// Color blend state using the same attachment state for the given number of attachments.
+	pub fn replicated(attachment: Attachment, attachment_count: u32) -> ColorBlend {
+		let mut color_blend = Self::new(None, [0.0; 4]);
+		for _ in 0..attachment_count {
+			color_blend.add_attachment(attachment)
+		}
+
+		color_blend
+	}
+
+	pub fn with_logic_operation(mut self, logic_operation: Option<ops::Logic>) -> Self {
+		self.inner.logic_op_enable = if logic_operation.is_some() { vk::TRUE } else { vk::FALSE };
+		self.inner.logic_op = logic_operation.map(|o| o.into_vulkan()).unwrap_or_default();
+		self
+	}
+
+	pub fn with_blend_constants(mut self, blend_constants: [f32; 4]) -> Self {
+		self.inner.blend_constants = blend_constants;
+		self
+	}
+
+	#[inline]
+	pub fn is_logic_operation_enabled(&self) -> bool {
+		self.inner.logic_op_enable == vk::TRUE
+	}
+
+	/// Checks that all the attachments have the same state.
+	pub fn is_uniform(&self) -> bool {
+		self.attachments.windows(2).all(|w| w[0] == w[1])
+	}
+
+	#[inline]
+	pub fn attachment_count(&self) -> u32 {
+		self.attachments.len() as u32
+	}
+
 	pub(crate) fn as_vulkan(&self) -> &vk::PipelineColorBlendStateCreateInfo {
 		&self.inner
 	}
@@ -0,0 +1,210 @@
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::{
+	sync::Arc,
+	fmt
+};
+use crate::{
+	OomError,
+	Device,
+	Resource
+};
+use super::{
+	shader,
+	Specialization,
+	Cache,
+	Layout
+};
+
+#[derive(Debug)]
+pub enum CreationError {
+	OomError(OomError),
+	InvalidShader,
+	CompileRequired,
+
+	/// The entry point is not a compute shader entry point.
+	NotComputeStage(shader::Stage)
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(e: vk::Result) -> Self {
+		match e {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OomError(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OomError(OomError::Device),
+			vk::Result::ERROR_INVALID_SHADER_NV => CreationError::InvalidShader,
+			vk::Result::ERROR_PIPELINE_COMPILE_REQUIRED_EXT => CreationError::CompileRequired,
+			_ => unreachable!()
+		}
+	}
+}
+
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::OomError(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OomError(e) => e.fmt(f),
+			Self::InvalidShader => write!(f, "invalid shader"),
+			Self::CompileRequired => write!(f, "pipeline compilation required"),
+			Self::NotComputeStage(stage) => write!(f, "expected a compute shader entry point, found a {:?} entry point", stage)
+		}
+	}
+}
+
+pub trait ComputePipeline: Resource<Handle=vk::Pipeline> {
+	type Layout: Layout;
+
+	fn layout(&self) -> &Self::Layout;
+}
+
+/// Compute pipeline.
+///
+/// Doubling the values of a storage buffer with the `examples/shaders/double.comp` kernel,
+/// and reading them back:
+/// ```no_run
+/// # use std::{sync::Arc, rc::Rc};
+/// # use ash::vk;
+/// # use magma::{Device, command::{self, Buffer as _}, mem::{self, Slot}, ops, pipeline::{self, ComputePipeline, shader, descriptor, layout}, sync::{Task, fence, future::SignalFence}};
+/// # fn run(device: &Arc<Device>, queue: &magma::device::Queue) {
+/// const LEN: u32 = 256;
+/// const LOCAL_SIZE: u32 = 64;
+/// const SIZE: u64 = LEN as u64 * 4;
+///
+/// let code = std::fs::read("examples/shaders/double.comp.spv").unwrap();
+/// let set_layout = descriptor::SetLayout::builder()
+/// 	.binding(0, descriptor::Type::StorageBuffer, shader::Stages::compute())
+/// 	.build(device)
+/// 	.unwrap();
+/// let layout = layout::Raw::new(device, &[&set_layout], &[]).unwrap();
+/// // The SPIR-V code is not validated.
+/// let module = Arc::new(unsafe { shader::Module::from_bytes(device, &code) }.unwrap());
+/// let entry_point = unsafe { module.entry_point("main", shader::Stage::Compute) };
+/// let pipeline = Arc::new(pipeline::Compute::new(device, &entry_point, layout, None, None).unwrap());
+///
+/// let allocator = mem::staging::Allocator::new(mem::Unbounded::new(device));
+/// let buffer = Arc::new(mem::buffer::Bound::allocate(
+/// 	&allocator,
+/// 	SIZE,
+/// 	mem::buffer::Usage::StorageBuffer | mem::buffer::Usage::TransferSource,
+/// 	std::iter::once(queue)
+/// ).unwrap());
+///
+/// let values: Vec<u32> = (0..LEN).collect();
+/// unsafe {
+/// 	let ptr = buffer.memory_slot().ptr().unwrap();
+/// 	std::ptr::copy_nonoverlapping(values.as_ptr(), ptr as *mut u32, values.len());
+/// }
+///
+/// let pool = Arc::new(descriptor::Pool::for_layouts(device, &[(&set_layout, 1)]).unwrap());
+/// let set = Arc::new(pool.allocate(&set_layout).unwrap());
+/// unsafe {
+/// 	set.update(&[descriptor::Write::storage_buffer(0, &buffer, 0..SIZE)]);
+/// }
+///
+/// let command_pool = Rc::new(command::pool::Raw::new(device, queue.family(), command::pool::Flags::none()).unwrap());
+/// let command_buffer = command_pool.allocate_rc(1, command::pool::Level::Primary).unwrap().pop().unwrap();
+/// let mut recorder = command_buffer.begin_local(command::buffer::Usage::OneTimeSubmit).unwrap();
+/// recorder.bind_compute_pipeline(&pipeline);
+/// recorder.bind_compute_descriptor_sets(pipeline.layout(), 0, &[set.clone()], &[]).unwrap();
+/// // One invocation per value.
+/// recorder.dispatch(LEN / LOCAL_SIZE, 1, 1);
+/// // Make the shader writes visible to the readback copy.
+/// recorder.memory_barrier(vk::PipelineStageFlags::COMPUTE_SHADER, vk::PipelineStageFlags::TRANSFER, vk::AccessFlags::SHADER_WRITE, vk::AccessFlags::TRANSFER_READ);
+/// let executable = recorder.finish().unwrap();
+///
+/// let fence = fence::Raw::new(device).unwrap();
+/// let ((), done) = queue.submit(executable).then_signal_fence(&fence).unwrap();
+/// done.wait(None).unwrap();
+///
+/// let bytes = ops::readback_buffer(queue, &*buffer, 0..SIZE).unwrap().wait_data(None).unwrap();
+/// let doubled = bytes.chunks_exact(4).map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]));
+/// assert!(doubled.zip(&values).all(|(d, v)| d == v * 2));
+/// # }
+/// ```
+pub struct Compute<L: Layout> {
+	device: Arc<Device>,
+	handle: vk::Pipeline,
+	layout: L
+}
+
+impl<L: Layout> Compute<L> {
+	/// Creates a new compute pipeline.
+	///
+	/// The entry point must be a compute shader entry point.
+	pub fn new(device: &Arc<Device>, entry_point: &shader::EntryPoint, layout: L, specialization: Option<&Specialization>, cache: Option<&Cache>) -> Result<Compute<L>, CreationError> {
+		if entry_point.stage() != shader::Stage::Compute {
+			return Err(CreationError::NotComputeStage(entry_point.stage()))
+		}
+
+		let vk_specialization = specialization.map(|s| s.as_vulkan());
+
+		// The shader module is only needed during the creation.
+		let stage = vk::PipelineShaderStageCreateInfo {
+			stage: vk::ShaderStageFlags::COMPUTE,
+			module: entry_point.module().handle(),
+			p_name: entry_point.name().as_ptr(),
+			p_specialization_info: vk_specialization.as_ref().map(|s| s as *const _).unwrap_or(std::ptr::null()),
+			..Default::default()
+		};
+
+		let infos = vk::ComputePipelineCreateInfo {
+			stage,
+			layout: layout.handle(),
+			base_pipeline_handle: vk::Pipeline::null(),
+			base_pipeline_index: -1,
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			match device.handle().create_compute_pipelines(cache.map(|c| c.handle()).unwrap_or(vk::PipelineCache::null()), &[infos], None) {
+				Ok(handles) => handles.into_iter().next().unwrap(),
+				Err((handles, e)) => {
+					for handle in handles {
+						device.handle().destroy_pipeline(handle, None);
+					}
+
+					return Err(e.into())
+				}
+			}
+		};
+
+		Ok(Self {
+			device: device.clone(),
+			handle,
+			layout
+		})
+	}
+}
+
+unsafe impl<L: Layout> crate::Resource for Compute<L> {
+	type Handle = vk::Pipeline;
+
+	fn handle(&self) -> vk::Pipeline {
+		self.handle
+	}
+}
+
+impl<L: Layout> ComputePipeline for Compute<L> {
+	type Layout = L;
+
+	fn layout(&self) -> &L {
+		&self.layout
+	}
+}
+
+impl<L: Layout> Drop for Compute<L> {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_pipeline(self.handle, None)
+		}
+	}
+}
@@ -0,0 +1,130 @@
+use ash::vk;
+use std::fmt;
+use crate::ops;
+use super::{
+	DepthTest,
+	StencilTest
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthBoundsError {
+	/// The depth bounds test is part of the depth test, which is disabled.
+	DepthTestDisabled,
+
+	/// The minimum bound is greater than the maximum bound, or one of them is NaN.
+	InvalidBounds
+}
+
+impl fmt::Display for DepthBoundsError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::DepthTestDisabled => write!(f, "depth bounds without depth test"),
+			Self::InvalidBounds => write!(f, "invalid depth bounds")
+		}
+	}
+}
+
+impl std::error::Error for DepthBoundsError { }
+
+/// Depth and stencil tests configuration.
+///
+/// When the stencil reference, compare mask or write mask is a dynamic state of the pipeline,
+/// the corresponding values of the stencil tests are ignored and must be set when recording.
+/// The same goes for the depth bounds.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DepthStencil {
+	/// Depth test, disabled if `None`.
+	pub depth_test: Option<DepthTest>,
+
+	/// Front and back faces stencil tests, disabled if `None`.
+	pub stencil_tests: Option<(StencilTest, StencilTest)>
+}
+
+impl DepthStencil {
+	/// No depth nor stencil test.
+	pub fn disabled() -> Self {
+		DepthStencil::default()
+	}
+
+	/// Depth test keeping the closest fragments, writing their depth, without stencil test.
+	pub fn simple_depth_test() -> Self {
+		DepthStencil {
+			depth_test: Some(DepthTest::new(true, ops::Compare::Less, None)),
+			stencil_tests: None
+		}
+	}
+
+	pub fn with_depth_test(mut self, depth_test: DepthTest) -> Self {
+		self.depth_test = Some(depth_test);
+		self
+	}
+
+	/// Enable the depth bounds test.
+	///
+	/// Requires the `DepthBounds` device feature.
+	/// Returns an error if the depth test is disabled, or if `min` is not lower or equal to `max`.
+	pub fn with_depth_bounds(mut self, min: f32, max: f32) -> Result<Self, DepthBoundsError> {
+		let depth_test = self.depth_test.as_mut().ok_or(DepthBoundsError::DepthTestDisabled)?;
+
+		if !(min <= max) {
+			return Err(DepthBoundsError::InvalidBounds)
+		}
+
+		depth_test.bounds = Some((min, max));
+		Ok(self)
+	}
+
+	pub fn with_stencil_tests(mut self, front: StencilTest, back: StencilTest) -> Self {
+		self.stencil_tests = Some((front, back));
+		self
+	}
+
+	/// Checks if the depth bounds test is enabled.
+	pub fn uses_depth_bounds(&self) -> bool {
+		self.depth_test.map(|t| t.bounds.is_some()).unwrap_or(false)
+	}
+
+	pub(crate) fn into_vulkan(self) -> Option<vk::PipelineDepthStencilStateCreateInfo> {
+		if self.depth_test.is_none() && self.stencil_tests.is_none() {
+			return None
+		}
+
+		let mut infos = vk::PipelineDepthStencilStateCreateInfo::default();
+
+		if let Some(depth_test) = self.depth_test {
+			depth_test.set_vulkan(&mut infos);
+		}
+
+		if let Some((front, back)) = self.stencil_tests {
+			infos.stencil_test_enable = vk::TRUE;
+			front.set_vulkan(&mut infos.front);
+			back.set_vulkan(&mut infos.back);
+		}
+
+		Some(infos)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn depth_bounds_require_depth_test() {
+		assert_eq!(DepthStencil::disabled().with_depth_bounds(0.0, 1.0).err(), Some(DepthBoundsError::DepthTestDisabled));
+
+		let depth_stencil = DepthStencil::simple_depth_test().with_depth_bounds(0.25, 0.75).unwrap();
+		assert!(depth_stencil.uses_depth_bounds());
+
+		let infos = depth_stencil.into_vulkan().unwrap();
+		assert_eq!(infos.depth_bounds_test_enable, vk::TRUE);
+		assert_eq!((infos.min_depth_bounds, infos.max_depth_bounds), (0.25, 0.75));
+	}
+
+	#[test]
+	fn invalid_depth_bounds() {
+		assert_eq!(DepthStencil::simple_depth_test().with_depth_bounds(0.75, 0.25).err(), Some(DepthBoundsError::InvalidBounds));
+		assert_eq!(DepthStencil::simple_depth_test().with_depth_bounds(std::f32::NAN, 1.0).err(), Some(DepthBoundsError::InvalidBounds));
+		assert!(DepthStencil::simple_depth_test().with_depth_bounds(0.5, 0.5).is_ok());
+	}
+}
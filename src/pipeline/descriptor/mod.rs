@@ -0,0 +1,16 @@
+mod ty;
+pub mod set_layout;
+pub mod pool;
+mod write;
+
+pub use ty::Type;
+pub use set_layout::{
+	SetLayout,
+	Binding
+};
+pub use pool::{
+	Pool,
+	Set,
+	AllocError
+};
+pub use write::Write;
@@ -0,0 +1,342 @@
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::{
+	sync::{
+		Arc,
+		atomic::{
+			AtomicU64,
+			Ordering
+		}
+	},
+	collections::HashMap,
+	fmt
+};
+use parking_lot::Mutex;
+use crate::{
+	OomError,
+	Device,
+	DeviceOwned
+};
+use super::{
+	Type,
+	SetLayout,
+	Write,
+	write::{
+		Infos,
+		Descriptor
+	}
+};
+
+#[derive(Debug)]
+pub enum CreationError {
+	OutOfMemory(OomError),
+	Fragmentation
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
+			vk::Result::ERROR_FRAGMENTATION_EXT => CreationError::Fragmentation,
+			_ => unreachable!()
+		}
+	}
+}
+
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::Fragmentation => write!(f, "memory fragmentation")
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum AllocError {
+	OutOfMemory(OomError),
+
+	/// The pool has not enough descriptors or sets left.
+	OutOfPoolMemory,
+
+	/// The pool has enough descriptors left, but they are too fragmented.
+	FragmentedPool
+}
+
+impl From<vk::Result> for AllocError {
+	fn from(r: vk::Result) -> AllocError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => AllocError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => AllocError::OutOfMemory(OomError::Device),
+			vk::Result::ERROR_OUT_OF_POOL_MEMORY => AllocError::OutOfPoolMemory,
+			vk::Result::ERROR_FRAGMENTED_POOL => AllocError::FragmentedPool,
+			_ => unreachable!()
+		}
+	}
+}
+
+impl std::error::Error for AllocError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for AllocError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::OutOfPoolMemory => write!(f, "out of descriptor pool memory"),
+			Self::FragmentedPool => write!(f, "fragmented descriptor pool")
+		}
+	}
+}
+
+/// Descriptor pool.
+///
+/// Sets are not freed individually: they are all invalidated at once when the pool is reset.
+pub struct Pool {
+	device: Arc<Device>,
+	handle: vk::DescriptorPool,
+
+	/// Synchronizes the accesses to the pool.
+	lock: Mutex<()>,
+
+	/// Number of times the pool has been reset.
+	generation: AtomicU64
+}
+
+impl Pool {
+	/// Create a pool of at most `max_sets` sets, with the given number of descriptors for each type.
+	pub fn new(device: &Arc<Device>, max_sets: u32, pool_sizes: &[(Type, u32)]) -> Result<Pool, CreationError> {
+		let vk_pool_sizes: Vec<_> = pool_sizes.iter().map(|(ty, count)| {
+			vk::DescriptorPoolSize {
+				ty: ty.into_vulkan(),
+				descriptor_count: *count
+			}
+		}).collect();
+
+		let infos = vk::DescriptorPoolCreateInfo {
+			max_sets,
+			pool_size_count: vk_pool_sizes.len() as u32,
+			p_pool_sizes: vk_pool_sizes.as_ptr(),
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle().create_descriptor_pool(&infos, None)?
+		};
+
+		Ok(Pool {
+			device: device.clone(),
+			handle,
+			lock: Mutex::new(()),
+			generation: AtomicU64::new(0)
+		})
+	}
+
+	/// Create a pool large enough to allocate the given number of sets of each layout.
+	pub fn for_layouts(device: &Arc<Device>, layouts: &[(&SetLayout, u32)]) -> Result<Pool, CreationError> {
+		let mut max_sets = 0;
+		let mut pool_sizes: Vec<(Type, u32)> = Vec::new();
+		for (layout, count) in layouts {
+			max_sets += count;
+			for (ty, n) in layout.descriptor_counts() {
+				match pool_sizes.iter_mut().find(|(t, _)| t == ty) {
+					Some((_, total)) => *total += n * count,
+					None => pool_sizes.push((*ty, n * count))
+				}
+			}
+		}
+
+		Self::new(device, max_sets, &pool_sizes)
+	}
+
+	#[inline]
+	pub fn handle(&self) -> vk::DescriptorPool {
+		self.handle
+	}
+
+	/// Allocate a descriptor set with the given layout.
+	pub fn allocate(self: &Arc<Self>, layout: &SetLayout) -> Result<Set, AllocError> {
+		let _lock = self.lock.lock();
+
+		let layouts = [layout.handle()];
+		let infos = vk::DescriptorSetAllocateInfo {
+			descriptor_pool: self.handle,
+			descriptor_set_count: 1,
+			p_set_layouts: layouts.as_ptr(),
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			self.device.handle().allocate_descriptor_sets(&infos)?[0]
+		};
+
+		Ok(Set {
+			pool: self.clone(),
+			layout: layout.clone(),
+			handle,
+			generation: self.generation(),
+			descriptors: Mutex::new(HashMap::new())
+		})
+	}
+
+	/// Reset the pool, invalidating every set allocated from it.
+	///
+	/// Invalidated sets are detected in debug builds when used.
+	///
+	/// ## Safety
+	///
+	/// The sets allocated from this pool must not be in use by the device.
+	pub unsafe fn reset(&self) -> Result<(), OomError> {
+		let _lock = self.lock.lock();
+
+		match self.device.handle().reset_descriptor_pool(self.handle, vk::DescriptorPoolResetFlags::empty()) {
+			Ok(()) => (),
+			Err(vk::Result::ERROR_OUT_OF_HOST_MEMORY) => return Err(OomError::Host),
+			Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY) => return Err(OomError::Device),
+			Err(_) => unreachable!()
+		}
+
+		self.generation.fetch_add(1, Ordering::Relaxed);
+		Ok(())
+	}
+
+	fn generation(&self) -> u64 {
+		self.generation.load(Ordering::Relaxed)
+	}
+}
+
+impl DeviceOwned for Pool {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for Pool {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_descriptor_pool(self.handle, None)
+		}
+	}
+}
+
+/// Descriptor set allocated from a pool.
+pub struct Set {
+	pool: Arc<Pool>,
+	layout: SetLayout,
+	handle: vk::DescriptorSet,
+
+	/// Generation of the pool at allocation time.
+	generation: u64,
+
+	/// Resources written in the set, for each binding and array element.
+	descriptors: Mutex<HashMap<(u32, u32), Descriptor>>
+}
+
+impl Set {
+	#[inline]
+	pub fn pool(&self) -> &Arc<Pool> {
+		&self.pool
+	}
+
+	#[inline]
+	pub fn layout(&self) -> &SetLayout {
+		&self.layout
+	}
+
+	/// Checks that the pool has not been reset since the set was allocated.
+	#[inline]
+	pub fn is_valid(&self) -> bool {
+		self.pool.generation() == self.generation
+	}
+
+	/// Handle of the set.
+	///
+	/// # Panic
+	///
+	/// In debug builds, panics if the pool has been reset since the set was allocated.
+	#[inline]
+	pub fn handle(&self) -> vk::DescriptorSet {
+		debug_assert!(self.is_valid(), "descriptor set used after its pool was reset");
+		self.handle
+	}
+
+	/// Write descriptors in the set.
+	///
+	/// The written resources are kept alive by the set until they are overwritten.
+	/// In debug builds, the writes are checked against the set layout.
+	///
+	/// ## Safety
+	///
+	/// The set must not be in use by the device.
+	pub unsafe fn update(&self, writes: &[Write]) {
+		let mut descriptors = self.descriptors.lock();
+
+		if cfg!(debug_assertions) {
+			for w in writes {
+				let binding = self.layout.binding(w.binding).unwrap_or_else(|| panic!("undefined descriptor binding {}", w.binding));
+				assert!(binding.ty() == w.ty, "{:?} descriptors written in a {:?} binding", w.ty, binding.ty());
+				assert!(w.first_element + w.count() <= binding.count(), "descriptor write out of binding {}", w.binding);
+				assert!(binding.immutable_samplers().is_empty() || w.ty != Type::Sampler, "write to an immutable sampler binding");
+			}
+		}
+
+		let vk_writes: Vec<_> = writes.iter().map(|w| {
+			let mut write = vk::WriteDescriptorSet {
+				dst_set: self.handle(),
+				dst_binding: w.binding,
+				dst_array_element: w.first_element,
+				descriptor_count: w.count(),
+				descriptor_type: w.ty.into_vulkan(),
+				..Default::default()
+			};
+
+			match &w.infos {
+				Infos::Buffers(infos) => write.p_buffer_info = infos.as_ptr(),
+				Infos::Images(infos) => write.p_image_info = infos.as_ptr(),
+				Infos::TexelBuffers(views) => write.p_texel_buffer_view = views.as_ptr()
+			}
+
+			write
+		}).collect();
+
+		// The info arrays are borrowed from `writes` and outlive the call.
+		self.pool.device.handle().update_descriptor_sets(&vk_writes, &[]);
+
+		for w in writes {
+			for (i, resource) in w.resources.iter().enumerate() {
+				descriptors.insert((w.binding, w.first_element + i as u32), resource.clone());
+			}
+		}
+	}
+}
+
+impl DeviceOwned for Set {
+	fn device(&self) -> &Arc<Device> {
+		&self.pool.device
+	}
+}
+
+unsafe impl crate::Resource for Set {
+	type Handle = vk::DescriptorSet;
+
+	fn handle(&self) -> vk::DescriptorSet {
+		Set::handle(self)
+	}
+}
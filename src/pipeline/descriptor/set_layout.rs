@@ -0,0 +1,267 @@
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::{
+	sync::Arc,
+	fmt
+};
+use crate::{
+	OomError,
+	Device,
+	DeviceOwned,
+	image::Sampler,
+	pipeline::shader
+};
+use super::Type;
+
+#[derive(Debug)]
+pub enum CreationError {
+	OutOfMemory(OomError),
+
+	/// The same binding number is used twice.
+	DuplicateBinding(u32),
+
+	/// Immutable samplers are given for a binding that is not a `Sampler` or `CombinedImageSampler` binding.
+	InvalidImmutableSamplers(u32, Type)
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::DuplicateBinding(b) => write!(f, "descriptor binding {} is defined twice", b),
+			Self::InvalidImmutableSamplers(b, ty) => write!(f, "immutable samplers given for binding {} of type {:?}", b, ty)
+		}
+	}
+}
+
+/// Descriptor set layout binding.
+#[derive(Clone)]
+pub struct Binding {
+	binding: u32,
+	ty: Type,
+	count: u32,
+	stages: shader::Stages,
+	immutable_samplers: Vec<Arc<Sampler>>
+}
+
+impl Binding {
+	#[inline]
+	pub fn binding(&self) -> u32 {
+		self.binding
+	}
+
+	#[inline]
+	pub fn ty(&self) -> Type {
+		self.ty
+	}
+
+	/// Number of descriptors in the binding.
+	#[inline]
+	pub fn count(&self) -> u32 {
+		self.count
+	}
+
+	#[inline]
+	pub fn stages(&self) -> shader::Stages {
+		self.stages
+	}
+
+	#[inline]
+	pub fn immutable_samplers(&self) -> &[Arc<Sampler>] {
+		&self.immutable_samplers
+	}
+}
+
+/// Descriptor set layout builder.
+#[derive(Default)]
+pub struct Builder {
+	bindings: Vec<Binding>
+}
+
+impl Builder {
+	pub fn new() -> Builder {
+		Builder::default()
+	}
+
+	/// Add a binding with a single descriptor.
+	pub fn binding(self, binding: u32, ty: Type, stages: shader::Stages) -> Self {
+		self.array(binding, ty, 1, stages)
+	}
+
+	/// Bindings added so far.
+	#[inline]
+	pub fn bindings(&self) -> &[Binding] {
+		&self.bindings
+	}
+
+	/// Add a binding with an array of `count` descriptors.
+	pub fn array(mut self, binding: u32, ty: Type, count: u32, stages: shader::Stages) -> Self {
+		self.bindings.push(Binding {
+			binding,
+			ty,
+			count,
+			stages,
+			immutable_samplers: Vec::new()
+		});
+		self
+	}
+
+	/// Add a `Sampler` or `CombinedImageSampler` binding with immutable samplers,
+	/// one descriptor per sampler.
+	pub fn immutable_samplers(mut self, binding: u32, ty: Type, samplers: &[Arc<Sampler>], stages: shader::Stages) -> Self {
+		self.bindings.push(Binding {
+			binding,
+			ty,
+			count: samplers.len() as u32,
+			stages,
+			immutable_samplers: samplers.to_vec()
+		});
+		self
+	}
+
+	pub fn build(self, device: &Arc<Device>) -> Result<SetLayout, CreationError> {
+		for (i, b) in self.bindings.iter().enumerate() {
+			if self.bindings[(i+1)..].iter().any(|other| other.binding == b.binding) {
+				return Err(CreationError::DuplicateBinding(b.binding))
+			}
+
+			if !b.immutable_samplers.is_empty() && !b.ty.is_sampler() {
+				return Err(CreationError::InvalidImmutableSamplers(b.binding, b.ty))
+			}
+		}
+
+		// The sampler handle arrays must live until the layout is created.
+		let sampler_handles: Vec<Vec<vk::Sampler>> = self.bindings.iter().map(|b| {
+			b.immutable_samplers.iter().map(|s| s.handle()).collect()
+		}).collect();
+
+		let vk_bindings: Vec<_> = self.bindings.iter().zip(&sampler_handles).map(|(b, samplers)| {
+			vk::DescriptorSetLayoutBinding {
+				binding: b.binding,
+				descriptor_type: b.ty.into_vulkan(),
+				descriptor_count: b.count,
+				stage_flags: b.stages.into_vulkan(),
+				p_immutable_samplers: if samplers.is_empty() { std::ptr::null() } else { samplers.as_ptr() }
+			}
+		}).collect();
+
+		let infos = vk::DescriptorSetLayoutCreateInfo {
+			flags: vk::DescriptorSetLayoutCreateFlags::empty(),
+			binding_count: vk_bindings.len() as u32,
+			p_bindings: vk_bindings.as_ptr(),
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle().create_descriptor_set_layout(&infos, None)?
+		};
+
+		let mut descriptor_counts: Vec<(Type, u32)> = Vec::new();
+		for b in &self.bindings {
+			match descriptor_counts.iter_mut().find(|(ty, _)| *ty == b.ty) {
+				Some((_, count)) => *count += b.count,
+				None => descriptor_counts.push((b.ty, b.count))
+			}
+		}
+
+		Ok(SetLayout(Arc::new(Inner {
+			device: device.clone(),
+			handle,
+			bindings: self.bindings,
+			descriptor_counts
+		})))
+	}
+}
+
+struct Inner {
+	device: Arc<Device>,
+	handle: vk::DescriptorSetLayout,
+	bindings: Vec<Binding>,
+	descriptor_counts: Vec<(Type, u32)>
+}
+
+/// Descriptor set layout.
+///
+/// Cloning a set layout is cheap: clones share the same Vulkan object.
+#[derive(Clone)]
+pub struct SetLayout(Arc<Inner>);
+
+impl SetLayout {
+	#[inline]
+	pub fn builder() -> Builder {
+		Builder::new()
+	}
+
+	#[inline]
+	pub fn handle(&self) -> vk::DescriptorSetLayout {
+		self.0.handle
+	}
+
+	#[inline]
+	pub fn bindings(&self) -> &[Binding] {
+		&self.0.bindings
+	}
+
+	/// Binding with the given number, if any.
+	#[inline]
+	pub fn binding(&self, binding: u32) -> Option<&Binding> {
+		self.0.bindings.iter().find(|b| b.binding == binding)
+	}
+
+	/// Total number of descriptors of each type in a set with this layout.
+	#[inline]
+	pub fn descriptor_counts(&self) -> &[(Type, u32)] {
+		&self.0.descriptor_counts
+	}
+}
+
+impl PartialEq for SetLayout {
+	fn eq(&self, other: &SetLayout) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+}
+
+impl Eq for SetLayout {}
+
+impl DeviceOwned for SetLayout {
+	fn device(&self) -> &Arc<Device> {
+		&self.0.device
+	}
+}
+
+unsafe impl crate::Resource for SetLayout {
+	type Handle = vk::DescriptorSetLayout;
+
+	fn handle(&self) -> vk::DescriptorSetLayout {
+		self.0.handle
+	}
+}
+
+impl Drop for Inner {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_descriptor_set_layout(self.handle, None);
+		}
+	}
+}
@@ -0,0 +1,34 @@
+use ash::vk;
+
+/// Descriptor type.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(i32)]
+pub enum Type {
+	Sampler = vk::DescriptorType::SAMPLER.as_raw(),
+	CombinedImageSampler = vk::DescriptorType::COMBINED_IMAGE_SAMPLER.as_raw(),
+	SampledImage = vk::DescriptorType::SAMPLED_IMAGE.as_raw(),
+	StorageImage = vk::DescriptorType::STORAGE_IMAGE.as_raw(),
+	UniformTexelBuffer = vk::DescriptorType::UNIFORM_TEXEL_BUFFER.as_raw(),
+	StorageTexelBuffer = vk::DescriptorType::STORAGE_TEXEL_BUFFER.as_raw(),
+	UniformBuffer = vk::DescriptorType::UNIFORM_BUFFER.as_raw(),
+	StorageBuffer = vk::DescriptorType::STORAGE_BUFFER.as_raw(),
+	UniformBufferDynamic = vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC.as_raw(),
+	StorageBufferDynamic = vk::DescriptorType::STORAGE_BUFFER_DYNAMIC.as_raw(),
+	InputAttachment = vk::DescriptorType::INPUT_ATTACHMENT.as_raw()
+}
+
+impl Type {
+	/// Checks if descriptors of this type can use immutable samplers.
+	#[inline]
+	pub fn is_sampler(&self) -> bool {
+		match self {
+			Self::Sampler | Self::CombinedImageSampler => true,
+			_ => false
+		}
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::DescriptorType {
+		vk::DescriptorType::from_raw(self as i32)
+	}
+}
@@ -0,0 +1,241 @@
+use ash::vk;
+use std::{
+	sync::Arc,
+	ops::Range,
+	any::Any
+};
+use crate::{
+	DeviceOwned,
+	Image,
+	image::{
+		self,
+		Sampler
+	},
+	mem::{
+		self,
+		Buffer,
+		BufferProperties
+	}
+};
+use super::Type;
+
+/// Resources kept alive by a descriptor.
+pub(crate) type Descriptor = Arc<dyn Any + Send + Sync>;
+
+pub(crate) enum Infos {
+	Buffers(Vec<vk::DescriptorBufferInfo>),
+	Images(Vec<vk::DescriptorImageInfo>),
+	TexelBuffers(Vec<vk::BufferView>)
+}
+
+impl Infos {
+	fn len(&self) -> usize {
+		match self {
+			Self::Buffers(infos) => infos.len(),
+			Self::Images(infos) => infos.len(),
+			Self::TexelBuffers(views) => views.len()
+		}
+	}
+}
+
+/// Descriptor set write, to be given to `Set::update`.
+///
+/// The written resources are kept alive by the set until they are overwritten.
+pub struct Write {
+	pub(crate) binding: u32,
+	pub(crate) first_element: u32,
+	pub(crate) ty: Type,
+	pub(crate) infos: Infos,
+
+	/// Resources of each written descriptor.
+	pub(crate) resources: Vec<Descriptor>
+}
+
+impl Write {
+	/// Binding written.
+	#[inline]
+	pub fn binding(&self) -> u32 {
+		self.binding
+	}
+
+	/// First array element written.
+	#[inline]
+	pub fn first_element(&self) -> u32 {
+		self.first_element
+	}
+
+	/// Type of the descriptors written.
+	#[inline]
+	pub fn ty(&self) -> Type {
+		self.ty
+	}
+
+	/// Number of descriptors written.
+	#[inline]
+	pub fn count(&self) -> u32 {
+		self.infos.len() as u32
+	}
+
+	fn buffers<B: 'static + Send + Sync + BufferProperties>(binding: u32, first_element: u32, ty: Type, buffers: &[(&Arc<B>, Range<u64>)]) -> Write {
+		let mut infos = Vec::with_capacity(buffers.len());
+		let mut resources: Vec<Descriptor> = Vec::with_capacity(buffers.len());
+		for (buffer, range) in buffers {
+			debug_assert!(range.start <= range.end && range.end <= buffer.size(), "descriptor buffer range out of bounds");
+			debug_assert!(match ty {
+				Type::UniformBuffer | Type::UniformBufferDynamic => buffer.usage().uniform_buffer(),
+				_ => buffer.usage().storage_buffer()
+			}, "buffer without the usage required by the {:?} descriptor", ty);
+
+			infos.push(vk::DescriptorBufferInfo {
+				buffer: buffer.handle(),
+				offset: range.start,
+				range: range.end - range.start
+			});
+			resources.push((*buffer).clone());
+		}
+
+		Write {
+			binding,
+			first_element,
+			ty,
+			infos: Infos::Buffers(infos),
+			resources
+		}
+	}
+
+	fn images<I: 'static + Send + Sync + Image>(binding: u32, first_element: u32, ty: Type, images: &[(&Arc<image::View<I>>, Option<&Arc<Sampler>>)], layout: image::Layout) -> Write {
+		let mut infos = Vec::with_capacity(images.len());
+		let mut resources: Vec<Descriptor> = Vec::with_capacity(images.len());
+		for (view, sampler) in images {
+			infos.push(vk::DescriptorImageInfo {
+				sampler: sampler.map(|s| s.handle()).unwrap_or(vk::Sampler::null()),
+				image_view: view.handle(),
+				image_layout: layout.into_vulkan()
+			});
+			match sampler {
+				Some(sampler) => resources.push(Arc::new(((*view).clone(), (*sampler).clone()))),
+				None => resources.push((*view).clone())
+			}
+		}
+
+		Write {
+			binding,
+			first_element,
+			ty,
+			infos: Infos::Images(infos),
+			resources
+		}
+	}
+
+	pub fn uniform_buffer<B: 'static + Send + Sync + BufferProperties>(binding: u32, buffer: &Arc<B>, range: Range<u64>) -> Write {
+		Self::uniform_buffers(binding, 0, &[(buffer, range)])
+	}
+
+	pub fn uniform_buffers<B: 'static + Send + Sync + BufferProperties>(binding: u32, first_element: u32, buffers: &[(&Arc<B>, Range<u64>)]) -> Write {
+		Self::buffers(binding, first_element, Type::UniformBuffer, buffers)
+	}
+
+	pub fn uniform_buffer_dynamic<B: 'static + Send + Sync + BufferProperties>(binding: u32, buffer: &Arc<B>, range: Range<u64>) -> Write {
+		Self::buffers(binding, 0, Type::UniformBufferDynamic, &[(buffer, range)])
+	}
+
+	pub fn storage_buffer<B: 'static + Send + Sync + BufferProperties>(binding: u32, buffer: &Arc<B>, range: Range<u64>) -> Write {
+		Self::storage_buffers(binding, 0, &[(buffer, range)])
+	}
+
+	pub fn storage_buffers<B: 'static + Send + Sync + BufferProperties>(binding: u32, first_element: u32, buffers: &[(&Arc<B>, Range<u64>)]) -> Write {
+		Self::buffers(binding, first_element, Type::StorageBuffer, buffers)
+	}
+
+	pub fn storage_buffer_dynamic<B: 'static + Send + Sync + BufferProperties>(binding: u32, buffer: &Arc<B>, range: Range<u64>) -> Write {
+		Self::buffers(binding, 0, Type::StorageBufferDynamic, &[(buffer, range)])
+	}
+
+	/// The image must be in the given layout whenever the descriptor is used.
+	pub fn combined_image_sampler<I: 'static + Send + Sync + Image>(binding: u32, view: &Arc<image::View<I>>, sampler: &Arc<Sampler>, layout: image::Layout) -> Write {
+		Self::combined_image_samplers(binding, 0, &[(view, sampler)], layout)
+	}
+
+	/// The images must be in the given layout whenever the descriptors are used.
+	pub fn combined_image_samplers<I: 'static + Send + Sync + Image>(binding: u32, first_element: u32, views: &[(&Arc<image::View<I>>, &Arc<Sampler>)], layout: image::Layout) -> Write {
+		let images: Vec<_> = views.iter().map(|(view, sampler)| (*view, Some(*sampler))).collect();
+		Self::images(binding, first_element, Type::CombinedImageSampler, &images, layout)
+	}
+
+	/// The image must be in the given layout whenever the descriptor is used.
+	pub fn sampled_image<I: 'static + Send + Sync + Image>(binding: u32, view: &Arc<image::View<I>>, layout: image::Layout) -> Write {
+		Self::sampled_images(binding, 0, &[view], layout)
+	}
+
+	/// The images must be in the given layout whenever the descriptors are used.
+	pub fn sampled_images<I: 'static + Send + Sync + Image>(binding: u32, first_element: u32, views: &[&Arc<image::View<I>>], layout: image::Layout) -> Write {
+		let images: Vec<_> = views.iter().map(|view| (*view, None)).collect();
+		Self::images(binding, first_element, Type::SampledImage, &images, layout)
+	}
+
+	/// The image must be in the `General` layout whenever the descriptor is used.
+	pub fn storage_image<I: 'static + Send + Sync + Image>(binding: u32, view: &Arc<image::View<I>>) -> Write {
+		Self::storage_images(binding, 0, &[view])
+	}
+
+	/// The images must be in the `General` layout whenever the descriptors are used.
+	pub fn storage_images<I: 'static + Send + Sync + Image>(binding: u32, first_element: u32, views: &[&Arc<image::View<I>>]) -> Write {
+		let images: Vec<_> = views.iter().map(|view| (*view, None)).collect();
+		Self::images(binding, first_element, Type::StorageImage, &images, image::Layout::General)
+	}
+
+	/// Write an input attachment descriptor, reading the given view in the current subpass.
+	///
+	/// The view must be an attachment of the framebuffer,
+	/// used as input attachment of the subpass with the given layout.
+	pub fn input_attachment<I: 'static + Send + Sync + Image>(binding: u32, view: &Arc<image::View<I>>, layout: image::Layout) -> Write {
+		Self::input_attachments(binding, 0, &[view], layout)
+	}
+
+	pub fn input_attachments<I: 'static + Send + Sync + Image>(binding: u32, first_element: u32, views: &[&Arc<image::View<I>>], layout: image::Layout) -> Write {
+		let images: Vec<_> = views.iter().map(|view| (*view, None)).collect();
+		Self::images(binding, first_element, Type::InputAttachment, &images, layout)
+	}
+
+	/// Write a `Sampler` descriptor.
+	pub fn sampler(binding: u32, sampler: &Arc<Sampler>) -> Write {
+		Self::samplers(binding, 0, &[sampler])
+	}
+
+	pub fn samplers(binding: u32, first_element: u32, samplers: &[&Arc<Sampler>]) -> Write {
+		Write {
+			binding,
+			first_element,
+			ty: Type::Sampler,
+			infos: Infos::Images(samplers.iter().map(|s| vk::DescriptorImageInfo {
+				sampler: s.handle(),
+				..Default::default()
+			}).collect()),
+			resources: samplers.iter().map(|s| (*s).clone() as Descriptor).collect()
+		}
+	}
+
+	/// Write a uniform or storage texel buffer descriptor, depending on the view type.
+	pub fn texel_buffer<B: 'static + Send + Sync + Buffer + DeviceOwned>(binding: u32, view: &Arc<mem::buffer::View<B>>) -> Write {
+		Self::texel_buffers(binding, 0, &[view])
+	}
+
+	/// # Panic
+	///
+	/// Panics if `views` is empty or if the views are not all of the same type.
+	pub fn texel_buffers<B: 'static + Send + Sync + Buffer + DeviceOwned>(binding: u32, first_element: u32, views: &[&Arc<mem::buffer::View<B>>]) -> Write {
+		let view_ty = views.first().expect("no texel buffer view").ty();
+		assert!(views.iter().all(|v| v.ty() == view_ty), "texel buffer views of different types");
+
+		Write {
+			binding,
+			first_element,
+			ty: match view_ty {
+				mem::buffer::TexelBufferType::Uniform => Type::UniformTexelBuffer,
+				mem::buffer::TexelBufferType::Storage => Type::StorageTexelBuffer
+			},
+			infos: Infos::TexelBuffers(views.iter().map(|v| v.handle()).collect()),
+			resources: views.iter().map(|v| (*v).clone() as Descriptor).collect()
+		}
+	}
+}
@@ -2,7 +2,8 @@ use ash::vk;
 
 macro_rules! dynamic_states {
 	($($name:ident : $variant:ident ($vulkan:ident)),*) => {
-		#[derive(Clone, Copy, Default, Debug)]
+		/// Set of dynamic states, known at runtime.
+		#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
 		pub struct DynamicStates {
 			$(
 				$name: bool
@@ -34,6 +35,44 @@ macro_rules! dynamic_states {
 				}
 			}
 
+			/// Dynamic states declared by the given type.
+			pub fn of<D: super::DynamicStates>() -> DynamicStates {
+				DynamicStates {
+					$(
+						$name: <D::$variant as super::DynamicState>::IS_DYNAMIC
+					),*
+				}
+			}
+
+			pub fn union(&self, other: &DynamicStates) -> DynamicStates {
+				DynamicStates {
+					$(
+						$name: self.$name || other.$name
+					),*
+				}
+			}
+
+			pub fn intersection(&self, other: &DynamicStates) -> DynamicStates {
+				DynamicStates {
+					$(
+						$name: self.$name && other.$name
+					),*
+				}
+			}
+
+			/// Iterate over the states of the set.
+			pub fn iter(&self) -> impl Iterator<Item=DynamicState> {
+				let mut states = Vec::new();
+
+				$(
+					if self.$name {
+						states.push(DynamicState::$variant)
+					}
+				)*
+
+				states.into_iter()
+			}
+
 			pub(crate) fn into_vulkan(self) -> Vec<vk::DynamicState> {
 				let mut vec = Vec::new();
 
@@ -47,7 +86,7 @@ macro_rules! dynamic_states {
 			}
 		}
 
-		#[derive(Clone, Copy, Debug)]
+		#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 		#[repr(i32)]
 		pub enum DynamicState {
 			$(
@@ -69,6 +108,16 @@ dynamic_states! {
 	stencil_reference: StencilReference (STENCIL_REFERENCE)
 }
 
+impl<'a> From<&'a [DynamicState]> for DynamicStates {
+	fn from(states: &'a [DynamicState]) -> DynamicStates {
+		let mut o = DynamicStates::new();
+		for state in states {
+			o.add(*state);
+		}
+		o
+	}
+}
+
 impl From<DynamicState> for DynamicStates {
 	fn from(i: DynamicState) -> DynamicStates {
 		let mut o = DynamicStates::new();
@@ -1,5 +1,7 @@
 use ash::vk;
 
+pub mod flags;
+
 /// Type describing the dynamic states of a graphics pipeline.
 pub trait DynamicStates: 'static {
 	type Viewport: DynamicState;
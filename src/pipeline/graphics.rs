@@ -9,11 +9,23 @@ use std::{
 use crate::{
 	OomError,
 	Device,
-	framebuffer,
+	device::Feature,
+	framebuffer::{
+		self,
+		rendering::{
+			self,
+			RenderingFormats
+		}
+	},
+	ffi,
+	ops,
 	Resource
 };
 use super::{
 	shader,
+	specialization,
+	Specialization,
+	Cache,
 	Stages,
 	Layout,
 	VertexInput,
@@ -22,18 +34,56 @@ use super::{
 	Viewport,
 	Scissor,
 	Rasterization,
+	rasterization::PolygonMode,
 	Multisample,
 	DepthTest,
 	StencilTest,
+	DepthStencil,
 	ColorBlend,
-	DynamicStates
+	color_blend,
+	DynamicStates,
+	dynamic_state::flags
 };
 
 #[derive(Debug)]
 pub enum CreationError {
 	OomError(OomError),
 	InvalidShader,
-	CompileRequired
+	CompileRequired,
+
+	/// The rasterization sample count differs from the sample count of the subpass attachments.
+	SampleCountMismatch {
+		subpass: framebuffer::SampleCount,
+		pipeline: framebuffer::SampleCount
+	},
+
+	/// The number of color blend attachments differs from the number of color attachments of the subpass,
+	/// or from the number of color formats with dynamic rendering.
+	ColorAttachmentCountMismatch {
+		subpass: u32,
+		pipeline: u32
+	},
+
+	/// No viewport or scissor is given while they are not dynamic states.
+	MissingViewport,
+
+	/// The numbers of viewports and scissors differ.
+	ViewportCountMismatch {
+		viewports: u32,
+		scissors: u32
+	},
+
+	/// A device feature required by the pipeline state is not enabled.
+	MissingFeature(Feature),
+
+	/// The base pipeline does not allow derivatives, or does not precede the derivative pipeline in the batch.
+	InvalidBasePipeline,
+
+	/// The pipeline is created for dynamic rendering, which is not enabled on the device.
+	UnsupportedDynamicRendering(rendering::SupportError),
+
+	/// Specialization constants are given for a shader stage the pipeline does not have.
+	MissingSpecializedStage(shader::Stage)
 }
 
 impl From<vk::Result> for CreationError {
@@ -54,18 +104,85 @@ pub trait GraphicsPipeline: Resource<Handle=vk::Pipeline> {
 	type DynamicStates: DynamicStates;
 
 	fn layout(&self) -> &Self::Layout;
+
+	/// Dynamic states of the pipeline, including the ones declared by `Self::DynamicStates`.
+	fn dynamic_states(&self) -> flags::DynamicStates;
+}
+
+/// What a graphics pipeline renders into.
+#[derive(Clone)]
+pub enum Target {
+	/// Subpass of a render pass.
+	Subpass(framebuffer::render_pass::subpass::Reference),
+
+	/// Dynamic rendering, into views of the given formats.
+	Rendering(RenderingFormats)
+}
+
+impl Target {
+	/// Number of color attachments rendered into.
+	pub fn color_attachment_count(&self) -> Option<u32> {
+		match self {
+			Target::Subpass(subpass) => subpass.render_pass().subpass_color_attachment_count(subpass.index()),
+			Target::Rendering(formats) => Some(formats.color_formats.len() as u32)
+		}
+	}
 }
 
 pub struct Graphics<L: Layout, I: VertexInput, D: DynamicStates> {
 	device: Arc<Device>,
-	render_subpass: framebuffer::render_pass::subpass::Reference,
+	target: Target,
 	handle: vk::Pipeline,
-	shaders: Vec<Arc<shader::Module>>,
 	layout: L,
+	dynamic_state_flags: flags::DynamicStates,
+	allows_derivatives: bool,
 	vertex_input: PhantomData<I>,
 	dynamic_states: PhantomData<D>
 }
 
+impl<L: Layout> Graphics<L, (), ()> {
+	/// Creates a graphics pipeline builder.
+	///
+	/// The pipeline has no vertex input and no dynamic state by default.
+	pub fn builder<'s, S: Stages>(stages: &'s S, layout: L, render_subpass: framebuffer::render_pass::subpass::Reference) -> Builder<'s, S, L, (), ()> {
+		Self::builder_for(stages, layout, Target::Subpass(render_subpass))
+	}
+
+	/// Creates a builder for a graphics pipeline used with dynamic rendering,
+	/// rendering into views of the given formats.
+	///
+	/// Dynamic rendering must be enabled on the device.
+	pub fn builder_for_rendering<'s, S: Stages>(stages: &'s S, layout: L, formats: RenderingFormats) -> Builder<'s, S, L, (), ()> {
+		Self::builder_for(stages, layout, Target::Rendering(formats))
+	}
+
+	/// Creates a builder for a graphics pipeline rendering into the given target.
+	pub fn builder_for<'s, S: Stages>(stages: &'s S, layout: L, target: Target) -> Builder<'s, S, L, (), ()> {
+		Builder {
+			stages,
+			specializations: Vec::new(),
+			cache: None,
+			extra_dynamic_states: flags::DynamicStates::empty(),
+			vertex_input: (),
+			tesselation: None,
+			viewports: Vec::new(),
+			scissors: Vec::new(),
+			rasterization: Rasterization::default(),
+			multisample: Multisample::default(),
+			depth_stencil: DepthStencil::disabled(),
+			color_blend: None,
+			blend_attachments: BlendAttachments::Replicated(color_blend::Attachment::disabled()),
+			logic_operation: None,
+			blend_constants: [0.0; 4],
+			layout,
+			target,
+			allow_derivatives: false,
+			base: Base::None,
+			dynamic_states: PhantomData
+		}
+	}
+}
+
 impl<L: Layout, I: VertexInput, D: DynamicStates> Graphics<L, I, D> {
 	/// Creates a new graphics pipeline.
 	pub fn new<S: Stages, const V: usize>(
@@ -81,110 +198,366 @@ impl<L: Layout, I: VertexInput, D: DynamicStates> Graphics<L, I, D> {
 		stencil_tests: Option<(StencilTest, StencilTest)>,
 		color_blend: ColorBlend,
 		layout: L,
-		render_subpass: framebuffer::render_pass::subpass::Reference
+		render_subpass: framebuffer::render_pass::subpass::Reference,
+		cache: Option<&Cache>
 	) -> Result<Graphics<L, I, D>, CreationError> {
-		let mut shaders = Vec::new();
-		let mut vk_stages = Vec::new();
-		stages.for_each(|stage| {
-			vk_stages.push(vk::PipelineShaderStageCreateInfo {
-				stage: stage.ty.into_vulkan(),
-				module: stage.entry_point.module().handle(),
-				p_name: stage.entry_point.name().as_ptr(),
-				p_specialization_info: std::ptr::null(),
-				..Default::default()
-			});
+		let state = State {
+			stages,
+			specializations: Vec::new(),
+			vertex_input,
+			tesselation,
+			viewports: Vec::from(viewports),
+			scissors: Vec::from(scissors),
+			rasterization,
+			multisample,
+			depth_stencil: DepthStencil {
+				depth_test,
+				stencil_tests
+			},
+			color_blend,
+			layout,
+			target: Target::Subpass(render_subpass),
+			dynamic_state_flags: flags::DynamicStates::of::<D>(),
+			allow_derivatives: false,
+			base: Base::None
+		};
 
-			shaders.push(stage.entry_point.module().clone())
-		});
+		let mut pipelines = Self::create(device, cache, vec![state]).map_err(|(e, _)| e)?;
+		Ok(pipelines.pop().unwrap())
+	}
 
-		let viewport_state = vk::PipelineViewportStateCreateInfo {
-			viewport_count: viewports.len() as u32,
-			p_viewports: viewports.as_ptr() as *const _,
-			scissor_count: scissors.len() as u32,
-			p_scissors: scissors.as_ptr() as *const _,
-			..Default::default()
+	/// Creates many graphics pipelines at once.
+	///
+	/// Derivative pipelines may use a pipeline preceding them in the batch as base pipeline,
+	/// with `Builder::derivative_of_index`.
+	/// The caches given to the builders are ignored in favor of `cache`.
+	///
+	/// On error, returns the index of the builder that failed.
+	pub fn create_many<'s, S: Stages>(device: &Arc<Device>, cache: Option<&Cache>, builders: Vec<Builder<'s, S, L, I, D>>) -> Result<Vec<Arc<Graphics<L, I, D>>>, (CreationError, usize)> {
+		if builders.is_empty() {
+			return Ok(Vec::new())
+		}
+
+		let states = builders.into_iter().map(Builder::into_state).collect();
+		let pipelines = Self::create(device, cache, states)?;
+		Ok(pipelines.into_iter().map(Arc::new).collect())
+	}
+
+	fn create<S: Stages>(device: &Arc<Device>, cache: Option<&Cache>, states: Vec<State<S, L, I>>) -> Result<Vec<Graphics<L, I, D>>, (CreationError, usize)> {
+		for (i, state) in states.iter().enumerate() {
+			state.check(device).map_err(|e| (e, i))?;
+
+			if let Base::Index(base) = state.base {
+				if base >= i || !states[base].allow_derivatives {
+					return Err((CreationError::InvalidBasePipeline, i))
+				}
+			}
+		}
+
+		let handles = {
+			// The create infos point to `infos`, which point to `states`.
+			let infos: Vec<_> = states.iter().map(State::infos).collect();
+			let create_infos: Vec<_> = states.iter().zip(&infos).map(|(state, infos)| state.create_info(infos)).collect();
+
+			unsafe {
+				match device.handle().create_graphics_pipelines(cache.map(|c| c.handle()).unwrap_or(vk::PipelineCache::null()), &create_infos, None) {
+					Ok(handles) => handles,
+					Err((handles, e)) => {
+						// Pipelines that failed are null.
+						let index = handles.iter().position(|h| *h == vk::Pipeline::null()).unwrap_or(0);
+
+						for handle in handles {
+							if handle != vk::Pipeline::null() {
+								device.handle().destroy_pipeline(handle, None);
+							}
+						}
+
+						return Err((e.into(), index))
+					}
+				}
+			}
 		};
 
-		let depth_stencil_state = if depth_test.is_some() || stencil_tests.is_some() {
-			let mut infos = vk::PipelineDepthStencilStateCreateInfo::default();
+		Ok(states.into_iter().zip(handles).map(|(state, handle)| {
+			Graphics {
+				device: device.clone(),
+				target: state.target,
+				handle,
+				layout: state.layout,
+				dynamic_state_flags: state.dynamic_state_flags,
+				allows_derivatives: state.allow_derivatives,
+				vertex_input: PhantomData,
+				dynamic_states: PhantomData
+			}
+		}).collect())
+	}
 
-			if let Some(depth_test) = depth_test {
-				depth_test.set_vulkan(&mut infos);
+	pub fn layout(&self) -> &L {
+		&self.layout
+	}
+
+	pub fn target(&self) -> &Target {
+		&self.target
+	}
+
+	/// Render pass subpass of the pipeline, if it is not used with dynamic rendering.
+	pub fn render_subpass(&self) -> Option<&framebuffer::render_pass::subpass::Reference> {
+		match &self.target {
+			Target::Subpass(subpass) => Some(subpass),
+			Target::Rendering(_) => None
+		}
+	}
+
+	/// Checks if the pipeline can be used as base pipeline of derivative pipelines.
+	pub fn allows_derivatives(&self) -> bool {
+		self.allows_derivatives
+	}
+}
+
+/// Base pipeline of a derivative pipeline.
+#[derive(Clone, Copy)]
+enum Base {
+	None,
+
+	/// Handle of a base pipeline borrowed by the builder.
+	Handle(vk::Pipeline),
+
+	/// Index of the base pipeline in the creation batch.
+	Index(usize)
+}
+
+/// Graphics pipeline state, owning the data referenced by the create infos.
+struct State<'s, S: Stages, L: Layout, I: VertexInput> {
+	stages: &'s S,
+	specializations: Vec<(shader::Stage, Specialization)>,
+	vertex_input: I,
+	tesselation: Option<Tesselation>,
+	viewports: Vec<Viewport>,
+	scissors: Vec<Scissor>,
+	rasterization: Rasterization,
+	multisample: Multisample,
+	depth_stencil: DepthStencil,
+	color_blend: ColorBlend,
+	layout: L,
+	target: Target,
+	dynamic_state_flags: flags::DynamicStates,
+	allow_derivatives: bool,
+	base: Base
+}
+
+/// Create infos of the state blocks, borrowing a `State`.
+struct StateInfos {
+	/// Referenced by `stages`.
+	_specializations: Vec<(shader::Stage, vk::SpecializationInfo)>,
+	stages: Vec<vk::PipelineShaderStageCreateInfo>,
+	vertex_input: vk::PipelineVertexInputStateCreateInfo,
+	input_assembly: vk::PipelineInputAssemblyStateCreateInfo,
+	viewport: vk::PipelineViewportStateCreateInfo,
+	depth_stencil: Option<vk::PipelineDepthStencilStateCreateInfo>,
+	/// Referenced by `dynamic`.
+	_dynamic_states: Vec<vk::DynamicState>,
+	dynamic: vk::PipelineDynamicStateCreateInfo,
+	/// Referenced by `rendering`.
+	_color_formats: Vec<vk::Format>,
+	/// Chained to the create info when using dynamic rendering.
+	rendering: Option<ffi::PipelineRenderingCreateInfo>
+}
+
+impl<'s, S: Stages, L: Layout, I: VertexInput> State<'s, S, L, I> {
+	fn check(&self, device: &Device) -> Result<(), CreationError> {
+		let color_blend = &self.color_blend;
+		let rasterization = &self.rasterization;
+		let dynamic_state_flags = &self.dynamic_state_flags;
+
+		if self.viewports.len() != self.scissors.len() {
+			return Err(CreationError::ViewportCountMismatch {
+				viewports: self.viewports.len() as u32,
+				scissors: self.scissors.len() as u32
+			})
+		}
+
+		if self.viewports.is_empty() && !(dynamic_state_flags.contains(flags::DynamicState::Viewport) && dynamic_state_flags.contains(flags::DynamicState::Scissor)) {
+			return Err(CreationError::MissingViewport)
+		}
+
+		let mut stages = Vec::new();
+		self.stages.for_each(|stage| stages.push(stage.ty));
+		specialization::check_stages(&self.specializations, &stages).map_err(CreationError::MissingSpecializedStage)?;
+
+		if let Target::Rendering(_) = &self.target {
+			rendering::check_support(device).map_err(CreationError::UnsupportedDynamicRendering)?;
+		}
+
+		if let Some(subpass_color_attachments) = self.target.color_attachment_count() {
+			if subpass_color_attachments != color_blend.attachment_count() {
+				return Err(CreationError::ColorAttachmentCountMismatch {
+					subpass: subpass_color_attachments,
+					pipeline: color_blend.attachment_count()
+				})
 			}
+		}
+
+		if color_blend.is_logic_operation_enabled() && !device.enabled_features().contains(Feature::LogicOp) {
+			return Err(CreationError::MissingFeature(Feature::LogicOp))
+		}
+
+		if !color_blend.is_uniform() && !device.enabled_features().contains(Feature::IndependentBlend) {
+			return Err(CreationError::MissingFeature(Feature::IndependentBlend))
+		}
+
+		if rasterization.polygon_mode() != PolygonMode::Fill && !device.enabled_features().contains(Feature::FillModeNonSolid) {
+			return Err(CreationError::MissingFeature(Feature::FillModeNonSolid))
+		}
+
+		if !dynamic_state_flags.contains(flags::DynamicState::LineWidth) && rasterization.line_width() != 1.0 && !device.enabled_features().contains(Feature::WideLines) {
+			return Err(CreationError::MissingFeature(Feature::WideLines))
+		}
+
+		if rasterization.is_depth_clamp_enabled() && !device.enabled_features().contains(Feature::DepthClamp) {
+			return Err(CreationError::MissingFeature(Feature::DepthClamp))
+		}
+
+		if !dynamic_state_flags.contains(flags::DynamicState::DepthBias) && rasterization.depth_bias().map(|b| b.clamp() != 0.0).unwrap_or(false) && !device.enabled_features().contains(Feature::DepthBiasClamp) {
+			return Err(CreationError::MissingFeature(Feature::DepthBiasClamp))
+		}
+
+		if self.depth_stencil.uses_depth_bounds() && !device.enabled_features().contains(Feature::DepthBounds) {
+			return Err(CreationError::MissingFeature(Feature::DepthBounds))
+		}
 
-			if let Some((front, back)) = stencil_tests {
-				front.set_vulkan(&mut infos.front);
-				back.set_vulkan(&mut infos.back);
+		if let Target::Subpass(render_subpass) = &self.target {
+			if let Some(subpass_samples) = render_subpass.render_pass().subpass_samples(render_subpass.index()) {
+				if subpass_samples != self.multisample.rasterization_samples() {
+					return Err(CreationError::SampleCountMismatch {
+						subpass: subpass_samples,
+						pipeline: self.multisample.rasterization_samples()
+					})
+				}
 			}
+		}
 
-			Some(infos)
-		} else {
-			None
+		Ok(())
+	}
+
+	fn infos(&self) -> StateInfos {
+		let specializations: Vec<_> = self.specializations.iter().map(|(stage, s)| (*stage, s.as_vulkan())).collect();
+
+		// Shader modules are only needed during the creation.
+		let mut stages = Vec::new();
+		self.stages.for_each(|stage| {
+			stages.push(vk::PipelineShaderStageCreateInfo {
+				stage: stage.ty.into_vulkan(),
+				module: stage.entry_point.module().handle(),
+				p_name: stage.entry_point.name().as_ptr(),
+				p_specialization_info: specializations.iter()
+					.find(|(ty, _)| *ty == stage.ty)
+					.map(|(_, s)| s as *const _)
+					.unwrap_or(std::ptr::null()),
+				..Default::default()
+			})
+		});
+
+		let mut vertex_input = vk::PipelineVertexInputStateCreateInfo::default();
+		vertex_input.vertex_binding_description_count = self.vertex_input.bindings().len() as u32;
+		vertex_input.p_vertex_binding_descriptions = self.vertex_input.bindings().as_ptr() as *const _;
+		vertex_input.vertex_attribute_description_count = self.vertex_input.attributes().len() as u32;
+		vertex_input.p_vertex_attribute_descriptions = self.vertex_input.attributes().as_ptr() as *const _;
+
+		// Dynamic viewports and scissors are still counted.
+		let viewport_count = std::cmp::max(self.viewports.len(), 1) as u32;
+		let viewport = vk::PipelineViewportStateCreateInfo {
+			viewport_count,
+			p_viewports: if self.viewports.is_empty() { std::ptr::null() } else { self.viewports.as_ptr() as *const _ },
+			scissor_count: viewport_count,
+			p_scissors: if self.scissors.is_empty() { std::ptr::null() } else { self.scissors.as_ptr() as *const _ },
+			..Default::default()
 		};
 
-		let vk_dynamic_states = D::vulkan();
-		let dynamic_state = vk::PipelineDynamicStateCreateInfo {
-			dynamic_state_count: vk_dynamic_states.len() as u32,
-			p_dynamic_states: vk_dynamic_states.as_ptr() as *const _,
+		let dynamic_states = self.dynamic_state_flags.into_vulkan();
+		let dynamic = vk::PipelineDynamicStateCreateInfo {
+			dynamic_state_count: dynamic_states.len() as u32,
+			p_dynamic_states: dynamic_states.as_ptr(),
 			..Default::default()
 		};
 
-		let mut vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default();
-		vertex_input_state.vertex_binding_description_count = vertex_input.bindings().len() as u32;
-		vertex_input_state.p_vertex_binding_descriptions = vertex_input.bindings().as_ptr() as *const _;
-		vertex_input_state.vertex_attribute_description_count = vertex_input.attributes().len() as u32;
-		vertex_input_state.p_vertex_attribute_descriptions = vertex_input.attributes().as_ptr() as *const _;
+		let color_formats: Vec<_> = match &self.target {
+			Target::Rendering(formats) => formats.color_formats.iter().map(|f| f.into_vulkan()).collect(),
+			Target::Subpass(_) => Vec::new()
+		};
 
-		let infos = vk::GraphicsPipelineCreateInfo {
-			// Shader stages
-			stage_count: vk_stages.len() as u32,
-			p_stages: vk_stages.as_ptr(),
-			//
-			p_vertex_input_state: &vertex_input_state,
-			p_input_assembly_state: &I::Assembly::vulkan(),
-			p_tessellation_state: tesselation.as_ref().map(|t| t.as_vulkan() as *const _).unwrap_or(std::ptr::null()),
-			//
-			p_viewport_state: &viewport_state,
-			p_rasterization_state: rasterization.as_vulkan(),
-			p_multisample_state: multisample.as_vulkan(),
-			p_depth_stencil_state: depth_stencil_state.as_ref().map(|t| t as *const _).unwrap_or(std::ptr::null()),
-			p_color_blend_state: color_blend.as_vulkan(),
-			p_dynamic_state: &dynamic_state,
-			//
-			layout: layout.handle(),
-			render_pass: render_subpass.render_pass().handle(),
-			subpass: render_subpass.index(),
-			base_pipeline_handle: vk::Pipeline::null(),
-			base_pipeline_index: 0,
-			..Default::default()
+		let rendering = match &self.target {
+			Target::Rendering(formats) => Some(ffi::PipelineRenderingCreateInfo {
+				color_attachment_count: color_formats.len() as u32,
+				p_color_attachment_formats: color_formats.as_ptr(),
+				depth_attachment_format: formats.depth_format.map(|f| f.into_vulkan()).unwrap_or(vk::Format::UNDEFINED),
+				stencil_attachment_format: formats.stencil_format.map(|f| f.into_vulkan()).unwrap_or(vk::Format::UNDEFINED),
+				..Default::default()
+			}),
+			Target::Subpass(_) => None
 		};
 
-		let handle = unsafe {
-			match device.handle().create_graphics_pipelines(vk::PipelineCache::null(), &[infos], None) {
-				Ok(handles) => handles.into_iter().next().unwrap(),
-				Err((handles, e)) => {
-					for handle in handles {
-						device.handle().destroy_pipeline(handle, None);
-					}
+		StateInfos {
+			_specializations: specializations,
+			stages,
+			vertex_input,
+			input_assembly: I::Assembly::vulkan(),
+			viewport,
+			depth_stencil: self.depth_stencil.into_vulkan(),
+			_dynamic_states: dynamic_states,
+			dynamic,
+			_color_formats: color_formats,
+			rendering
+		}
+	}
 
-					return Err(e.into())
-				}
+	fn create_info(&self, infos: &StateInfos) -> vk::GraphicsPipelineCreateInfo {
+		let mut flags = vk::PipelineCreateFlags::empty();
+		if self.allow_derivatives {
+			flags |= vk::PipelineCreateFlags::ALLOW_DERIVATIVES;
+		}
+
+		let (base_pipeline_handle, base_pipeline_index) = match self.base {
+			Base::None => (vk::Pipeline::null(), -1),
+			Base::Handle(handle) => {
+				flags |= vk::PipelineCreateFlags::DERIVATIVE;
+				(handle, -1)
+			},
+			Base::Index(index) => {
+				flags |= vk::PipelineCreateFlags::DERIVATIVE;
+				(vk::Pipeline::null(), index as i32)
 			}
 		};
 
-		Ok(Self {
-			device: device.clone(),
-			render_subpass: render_subpass,
-			handle,
-			shaders,
-			layout,
-			vertex_input: PhantomData,
-			dynamic_states: PhantomData
-		})
-	}
+		let (render_pass, subpass) = match &self.target {
+			Target::Subpass(subpass) => (subpass.render_pass().handle(), subpass.index()),
+			Target::Rendering(_) => (vk::RenderPass::null(), 0)
+		};
 
-	pub fn render_subpass(&self) -> &framebuffer::render_pass::subpass::Reference {
-		&self.render_subpass
+		vk::GraphicsPipelineCreateInfo {
+			p_next: infos.rendering.as_ref().map(|r| r as *const _ as *const std::ffi::c_void).unwrap_or(std::ptr::null()),
+			flags,
+			// Shader stages
+			stage_count: infos.stages.len() as u32,
+			p_stages: infos.stages.as_ptr(),
+			//
+			p_vertex_input_state: &infos.vertex_input,
+			p_input_assembly_state: &infos.input_assembly,
+			p_tessellation_state: self.tesselation.as_ref().map(|t| t.as_vulkan() as *const _).unwrap_or(std::ptr::null()),
+			//
+			p_viewport_state: &infos.viewport,
+			p_rasterization_state: self.rasterization.as_vulkan(),
+			p_multisample_state: self.multisample.as_vulkan(),
+			p_depth_stencil_state: infos.depth_stencil.as_ref().map(|t| t as *const _).unwrap_or(std::ptr::null()),
+			p_color_blend_state: self.color_blend.as_vulkan(),
+			p_dynamic_state: &infos.dynamic,
+			//
+			layout: self.layout.handle(),
+			render_pass,
+			subpass,
+			base_pipeline_handle,
+			base_pipeline_index,
+			..Default::default()
+		}
 	}
 }
 
@@ -204,6 +577,269 @@ impl<L: Layout, I: VertexInput, D: DynamicStates> GraphicsPipeline for Graphics<
 	fn layout(&self) -> &L {
 		&self.layout
 	}
+
+	fn dynamic_states(&self) -> flags::DynamicStates {
+		self.dynamic_state_flags
+	}
+}
+
+/// Blend states of the color attachments given to the builder.
+enum BlendAttachments {
+	Replicated(color_blend::Attachment),
+	Explicit(Vec<color_blend::Attachment>)
+}
+
+/// Graphics pipeline builder.
+pub struct Builder<'s, S: Stages, L: Layout, I: VertexInput, D: DynamicStates> {
+	stages: &'s S,
+	specializations: Vec<(shader::Stage, Specialization)>,
+	cache: Option<&'s Cache>,
+	extra_dynamic_states: flags::DynamicStates,
+	vertex_input: I,
+	tesselation: Option<Tesselation>,
+	viewports: Vec<Viewport>,
+	scissors: Vec<Scissor>,
+	rasterization: Rasterization,
+	multisample: Multisample,
+	depth_stencil: DepthStencil,
+	color_blend: Option<ColorBlend>,
+	blend_attachments: BlendAttachments,
+	logic_operation: Option<ops::Logic>,
+	blend_constants: [f32; 4],
+	layout: L,
+	target: Target,
+	allow_derivatives: bool,
+	base: Base,
+	dynamic_states: PhantomData<D>
+}
+
+impl<'s, S: Stages, L: Layout, I: VertexInput, D: DynamicStates> Builder<'s, S, L, I, D> {
+	pub fn vertex_input<J: VertexInput>(self, vertex_input: J) -> Builder<'s, S, L, J, D> {
+		Builder {
+			stages: self.stages,
+			specializations: self.specializations,
+			cache: self.cache,
+			extra_dynamic_states: self.extra_dynamic_states,
+			vertex_input,
+			tesselation: self.tesselation,
+			viewports: self.viewports,
+			scissors: self.scissors,
+			rasterization: self.rasterization,
+			multisample: self.multisample,
+			depth_stencil: self.depth_stencil,
+			color_blend: self.color_blend,
+			blend_attachments: self.blend_attachments,
+			logic_operation: self.logic_operation,
+			blend_constants: self.blend_constants,
+			layout: self.layout,
+			target: self.target,
+			allow_derivatives: self.allow_derivatives,
+			base: self.base,
+			dynamic_states: PhantomData
+		}
+	}
+
+	pub fn dynamic_states<E: DynamicStates>(self) -> Builder<'s, S, L, I, E> {
+		Builder {
+			stages: self.stages,
+			specializations: self.specializations,
+			cache: self.cache,
+			extra_dynamic_states: self.extra_dynamic_states,
+			vertex_input: self.vertex_input,
+			tesselation: self.tesselation,
+			viewports: self.viewports,
+			scissors: self.scissors,
+			rasterization: self.rasterization,
+			multisample: self.multisample,
+			depth_stencil: self.depth_stencil,
+			color_blend: self.color_blend,
+			blend_attachments: self.blend_attachments,
+			logic_operation: self.logic_operation,
+			blend_constants: self.blend_constants,
+			layout: self.layout,
+			target: self.target,
+			allow_derivatives: self.allow_derivatives,
+			base: self.base,
+			dynamic_states: PhantomData
+		}
+	}
+
+	/// Set the specialization constants of the given shader stage.
+	///
+	/// The pipeline creation fails with `CreationError::MissingSpecializedStage`
+	/// if the pipeline has no such stage.
+	pub fn specialization(mut self, stage: shader::Stage, specialization: Specialization) -> Self {
+		self.specializations.retain(|(s, _)| *s != stage);
+		self.specializations.push((stage, specialization));
+		self
+	}
+
+	/// Use the given pipeline cache to create the pipeline.
+	pub fn cache(mut self, cache: &'s Cache) -> Self {
+		self.cache = Some(cache);
+		self
+	}
+
+	/// Declare additional dynamic states, on top of the ones declared by the `DynamicStates` type.
+	///
+	/// The values of these states are not checked when drawing, and must be set with the recorder setters.
+	pub fn with_dynamic_states(mut self, states: &[flags::DynamicState]) -> Self {
+		self.extra_dynamic_states = self.extra_dynamic_states.union(&states.into());
+		self
+	}
+
+	pub fn tesselation(mut self, tesselation: Tesselation) -> Self {
+		self.tesselation = Some(tesselation);
+		self
+	}
+
+	/// Add a viewport and its scissor.
+	pub fn viewport(mut self, viewport: Viewport, scissor: Scissor) -> Self {
+		self.viewports.push(viewport);
+		self.scissors.push(scissor);
+		self
+	}
+
+	/// Add a viewport and scissor covering the given extent.
+	pub fn extent(self, (width, height): (u32, u32)) -> Self {
+		self.viewport(
+			Viewport::new(0.0, 0.0, width as f32, height as f32, 0.0, 1.0),
+			Scissor::new(0, 0, width, height)
+		)
+	}
+
+	pub fn rasterization(mut self, rasterization: Rasterization) -> Self {
+		self.rasterization = rasterization;
+		self
+	}
+
+	pub fn multisample(mut self, multisample: Multisample) -> Self {
+		self.multisample = multisample;
+		self
+	}
+
+	pub fn depth_test(mut self, depth_test: DepthTest) -> Self {
+		self.depth_stencil.depth_test = Some(depth_test);
+		self
+	}
+
+	pub fn stencil_tests(mut self, front: StencilTest, back: StencilTest) -> Self {
+		self.depth_stencil.stencil_tests = Some((front, back));
+		self
+	}
+
+	/// Set the depth and stencil tests.
+	///
+	/// Depth and stencil tests are disabled by default.
+	pub fn depth_stencil(mut self, depth_stencil: DepthStencil) -> Self {
+		self.depth_stencil = depth_stencil;
+		self
+	}
+
+	/// Set the color blend state.
+	///
+	/// Overrides the blend attachments, logic operation and blend constants given to the builder.
+	/// By default, every color attachment of the subpass is written without blending.
+	pub fn color_blend(mut self, color_blend: ColorBlend) -> Self {
+		self.color_blend = Some(color_blend);
+		self
+	}
+
+	/// Use the same blend state for every color attachment of the subpass.
+	pub fn blend_attachment(mut self, attachment: color_blend::Attachment) -> Self {
+		self.blend_attachments = BlendAttachments::Replicated(attachment);
+		self
+	}
+
+	/// Set the blend state of each color attachment of the subpass.
+	///
+	/// Their number must match the number of color attachments of the subpass.
+	pub fn blend_attachments(mut self, attachments: &[color_blend::Attachment]) -> Self {
+		self.blend_attachments = BlendAttachments::Explicit(attachments.to_vec());
+		self
+	}
+
+	/// Set the logic operation applied to the color attachments.
+	///
+	/// Requires the `logic_op` feature.
+	pub fn logic_operation(mut self, logic_operation: ops::Logic) -> Self {
+		self.logic_operation = Some(logic_operation);
+		self
+	}
+
+	pub fn blend_constants(mut self, blend_constants: [f32; 4]) -> Self {
+		self.blend_constants = blend_constants;
+		self
+	}
+
+	/// Allow the pipeline to be used as base pipeline of derivative pipelines.
+	pub fn allow_derivatives(mut self) -> Self {
+		self.allow_derivatives = true;
+		self
+	}
+
+	/// Create the pipeline as a derivative of the given pipeline.
+	///
+	/// The parent pipeline is borrowed by the builder, so that it outlives the creation of the derivative.
+	///
+	/// # Panic
+	///
+	/// Panics if the parent pipeline does not allow derivatives.
+	pub fn derivative_of<M: Layout, J: VertexInput, E: DynamicStates>(mut self, parent: &'s Graphics<M, J, E>) -> Self {
+		assert!(parent.allows_derivatives(), "the base pipeline does not allow derivatives");
+		self.base = Base::Handle(parent.handle());
+		self
+	}
+
+	/// Create the pipeline as a derivative of the pipeline of the given index in the batch given to `Graphics::create_many`.
+	///
+	/// The parent pipeline must precede this pipeline in the batch, and allow derivatives.
+	pub fn derivative_of_index(mut self, index: usize) -> Self {
+		self.base = Base::Index(index);
+		self
+	}
+
+	fn into_state(self) -> State<'s, S, L, I> {
+		let color_blend = match self.color_blend {
+			Some(color_blend) => color_blend,
+			None => {
+				let color_blend = match self.blend_attachments {
+					BlendAttachments::Replicated(attachment) => {
+						ColorBlend::replicated(attachment, self.target.color_attachment_count().unwrap_or(0))
+					},
+					BlendAttachments::Explicit(attachments) => {
+						attachments.into_iter().fold(ColorBlend::new(None, [0.0; 4]), |c, a| c.with_attachment(a))
+					}
+				};
+
+				color_blend.with_logic_operation(self.logic_operation).with_blend_constants(self.blend_constants)
+			}
+		};
+
+		State {
+			stages: self.stages,
+			specializations: self.specializations,
+			vertex_input: self.vertex_input,
+			tesselation: self.tesselation,
+			viewports: self.viewports,
+			scissors: self.scissors,
+			rasterization: self.rasterization,
+			multisample: self.multisample,
+			depth_stencil: self.depth_stencil,
+			color_blend,
+			layout: self.layout,
+			target: self.target,
+			dynamic_state_flags: flags::DynamicStates::of::<D>().union(&self.extra_dynamic_states),
+			allow_derivatives: self.allow_derivatives,
+			base: self.base
+		}
+	}
+
+	pub fn build(self, device: &Arc<Device>) -> Result<Graphics<L, I, D>, CreationError> {
+		let cache = self.cache;
+		let mut pipelines = Graphics::create(device, cache, vec![self.into_state()]).map_err(|(e, _)| e)?;
+		Ok(pipelines.pop().unwrap())
+	}
 }
 
 // impl<S, D: dynamic_state::WithViewport> Graphics<D> {
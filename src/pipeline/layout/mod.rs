@@ -4,23 +4,40 @@ use ash::{
 };
 use std::{
 	sync::Arc,
-	marker::PhantomData
+	marker::PhantomData,
+	hash::{
+		Hash,
+		Hasher
+	},
+	fmt
 };
 use crate::{
 	OomError,
 	Device,
 	Resource
 };
+use super::{
+	shader,
+	descriptor::SetLayout
+};
 
 pub mod set;
 pub mod push_constant;
 
-pub use set::Set;
 pub use push_constant::PushConstants;
 
 #[derive(Debug)]
 pub enum CreationError {
 	OutOfMemory(OomError),
+
+	/// The push constant range is empty, misaligned or exceeds the `max_push_constants_size` device limit.
+	InvalidPushConstantRange {
+		range: push_constant::Range,
+		max_size: u32
+	},
+
+	/// The two push constant ranges share some shader stages.
+	PushConstantStagesConflict(push_constant::Range, push_constant::Range)
 }
 
 impl From<vk::Result> for CreationError {
@@ -33,22 +50,48 @@ impl From<vk::Result> for CreationError {
 	}
 }
 
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::InvalidPushConstantRange { range, max_size } => write!(f, "invalid push constant range {:?} (maximum size is {} bytes)", range, max_size),
+			Self::PushConstantStagesConflict(a, b) => write!(f, "push constant ranges {:?} and {:?} share some shader stages", a, b)
+		}
+	}
+}
+
 pub unsafe trait Layout: Resource<Handle=vk::PipelineLayout> {
 	type PushConstants: PushConstants;
 	type Sets;
+
+	/// Description of the layout, used to validate commands at record time.
+	fn description(&self) -> &Description;
 }
 
 unsafe impl<L: std::ops::Deref> Layout for L where L::Target: Layout {
 	type PushConstants = <L::Target as Layout>::PushConstants;
 	type Sets = <L::Target as Layout>::Sets;
+
+	fn description(&self) -> &Description {
+		self.deref().description()
+	}
 }
 
 /// Layout without descriptor sets.
-pub struct NoSets<P: PushConstants>(Raw<P>);
+pub struct NoSets<P: PushConstants>(Raw, PhantomData<P>);
 
 impl<P: PushConstants> NoSets<P> {
 	pub fn new(device: &Arc<Device>) -> Result<Self, CreationError> {
-		Ok(NoSets(Raw::new(device, &[])?))
+		Ok(NoSets(Raw::new(device, &[], P::RANGES)?, PhantomData))
 	}
 }
 
@@ -63,6 +106,10 @@ unsafe impl<P: PushConstants> Resource for NoSets<P> {
 unsafe impl<P: PushConstants> Layout for NoSets<P> {
 	type PushConstants = P;
 	type Sets = ();
+
+	fn description(&self) -> &Description {
+		self.0.description()
+	}
 }
 
 /// Empty layout.
@@ -82,17 +129,82 @@ unsafe impl<P: PushConstants, L: Layout<PushConstants=P>> CompatibleWith<L> for
 /// The `NoSets` layout is compatible with any layout with identical push constant ranges.
 unsafe impl<N: std::ops::Deref<Target=NoSets<P>>, P: PushConstants, L: Layout<PushConstants=P>> CompatibleWith<L> for N {}
 
-pub struct Raw<C: PushConstants> {
+/// The `Raw` layout does not carry its descriptor sets in its type,
+/// so its compatibility is not known from the types.
+/// Instead, draw commands check the bound descriptor sets against its description,
+/// and return `DrawError::IncompatibleDescriptorSets` when they do not match.
+unsafe impl<L: Layout<PushConstants=()>> CompatibleWith<L> for Raw {}
+
+/// Description of a pipeline layout.
+///
+/// Two layouts with the same description are interchangeable.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Description {
+	set_layouts: Vec<vk::DescriptorSetLayout>,
+	push_constant_ranges: Vec<push_constant::Range>
+}
+
+impl Description {
+	pub(crate) fn new(set_layouts: Vec<vk::DescriptorSetLayout>, push_constant_ranges: Vec<push_constant::Range>) -> Description {
+		Description {
+			set_layouts,
+			push_constant_ranges
+		}
+	}
+
+	/// Number of descriptor set layouts.
+	pub fn set_count(&self) -> u32 {
+		self.set_layouts.len() as u32
+	}
+
+	/// Handle of the descriptor set layout at the given index.
+	pub fn set_layout(&self, index: u32) -> Option<vk::DescriptorSetLayout> {
+		self.set_layouts.get(index as usize).cloned()
+	}
+
+	pub fn push_constant_ranges(&self) -> &[push_constant::Range] {
+		&self.push_constant_ranges
+	}
+
+	/// Checks that the given push constant update is valid for this layout.
+	pub fn check_push_constants(&self, stages: shader::Stages, offset: u32, size: u32) -> Result<(), push_constant::UpdateError> {
+		push_constant::Range::check_update(&self.push_constant_ranges, stages, offset, size)
+	}
+}
+
+/// Untyped pipeline layout.
+///
+/// Push constants are not typed and must be set with the recorder `push_constants` command.
+pub struct Raw {
 	device: Arc<Device>,
 	handle: vk::PipelineLayout,
-	pc: PhantomData<C>
+	set_layouts: Vec<SetLayout>,
+	desc: Description
 }
 
-impl<C: PushConstants> Raw<C> {
-	pub fn new(device: &Arc<Device>, set_layouts: &[Set]) -> Result<Raw<C>, CreationError> {
+impl Raw {
+	/// Creates a new pipeline layout.
+	///
+	/// The push constant ranges must fit in the `max_push_constants_size` device limit,
+	/// and no shader stage may appear in more than one range.
+	pub fn new(device: &Arc<Device>, set_layouts: &[&SetLayout], push_constant_ranges: &[push_constant::Range]) -> Result<Raw, CreationError> {
+		let max = device.physical_device().limits().max_push_constants_size();
+		for (i, range) in push_constant_ranges.iter().enumerate() {
+			if range.size() == 0 || range.offset() % 4 != 0 || range.size() % 4 != 0 || range.end().map_or(true, |end| end > max) {
+				return Err(CreationError::InvalidPushConstantRange {
+					range: *range,
+					max_size: max
+				})
+			}
+
+			for other in &push_constant_ranges[(i+1)..] {
+				if range.stages().intersects(&other.stages()) {
+					return Err(CreationError::PushConstantStagesConflict(*range, *other))
+				}
+			}
+		}
+
 		let vk_set_layouts: Vec<_> = set_layouts.iter().map(|l| l.handle()).collect();
-		
-		let push_constant_ranges = C::RANGES;
 
 		let infos = vk::PipelineLayoutCreateInfo {
 			flags: vk::PipelineLayoutCreateFlags::empty(),
@@ -110,19 +222,59 @@ impl<C: PushConstants> Raw<C> {
 		Ok(Raw {
 			device: device.clone(),
 			handle,
-			pc: PhantomData
+			set_layouts: set_layouts.iter().map(|l| (*l).clone()).collect(),
+			desc: Description::new(vk_set_layouts, push_constant_ranges.to_vec())
 		})
 	}
 
 	pub fn handle(&self) -> vk::PipelineLayout {
 		self.handle
 	}
+
+	pub fn set_layouts(&self) -> &[SetLayout] {
+		&self.set_layouts
+	}
+
+	pub fn description(&self) -> &Description {
+		&self.desc
+	}
 }
 
-impl<C: PushConstants> Drop for Raw<C> {
+unsafe impl Resource for Raw {
+	type Handle = vk::PipelineLayout;
+
+	fn handle(&self) -> vk::PipelineLayout {
+		self.handle
+	}
+}
+
+unsafe impl Layout for Raw {
+	type PushConstants = ();
+	type Sets = ();
+
+	fn description(&self) -> &Description {
+		&self.desc
+	}
+}
+
+impl PartialEq for Raw {
+	fn eq(&self, other: &Raw) -> bool {
+		self.desc == other.desc
+	}
+}
+
+impl Eq for Raw {}
+
+impl Hash for Raw {
+	fn hash<H: Hasher>(&self, h: &mut H) {
+		self.desc.hash(h)
+	}
+}
+
+impl Drop for Raw {
 	fn drop(&mut self) {
 		unsafe {
 			self.device.handle().destroy_pipeline_layout(self.handle, None)
 		}
 	}
-}
\ No newline at end of file
+}
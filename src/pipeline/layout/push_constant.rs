@@ -1,4 +1,11 @@
 use ash::vk;
+use std::{
+	hash::{
+		Hash,
+		Hasher
+	},
+	fmt
+};
 use crate::pipeline::shader;
 
 pub unsafe trait PushConstants {
@@ -6,7 +13,7 @@ pub unsafe trait PushConstants {
 }
 
 pub unsafe trait Setter<C: PushConstants> {
-	type Ranges<'a>: AsRef<[(Range, *const u8)]>;
+	type Ranges<'a>: AsRef<[(Range, *const u8)]> where Self: 'a;
 
 	fn ranges<'a>(&'a self) -> Self::Ranges<'a>;
 }
@@ -23,6 +30,40 @@ unsafe impl Setter<()> for () {
 	}
 }
 
+/// Push constant update error.
+#[derive(Debug)]
+pub enum UpdateError {
+	/// The offset or size is not a multiple of 4.
+	Misaligned,
+
+	/// Some bytes of the update are not covered by a push constant range with the given stages.
+	OutOfRange {
+		offset: u32,
+		size: u32
+	},
+
+	/// The update overlaps a push constant range whose stages are not all updated.
+	StagesMismatch {
+		range: shader::Stages,
+		update: shader::Stages
+	}
+}
+
+impl std::error::Error for UpdateError {
+	// ...
+}
+
+impl fmt::Display for UpdateError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Misaligned => write!(f, "push constant offset or size is not a multiple of 4"),
+			Self::OutOfRange { offset, size } => write!(f, "push constant update of {} bytes at offset {} is out of the layout ranges", size, offset),
+			Self::StagesMismatch { range, update } => write!(f, "push constant update stages {:?} do not include the overlapped range stages {:?}", update, range)
+		}
+	}
+}
+
+#[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct Range(pub(crate) vk::PushConstantRange); // This MUST be homomorphic to `vk::PushConstantRange`.
 
@@ -35,6 +76,10 @@ impl Range {
 		})
 	}
 
+	pub fn stages(&self) -> shader::Stages {
+		shader::Stages::from_vulkan(self.0.stage_flags)
+	}
+
 	pub fn offset(&self) -> u32 {
 		self.0.offset
 	}
@@ -42,4 +87,109 @@ impl Range {
 	pub fn size(&self) -> u32 {
 		self.0.size
 	}
-}
\ No newline at end of file
+
+	/// Offset of the first byte after the range.
+	///
+	/// Returns `None` if the end of the range overflows.
+	pub fn end(&self) -> Option<u32> {
+		self.0.offset.checked_add(self.0.size)
+	}
+
+	/// Checks that the given update is valid for the given push constant ranges.
+	pub fn check_update(ranges: &[Range], stages: shader::Stages, offset: u32, size: u32) -> Result<(), UpdateError> {
+		if offset % 4 != 0 || size % 4 != 0 {
+			return Err(UpdateError::Misaligned)
+		}
+
+		let end = offset.checked_add(size).ok_or(UpdateError::OutOfRange { offset, size })?;
+		let flags = stages.into_vulkan();
+
+		// Every updated byte must be covered, for each stage, by some range.
+		for stage in [
+			vk::ShaderStageFlags::VERTEX,
+			vk::ShaderStageFlags::TESSELLATION_CONTROL,
+			vk::ShaderStageFlags::TESSELLATION_EVALUATION,
+			vk::ShaderStageFlags::GEOMETRY,
+			vk::ShaderStageFlags::FRAGMENT,
+			vk::ShaderStageFlags::COMPUTE
+		].iter().filter(|s| flags.contains(**s)) {
+			let mut covered = offset;
+			while covered < end {
+				match ranges.iter().find_map(|r| r.end().filter(|end| r.0.stage_flags.contains(*stage) && r.offset() <= covered && covered < *end)) {
+					Some(range_end) => covered = range_end,
+					None => return Err(UpdateError::OutOfRange { offset, size })
+				}
+			}
+		}
+
+		// Every overlapped range must have all its stages updated.
+		for r in ranges {
+			if r.offset() < end && r.end().map_or(true, |r_end| offset < r_end) && !flags.contains(r.0.stage_flags) {
+				return Err(UpdateError::StagesMismatch {
+					range: r.stages(),
+					update: stages
+				})
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl PartialEq for Range {
+	fn eq(&self, other: &Range) -> bool {
+		self.0.stage_flags == other.0.stage_flags && self.0.offset == other.0.offset && self.0.size == other.0.size
+	}
+}
+
+impl Eq for Range {}
+
+impl Hash for Range {
+	fn hash<H: Hasher>(&self, h: &mut H) {
+		self.0.stage_flags.hash(h);
+		self.0.offset.hash(h);
+		self.0.size.hash(h);
+	}
+}
+
+impl fmt::Debug for Range {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Range")
+			.field("stages", &self.stages())
+			.field("offset", &self.0.offset)
+			.field("size", &self.0.size)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const VERTEX: shader::Stages = shader::Stages { vertex: true, ..shader::Stages::none() };
+	const FRAGMENT: shader::Stages = shader::Stages { fragment: true, ..shader::Stages::none() };
+
+	#[test]
+	fn updates_are_covered() {
+		let ranges = [
+			Range::new(VERTEX, 0, 16),
+			Range::new(FRAGMENT, 16, 16)
+		];
+
+		assert!(matches!(Range::check_update(&ranges, VERTEX, 0, 16), Ok(())));
+		assert!(matches!(Range::check_update(&ranges, VERTEX, 2, 4), Err(UpdateError::Misaligned)));
+		assert!(matches!(Range::check_update(&ranges, VERTEX, 8, 16), Err(UpdateError::OutOfRange { offset: 8, size: 16 })));
+	}
+
+	#[test]
+	fn overflowing_updates() {
+		let ranges = [
+			Range::new(VERTEX, 0, 16),
+			Range::new(VERTEX, 16, u32::MAX - 15)
+		];
+
+		assert_eq!(ranges[1].end(), None);
+		assert!(matches!(Range::check_update(&ranges, VERTEX, 8, u32::MAX - 7), Err(UpdateError::OutOfRange { offset: 8, .. })));
+		assert!(matches!(Range::check_update(&ranges, VERTEX, 12, 8), Err(UpdateError::OutOfRange { offset: 12, size: 8 })));
+	}
+}
@@ -1,7 +1,11 @@
+use ash::vk;
+
 pub mod shader;
 pub mod stage;
+pub mod descriptor;
 pub mod layout;
 pub mod vertex_input;
+pub mod vertex;
 pub mod input_assembly;
 pub mod tesselation;
 pub mod viewport;
@@ -10,13 +14,18 @@ pub mod rasterization;
 pub mod multisample;
 pub mod depth_test;
 pub mod stencil_test;
+pub mod depth_stencil;
 pub mod color_blend;
 pub mod dynamic_state;
+pub mod specialization;
+pub mod cache;
 pub mod graphics;
+pub mod compute;
 
 pub use stage::Stages;
 pub use layout::Layout;
 pub use vertex_input::VertexInput;
+pub use vertex::Vertex;
 pub use input_assembly::InputAssembly;
 pub use tesselation::Tesselation;
 pub use viewport::Viewport;
@@ -25,12 +34,39 @@ pub use rasterization::Rasterization;
 pub use multisample::Multisample;
 pub use depth_test::DepthTest;
 pub use stencil_test::StencilTest;
+pub use depth_stencil::{
+	DepthStencil,
+	DepthBoundsError
+};
 pub use color_blend::ColorBlend;
 pub use dynamic_state::{
 	DynamicState,
 	DynamicStates
 };
+pub use specialization::Specialization;
+pub use cache::Cache;
 pub use graphics::{
 	GraphicsPipeline,
 	Graphics
-};
\ No newline at end of file
+};
+pub use compute::{
+	ComputePipeline,
+	Compute
+};
+
+/// Pipeline type bound resources are used by.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BindPoint {
+	Graphics,
+	Compute
+}
+
+impl BindPoint {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::PipelineBindPoint {
+		match self {
+			BindPoint::Graphics => vk::PipelineBindPoint::GRAPHICS,
+			BindPoint::Compute => vk::PipelineBindPoint::COMPUTE
+		}
+	}
+}
@@ -24,6 +24,11 @@ impl Multisample {
 		})
 	}
 
+	/// Number of rasterization samples.
+	pub fn rasterization_samples(&self) -> SampleCount {
+		SampleCount::from_vulkan(self.0.rasterization_samples)
+	}
+
 	pub(crate) fn as_vulkan(&self) -> &vk::PipelineMultisampleStateCreateInfo {
 		&self.0
 	}
@@ -1,6 +1,6 @@
 use ash::vk;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(i32)]
 pub enum PolygonMode {
 	Fill = vk::PolygonMode::FILL.as_raw(),
@@ -14,7 +14,7 @@ impl PolygonMode {
 	}
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(u32)]
 pub enum CullMode {
 	None = vk::CullModeFlags::NONE.as_raw(),
@@ -29,7 +29,7 @@ impl CullMode {
 	}
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(i32)]
 pub enum FrontFace {
 	Clockwise = vk::FrontFace::CLOCKWISE.as_raw(),
@@ -43,13 +43,42 @@ impl FrontFace {
 }
 
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct DepthBias {
 	constant_factor: f32,
 	clamp: f32,
 	slope_factor: f32
 }
 
+impl DepthBias {
+	pub fn new(constant_factor: f32, clamp: f32, slope_factor: f32) -> DepthBias {
+		DepthBias {
+			constant_factor,
+			clamp,
+			slope_factor
+		}
+	}
+
+	#[inline]
+	pub fn constant_factor(&self) -> f32 {
+		self.constant_factor
+	}
+
+	/// Maximum (or minimum) depth bias of a fragment.
+	///
+	/// A non-zero clamp requires the `DepthBiasClamp` device feature.
+	#[inline]
+	pub fn clamp(&self) -> f32 {
+		self.clamp
+	}
+
+	#[inline]
+	pub fn slope_factor(&self) -> f32 {
+		self.slope_factor
+	}
+}
+
+#[derive(Clone, Copy)]
 pub struct Rasterization(vk::PipelineRasterizationStateCreateInfo);
 
 impl Rasterization {
@@ -81,7 +110,100 @@ impl Rasterization {
 		})
 	}
 
+	/// Polygon modes other than `Fill` require the `FillModeNonSolid` device feature.
+	pub fn with_polygon_mode(mut self, polygon_mode: PolygonMode) -> Self {
+		self.0.polygon_mode = polygon_mode.into_vulkan();
+		self
+	}
+
+	pub fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+		self.0.cull_mode = cull_mode.into_vulkan();
+		self
+	}
+
+	pub fn with_front_face(mut self, front_face: FrontFace) -> Self {
+		self.0.front_face = front_face.into_vulkan();
+		self
+	}
+
+	/// Requires the `DepthClamp` device feature.
+	pub fn with_depth_clamp(mut self, depth_clamp: bool) -> Self {
+		self.0.depth_clamp_enable = if depth_clamp { vk::TRUE } else { vk::FALSE };
+		self
+	}
+
+	/// Discard the primitives right before rasterization.
+	pub fn with_rasterizer_discard(mut self, discard: bool) -> Self {
+		self.0.rasterizer_discard_enable = if discard { vk::TRUE } else { vk::FALSE };
+		self
+	}
+
+	/// The bias values are ignored if the depth bias is a dynamic state of the pipeline.
+	pub fn with_depth_bias(mut self, depth_bias: DepthBias) -> Self {
+		self.0.depth_bias_enable = vk::TRUE;
+		self.0.depth_bias_constant_factor = depth_bias.constant_factor;
+		self.0.depth_bias_clamp = depth_bias.clamp;
+		self.0.depth_bias_slope_factor = depth_bias.slope_factor;
+		self
+	}
+
+	pub fn without_depth_bias(mut self) -> Self {
+		self.0.depth_bias_enable = vk::FALSE;
+		self.0.depth_bias_constant_factor = 0.0;
+		self.0.depth_bias_clamp = 0.0;
+		self.0.depth_bias_slope_factor = 0.0;
+		self
+	}
+
+	/// Line widths other than `1.0` require the `WideLines` device feature.
+	///
+	/// Ignored if the line width is a dynamic state of the pipeline.
+	pub fn with_line_width(mut self, line_width: f32) -> Self {
+		self.0.line_width = line_width;
+		self
+	}
+
+	#[inline]
+	pub fn polygon_mode(&self) -> PolygonMode {
+		match self.0.polygon_mode {
+			vk::PolygonMode::LINE => PolygonMode::Line,
+			vk::PolygonMode::POINT => PolygonMode::Point,
+			_ => PolygonMode::Fill
+		}
+	}
+
+	#[inline]
+	pub fn is_depth_clamp_enabled(&self) -> bool {
+		self.0.depth_clamp_enable == vk::TRUE
+	}
+
+	#[inline]
+	pub fn is_rasterizer_discard_enabled(&self) -> bool {
+		self.0.rasterizer_discard_enable == vk::TRUE
+	}
+
+	#[inline]
+	pub fn depth_bias(&self) -> Option<DepthBias> {
+		if self.0.depth_bias_enable == vk::TRUE {
+			Some(DepthBias::new(self.0.depth_bias_constant_factor, self.0.depth_bias_clamp, self.0.depth_bias_slope_factor))
+		} else {
+			None
+		}
+	}
+
+	#[inline]
+	pub fn line_width(&self) -> f32 {
+		self.0.line_width
+	}
+
 	pub(crate) fn as_vulkan(&self) -> &vk::PipelineRasterizationStateCreateInfo {
 		&self.0
 	}
+}
+
+/// Filled polygons, with back faces culled and counter-clockwise front faces.
+impl Default for Rasterization {
+	fn default() -> Self {
+		Self::new(false, false, PolygonMode::Fill, CullMode::Back, FrontFace::CounterClockwise, None, 1.0)
+	}
 }
\ No newline at end of file
@@ -5,18 +5,23 @@ use std::{
 	},
 	sync::Arc
 };
-use super::Module;
+use super::{
+	Module,
+	Stage
+};
 
 pub struct EntryPoint {
 	module: Arc<Module>,
-	name: CString
+	name: CString,
+	stage: Stage
 }
 
 impl EntryPoint {
-	pub(crate) unsafe fn new(module: &Arc<Module>, name: CString) -> EntryPoint {
+	pub(crate) unsafe fn new(module: &Arc<Module>, name: CString, stage: Stage) -> EntryPoint {
 		EntryPoint {
 			module: module.clone(),
-			name
+			name,
+			stage
 		}
 	}
 
@@ -27,4 +32,8 @@ impl EntryPoint {
 	pub fn name(&self) -> &CStr {
 		&self.name
 	}
+
+	pub fn stage(&self) -> Stage {
+		self.stage
+	}
 }
\ No newline at end of file
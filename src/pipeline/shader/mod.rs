@@ -2,7 +2,12 @@ mod module;
 mod stage;
 mod entry_point;
 
-pub use module::Module;
+pub use module::{
+	Module,
+	CreationError
+};
+#[doc(hidden)]
+pub use module::spirv_words;
 pub use stage::{
 	Stage,
 	Stages
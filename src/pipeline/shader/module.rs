@@ -2,16 +2,32 @@ use ash::{
 	vk,
 	version::DeviceV1_0
 };
-use std::sync::Arc;
+use std::{
+	sync::Arc,
+	borrow::Cow,
+	fmt
+};
 use crate::{
 	OomError,
 	device::Device
 };
-use super::EntryPoint;
+use super::{
+	EntryPoint,
+	Stage
+};
+
+/// SPIR-V magic number.
+const MAGIC: u32 = 0x07230203;
 
 #[derive(Debug)]
 pub enum CreationError {
 	OomError(OomError),
+
+	/// The code length is not a non-zero multiple of 4 bytes.
+	InvalidLength(usize),
+
+	/// The code does not start with the SPIR-V magic number.
+	InvalidMagic(u32)
 }
 
 impl From<vk::Result> for CreationError {
@@ -24,31 +40,84 @@ impl From<vk::Result> for CreationError {
 	}
 }
 
+impl std::error::Error for CreationError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::OomError(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for CreationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OomError(e) => e.fmt(f),
+			Self::InvalidLength(len) => write!(f, "invalid SPIR-V code length: {} bytes", len),
+			Self::InvalidMagic(magic) => write!(f, "invalid SPIR-V magic number: {:#010x}", magic)
+		}
+	}
+}
+
 pub struct Module {
 	device: Arc<Device>,
 	handle: vk::ShaderModule
 }
 
 impl Module {
-	pub unsafe fn new<B: AsRef<[u8]>>(device: &Arc<Device>, vspir: B) -> Result<Module, CreationError> {
-		let bytes = vspir.as_ref();
+	/// Create a shader module from SPIR-V code.
+	///
+	/// Code with the opposite endianness is byte-swapped.
+	///
+	/// # Safety
+	///
+	/// Only the SPIR-V header is checked: the code must be valid SPIR-V for the device.
+	pub unsafe fn new(device: &Arc<Device>, spirv: &[u32]) -> Result<Module, CreationError> {
+		let words = match spirv.first() {
+			Some(&MAGIC) => Cow::Borrowed(spirv),
+			Some(&magic) if magic.swap_bytes() == MAGIC => Cow::Owned(spirv.iter().map(|w| w.swap_bytes()).collect()),
+			Some(&magic) => return Err(CreationError::InvalidMagic(magic)),
+			None => return Err(CreationError::InvalidLength(0))
+		};
 
 		let infos = vk::ShaderModuleCreateInfo {
-			code_size: bytes.len(),
-			p_code: bytes.as_ptr() as *const u32,
+			code_size: words.len() * 4,
+			p_code: words.as_ptr(),
 			..Default::default()
 		};
 
 		let handle = device.handle().create_shader_module(&infos, None)?;
-	
+
 		Ok(Module {
 			device: device.clone(),
 			handle
 		})
 	}
 
-	pub unsafe fn entry_point(self: &Arc<Self>, name: &str) -> EntryPoint {
-		EntryPoint::new(self, std::ffi::CString::new(name).expect("invalid shader module entry point name"))
+	/// Create a shader module from SPIR-V code given as bytes, such as the content of a `.spv` file.
+	///
+	/// The bytes do not need to be aligned.
+	///
+	/// # Safety
+	///
+	/// Only the SPIR-V header is checked: the code must be valid SPIR-V for the device.
+	pub unsafe fn from_bytes<B: AsRef<[u8]>>(device: &Arc<Device>, bytes: B) -> Result<Module, CreationError> {
+		let bytes = bytes.as_ref();
+		if bytes.len() % 4 != 0 {
+			return Err(CreationError::InvalidLength(bytes.len()))
+		}
+
+		let words: Vec<u32> = bytes.chunks_exact(4).map(|w| u32::from_ne_bytes([w[0], w[1], w[2], w[3]])).collect();
+		Self::new(device, &words)
+	}
+
+	/// Entry point of the module with the given name, for the given stage.
+	///
+	/// # Safety
+	///
+	/// The module must define an entry point with this name for this stage.
+	pub unsafe fn entry_point(self: &Arc<Self>, name: &str, stage: Stage) -> EntryPoint {
+		EntryPoint::new(self, std::ffi::CString::new(name).expect("invalid shader module entry point name"), stage)
 	}
 
 	pub(crate) fn handle(&self) -> vk::ShaderModule {
@@ -62,4 +131,25 @@ impl Drop for Module {
 			self.device.handle().destroy_shader_module(self.handle, None)
 		}
 	}
-}
\ No newline at end of file
+}
+
+/// Reinterpret bytes included with `include_spirv!` as SPIR-V words.
+#[doc(hidden)]
+pub fn spirv_words(bytes: &'static [u8]) -> &'static [u32] {
+	assert!(bytes.len() % 4 == 0, "invalid SPIR-V code length");
+	assert!(bytes.as_ptr() as usize % std::mem::align_of::<u32>() == 0, "unaligned SPIR-V code");
+	unsafe {
+		std::slice::from_raw_parts(bytes.as_ptr() as *const u32, bytes.len() / 4)
+	}
+}
+
+/// Include a compiled SPIR-V file as a `&'static [u32]`, to be given to `Module::new`.
+#[macro_export]
+macro_rules! include_spirv {
+	($path:expr) => {{
+		#[repr(C, align(4))]
+		struct Aligned<B: ?Sized>(B);
+		static ALIGNED: &Aligned<[u8]> = &Aligned(*include_bytes!($path));
+		$crate::pipeline::shader::spirv_words(&ALIGNED.0)
+	}};
+}
@@ -1,6 +1,6 @@
 use ash::vk;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[repr(u32)]
 pub enum Stage {
 	Vertex = vk::ShaderStageFlags::VERTEX.as_raw(),
@@ -19,7 +19,7 @@ impl Stage {
 
 /// Describes which shader stages have access to a descriptor.
 // TODO: add example with BitOr
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Stages {
 	/// `True` means that the descriptor will be used by the vertex shader.
 	pub vertex: bool,
@@ -116,6 +116,18 @@ impl Stages {
 			|| (self.compute && other.compute)
 	}
 
+	#[inline]
+	pub(crate) fn from_vulkan(flags: vk::ShaderStageFlags) -> Stages {
+		Stages {
+			vertex: flags.contains(vk::ShaderStageFlags::VERTEX),
+			tessellation_control: flags.contains(vk::ShaderStageFlags::TESSELLATION_CONTROL),
+			tessellation_evaluation: flags.contains(vk::ShaderStageFlags::TESSELLATION_EVALUATION),
+			geometry: flags.contains(vk::ShaderStageFlags::GEOMETRY),
+			fragment: flags.contains(vk::ShaderStageFlags::FRAGMENT),
+			compute: flags.contains(vk::ShaderStageFlags::COMPUTE),
+		}
+	}
+
 	#[inline]
 	pub(crate) const fn into_vulkan(self) -> vk::ShaderStageFlags {
 		let mut result = 0;
@@ -0,0 +1,174 @@
+use ash::vk;
+use super::shader;
+
+/// Specialization constant value.
+pub unsafe trait Constant {
+	/// Append the value to the specialization data, as expected by the shader.
+	fn write(&self, data: &mut Vec<u8>);
+}
+
+macro_rules! constants {
+	($($ty:ty),*) => {
+		$(
+			unsafe impl Constant for $ty {
+				fn write(&self, data: &mut Vec<u8>) {
+					data.extend_from_slice(&self.to_ne_bytes())
+				}
+			}
+		)*
+	};
+}
+
+constants!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+/// Booleans are encoded as 32-bit `VkBool32` values.
+unsafe impl Constant for bool {
+	fn write(&self, data: &mut Vec<u8>) {
+		let value: vk::Bool32 = if *self { vk::TRUE } else { vk::FALSE };
+		data.extend_from_slice(&value.to_ne_bytes())
+	}
+}
+
+/// Specialization constants of a shader stage.
+#[derive(Clone, Default, Debug)]
+pub struct Specialization {
+	entries: Vec<vk::SpecializationMapEntry>,
+	data: Vec<u8>
+}
+
+impl Specialization {
+	pub fn new() -> Specialization {
+		Specialization::default()
+	}
+
+	/// Set the value of the constant with the given `constant_id`.
+	///
+	/// # Panic
+	///
+	/// Panics if the constant is already set.
+	pub fn constant<T: Constant>(mut self, id: u32, value: T) -> Self {
+		assert!(!self.contains(id), "specialization constant {} is set twice", id);
+
+		let offset = self.data.len();
+		value.write(&mut self.data);
+		self.entries.push(vk::SpecializationMapEntry {
+			constant_id: id,
+			offset: offset as u32,
+			size: self.data.len() - offset
+		});
+		self
+	}
+
+	/// Checks if the constant with the given `constant_id` is set.
+	pub fn contains(&self, id: u32) -> bool {
+		self.entries.iter().any(|e| e.constant_id == id)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// The returned structure borrows `self`.
+	pub(crate) fn as_vulkan(&self) -> vk::SpecializationInfo {
+		vk::SpecializationInfo {
+			map_entry_count: self.entries.len() as u32,
+			p_map_entries: self.entries.as_ptr(),
+			data_size: self.data.len(),
+			p_data: self.data.as_ptr() as *const _
+		}
+	}
+}
+
+/// Checks that every specialized stage is one of the stages of the pipeline.
+///
+/// Returns the first specialized stage missing from the pipeline.
+pub(crate) fn check_stages<'s, I>(specializations: I, stages: &[shader::Stage]) -> Result<(), shader::Stage>
+where
+	I: IntoIterator<Item=&'s (shader::Stage, Specialization)>
+{
+	for (stage, _) in specializations {
+		if !stages.contains(stage) {
+			return Err(*stage)
+		}
+	}
+
+	Ok(())
+}
+
+/// Build a `Specialization` from a list of `constant_id => value` pairs.
+///
+/// ```ignore
+/// let specialization = specialize! { 0 => 64u32, 1 => true };
+/// ```
+#[macro_export]
+macro_rules! specialize {
+	($($id:expr => $value:expr),* $(,)?) => {
+		$crate::pipeline::Specialization::new()$(.constant($id, $value))*
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn map_entries_follow_data() {
+		let s = Specialization::new().constant(3, 7u8).constant(0, 1u64).constant(1, 2u16);
+
+		let entries: Vec<_> = s.entries.iter().map(|e| (e.constant_id, e.offset, e.size)).collect();
+		assert_eq!(entries, vec![(3, 0, 1), (0, 1, 8), (1, 9, 2)]);
+		assert_eq!(s.data.len(), 11);
+
+		let mut expected = vec![7u8];
+		expected.extend_from_slice(&1u64.to_ne_bytes());
+		expected.extend_from_slice(&2u16.to_ne_bytes());
+		assert_eq!(s.data, expected);
+	}
+
+	#[test]
+	fn booleans_are_32_bits() {
+		let s = Specialization::new().constant(0, true).constant(1, false);
+
+		assert_eq!(s.entries[0].size, 4);
+		assert_eq!(s.entries[1].offset, 4);
+		assert_eq!(&s.data[0..4], &vk::TRUE.to_ne_bytes());
+		assert_eq!(&s.data[4..8], &vk::FALSE.to_ne_bytes());
+	}
+
+	#[test]
+	fn vulkan_info_references_entries_and_data() {
+		let s = specialize! { 0 => 1.5f32, 4 => -2i32 };
+		assert!(s.contains(0) && s.contains(4) && !s.contains(1));
+
+		let info = s.as_vulkan();
+		assert_eq!(info.map_entry_count, 2);
+		assert_eq!(info.data_size, 8);
+		assert_eq!(info.p_map_entries, s.entries.as_ptr());
+		assert_eq!(info.p_data as *const u8, s.data.as_ptr());
+	}
+
+	#[test]
+	#[should_panic]
+	fn constant_set_twice() {
+		Specialization::new().constant(0, 1u32).constant(0, 2u32);
+	}
+
+	#[test]
+	fn specialized_stages_must_be_in_the_pipeline() {
+		let stages = [shader::Stage::Vertex, shader::Stage::Fragment];
+		let specializations = vec![
+			(shader::Stage::Vertex, Specialization::new().constant(0, 1u32)),
+			(shader::Stage::Fragment, Specialization::new())
+		];
+
+		assert_eq!(check_stages(&specializations, &stages), Ok(()));
+		assert_eq!(check_stages(&[], &stages), Ok(()));
+
+		let specializations = vec![
+			(shader::Stage::Vertex, Specialization::new()),
+			(shader::Stage::Geometry, Specialization::new().constant(0, true))
+		];
+
+		assert_eq!(check_stages(&specializations, &stages), Err(shader::Stage::Geometry));
+	}
+}
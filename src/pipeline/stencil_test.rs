@@ -29,6 +29,21 @@ impl Default for Operation {
 	}
 }
 
+/// Faces affected by a stencil state update.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum Faces {
+	Front = vk::StencilFaceFlags::FRONT.as_raw(),
+	Back = vk::StencilFaceFlags::BACK.as_raw(),
+	FrontAndBack = vk::StencilFaceFlags::FRONT_AND_BACK.as_raw()
+}
+
+impl Faces {
+	pub(crate) fn into_vulkan(self) -> vk::StencilFaceFlags {
+		vk::StencilFaceFlags::from_raw(self as u32)
+	}
+}
+
 #[derive(Clone, Copy, Default, Debug)]
 pub struct StencilTest {
 	pub fail_operation: Operation,
@@ -0,0 +1,231 @@
+//! Vertex layouts.
+//!
+//! Vertex types describe their attributes with the `Vertex` trait,
+//! usually derived with the `vertex_input!` macro:
+//!
+//! ```ignore
+//! magma::vertex_input! {
+//! 	#[derive(Clone, Copy)]
+//! 	pub struct MyVertex {
+//! 		pub position: [f32; 3], // location 0
+//! 		pub color: [f32; 4] // location 1
+//! 	}
+//! }
+//!
+//! let vertex_input = pipeline::vertex::input::<MyVertex>();
+//! ```
+use std::marker::PhantomData;
+use crate::{
+	Format,
+	mem::{
+		LocalBuffers,
+		TypedBuffer
+	}
+};
+use super::{
+	input_assembly,
+	InputAssembly,
+	vertex_input::{
+		self,
+		Binding,
+		Rate,
+		Bind,
+		VertexInput
+	}
+};
+
+/// Type that can be used as a vertex attribute.
+///
+/// The format is picked from the type, so a field cannot be read with a mismatched format.
+pub unsafe trait AttributeType: Copy {
+	/// Format of the attribute.
+	const FORMAT: Format;
+
+	/// Format, size and alignment of the attribute.
+	#[doc(hidden)]
+	const LAYOUT: (Format, usize, usize) = (Self::FORMAT, std::mem::size_of::<Self>(), std::mem::align_of::<Self>());
+}
+
+macro_rules! attribute_types {
+	($($ty:ty => $format:ident),*) => {
+		$(
+			unsafe impl AttributeType for $ty {
+				const FORMAT: Format = Format::$format;
+			}
+		)*
+	};
+}
+
+attribute_types! {
+	f32 => R32Sfloat,
+	[f32; 2] => R32G32Sfloat,
+	[f32; 3] => R32G32B32Sfloat,
+	[f32; 4] => R32G32B32A32Sfloat,
+	u32 => R32Uint,
+	[u32; 2] => R32G32Uint,
+	[u32; 3] => R32G32B32Uint,
+	[u32; 4] => R32G32B32A32Uint,
+	i32 => R32Sint,
+	[i32; 2] => R32G32Sint,
+	[i32; 3] => R32G32B32Sint,
+	[i32; 4] => R32G32B32A32Sint,
+	u16 => R16Uint,
+	[u16; 2] => R16G16Uint,
+	[u16; 4] => R16G16B16A16Uint,
+	i16 => R16Sint,
+	[i16; 2] => R16G16Sint,
+	[i16; 4] => R16G16B16A16Sint,
+	u8 => R8Uint,
+	[u8; 2] => R8G8Uint,
+	[u8; 4] => R8G8B8A8Uint,
+	i8 => R8Sint,
+	[i8; 2] => R8G8Sint,
+	[i8; 4] => R8G8B8A8Sint
+}
+
+/// Vertex attribute, independent of the binding it is read from.
+#[derive(Clone, Copy, Debug)]
+pub struct Attribute {
+	pub location: u32,
+	pub format: Format,
+
+	/// Offset of the attribute in the vertex, in bytes.
+	pub offset: u32
+}
+
+impl Attribute {
+	pub const fn new(location: u32, format: Format, offset: u32) -> Attribute {
+		Attribute {
+			location,
+			format,
+			offset
+		}
+	}
+
+	/// Attribute description for the given binding.
+	#[inline]
+	pub const fn with_binding(&self, binding: u32) -> vertex_input::Attribute {
+		vertex_input::Attribute::new(self.location, binding, self.format, self.offset)
+	}
+}
+
+/// Vertex type.
+///
+/// ## Safety
+///
+/// The attributes must be inside of the vertex, and their format must match the type of the data at their offset.
+pub unsafe trait Vertex: 'static + Copy {
+	/// Size of a vertex in bytes.
+	const STRIDE: u32;
+
+	const ATTRIBUTES: &'static [Attribute];
+}
+
+macro_rules! single_attribute_vertices {
+	($($ty:ty),*) => {
+		$(
+			unsafe impl Vertex for $ty {
+				const STRIDE: u32 = std::mem::size_of::<$ty>() as u32;
+
+				const ATTRIBUTES: &'static [Attribute] = &[Attribute::new(0, <$ty as AttributeType>::FORMAT, 0)];
+			}
+		)*
+	};
+}
+
+single_attribute_vertices! {
+	[f32; 2],
+	[f32; 3],
+	[f32; 4]
+}
+
+/// Attributes of a `#[repr(C)]` struct with the given field layouts, with consecutive locations.
+#[doc(hidden)]
+pub const fn repr_c_attributes<const N: usize>(fields: [(Format, usize, usize); N]) -> [Attribute; N] {
+	let mut attributes = [Attribute::new(0, Format::R8Uint, 0); N];
+	let mut offset = 0;
+	let mut i = 0;
+	while i < N {
+		let (format, size, align) = fields[i];
+		offset = (offset + align - 1) / align * align;
+		attributes[i] = Attribute::new(i as u32, format, offset as u32);
+		offset += size;
+		i += 1;
+	}
+
+	attributes
+}
+
+/// Define a `#[repr(C)]` vertex struct and derive its `Vertex` implementation.
+///
+/// Each field is an attribute, at the location given by its position in the struct,
+/// with the format of its `AttributeType`.
+#[macro_export]
+macro_rules! vertex_input {
+	(
+		$(#[$meta:meta])*
+		$vis:vis struct $name:ident {
+			$($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+		}
+	) => {
+		$(#[$meta])*
+		#[repr(C)]
+		$vis struct $name {
+			$($(#[$field_meta])* $field_vis $field: $ty),*
+		}
+
+		unsafe impl $crate::pipeline::vertex::Vertex for $name {
+			const STRIDE: u32 = std::mem::size_of::<$name>() as u32;
+
+			const ATTRIBUTES: &'static [$crate::pipeline::vertex::Attribute] = &$crate::pipeline::vertex::repr_c_attributes([
+				$(<$ty as $crate::pipeline::vertex::AttributeType>::LAYOUT),*
+			]);
+		}
+	};
+}
+
+/// Vertex input reading vertices of type `V` from the binding 0,
+/// assembled with `A`.
+pub struct Input<V: Vertex, A: InputAssembly = input_assembly::TriangleList> {
+	bindings: [Binding; 1],
+	attributes: Vec<vertex_input::Attribute>,
+	v: PhantomData<(V, A)>
+}
+
+impl<V: Vertex, A: InputAssembly> Input<V, A> {
+	pub fn new() -> Input<V, A> {
+		Input {
+			bindings: [Binding::new(0, V::STRIDE, Rate::Vertex)],
+			attributes: V::ATTRIBUTES.iter().map(|attribute| attribute.with_binding(0)).collect(),
+			v: PhantomData
+		}
+	}
+}
+
+/// Vertex input reading vertices of type `V` as a triangle list.
+#[inline]
+pub fn input<V: Vertex>() -> Input<V> {
+	Input::new()
+}
+
+unsafe impl<V: Vertex, A: 'static + InputAssembly> VertexInput for Input<V, A> {
+	type Assembly = A;
+
+	fn bindings(&self) -> &[Binding] {
+		&self.bindings
+	}
+
+	fn attributes(&self) -> &[vertex_input::Attribute] {
+		&self.attributes
+	}
+}
+
+unsafe impl<'a, V: Vertex, A: 'static + InputAssembly, B: 'a + TypedBuffer<Item=V>> Bind<'a, Input<V, A>> for B {
+	type Offsets = [u64; 1];
+
+	fn get(self) -> (u32, LocalBuffers<'a>, [u64; 1]) {
+		let mut buffers = LocalBuffers::new();
+		buffers.push(self);
+		(0, buffers, [0])
+	}
+}
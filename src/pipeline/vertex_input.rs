@@ -35,6 +35,7 @@ impl Binding {
 	}
 }
 
+#[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct Attribute(vk::VertexInputAttributeDescription); // This MUST be homomorphic to `vk::VertexInputAttributeDescription`.
 
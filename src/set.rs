@@ -1,5 +1,5 @@
 macro_rules! set_c_names {
-	($elem:ident, $set:ident, $($field:ident : $var:ident => $s:expr,)*) => {
+	($elem:ident, $set:ident, $([$raw:ident])? $($field:ident : $var:ident => $s:expr,)*) => {
 		impl $elem {
 			pub fn from_c_name(c_name: &CStr) -> Option<$elem> {
 				use $elem::*;
@@ -69,9 +69,69 @@ macro_rules! set_c_names {
 					}
 				)*
 
+				$(
+					for name in &self.$raw {
+						if !first { write!(f, ", ")? }
+						else { first = false; }
+						f.write_str(&name.to_string_lossy())?;
+					}
+				)?
+
 				write!(f, "]")
 			}
 		}
+
+		$(
+			impl $set {
+				/// Checks if the set contains the element of the given name.
+				///
+				/// The name may or may not be known by this crate.
+				#[inline]
+				pub fn contains_raw(&self, c_name: &CStr) -> bool {
+					match $elem::from_c_name(c_name) {
+						Some(item) => self.contains(item),
+						None => self.$raw.iter().any(|name| name.as_c_str() == c_name)
+					}
+				}
+
+				/// Inserts the element of the given name in the set.
+				///
+				/// If the name is not known by this crate,
+				/// it is stored as is in the list of raw names.
+				#[inline]
+				pub fn insert_raw(&mut self, c_name: &CStr) {
+					match $elem::from_c_name(c_name) {
+						Some(item) => self.insert(item),
+						None => {
+							if !self.$raw.iter().any(|name| name.as_c_str() == c_name) {
+								self.$raw.push(c_name.to_owned())
+							}
+						}
+					}
+				}
+
+				/// Removes the element of the given name from the set.
+				#[inline]
+				pub fn remove_raw(&mut self, c_name: &CStr) {
+					match $elem::from_c_name(c_name) {
+						Some(item) => self.remove(item),
+						None => self.$raw.retain(|name| name.as_c_str() != c_name)
+					}
+				}
+
+				/// Names of the elements of the set that are not known by this crate.
+				#[inline]
+				pub fn raw_names(&self) -> impl '_ + Iterator<Item=&CStr> {
+					self.$raw.iter().map(|name| name.as_c_str())
+				}
+
+				/// Names of all the elements of the set, known or not.
+				#[inline]
+				pub fn c_names(&self) -> impl '_ + Iterator<Item=&CStr> {
+					self.into_iter().map(|item| item.c_name()).chain(self.raw_names())
+				}
+			}
+		)?
 	}
 }
 
@@ -148,7 +208,7 @@ macro_rules! set {
 		set!(@iter ($this) $i + 1u8, $($tfield : $tvar => $ts,)*)
 	};
 
-	($elem:ident, $set:ident, $iter:ident, $into_iter:ident, $($field:ident : $var:ident => $s:expr,)*) => {
+	($elem:ident, $set:ident, $iter:ident, $into_iter:ident, $([$raw:ident])? $($field:ident : $var:ident => $s:expr,)*) => {
 		#[derive(Copy, Clone, PartialEq, Eq)]
 		pub enum $elem {
 			$(
@@ -164,6 +224,11 @@ macro_rules! set {
 				pub $field: bool,
 			)*
 
+			$(
+				/// Elements unknown to this crate, by name.
+				pub $raw: Vec<std::ffi::CString>,
+			)?
+
 			pub _unbuildable: Unbuildable,
 		}
 
@@ -173,10 +238,17 @@ macro_rules! set {
 			pub fn none() -> $set {
 				$set {
 					$($field: false,)*
+					$($raw: Vec::new(),)?
 					_unbuildable: Unbuildable(())
 				}
 			}
 
+			/// Checks if no member is set.
+			#[inline]
+			pub fn is_empty(&self) -> bool {
+				$(!self.$field &&)* true $(&& self.$raw.is_empty())?
+			}
+
 			#[inline]
 			pub fn contains(&self, item: $elem) -> bool {
 				use $elem::*;
@@ -214,6 +286,7 @@ macro_rules! set {
 					$(
 						$field: self.$field || other.$field,
 					)*
+					$($raw: $crate::set::raw_union(&self.$raw, &other.$raw),)?
 					_unbuildable: Unbuildable(())
 				}
 			}
@@ -225,6 +298,7 @@ macro_rules! set {
 					$(
 						$field: self.$field && other.$field,
 					)*
+					$($raw: $crate::set::raw_intersection(&self.$raw, &other.$raw),)?
 					_unbuildable: Unbuildable(())
 				}
 			}
@@ -236,6 +310,7 @@ macro_rules! set {
 					$(
 						$field: self.$field && !other.$field,
 					)*
+					$($raw: $crate::set::raw_difference(&self.$raw, &other.$raw),)?
 					_unbuildable: Unbuildable(())
 				}
 			}
@@ -312,11 +387,17 @@ macro_rules! extensions {
 	}
 }
 
+macro_rules! raw_extensions {
+	($($field:ident : $var:ident => $s:expr,)*) => {
+		set!(Extension, Extensions, ExtensionsIter, ExtensionsIntoIter, [raw] $($field : $var => $s,)*);
+		set_c_names!(Extension, Extensions, [raw] $($field : $var => $s,)*);
+	}
+}
+
 macro_rules! validation_layers {
 	($($field:ident : $var:ident => $s:expr,)*) => {
-		set!(ValidationLayer, ValidationLayers, ValidationLayersIter, ValidationLayersIntoIter, $($field : $var => $s,)*);
-		set_c_names!(ValidationLayer, ValidationLayers, $($field : $var => $s,)*);
-		impl Copy for ValidationLayers { }
+		set!(ValidationLayer, ValidationLayers, ValidationLayersIter, ValidationLayersIntoIter, [raw] $($field : $var => $s,)*);
+		set_c_names!(ValidationLayer, ValidationLayers, [raw] $($field : $var => $s,)*);
 	}
 }
 
@@ -359,3 +440,24 @@ macro_rules! features {
 		}
 	}
 }
+
+use std::ffi::CString;
+
+pub(crate) fn raw_union(a: &[CString], b: &[CString]) -> Vec<CString> {
+	let mut result = a.to_vec();
+	for name in b {
+		if !a.contains(name) {
+			result.push(name.clone())
+		}
+	}
+
+	result
+}
+
+pub(crate) fn raw_intersection(a: &[CString], b: &[CString]) -> Vec<CString> {
+	a.iter().filter(|name| b.contains(name)).cloned().collect()
+}
+
+pub(crate) fn raw_difference(a: &[CString], b: &[CString]) -> Vec<CString> {
+	a.iter().filter(|name| !b.contains(name)).cloned().collect()
+}
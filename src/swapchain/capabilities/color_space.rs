@@ -87,7 +87,7 @@ use ash::vk;
 /// Additionally you can try detect whether the implementation supports any additional color space
 /// and perform a manual conversion to that color space from inside your shader.
 ///
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[repr(i32)]
 pub enum ColorSpace {
 	SrgbNonLinear = vk::ColorSpaceKHR::SRGB_NONLINEAR.as_raw(),
@@ -108,8 +108,8 @@ pub enum ColorSpace {
 
 impl ColorSpace {
 	#[inline]
-	pub(crate) fn from_vulkan(val: vk::ColorSpaceKHR) -> Self {
-		match val {
+	pub(crate) fn from_vulkan(val: vk::ColorSpaceKHR) -> Option<Self> {
+		Some(match val {
 			vk::ColorSpaceKHR::SRGB_NONLINEAR => ColorSpace::SrgbNonLinear,
 			vk::ColorSpaceKHR::DISPLAY_P3_NONLINEAR_EXT => ColorSpace::DisplayP3NonLinear,
 			vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT => ColorSpace::ExtendedSrgbLinear,
@@ -124,8 +124,16 @@ impl ColorSpace {
 			vk::ColorSpaceKHR::ADOBERGB_LINEAR_EXT => ColorSpace::AdobeRgbLinear,
 			vk::ColorSpaceKHR::ADOBERGB_NONLINEAR_EXT => ColorSpace::AdobeRgbNonLinear,
 			vk::ColorSpaceKHR::PASS_THROUGH_EXT => ColorSpace::PassThrough,
-			_ => panic!("Wrong value for color space enum"),
-		}
+			_ => return None
+		})
+	}
+
+	/// Checks if this color space is provided by the `VK_EXT_swapchain_colorspace` instance extension.
+	///
+	/// Every color space other than `SrgbNonLinear` is.
+	#[inline]
+	pub fn is_extended(&self) -> bool {
+		*self != ColorSpace::SrgbNonLinear
 	}
 
 	#[inline]
@@ -24,17 +24,35 @@ pub use present_mode::{
 };
 pub use color_space::ColorSpace;
 
+/// Capabilities of a surface when used by a physical device.
 pub struct Capabilities {
+	/// Minimum number of images of a swapchain.
 	pub min_image_count: u32,
+
+	/// Maximum number of images of a swapchain, if any.
 	pub max_image_count: Option<u32>,
-	pub current_extent: Option<[u32; 2]>,
-	pub min_image_extent: [u32; 2],
-	pub max_image_extent: [u32; 2],
+
+	/// Current extent of the surface.
+	///
+	/// `None` if the extent is determined by the extent of the swapchain.
+	pub current_extent: Option<(u32, u32)>,
+
+	/// Minimum extent of a swapchain.
+	pub min_image_extent: (u32, u32),
+
+	/// Maximum extent of a swapchain.
+	pub max_image_extent: (u32, u32),
+
 	pub max_image_array_layers: u32,
 	pub supported_transforms: SurfaceTransforms,
 	pub current_transform: SurfaceTransform,
 	pub supported_composite_alpha: CompositeAlphas,
-	pub supported_usage_flags: image::Usage,
-	pub supported_formats: Vec<(Format, ColorSpace)>,
-	pub present_modes: PresentModes,
+	pub supported_usage_flags: image::Usage
+}
+
+/// Format and color space pair supported by a surface.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SurfaceFormat {
+	pub format: Format,
+	pub color_space: ColorSpace
 }
\ No newline at end of file
@@ -30,6 +30,26 @@ flag_set! {
 	PresentModes: vec vk::PresentModeKHR [i32]
 }
 
+impl PresentMode {
+	/// Choose the first preferred present mode available,
+	/// or `Fifo` (always supported) if none of the preferred ones are.
+	pub fn choose(preferred: &[PresentMode], available: &PresentModes) -> PresentMode {
+		preferred.iter().copied().find(|mode| available.contains(*mode)).unwrap_or(PresentMode::Fifo)
+	}
+
+	/// Choose a present mode synchronized with the vertical blanking period or not.
+	///
+	/// With `vsync` the `Fifo` mode is chosen.
+	/// Without it, `Mailbox` or `Immediate` is chosen if available, `Fifo` otherwise.
+	pub fn vsync(vsync: bool, available: &PresentModes) -> PresentMode {
+		if vsync {
+			PresentMode::Fifo
+		} else {
+			Self::choose(&[PresentMode::Mailbox, PresentMode::Immediate], available)
+		}
+	}
+}
+
 // TODO: These can't be enabled yet because they have to be used with shared present surfaces
 // which vulkano doesnt support yet.
 //SharedDemand: vk::PresentModeKHR::SHARED_DEMAND_REFRESH,
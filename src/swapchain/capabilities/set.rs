@@ -11,6 +11,14 @@ macro_rules! flag_set {
 		impl $id {
 			flag_set!(@from [$mode] $native { $($name $variant : $flag),* });
 
+			pub fn contains(&self, item: $item) -> bool {
+				match item {
+					$(
+						$item::$variant => self.$name
+					),*
+				}
+			}
+
 			pub fn iter(&self) -> Iter {
 				Iter(*self)
 			}
@@ -0,0 +1,285 @@
+use std::{
+	sync::Arc,
+	cell::Cell,
+	fmt
+};
+use crate::{
+	OomError,
+	Device,
+	DeviceOwned,
+	pipeline,
+	command,
+	device::{
+		Queue,
+		queue::{
+			SubmitError,
+			PresentError
+		}
+	},
+	sync::{
+		semaphore,
+		fence,
+		Fence,
+		Task,
+		future::{
+			SignalSemaphore,
+			SignalSemaphores
+		}
+	}
+};
+use super::{
+	Swapchain,
+	Acquired,
+	Acquiring,
+	AcquireError
+};
+
+#[derive(Debug)]
+pub enum FrameError {
+	Submit(SubmitError),
+	Present(PresentError)
+}
+
+impl From<SubmitError> for FrameError {
+	fn from(e: SubmitError) -> Self {
+		FrameError::Submit(e)
+	}
+}
+
+impl From<PresentError> for FrameError {
+	fn from(e: PresentError) -> Self {
+		FrameError::Present(e)
+	}
+}
+
+impl std::error::Error for FrameError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Submit(e) => Some(e),
+			Self::Present(e) => Some(e)
+		}
+	}
+}
+
+impl fmt::Display for FrameError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Submit(e) => write!(f, "unable to submit frame: {}", e),
+			Self::Present(e) => write!(f, "unable to present frame: {}", e)
+		}
+	}
+}
+
+/// Synchronization primitives of a frame in flight.
+struct FrameSync {
+	image_available: semaphore::Raw,
+	render_finished: semaphore::Raw,
+	in_flight: fence::Raw,
+
+	/// Whether `in_flight` will be signaled by a pending submission.
+	submitted: Cell<bool>,
+
+	/// Whether `image_available` is signaled by an acquisition that no submission waits for.
+	acquired: Cell<bool>
+}
+
+impl FrameSync {
+	fn new(device: &Arc<Device>) -> Result<FrameSync, OomError> {
+		let semaphore = || semaphore::Raw::new(device).map_err(|semaphore::CreationError::OutOfMemory(e)| e);
+
+		Ok(FrameSync {
+			image_available: semaphore()?,
+			render_finished: semaphore()?,
+			in_flight: fence::Raw::new(device).map_err(|fence::CreationError::OutOfMemory(e)| e)?,
+			submitted: Cell::new(false),
+			acquired: Cell::new(false)
+		})
+	}
+
+	/// Replace `image_available` if it is left signaled by a frame that was not submitted.
+	///
+	/// A signaled semaphore cannot be signaled again by the next acquisition,
+	/// and the previous acquisition may still be pending, so the device is waited before destroying it.
+	fn reset_image_available(&mut self, device: &Arc<Device>) -> Result<(), AcquireError> {
+		if self.acquired.get() {
+			device.wait_idle()?;
+			self.image_available = semaphore::Raw::new(device).map_err(|semaphore::CreationError::OutOfMemory(e)| e)?;
+			self.acquired.set(false);
+		}
+
+		Ok(())
+	}
+
+	/// Block until the last submission of this frame is done.
+	fn wait(&self) -> Result<(), fence::WaitError> {
+		if self.submitted.get() {
+			self.in_flight.wait(None)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Manager of the synchronization of a fixed number of frames in flight.
+///
+/// Each frame owns a semaphore signaled when its swapchain image is acquired,
+/// a semaphore signaled when its rendering is done, and a fence signaled when its submission is done.
+/// Before a frame is reused, its fence is waited,
+/// as well as the fence of any other frame still rendering to the same swapchain image.
+pub struct FrameManager {
+	device: Arc<Device>,
+	frames: Vec<FrameSync>,
+
+	/// Frame that last rendered to each swapchain image.
+	images_in_flight: Vec<Option<usize>>,
+
+	current: usize
+}
+
+impl FrameManager {
+	/// Create a frame manager for the given number of frames in flight.
+	///
+	/// # Panic
+	///
+	/// - Panics if `frames_in_flight` is 0.
+	///
+	pub fn new(device: &Arc<Device>, frames_in_flight: usize) -> Result<FrameManager, OomError> {
+		assert!(frames_in_flight > 0, "there must be at least one frame in flight");
+
+		let mut frames = Vec::with_capacity(frames_in_flight);
+		for _ in 0..frames_in_flight {
+			frames.push(FrameSync::new(device)?);
+		}
+
+		Ok(FrameManager {
+			device: device.clone(),
+			frames,
+			images_in_flight: Vec::new(),
+			current: 0
+		})
+	}
+
+	#[inline]
+	pub fn frames_in_flight(&self) -> usize {
+		self.frames.len()
+	}
+
+	/// Begin the next frame by acquiring the next swapchain image.
+	///
+	/// Blocks until the previous submission of the frame is done,
+	/// and until the acquired image is no longer used by another frame.
+	pub fn begin_frame<'a, W>(&'a mut self, swapchain: &mut Swapchain<W>) -> Result<Frame<'a, W>, AcquireError> {
+		let index = self.current;
+
+		{
+			let sync = &mut self.frames[index];
+			if sync.submitted.get() {
+				sync.in_flight.wait(None)?;
+				sync.in_flight.reset()?;
+				sync.submitted.set(false);
+			}
+
+			sync.reset_image_available(&self.device)?;
+		}
+
+		let sync = &self.frames[index];
+		let (acquired, image_acquired) = swapchain.acquire_next_image(None).then_signal_semaphore(&sync.image_available)?;
+		sync.acquired.set(true);
+
+		let image_index = acquired.image_index as usize;
+		if self.images_in_flight.len() <= image_index {
+			self.images_in_flight.resize(image_index + 1, None)
+		}
+
+		if let Some(other) = self.images_in_flight[image_index] {
+			if other != index {
+				self.frames[other].wait()?;
+			}
+		}
+
+		self.images_in_flight[image_index] = Some(index);
+		self.current = (index + 1) % self.frames.len();
+
+		Ok(Frame {
+			sync,
+			acquired,
+			image_acquired
+		})
+	}
+}
+
+impl DeviceOwned for FrameManager {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for FrameManager {
+	fn drop(&mut self) {
+		// The semaphores and fences must not be in use when destroyed,
+		// including by pending acquisitions and presentations, which no fence tracks.
+		if let Err(e) = self.device.wait_idle() {
+			error!("unable to wait for frames: {}", e)
+		}
+	}
+}
+
+/// Frame in flight, with an acquired swapchain image.
+///
+/// The frame should be submitted, since the acquisition semaphore is signaled.
+/// Otherwise the frame manager waits for the device to be idle and recreates the semaphore
+/// the next time this frame begins.
+#[must_use]
+pub struct Frame<'a, W> {
+	sync: &'a FrameSync,
+	acquired: Acquired,
+	image_acquired: semaphore::Future<Acquiring<W>, &'a semaphore::Raw>
+}
+
+impl<'a, W> Frame<'a, W> {
+	/// Index of the acquired swapchain image.
+	#[inline]
+	pub fn image_index(&self) -> u32 {
+		self.acquired.image_index
+	}
+
+	/// Whether the swapchain should be recreated.
+	#[inline]
+	pub fn is_suboptimal(&self) -> bool {
+		self.acquired.suboptimal
+	}
+
+	/// Future signaling a semaphore when the swapchain image is acquired.
+	///
+	/// Can be used to chain custom tasks instead of using `submit_and_present`,
+	/// in which case the frame manager does not track the completion of the frame,
+	/// and the returned future must be waited.
+	#[inline]
+	pub fn image_acquired(self) -> semaphore::Future<Acquiring<W>, &'a semaphore::Raw> {
+		self.sync.acquired.set(false);
+		self.image_acquired
+	}
+
+	/// Submit the given command buffer once the image is acquired, then present the image once the buffer is executed.
+	///
+	/// The command buffer must outlive its execution,
+	/// which is guaranteed once the frame manager begins this frame again.
+	/// Returns `true` if the swapchain is suboptimal.
+	pub fn submit_and_present<B: command::RecordedBuffer>(self, queue: &Queue, swapchain: &Swapchain<W>, command_buffer: B) -> Result<bool, FrameError> {
+		let sync = self.sync;
+		let image_index = self.acquired.image_index;
+
+		let ((), render_finished) = self.image_acquired
+			.and_then_pipeline_stages_of(queue.submit(command_buffer), pipeline::stage::Flags::COLOR_ATTACHMENT_OUTPUT)
+			.then_signal_semaphore_and_fence(&sync.render_finished, &sync.in_flight)?;
+
+		sync.submitted.set(true);
+		sync.acquired.set(false);
+
+		let (suboptimal, _) = render_finished
+			.and_then(queue.present(swapchain, image_index))
+			.in_parallel()?;
+
+		Ok(suboptimal || self.acquired.suboptimal)
+	}
+}
@@ -0,0 +1,103 @@
+use ash::vk;
+use std::fmt;
+use crate::{
+	OomError,
+	DeviceLost,
+	device
+};
+
+/// Full-screen exclusive behavior of a swapchain.
+///
+/// Only used with the `VK_EXT_full_screen_exclusive` device extension, on Win32 surfaces.
+/// The monitor given to the implementation is the one the window is on when the swapchain is created.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FullScreenExclusive {
+	/// The implementation decides.
+	Default,
+
+	/// The implementation may use full-screen exclusive mode.
+	Allowed,
+
+	/// The implementation must not use full-screen exclusive mode.
+	Disallowed,
+
+	/// Full-screen exclusive mode is acquired and released by the application,
+	/// using `Swapchain::acquire_full_screen_exclusive` and `Swapchain::release_full_screen_exclusive`.
+	ApplicationControlled
+}
+
+impl Default for FullScreenExclusive {
+	fn default() -> Self {
+		FullScreenExclusive::Default
+	}
+}
+
+impl FullScreenExclusive {
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::FullScreenExclusiveEXT {
+		match self {
+			Self::Default => vk::FullScreenExclusiveEXT::DEFAULT,
+			Self::Allowed => vk::FullScreenExclusiveEXT::ALLOWED,
+			Self::Disallowed => vk::FullScreenExclusiveEXT::DISALLOWED,
+			Self::ApplicationControlled => vk::FullScreenExclusiveEXT::APPLICATION_CONTROLLED
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum FullScreenExclusiveError {
+	OomError(OomError),
+	DeviceLost,
+	SurfaceLost,
+	InitializationFailed,
+	MissingDeviceExtension(device::MissingExtensionError),
+
+	/// The swapchain was not created with `FullScreenExclusive::ApplicationControlled`.
+	NotApplicationControlled,
+
+	/// The surface of the swapchain is not a Win32 surface.
+	UnsupportedSurface
+}
+
+impl From<device::MissingExtensionError> for FullScreenExclusiveError {
+	fn from(e: device::MissingExtensionError) -> Self {
+		FullScreenExclusiveError::MissingDeviceExtension(e)
+	}
+}
+
+impl From<DeviceLost> for FullScreenExclusiveError {
+	fn from(_: DeviceLost) -> Self {
+		FullScreenExclusiveError::DeviceLost
+	}
+}
+
+impl From<vk::Result> for FullScreenExclusiveError {
+	fn from(e: vk::Result) -> Self {
+		match e {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => FullScreenExclusiveError::OomError(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => FullScreenExclusiveError::OomError(OomError::Device),
+			vk::Result::ERROR_DEVICE_LOST => FullScreenExclusiveError::DeviceLost,
+			vk::Result::ERROR_SURFACE_LOST_KHR => FullScreenExclusiveError::SurfaceLost,
+			vk::Result::ERROR_INITIALIZATION_FAILED => FullScreenExclusiveError::InitializationFailed,
+			_ => unreachable!()
+		}
+	}
+}
+
+impl std::error::Error for FullScreenExclusiveError {
+	// ...
+}
+
+impl fmt::Display for FullScreenExclusiveError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OomError(e) => e.fmt(f),
+			Self::DeviceLost => write!(f, "device lost"),
+			Self::SurfaceLost => write!(f, "surface lost"),
+			Self::InitializationFailed => write!(f, "initialization failed"),
+			Self::MissingDeviceExtension(e) => e.fmt(f),
+			Self::NotApplicationControlled => write!(f, "full-screen exclusive mode is not application controlled"),
+			Self::UnsupportedSurface => write!(f, "full-screen exclusive mode is only supported on Win32 surfaces")
+		}
+	}
+}
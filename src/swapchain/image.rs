@@ -5,7 +5,10 @@ use std::{
 };
 use crate::{
 	Device,
-	DeviceOwned
+	DeviceOwned,
+	Format,
+	image,
+	framebuffer::SampleCount
 };
 use super::{
 	Inner
@@ -25,6 +28,14 @@ impl<W> Image<W> {
 			handle
 		}
 	}
+
+	/// Checks if the swapchain owning this image has been retired by a recreation.
+	///
+	/// Views and framebuffers created from a retired image should be recreated.
+	#[inline]
+	pub fn is_retired(&self) -> bool {
+		self.inner.retired.get()
+	}
 }
 
 impl<W> DeviceOwned for Image<W> {
@@ -37,4 +48,46 @@ unsafe impl<W> crate::Image for Image<W> {
 	fn handle(&self) -> vk::Image {
 		self.handle
 	}
-}
\ No newline at end of file
+}
+
+unsafe impl<W> image::ImageProperties for Image<W> {
+	fn format(&self) -> Format {
+		self.inner.format
+	}
+
+	fn ty(&self) -> image::Type {
+		image::Type::D2
+	}
+
+	fn extent(&self) -> (u32, u32, u32) {
+		(self.inner.extent.0, self.inner.extent.1, 1)
+	}
+
+	fn mip_levels(&self) -> u32 {
+		1
+	}
+
+	fn array_layers(&self) -> u32 {
+		self.inner.layers
+	}
+
+	fn samples(&self) -> SampleCount {
+		SampleCount::S1
+	}
+
+	fn tiling(&self) -> image::Tiling {
+		image::Tiling::Optimal
+	}
+
+	fn usage(&self) -> image::Usage {
+		self.inner.usage
+	}
+
+	fn flags(&self) -> image::Flags {
+		image::Flags::none()
+	}
+
+	fn is_concurrent(&self) -> bool {
+		self.inner.sharing_queues.is_concurrent()
+	}
+}
@@ -1,16 +1,27 @@
 use ash::vk;
 use std::{
 	rc::Rc,
+	cell::{
+		Cell,
+		RefCell
+	},
 	sync::Arc,
+	time::Duration,
 	fmt
 };
 use crate::{
 	OomError,
+	DeviceLost,
 	Device,
 	DeviceOwned,
 	device,
 	Format,
-	image::Usage,
+	image::{
+		Usage,
+		View,
+		view,
+		Image as _
+	},
 	sync::{
 		self,
 		task,
@@ -21,16 +32,29 @@ use crate::{
 pub mod surface;
 pub mod capabilities;
 mod image;
+mod frames;
+mod full_screen_exclusive;
 
 pub use surface::Surface;
 pub use capabilities::Capabilities;
-use capabilities::{
+pub use capabilities::{
 	ColorSpace,
+	SurfaceFormat,
 	SurfaceTransform,
 	CompositeAlpha,
-	PresentMode
+	PresentMode,
+	PresentModes
 };
 pub use image::Image;
+pub use full_screen_exclusive::{
+	FullScreenExclusive,
+	FullScreenExclusiveError
+};
+pub use frames::{
+	FrameManager,
+	Frame,
+	FrameError
+};
 
 #[derive(Debug)]
 pub enum CreationError {
@@ -41,7 +65,20 @@ pub enum CreationError {
 	InitializationFailed,
 	MissingDeviceExtension(device::MissingExtensionError),
 	CapabilitiesError(surface::CapabilitiesError),
-	UnsupportedDimensions((u32, u32))
+	UnsupportedDimensions((u32, u32)),
+
+	/// The color space requires the `VK_EXT_swapchain_colorspace` instance extension,
+	/// which is not loaded.
+	MissingColorSpaceExtension(ColorSpace),
+
+	/// The present mode is not supported by the surface.
+	UnsupportedPresentMode(PresentMode),
+
+	/// The surface transform is not supported by the surface.
+	UnsupportedTransform(SurfaceTransform),
+
+	/// The composite alpha mode is not supported by the surface.
+	UnsupportedCompositeAlpha(CompositeAlpha)
 }
 
 impl From<device::MissingExtensionError> for CreationError {
@@ -79,7 +116,10 @@ pub enum AcquireError {
 	SurfaceLost,
 	FullScreenExclusiveModeLost,
 	MissingDeviceExtension(device::MissingExtensionError),
-	OutOfDate
+	OutOfDate,
+
+	/// The swapchain has been retired by a recreation.
+	Retired
 }
 
 impl From<device::MissingExtensionError> for AcquireError {
@@ -88,6 +128,29 @@ impl From<device::MissingExtensionError> for AcquireError {
 	}
 }
 
+impl From<DeviceLost> for AcquireError {
+	fn from(_: DeviceLost) -> Self {
+		AcquireError::DeviceLost
+	}
+}
+
+impl From<OomError> for AcquireError {
+	fn from(e: OomError) -> Self {
+		AcquireError::OomError(e)
+	}
+}
+
+impl From<sync::fence::WaitError> for AcquireError {
+	fn from(e: sync::fence::WaitError) -> Self {
+		match e {
+			sync::fence::WaitError::OutOfMemory(e) => AcquireError::OomError(e),
+			sync::fence::WaitError::DeviceLost => AcquireError::DeviceLost,
+			sync::fence::WaitError::Timeout => AcquireError::Timeout,
+			sync::fence::WaitError::MismatchedDevices | sync::fence::WaitError::NoFence => unreachable!() // waits on a single fence of the swapchain device.
+		}
+	}
+}
+
 impl From<vk::Result> for AcquireError {
 	fn from(e: vk::Result) -> Self {
 		match e {
@@ -118,7 +181,8 @@ impl fmt::Display for AcquireError {
 			Self::OutOfDate => write!(f, "swapchain out of date"),
 			Self::SurfaceLost => write!(f, "surface lost"),
 			Self::FullScreenExclusiveModeLost => write!(f, "full screen exclusive mode lost"),
-			Self::MissingDeviceExtension(e) => e.fmt(f)
+			Self::MissingDeviceExtension(e) => e.fmt(f),
+			Self::Retired => write!(f, "swapchain retired")
 		}
 	}
 }
@@ -128,14 +192,39 @@ pub(crate) struct Inner<W> {
 	surface: Arc<Surface<W>>,
 	format: Format,
 	color_space: ColorSpace,
+	extent: (u32, u32),
+	layers: u32,
+	usage: Usage,
+	num_images: u32,
+	sharing_queues: SharingQueues,
+	requested_transform: Option<SurfaceTransform>,
+	requested_alpha: Option<CompositeAlpha>,
+	transform: SurfaceTransform,
+	alpha: CompositeAlpha,
+	mode: PresentMode,
+	clipped: bool,
+	full_screen_exclusive: FullScreenExclusive,
+	retired: Cell<bool>,
+	images: Vec<vk::Image>,
 	handle: vk::SwapchainKHR
 }
 
 pub struct Swapchain<W> {
 	inner: Rc<Inner<W>>,
+
+	/// Cached views of the swapchain images.
+	views: RefCell<Option<Vec<Arc<View<Image<W>>>>>>
 }
 
 impl<W> Swapchain<W> {
+	/// Create a new swapchain.
+	///
+	/// If `transform` is `None`, the current transform of the surface is used.
+	/// If `alpha` is `None`, the first supported composite alpha mode is used,
+	/// in the order `Opaque`, `Inherit`, `PreMultiplied` and `PostMultiplied`.
+	///
+	/// The full-screen exclusive behavior is ignored if the `VK_EXT_full_screen_exclusive` device extension is not loaded,
+	/// or if the surface is not a Win32 surface.
 	pub fn new<'a, S: Into<SharingQueues>>(
 		device: &Arc<Device>,
 		surface: &Arc<Surface<W>>,
@@ -146,31 +235,55 @@ impl<W> Swapchain<W> {
 		layers: u32,
 		usage: Usage,
 		sharing_queues: S,
-		transform: SurfaceTransform,
-		alpha: CompositeAlpha,
+		requested_transform: Option<SurfaceTransform>,
+		requested_alpha: Option<CompositeAlpha>,
 		mode: PresentMode,
 		clipped: bool,
+		full_screen_exclusive: FullScreenExclusive,
 		old_swapchain: Option<&Swapchain<W>>
 	) -> Result<(Swapchain<W>, Vec<Image<W>>), CreationError> {
+		if color_space.is_extended() && !device.physical_device().instance().loaded_extensions().ext_swapchain_colorspace {
+			return Err(CreationError::MissingColorSpaceExtension(color_space))
+		}
+
 		let capabilities = surface.capabilities(device.physical_device())?;
 
+		if !surface.present_modes(device.physical_device())?.contains(mode) {
+			return Err(CreationError::UnsupportedPresentMode(mode))
+		}
+
+		let transform = match requested_transform {
+			Some(transform) if !capabilities.supported_transforms.contains(transform) => return Err(CreationError::UnsupportedTransform(transform)),
+			Some(transform) => transform,
+			None => capabilities.current_transform
+		};
+
+		let alpha = match requested_alpha {
+			Some(alpha) if !capabilities.supported_composite_alpha.contains(alpha) => return Err(CreationError::UnsupportedCompositeAlpha(alpha)),
+			Some(alpha) => alpha,
+			None => {
+				[CompositeAlpha::Opaque, CompositeAlpha::Inherit, CompositeAlpha::PreMultiplied, CompositeAlpha::PostMultiplied].iter().copied()
+					.find(|alpha| capabilities.supported_composite_alpha.contains(*alpha))
+					.unwrap_or(CompositeAlpha::Opaque)
+			}
+		};
+
 		let dimensions = if let Some(dimensions) = dimensions {
-			if dimensions.0 < capabilities.min_image_extent[0] {
+			if dimensions.0 < capabilities.min_image_extent.0 {
 				return Err(CreationError::UnsupportedDimensions(dimensions));
 			}
-			if dimensions.1 < capabilities.min_image_extent[1] {
+			if dimensions.1 < capabilities.min_image_extent.1 {
 				return Err(CreationError::UnsupportedDimensions(dimensions));
 			}
-			if dimensions.0 > capabilities.max_image_extent[0] {
+			if dimensions.0 > capabilities.max_image_extent.0 {
 				return Err(CreationError::UnsupportedDimensions(dimensions));
 			}
-			if dimensions.1 > capabilities.max_image_extent[1] {
+			if dimensions.1 > capabilities.max_image_extent.1 {
 				return Err(CreationError::UnsupportedDimensions(dimensions));
 			}
 			dimensions
 		} else {
-			let extent = capabilities.current_extent.unwrap();
-			(extent[0], extent[1])
+			capabilities.current_extent.unwrap()
 		};
 
 		// let mut ids: Vec<u32> = sharing_queues.into_iter().map(|q| q.family_index()).collect();
@@ -183,10 +296,16 @@ impl<W> Swapchain<W> {
 		// 	(vk::SharingMode::CONCURRENT, ids.len() as u32, ids.as_ptr())
 		// };
 
-		let sharing_queues = sharing_queues.into();
+		let sharing_queues: SharingQueues = sharing_queues.into();
 		let (sh_mode, sh_count, sh_indices) = sharing_queues.as_vulkan();
 
-		let infos = vk::SwapchainCreateInfoKHR {
+		let mut full_screen_exclusive_win32_info = vk::SurfaceFullScreenExclusiveWin32InfoEXT::default();
+		let mut full_screen_exclusive_info = vk::SurfaceFullScreenExclusiveInfoEXT {
+			full_screen_exclusive: full_screen_exclusive.into_vulkan(),
+			..Default::default()
+		};
+
+		let mut infos = vk::SwapchainCreateInfoKHR {
 			surface: surface.handle(),
 			min_image_count: num_images,
 			image_format: format.into_vulkan(),
@@ -204,7 +323,7 @@ impl<W> Swapchain<W> {
 			composite_alpha: alpha.into_vulkan(),
 			present_mode: mode.into_vulkan(),
 			clipped: if clipped { vk::TRUE } else { vk::FALSE },
-			old_swapchain: if let Some(ref old_swapchain) = old_swapchain {
+			old_swapchain: if let Some(old_swapchain) = old_swapchain {
 				old_swapchain.handle()
 			} else {
 				vk::SwapchainKHR::null()
@@ -212,31 +331,181 @@ impl<W> Swapchain<W> {
 			..Default::default()
 		};
 
+		if full_screen_exclusive != FullScreenExclusive::Default {
+			if !device.loaded_extensions().ext_full_screen_exclusive {
+				debug!("ignoring full-screen exclusive mode {:?}: VK_EXT_full_screen_exclusive is not loaded", full_screen_exclusive)
+			} else if let Some(monitor) = surface.win32_monitor() {
+				full_screen_exclusive_win32_info.hmonitor = monitor;
+				full_screen_exclusive_info.p_next = &mut full_screen_exclusive_win32_info as *mut _ as *mut std::ffi::c_void;
+				infos.p_next = &full_screen_exclusive_info as *const _ as *const std::ffi::c_void;
+			} else {
+				debug!("ignoring full-screen exclusive mode {:?}: the surface is not a Win32 surface", full_screen_exclusive)
+			}
+		}
+
 		let ext_khr_swapchain = device.ext_khr_swapchain()?;
 
 		let handle = unsafe {
 			ext_khr_swapchain.create_swapchain(&infos, None)?
 		};
 
+		let handles = unsafe {
+			ext_khr_swapchain.get_swapchain_images(handle)?
+		};
+
 		let inner = Rc::new(Inner {
 			device: device.clone(),
 			surface: surface.clone(),
 			handle,
 			format,
-			color_space
+			color_space,
+			extent: dimensions,
+			layers,
+			usage,
+			num_images,
+			sharing_queues,
+			requested_transform,
+			requested_alpha,
+			transform,
+			alpha,
+			mode,
+			clipped,
+			full_screen_exclusive,
+			retired: Cell::new(false),
+			images: handles
 		});
 
-		let images = unsafe {
-			ext_khr_swapchain.get_swapchain_images(handle)?
-		}.into_iter().map(|h| Image::new(&inner, h)).collect();
+		let images = inner.images.iter().map(|h| Image::new(&inner, *h)).collect();
 
 		let swapchain = Swapchain {
-			inner
+			inner,
+			views: RefCell::new(None)
 		};
 
+		if let Some(old_swapchain) = old_swapchain {
+			old_swapchain.inner.retired.set(true);
+			old_swapchain.views.borrow_mut().take();
+		}
+
 		Ok((swapchain, images))
 	}
 
+	/// Create a new swapchain replacing this one, typically after a window resize.
+	///
+	/// The new extent is clamped into the extent range supported by the surface,
+	/// and the rest of the configuration is reused, except for the present mode if `present_mode` is given.
+	/// This swapchain and its images are retired: images can no longer be acquired from it,
+	/// and its cached image views are released.
+	pub fn recreate(&self, new_extent: (u32, u32), present_mode: Option<PresentMode>) -> Result<(Swapchain<W>, Vec<Image<W>>), CreationError> {
+		let inner = &self.inner;
+		let capabilities = inner.surface.capabilities(inner.device.physical_device())?;
+
+		let extent = (
+			new_extent.0.max(capabilities.min_image_extent.0).min(capabilities.max_image_extent.0),
+			new_extent.1.max(capabilities.min_image_extent.1).min(capabilities.max_image_extent.1)
+		);
+
+		Self::new(
+			&inner.device,
+			&inner.surface,
+			inner.num_images,
+			inner.format,
+			inner.color_space,
+			Some(extent),
+			inner.layers,
+			inner.usage,
+			inner.sharing_queues.clone(),
+			inner.requested_transform,
+			inner.requested_alpha,
+			present_mode.unwrap_or(inner.mode),
+			inner.clipped,
+			inner.full_screen_exclusive,
+			Some(self)
+		)
+	}
+
+	/// Color views of the swapchain images, in the order of the images.
+	///
+	/// The views are created on the first call and cached until the swapchain is retired.
+	pub fn image_views(&self) -> Result<Vec<Arc<View<Image<W>>>>, view::CreationError> {
+		let mut views = self.views.borrow_mut();
+
+		if views.is_none() {
+			let mut list = Vec::with_capacity(self.inner.images.len());
+			for h in &self.inner.images {
+				list.push(Arc::new(Image::new(&self.inner, *h).create_default_view()?));
+			}
+
+			*views = Some(list);
+		}
+
+		Ok(views.as_ref().unwrap().clone())
+	}
+
+	/// Acquire full-screen exclusive mode.
+	///
+	/// The swapchain must have been created with `FullScreenExclusive::ApplicationControlled`,
+	/// on a Win32 surface.
+	pub fn acquire_full_screen_exclusive(&self) -> Result<(), FullScreenExclusiveError> {
+		let ext = self.full_screen_exclusive_ext()?;
+		let device = &self.inner.device;
+
+		unsafe {
+			match ext.acquire_full_screen_exclusive_mode_ext(device.handle().handle(), self.inner.handle) {
+				vk::Result::SUCCESS => Ok(()),
+				r => Err(device.track_lost::<()>(Err(r)).unwrap_err().into())
+			}
+		}
+	}
+
+	/// Release full-screen exclusive mode.
+	///
+	/// The swapchain must have been created with `FullScreenExclusive::ApplicationControlled`,
+	/// on a Win32 surface.
+	pub fn release_full_screen_exclusive(&self) -> Result<(), FullScreenExclusiveError> {
+		let ext = self.full_screen_exclusive_ext()?;
+		let device = &self.inner.device;
+
+		unsafe {
+			match ext.release_full_screen_exclusive_mode_ext(device.handle().handle(), self.inner.handle) {
+				vk::Result::SUCCESS => Ok(()),
+				r => Err(device.track_lost::<()>(Err(r)).unwrap_err().into())
+			}
+		}
+	}
+
+	fn full_screen_exclusive_ext(&self) -> Result<&vk::ExtFullScreenExclusiveFn, FullScreenExclusiveError> {
+		if self.inner.full_screen_exclusive != FullScreenExclusive::ApplicationControlled {
+			return Err(FullScreenExclusiveError::NotApplicationControlled)
+		}
+
+		if !self.inner.surface.is_win32() {
+			return Err(FullScreenExclusiveError::UnsupportedSurface)
+		}
+
+		self.inner.device.check_lost()?;
+		Ok(self.inner.device.ext_ext_full_screen_exclusive()?)
+	}
+
+	/// Checks if this swapchain has been retired by a recreation.
+	#[inline]
+	pub fn is_retired(&self) -> bool {
+		self.inner.retired.get()
+	}
+
+	/// Choose the first preferred format and color space pair available,
+	/// or the first available pair if none of the preferred ones are.
+	///
+	/// # Panic
+	///
+	/// - Panics if `available` is empty.
+	///
+	pub fn choose_format(preferred: &[(Format, ColorSpace)], available: &[SurfaceFormat]) -> SurfaceFormat {
+		preferred.iter().find_map(|&(format, color_space)| {
+			available.iter().find(|f| f.format == format && f.color_space == color_space).copied()
+		}).unwrap_or_else(|| *available.first().expect("no available surface format"))
+	}
+
 	pub fn surface(&self) -> &Arc<Surface<W>> {
 		&self.inner.surface
 	}
@@ -256,7 +525,29 @@ impl<W> Swapchain<W> {
 		self.inner.color_space
 	}
 
-	pub fn acquire_next_image(&mut self, timeout: Option<u64>) -> Acquire<W> {
+	/// Transform applied to the images before presentation.
+	///
+	/// If not `Identity`, the rendering should be pre-rotated accordingly.
+	#[inline]
+	pub fn transform(&self) -> SurfaceTransform {
+		self.inner.transform
+	}
+
+	#[inline]
+	pub fn composite_alpha(&self) -> CompositeAlpha {
+		self.inner.alpha
+	}
+
+	#[inline]
+	pub fn present_mode(&self) -> PresentMode {
+		self.inner.mode
+	}
+
+	/// Acquire the next available image of the swapchain.
+	///
+	/// Waits at most `timeout` for an image to be available, or indefinitely if `None`.
+	/// The returned task can signal a semaphore and/or a fence once the image is actually acquired.
+	pub fn acquire_next_image(&mut self, timeout: Option<Duration>) -> Acquire<W> {
 		Acquire {
 			swapchain: self,
 			timeout
@@ -276,13 +567,26 @@ impl<W> DeviceOwned for Swapchain<W> {
 /// to ensure that it is not released while acquiring an image.
 pub struct Acquiring<W>(Rc<Inner<W>>);
 
+/// Acquired swapchain image.
+#[derive(Clone, Copy, Debug)]
+pub struct Acquired {
+	/// Index of the acquired image in the swapchain images.
+	pub image_index: u32,
+
+	/// The swapchain no longer matches the surface properties exactly,
+	/// but can still be used to present.
+	///
+	/// The swapchain should be recreated.
+	pub suboptimal: bool
+}
+
 pub struct Acquire<'a, W> {
 	swapchain: &'a mut Swapchain<W>,
-	timeout: Option<u64>
+	timeout: Option<Duration>
 }
 
 unsafe impl<'a, W> task::Task for Acquire<'a, W> {
-	type Output = (u32, bool);
+	type Output = Acquired;
 	type Error = AcquireError;
 	type Payload = Acquiring<W>;
 
@@ -290,18 +594,25 @@ unsafe impl<'a, W> task::Task for Acquire<'a, W> {
 		self,
 		signal_semaphore: Option<&[vk::Semaphore]>,
 		signal_fence: Option<vk::Fence>,
-	) -> Result<((u32, bool), Acquiring<W>), AcquireError> {
-		let ext_khr_swapchain = self.swapchain.inner.device.ext_khr_swapchain()?;
-		let output = unsafe {
-				ext_khr_swapchain.acquire_next_image(
+	) -> Result<(Acquired, Acquiring<W>), AcquireError> {
+		if self.swapchain.inner.retired.get() {
+			return Err(AcquireError::Retired)
+		}
+
+		let device = &self.swapchain.inner.device;
+		device.check_lost()?;
+		let ext_khr_swapchain = device.ext_khr_swapchain()?;
+		let timeout = self.timeout.map(|t| std::cmp::min(t.as_nanos(), u64::MAX as u128) as u64).unwrap_or(u64::MAX);
+		let (image_index, suboptimal) = unsafe {
+			device.track_lost(ext_khr_swapchain.acquire_next_image(
 				self.swapchain.inner.handle,
-				self.timeout.unwrap_or(u64::MAX),
+				timeout,
 				signal_semaphore.map(|s| *s.first().unwrap()).unwrap_or(vk::Semaphore::null()),
 				signal_fence.unwrap_or(vk::Fence::null())
-			)?
+			))?
 		};
 
-		Ok((output, Acquiring(self.swapchain.inner.clone())))
+		Ok((Acquired { image_index, suboptimal }, Acquiring(self.swapchain.inner.clone())))
 	}
 }
 
@@ -16,18 +16,31 @@ use crate::{
 		PhysicalDevice,
 		physical_device::QueueFamily
 	},
-	swapchain::{
-		capabilities,
-		Capabilities
-	},
+	swapchain::capabilities,
 	image,
 	Format
 };
 
+#[cfg(feature = "raw-window-handle")]
+use raw_window_handle::{
+	RawWindowHandle,
+	HasRawWindowHandle
+};
+
+pub use capabilities::{
+	Capabilities,
+	SurfaceFormat,
+	PresentMode,
+	PresentModes
+};
+
 #[derive(Debug)]
 pub enum CreationError {
 	MissingExtension(Extension),
-	OutOfMemory(OomError)
+	OutOfMemory(OomError),
+
+	/// The window handle belongs to a window system unsupported on this platform.
+	UnsupportedWindowSystem
 }
 
 impl Error for CreationError {
@@ -43,7 +56,8 @@ impl fmt::Display for CreationError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			CreationError::MissingExtension(ext) =>  write!(f, "missing instance extension `{}`", ext),
-			CreationError::OutOfMemory(oom) =>  oom.fmt(f)
+			CreationError::OutOfMemory(oom) =>  oom.fmt(f),
+			CreationError::UnsupportedWindowSystem => write!(f, "unsupported window system")
 		}
 	}
 }
@@ -74,6 +88,9 @@ pub enum CapabilitiesError {
 
 	/// An extension is missing.
 	MissingExtension(Extension),
+
+	/// The physical device has no queue family with the given index.
+	InvalidQueueFamily(u32)
 }
 
 impl Error for CapabilitiesError {
@@ -90,7 +107,8 @@ impl fmt::Display for CapabilitiesError {
 		match self {
 			CapabilitiesError::OutOfMemory(oom) =>  oom.fmt(f),
 			CapabilitiesError::SurfaceLost => write!(f, "surface is no longer accessible"),
-			CapabilitiesError::MissingExtension(ext) =>  write!(f, "missing instance extension `{}`", ext)
+			CapabilitiesError::MissingExtension(ext) =>  write!(f, "missing instance extension `{}`", ext),
+			CapabilitiesError::InvalidQueueFamily(index) => write!(f, "invalid queue family index {}", index)
 		}
 	}
 }
@@ -112,9 +130,46 @@ impl From<MissingExtensionError> for CapabilitiesError {
 	}
 }
 
+/// XCB connection of the given Xlib display,
+/// or `None` if `libX11-xcb` is not available.
+#[cfg(all(feature = "raw-window-handle", any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")))]
+unsafe fn xlib_xcb_connection(display: *mut std::ffi::c_void) -> Option<*mut std::ffi::c_void> {
+	use once_cell::sync::OnceCell;
+	type GetXcbConnection = unsafe extern "C" fn(*mut std::ffi::c_void) -> *mut std::ffi::c_void;
+
+	static X11_XCB: OnceCell<Option<libloading::Library>> = OnceCell::new();
+	let library = X11_XCB.get_or_init(|| {
+		libloading::Library::new("libX11-xcb.so.1").or_else(|_| libloading::Library::new("libX11-xcb.so")).ok()
+	}).as_ref()?;
+
+	let get_xcb_connection = library.get::<GetXcbConnection>(b"XGetXCBConnection\0").ok()?;
+	let connection = get_xcb_connection(display);
+	if connection.is_null() {
+		None
+	} else {
+		Some(connection)
+	}
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "user32")]
+extern "system" {
+	fn MonitorFromWindow(hwnd: vk::HWND, flags: u32) -> vk::HMONITOR;
+}
+
+/// `MONITOR_DEFAULTTONEAREST` flag of `MonitorFromWindow`.
+#[cfg(target_os = "windows")]
+const MONITOR_DEFAULTTONEAREST: u32 = 2;
+
 pub struct Surface<W> {
 	instance: Arc<Instance>,
 	handle: vk::SurfaceKHR,
+
+	/// Window handle of Win32 surfaces.
+	///
+	/// Stored as an integer so that the surface stays `Send` and `Sync`.
+	win32_window: Option<usize>,
+
 	backend: W
 }
 
@@ -144,6 +199,7 @@ impl<W> Surface<W> {
 		Ok(Surface {
 			instance: instance.clone(),
 			handle,
+			win32_window: None,
 			backend
 		})
 	}
@@ -154,56 +210,15 @@ impl<W> Surface<W> {
 	///
 	/// - Panics if the device and the surface don't belong to the same instance.
 	///
-	pub fn capabilities(&self, device: PhysicalDevice) -> Result<Capabilities, CapabilitiesError> {
+	pub fn capabilities(&self, device: &PhysicalDevice) -> Result<Capabilities, CapabilitiesError> {
 		unsafe {
-			assert_eq!(
-				&*self.instance as *const _,
-				&**device.instance() as *const _,
-				"Instance mismatch in Surface::capabilities"
-			);
+			self.assert_same_instance(device, "Instance mismatch in Surface::capabilities");
 
-			let ext_khr_surface = device.instance().ext_khr_surface()?;
-
-			let caps = ext_khr_surface.get_physical_device_surface_capabilities(
+			let caps = device.instance().ext_khr_surface()?.get_physical_device_surface_capabilities(
 				device.handle(),
 				self.handle
 			)?;
 
-			let formats = ext_khr_surface.get_physical_device_surface_formats(
-				device.handle(),
-				self.handle
-			)?;
-
-			let modes = ext_khr_surface.get_physical_device_surface_present_modes(
-				device.handle(),
-				self.handle
-			)?;
-
-			// let modes = {
-			// 	let mut num = 0;
-			// 	check_errors(vk.GetPhysicalDeviceSurfacePresentModesKHR(
-			// 		device.internal_object(),
-			// 		self.surface,
-			// 		&mut num,
-			// 		ptr::null_mut(),
-			// 	))?;
-
-			// 	let mut modes = Vec::with_capacity(num as usize);
-			// 	check_errors(vk.GetPhysicalDeviceSurfacePresentModesKHR(
-			// 		device.internal_object(),
-			// 		self.surface,
-			// 		&mut num,
-			// 		modes.as_mut_ptr(),
-			// 	))?;
-			// 	modes.set_len(num as usize);
-			// 	debug_assert!(modes
-			// 		.iter()
-			// 		.find(|&&m| m == vk::PRESENT_MODE_FIFO_KHR)
-			// 		.is_some());
-			// 	debug_assert!(modes.iter().count() > 0);
-			// 	capabilities::supported_present_modes_from_list(modes.into_iter())
-			// };
-
 			Ok(Capabilities {
 				min_image_count: caps.min_image_count,
 				max_image_count: if caps.max_image_count == 0 {
@@ -215,11 +230,11 @@ impl<W> Surface<W> {
 					if caps.current_extent.width == 0xffffffff && caps.current_extent.height == 0xffffffff {
 						None
 					} else {
-						Some([caps.current_extent.width, caps.current_extent.height])
+						Some((caps.current_extent.width, caps.current_extent.height))
 					}
 				},
-				min_image_extent: [caps.min_image_extent.width, caps.min_image_extent.height],
-				max_image_extent: [caps.max_image_extent.width, caps.max_image_extent.height],
+				min_image_extent: (caps.min_image_extent.width, caps.min_image_extent.height),
+				max_image_extent: (caps.max_image_extent.width, caps.max_image_extent.height),
 				max_image_array_layers: caps.max_image_array_layers,
 				supported_transforms: capabilities::SurfaceTransforms::from_vulkan(caps.supported_transforms),
 				current_transform: capabilities::SurfaceTransforms::from_vulkan(caps.current_transform).iter().next().unwrap(),
@@ -228,17 +243,64 @@ impl<W> Surface<W> {
 					let usage = image::Usage::from_vulkan(caps.supported_usage_flags);
 					debug_assert!(usage.color_attachment); // specs say that this must be true
 					usage
-				},
-				supported_formats: formats.into_iter().map(|f| {
-					(
-						Format::from_vulkan(f.format).unwrap(),
-						capabilities::ColorSpace::from_vulkan(f.color_space),
-					)
-				}).collect(),
-				present_modes: capabilities::PresentModes::from_vulkan(modes),
+				}
 			})
 		}
 	}
+
+	/// Retrieves the formats and color spaces supported by a surface when used by a certain device.
+	///
+	/// Formats unknown to this crate are returned as `Format::Other`,
+	/// while pairs with a color space unknown to this crate are skipped.
+	///
+	/// # Panic
+	///
+	/// - Panics if the device and the surface don't belong to the same instance.
+	///
+	pub fn formats(&self, device: &PhysicalDevice) -> Result<Vec<SurfaceFormat>, CapabilitiesError> {
+		unsafe {
+			self.assert_same_instance(device, "Instance mismatch in Surface::formats");
+
+			let formats = device.instance().ext_khr_surface()?.get_physical_device_surface_formats(
+				device.handle(),
+				self.handle
+			)?;
+
+			// Color spaces unknown to this crate cannot be requested anyway.
+			Ok(formats.into_iter().filter_map(|f| Some(SurfaceFormat {
+				format: Format::from_vulkan_lossless(f.format),
+				color_space: capabilities::ColorSpace::from_vulkan(f.color_space)?
+			})).collect())
+		}
+	}
+
+	/// Retrieves the present modes supported by a surface when used by a certain device.
+	///
+	/// # Panic
+	///
+	/// - Panics if the device and the surface don't belong to the same instance.
+	///
+	pub fn present_modes(&self, device: &PhysicalDevice) -> Result<PresentModes, CapabilitiesError> {
+		unsafe {
+			self.assert_same_instance(device, "Instance mismatch in Surface::present_modes");
+
+			let modes = device.instance().ext_khr_surface()?.get_physical_device_surface_present_modes(
+				device.handle(),
+				self.handle
+			)?;
+
+			Ok(PresentModes::from_vulkan(modes))
+		}
+	}
+
+	#[inline]
+	fn assert_same_instance(&self, device: &PhysicalDevice, msg: &str) {
+		assert_eq!(
+			&*self.instance as *const _,
+			&**device.instance() as *const _,
+			"{}", msg
+		);
+	}
 	
 	/// Creates a `Surface` from an Xlib window.
 	///
@@ -265,6 +327,7 @@ impl<W> Surface<W> {
 		Ok(Surface {
 			instance: instance.clone(),
 			handle,
+			win32_window: None,
 			backend
 		})
 	}
@@ -293,10 +356,173 @@ impl<W> Surface<W> {
 		Ok(Surface {
 			instance: instance.clone(),
 			handle,
+			win32_window: None,
+			backend
+		})
+	}
+
+	/// Create a surface from a Win32 window.
+	///
+	/// ## Safety
+	///
+	/// `hinstance` and `hwnd` must be valid handles of the window instance and window,
+	/// and must not be freed before `backend`.
+	pub unsafe fn from_win32(
+		instance: &Arc<Instance>,
+		hinstance: *const std::ffi::c_void,
+		hwnd: *const std::ffi::c_void,
+		backend: W
+	) -> Result<Surface<W>, CreationError> {
+		let infos = vk::Win32SurfaceCreateInfoKHR {
+			hinstance,
+			hwnd,
+			..Default::default()
+		};
+
+		let handle = instance.ext_khr_win32_surface()?.create_win32_surface(&infos, None)?;
+
+		Ok(Surface {
+			instance: instance.clone(),
+			handle,
+			win32_window: Some(hwnd as usize),
+			backend
+		})
+	}
+
+	/// Create a surface from a macOS view, using MoltenVK.
+	///
+	/// ## Safety
+	///
+	/// `view` must be a valid `NSView` backed by a `CAMetalLayer`,
+	/// and must not be freed before `backend`.
+	pub unsafe fn from_macos(
+		instance: &Arc<Instance>,
+		view: *const std::ffi::c_void,
+		backend: W
+	) -> Result<Surface<W>, CreationError> {
+		let infos = vk::MacOSSurfaceCreateInfoMVK {
+			p_view: view,
+			..Default::default()
+		};
+
+		let handle = instance.ext_mvk_macos_surface()?.create_mac_os_surface_mvk(&infos, None)?;
+
+		Ok(Surface {
+			instance: instance.clone(),
+			handle,
+			win32_window: None,
 			backend
 		})
 	}
 
+	/// Create a surface from an iOS view, using MoltenVK.
+	///
+	/// ## Safety
+	///
+	/// `view` must be a valid `UIView` backed by a `CAMetalLayer`,
+	/// and must not be freed before `backend`.
+	pub unsafe fn from_ios(
+		instance: &Arc<Instance>,
+		view: *const std::ffi::c_void,
+		backend: W
+	) -> Result<Surface<W>, CreationError> {
+		let infos = vk::IOSSurfaceCreateInfoMVK {
+			p_view: view,
+			..Default::default()
+		};
+
+		let handle = instance.ext_mvk_ios_surface()?.create_ios_surface_mvk(&infos, None)?;
+
+		Ok(Surface {
+			instance: instance.clone(),
+			handle,
+			win32_window: None,
+			backend
+		})
+	}
+
+	/// Create a surface from an Android native window.
+	///
+	/// ## Safety
+	///
+	/// `window` must be a valid `ANativeWindow`, and must not be released before `backend`.
+	pub unsafe fn from_android(
+		instance: &Arc<Instance>,
+		window: *mut std::ffi::c_void,
+		backend: W
+	) -> Result<Surface<W>, CreationError> {
+		let infos = vk::AndroidSurfaceCreateInfoKHR {
+			window,
+			..Default::default()
+		};
+
+		let handle = instance.ext_khr_android_surface()?.create_android_surface(&infos, None)?;
+
+		Ok(Surface {
+			instance: instance.clone(),
+			handle,
+			win32_window: None,
+			backend
+		})
+	}
+
+	/// Create a surface from a raw window handle, whatever its window system.
+	///
+	/// Returns `CreationError::UnsupportedWindowSystem` if the window system is not supported.
+	/// The instance extension of the window system must be enabled.
+	/// Xlib windows are presented through XCB if the `KHR_xlib_surface` extension is not enabled.
+	///
+	/// `raw-window-handle` 0.3 has no separate display handle:
+	/// the display connection of Wayland, Xlib and XCB windows is part of their `RawWindowHandle`,
+	/// which is why a single handle is taken here.
+	///
+	/// macOS and iOS views are not backed by a `CAMetalLayer` by default, which MoltenVK requires.
+	/// Their handles are rejected with `CreationError::UnsupportedWindowSystem`:
+	/// use `from_macos` or `from_ios` with a view whose layer is a `CAMetalLayer`.
+	///
+	/// ## Safety
+	///
+	/// The handle must be valid, and must not be freed before `backend`.
+	#[cfg(feature = "raw-window-handle")]
+	pub unsafe fn from_raw_handle(
+		instance: &Arc<Instance>,
+		handle: RawWindowHandle,
+		backend: W
+	) -> Result<Surface<W>, CreationError> {
+		match handle {
+			#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+			RawWindowHandle::Wayland(handle) => Self::from_wayland(instance, handle.display, handle.surface, backend),
+			#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+			RawWindowHandle::Xlib(handle) => {
+				if instance.loaded_extensions().khr_xlib_surface {
+					Self::from_xlib(instance, handle.display, handle.window, backend)
+				} else {
+					// Fall back to the XCB connection of the display.
+					let connection = xlib_xcb_connection(handle.display).ok_or(CreationError::MissingExtension(Extension::KhrXlibSurface))?;
+					Self::from_xcb(instance, connection, handle.window as u32, backend)
+				}
+			},
+			#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+			RawWindowHandle::Xcb(handle) => Self::from_xcb(instance, handle.connection, handle.window, backend),
+			#[cfg(target_os = "windows")]
+			RawWindowHandle::Windows(handle) => Self::from_win32(instance, handle.hinstance, handle.hwnd, backend),
+			#[cfg(target_os = "android")]
+			RawWindowHandle::Android(handle) => Self::from_android(instance, handle.a_native_window, backend),
+			_ => Err(CreationError::UnsupportedWindowSystem)
+		}
+	}
+
+	/// Create a surface for the given window, kept alive by the surface.
+	///
+	/// Returns `CreationError::UnsupportedWindowSystem` for macOS and iOS windows,
+	/// see `from_raw_handle`.
+	#[cfg(feature = "raw-window-handle")]
+	pub fn from_window(instance: &Arc<Instance>, window: W) -> Result<Surface<W>, CreationError> where W: HasRawWindowHandle {
+		unsafe {
+			Self::from_raw_handle(instance, window.raw_window_handle(), window)
+		}
+	}
+
 	#[inline]
 	pub(crate) fn handle(&self) -> vk::SurfaceKHR {
 		self.handle
@@ -307,12 +533,41 @@ impl<W> Surface<W> {
 		&self.backend
 	}
 
+	/// Checks if this surface was created from a Win32 window.
+	#[inline]
+	pub fn is_win32(&self) -> bool {
+		self.win32_window.is_some()
+	}
+
+	/// Monitor on which the window of a Win32 surface currently is.
+	///
+	/// Returns `None` if the surface is not a Win32 surface.
+	pub(crate) fn win32_monitor(&self) -> Option<vk::HMONITOR> {
+		#[cfg(target_os = "windows")]
+		{
+			self.win32_window.map(|hwnd| unsafe {
+				MonitorFromWindow(hwnd as vk::HWND, MONITOR_DEFAULTTONEAREST)
+			})
+		}
+
+		#[cfg(not(target_os = "windows"))]
+		{
+			None
+		}
+	}
+
 	/// Queue family supports presentation on the given surface.
 	///
 	/// The `KHR_Surface` extension must be enabled or a missing extension error is returned.
+	///
+	/// # Panic
+	///
+	/// Panics if the queue family and the surface don't belong to the same instance.
 	#[inline]
 	pub fn is_supported(&self, queue_family: QueueFamily) -> Result<bool, CapabilitiesError> {
 		unsafe {
+			self.assert_same_instance(queue_family.physical_device(), "Instance mismatch in Surface::is_supported");
+
 			Ok(queue_family.physical_device().instance().ext_khr_surface()?.get_physical_device_surface_support(
 				queue_family.physical_device().handle(),
 				queue_family.index(),
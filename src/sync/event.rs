@@ -0,0 +1,151 @@
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::sync::Arc;
+use crate::{
+	OomError,
+	DeviceLost,
+	Device,
+	DeviceOwned,
+	device::{
+		self,
+		ExtendedFeature,
+		ExtendedFeatures,
+		feature::portability_subset
+	}
+};
+
+#[derive(Debug)]
+pub enum CreationError {
+	OutOfMemory(OomError),
+
+	/// The device is a portability implementation without the `Events` feature enabled.
+	MissingFeature
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+/// Checks that events can be created on a device with the given extensions and extended features.
+///
+/// Portability implementations only support events when the `Events` feature is enabled.
+fn check_support(extensions: &device::Extensions, features: &ExtendedFeatures) -> Result<(), CreationError> {
+	if extensions.khr_portability_subset && !features.contains(ExtendedFeature::PortabilitySubset(portability_subset::Feature::Events)) {
+		Err(CreationError::MissingFeature)
+	} else {
+		Ok(())
+	}
+}
+
+/// Fine-grained synchronization primitive between commands of a queue, or between the host and a queue.
+///
+/// An event can be set and reset from the host or by commands,
+/// and waited for by commands. The host can only poll its status:
+/// there is no way to block on an event from the host.
+pub struct Event {
+	device: Arc<Device>,
+	handle: vk::Event
+}
+
+impl Event {
+	/// Create an unset event.
+	///
+	/// On portability implementations (`VK_KHR_portability_subset`),
+	/// the `Events` portability feature must be enabled on the device.
+	pub fn new(device: &Arc<Device>) -> Result<Event, CreationError> {
+		check_support(device.loaded_extensions(), device.enabled_extended_features())?;
+
+		let infos = vk::EventCreateInfo::default();
+
+		let handle = unsafe {
+			device.handle().create_event(&infos, None)?
+		};
+
+		Ok(Event {
+			device: device.clone(),
+			handle
+		})
+	}
+
+	#[inline]
+	pub fn handle(&self) -> vk::Event {
+		self.handle
+	}
+
+	/// Set the event from the host.
+	pub fn set(&self) -> Result<(), OomError> {
+		unsafe {
+			self.device.handle().set_event(self.handle)?
+		}
+
+		Ok(())
+	}
+
+	/// Reset the event from the host.
+	///
+	/// # Safety
+	///
+	/// No pending command must be waiting for the event.
+	pub unsafe fn reset(&self) -> Result<(), OomError> {
+		self.device.handle().reset_event(self.handle)?;
+		Ok(())
+	}
+
+	/// Checks if the event is set, without blocking.
+	pub fn status(&self) -> Result<bool, DeviceLost> {
+		self.device.check_lost()?;
+
+		unsafe {
+			Ok(self.device.track_lost(self.device.handle().get_event_status(self.handle))?)
+		}
+	}
+}
+
+impl DeviceOwned for Event {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for Event {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_event(self.handle, None)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn events_without_portability_subset() {
+		assert!(check_support(&device::Extensions::none(), &ExtendedFeatures::none()).is_ok())
+	}
+
+	#[test]
+	fn events_with_portability_subset() {
+		let extensions = device::Extensions {
+			khr_portability_subset: true,
+			..device::Extensions::none()
+		};
+
+		let mut features = ExtendedFeatures::none();
+		match check_support(&extensions, &features) {
+			Err(CreationError::MissingFeature) => (),
+			_ => panic!("expected a missing feature error")
+		}
+
+		features.portability_subset.insert(portability_subset::Feature::Events);
+		assert!(check_support(&extensions, &features).is_ok())
+	}
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+use ash::vk;
+use crate::{
+	OomError,
+	device::{
+		Extension,
+		MissingExtensionError
+	}
+};
+
+/// Type of external semaphore handle.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ExternalSemaphoreHandleType {
+	/// POSIX file descriptor only meaningful to Vulkan implementations compatible with this one.
+	OpaqueFd,
+
+	/// Linux sync file descriptor, shared with other APIs such as compositors and media frameworks.
+	///
+	/// Only semaphores signaled, or with a pending signal operation, can be exported,
+	/// and imports are always temporary.
+	SyncFd
+}
+
+impl ExternalSemaphoreHandleType {
+	/// Device extension required to use this handle type.
+	#[inline]
+	pub fn required_extension(&self) -> Extension {
+		Extension::KhrExternalSemaphoreFd
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ExternalSemaphoreHandleTypeFlags {
+		match self {
+			Self::OpaqueFd => vk::ExternalSemaphoreHandleTypeFlags::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD,
+			Self::SyncFd => vk::ExternalSemaphoreHandleTypeFlags::EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD
+		}
+	}
+}
+
+/// Type of external fence handle.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ExternalFenceHandleType {
+	/// POSIX file descriptor only meaningful to Vulkan implementations compatible with this one.
+	OpaqueFd,
+
+	/// Linux sync file descriptor, shared with other APIs such as compositors and media frameworks.
+	///
+	/// Only signaled fences, or fences with a pending signal operation, can be exported,
+	/// and imports are always temporary.
+	SyncFd
+}
+
+impl ExternalFenceHandleType {
+	/// Device extension required to use this handle type.
+	#[inline]
+	pub fn required_extension(&self) -> Extension {
+		Extension::KhrExternalFenceFd
+	}
+
+	#[inline]
+	pub(crate) fn into_vulkan(self) -> vk::ExternalFenceHandleTypeFlags {
+		match self {
+			Self::OpaqueFd => vk::ExternalFenceHandleTypeFlags::EXTERNAL_FENCE_HANDLE_TYPE_OPAQUE_FD,
+			Self::SyncFd => vk::ExternalFenceHandleTypeFlags::EXTERNAL_FENCE_HANDLE_TYPE_SYNC_FD
+		}
+	}
+}
+
+/// Support of an external semaphore or fence handle type by a physical device.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ExternalProperties {
+	/// Handles of this type can be exported.
+	pub exportable: bool,
+
+	/// Handles of this type can be imported.
+	pub importable: bool
+}
+
+/// External semaphore or fence export or import error.
+#[derive(Debug)]
+pub enum ExternalSyncError {
+	MissingExtension(Extension),
+	OutOfMemory(OomError),
+	InvalidExternalHandle,
+	TooManyObjects,
+
+	/// The physical device cannot export handles of the requested type.
+	UnsupportedExport,
+
+	/// The physical device cannot import handles of the requested type.
+	UnsupportedImport,
+
+	/// The object was not created to be exported with the requested handle type.
+	NotExportable
+}
+
+impl fmt::Display for ExternalSyncError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingExtension(ext) => write!(f, "missing device extension `{}`", ext),
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::InvalidExternalHandle => write!(f, "invalid external handle"),
+			Self::TooManyObjects => write!(f, "too many objects"),
+			Self::UnsupportedExport => write!(f, "handle type cannot be exported by the device"),
+			Self::UnsupportedImport => write!(f, "handle type cannot be imported by the device"),
+			Self::NotExportable => write!(f, "object is not exportable with this handle type")
+		}
+	}
+}
+
+impl std::error::Error for ExternalSyncError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<MissingExtensionError> for ExternalSyncError {
+	fn from(MissingExtensionError(ext): MissingExtensionError) -> ExternalSyncError {
+		ExternalSyncError::MissingExtension(ext)
+	}
+}
+
+impl From<vk::Result> for ExternalSyncError {
+	fn from(r: vk::Result) -> ExternalSyncError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => ExternalSyncError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => ExternalSyncError::OutOfMemory(OomError::Device),
+			vk::Result::ERROR_INVALID_EXTERNAL_HANDLE => ExternalSyncError::InvalidExternalHandle,
+			vk::Result::ERROR_TOO_MANY_OBJECTS => ExternalSyncError::TooManyObjects,
+			_ => unreachable!()
+		}
+	}
+}
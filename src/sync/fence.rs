@@ -4,17 +4,43 @@ use ash::{
 };
 use std::{
 	borrow::Borrow,
-	sync::Arc,
+	sync::{
+		Arc,
+		atomic::{
+			AtomicBool,
+			Ordering
+		}
+	},
+	time::{
+		Duration,
+		Instant
+	},
+	convert::TryFrom,
+	ffi::c_void,
 	fmt
 };
+#[cfg(unix)]
+use std::os::unix::io::{
+	OwnedFd,
+	AsRawFd,
+	IntoRawFd,
+	FromRawFd
+};
+use crossbeam_queue::SegQueue;
 use crate::{
 	OomError,
 	Device,
 	DeviceOwned
 };
+
+pub use crate::DeviceLost;
 use super::{
 	task,
 	future,
+	external::{
+		ExternalFenceHandleType,
+		ExternalSyncError
+	},
 	Semaphore
 };
 
@@ -23,7 +49,16 @@ pub type VulkanFence = vk::Fence;
 #[derive(Debug)]
 pub enum WaitError {
 	OutOfMemory(OomError),
-	DeviceLost
+	DeviceLost,
+
+	/// The timeout expired before the fences were signaled.
+	Timeout,
+
+	/// The waited fences do not belong to the same device.
+	MismatchedDevices,
+
+	/// No fence was given to wait for.
+	NoFence
 }
 
 impl From<vk::Result> for WaitError {
@@ -32,33 +67,56 @@ impl From<vk::Result> for WaitError {
 			vk::Result::ERROR_OUT_OF_HOST_MEMORY => WaitError::OutOfMemory(OomError::Host),
 			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => WaitError::OutOfMemory(OomError::Device),
 			vk::Result::ERROR_DEVICE_LOST => WaitError::DeviceLost,
+			vk::Result::TIMEOUT => WaitError::Timeout,
 			_ => unreachable!()
 		}
 	}
 }
 
-#[derive(Debug)]
-pub struct DeviceLost;
+impl From<DeviceLost> for WaitError {
+	fn from(e: DeviceLost) -> WaitError {
+		match e {
+			DeviceLost::Lost => WaitError::DeviceLost,
+			DeviceLost::Unexpected(r) => WaitError::OutOfMemory(r.into())
+		}
+	}
+}
 
-impl From<vk::Result> for DeviceLost {
-	fn from(r: vk::Result) -> DeviceLost {
-		match r {
-			vk::Result::ERROR_DEVICE_LOST => DeviceLost,
-			_ => unreachable!()
+impl std::error::Error for WaitError {
+	fn source(&self) -> Option<&(dyn 'static + std::error::Error)> {
+		match self {
+			Self::OutOfMemory(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for WaitError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfMemory(e) => e.fmt(f),
+			Self::DeviceLost => write!(f, "device lost"),
+			Self::Timeout => write!(f, "timeout"),
+			Self::MismatchedDevices => write!(f, "fences of different devices"),
+			Self::NoFence => write!(f, "no fence to wait for")
 		}
 	}
 }
 
 pub enum UnwrapError<F> {
 	Unsignaled(F),
-	DeviceLost
+	DeviceLost,
+
+	/// The implementation returned an error code not expected from this operation.
+	Unexpected(vk::Result)
 }
 
 impl<F> fmt::Display for UnwrapError<F> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			Self::Unsignaled(_) => write!(f, "unsignaled fence"),
-			Self::DeviceLost => write!(f, "device lost")
+			Self::DeviceLost => write!(f, "device lost"),
+			Self::Unexpected(r) => write!(f, "unexpected error: {}", r)
 		}
 	}
 }
@@ -97,18 +155,34 @@ pub trait Fence: DeviceOwned {
 		Ok((output, future))
 	}
 
-	/// Block until the fence is signaled.
-	fn wait(&self, timeout: Option<u64>) -> Result<(), WaitError> {
+	/// Block until the fence is signaled, or the timeout expires.
+	///
+	/// A zero timeout only checks the status of the fence.
+	fn wait(&self, timeout: Option<Duration>) -> Result<(), WaitError> {
+		if timeout == Some(Duration::from_secs(0)) {
+			return if self.is_signaled()? {
+				Ok(())
+			} else {
+				Err(WaitError::Timeout)
+			}
+		}
+
+		let device = self.device();
+		device.check_lost()?;
+
 		unsafe {
-			self.device().handle().wait_for_fences(std::slice::from_ref(self.handle()), true, timeout.unwrap_or(u64::MAX))?
+			device.track_lost(device.handle().wait_for_fences(std::slice::from_ref(self.handle()), true, timeout_nanos(timeout)))?
 		}
 
 		Ok(())
 	}
 
 	fn is_signaled(&self) -> Result<bool, DeviceLost> {
+		let device = self.device();
+		device.check_lost()?;
+
 		unsafe {
-			Ok(self.device().handle().get_fence_status(*self.handle())?)
+			Ok(device.track_lost(device.handle().get_fence_status(*self.handle()))?)
 		}
 	}
 
@@ -122,6 +196,64 @@ pub trait Fence: DeviceOwned {
 	}
 }
 
+/// Timeout in nanoseconds, saturated to `u64::MAX`, for no timeout.
+pub(crate) fn timeout_nanos(timeout: Option<Duration>) -> u64 {
+	timeout.map(|t| u64::try_from(t.as_nanos()).unwrap_or(u64::MAX)).unwrap_or(u64::MAX)
+}
+
+/// Handles of the given fences, checking that they belong to the same device.
+fn fence_handles(fences: &[&dyn Fence]) -> Result<Option<(Arc<Device>, Vec<VulkanFence>)>, WaitError> {
+	let device = match fences.first() {
+		Some(fence) => fence.device().clone(),
+		None => return Ok(None)
+	};
+
+	if fences.iter().any(|f| *f.device() != device) {
+		return Err(WaitError::MismatchedDevices)
+	}
+
+	device.check_lost()?;
+	Ok(Some((device, fences.iter().map(|f| *f.handle()).collect())))
+}
+
+/// Block until all the given fences are signaled.
+///
+/// The fences must belong to the same device.
+pub fn wait_all(fences: &[&dyn Fence], timeout: Option<Duration>) -> Result<(), WaitError> {
+	if let Some((device, handles)) = fence_handles(fences)? {
+		unsafe {
+			device.track_lost(device.handle().wait_for_fences(&handles, true, timeout_nanos(timeout)))?
+		}
+	}
+
+	Ok(())
+}
+
+/// Block until any of the given fences is signaled, and returns its index.
+///
+/// The fences must belong to the same device.
+/// If several fences are signaled when the wait returns, the index of the first one is returned.
+/// Returns `WaitError::NoFence` if `fences` is empty.
+pub fn wait_any(fences: &[&dyn Fence], timeout: Option<Duration>) -> Result<usize, WaitError> {
+	let (device, handles) = fence_handles(fences)?.ok_or(WaitError::NoFence)?;
+	let deadline = timeout.and_then(|t| Instant::now().checked_add(t));
+
+	loop {
+		let timeout = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+		unsafe {
+			device.track_lost(device.handle().wait_for_fences(&handles, false, timeout_nanos(timeout)))?
+		}
+
+		for (i, fence) in fences.iter().enumerate() {
+			if fence.is_signaled()? {
+				return Ok(i)
+			}
+		}
+
+		// The signaled fence has been reset by another thread since the wait returned.
+	}
+}
+
 #[must_use]
 pub struct Future<P, F> {
 	payload: P,
@@ -140,8 +272,14 @@ unsafe impl<P, F: Fence> future::Future for Future<P, F> {
 	}
 }
 
+impl<P, F: Fence> DeviceOwned for Future<P, F> {
+	fn device(&self) -> &Arc<Device> {
+		self.fence.device()
+	}
+}
+
 impl<P, F: Fence> future::SignalFence for Future<P, F> {
-	fn wait(self, timeout: Option<u64>) -> Result<(), WaitError> {
+	fn wait(self, timeout: Option<Duration>) -> Result<(), WaitError> {
 		self.fence.wait(timeout)
 	}
 
@@ -174,8 +312,14 @@ unsafe impl<P, F: Fence, S: Semaphore> future::Future for FutureWithSemaphore<P,
 }
 
 impl<P, F: Fence, S: Semaphore> future::SignalSemaphore for FutureWithSemaphore<P, F, S> {}
+impl<P, F: Fence, S> DeviceOwned for FutureWithSemaphore<P, F, S> {
+	fn device(&self) -> &Arc<Device> {
+		self.fence.device()
+	}
+}
+
 impl<P, F: Fence, S: Semaphore> future::SignalFence for FutureWithSemaphore<P, F, S> {
-	fn wait(self, timeout: Option<u64>) -> Result<(), WaitError> {
+	fn wait(self, timeout: Option<Duration>) -> Result<(), WaitError> {
 		self.fence.wait(timeout)
 	}
 
@@ -202,12 +346,21 @@ impl From<vk::Result> for CreationError {
 #[derive(PartialEq, Eq, Hash)]
 pub struct Raw {
 	device: Arc<Device>,
-	handle: VulkanFence
+	handle: VulkanFence,
+
+	/// Handle type with which the fence can be exported.
+	export_handle_type: Option<ExternalFenceHandleType>
 }
 
 impl Raw {
 	pub fn new(device: &Arc<Device>) -> Result<Raw, CreationError> {
+		Self::with_state(device, false)
+	}
+
+	/// Create a fence, initially signaled if `signaled` is `true`.
+	pub fn with_state(device: &Arc<Device>, signaled: bool) -> Result<Raw, CreationError> {
 		let infos = vk::FenceCreateInfo {
+			flags: if signaled { vk::FenceCreateFlags::SIGNALED } else { vk::FenceCreateFlags::empty() },
 			..Default::default()
 		};
 
@@ -217,9 +370,115 @@ impl Raw {
 
 		Ok(Raw {
 			device: device.clone(),
-			handle
+			handle,
+			export_handle_type: None
 		})
 	}
+
+	/// Create an unsignaled fence that can be exported with the given handle type.
+	///
+	/// The device extension required by the handle type must be enabled,
+	/// and the physical device must support exporting it.
+	pub fn new_exportable(device: &Arc<Device>, handle_type: ExternalFenceHandleType) -> Result<Raw, ExternalSyncError> {
+		let ext = handle_type.required_extension();
+		if !device.loaded_extensions().contains(ext) {
+			return Err(ExternalSyncError::MissingExtension(ext))
+		}
+
+		if !device.physical_device().external_fence_properties(handle_type).exportable {
+			return Err(ExternalSyncError::UnsupportedExport)
+		}
+
+		let export_infos = vk::ExportFenceCreateInfo {
+			handle_types: handle_type.into_vulkan(),
+			..Default::default()
+		};
+
+		let infos = vk::FenceCreateInfo {
+			p_next: &export_infos as *const vk::ExportFenceCreateInfo as *const c_void,
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle().create_fence(&infos, None)?
+		};
+
+		Ok(Raw {
+			device: device.clone(),
+			handle,
+			export_handle_type: Some(handle_type)
+		})
+	}
+
+	/// Export the fence as a file descriptor.
+	///
+	/// The fence must have been created with `new_exportable`.
+	/// Exporting a `SyncFd` resets the fence.
+	///
+	/// # Safety
+	///
+	/// When exporting a `SyncFd`, the fence must be signaled,
+	/// or have a pending signal operation.
+	#[cfg(unix)]
+	pub unsafe fn export_fd(&self) -> Result<OwnedFd, ExternalSyncError> {
+		let handle_type = self.export_handle_type.ok_or(ExternalSyncError::NotExportable)?;
+		let ext = self.device.ext_khr_external_fence_fd()?;
+
+		let infos = vk::FenceGetFdInfoKHR {
+			fence: self.handle,
+			handle_type: handle_type.into_vulkan(),
+			..Default::default()
+		};
+
+		let mut fd = -1;
+		match ext.get_fence_fd_khr(self.device.handle().handle(), &infos, &mut fd) {
+			vk::Result::SUCCESS => Ok(OwnedFd::from_raw_fd(fd)),
+			r => Err(r.into())
+		}
+	}
+
+	/// Import a fence payload from a file descriptor.
+	///
+	/// A temporary import only lasts until the fence is next reset,
+	/// after which the fence is restored to its own payload.
+	/// `SyncFd` imports are always temporary.
+	/// On success the Vulkan implementation takes ownership of the file descriptor.
+	///
+	/// # Safety
+	///
+	/// No pending operation must use the fence,
+	/// and `fd` must be a payload of the given handle type.
+	#[cfg(unix)]
+	pub unsafe fn import_fd(&self, fd: OwnedFd, handle_type: ExternalFenceHandleType, temporary: bool) -> Result<(), ExternalSyncError> {
+		let ext = self.device.ext_khr_external_fence_fd()?;
+
+		if !self.device.physical_device().external_fence_properties(handle_type).importable {
+			return Err(ExternalSyncError::UnsupportedImport)
+		}
+
+		let flags = if temporary || handle_type == ExternalFenceHandleType::SyncFd {
+			vk::FenceImportFlags::TEMPORARY
+		} else {
+			vk::FenceImportFlags::empty()
+		};
+
+		let infos = vk::ImportFenceFdInfoKHR {
+			fence: self.handle,
+			flags,
+			handle_type: handle_type.into_vulkan(),
+			fd: fd.as_raw_fd(),
+			..Default::default()
+		};
+
+		match ext.import_fence_fd_khr(self.device.handle().handle(), &infos) {
+			vk::Result::SUCCESS => (),
+			r => return Err(r.into())
+		}
+
+		// The implementation now owns the file descriptor.
+		let _ = fd.into_raw_fd();
+		Ok(())
+	}
 }
 
 impl Drop for Raw {
@@ -240,4 +499,144 @@ impl DeviceOwned for Raw {
 	fn device(&self) -> &Arc<Device> {
 		&self.device
 	}
-}
\ No newline at end of file
+}
+
+/// Unsignaled fences of a device, kept for reuse.
+pub struct FencePool {
+	free: SegQueue<VulkanFence>
+}
+
+impl FencePool {
+	pub(crate) fn new() -> FencePool {
+		FencePool {
+			free: SegQueue::new()
+		}
+	}
+
+	/// Number of fences available for reuse.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.free.len()
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.free.is_empty()
+	}
+
+	/// Destroy the pooled fences.
+	///
+	/// # Safety
+	///
+	/// The fences must have been created by the given device.
+	pub(crate) unsafe fn clear(&self, device: &ash::Device) {
+		while let Some(handle) = self.free.pop() {
+			device.destroy_fence(handle, None)
+		}
+	}
+}
+
+/// Fence taken from the pool of its device.
+///
+/// The fence is reset and given back to the pool when dropped.
+/// Dropping a fence submitted since its last reset blocks until it is signaled.
+pub struct Pooled {
+	device: Arc<Device>,
+	handle: VulkanFence,
+
+	/// Submitted to a queue, and not reset since.
+	submitted: AtomicBool
+}
+
+impl Pooled {
+	/// Take an unsignaled fence from the pool of the device, or create one if the pool is empty.
+	pub fn new(device: &Arc<Device>) -> Result<Pooled, CreationError> {
+		let handle = match device.fence_pool().free.pop() {
+			Some(handle) => handle,
+			None => unsafe {
+				device.handle().create_fence(&vk::FenceCreateInfo::default(), None)?
+			}
+		};
+
+		Ok(Pooled {
+			device: device.clone(),
+			handle,
+			submitted: AtomicBool::new(false)
+		})
+	}
+
+	/// Record that the fence has been submitted to a queue.
+	#[inline]
+	pub(crate) fn set_submitted(&self) {
+		self.submitted.store(true, Ordering::Release)
+	}
+}
+
+impl Fence for Pooled {
+	fn handle(&self) -> &VulkanFence {
+		&self.handle
+	}
+
+	fn signal<T: task::SignalFence>(self, task: T) -> Result<(T::Output, Future<T::Payload, Self>), T::Error> {
+		let (output, payload) = task.execute(None, Some(self.handle))?;
+		self.set_submitted();
+
+		let future = Future {
+			payload,
+			fence: self
+		};
+
+		Ok((output, future))
+	}
+
+	fn signal_with_semaphore<S: Semaphore, T: task::SignalFence + task::SignalSemaphore>(self, semaphore: S, task: T) -> Result<(T::Output, FutureWithSemaphore<T::Payload, Self, S>), T::Error> {
+		let (output, payload) = task.execute(Some(&[*semaphore.handle()]), Some(self.handle))?;
+		self.set_submitted();
+
+		let future = FutureWithSemaphore {
+			payload,
+			fence: self,
+			semaphore
+		};
+
+		Ok((output, future))
+	}
+
+	fn reset(&mut self) -> Result<(), OomError> {
+		unsafe {
+			self.device.handle().reset_fences(&[self.handle])?
+		}
+
+		self.submitted.store(false, Ordering::Release);
+		Ok(())
+	}
+}
+
+impl DeviceOwned for Pooled {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for Pooled {
+	fn drop(&mut self) {
+		// A pending fence cannot be reset.
+		if self.submitted.load(Ordering::Acquire) {
+			if let Err(e) = Fence::wait(self, None) {
+				error!("unable to wait for pooled fence: {}", e);
+				unsafe {
+					self.device.handle().destroy_fence(self.handle, None)
+				}
+
+				return
+			}
+		}
+
+		unsafe {
+			match self.device.handle().reset_fences(&[self.handle]) {
+				Ok(()) => self.device.fence_pool().free.push(self.handle),
+				Err(_) => self.device.handle().destroy_fence(self.handle, None)
+			}
+		}
+	}
+}
@@ -1,8 +1,18 @@
+use std::{
+	sync::Arc,
+	time::Duration,
+	fmt
+};
 use ash::vk;
-use crate::pipeline;
+use crate::{
+	Device,
+	DeviceOwned,
+	pipeline
+};
 use super::{
 	task,
-	fence
+	fence,
+	TimelineSemaphore
 };
 
 /// GPU future.
@@ -16,6 +26,10 @@ pub unsafe trait Future {
 	fn signal_fence(&self) -> Option<&vk::Fence> {
 		None
 	}
+
+	/// Called once a wait on the semaphore returned by `signal_semaphore` is submitted,
+	/// consuming its signal.
+	fn waited(&self) {}
 }
 
 /// Group of GPU futures.
@@ -28,8 +42,21 @@ pub unsafe trait Futures {
 
 	/// Fence signaled when *all* the futures are done.
 	/// 
-	/// If `None`, then each underlying future signals a semaphore.
+	/// If `None`, then each underlying future signals a semaphore,
+	/// or `signal_fences` is not empty.
 	fn signal_fence(&self) -> Option<&vk::Fence>;
+
+	/// Fences signaled when the futures are done.
+	///
+	/// If `signal_semaphores` is `None`, then each underlying future signals at least one of the returned fences.
+	/// By default, the fence returned by `signal_fence`.
+	fn signal_fences(&self) -> &[vk::Fence] {
+		self.signal_fence().map(std::slice::from_ref).unwrap_or(&[])
+	}
+
+	/// Called once a wait on the semaphores returned by `signal_semaphores` is submitted,
+	/// consuming their signals.
+	fn waited(&self) {}
 }
 
 unsafe impl<F: Future> Futures for F {
@@ -40,6 +67,45 @@ unsafe impl<F: Future> Futures for F {
 	fn signal_fence(&self) -> Option<&vk::Fence> {
 		Future::signal_fence(self)
 	}
+
+	fn waited(&self) {
+		Future::waited(self)
+	}
+}
+
+/// Optional future, `None` being no future.
+unsafe impl<F: Futures> Futures for Option<F> {
+	fn signal_semaphores(&self) -> Option<&[vk::Semaphore]> {
+		match self {
+			Some(future) => future.signal_semaphores(),
+			None => Some(&[])
+		}
+	}
+
+	fn signal_fence(&self) -> Option<&vk::Fence> {
+		self.as_ref().and_then(Futures::signal_fence)
+	}
+
+	fn signal_fences(&self) -> &[vk::Fence] {
+		self.as_ref().map(Futures::signal_fences).unwrap_or(&[])
+	}
+
+	fn waited(&self) {
+		if let Some(future) = self {
+			future.waited()
+		}
+	}
+}
+
+/// No future.
+unsafe impl Futures for () {
+	fn signal_semaphores(&self) -> Option<&[vk::Semaphore]> {
+		Some(&[])
+	}
+
+	fn signal_fence(&self) -> Option<&vk::Fence> {
+		None
+	}
 }
 
 pub trait SignalSemaphore: Future {
@@ -60,7 +126,11 @@ pub trait SignalFence: Futures {
 		Futures::signal_fence(self).unwrap()
 	}
 
-	fn wait(self, timeout: Option<u64>) -> Result<(), fence::WaitError>;
+	/// Block until the future is done.
+	///
+	/// Returns `WaitError::Timeout` if the timeout expires first.
+	/// A zero timeout only checks the status of the future.
+	fn wait(self, timeout: Option<Duration>) -> Result<(), fence::WaitError>;
 
 	fn is_signaled(&self) -> Result<bool, fence::DeviceLost>;
 
@@ -71,7 +141,8 @@ pub trait SignalFence: Futures {
 		match self.is_signaled() {
 			Ok(true) => Ok(()),
 			Ok(false) => Err(fence::UnwrapError::Unsignaled(self)),
-			Err(fence::DeviceLost) => Err(fence::UnwrapError::DeviceLost)
+			Err(fence::DeviceLost::Lost) => Err(fence::UnwrapError::DeviceLost),
+			Err(fence::DeviceLost::Unexpected(r)) => Err(fence::UnwrapError::Unexpected(r))
 		}
 	}
 }
@@ -79,14 +150,250 @@ pub trait SignalFence: Futures {
 pub trait SignalSemaphores {
 	fn semaphores(&self) -> &[vk::Semaphore];
 
+	/// Called once a wait on the semaphores is submitted, consuming their signals.
+	fn waited(&self) {}
+
 	#[inline]
 	fn and_then_pipeline_stages_of<T: task::WaitPipelineStages>(self, task: T, wait_pipeline_stage_mask: pipeline::stage::Flags) -> task::DelayedPipelineStages<Self, T> where Self: Sized {
 		task::DelayedPipelineStages::new(self, task, wait_pipeline_stage_mask)
 	}
+
+	/// Execute the given task, waiting on each semaphore at its own pipeline stages.
+	///
+	/// # Panic
+	///
+	/// Panics if there is not one stage mask per semaphore.
+	#[inline]
+	fn and_then_pipeline_stages_of_each<T: task::WaitPipelineStages>(self, task: T, wait_pipeline_stage_masks: &[pipeline::stage::Flags]) -> task::DelayedPipelineStages<Self, T> where Self: Sized {
+		task::DelayedPipelineStages::with_stage_masks(self, task, wait_pipeline_stage_masks.to_vec())
+	}
 }
 
 impl<F: SignalSemaphore> SignalSemaphores for F {
 	fn semaphores(&self) -> &[vk::Semaphore] {
 		std::slice::from_ref(self.semaphore())
 	}
-}
\ No newline at end of file
+
+	fn waited(&self) {
+		Future::waited(self)
+	}
+}
+
+/// Futures joined together, done when all of them are.
+///
+/// Keeps the joined futures, and the resources they use, alive.
+#[must_use]
+pub struct Join<F> {
+	futures: F,
+
+	/// Semaphores signaled by the futures, if they all signal semaphores.
+	semaphores: Option<Vec<vk::Semaphore>>,
+
+	/// Fences signaled by the futures, if they do not all signal semaphores.
+	fences: Vec<vk::Fence>
+}
+
+/// Futures that cannot be joined,
+/// since some of them only signal semaphores while others only signal fences.
+///
+/// The futures are given back.
+pub struct JoinError<F>(pub F);
+
+impl<F> fmt::Display for JoinError<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "cannot join futures signaling only semaphores with futures signaling only fences")
+	}
+}
+
+impl<F> fmt::Debug for JoinError<F> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+impl<F> std::error::Error for JoinError<F> {}
+
+/// Semaphores signaled by the joined futures if they all signal semaphores,
+/// and their fences otherwise.
+///
+/// Returns `None` if some futures only signal semaphores while others only signal fences.
+fn joined_signals(joined: &[&dyn Futures]) -> Option<(Option<Vec<vk::Semaphore>>, Vec<vk::Fence>)> {
+	let semaphores = joined.iter().try_fold(Vec::new(), |mut semaphores, f| {
+		semaphores.extend_from_slice(f.signal_semaphores()?);
+		Some(semaphores)
+	});
+
+	let fences = if semaphores.is_some() {
+		Vec::new()
+	} else {
+		let mut fences = Vec::new();
+		for f in joined {
+			let signaled = f.signal_fences();
+			if signaled.is_empty() {
+				return None
+			}
+
+			fences.extend_from_slice(signaled)
+		}
+
+		fences
+	};
+
+	Some((semaphores, fences))
+}
+
+/// Fence signaled by a joined future, waited with the fences of the other futures.
+struct JoinedFence<'a> {
+	device: &'a Arc<Device>,
+	handle: &'a vk::Fence
+}
+
+impl<'a> JoinedFence<'a> {
+	fn new<F: SignalFence + DeviceOwned>(future: &'a F) -> Self {
+		JoinedFence {
+			device: future.device(),
+			handle: future.fence()
+		}
+	}
+}
+
+impl<'a> DeviceOwned for JoinedFence<'a> {
+	fn device(&self) -> &Arc<Device> {
+		self.device
+	}
+}
+
+impl<'a> fence::Fence for JoinedFence<'a> {
+	fn handle(&self) -> &vk::Fence {
+		self.handle
+	}
+}
+
+impl<F> Join<F> {
+	#[inline]
+	pub fn futures(&self) -> &F {
+		&self.futures
+	}
+
+	#[inline]
+	pub fn into_futures(self) -> F {
+		self.futures
+	}
+}
+
+/// Join two futures.
+///
+/// Fails if one future only signals semaphores while the other only signals fences.
+pub fn join<A: Futures, B: Futures>(a: A, b: B) -> Result<Join<(A, B)>, JoinError<(A, B)>> {
+	match joined_signals(&[&a, &b]) {
+		Some((semaphores, fences)) => Ok(Join {
+			futures: (a, b),
+			semaphores,
+			fences
+		}),
+		None => Err(JoinError((a, b)))
+	}
+}
+
+/// Join any number of futures.
+///
+/// Fails if some futures only signal semaphores while others only signal fences.
+pub fn join_all<F: Futures>(futures: Vec<F>) -> Result<Join<Vec<F>>, JoinError<Vec<F>>> {
+	let joined: Vec<&dyn Futures> = futures.iter().map(|f| f as &dyn Futures).collect();
+	let signals = joined_signals(&joined);
+
+	match signals {
+		Some((semaphores, fences)) => Ok(Join {
+			futures,
+			semaphores,
+			fences
+		}),
+		None => Err(JoinError(futures))
+	}
+}
+
+unsafe impl<F> Futures for Join<F> {
+	fn signal_semaphores(&self) -> Option<&[vk::Semaphore]> {
+		self.semaphores.as_deref()
+	}
+
+	fn signal_fence(&self) -> Option<&vk::Fence> {
+		match self.fences.as_slice() {
+			[fence] => Some(fence),
+			_ => None
+		}
+	}
+
+	fn signal_fences(&self) -> &[vk::Fence] {
+		&self.fences
+	}
+}
+
+/// `fence` panics unless a single future is joined.
+impl<A: SignalFence + DeviceOwned, B: SignalFence + DeviceOwned> SignalFence for Join<(A, B)> {
+	/// Block until both futures are done, or the timeout expires.
+	///
+	/// The futures must belong to the same device.
+	fn wait(self, timeout: Option<Duration>) -> Result<(), fence::WaitError> {
+		let (a, b) = &self.futures;
+		fence::wait_all(&[&JoinedFence::new(a), &JoinedFence::new(b)], timeout)
+	}
+
+	fn is_signaled(&self) -> Result<bool, fence::DeviceLost> {
+		Ok(self.futures.0.is_signaled()? && self.futures.1.is_signaled()?)
+	}
+}
+
+/// `fence` panics unless a single future is joined.
+impl<F: SignalFence + DeviceOwned> SignalFence for Join<Vec<F>> {
+	/// Block until all the futures are done, or the timeout expires.
+	///
+	/// The futures must belong to the same device.
+	fn wait(self, timeout: Option<Duration>) -> Result<(), fence::WaitError> {
+		let fences: Vec<_> = self.futures.iter().map(JoinedFence::new).collect();
+		let fences: Vec<&dyn fence::Fence> = fences.iter().map(|f| f as &dyn fence::Fence).collect();
+		fence::wait_all(&fences, timeout)
+	}
+
+	fn is_signaled(&self) -> Result<bool, fence::DeviceLost> {
+		for f in &self.futures {
+			if !f.is_signaled()? {
+				return Ok(false)
+			}
+		}
+
+		Ok(true)
+	}
+}
+
+impl<A: Futures + SignalSemaphores, B: Futures + SignalSemaphores> SignalSemaphores for Join<(A, B)> {
+	fn semaphores(&self) -> &[vk::Semaphore] {
+		self.semaphores.as_deref().unwrap()
+	}
+
+	fn waited(&self) {
+		SignalSemaphores::waited(&self.futures.0);
+		SignalSemaphores::waited(&self.futures.1)
+	}
+}
+
+impl<F: Futures + SignalSemaphores> SignalSemaphores for Join<Vec<F>> {
+	fn semaphores(&self) -> &[vk::Semaphore] {
+		self.semaphores.as_deref().unwrap()
+	}
+
+	fn waited(&self) {
+		self.futures.iter().for_each(SignalSemaphores::waited)
+	}
+}
+
+/// Timeline semaphore, whose counter is signaled to increasing values.
+pub trait SignalTimeline {
+	fn timeline(&self) -> &TimelineSemaphore;
+
+	/// Execute the given task once the counter of the timeline reaches `value`.
+	#[inline]
+	fn and_then_at<T: task::WaitTimeline>(self, value: u64, task: T) -> task::DelayedTimeline<Self, T> where Self: Sized {
+		task::DelayedTimeline::new(self, value, task)
+	}
+}
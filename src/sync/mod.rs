@@ -5,10 +5,30 @@ pub mod task;
 pub mod future;
 pub mod semaphore;
 pub mod fence;
+pub mod timeline;
+pub mod event;
+pub mod external;
 pub mod sharing_mode;
 
 pub use task::Task;
 pub use future::Future;
-pub use semaphore::Semaphore;
-pub use fence::Fence;
+pub use semaphore::{
+	Semaphore,
+	SemaphorePool
+};
+pub use fence::{
+	Fence,
+	FencePool
+};
+pub use timeline::{
+	TimelineSemaphore,
+	Timelines
+};
+pub use event::Event;
+pub use external::{
+	ExternalSemaphoreHandleType,
+	ExternalFenceHandleType,
+	ExternalProperties,
+	ExternalSyncError
+};
 pub use sharing_mode::SharingQueues;
\ No newline at end of file
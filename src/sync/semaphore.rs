@@ -4,8 +4,23 @@ use ash::{
 };
 use std::{
 	borrow::Borrow,
-	sync::Arc
+	sync::{
+		Arc,
+		atomic::{
+			AtomicBool,
+			Ordering
+		}
+	},
+	ffi::c_void
+};
+#[cfg(unix)]
+use std::os::unix::io::{
+	OwnedFd,
+	AsRawFd,
+	IntoRawFd,
+	FromRawFd
 };
+use crossbeam_queue::SegQueue;
 use crate::{
 	OomError,
 	Device,
@@ -13,7 +28,11 @@ use crate::{
 };
 use super::{
 	task,
-	future
+	future,
+	external::{
+		ExternalSemaphoreHandleType,
+		ExternalSyncError
+	}
 };
 
 pub type VulkanSemaphore = vk::Semaphore;
@@ -32,6 +51,9 @@ pub trait Semaphore {
 
 		Ok((output, future))
 	}
+
+	/// Called once a wait on the semaphore is submitted, consuming its signal.
+	fn waited(&self) {}
 }
 
 #[must_use]
@@ -50,6 +72,10 @@ unsafe impl<P, S: Semaphore> future::Future for Future<P, S> {
 	fn signal_semaphore(&self) -> Option<&vk::Semaphore> {
 		Some(self.semaphore.handle())
 	}
+
+	fn waited(&self) {
+		self.semaphore.waited()
+	}
 }
 
 impl<P, S: Semaphore> future::SignalSemaphore for Future<P, S> {}
@@ -71,7 +97,10 @@ impl From<vk::Result> for CreationError {
 
 pub struct Raw {
 	device: Arc<Device>,
-	handle: vk::Semaphore
+	handle: vk::Semaphore,
+
+	/// Handle type with which the semaphore can be exported.
+	export_handle_type: Option<ExternalSemaphoreHandleType>
 }
 
 impl Raw {
@@ -86,9 +115,115 @@ impl Raw {
 
 		Ok(Raw {
 			device: device.clone(),
-			handle
+			handle,
+			export_handle_type: None
+		})
+	}
+
+	/// Create a semaphore that can be exported with the given handle type.
+	///
+	/// The device extension required by the handle type must be enabled,
+	/// and the physical device must support exporting it.
+	pub fn new_exportable(device: &Arc<Device>, handle_type: ExternalSemaphoreHandleType) -> Result<Raw, ExternalSyncError> {
+		let ext = handle_type.required_extension();
+		if !device.loaded_extensions().contains(ext) {
+			return Err(ExternalSyncError::MissingExtension(ext))
+		}
+
+		if !device.physical_device().external_semaphore_properties(handle_type).exportable {
+			return Err(ExternalSyncError::UnsupportedExport)
+		}
+
+		let export_infos = vk::ExportSemaphoreCreateInfo {
+			handle_types: handle_type.into_vulkan(),
+			..Default::default()
+		};
+
+		let infos = vk::SemaphoreCreateInfo {
+			p_next: &export_infos as *const vk::ExportSemaphoreCreateInfo as *const c_void,
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle().create_semaphore(&infos, None)?
+		};
+
+		Ok(Raw {
+			device: device.clone(),
+			handle,
+			export_handle_type: Some(handle_type)
 		})
 	}
+
+	/// Export the semaphore as a file descriptor.
+	///
+	/// The semaphore must have been created with `new_exportable`.
+	/// Exporting a `SyncFd` unsignals the semaphore.
+	///
+	/// # Safety
+	///
+	/// When exporting a `SyncFd`, the semaphore must be signaled,
+	/// or have a pending signal operation.
+	#[cfg(unix)]
+	pub unsafe fn export_fd(&self) -> Result<OwnedFd, ExternalSyncError> {
+		let handle_type = self.export_handle_type.ok_or(ExternalSyncError::NotExportable)?;
+		let ext = self.device.ext_khr_external_semaphore_fd()?;
+
+		let infos = vk::SemaphoreGetFdInfoKHR {
+			semaphore: self.handle,
+			handle_type: handle_type.into_vulkan(),
+			..Default::default()
+		};
+
+		let mut fd = -1;
+		match ext.get_semaphore_fd_khr(self.device.handle().handle(), &infos, &mut fd) {
+			vk::Result::SUCCESS => Ok(OwnedFd::from_raw_fd(fd)),
+			r => Err(r.into())
+		}
+	}
+
+	/// Import a semaphore payload from a file descriptor.
+	///
+	/// A temporary import only lasts until the semaphore is next waited on,
+	/// after which the semaphore is restored to its own payload.
+	/// `SyncFd` imports are always temporary.
+	/// On success the Vulkan implementation takes ownership of the file descriptor.
+	///
+	/// # Safety
+	///
+	/// No pending operation must use the semaphore,
+	/// and `fd` must be a payload of the given handle type.
+	#[cfg(unix)]
+	pub unsafe fn import_fd(&self, fd: OwnedFd, handle_type: ExternalSemaphoreHandleType, temporary: bool) -> Result<(), ExternalSyncError> {
+		let ext = self.device.ext_khr_external_semaphore_fd()?;
+
+		if !self.device.physical_device().external_semaphore_properties(handle_type).importable {
+			return Err(ExternalSyncError::UnsupportedImport)
+		}
+
+		let flags = if temporary || handle_type == ExternalSemaphoreHandleType::SyncFd {
+			vk::SemaphoreImportFlags::TEMPORARY
+		} else {
+			vk::SemaphoreImportFlags::empty()
+		};
+
+		let infos = vk::ImportSemaphoreFdInfoKHR {
+			semaphore: self.handle,
+			flags,
+			handle_type: handle_type.into_vulkan(),
+			fd: fd.as_raw_fd(),
+			..Default::default()
+		};
+
+		match ext.import_semaphore_fd_khr(self.device.handle().handle(), &infos) {
+			vk::Result::SUCCESS => (),
+			r => return Err(r.into())
+		}
+
+		// The implementation now owns the file descriptor.
+		let _ = fd.into_raw_fd();
+		Ok(())
+	}
 }
 
 impl Drop for Raw {
@@ -109,4 +244,129 @@ impl DeviceOwned for Raw {
 	fn device(&self) -> &Arc<Device> {
 		&self.device
 	}
-}
\ No newline at end of file
+}
+
+/// Semaphores of a device, kept for reuse.
+pub struct SemaphorePool {
+	free: SegQueue<VulkanSemaphore>
+}
+
+impl SemaphorePool {
+	pub(crate) fn new() -> SemaphorePool {
+		SemaphorePool {
+			free: SegQueue::new()
+		}
+	}
+
+	/// Number of semaphores available for reuse.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.free.len()
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.free.is_empty()
+	}
+
+	/// Destroy the pooled semaphores.
+	///
+	/// # Safety
+	///
+	/// The semaphores must have been created by the given device.
+	pub(crate) unsafe fn clear(&self, device: &ash::Device) {
+		while let Some(handle) = self.free.pop() {
+			device.destroy_semaphore(handle, None)
+		}
+	}
+}
+
+/// Semaphore taken from the pool of its device.
+///
+/// The semaphore is given back to the pool when dropped,
+/// if any signal of the semaphore has been waited.
+/// Otherwise it may still be signaled, and is destroyed instead,
+/// once the device is idle since its signal operation may still be pending.
+pub struct Pooled {
+	device: Arc<Device>,
+	handle: VulkanSemaphore,
+
+	/// Signaled, or with a pending signal operation, and not waited since.
+	signaled: AtomicBool
+}
+
+impl Pooled {
+	/// Take a semaphore from the pool of the device, or create one if the pool is empty.
+	pub fn new(device: &Arc<Device>) -> Result<Pooled, CreationError> {
+		let handle = match device.semaphore_pool().free.pop() {
+			Some(handle) => handle,
+			None => unsafe {
+				device.handle().create_semaphore(&vk::SemaphoreCreateInfo::default(), None)?
+			}
+		};
+
+		Ok(Pooled {
+			device: device.clone(),
+			handle,
+			signaled: AtomicBool::new(false)
+		})
+	}
+
+	/// Record that a signal operation of the semaphore has been submitted.
+	#[inline]
+	pub(crate) fn set_signaled(&self) {
+		self.signaled.store(true, Ordering::Release)
+	}
+
+	/// Record that a wait consuming the signal of the semaphore has been submitted.
+	#[inline]
+	pub(crate) fn set_waited(&self) {
+		self.signaled.store(false, Ordering::Release)
+	}
+}
+
+impl Semaphore for Pooled {
+	fn handle(&self) -> &VulkanSemaphore {
+		&self.handle
+	}
+
+	fn signal<T: task::SignalSemaphore>(self, task: T) -> Result<(T::Output, Future<T::Payload, Self>), T::Error> {
+		let (output, payload) = task.execute(Some(&[self.handle]), None)?;
+		self.set_signaled();
+
+		let future = Future {
+			payload,
+			semaphore: self
+		};
+
+		Ok((output, future))
+	}
+
+	fn waited(&self) {
+		self.set_waited()
+	}
+}
+
+impl DeviceOwned for Pooled {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for Pooled {
+	fn drop(&mut self) {
+		if self.signaled.load(Ordering::Acquire) {
+			// A signaled semaphore cannot be reused to be signaled again,
+			// and the semaphore does not know which fence its signal operation completes with.
+			if let Err(e) = self.device.wait_idle() {
+				error!("unable to wait for pooled semaphore: {}", e)
+			}
+
+			unsafe {
+				self.device.handle().destroy_semaphore(self.handle, None)
+			}
+		} else {
+			self.device.semaphore_pool().free.push(self.handle)
+		}
+	}
+}
@@ -6,32 +6,59 @@ use crate::{
 	DeviceOwned
 };
 
+/// Queue families sharing a resource.
+///
+/// Resources shared by at most one queue family are created in exclusive mode,
+/// and in concurrent mode otherwise.
 #[derive(Clone)]
 pub struct SharingQueues {
-	device: Arc<Device>,
+	/// Device of the queues, unknown until a queue is added.
+	device: Option<Arc<Device>>,
+
+	/// Sorted and deduplicated queue family indices.
 	queues: Vec<u32>
 }
 
 impl SharingQueues {
+	/// Returns the sharing mode, and the queue family count and indices to use in concurrent mode.
 	pub(crate) fn as_vulkan(&self) -> (vk::SharingMode, u32, *const u32) {
-		if self.queues.len() <= 1 {
+		if !self.is_concurrent() {
 			(vk::SharingMode::EXCLUSIVE, 0, std::ptr::null())
 		} else {
 			(vk::SharingMode::CONCURRENT, self.queues.len() as u32, self.queues.as_ptr())
 		}
 	}
 
+	/// Checks if resources are shared concurrently by several queue families.
+	#[inline]
+	pub fn is_concurrent(&self) -> bool {
+		self.queues.len() > 1
+	}
+
+	/// Checks if the family of the given queue is part of the sharing queue families.
 	pub fn contains(&self, queue: &device::Queue) -> bool {
-		&self.device == queue.device() && self.queues.contains(&queue.index())
+		self.device.as_ref() == Some(queue.device()) && self.queues.binary_search(&queue.family_index()).is_ok()
 	}
 
+	/// Adds the family of the given queue to the sharing queue families.
+	///
+	/// Returns `false` if the family was already present.
+	///
+	/// # Panic
+	///
+	/// Panics if the queue belongs to another device.
 	pub fn insert(&mut self, queue: &device::Queue) -> bool {
-		if !self.contains(queue) {
-			assert_eq!(self.device, *queue.device());
-			self.queues.push(queue.index());
-			true
-		} else {
-			false
+		match &self.device {
+			Some(device) => assert_eq!(device, queue.device()),
+			None => self.device = Some(queue.device().clone())
+		}
+
+		match self.queues.binary_search(&queue.family_index()) {
+			Ok(_) => false,
+			Err(i) => {
+				self.queues.insert(i, queue.family_index());
+				true
+			}
 		}
 	}
 }
@@ -61,8 +88,39 @@ impl<'a, I: IntoIterator> From<I> for SharingQueues where I::Item: std::ops::Der
 		ids.dedup();
 
 		SharingQueues {
-			device: device.unwrap(),
+			device,
 			queues: ids
 		}
 	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sharing_queues(queues: Vec<u32>) -> SharingQueues {
+		SharingQueues {
+			device: None,
+			queues
+		}
+	}
+
+	#[test]
+	fn no_family_is_exclusive() {
+		let queues = SharingQueues::from(Vec::<&device::Queue>::new());
+		assert!(queues.device.is_none());
+		assert_eq!(queues.as_vulkan(), (vk::SharingMode::EXCLUSIVE, 0, std::ptr::null()));
+	}
+
+	#[test]
+	fn single_family_is_exclusive() {
+		let queues = sharing_queues(vec![2]);
+		assert_eq!(queues.as_vulkan(), (vk::SharingMode::EXCLUSIVE, 0, std::ptr::null()));
+	}
+
+	#[test]
+	fn several_families_are_concurrent() {
+		let queues = sharing_queues(vec![0, 1, 3]);
+		assert_eq!(queues.as_vulkan(), (vk::SharingMode::CONCURRENT, 3, queues.queues.as_ptr()));
+	}
 }
\ No newline at end of file
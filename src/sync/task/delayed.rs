@@ -33,6 +33,7 @@ unsafe impl<P: future::SignalSemaphore, T: Wait> Task for Delayed<P, T> {
 		signal_fence: Option<vk::Fence>,
 	) -> Result<(Self::Output, Self::Payload), Self::Error> {
 		let (output, payload) = self.task.execute(Some(std::slice::from_ref(self.past.semaphore())), signal_semaphore, signal_fence)?;
+		future::Future::waited(&self.past);
 		Ok((output, (self.past, payload)))
 	}
 }
@@ -43,13 +44,29 @@ impl<P: future::SignalSemaphore, T: Wait + SignalFence> SignalFence for Delayed<
 pub struct DelayedPipelineStages<P, T> {
 	past: P,
 	task: T,
-	wait_pipeline_stage_mask: pipeline::stage::Flags
+
+	/// Stage mask of each semaphore of `past`.
+	wait_pipeline_stage_masks: Vec<pipeline::stage::Flags>
 }
 
 impl<P: future::SignalSemaphores, T: WaitPipelineStages> DelayedPipelineStages<P, T> {
+	/// Wait on all the semaphores of `past` at the same pipeline stages.
 	pub fn new(past: P, task: T, wait_pipeline_stage_mask: pipeline::stage::Flags) -> Self {
+		let wait_pipeline_stage_masks = vec![wait_pipeline_stage_mask; past.semaphores().len()];
 		DelayedPipelineStages {
-			past, task, wait_pipeline_stage_mask
+			past, task, wait_pipeline_stage_masks
+		}
+	}
+
+	/// Wait on each semaphore of `past` at its own pipeline stages.
+	///
+	/// # Panic
+	///
+	/// Panics if there is not one stage mask per semaphore.
+	pub fn with_stage_masks(past: P, task: T, wait_pipeline_stage_masks: Vec<pipeline::stage::Flags>) -> Self {
+		assert_eq!(wait_pipeline_stage_masks.len(), past.semaphores().len(), "there must be one stage mask per semaphore");
+		DelayedPipelineStages {
+			past, task, wait_pipeline_stage_masks
 		}
 	}
 }
@@ -65,10 +82,45 @@ unsafe impl<P: future::SignalSemaphores, T: WaitPipelineStages> Task for Delayed
 		signal_semaphore: Option<&[vk::Semaphore]>,
 		signal_fence: Option<vk::Fence>,
 	) -> Result<(Self::Output, Self::Payload), Self::Error> {
-		let (output, payload) = self.task.execute(Some(self.past.semaphores()), Some(std::slice::from_ref(&self.wait_pipeline_stage_mask)), signal_semaphore, signal_fence)?;
+		let (output, payload) = self.task.execute(Some(self.past.semaphores()), Some(&self.wait_pipeline_stage_masks), signal_semaphore, signal_fence)?;
+		future::SignalSemaphores::waited(&self.past);
 		Ok((output, (self.past, payload)))
 	}
 }
 
 impl<P: future::SignalSemaphores, T: WaitPipelineStages + SignalSemaphore> SignalSemaphore for DelayedPipelineStages<P, T> {}
-impl<P: future::SignalSemaphores, T: WaitPipelineStages + SignalFence> SignalFence for DelayedPipelineStages<P, T> {}
\ No newline at end of file
+impl<P: future::SignalSemaphores, T: WaitPipelineStages + SignalFence> SignalFence for DelayedPipelineStages<P, T> {}
+
+pub struct DelayedTimeline<P, T> {
+	past: P,
+	value: u64,
+	task: T
+}
+
+impl<P: future::SignalTimeline, T: WaitTimeline> DelayedTimeline<P, T> {
+	pub fn new(past: P, value: u64, task: T) -> Self {
+		DelayedTimeline {
+			past, value, task
+		}
+	}
+}
+
+unsafe impl<P: future::SignalTimeline, T: WaitTimeline> Task for DelayedTimeline<P, T> {
+	type Output = T::Output;
+	type Error = T::Error;
+	type Payload = (P, T::Payload);
+
+	#[inline]
+	fn execute(
+		self,
+		signal_semaphore: Option<&[vk::Semaphore]>,
+		signal_fence: Option<vk::Fence>,
+	) -> Result<(Self::Output, Self::Payload), Self::Error> {
+		let wait = (*self.past.timeline().handle(), self.value);
+		let (output, payload) = self.task.execute(std::slice::from_ref(&wait), signal_semaphore, signal_fence)?;
+		Ok((output, (self.past, payload)))
+	}
+}
+
+impl<P: future::SignalTimeline, T: WaitTimeline + SignalSemaphore> SignalSemaphore for DelayedTimeline<P, T> {}
+impl<P: future::SignalTimeline, T: WaitTimeline + SignalFence> SignalFence for DelayedTimeline<P, T> {}
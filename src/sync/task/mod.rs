@@ -128,6 +128,27 @@ unsafe impl<T: WaitPipelineStages> Wait for T {
 	}
 }
 
+/// Task that *can* wait on timeline semaphores.
+pub unsafe trait WaitTimeline: Sized {
+	/// The output value of the task, returned when the task starts.
+	type Output;
+
+	/// Error that may be raised by the task when it starts.
+	type Error: std::error::Error;
+
+	type Payload;
+
+	/// Execute the task once the counter of each timeline semaphore of `wait_timelines` reaches its value.
+	///
+	/// Note that `signal_semaphore` and `signal_fence` may not be used.
+	fn execute(
+		self,
+		wait_timelines: &[(vk::Semaphore, u64)],
+		signal_semaphore: Option<&[vk::Semaphore]>,
+		signal_fence: Option<vk::Fence>,
+	) -> Result<(Self::Output, Self::Payload), Self::Error>;
+}
+
 /// A task that *can* signal a semaphore upon completion.
 pub trait SignalSemaphore: Task {}
 
@@ -0,0 +1,211 @@
+use ash::{
+	vk,
+	version::DeviceV1_0
+};
+use std::{
+	borrow::Borrow,
+	sync::Arc,
+	time::Duration
+};
+use crate::{
+	OomError,
+	pipeline,
+	Device,
+	DeviceOwned,
+	device::{
+		self,
+		ExtendedFeature,
+		feature::timeline_semaphore
+	},
+	ffi::TimelineSemaphoreFn
+};
+use super::{
+	future,
+	fence::{
+		WaitError,
+		timeout_nanos
+	}
+};
+
+#[derive(Debug)]
+pub enum CreationError {
+	OutOfMemory(OomError),
+
+	/// The `TimelineSemaphore` feature is not enabled on the device.
+	MissingFeature,
+
+	/// The device does not support Vulkan 1.2, and the `VK_KHR_timeline_semaphore` extension is not enabled.
+	MissingExtension(device::Extension)
+}
+
+impl From<vk::Result> for CreationError {
+	fn from(r: vk::Result) -> CreationError {
+		match r {
+			vk::Result::ERROR_OUT_OF_HOST_MEMORY => CreationError::OutOfMemory(OomError::Host),
+			vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => CreationError::OutOfMemory(OomError::Device),
+			_ => unreachable!()
+		}
+	}
+}
+
+/// Semaphore with a monotonically increasing 64-bit counter.
+///
+/// Submissions can wait for the counter to reach a value, and signal it to a greater value,
+/// and so can the host.
+pub struct TimelineSemaphore {
+	device: Arc<Device>,
+	handle: vk::Semaphore,
+
+	/// Commands loaded from Vulkan 1.2 or the extension.
+	fns: TimelineSemaphoreFn
+}
+
+impl TimelineSemaphore {
+	/// Create a timeline semaphore with the given initial counter value.
+	///
+	/// The `TimelineSemaphore` feature must be enabled on the device,
+	/// and the device must support Vulkan 1.2 or the `VK_KHR_timeline_semaphore` extension.
+	pub fn new(device: &Arc<Device>, initial_value: u64) -> Result<TimelineSemaphore, CreationError> {
+		if !device.enabled_extended_features().contains(ExtendedFeature::TimelineSemaphore(timeline_semaphore::Feature::TimelineSemaphore)) {
+			return Err(CreationError::MissingFeature)
+		}
+
+		let fns = *device.timeline_semaphore().map_err(|e| CreationError::MissingExtension(e.0))?;
+
+		let type_infos = vk::SemaphoreTypeCreateInfo {
+			semaphore_type: vk::SemaphoreType::TIMELINE,
+			initial_value,
+			..Default::default()
+		};
+
+		let infos = vk::SemaphoreCreateInfo {
+			p_next: &type_infos as *const _ as *const std::ffi::c_void,
+			..Default::default()
+		};
+
+		let handle = unsafe {
+			device.handle().create_semaphore(&infos, None)?
+		};
+
+		Ok(TimelineSemaphore {
+			device: device.clone(),
+			handle,
+			fns
+		})
+	}
+
+	#[inline]
+	pub fn handle(&self) -> &vk::Semaphore {
+		&self.handle
+	}
+
+	/// Current value of the counter.
+	pub fn counter_value(&self) -> Result<u64, WaitError> {
+		self.device.check_lost()?;
+
+		let mut value = 0;
+		unsafe {
+			let result = (self.fns.get_semaphore_counter_value)(self.device.handle().handle(), self.handle, &mut value);
+			self.device.track_lost(result_of(result))?
+		}
+
+		Ok(value)
+	}
+
+	/// Set the counter to the given value from the host.
+	///
+	/// The value must be greater than the current value of the counter,
+	/// and than the values of any pending signal operation.
+	pub fn signal(&self, value: u64) -> Result<(), OomError> {
+
+		let infos = vk::SemaphoreSignalInfo {
+			semaphore: self.handle,
+			value,
+			..Default::default()
+		};
+
+		unsafe {
+			result_of((self.fns.signal_semaphore)(self.device.handle().handle(), &infos))?
+		}
+
+		Ok(())
+	}
+
+	/// Block until the counter reaches the given value, or the timeout expires.
+	pub fn wait(&self, value: u64, timeout: Option<Duration>) -> Result<(), WaitError> {
+		self.device.check_lost()?;
+
+		let infos = vk::SemaphoreWaitInfo {
+			semaphore_count: 1,
+			p_semaphores: &self.handle,
+			p_values: &value,
+			..Default::default()
+		};
+
+		unsafe {
+			let result = (self.fns.wait_semaphores)(self.device.handle().handle(), &infos, timeout_nanos(timeout));
+			self.device.track_lost(result_of(result))?
+		}
+
+		Ok(())
+	}
+}
+
+fn result_of(result: vk::Result) -> Result<(), vk::Result> {
+	match result {
+		vk::Result::SUCCESS => Ok(()),
+		e => Err(e)
+	}
+}
+
+impl DeviceOwned for TimelineSemaphore {
+	fn device(&self) -> &Arc<Device> {
+		&self.device
+	}
+}
+
+impl Drop for TimelineSemaphore {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.handle().destroy_semaphore(self.handle, None)
+		}
+	}
+}
+
+impl<T: Borrow<TimelineSemaphore>> future::SignalTimeline for T {
+	fn timeline(&self) -> &TimelineSemaphore {
+		self.borrow()
+	}
+}
+
+/// Timeline semaphore values waited and signaled by a submission.
+///
+/// Binary and timeline semaphores can be mixed in the same submission.
+#[derive(Default)]
+pub struct Timelines {
+	pub(crate) waits: Vec<(Arc<TimelineSemaphore>, u64, pipeline::stage::Flags)>,
+	pub(crate) signals: Vec<(Arc<TimelineSemaphore>, u64)>
+}
+
+impl Timelines {
+	pub fn new() -> Timelines {
+		Timelines::default()
+	}
+
+	/// Wait, at the given pipeline stages, for the counter of the semaphore to reach `value`.
+	pub fn wait(mut self, semaphore: &Arc<TimelineSemaphore>, value: u64, stages: pipeline::stage::Flags) -> Timelines {
+		self.waits.push((semaphore.clone(), value, stages));
+		self
+	}
+
+	/// Set the counter of the semaphore to `value` once the submission is done.
+	pub fn signal(mut self, semaphore: &Arc<TimelineSemaphore>, value: u64) -> Timelines {
+		self.signals.push((semaphore.clone(), value));
+		self
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.waits.is_empty() && self.signals.is_empty()
+	}
+}
@@ -13,6 +13,7 @@ use std::{
 };
 use crate::{
 	Entry,
+	EntryError,
 	Instance,
 	instance,
 	swapchain::{
@@ -73,7 +74,7 @@ impl WindowBuilderExt for WindowBuilder {
 }
 
 /// Get the required extensions to enable to create surfaces with `winit`.
-pub fn required_extensions(entry: &Entry) -> instance::Extensions {
+pub fn required_extensions(entry: &Entry) -> Result<instance::Extensions, EntryError> {
 	let ideal = instance::Extensions {
 		khr_surface: true,
 		khr_xlib_surface: true,
@@ -88,52 +89,12 @@ pub fn required_extensions(entry: &Entry) -> instance::Extensions {
 		..instance::Extensions::none()
 	};
 
-	entry.extensions().intersection(&ideal)
+	Ok(entry.extensions()?.intersection(&ideal))
 }
 
-#[cfg(all(unix, not(target_os = "android"), not(target_os = "macos")))]
+/// Create a surface for the given window, through its raw window handle.
+///
+/// Fails on macOS and iOS, whose views are not backed by a `CAMetalLayer`, see `Surface::from_raw_handle`.
 pub fn create_winit_surface(instance: &Arc<Instance>, window: Window) -> Result<Surface<Window>, SurfaceCreationError> {
-	unsafe {
-		use winit::platform::unix::WindowExtUnix;
-
-		match (window.wayland_display(), window.wayland_surface()) {
-			(Some(display), Some(surface)) => {
-				Surface::from_wayland(instance, display, surface, window)
-			},
-			_ => {
-				// No wayland display found, check if we can use xlib.
-				// If not, we use xcb.
-				if instance.loaded_extensions().khr_xlib_surface {
-					Surface::from_xlib(
-						instance,
-						window.xlib_display().unwrap(),
-						window.xlib_window().unwrap() as _,
-						window
-					)
-				} else {
-					Surface::from_xcb(
-						instance,
-						window.xcb_connection().unwrap(),
-						window.xlib_window().unwrap() as _,
-						window,
-					)
-				}
-			}
-		}
-	}
-}
-
-#[cfg(target_os = "windows")]
-pub fn create_winit_surface(instance: &Arc<Instance>, window: Window) -> Result<Surface<Window>, SurfaceCreationError> {
-	panic!("Windows is not supported yet")
-}
-
-#[cfg(target_os = "macos")]
-pub fn create_winit_surface(instance: &Arc<Instance>, window: Window) -> Result<Surface<Window>, SurfaceCreationError> {
-	panic!("Macos is not supported yet")
-}
-
-#[cfg(target_os = "android")]
-pub fn create_winit_surface(instance: &Arc<Instance>, window: Window) -> Result<Surface<Window>, SurfaceCreationError> {
-	panic!("Android is not supported yet")
+	Surface::from_window(instance, window)
 }